@@ -0,0 +1,578 @@
+//! Typed async client for the Jasper companion daemon's D-Bus interface
+//! (`org.jasper.Daemon1` at `/org/jasper/Daemon` on the session bus).
+//!
+//! Frontends (COSMIC, GNOME helpers, KDE, TUIs) share this instead of
+//! hand-rolling proxies: methods return plain structs rather than tuples,
+//! "nothing yet" replies (id 0, empty title) become `None`, and signals come
+//! as streams of typed values.
+//!
+//! ```no_run
+//! # async fn run() -> zbus::Result<()> {
+//! use futures_util::StreamExt;
+//!
+//! let client = jasper_client::JasperClient::connect().await?;
+//! client.register("my-bar", jasper_client::Format::Plain).await?;
+//! if let Some(insight) = client.latest_insight().await? {
+//!     println!("{} {}", insight.emoji, insight.text);
+//! }
+//! let mut updates = client.insight_updates().await?;
+//! while let Some(update) = updates.next().await {
+//!     println!("{} {}", update.emoji, update.preview);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::{Stream, StreamExt};
+use std::collections::HashMap;
+use zbus::{proxy, Connection};
+
+/// Raw proxy, for anything the typed wrapper doesn't cover
+#[proxy(
+    interface = "org.jasper.Daemon1",
+    default_service = "org.jasper.Daemon",
+    default_path = "/org/jasper/Daemon"
+)]
+pub trait JasperDaemon {
+    fn get_latest_insight(&self) -> zbus::Result<(i64, String, String, String)>;
+    fn get_insight_for_frontend(
+        &self,
+        frontend_id: &str,
+    ) -> zbus::Result<(i64, String, String, String)>;
+    fn get_insight_by_id(&self, insight_id: i64) -> zbus::Result<(i64, String, String, String)>;
+    fn get_template_fields(&self) -> zbus::Result<HashMap<String, String>>;
+    fn get_next_event(&self) -> zbus::Result<(String, String, i64, i64, String)>;
+    fn get_latest_digest(&self) -> zbus::Result<(i64, String, i64)>;
+    fn get_insight_sources(&self, insight_id: i64) -> zbus::Result<HashMap<String, u32>>;
+    fn get_insight_freshness(&self) -> zbus::Result<(i64, bool)>;
+    fn get_insight_actions(&self, insight_id: i64) -> zbus::Result<Vec<(String, String)>>;
+    fn register_frontend(&self, frontend_id: &str, pid: i32) -> zbus::Result<bool>;
+    fn register_frontend_with_format(
+        &self,
+        frontend_id: &str,
+        pid: i32,
+        format: &str,
+    ) -> zbus::Result<bool>;
+    fn register_guest_frontend(
+        &self,
+        frontend_id: &str,
+        pid: i32,
+        format: &str,
+    ) -> zbus::Result<bool>;
+    fn unregister_frontend(&self, frontend_id: &str) -> zbus::Result<bool>;
+    fn heartbeat(&self, frontend_id: &str) -> zbus::Result<bool>;
+    fn force_refresh(&self) -> zbus::Result<u64>;
+    fn snooze(&self, minutes: u32) -> zbus::Result<i64>;
+    fn get_snoozed_until(&self) -> zbus::Result<i64>;
+    fn get_next_meeting(&self) -> zbus::Result<(String, i64, String)>;
+    fn join_next_meeting(&self) -> zbus::Result<String>;
+    fn rsvp(&self, event: &str, response: &str) -> zbus::Result<String>;
+    fn get_calendar_colors(&self) -> zbus::Result<HashMap<String, String>>;
+    fn get_status(&self) -> zbus::Result<(bool, u32, i64)>;
+
+    #[zbus(property)]
+    fn version(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn update_available(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn latest_insight_model(&self) -> zbus::Result<String>;
+    #[zbus(property)]
+    fn latest_insight_fallback(&self) -> zbus::Result<bool>;
+
+    #[zbus(signal)]
+    fn insight_updated(
+        &self,
+        insight_id: i64,
+        emoji: String,
+        preview: String,
+        formatted: HashMap<String, String>,
+        age_seconds: i64,
+        stale: bool,
+        actions: Vec<(String, String)>,
+    ) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn guest_insight_updated(&self, emoji: String, text: String) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn refresh_progress(&self, request_id: u64, stage: String, detail: String) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn daemon_stopping(&self) -> zbus::Result<()>;
+}
+
+/// Markup a frontend displays; InsightUpdated carries a payload in it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Plain,
+    Pango,
+    Markdown,
+    Html,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Plain => "plain",
+            Format::Pango => "pango",
+            Format::Markdown => "markdown",
+            Format::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insight {
+    pub id: i64,
+    pub emoji: String,
+    pub text: String,
+    pub context_hash: String,
+}
+
+impl Insight {
+    /// None for the daemon's "no insight" placeholder (id 0)
+    fn from_reply((id, emoji, text, context_hash): (i64, String, String, String)) -> Option<Self> {
+        (id > 0).then_some(Self {
+            id,
+            emoji,
+            text,
+            context_hash,
+        })
+    }
+}
+
+/// Payload of the InsightUpdated signal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsightUpdate {
+    pub id: i64,
+    pub emoji: String,
+    pub preview: String,
+    /// Format name ("plain", "pango", …) to a ready-to-show payload
+    pub formatted: HashMap<String, String>,
+    /// Seconds since the insight was generated
+    pub age_seconds: i64,
+    /// Older than the daemon's `stale_after_minutes`; gray it out
+    pub stale: bool,
+    /// What the insight is about, to open on click; the first is the default
+    pub actions: Vec<InsightAction>,
+}
+
+/// A link from an insight to the event, task or note it mentions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsightAction {
+    /// e.g. "Open \"Dentist\" in Google Calendar"
+    pub label: String,
+    pub uri: String,
+}
+
+impl InsightAction {
+    fn from_pairs(pairs: Vec<(String, String)>) -> Vec<Self> {
+        pairs
+            .into_iter()
+            .map(|(label, uri)| Self { label, uri })
+            .collect()
+    }
+}
+
+impl InsightUpdate {
+    /// The payload for `format`, falling back to the plain preview
+    pub fn text(&self, format: Format) -> &str {
+        self.formatted
+            .get(format.name())
+            .map(String::as_str)
+            .unwrap_or(&self.preview)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextEvent {
+    pub title: String,
+    pub location: Option<String>,
+    /// Unix seconds
+    pub starts_at: i64,
+    pub starts_in_seconds: i64,
+    /// e.g. "Standup in 12m"
+    pub countdown: String,
+}
+
+impl NextEvent {
+    fn from_reply(
+        (title, location, starts_at, starts_in_seconds, countdown): (
+            String,
+            String,
+            i64,
+            i64,
+            String,
+        ),
+    ) -> Option<Self> {
+        (!title.is_empty()).then(|| Self {
+            title,
+            location: (!location.is_empty()).then_some(location),
+            starts_at,
+            starts_in_seconds,
+            countdown,
+        })
+    }
+}
+
+/// A video call to join
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Meeting {
+    pub title: String,
+    /// Unix seconds
+    pub starts_at: i64,
+    /// Meet, Zoom, Teams or Webex link
+    pub url: String,
+}
+
+impl Meeting {
+    fn from_reply((title, starts_at, url): (String, i64, String)) -> Option<Self> {
+        (!url.is_empty()).then_some(Self {
+            title,
+            starts_at,
+            url,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digest {
+    pub id: i64,
+    pub content: String,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+/// Where a ForceRefresh request is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshStage {
+    /// Waiting for a scheduled check that was already running
+    Queued,
+    Collecting,
+    Analyzing,
+    Done,
+    Failed,
+}
+
+impl RefreshStage {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "queued" => Some(Self::Queued),
+            "collecting" => Some(Self::Collecting),
+            "analyzing" => Some(Self::Analyzing),
+            "done" => Some(Self::Done),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Done | Self::Failed)
+    }
+}
+
+/// Payload of the RefreshProgress signal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshUpdate {
+    pub request_id: u64,
+    pub stage: RefreshStage,
+    /// Which insight was generated, why none was, or why the refresh failed
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Status {
+    pub running: bool,
+    pub active_frontends: u32,
+    pub insights_count: i64,
+}
+
+/// Connection to the daemon with typed methods
+#[derive(Clone)]
+pub struct JasperClient {
+    proxy: JasperDaemonProxy<'static>,
+}
+
+impl JasperClient {
+    /// Connect on the session bus
+    pub async fn connect() -> zbus::Result<Self> {
+        Self::with_connection(&Connection::session().await?).await
+    }
+
+    /// Use an existing bus connection
+    pub async fn with_connection(connection: &Connection) -> zbus::Result<Self> {
+        Ok(Self {
+            proxy: JasperDaemonProxy::new(connection).await?,
+        })
+    }
+
+    pub fn proxy(&self) -> &JasperDaemonProxy<'static> {
+        &self.proxy
+    }
+
+    /// Register this process as an active frontend
+    pub async fn register(&self, frontend_id: &str, format: Format) -> zbus::Result<bool> {
+        self.proxy
+            .register_frontend_with_format(frontend_id, std::process::id() as i32, format.name())
+            .await
+    }
+
+    /// Register this process as a shared display. From then on the daemon
+    /// answers this connection with family-safe guest insights only.
+    pub async fn register_guest(&self, frontend_id: &str, format: Format) -> zbus::Result<bool> {
+        self.proxy
+            .register_guest_frontend(frontend_id, std::process::id() as i32, format.name())
+            .await
+    }
+
+    pub async fn unregister(&self, frontend_id: &str) -> zbus::Result<bool> {
+        self.proxy.unregister_frontend(frontend_id).await
+    }
+
+    /// Keep the registration alive; call well within the daemon's heartbeat timeout
+    pub async fn heartbeat(&self, frontend_id: &str) -> zbus::Result<bool> {
+        self.proxy.heartbeat(frontend_id).await
+    }
+
+    pub async fn latest_insight(&self) -> zbus::Result<Option<Insight>> {
+        Ok(Insight::from_reply(self.proxy.get_latest_insight().await?))
+    }
+
+    /// Latest insight shortened to this frontend's configured length
+    pub async fn insight_for_frontend(&self, frontend_id: &str) -> zbus::Result<Option<Insight>> {
+        Ok(Insight::from_reply(
+            self.proxy.get_insight_for_frontend(frontend_id).await?,
+        ))
+    }
+
+    pub async fn insight_by_id(&self, insight_id: i64) -> zbus::Result<Option<Insight>> {
+        Ok(Insight::from_reply(
+            self.proxy.get_insight_by_id(insight_id).await?,
+        ))
+    }
+
+    /// Items each context source contributed to an insight's prompt
+    pub async fn insight_sources(&self, insight_id: i64) -> zbus::Result<HashMap<String, u32>> {
+        self.proxy.get_insight_sources(insight_id).await
+    }
+
+    /// Links to what an insight mentions (calendar event, Todoist task,
+    /// Obsidian note), in the order it mentions them
+    pub async fn insight_actions(&self, insight_id: i64) -> zbus::Result<Vec<InsightAction>> {
+        Ok(InsightAction::from_pairs(
+            self.proxy.get_insight_actions(insight_id).await?,
+        ))
+    }
+
+    /// Seconds since the latest insight was generated and whether it is
+    /// stale; None if there is no insight yet
+    pub async fn freshness(&self) -> zbus::Result<Option<(i64, bool)>> {
+        let (age_seconds, stale) = self.proxy.get_insight_freshness().await?;
+        Ok((age_seconds >= 0).then_some((age_seconds, stale)))
+    }
+
+    /// Values for terminal template placeholders (age, stale, next_event, tasks)
+    pub async fn template_fields(&self) -> zbus::Result<HashMap<String, String>> {
+        self.proxy.get_template_fields().await
+    }
+
+    pub async fn next_event(&self) -> zbus::Result<Option<NextEvent>> {
+        Ok(NextEvent::from_reply(self.proxy.get_next_event().await?))
+    }
+
+    pub async fn latest_digest(&self) -> zbus::Result<Option<Digest>> {
+        let (id, content, created_at) = self.proxy.get_latest_digest().await?;
+        Ok((id > 0).then_some(Digest {
+            id,
+            content,
+            created_at,
+        }))
+    }
+
+    /// Start collecting context and analyzing it now. Returns the request ID
+    /// its `refresh_progress` updates carry; while a refresh is running, that
+    /// refresh's ID is returned instead of starting another.
+    pub async fn force_refresh(&self) -> zbus::Result<u64> {
+        self.proxy.force_refresh().await
+    }
+
+    /// Hold scheduled insights for `minutes` (0 resumes them). Returns the
+    /// Unix time the snooze ends, None when resumed.
+    pub async fn snooze(&self, minutes: u32) -> zbus::Result<Option<i64>> {
+        let until = self.proxy.snooze(minutes).await?;
+        Ok((until > 0).then_some(until))
+    }
+
+    /// Unix time the current snooze ends, None when insights aren't snoozed
+    pub async fn snoozed_until(&self) -> zbus::Result<Option<i64>> {
+        let until = self.proxy.get_snoozed_until().await?;
+        Ok((until > 0).then_some(until))
+    }
+
+    /// The next video call, or the one under way
+    pub async fn next_meeting(&self) -> zbus::Result<Option<Meeting>> {
+        Ok(Meeting::from_reply(self.proxy.get_next_meeting().await?))
+    }
+
+    /// Have the daemon open the next video call; returns the link it opened
+    pub async fn join_next_meeting(&self) -> zbus::Result<Option<String>> {
+        let url = self.proxy.join_next_meeting().await?;
+        Ok((!url.is_empty()).then_some(url))
+    }
+
+    /// Answer an upcoming invitation, named by event ID or part of its
+    /// title, with "accept", "decline" or "tentative". Returns the title of
+    /// the event answered, None when nothing was.
+    pub async fn rsvp(&self, event: &str, response: &str) -> zbus::Result<Option<String>> {
+        let title = self.proxy.rsvp(event, response).await?;
+        Ok((!title.is_empty()).then_some(title))
+    }
+
+    /// Calendar name to color (e.g. "#9fe1e7"), for styling events by calendar
+    pub async fn calendar_colors(&self) -> zbus::Result<HashMap<String, String>> {
+        self.proxy.get_calendar_colors().await
+    }
+
+    /// Refresh and wait for it to finish; the final update is `Done` or `Failed`
+    pub async fn refresh(&self) -> zbus::Result<RefreshUpdate> {
+        // Subscribe before starting so the final stage can't be missed
+        let mut progress = std::pin::pin!(self.refresh_progress().await?);
+        let request_id = self.force_refresh().await?;
+        while let Some(update) = progress.next().await {
+            if update.request_id == request_id && update.stage.is_finished() {
+                return Ok(update);
+            }
+        }
+        Err(zbus::Error::Failure(
+            "RefreshProgress stream ended".to_string(),
+        ))
+    }
+
+    pub async fn status(&self) -> zbus::Result<Status> {
+        let (running, active_frontends, insights_count) = self.proxy.get_status().await?;
+        Ok(Status {
+            running,
+            active_frontends,
+            insights_count,
+        })
+    }
+
+    pub async fn version(&self) -> zbus::Result<String> {
+        self.proxy.version().await
+    }
+
+    /// Newer release found by the daemon's update check, if any
+    pub async fn update_available(&self) -> zbus::Result<Option<String>> {
+        let version = self.proxy.update_available().await?;
+        Ok((!version.is_empty()).then_some(version))
+    }
+
+    /// Model behind the latest insight, and whether it was a fallback model
+    /// standing in for an unavailable primary; None for locally produced insights
+    pub async fn latest_insight_model(&self) -> zbus::Result<Option<(String, bool)>> {
+        let model = self.proxy.latest_insight_model().await?;
+        if model.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some((model, self.proxy.latest_insight_fallback().await?)))
+    }
+
+    /// New insights as they are generated
+    pub async fn insight_updates(&self) -> zbus::Result<impl Stream<Item = InsightUpdate>> {
+        Ok(self
+            .proxy
+            .receive_insight_updated()
+            .await?
+            .filter_map(|signal| {
+                let update = signal.args().ok().map(|args| InsightUpdate {
+                    id: args.insight_id,
+                    emoji: args.emoji,
+                    preview: args.preview,
+                    formatted: args.formatted,
+                    age_seconds: args.age_seconds,
+                    stale: args.stale,
+                    actions: InsightAction::from_pairs(args.actions),
+                });
+                futures_util::future::ready(update)
+            }))
+    }
+
+    /// Stages of every ForceRefresh request, from any client
+    pub async fn refresh_progress(&self) -> zbus::Result<impl Stream<Item = RefreshUpdate>> {
+        Ok(self
+            .proxy
+            .receive_refresh_progress()
+            .await?
+            .filter_map(|signal| {
+                let update = signal.args().ok().and_then(|args| {
+                    Some(RefreshUpdate {
+                        request_id: args.request_id,
+                        stage: RefreshStage::from_name(&args.stage)?,
+                        detail: args.detail,
+                    })
+                });
+                futures_util::future::ready(update)
+            }))
+    }
+
+    /// Fires once when the daemon shuts down
+    pub async fn daemon_stopping(&self) -> zbus::Result<impl Stream<Item = ()>> {
+        Ok(self.proxy.receive_daemon_stopping().await?.map(|_| ()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_placeholder_replies_become_none() {
+        let empty = (
+            0,
+            "🔍".to_string(),
+            "No insights available".to_string(),
+            String::new(),
+        );
+        assert_eq!(Insight::from_reply(empty), None);
+        let insight = Insight::from_reply((
+            7,
+            "☕".to_string(),
+            "Standup at 10".to_string(),
+            "h".to_string(),
+        ));
+        assert_eq!(insight.unwrap().id, 7);
+
+        assert_eq!(
+            NextEvent::from_reply((String::new(), String::new(), 0, 0, String::new())),
+            None
+        );
+        let event = NextEvent::from_reply((
+            "Standup".to_string(),
+            String::new(),
+            1_700_000_000,
+            720,
+            "Standup in 12m".to_string(),
+        ))
+        .unwrap();
+        assert_eq!(event.location, None);
+
+        let update = InsightUpdate {
+            id: 1,
+            emoji: "☕".to_string(),
+            preview: "Coffee & standup".to_string(),
+            formatted: HashMap::from([("pango".to_string(), "Coffee &amp; standup".to_string())]),
+            age_seconds: 0,
+            stale: false,
+            actions: vec![],
+        };
+        assert_eq!(update.text(Format::Pango), "Coffee &amp; standup");
+        assert_eq!(update.text(Format::Html), "Coffee & standup");
+
+        assert_eq!(
+            RefreshStage::from_name("analyzing"),
+            Some(RefreshStage::Analyzing)
+        );
+        assert!(RefreshStage::from_name("failed").unwrap().is_finished());
+        assert_eq!(RefreshStage::from_name("exploded"), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! Event storage at scale: the sync upsert that every calendar refresh goes
+//! through, and the range read behind context collection. Run with
+//! `cargo bench -p jasper-core --bench database`.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use jasper_core::database::{Database, DatabaseInner, Event};
+use std::time::Duration;
+
+const SIZES: [usize; 3] = [10_000, 50_000, 100_000];
+
+/// Events every 15 minutes from 2026-01-01, a few sharing each slot
+fn events(count: usize, generation: u32) -> Vec<Event> {
+    const START: i64 = 1_767_225_600;
+    (0..count)
+        .map(|i| Event {
+            id: 0,
+            source_id: format!("event-{}", i),
+            calendar_id: 0,
+            title: Some(format!("Event {} v{}", i, generation)),
+            description: Some("Weekly sync about the roadmap".to_string()),
+            start_time: START + (i as i64 / 3) * 900,
+            end_time: Some(START + (i as i64 / 3) * 900 + 1800),
+            location: (i % 4 == 0).then(|| "Room 4".to_string()),
+            event_type: None,
+            participants: None,
+            raw_data_json: None,
+            is_all_day: Some(false),
+            meeting_url: None,
+            links: None,
+            my_response: None,
+        })
+        .collect()
+}
+
+fn open(runtime: &tokio::runtime::Runtime, dir: &tempfile::TempDir) -> (Database, i64) {
+    let db: Database = runtime
+        .block_on(DatabaseInner::new(&dir.path().join("jasper.db"), None))
+        .unwrap();
+    let calendar = db
+        .create_or_update_calendar("primary", "Primary", None, Some("owner"), true)
+        .unwrap();
+    (db, calendar)
+}
+
+fn bench_sync(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("sync_calendar_events");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(20));
+
+    for size in SIZES {
+        let fresh = events(size, 0);
+        let changed = events(size, 1);
+
+        // First sync of a calendar: every event is an insert
+        group.bench_with_input(BenchmarkId::new("insert", size), &fresh, |b, fresh| {
+            b.iter_batched(
+                || {
+                    let dir = tempfile::tempdir().unwrap();
+                    let (db, calendar) = open(&runtime, &dir);
+                    (dir, db, calendar)
+                },
+                |(_dir, db, calendar)| db.sync_calendar_events(calendar, fresh).unwrap(),
+                BatchSize::PerIteration,
+            )
+        });
+
+        // Re-sync with nothing changed: content hashes short-circuit updates
+        let dir = tempfile::tempdir().unwrap();
+        let (db, calendar) = open(&runtime, &dir);
+        db.sync_calendar_events(calendar, &fresh).unwrap();
+        group.bench_with_input(BenchmarkId::new("unchanged", size), &fresh, |b, fresh| {
+            b.iter(|| db.sync_calendar_events(calendar, fresh).unwrap())
+        });
+
+        // Re-sync with every title changed: in-place updates, ids kept
+        group.bench_with_input(BenchmarkId::new("update", size), &size, |b, _| {
+            let mut generation = 0;
+            b.iter_batched(
+                || {
+                    generation ^= 1;
+                    if generation == 1 {
+                        changed.clone()
+                    } else {
+                        fresh.clone()
+                    }
+                },
+                |batch| db.sync_calendar_events(calendar, &batch).unwrap(),
+                BatchSize::PerIteration,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_range(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("get_events_in_range");
+    group.sample_size(20);
+
+    for size in SIZES {
+        let dir = tempfile::tempdir().unwrap();
+        let (db, calendar) = open(&runtime, &dir);
+        let stored = events(size, 0);
+        db.sync_calendar_events(calendar, &stored).unwrap();
+        let first = stored[0].start_time;
+        let at = |secs| chrono::DateTime::from_timestamp(secs, 0).unwrap();
+
+        // A day's context window, from the middle of the table
+        let day_start = first + (size as i64 / 6) * 900;
+        group.bench_with_input(BenchmarkId::new("one_day", size), &size, |b, _| {
+            b.iter(|| {
+                db.get_events_in_range(at(day_start), at(day_start + 86_400))
+                    .unwrap()
+            })
+        });
+
+        // Everything: bounded by how many rows the read pool materializes
+        let last = stored[size - 1].start_time;
+        group.bench_with_input(BenchmarkId::new("all", size), &size, |b, _| {
+            b.iter(|| db.get_events_in_range(at(first), at(last + 1)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_sync, bench_range);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+//! Schedule situation detection (overlaps and tight timing) over large event
+//! lists. The pass compares every pair, so time grows with the square of the
+//! event count: 10k events is already far beyond a real context window, and
+//! a single 100k iteration takes minutes, so 50k and 100k only run with
+//! `JASPER_BENCH_LARGE=1`. Run with
+//! `cargo bench -p jasper-core --bench overlaps`.
+
+use chrono::{FixedOffset, TimeZone};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use jasper_core::new_daemon_core::SimplifiedDaemonCore;
+use jasper_core::significance_engine::CalendarEventSummary;
+use std::time::Duration;
+
+const SIZES: [usize; 3] = [1_000, 5_000, 10_000];
+
+/// Sizes matching the database benchmarks, opt-in for their run time
+const LARGE_SIZES: [usize; 2] = [50_000, 100_000];
+
+/// Half-hour events every 20 minutes, alternating between the primary and a
+/// family calendar, so neighbours overlap and some gaps are tight
+fn events(count: usize) -> Vec<CalendarEventSummary> {
+    let start = chrono::Utc.with_ymd_and_hms(2026, 1, 1, 8, 0, 0).unwrap();
+    (0..count)
+        .map(|i| {
+            let begins = start + chrono::Duration::minutes(i as i64 * 20);
+            let primary = i % 2 == 0;
+            CalendarEventSummary {
+                id: format!("event-{}", i),
+                title: format!("Event {}", i),
+                start_time: begins,
+                end_time: Some(begins + chrono::Duration::minutes(30)),
+                location: None,
+                is_all_day: false,
+                calendar_name: Some(if primary { "Work" } else { "Family" }.to_string()),
+                calendar_color: None,
+                is_own_calendar: true,
+                is_primary_calendar: primary,
+                travel_time: None,
+                attendees: vec![],
+                links: vec![],
+                awaiting_reply: false,
+                tentative: false,
+            }
+        })
+        .collect()
+}
+
+fn bench_situations(c: &mut Criterion) {
+    let now = FixedOffset::west_opt(5 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2026, 1, 1, 2, 0, 0)
+        .unwrap();
+    let mut group = c.benchmark_group("detect_schedule_situations");
+    group
+        .sample_size(10)
+        .measurement_time(Duration::from_secs(15));
+
+    let large = std::env::var_os("JASPER_BENCH_LARGE").is_some_and(|v| v == "1");
+    let sizes = SIZES
+        .iter()
+        .chain(LARGE_SIZES.iter().filter(|_| large))
+        .copied();
+    for size in sizes {
+        let events = events(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &events, |b, events| {
+            b.iter(|| SimplifiedDaemonCore::detect_schedule_situations(events, &now))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_situations);
+criterion_main!(benches);
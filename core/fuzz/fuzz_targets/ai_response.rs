@@ -0,0 +1,16 @@
+//! Arbitrary model output through the same parsing path as a real response.
+//! Run with `cargo +nightly fuzz run ai_response` from `core/`.
+
+#![no_main]
+
+use jasper_core::ai_response::{self, MAX_DIGEST_CHARS, MAX_INSIGHT_CHARS};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let content = String::from_utf8_lossy(data);
+    let (insight, digest) = ai_response::split_digest(&content);
+    let (emoji, text) = ai_response::parse_insight(&insight);
+    assert!(!emoji.is_empty());
+    assert!(text.chars().count() <= MAX_INSIGHT_CHARS);
+    assert!(digest.map_or(0, |d| d.chars().count()) <= MAX_DIGEST_CHARS);
+});
@@ -0,0 +1,117 @@
+//! Screen-reader friendly rendering of insights.
+//!
+//! Frontends normally show a leading emoji plus a terse insight. With
+//! `--accessible` the emoji is replaced by a spoken label where it carries
+//! meaning (urgency, category), decorative glyphs are dropped and the
+//! result is always a complete sentence.
+
+use crate::glyphs;
+
+/// Characters that are purely visual: emoji, nerd-font icons (private use
+/// area), box drawing/block art and the joiners/selectors used to compose
+/// emoji sequences.
+pub fn is_decorative_glyph(c: char) -> bool {
+    matches!(c,
+        '\u{1F000}'..='\u{1FAFF}' | // Emoji, pictographs, symbols
+        '\u{2600}'..='\u{27BF}' |   // Misc symbols and dingbats
+        '\u{2B00}'..='\u{2BFF}' |   // Misc symbols and arrows (⭐, ⬆)
+        '\u{2500}'..='\u{259F}' |   // Box drawing and block elements
+        '\u{E000}'..='\u{F8FF}' |   // Private use area (nerd fonts)
+        '\u{F0000}'..='\u{10FFFF}' | // Supplementary private use (nerd fonts v3)
+        '\u{FE00}'..='\u{FE0F}' |   // Variation selectors
+        '\u{200D}' |                // Zero-width joiner
+        '\u{20E3}'                  // Combining enclosing keycap
+    )
+}
+
+/// Spoken label for an insight emoji, when the emoji conveys urgency or topic
+pub fn describe_emoji(emoji: &str) -> Option<&'static str> {
+    let label = match glyphs::emoji_category(emoji)? {
+        "urgent" => "Urgent",
+        "warning" => "Warning",
+        "time" => "Time-sensitive",
+        "action" => "Action needed",
+        "calendar" => "Calendar",
+        "tasks" => "Tasks",
+        "weather" => "Weather",
+        "travel" => "Travel",
+        "status" => "Status",
+        "people" => "People",
+        _ => return None,
+    };
+    Some(label)
+}
+
+/// Replace symbols that screen readers announce awkwardly with plain words
+/// and drop purely decorative glyphs.
+pub fn strip_glyphs(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '→' | '⇒' | '➜' | '➡' => out.push_str(" to "),
+            '←' | '⇐' | '⬅' => out.push_str(" from "),
+            '•' | '·' | '‣' | '▪' => out.push_str(", "),
+            '…' => out.push_str("..."),
+            '—' | '–' => out.push_str(" - "),
+            '°' => out.push_str(" degrees "),
+            c if is_decorative_glyph(c) => out.push(' '),
+            c => out.push(c),
+        }
+    }
+
+    // Collapse the whitespace left behind by removed glyphs
+    let collapsed = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    collapsed
+        .replace(" ,", ",")
+        .replace(" .", ".")
+        .trim_start_matches([',', ' '])
+        .to_string()
+}
+
+/// Render an insight as a single full sentence suitable for screen readers
+pub fn plain_insight(emoji: &str, insight: &str) -> String {
+    let mut text = strip_glyphs(insight);
+    if text.is_empty() {
+        return describe_emoji(emoji)
+            .map(|label| format!("{}.", label))
+            .unwrap_or_default();
+    }
+
+    if !text.ends_with(['.', '!', '?']) {
+        text.push('.');
+    }
+
+    match describe_emoji(emoji) {
+        Some(label) => format!("{}: {}", label, text),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_insight_spells_out_urgency() {
+        assert_eq!(
+            plain_insight("⚠️", "Leave by 8:10 for the dentist"),
+            "Warning: Leave by 8:10 for the dentist."
+        );
+        assert_eq!(
+            plain_insight("☕", "Quiet morning, nothing until 11"),
+            "Quiet morning, nothing until 11."
+        );
+    }
+
+    #[test]
+    fn test_strip_glyphs_removes_icons_and_art() {
+        assert_eq!(
+            strip_glyphs("Standup → Review \u{f073} ██ done…"),
+            "Standup to Review done..."
+        );
+        assert_eq!(
+            strip_glyphs("High 72°F 🌧️ later"),
+            "High 72 degrees F later"
+        );
+    }
+}
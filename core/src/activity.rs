@@ -0,0 +1,284 @@
+//! Indoor/outdoor tagging of calendar events, and how much a weather change
+//! matters for each kind of activity.
+//!
+//! A 10°F swing matters for a picnic, not for a Zoom call. Events are tagged
+//! from their title and location: the user's `[activities]` keywords first,
+//! then built-in outdoor and remote words, then whether there is a location
+//! at all. The significance engine only counts a weather change when it
+//! crosses the threshold of an activity coming up soon, so a quiet day of
+//! video calls doesn't regenerate the insight every time a cloud passes.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::{ActivitiesConfig, ActivityThresholdConfig};
+use crate::significance_engine::CalendarEventSummary;
+
+/// Titles that suggest the event happens outside
+const OUTDOOR_KEYWORDS: &[&str] = &[
+    "soccer", "baseball", "softball", "football", "lacrosse", "tennis", "golf", "practice", "game",
+    "run", "hike", "bike", "walk", "park", "field", "picnic", "bbq", "barbecue", "garden",
+    "outdoor", "pool", "beach", "camp", "market", "parade",
+];
+
+/// Titles or locations that suggest the event happens on a screen
+const REMOTE_KEYWORDS: &[&str] = &[
+    "zoom", "teams", "webex", "hangout", "call", "video", "virtual", "online", "remote", "webinar",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActivityType {
+    /// Happens outside; temperature and conditions both matter
+    Outdoor,
+    /// Somewhere to get to; conditions matter for the trip
+    InPerson,
+    /// Calls and focus blocks; weather hardly matters
+    Remote,
+}
+
+impl ActivityType {
+    fn default_threshold(self) -> WeatherThreshold {
+        match self {
+            ActivityType::Outdoor => WeatherThreshold {
+                temperature_delta_f: 10,
+                conditions: true,
+            },
+            ActivityType::InPerson => WeatherThreshold {
+                temperature_delta_f: 0,
+                conditions: true,
+            },
+            ActivityType::Remote => WeatherThreshold {
+                temperature_delta_f: 0,
+                conditions: false,
+            },
+        }
+    }
+}
+
+/// Weather change that counts as significant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeatherThreshold {
+    /// Smallest temperature swing in °F that counts; 0 = none does
+    pub temperature_delta_f: u32,
+    /// Whether a change in conditions (clear → rain) counts
+    pub conditions: bool,
+}
+
+impl WeatherThreshold {
+    /// The most sensitive of two thresholds
+    fn loosest(self, other: Self) -> Self {
+        let temperature_delta_f = match (self.temperature_delta_f, other.temperature_delta_f) {
+            (0, delta) | (delta, 0) => delta,
+            (a, b) => a.min(b),
+        };
+        Self {
+            temperature_delta_f,
+            conditions: self.conditions || other.conditions,
+        }
+    }
+}
+
+/// Tags events by activity type, built-in keywords plus the user's rules
+#[derive(Debug, Clone, Default)]
+pub struct ActivityClassifier {
+    outdoor: Vec<String>,
+    indoor: Vec<String>,
+}
+
+impl ActivityClassifier {
+    pub fn from_config(config: &ActivitiesConfig) -> Self {
+        Self {
+            outdoor: config
+                .outdoor_keywords
+                .iter()
+                .map(|k| normalize(k))
+                .collect(),
+            indoor: config
+                .indoor_keywords
+                .iter()
+                .map(|k| normalize(k))
+                .collect(),
+        }
+    }
+
+    pub fn classify(&self, event: &CalendarEventSummary) -> ActivityType {
+        let location = event.location.as_deref().unwrap_or_default();
+        let text = normalize(&format!("{} {}", event.title, location));
+        let has = |phrase: &str| !phrase.trim().is_empty() && text.contains(phrase);
+        let has_word = |words: &[&str]| words.iter().any(|w| text.contains(&format!(" {} ", w)));
+
+        if self.indoor.iter().any(|k| has(k)) {
+            ActivityType::InPerson
+        } else if self.outdoor.iter().any(|k| has(k)) || has_word(OUTDOOR_KEYWORDS) {
+            ActivityType::Outdoor
+        } else if has_word(REMOTE_KEYWORDS) || location.contains("://") || location.is_empty() {
+            ActivityType::Remote
+        } else {
+            ActivityType::InPerson
+        }
+    }
+
+    /// Whether the title or location suggests the event happens outside
+    pub fn is_outdoor(&self, event: &CalendarEventSummary) -> bool {
+        self.classify(event) == ActivityType::Outdoor
+    }
+}
+
+/// Per-activity weather thresholds, applied to the events coming up
+#[derive(Debug, Clone)]
+pub struct WeatherRules {
+    classifier: ActivityClassifier,
+    outdoor: WeatherThreshold,
+    in_person: WeatherThreshold,
+    remote: WeatherThreshold,
+    horizon: Duration,
+}
+
+impl WeatherRules {
+    pub fn from_config(config: &ActivitiesConfig) -> Self {
+        let resolve = |activity: ActivityType, overrides: &ActivityThresholdConfig| {
+            let default = activity.default_threshold();
+            WeatherThreshold {
+                temperature_delta_f: overrides
+                    .temperature_delta_f
+                    .unwrap_or(default.temperature_delta_f),
+                conditions: overrides.conditions.unwrap_or(default.conditions),
+            }
+        };
+        Self {
+            classifier: ActivityClassifier::from_config(config),
+            outdoor: resolve(ActivityType::Outdoor, &config.outdoor),
+            in_person: resolve(ActivityType::InPerson, &config.in_person),
+            remote: resolve(ActivityType::Remote, &config.remote),
+            horizon: Duration::hours(config.horizon_hours as i64),
+        }
+    }
+
+    pub fn threshold(&self, activity: ActivityType) -> WeatherThreshold {
+        match activity {
+            ActivityType::Outdoor => self.outdoor,
+            ActivityType::InPerson => self.in_person,
+            ActivityType::Remote => self.remote,
+        }
+    }
+
+    /// The most sensitive threshold among events not over yet that start
+    /// within the horizon; the remote one when there are none
+    pub fn threshold_for(
+        &self,
+        events: &[CalendarEventSummary],
+        now: DateTime<Utc>,
+    ) -> WeatherThreshold {
+        events
+            .iter()
+            .filter(|e| {
+                e.end_time.unwrap_or(e.start_time) > now && e.start_time < now + self.horizon
+            })
+            .map(|e| self.threshold(self.classifier.classify(e)))
+            .fold(self.remote, WeatherThreshold::loosest)
+    }
+}
+
+/// Lowercase words separated by single spaces, padded so " word " matches whole words
+fn normalize(text: &str) -> String {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!(" {} ", words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(title: &str, location: Option<&str>, start: DateTime<Utc>) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::hours(1)),
+            location: location.map(str::to_string),
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_classify_with_user_rules() {
+        let now = Utc::now();
+        let builtin = ActivityClassifier::default();
+        assert_eq!(
+            builtin.classify(&event("Soccer practice", Some("Rec Center"), now)),
+            ActivityType::Outdoor
+        );
+        assert_eq!(
+            builtin.classify(&event("Dentist", Some("Main St"), now)),
+            ActivityType::InPerson
+        );
+        assert_eq!(
+            builtin.classify(&event("Design review", Some("https://zoom.us/j/1"), now)),
+            ActivityType::Remote
+        );
+        assert_eq!(
+            builtin.classify(&event("Focus time", None, now)),
+            ActivityType::Remote
+        );
+
+        let config = ActivitiesConfig {
+            outdoor_keywords: vec!["Dog Walk".to_string()],
+            indoor_keywords: vec!["indoor pool".to_string()],
+            ..ActivitiesConfig::default()
+        };
+        let classifier = ActivityClassifier::from_config(&config);
+        assert_eq!(
+            classifier.classify(&event("Dog walk with Sam", None, now)),
+            ActivityType::Outdoor
+        );
+        assert_eq!(
+            classifier.classify(&event("Swim lessons", Some("YMCA indoor pool"), now)),
+            ActivityType::InPerson
+        );
+    }
+
+    #[test]
+    fn test_threshold_follows_upcoming_events() {
+        let now = Utc::now();
+        let rules = WeatherRules::from_config(&ActivitiesConfig::default());
+
+        let calls = vec![event("Zoom sync", None, now + Duration::hours(1))];
+        assert_eq!(
+            rules.threshold_for(&calls, now),
+            rules.threshold(ActivityType::Remote)
+        );
+
+        let mut day = calls.clone();
+        day.push(event("Picnic", Some("Riverside"), now + Duration::hours(3)));
+        assert_eq!(
+            rules.threshold_for(&day, now),
+            WeatherThreshold {
+                temperature_delta_f: 10,
+                conditions: true
+            }
+        );
+
+        // Tomorrow's picnic is past the horizon
+        let later = vec![event(
+            "Picnic",
+            Some("Riverside"),
+            now + Duration::hours(30),
+        )];
+        assert_eq!(
+            rules.threshold_for(&later, now),
+            rules.threshold(ActivityType::Remote)
+        );
+    }
+}
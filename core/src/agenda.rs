@@ -0,0 +1,347 @@
+//! Human-readable agenda for `jasper-companion-daemon agenda`.
+//!
+//! Built from the same context snapshot the AI sees (events from every
+//! calendar with travel times, tasks, weather forecast), grouped by local
+//! day: each event labelled with whose calendar it's on, tasks due that day,
+//! and the day's forecast. Rendered as plain text, Markdown or JSON.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+
+use crate::attachments::EventLink;
+use crate::context_sources::WeatherContext;
+use crate::insight_format::InsightFormat;
+use crate::significance_engine::{CalendarEventSummary, ContextSnapshot};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgendaDay {
+    pub date: NaiveDate,
+    /// e.g. "Showers, 64°F / 50°F, 70% rain"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<String>,
+    pub events: Vec<AgendaEvent>,
+    pub tasks_due: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AgendaEvent {
+    /// "9:00 AM-9:30 AM", or "All day"
+    pub time: String,
+    pub title: String,
+    /// "You" for the primary calendar, otherwise the calendar's name
+    pub calendar: String,
+    /// The calendar's color, e.g. "#9fe1e7"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// e.g. "25 min drive from home"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub travel: Option<String>,
+    /// Agenda docs, tickets and other links on the event
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<EventLink>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub tentative: bool,
+    pub starts_at: DateTime<Utc>,
+}
+
+/// Group a context snapshot into `days` local days starting at `first_day`.
+/// Incomplete overdue tasks are listed on the first day.
+pub fn build(
+    context: &ContextSnapshot,
+    first_day: NaiveDate,
+    days: u32,
+    tz: &Tz,
+) -> Vec<AgendaDay> {
+    (0..days)
+        .filter_map(|offset| first_day.checked_add_days(chrono::Days::new(offset.into())))
+        .map(|date| AgendaDay {
+            date,
+            weather: context
+                .weather_context
+                .as_ref()
+                .and_then(|w| weather_line(w, date)),
+            events: context
+                .calendar_events
+                .iter()
+                .filter(|e| occurs_on(e, date, tz))
+                .map(|e| agenda_event(e, tz))
+                .collect(),
+            tasks_due: context
+                .tasks
+                .iter()
+                .filter(|t| !t.completed)
+                .filter(|t| match t.due {
+                    Some(due) => {
+                        let due_day = due.with_timezone(tz).date_naive();
+                        due_day == date || (date == first_day && due_day < first_day)
+                    }
+                    None => false,
+                })
+                .map(|t| t.title.clone())
+                .collect(),
+        })
+        .collect()
+}
+
+/// Timed events belong to their start day; all-day events to every day they span
+fn occurs_on(event: &CalendarEventSummary, date: NaiveDate, tz: &Tz) -> bool {
+    let start = event.start_time.with_timezone(tz).date_naive();
+    if !event.is_all_day {
+        return start == date;
+    }
+    // All-day end dates are exclusive (midnight after the last day)
+    let end = event
+        .end_time
+        .map(|end| end.with_timezone(tz).date_naive())
+        .filter(|end| *end > start)
+        .unwrap_or_else(|| start.succ_opt().unwrap_or(start));
+    start <= date && date < end
+}
+
+fn agenda_event(event: &CalendarEventSummary, tz: &Tz) -> AgendaEvent {
+    let time = if event.is_all_day {
+        "All day".to_string()
+    } else {
+        let start = event.start_time.with_timezone(tz).format("%-I:%M %p");
+        match event.end_time {
+            Some(end) => format!("{}-{}", start, end.with_timezone(tz).format("%-I:%M %p")),
+            None => start.to_string(),
+        }
+    };
+    let calendar = if event.is_primary_calendar {
+        "You".to_string()
+    } else {
+        event
+            .calendar_name
+            .clone()
+            .unwrap_or_else(|| "Shared".to_string())
+    };
+    let travel = event.travel_time.as_ref().map(|tt| {
+        format!(
+            "{} min {} from {}",
+            tt.duration_in_traffic_minutes
+                .unwrap_or(tt.duration_minutes),
+            tt.travel_mode_label,
+            tt.origin_label
+        )
+    });
+
+    AgendaEvent {
+        time,
+        title: event.title.clone(),
+        calendar,
+        color: event.calendar_color.clone(),
+        location: event.location.clone(),
+        travel,
+        links: event.links.clone(),
+        tentative: event.tentative,
+        starts_at: event.start_time,
+    }
+}
+
+pub(crate) fn weather_line(weather: &WeatherContext, date: NaiveDate) -> Option<String> {
+    // Forecast days are stored as noon UTC of the local calendar date
+    let forecast = weather
+        .forecast
+        .iter()
+        .find(|f| f.date.date_naive() == date)?;
+    let symbol = weather.units.temperature_symbol();
+    Some(format!(
+        "{}, {:.0}{} / {:.0}{}, {:.0}% rain",
+        forecast.conditions,
+        forecast.temperature_high,
+        symbol,
+        forecast.temperature_low,
+        symbol,
+        forecast.precipitation_chance * 100.0
+    ))
+}
+
+fn day_heading(date: NaiveDate) -> String {
+    date.format("%A %-d %B").to_string()
+}
+
+pub fn render_text(days: &[AgendaDay]) -> String {
+    let mut out = String::new();
+    for day in days {
+        out.push_str(&day_heading(day.date));
+        if let Some(ref weather) = day.weather {
+            out.push_str(&format!("  ({})", weather));
+        }
+        out.push('\n');
+        if day.events.is_empty() && day.tasks_due.is_empty() {
+            out.push_str("  Nothing scheduled\n");
+        }
+        for event in &day.events {
+            out.push_str(&format!(
+                "  {:<17} {} [{}]",
+                event.time, event.title, event.calendar
+            ));
+            if let Some(ref location) = event.location {
+                out.push_str(&format!(" @ {}", location));
+            }
+            if let Some(ref travel) = event.travel {
+                out.push_str(&format!(" ({})", travel));
+            }
+            if event.tentative {
+                out.push_str(" (tentative)");
+            }
+            out.push('\n');
+            for link in &event.links {
+                out.push_str(&format!("  {:<17} {}: {}\n", "", link.label(), link.url));
+            }
+        }
+        for task in &day.tasks_due {
+            out.push_str(&format!("  Due: {}\n", task));
+        }
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+pub fn render_markdown(days: &[AgendaDay]) -> String {
+    let md = |text: &str| InsightFormat::Markdown.escape(text);
+    let mut out = String::new();
+    for day in days {
+        out.push_str(&format!("## {}\n\n", day_heading(day.date)));
+        if let Some(ref weather) = day.weather {
+            out.push_str(&format!("_{}_\n\n", md(weather)));
+        }
+        if day.events.is_empty() && day.tasks_due.is_empty() {
+            out.push_str("Nothing scheduled\n\n");
+            continue;
+        }
+        for event in &day.events {
+            out.push_str(&format!(
+                "- **{}** {} ({})",
+                event.time,
+                md(&event.title),
+                md(&event.calendar)
+            ));
+            if let Some(ref location) = event.location {
+                out.push_str(&format!(" at {}", md(location)));
+            }
+            if let Some(ref travel) = event.travel {
+                out.push_str(&format!(", {}", md(travel)));
+            }
+            if event.tentative {
+                out.push_str(" _(tentative)_");
+            }
+            out.push('\n');
+            for link in &event.links {
+                out.push_str(&format!("  - [{}]({})\n", md(&link.label()), link.url));
+            }
+        }
+        for task in &day.tasks_due {
+            out.push_str(&format!("- [ ] {}\n", md(task)));
+        }
+        out.push('\n');
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_sources::{UnitSystem, WeatherForecast};
+    use crate::significance_engine::TaskSummary;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_week_groups_by_local_day() {
+        let tz: Tz = "America/Detroit".parse().unwrap();
+        let today = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let tomorrow = today.succ_opt().unwrap();
+        let at = |day: NaiveDate, h| {
+            tz.from_local_datetime(&day.and_hms_opt(h, 0, 0).unwrap())
+                .unwrap()
+                .to_utc()
+        };
+        let event = |title: &str, start, calendar: Option<&str>, all_day| CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + chrono::Duration::hours(if all_day { 24 } else { 1 })),
+            location: None,
+            is_all_day: all_day,
+            calendar_name: calendar.map(str::to_string),
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: calendar.is_none(),
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let task = |title: &str, due: Option<DateTime<Utc>>| TaskSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            due,
+            completed: false,
+            priority: 5,
+            urgency: 5,
+            source: "local".to_string(),
+        };
+        let context = ContextSnapshot {
+            calendar_events: vec![
+                event("Standup", at(today, 9), None, false),
+                // 9 PM local is already tomorrow in UTC
+                event("Late call", at(today, 21), None, false),
+                event("Field trip", at(tomorrow, 0), Some("Kieran"), true),
+            ],
+            weather: None,
+            tasks: vec![
+                task(
+                    "Renew passport",
+                    Some(at(today, 8) - chrono::Duration::days(3)),
+                ),
+                task("Pay rent", Some(at(tomorrow, 12))),
+                task("Someday", None),
+            ],
+            notes_context: None,
+            weather_context: Some(WeatherContext {
+                current_conditions: "Cloudy".to_string(),
+                forecast: vec![WeatherForecast {
+                    date: Utc.from_utc_datetime(&today.and_hms_opt(12, 0, 0).unwrap()),
+                    temperature_high: 64.0,
+                    temperature_low: 50.0,
+                    conditions: "Showers".to_string(),
+                    precipitation_chance: 0.7,
+                    description: String::new(),
+                }],
+                alerts: vec![],
+                units: UnitSystem::Imperial,
+            }),
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: String::new(),
+        };
+
+        let days = build(&context, today, 3, &tz);
+        assert_eq!(days.len(), 3);
+        let titles = |d: &AgendaDay| d.events.iter().map(|e| e.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&days[0]), ["Standup", "Late call"]);
+        assert_eq!(days[0].tasks_due, ["Renew passport"]);
+        assert_eq!(
+            days[0].weather.as_deref(),
+            Some("Showers, 64°F / 50°F, 70% rain")
+        );
+        assert_eq!(titles(&days[1]), ["Field trip"]);
+        assert_eq!(days[1].events[0].calendar, "Kieran");
+        assert_eq!(days[1].events[0].time, "All day");
+        assert_eq!(days[1].tasks_due, ["Pay rent"]);
+        assert!(days[2].events.is_empty());
+
+        let text = render_text(&days);
+        assert!(text.contains("9:00 AM-10:00 AM  Standup [You]"), "{}", text);
+        assert!(text.contains("Nothing scheduled"));
+        assert!(render_markdown(&days).contains("- [ ] Pay rent"));
+    }
+}
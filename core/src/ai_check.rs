@@ -0,0 +1,218 @@
+//! `verify-ai`: checks the configured Anthropic key before an insight has to.
+//!
+//! A bad key otherwise only shows up as the first insight quietly falling
+//! back. This lists the models the key can use, confirms every configured
+//! model is among them, and sends a one-token request to read the rate
+//! limit headers. Failures are explained in terms of what to fix.
+
+use reqwest::header::HeaderMap;
+use serde::Deserialize;
+
+use crate::config::{AiCallType, AiConfig};
+use crate::errors::{JasperError, JasperResult};
+
+const API_BASE: &str = "https://api.anthropic.com/v1";
+const API_VERSION: &str = "2023-06-01";
+/// Limits reported as `anthropic-ratelimit-<kind>-{limit,remaining,reset}`
+const LIMIT_KINDS: &[&str] = &["requests", "tokens", "input-tokens", "output-tokens"];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelInfo {
+    pub id: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelInfo>,
+}
+
+/// Models the key can use
+pub async fn list_models(client: &reqwest::Client, api_key: &str) -> JasperResult<Vec<ModelInfo>> {
+    let response = client
+        .get(format!("{}/models?limit=1000", API_BASE))
+        .header("x-api-key", api_key)
+        .header("anthropic-version", API_VERSION)
+        .send()
+        .await?;
+    let status = response.status().as_u16();
+    if !response.status().is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(error(status, &body));
+    }
+    let list: ModelList = response.json().await?;
+    Ok(list.data)
+}
+
+/// Send `model` a one-token request; returns the rate limit headers
+pub async fn ping(client: &reqwest::Client, api_key: &str, model: &str) -> JasperResult<HeaderMap> {
+    let response = client
+        .post(format!("{}/messages", API_BASE))
+        .header("x-api-key", api_key)
+        .header("anthropic-version", API_VERSION)
+        .json(&serde_json::json!({
+            "model": model,
+            "max_tokens": 1,
+            "messages": [{"role": "user", "content": "ping"}],
+        }))
+        .send()
+        .await?;
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    if !response.status().is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(error(status, &body));
+    }
+    Ok(headers)
+}
+
+/// Every model the config may send to, with the setting that names it
+pub fn configured_models(ai: &AiConfig) -> Vec<(String, String)> {
+    let mut models = vec![(ai.model.clone(), "ai.model".to_string())];
+    for call in [
+        AiCallType::Insight,
+        AiCallType::Digest,
+        AiCallType::Reflection,
+    ] {
+        if let Some(ref model) = ai.call(call).model {
+            models.push((model.clone(), format!("ai.calls.{}.model", call.name())));
+        }
+    }
+    for model in &ai.fallback_models {
+        models.push((model.clone(), "ai.fallback_models".to_string()));
+    }
+    models
+}
+
+/// Whether `model` is one of `available`, or an alias of one
+/// ("claude-sonnet-4-5" or "-latest" for "claude-sonnet-4-5-20250929")
+pub fn is_available(model: &str, available: &[ModelInfo]) -> bool {
+    let alias = model.strip_suffix("-latest").unwrap_or(model);
+    available.iter().any(|m| {
+        let undated = match m.id.rsplit_once('-') {
+            Some((base, date)) if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) => {
+                base
+            }
+            _ => m.id.as_str(),
+        };
+        m.id == model || undated == alias
+    })
+}
+
+/// "requests: 49 of 50 left, resets 2026-03-02T15:01:00Z", one line per
+/// limit the response reported
+pub fn rate_limits(headers: &HeaderMap) -> Vec<String> {
+    let header = |name: String| {
+        headers
+            .get(name.as_str())
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    LIMIT_KINDS
+        .iter()
+        .filter_map(|kind| {
+            let prefix = format!("anthropic-ratelimit-{}", kind);
+            let limit = header(format!("{}-limit", prefix))?;
+            let remaining = header(format!("{}-remaining", prefix))?;
+            let reset = header(format!("{}-reset", prefix))
+                .map(|reset| format!(", resets {}", reset))
+                .unwrap_or_default();
+            Some(format!(
+                "{}: {} of {} left{}",
+                kind, remaining, limit, reset
+            ))
+        })
+        .collect()
+}
+
+/// What an error response means and what to do about it
+pub fn explain(status: u16, body: &str) -> String {
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.pointer("/error/message")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+    match status {
+        401 => "The API key was rejected. Check it is a current key from \
+                console.anthropic.com (they start with sk-ant-), then run `set-api-key`."
+            .to_string(),
+        403 => format!(
+            "The key is not allowed to do this ({}). Check its workspace's model access \
+             at console.anthropic.com.",
+            message
+        ),
+        404 => format!(
+            "Not found ({}). Check the model name under [ai] against the list above.",
+            message
+        ),
+        400 if message.to_lowercase().contains("credit balance") => {
+            "The account is out of credits. Add some under Plans & Billing at \
+             console.anthropic.com."
+                .to_string()
+        }
+        429 => format!(
+            "Rate limited ({}). The key works; wait a minute, or raise the limits \
+             at console.anthropic.com.",
+            message
+        ),
+        500..=599 => format!(
+            "Anthropic returned {} ({}). The key may well be fine; try again shortly.",
+            status, message
+        ),
+        _ => format!("HTTP {}: {}", status, message),
+    }
+}
+
+fn error(status: u16, body: &str) -> JasperError {
+    let message = explain(status, body);
+    match status {
+        401 | 403 => JasperError::Authentication {
+            service: "anthropic".into(),
+            message,
+        },
+        _ => JasperError::Api {
+            service: "anthropic".into(),
+            message,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_limits_and_errors_read_plainly() {
+        let mut headers = HeaderMap::new();
+        for (name, value) in [
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "49"),
+            ("anthropic-ratelimit-requests-reset", "2026-03-02T15:01:00Z"),
+            ("anthropic-ratelimit-tokens-limit", "40000"),
+            ("anthropic-ratelimit-tokens-remaining", "39990"),
+        ] {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+        assert_eq!(
+            rate_limits(&headers),
+            vec![
+                "requests: 49 of 50 left, resets 2026-03-02T15:01:00Z",
+                "tokens: 39990 of 40000 left",
+            ]
+        );
+
+        let available = [ModelInfo {
+            id: "claude-sonnet-4-5-20250929".to_string(),
+            display_name: "Claude Sonnet 4.5".to_string(),
+        }];
+        assert!(is_available("claude-sonnet-4-5", &available));
+        assert!(is_available("claude-sonnet-4-5-20250929", &available));
+        assert!(!is_available("claude-sonnet-4", &available));
+
+        let low_credit = r#"{"type":"error","error":{"type":"invalid_request_error","message":"Your credit balance is too low to access the Anthropic API."}}"#;
+        assert!(explain(400, low_credit).starts_with("The account is out of credits"));
+        assert!(explain(529, r#"{"error":{"message":"Overloaded"}}"#)
+            .contains("(Overloaded). The key may well be fine"));
+    }
+}
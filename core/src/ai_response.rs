@@ -0,0 +1,261 @@
+//! Parsing of raw model output into an insight (and optional digest).
+//!
+//! Responses are arbitrary text: they may be huge, lack the expected labels,
+//! or start with multi-codepoint emoji (ZWJ sequences, skin tones, flags).
+//! Everything here works on `char` boundaries and caps what it keeps, so no
+//! response can panic the daemon or flood a frontend. `core/fuzz` drives
+//! these functions with arbitrary input.
+
+use crate::text_budget;
+
+/// Longer responses are cut (at a character boundary) before parsing
+pub const MAX_RESPONSE_CHARS: usize = 16_000;
+
+/// Longest insight text kept; frontends apply their own, smaller budgets
+pub const MAX_INSIGHT_CHARS: usize = 1_000;
+
+/// Longest digest kept
+pub const MAX_DIGEST_CHARS: usize = 4_000;
+
+/// Codepoints in one emoji cluster (a family ZWJ sequence is 7–11)
+const MAX_EMOJI_CHARS: usize = 16;
+
+/// Shown when the response has no usable emoji
+pub const FALLBACK_EMOJI: &str = "🤖";
+
+const EMPTY_INSIGHT: &str = "AI analysis complete - check your schedule and priorities";
+
+fn is_emoji_char(ch: char) -> bool {
+    // Simple emoji detection - Unicode ranges for common emojis
+    matches!(ch,
+        '\u{1F600}'..='\u{1F64F}' | // Emoticons
+        '\u{1F300}'..='\u{1F5FF}' | // Misc Symbols and Pictographs
+        '\u{1F680}'..='\u{1F6FF}' | // Transport and Map
+        '\u{1F700}'..='\u{1F77F}' | // Alchemical Symbols
+        '\u{1F780}'..='\u{1F7FF}' | // Geometric Shapes Extended
+        '\u{1F800}'..='\u{1F8FF}' | // Supplemental Arrows-C
+        '\u{1F900}'..='\u{1F9FF}' | // Supplemental Symbols and Pictographs
+        '\u{1FA00}'..='\u{1FA6F}' | // Chess Symbols
+        '\u{1FA70}'..='\u{1FAFF}' | // Symbols and Pictographs Extended-A
+        '\u{2600}'..='\u{26FF}' |   // Misc symbols
+        '\u{2700}'..='\u{27BF}'     // Dingbats
+    )
+}
+
+fn is_regional_indicator(ch: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch)
+}
+
+/// Variation selector, skin tone, keycap or tag: attaches to the previous emoji
+fn is_emoji_modifier(ch: char) -> bool {
+    matches!(ch,
+        '\u{FE0E}' | '\u{FE0F}' | '\u{20E3}' |
+        '\u{1F3FB}'..='\u{1F3FF}' |
+        '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// The emoji cluster `text` starts with and the text after it. A cluster is
+/// one emoji with its modifiers, a ZWJ sequence of those, or a flag (pair of
+/// regional indicators). None if `text` doesn't start with an emoji.
+pub fn leading_emoji(text: &str) -> Option<(&str, &str)> {
+    let mut chars = text.char_indices().peekable();
+    let (_, first) = *chars.peek()?;
+
+    let end = if is_regional_indicator(first) {
+        chars.next();
+        match chars.next() {
+            Some((i, ch)) if is_regional_indicator(ch) => i + ch.len_utf8(),
+            _ => return None,
+        }
+    } else {
+        let mut end = 0;
+        let mut count = 0;
+        let mut want_base = true;
+        while let Some(&(i, ch)) = chars.peek() {
+            let takes = if want_base {
+                is_emoji_char(ch)
+            } else {
+                is_emoji_modifier(ch) || ch == '\u{200D}'
+            };
+            if !takes || count == MAX_EMOJI_CHARS {
+                break;
+            }
+            chars.next();
+            count += 1;
+            want_base = ch == '\u{200D}';
+            if !want_base {
+                end = i + ch.len_utf8();
+            }
+        }
+        end
+    };
+
+    (end > 0).then(|| text.split_at(end))
+}
+
+/// Cut `text` to at most `max_chars` characters, at a character boundary
+fn clamp(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((i, _)) => &text[..i],
+        None => text,
+    }
+}
+
+/// Split a combined response into its insight part and optional digest.
+/// The combined format is "Insight: <emoji> <sentence>" followed by a
+/// "Digest:" line and the digest body.
+pub fn split_digest(content: &str) -> (String, Option<String>) {
+    let content = clamp(content, MAX_RESPONSE_CHARS).trim();
+    let Some(pos) = content
+        .lines()
+        .position(|line| line.trim_start().starts_with("Digest:"))
+    else {
+        return (content.to_string(), None);
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let insight_part = lines[..pos].join("\n");
+    let first_digest_line = lines[pos].trim_start().trim_start_matches("Digest:").trim();
+    let digest = std::iter::once(first_digest_line)
+        .chain(lines[pos + 1..].iter().copied())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let digest = clamp(digest.trim(), MAX_DIGEST_CHARS)
+        .trim_end()
+        .to_string();
+
+    // "Insight: ☕ text" without an Emoji: line parses as freeform once the label is gone
+    let insight_part = if insight_part.contains("Emoji:") {
+        insight_part
+    } else {
+        insight_part
+            .trim()
+            .trim_start_matches("Insight:")
+            .trim()
+            .to_string()
+    };
+
+    (insight_part, (!digest.is_empty()).then_some(digest))
+}
+
+/// Parse the insight part of a response into (emoji, text).
+/// Supports both "Emoji:/Insight:" format and freeform "emoji text" format.
+pub fn parse_insight(content: &str) -> (String, String) {
+    let content = clamp(content, MAX_RESPONSE_CHARS).trim();
+    let mut emoji = Option::<String>::None;
+    let mut insight = Option::<String>::None;
+
+    // Try structured format first
+    for line in content.lines() {
+        if let Some(value) = line.strip_prefix("Emoji:") {
+            if let Some((e, _)) = leading_emoji(value.trim()) {
+                emoji = Some(e.to_string());
+            }
+        } else if let Some(value) = line.strip_prefix("Insight:") {
+            let value = value.trim();
+            if !value.is_empty() {
+                insight = Some(value.to_string());
+            }
+        }
+    }
+
+    let (emoji, text) = match (emoji, insight) {
+        (Some(e), Some(i)) => (e, i),
+        (emoji, insight) => match leading_emoji(content) {
+            // Freeform: the response starts with an emoji followed by text
+            Some((e, rest)) if !rest.trim().is_empty() => (e.to_string(), rest.trim().to_string()),
+            // Last resort: use full content as insight
+            _ => (
+                emoji.unwrap_or_else(|| FALLBACK_EMOJI.to_string()),
+                insight.unwrap_or_else(|| {
+                    if content.is_empty() {
+                        EMPTY_INSIGHT.to_string()
+                    } else {
+                        content.to_string()
+                    }
+                }),
+            ),
+        },
+    };
+
+    (emoji, text_budget::fit(&text, Some(MAX_INSIGHT_CHARS)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_split_digest_combined_response() {
+        let response = "Insight: ☕ Quiet start — dentist at 2 means leaving by 1:30.\n\
+                        Digest:\n\
+                        - 9:00 Standup\n\
+                        - 2:00 Dentist (25 min drive)";
+        let (insight, digest) = split_digest(response);
+        assert_eq!(
+            insight,
+            "☕ Quiet start — dentist at 2 means leaving by 1:30."
+        );
+        assert_eq!(
+            digest.as_deref(),
+            Some("- 9:00 Standup\n- 2:00 Dentist (25 min drive)")
+        );
+    }
+
+    #[test]
+    fn test_split_digest_plain_response() {
+        let (insight, digest) = split_digest("📋 Invoice is due tomorrow.");
+        assert_eq!(insight, "📋 Invoice is due tomorrow.");
+        assert!(digest.is_none());
+    }
+
+    #[test]
+    fn test_parse_insight_takes_one_emoji_cluster() {
+        let parse = parse_insight;
+        assert_eq!(
+            parse("Emoji: ☕\nInsight: Quiet morning."),
+            ("☕".into(), "Quiet morning.".into())
+        );
+        // ZWJ family, skin tone and flag stay whole; a second emoji is text
+        assert_eq!(
+            parse("👨‍👩‍👧 Pickup at 3."),
+            ("👨‍👩‍👧".into(), "Pickup at 3.".into())
+        );
+        assert_eq!(
+            parse("👋🏽📅 Standup moved."),
+            ("👋🏽".into(), "📅 Standup moved.".into())
+        );
+        assert_eq!(
+            parse("🇬🇧 Flight boards at 6."),
+            ("🇬🇧".into(), "Flight boards at 6.".into())
+        );
+        // Words in the emoji slot fall back to the default emoji
+        assert_eq!(
+            parse("Emoji: coffee\nInsight: Quiet morning."),
+            (FALLBACK_EMOJI.into(), "Quiet morning.".into())
+        );
+        assert_eq!(parse("").1, EMPTY_INSIGHT);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_arbitrary_responses_parse_within_bounds(content in any::<String>()) {
+            let (insight, digest) = split_digest(&content);
+            let (emoji, text) = parse_insight(&insight);
+            prop_assert!(!emoji.is_empty());
+            prop_assert!(emoji.chars().count() <= MAX_EMOJI_CHARS);
+            prop_assert!(text.chars().count() <= MAX_INSIGHT_CHARS);
+            prop_assert!(digest.map_or(0, |d| d.chars().count()) <= MAX_DIGEST_CHARS);
+        }
+
+        #[test]
+        fn prop_emoji_heavy_responses_parse(
+            content in "[\u{1F1E6}-\u{1F1FF}\u{1F3FB}-\u{1F3FF}\u{1F600}-\u{1F64F}\u{200D}\u{FE0F}\u{20E3} a:\n]{0,64}"
+        ) {
+            let (emoji, text) = parse_insight(&content);
+            prop_assert!(!emoji.is_empty() && !text.is_empty());
+        }
+    }
+}
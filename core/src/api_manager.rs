@@ -154,6 +154,15 @@ impl ApiManager {
         Ok(())
     }
 
+    /// Calls left under the daily limit (the count resets at UTC midnight)
+    pub fn calls_remaining_today(&self) -> u32 {
+        let stats = self.stats.read();
+        if Utc::now().date_naive() != stats.last_reset.date_naive() {
+            return stats.daily_limit;
+        }
+        stats.daily_limit.saturating_sub(stats.calls_today)
+    }
+
     pub fn record_api_call(&self, tokens_used: u64) {
         let mut stats = self.stats.write();
         stats.calls_today += 1;
@@ -313,6 +322,7 @@ mod tests {
 
         // Should allow calls initially
         assert!(manager.can_make_api_call().is_ok());
+        assert_eq!(manager.calls_remaining_today(), 200);
 
         // Exhaust daily limit
         {
@@ -325,6 +335,7 @@ mod tests {
             manager.can_make_api_call(),
             Err(RateLimitType::Daily)
         ));
+        assert_eq!(manager.calls_remaining_today(), 0);
     }
 
     #[test]
@@ -0,0 +1,201 @@
+//! Agenda docs, tickets and other links on calendar events.
+//!
+//! During sync each event's Google Drive attachments and the links in its
+//! description are picked out and stored with it, cleaned up first: Google's
+//! redirect wrapper is unwrapped, tracking parameters dropped, and video-call
+//! links (see `meeting_links`) and invitation boilerplate left out. The AI is
+//! told an event has an agenda attached, never given the URLs; the agenda
+//! command lists them and insights mentioning the event can open them.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::meeting_links;
+
+/// Links kept per event
+const MAX_LINKS: usize = 5;
+/// Longest title kept, in characters
+const MAX_TITLE_CHARS: usize = 80;
+/// Query parameters that only track the click
+const TRACKING_PARAMS: &[&str] = &["fbclid", "gclid", "mc_cid", "mc_eid", "usp"];
+/// Hosts of the help and dial-in links invitations carry
+const BOILERPLATE_HOSTS: &[&str] = &[
+    "aka.ms",
+    "calendar.google.com",
+    "g.co",
+    "support.google.com",
+    "www.google.com",
+    "zoom.us",
+];
+
+/// Something attached to or linked from an event
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventLink {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub url: String,
+}
+
+impl EventLink {
+    /// Title, else the URL's host
+    pub fn label(&self) -> String {
+        self.title.clone().unwrap_or_else(|| {
+            host(&self.url)
+                .map(str::to_string)
+                .unwrap_or_else(|| self.url.clone())
+        })
+    }
+}
+
+struct Patterns {
+    anchor: Regex,
+    url: Regex,
+    tag: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        anchor: Regex::new(r#"(?is)<a\s[^>]*href="([^"]+)"[^>]*>(.*?)</a>"#).unwrap(),
+        url: Regex::new(r#"(?i)https?://[^\s<>"']+"#).unwrap(),
+        tag: Regex::new(r"<[^>]+>").unwrap(),
+    })
+}
+
+/// An event's links: `attachments` as (title, file URL) from Google
+/// Calendar first, then the description's, in order
+pub fn extract(attachments: &[(Option<&str>, &str)], description: Option<&str>) -> Vec<EventLink> {
+    let mut found: Vec<(Option<String>, String)> = attachments
+        .iter()
+        .map(|(title, url)| (title.map(str::to_string), url.to_string()))
+        .collect();
+    if let Some(description) = description {
+        let p = patterns();
+        for anchor in p.anchor.captures_iter(description) {
+            let text = p.tag.replace_all(&anchor[2], "").trim().to_string();
+            let title = (!text.is_empty() && !p.url.is_match(&text)).then_some(text);
+            found.push((title, anchor[1].to_string()));
+        }
+        for url in p.url.find_iter(description) {
+            found.push((None, url.as_str().to_string()));
+        }
+    }
+
+    let mut links: Vec<EventLink> = Vec::new();
+    for (title, url) in found {
+        let Some(url) = sanitize(&url) else {
+            continue;
+        };
+        if links.iter().any(|l| l.url == url) {
+            continue;
+        }
+        let title = title
+            .map(|t| {
+                t.replace("&amp;", "&")
+                    .chars()
+                    .take(MAX_TITLE_CHARS)
+                    .collect()
+            })
+            .filter(|t: &String| !t.trim().is_empty());
+        links.push(EventLink { title, url });
+        if links.len() == MAX_LINKS {
+            break;
+        }
+    }
+    links
+}
+
+/// Links from an event's stored JSON
+pub fn parse(json: &str) -> Vec<EventLink> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+/// A link worth keeping, cleaned of redirects and tracking; None for
+/// anything else
+fn sanitize(url: &str) -> Option<String> {
+    let mut url = url
+        .replace("&amp;", "&")
+        .trim_end_matches(['.', ',', ';', ')', ']', '>'])
+        .to_string();
+    // https://www.google.com/url?q=<target>&sa=D&...
+    if url.starts_with("https://www.google.com/url?") {
+        let target = url
+            .split_once('?')?
+            .1
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("q="))?;
+        url = urlencoding::decode(target).ok()?.into_owned();
+    }
+    if !url.starts_with("https://") && !url.starts_with("http://") {
+        return None;
+    }
+    let host = host(&url)?;
+    if BOILERPLATE_HOSTS
+        .iter()
+        .any(|b| host == *b || host.ends_with(&format!(".{}", b)))
+        || meeting_links::find(&url).is_some()
+    {
+        return None;
+    }
+
+    let Some((base, query)) = url.split_once('?') else {
+        return Some(url);
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let name = pair.split('=').next().unwrap_or_default();
+            !name.starts_with("utm_") && !TRACKING_PARAMS.contains(&name)
+        })
+        .collect();
+    Some(if kept.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}?{}", base, kept.join("&"))
+    })
+}
+
+fn host(url: &str) -> Option<&str> {
+    let rest = url.split_once("://")?.1;
+    let host = rest.split(['/', '?', '#']).next()?;
+    Some(host.rsplit('@').next().unwrap_or(host)).filter(|h| !h.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_links_are_cleaned_and_call_links_left_out() {
+        let description = r#"Agenda: <a href="https://www.google.com/url?q=https%3A%2F%2Fdocs.google.com%2Fdocument%2Fd%2Fabc%2Fedit&amp;sa=D">Q3 planning doc</a><br>Ticket https://linear.app/acme/issue/ENG-42?utm_source=slack&amp;view=full.<br>Join: https://meet.google.com/abc-defg-hij<br>Invitation from Google Calendar: https://calendar.google.com/calendar/event?eid=xyz"#;
+        let links = extract(
+            &[(
+                Some("Budget.xlsx"),
+                "https://drive.google.com/open?id=1Bx&usp=drive_web",
+            )],
+            Some(description),
+        );
+        assert_eq!(
+            links,
+            vec![
+                EventLink {
+                    title: Some("Budget.xlsx".to_string()),
+                    url: "https://drive.google.com/open?id=1Bx".to_string(),
+                },
+                EventLink {
+                    title: Some("Q3 planning doc".to_string()),
+                    url: "https://docs.google.com/document/d/abc/edit".to_string(),
+                },
+                EventLink {
+                    title: None,
+                    url: "https://linear.app/acme/issue/ENG-42?view=full".to_string(),
+                },
+            ]
+        );
+        assert_eq!(links[2].label(), "linear.app");
+        assert_eq!(parse(&serde_json::to_string(&links).unwrap()), links);
+        assert!(extract(&[], Some("Bring snacks")).is_empty());
+    }
+}
@@ -0,0 +1,264 @@
+//! Keyboard-driven popup for `jasper-companion-daemon menu`.
+//!
+//! Speaks the dmenu protocol rofi, wofi and fuzzel use: run without a
+//! selection it prints one line per entry (the current insight and its
+//! links, the next few events, open tasks, refresh and snooze), and run with
+//! the chosen line it performs that entry's action. The menu is rebuilt to
+//! interpret the selection, so a line means whatever it said when shown.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::deep_links::InsightAction;
+use crate::significance_engine::ContextSnapshot;
+
+/// Events listed, soonest first
+const MAX_EVENTS: usize = 5;
+/// Open tasks listed, highest priority first
+const MAX_TASKS: usize = 5;
+/// Snooze lengths offered, in minutes
+const SNOOZE_OPTIONS: &[u32] = &[60, 240];
+
+/// What choosing a line does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    /// Nothing; the line is there to be read
+    Show,
+    Open(String),
+    Refresh,
+    /// Minutes; 0 resumes insights
+    Snooze(u32),
+    /// Check off an Obsidian task by its ID
+    CompleteTask(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MenuEntry {
+    pub line: String,
+    pub action: MenuAction,
+}
+
+/// What the menu is built from
+pub struct MenuState<'a> {
+    /// Emoji and text of the latest insight
+    pub insight: Option<(&'a str, &'a str)>,
+    pub insight_actions: &'a [InsightAction],
+    pub context: &'a ContextSnapshot,
+    /// Google Calendar page of each event, by event ID
+    pub event_links: &'a HashMap<String, String>,
+    /// Refresh and snooze need the daemon; None when it isn't running
+    pub snoozed_until: Option<Option<DateTime<Utc>>>,
+    pub now: DateTime<Utc>,
+    pub tz: Tz,
+}
+
+/// The menu's lines, top to bottom
+pub fn entries(state: &MenuState) -> Vec<MenuEntry> {
+    let mut entries = Vec::new();
+    let mut push = |line: String, action: MenuAction| {
+        entries.push(MenuEntry {
+            line: single_line(&line),
+            action,
+        })
+    };
+
+    if let Some((emoji, text)) = state.insight {
+        push(format!("{} {}", emoji, text), MenuAction::Show);
+    }
+    for action in state.insight_actions {
+        push(
+            format!("🔗 {}", action.label),
+            MenuAction::Open(action.uri.clone()),
+        );
+    }
+
+    let mut events: Vec<_> = state
+        .context
+        .calendar_events
+        .iter()
+        .filter(|e| e.end_time.unwrap_or(e.start_time) > state.now)
+        .collect();
+    events.sort_by_key(|e| e.start_time);
+    for event in events.into_iter().take(MAX_EVENTS) {
+        let time = if event.is_all_day {
+            "All day".to_string()
+        } else {
+            event
+                .start_time
+                .with_timezone(&state.tz)
+                .format("%-I:%M %p")
+                .to_string()
+        };
+        let action = state
+            .event_links
+            .get(&event.id)
+            .map_or(MenuAction::Show, |link| MenuAction::Open(link.clone()));
+        push(format!("📅 {} {}", time, event.title), action);
+    }
+
+    // Only Obsidian and todo.txt tasks can be checked off from here
+    let mut tasks: Vec<_> = state
+        .context
+        .tasks
+        .iter()
+        .filter(|t| !t.completed && matches!(t.source.as_str(), "obsidian" | "todotxt"))
+        .collect();
+    tasks.sort_by_key(|t| std::cmp::Reverse(t.priority));
+    for task in tasks.into_iter().take(MAX_TASKS) {
+        push(
+            format!("☐ Complete: {}", task.title),
+            MenuAction::CompleteTask(task.id.clone()),
+        );
+    }
+
+    if let Some(snoozed_until) = state.snoozed_until {
+        push("🔄 Refresh now".to_string(), MenuAction::Refresh);
+        match snoozed_until {
+            Some(until) => push(
+                format!(
+                    "🔔 Resume insights (snoozed until {})",
+                    until.with_timezone(&state.tz).format("%-I:%M %p")
+                ),
+                MenuAction::Snooze(0),
+            ),
+            None => {
+                for minutes in SNOOZE_OPTIONS {
+                    push(
+                        format!("💤 Snooze {}", duration_label(*minutes)),
+                        MenuAction::Snooze(*minutes),
+                    );
+                }
+            }
+        }
+    }
+    entries
+}
+
+/// The action for a line the launcher handed back; None if it isn't one of
+/// `entries` (the user typed something else)
+pub fn select<'a>(entries: &'a [MenuEntry], selection: &str) -> Option<&'a MenuAction> {
+    let selection = selection.trim();
+    entries
+        .iter()
+        .find(|entry| entry.line == selection)
+        .map(|entry| &entry.action)
+}
+
+/// Launchers show one line per entry
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn duration_label(minutes: u32) -> String {
+    match minutes {
+        60 => "1 hour".to_string(),
+        m if m % 60 == 0 => format!("{} hours", m / 60),
+        m => format!("{} minutes", m),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::significance_engine::{CalendarEventSummary, TaskSummary};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_selection_maps_back_to_its_action() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 15, 0, 0).unwrap();
+        let event = |id: &str, title: &str, hours: i64| CalendarEventSummary {
+            id: id.to_string(),
+            title: title.to_string(),
+            start_time: now + chrono::Duration::hours(hours),
+            end_time: Some(now + chrono::Duration::hours(hours) + chrono::Duration::minutes(30)),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let context = ContextSnapshot {
+            calendar_events: vec![
+                event("late", "Dinner", 3),
+                event("past", "Standup", -2),
+                event("soon", "Dentist", 1),
+            ],
+            weather: None,
+            tasks: vec![TaskSummary {
+                id: "2026-03-02.md:4:Call the plumber".to_string(),
+                title: "Call the plumber".to_string(),
+                due: None,
+                completed: false,
+                priority: 5,
+                urgency: 5,
+                source: "obsidian".to_string(),
+            }],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: now,
+            context_hash: String::new(),
+        };
+        let links = HashMap::from([("soon".to_string(), "https://calendar/soon".to_string())]);
+        let actions = [InsightAction {
+            label: "Open \"Dentist\" in Google Calendar".to_string(),
+            uri: "https://calendar/soon".to_string(),
+        }];
+        let state = MenuState {
+            insight: Some(("🦷", "Dentist at 4,\nleave by 3:40")),
+            insight_actions: &actions,
+            context: &context,
+            event_links: &links,
+            snoozed_until: Some(None),
+            now,
+            tz: chrono_tz::America::New_York,
+        };
+
+        let entries = entries(&state);
+        let lines: Vec<&str> = entries.iter().map(|e| e.line.as_str()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "🦷 Dentist at 4, leave by 3:40",
+                "🔗 Open \"Dentist\" in Google Calendar",
+                "📅 11:00 AM Dentist",
+                "📅 1:00 PM Dinner",
+                "☐ Complete: Call the plumber",
+                "🔄 Refresh now",
+                "💤 Snooze 1 hour",
+                "💤 Snooze 4 hours",
+            ]
+        );
+        assert_eq!(
+            select(&entries, "☐ Complete: Call the plumber\n"),
+            Some(&MenuAction::CompleteTask(
+                "2026-03-02.md:4:Call the plumber".to_string()
+            ))
+        );
+        assert_eq!(
+            select(&entries, "💤 Snooze 4 hours"),
+            Some(&MenuAction::Snooze(240))
+        );
+        assert_eq!(select(&entries, "something typed"), None);
+
+        // Without the daemon there is nothing to refresh or snooze
+        let offline = MenuState {
+            snoozed_until: None,
+            ..state
+        };
+        assert!(super::entries(&offline)
+            .iter()
+            .all(|e| !matches!(e.action, MenuAction::Refresh | MenuAction::Snooze(_))));
+    }
+}
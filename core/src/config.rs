@@ -0,0 +1,2896 @@
+#![allow(dead_code)]
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tokio::fs;
+use tracing::{debug, info, warn};
+// URL validation without external crate
+
+use crate::config_handle::ConfigHandle;
+use crate::config_migration::{self, MigrationReport};
+use crate::errors::JasperError;
+use crate::routing::RoutingConfig;
+use crate::sops_integration::SopsSecrets;
+
+/// Upper bound for `ai.calls.*.max_tokens`; a glance insight never needs more
+const MAX_OUTPUT_TOKENS: u32 = 8192;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// Layout version of the file (see `config_migration`)
+    #[serde(default = "default_config_version")]
+    pub config_version: u32,
+    pub general: GeneralConfig,
+    pub ai: AiConfig,
+    pub personality: PersonalityConfig,
+    pub google_calendar: Option<GoogleCalendarConfig>,
+    pub context_sources: Option<ContextSourcesConfig>,
+    /// Display settings for bar frontends (waybar, noctalia)
+    #[serde(default)]
+    pub frontends: FrontendsConfig,
+    /// Local database storage options
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    /// Delivery channels besides D-Bus (the only ones available headless)
+    #[serde(default)]
+    pub delivery: DeliveryConfig,
+    /// When scheduled check-in insights fire
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+    /// What happens when critical items keep being ignored
+    #[serde(default)]
+    pub escalation: EscalationConfig,
+    /// Evening-before "set an earlier alarm" rule
+    #[serde(default)]
+    pub alarm: AlarmConfig,
+    /// Weekly meeting load stats and the monthly reflection
+    #[serde(default)]
+    pub stats: StatsConfig,
+    /// Settle window that folds bursts of context changes into one insight
+    #[serde(default)]
+    pub coalescing: CoalescingConfig,
+    /// Indoor/outdoor event tagging and the weather changes that matter for each
+    #[serde(default)]
+    pub activities: ActivitiesConfig,
+    /// Daily summary of family calendars sent to a shared channel
+    #[serde(default)]
+    pub family_digest: FamilyDigestConfig,
+    /// People Jasper should recognize among event attendees
+    #[serde(default)]
+    pub people: PeopleConfig,
+    /// What happens to events the user declined or only tentatively accepted
+    #[serde(default)]
+    pub attendance: AttendanceConfig,
+    /// Nightly pruning, vacuum and token checks
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    /// Request budgets for third-party APIs (Google, Todoist, OpenAQ, ...)
+    #[serde(default)]
+    pub rate_limits: RateLimitsConfig,
+    /// Merging the same task found in more than one source
+    #[serde(default)]
+    pub tasks: TasksConfig,
+    /// Scrubbing context source text before it reaches the prompt
+    #[serde(default)]
+    pub sanitization: SanitizationConfig,
+    /// The once-a-day digest `greet` gives at login
+    #[serde(default)]
+    pub greet: GreetConfig,
+    /// Which channels each insight category reaches, and from what urgency
+    #[serde(default)]
+    pub routing: RoutingConfig,
+}
+
+/// Locations set with `--config`, `--data-dir` and `--db-path`.
+///
+/// Each falls back to its environment variable (`JASPER_CONFIG`,
+/// `JASPER_DATA_DIR`, `JASPER_DB_PATH`) and then to the XDG default, so
+/// containers, tests and extra profiles never touch the user's real data.
+#[derive(Debug, Clone, Default)]
+pub struct PathOverrides {
+    pub config: Option<PathBuf>,
+    pub data_dir: Option<PathBuf>,
+    pub db_path: Option<PathBuf>,
+}
+
+static PATH_OVERRIDES: OnceLock<PathOverrides> = OnceLock::new();
+
+impl PathOverrides {
+    /// Make these overrides process-wide; call once, before any path is resolved
+    pub fn install(self) {
+        if PATH_OVERRIDES.set(self).is_err() {
+            warn!("Path overrides were already installed; ignoring");
+        }
+    }
+
+    fn resolve(pick: fn(&Self) -> Option<&PathBuf>, env_var: &str) -> Option<PathBuf> {
+        PATH_OVERRIDES.get().and_then(pick).cloned().or_else(|| {
+            std::env::var_os(env_var)
+                .filter(|value| !value.is_empty())
+                .map(PathBuf::from)
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GoogleCalendarConfig {
+    pub enabled: bool,
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub calendar_ids: Vec<String>,
+    pub sync_interval_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneralConfig {
+    /// How far ahead should Jasper look?
+    pub planning_horizon_days: u32,
+    /// Timezone for displaying events
+    pub timezone: String,
+    /// Path to personal context markdown file (default: <config_dir>/context.md)
+    pub personal_context_file: Option<String>,
+    /// Check GitHub once a day for a newer release (reported only, never installed)
+    #[serde(default)]
+    pub check_for_updates: bool,
+    /// Seconds between context checks; longer saves power on battery devices
+    #[serde(default = "default_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// Each wait is moved by a random amount up to this many seconds either
+    /// way, so machines started together don't call the APIs in lockstep
+    #[serde(default)]
+    pub check_jitter_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AiConfig {
+    /// Model name
+    pub model: String,
+    /// API key for the AI provider (optional - falls back to environment variable)
+    pub api_key: Option<String>,
+    /// Approximate token budget for the whole prompt; low-priority context is trimmed to fit
+    #[serde(default = "default_max_prompt_tokens")]
+    pub max_prompt_tokens: usize,
+    /// Reuse the response to an identical prompt sent within this many minutes (0 = off)
+    #[serde(default = "default_response_cache_ttl_minutes")]
+    pub response_cache_ttl_minutes: u32,
+    /// "anthropic", or "mock" for canned offline responses (demo profiles, CI)
+    #[serde(default = "default_ai_provider")]
+    pub provider: String,
+    /// Per-call-type overrides of model, temperature and max_tokens
+    #[serde(default)]
+    pub calls: AiCallsConfig,
+    /// Models tried in order when the call's model errors or is overloaded
+    #[serde(default)]
+    pub fallback_models: Vec<String>,
+    /// Mark the instructions and personal context for the provider's prompt cache
+    #[serde(default = "default_true")]
+    pub prompt_caching: bool,
+    /// Keep a rolling summary of the day and send unchanged context only once a day
+    #[serde(default = "default_true")]
+    pub day_summary: bool,
+    /// Keep as little as possible, here and at the provider: no provider
+    /// prompt cache, no response cache, no stored context snapshots, no
+    /// prompts in debug logs, and source metadata dropped (see `privacy_audit`)
+    #[serde(default)]
+    pub zero_retention: bool,
+}
+
+/// Kinds of AI call, each with its own generation parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AiCallType {
+    /// The regular one-sentence glance insight
+    Insight,
+    /// Insight plus the morning digest, in one response
+    Digest,
+    /// The monthly look back over meeting load
+    Reflection,
+}
+
+impl AiCallType {
+    pub fn name(self) -> &'static str {
+        match self {
+            AiCallType::Insight => "insight",
+            AiCallType::Digest => "digest",
+            AiCallType::Reflection => "reflection",
+        }
+    }
+
+    fn default_temperature(self) -> f32 {
+        match self {
+            AiCallType::Insight => 0.7,
+            AiCallType::Digest => 0.5,
+            AiCallType::Reflection => 0.8,
+        }
+    }
+
+    fn default_max_tokens(self) -> u32 {
+        match self {
+            AiCallType::Insight | AiCallType::Reflection => 300,
+            AiCallType::Digest => 800,
+        }
+    }
+}
+
+/// `[ai.calls.<type>]` tables; anything unset uses the call type's default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AiCallsConfig {
+    #[serde(default)]
+    pub insight: AiCallConfig,
+    #[serde(default)]
+    pub digest: AiCallConfig,
+    #[serde(default)]
+    pub reflection: AiCallConfig,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AiCallConfig {
+    /// Overrides `ai.model` for this call type
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 0.0-1.0
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+}
+
+/// Generation parameters for one request, with defaults filled in
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerationParams {
+    pub model: String,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl AiConfig {
+    /// Whether prompts are marked for the provider's prompt cache
+    pub fn uses_prompt_cache(&self) -> bool {
+        self.prompt_caching && !self.zero_retention
+    }
+
+    /// Minutes an AI response is reused for; 0 when caching is off
+    pub fn response_cache_minutes(&self) -> u32 {
+        if self.zero_retention {
+            0
+        } else {
+            self.response_cache_ttl_minutes
+        }
+    }
+
+    pub fn call(&self, call: AiCallType) -> &AiCallConfig {
+        match call {
+            AiCallType::Insight => &self.calls.insight,
+            AiCallType::Digest => &self.calls.digest,
+            AiCallType::Reflection => &self.calls.reflection,
+        }
+    }
+
+    /// Model, temperature and max_tokens to send for `call`
+    pub fn params(&self, call: AiCallType) -> GenerationParams {
+        let overrides = self.call(call);
+        GenerationParams {
+            model: overrides
+                .model
+                .clone()
+                .unwrap_or_else(|| self.model.clone()),
+            temperature: overrides
+                .temperature
+                .unwrap_or_else(|| call.default_temperature()),
+            max_tokens: overrides
+                .max_tokens
+                .unwrap_or_else(|| call.default_max_tokens()),
+        }
+    }
+}
+
+/// Check-in windows; an insight is generated once per window even without changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatConfig {
+    #[serde(default = "default_heartbeat_phases")]
+    pub phases: Vec<HeartbeatPhase>,
+    /// Scale heartbeats per day with upcoming events and open tasks
+    #[serde(default)]
+    pub adaptive: bool,
+    /// Fewest heartbeats on a quiet day (adaptive only)
+    #[serde(default = "default_min_heartbeats")]
+    pub min_per_day: u32,
+    /// Most heartbeats on a packed day (adaptive only)
+    #[serde(default = "default_max_heartbeats")]
+    pub max_per_day: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatPhase {
+    pub name: String,
+    /// Local start time, "HH:MM"
+    pub start: String,
+    /// Local end time, "HH:MM" (exclusive; earlier than start means it runs past midnight)
+    pub end: String,
+    /// Days the phase starts on ("mon".."sun", "weekdays", "weekends"); empty = every day
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+fn default_heartbeat_phases() -> Vec<HeartbeatPhase> {
+    let phase = |name: &str, start: &str, end: &str| HeartbeatPhase {
+        name: name.to_string(),
+        start: start.to_string(),
+        end: end.to_string(),
+        days: Vec::new(),
+    };
+    vec![
+        phase("morning", "07:00", "09:00"),
+        phase("midday", "12:00", "14:00"),
+        phase("evening", "18:00", "20:00"),
+    ]
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            phases: default_heartbeat_phases(),
+            adaptive: false,
+            min_per_day: default_min_heartbeats(),
+            max_per_day: default_max_heartbeats(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonalityConfig {
+    /// How should Jasper address you?
+    pub user_title: String,
+    /// Formality level: "formal", "balanced", "casual"
+    pub formality: String,
+    /// Assistant persona description
+    pub assistant_persona: String,
+    /// Optional persona reference for personality (e.g., "like Alfred from Batman")
+    pub persona_reference: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSourcesConfig {
+    pub obsidian: Option<ObsidianConfig>,
+    pub weather: Option<WeatherConfig>,
+    pub travel: Option<TravelConfig>,
+    pub school_alerts: Option<SchoolAlertsConfig>,
+    #[serde(default)]
+    pub jira: Option<JiraConfig>,
+    #[serde(default)]
+    pub slack: Option<SlackSourceConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObsidianConfig {
+    pub enabled: bool,
+    pub vault_path: String,
+    #[serde(default)]
+    pub tomorrow_note: TomorrowNoteConfig,
+}
+
+/// Create tomorrow's daily note each evening (see `tomorrow_note`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TomorrowNoteConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Local time, "HH:MM", from which the note is created
+    #[serde(default = "default_tomorrow_note_run_at")]
+    pub run_at: String,
+    /// Template in the vault's templates folder; ".md" may be left off
+    #[serde(default = "default_tomorrow_note_template")]
+    pub template: String,
+}
+
+impl Default for TomorrowNoteConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            run_at: default_tomorrow_note_run_at(),
+            template: default_tomorrow_note_template(),
+        }
+    }
+}
+
+fn default_tomorrow_note_run_at() -> String {
+    "20:00".to_string()
+}
+
+fn default_tomorrow_note_template() -> String {
+    "Daily".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherConfig {
+    pub enabled: bool,
+    #[serde(default)]
+    pub google_api_key: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default = "default_units")]
+    pub units: String, // "metric", "imperial"
+    #[serde(default = "default_cache_duration")]
+    pub cache_duration_minutes: u32,
+    /// Read weather from this JSON file instead of the API (used by demo profiles)
+    #[serde(default)]
+    pub fixture_path: Option<String>,
+    #[serde(default)]
+    pub air_quality: AirQualityConfig,
+    #[serde(default)]
+    pub pollen: PollenConfig,
+}
+
+/// Air quality from OpenAQ, added to weather alerts above a threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AirQualityConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// OpenAQ API key (or set OPENAQ_API_KEY)
+    #[serde(default)]
+    pub openaq_api_key: String,
+    /// Alert at or above this US AQI (100 = unhealthy for sensitive groups)
+    #[serde(default = "default_alert_aqi")]
+    pub alert_aqi: u32,
+}
+
+impl Default for AirQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            openaq_api_key: String::new(),
+            alert_aqi: default_alert_aqi(),
+        }
+    }
+}
+
+/// Pollen forecast from the Google Pollen API (uses the weather API key)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollenConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Alert at or above this universal pollen index (0-5; 4 = high)
+    #[serde(default = "default_alert_pollen_index")]
+    pub alert_index: u32,
+}
+
+impl Default for PollenConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            alert_index: default_alert_pollen_index(),
+        }
+    }
+}
+
+/// School district closure, delay and early dismissal alerts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchoolAlertsConfig {
+    pub enabled: bool,
+    #[serde(default)]
+    pub feeds: Vec<SchoolFeedConfig>,
+    /// Items must mention one of these (case-insensitive); empty = built-in
+    /// closure, delay and early dismissal keywords
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Ignore feed items published longer ago than this
+    #[serde(default = "default_school_alert_max_age_hours")]
+    pub max_age_hours: u32,
+    /// How often the feeds are fetched (minutes)
+    #[serde(default = "default_school_alert_cache_minutes")]
+    pub cache_duration_minutes: u32,
+}
+
+/// Jira issues as tasks (see `context_sources::jira`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JiraConfig {
+    pub enabled: bool,
+    /// Site URL, e.g. "https://example.atlassian.net"
+    pub base_url: String,
+    /// Account email for Jira Cloud; leave empty to send `api_token` as a
+    /// Data Center personal access token
+    #[serde(default)]
+    pub email: String,
+    /// API token (prefer SOPS services.jira_api_token or JIRA_API_TOKEN env var)
+    #[serde(default)]
+    pub api_token: String,
+    /// Which issues become tasks
+    #[serde(default = "default_jira_jql")]
+    pub jql: String,
+    /// How often issues are fetched (minutes)
+    #[serde(default = "default_jira_cache_minutes")]
+    pub cache_duration_minutes: u32,
+}
+
+/// Unanswered Slack DMs and mentions (see `context_sources::slack`); the
+/// `[delivery.slack]` token is separate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackSourceConfig {
+    pub enabled: bool,
+    /// Bot or user token (prefer SOPS services.slack_bot_token or
+    /// SLACK_BOT_TOKEN env var)
+    #[serde(default)]
+    pub token: String,
+    /// Your Slack member ID ("U…"), whose replies and mentions count
+    pub user_id: String,
+    /// How long a message waits before it's worth mentioning
+    #[serde(default = "default_slack_unanswered_hours")]
+    pub unanswered_hours: u32,
+    /// Channel names or IDs to watch for mentions; empty watches every
+    /// channel the token is in
+    #[serde(default)]
+    pub channels: Vec<String>,
+    /// How often conversations are read (minutes)
+    #[serde(default = "default_slack_cache_minutes")]
+    pub cache_duration_minutes: u32,
+}
+
+/// A district's RSS/Atom feed, or an alert page to scrape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchoolFeedConfig {
+    /// District or school name, e.g. "Lincoln USD"
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TravelConfig {
+    pub enabled: bool,
+    /// Google Routes API key (prefer SOPS or GOOGLE_ROUTES_API_KEY env var)
+    #[serde(default)]
+    pub google_api_key: String,
+    /// Home address as plain text (geocoded by the Routes API)
+    #[serde(default)]
+    pub home_address: String,
+    /// Travel mode: DRIVE, TRANSIT, WALK, BICYCLE
+    #[serde(default = "default_travel_mode")]
+    pub travel_mode: String,
+    /// Only calculate travel time for events starting within this many hours
+    #[serde(default = "default_lookahead_hours")]
+    pub lookahead_hours: u32,
+    /// How long to cache a travel time result (minutes)
+    #[serde(default = "default_cache_duration")]
+    pub cache_duration_minutes: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrontendsConfig {
+    #[serde(default)]
+    pub glyphs: GlyphConfig,
+    /// Per-frontend insight length in characters (0 = unlimited), overriding
+    /// the built-in budgets (waybar 60, notification 200, terminal unlimited)
+    #[serde(default)]
+    pub max_length: std::collections::BTreeMap<String, usize>,
+    /// Template for `waybar --simple`; placeholders: {emoji} {text} {age} {stale} {next_event} {tasks}
+    #[serde(default = "default_terminal_template")]
+    pub terminal_template: String,
+    /// A frontend with no heartbeat for this long is considered gone
+    #[serde(default = "default_heartbeat_timeout_seconds")]
+    pub heartbeat_timeout_seconds: u64,
+    /// How long the daemon waits with no frontends before auto-stopping
+    #[serde(default = "default_stop_grace_seconds")]
+    pub stop_grace_seconds: u64,
+    #[serde(default)]
+    pub auto_stop: AutoStopPolicy,
+    /// Insights older than this are flagged stale so frontends can gray them out
+    #[serde(default = "default_stale_after_minutes")]
+    pub stale_after_minutes: u64,
+    /// Screen-reader text in desktop notifications, ntfy, Telegram and email:
+    /// urgency spelled out instead of emoji, no glyphs, full sentences
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+impl Default for FrontendsConfig {
+    fn default() -> Self {
+        Self {
+            glyphs: GlyphConfig::default(),
+            max_length: std::collections::BTreeMap::new(),
+            terminal_template: default_terminal_template(),
+            heartbeat_timeout_seconds: default_heartbeat_timeout_seconds(),
+            stop_grace_seconds: default_stop_grace_seconds(),
+            auto_stop: AutoStopPolicy::default(),
+            stale_after_minutes: default_stale_after_minutes(),
+            accessible: false,
+        }
+    }
+}
+
+/// Whether the daemon exits once its last frontend goes away
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoStopPolicy {
+    /// Stop after `stop_grace_seconds` without any live frontend
+    #[default]
+    WhenIdle,
+    /// Keep running until stopped explicitly
+    Never,
+}
+
+/// Replace AI-chosen emoji with Nerd Font glyphs in bar output
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GlyphConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Category name (calendar, weather, tasks, ...) → glyph, overriding the built-in table
+    #[serde(default)]
+    pub categories: std::collections::BTreeMap<String, String>,
+    /// Exact emoji → glyph, checked before categories
+    #[serde(default)]
+    pub emoji: std::collections::BTreeMap<String, String>,
+    /// Glyph for emoji that match nothing (defaults to a lightbulb)
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+/// Encryption at rest for the local database (requires the `sqlcipher` build feature)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseConfig {
+    #[serde(default)]
+    pub encrypt: bool,
+    /// Where the encryption key comes from: "keyring" (Secret Service via
+    /// `secret-tool`, or the Secret portal when sandboxed), "portal",
+    /// "sops" (`services.jasper_database_key`) or "env" (`JASPER_DB_KEY`)
+    #[serde(default = "default_key_source")]
+    pub key_source: String,
+    /// Largest compressed context snapshot kept with an insight, in KiB
+    #[serde(default = "default_snapshot_max_kb")]
+    pub snapshot_max_kb: u32,
+    /// Days context snapshots are kept
+    #[serde(default = "default_snapshot_retention_days")]
+    pub snapshot_retention_days: u32,
+    /// Keep nothing between runs: use a throwaway database instead of
+    /// `jasper.db` (same as `--db-path :memory:`)
+    #[serde(default)]
+    pub ephemeral: bool,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            encrypt: false,
+            key_source: default_key_source(),
+            snapshot_max_kb: default_snapshot_max_kb(),
+            snapshot_retention_days: default_snapshot_retention_days(),
+            ephemeral: false,
+        }
+    }
+}
+
+/// Push delivery for servers and other sessions without a desktop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryConfig {
+    /// ntfy topic URL, e.g. "https://ntfy.sh/my-jasper"
+    #[serde(default)]
+    pub ntfy_url: Option<String>,
+    /// Access token for protected topics (or set NTFY_TOKEN)
+    #[serde(default)]
+    pub ntfy_token: Option<String>,
+    /// With desktop notifications, offer to join a video call this many
+    /// minutes before it starts (0 = never)
+    #[serde(default = "default_join_reminder_minutes")]
+    pub join_reminder_minutes: u32,
+    /// Rewrite this .ics file whenever a reminder or time block is added or
+    /// removed, so other calendar apps can subscribe to it
+    #[serde(default)]
+    pub ics_path: Option<String>,
+    /// POST new insights (and high-urgency alerts) to a URL
+    #[serde(default)]
+    pub webhook: Option<WebhookConfig>,
+    /// DM urgent insights and mirror meetings into the Slack status
+    #[serde(default)]
+    pub slack: Option<SlackConfig>,
+    /// Email insights through the local MTA (critical ones unless `[routing]`
+    /// says otherwise)
+    #[serde(default)]
+    pub email: Option<EmailDeliveryConfig>,
+    /// Send insights to a Telegram chat from a bot
+    #[serde(default)]
+    pub telegram: Option<TelegramConfig>,
+}
+
+impl Default for DeliveryConfig {
+    fn default() -> Self {
+        Self {
+            ntfy_url: None,
+            ntfy_token: None,
+            join_reminder_minutes: default_join_reminder_minutes(),
+            ics_path: None,
+            webhook: None,
+            slack: None,
+            email: None,
+            telegram: None,
+        }
+    }
+}
+
+/// Slack user-token integration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// User OAuth token (xoxp-…); or SOPS services.slack_token, or SLACK_TOKEN
+    #[serde(default)]
+    pub token: String,
+    /// Member ID to DM urgent insights and alerts to, e.g. "U012AB3CD"
+    #[serde(default)]
+    pub dm_user_id: Option<String>,
+    /// Set "In a meeting until 3" while a primary-calendar event is in progress
+    #[serde(default)]
+    pub set_status: bool,
+    #[serde(default = "default_slack_status_emoji")]
+    pub status_emoji: String,
+}
+
+/// Insight email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailDeliveryConfig {
+    pub to: String,
+    #[serde(default = "default_sendmail_path")]
+    pub sendmail_path: String,
+}
+
+/// Telegram bot delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramConfig {
+    /// Token from @BotFather; or SOPS services.telegram_bot_token, or TELEGRAM_BOT_TOKEN
+    #[serde(default)]
+    pub bot_token: String,
+    /// Chat to send to: your user ID, or a group's (negative) ID
+    pub chat_id: String,
+}
+
+/// Outbound webhook for Slack, Discord, n8n and similar
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Extra request headers, e.g. Authorization
+    #[serde(default)]
+    pub headers: std::collections::BTreeMap<String, String>,
+    /// JSON body with {event}, {emoji}, {text}, {insight_id} and {timestamp}
+    /// placeholders; defaults to an object with all five
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Also fire for severe weather and unhealthy air quality
+    #[serde(default = "default_true")]
+    pub alerts: bool,
+}
+
+/// Escalation for overdue high-priority tasks and double-bookings that stay
+/// unaddressed across consecutive insights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Consecutive insights an item must appear in before escalating
+    #[serde(default = "default_escalation_after_insights")]
+    pub after_insights: u32,
+    /// Overdue tasks at or above this priority (1-10; Todoist "high" is 8) count
+    #[serde(default = "default_escalation_min_task_priority")]
+    pub min_task_priority: i32,
+    /// Treat overlapping events on the primary calendar as critical
+    #[serde(default = "default_true")]
+    pub include_conflicts: bool,
+    /// Show the escalation as a critical-urgency desktop notification
+    #[serde(default = "default_true")]
+    pub critical_notification: bool,
+    /// Also email escalations to this address, through the local MTA
+    #[serde(default)]
+    pub email_to: Option<String>,
+    #[serde(default = "default_sendmail_path")]
+    pub sendmail_path: String,
+}
+
+impl Default for EscalationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            after_insights: default_escalation_after_insights(),
+            min_task_priority: default_escalation_min_task_priority(),
+            include_conflicts: true,
+            critical_notification: true,
+            email_to: None,
+            sendmail_path: default_sendmail_path(),
+        }
+    }
+}
+
+/// Meeting load analytics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsConfig {
+    /// Working hours, "HH:MM"; meetings outside them count as after hours
+    #[serde(default = "default_workday_start")]
+    pub workday_start: String,
+    #[serde(default = "default_workday_end")]
+    pub workday_end: String,
+    /// Early each month, turn the last weeks' stats into a reflective insight
+    #[serde(default = "default_true")]
+    pub monthly_reflection: bool,
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            workday_start: default_workday_start(),
+            workday_end: default_workday_end(),
+            monthly_reflection: true,
+        }
+    }
+}
+
+impl StatsConfig {
+    /// Working hours; 09:00-17:00 if unparseable (rejected at load anyway)
+    pub fn workday(&self) -> (chrono::NaiveTime, chrono::NaiveTime) {
+        let parse = |value: &str, fallback| {
+            chrono::NaiveTime::parse_from_str(value, "%H:%M")
+                .unwrap_or_else(|_| chrono::NaiveTime::from_hms_opt(fallback, 0, 0).unwrap())
+        };
+        (parse(&self.workday_start, 9), parse(&self.workday_end, 17))
+    }
+}
+
+fn default_workday_start() -> String {
+    "09:00".to_string()
+}
+fn default_workday_end() -> String {
+    "17:00".to_string()
+}
+
+/// Debouncing of context-change triggers: changes arriving in quick
+/// succession are collected until things settle, then analyzed together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoalescingConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Quiet period after the latest change before the burst is analyzed
+    #[serde(default = "default_settle_seconds")]
+    pub settle_seconds: u64,
+    /// Analyze a burst after this long even if changes keep arriving
+    #[serde(default = "default_max_wait_seconds")]
+    pub max_wait_seconds: u64,
+    /// Changes scoring below this (see `prefilter`) update the context
+    /// without an AI call; 0 sends every change
+    #[serde(default = "default_min_score")]
+    pub min_score: f64,
+}
+
+impl Default for CoalescingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            settle_seconds: default_settle_seconds(),
+            max_wait_seconds: default_max_wait_seconds(),
+            min_score: default_min_score(),
+        }
+    }
+}
+
+fn default_settle_seconds() -> u64 {
+    120
+}
+fn default_max_wait_seconds() -> u64 {
+    600
+}
+fn default_min_score() -> f64 {
+    1.0
+}
+
+/// Tags events as outdoor, in person or remote, and sets how big a weather
+/// change must be to count as significant while each kind is coming up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivitiesConfig {
+    /// Words or phrases in an event's title or location marking it as outdoor
+    #[serde(default)]
+    pub outdoor_keywords: Vec<String>,
+    /// Words or phrases marking an event as indoor; these win over outdoor ones
+    #[serde(default)]
+    pub indoor_keywords: Vec<String>,
+    /// Only events starting within this many hours affect weather significance
+    #[serde(default = "default_activity_horizon_hours")]
+    pub horizon_hours: u32,
+    #[serde(default)]
+    pub outdoor: ActivityThresholdConfig,
+    #[serde(default)]
+    pub in_person: ActivityThresholdConfig,
+    /// Applies when nothing else is coming up
+    #[serde(default)]
+    pub remote: ActivityThresholdConfig,
+}
+
+impl Default for ActivitiesConfig {
+    fn default() -> Self {
+        Self {
+            outdoor_keywords: Vec::new(),
+            indoor_keywords: Vec::new(),
+            horizon_hours: default_activity_horizon_hours(),
+            outdoor: ActivityThresholdConfig::default(),
+            in_person: ActivityThresholdConfig::default(),
+            remote: ActivityThresholdConfig::default(),
+        }
+    }
+}
+
+/// `[activities.<type>]` tables; anything unset uses the activity type's default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityThresholdConfig {
+    /// Smallest temperature swing in °F that counts (0 = none)
+    #[serde(default)]
+    pub temperature_delta_f: Option<u32>,
+    /// Whether a change in conditions (clear → rain) counts
+    #[serde(default)]
+    pub conditions: Option<bool>,
+}
+
+fn default_activity_horizon_hours() -> u32 {
+    12
+}
+
+/// A morning digest of family and shared calendars, sent to a channel other
+/// people read (a family ntfy topic, or a group chat through a webhook)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FamilyDigestConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Local time, "HH:MM", from which the day's digest is sent
+    #[serde(default = "default_family_digest_send_at")]
+    pub send_at: String,
+    /// Calendar names to include; empty = every owned calendar except the primary one
+    #[serde(default)]
+    pub calendars: Vec<String>,
+    /// ntfy topic URL for the family, separate from `delivery.ntfy_url`
+    #[serde(default)]
+    pub ntfy_url: Option<String>,
+    /// Access token for a protected family topic
+    #[serde(default)]
+    pub ntfy_token: Option<String>,
+    /// POST the digest here, e.g. a Telegram bot's sendMessage for a group chat
+    #[serde(default)]
+    pub webhook: Option<WebhookConfig>,
+}
+
+impl Default for FamilyDigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            send_at: default_family_digest_send_at(),
+            calendars: Vec::new(),
+            ntfy_url: None,
+            ntfy_token: None,
+            webhook: None,
+        }
+    }
+}
+
+fn default_family_digest_send_at() -> String {
+    "07:00".to_string()
+}
+
+/// How events are treated by the user's answer to them, whichever calendar
+/// backend they came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AttendanceConfig {
+    #[serde(default = "default_declined_policy")]
+    pub declined: AttendancePolicy,
+    #[serde(default = "default_tentative_policy")]
+    pub tentative: AttendancePolicy,
+}
+
+impl Default for AttendanceConfig {
+    fn default() -> Self {
+        Self {
+            declined: default_declined_policy(),
+            tentative: default_tentative_policy(),
+        }
+    }
+}
+
+/// The nightly maintenance window: prune old rows, vacuum, checkpoint the
+/// WAL, evict expired caches and check the calendar token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Local time, "HH:MM", from which the night's maintenance runs
+    #[serde(default = "default_maintenance_run_at")]
+    pub run_at: String,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            run_at: default_maintenance_run_at(),
+        }
+    }
+}
+
+fn default_maintenance_run_at() -> String {
+    "03:00".to_string()
+}
+
+/// The same task in two sources (a Todoist task mirrored by a vault
+/// checkbox) is shown once: titles that are close enough, with due dates
+/// that don't disagree, count as one task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TasksConfig {
+    #[serde(default = "default_true")]
+    pub dedupe: bool,
+    /// Whose copy is kept, most preferred first, by task source ("todoist",
+    /// "obsidian", "local"); unlisted sources rank last
+    #[serde(default = "default_task_preference")]
+    pub prefer: Vec<String>,
+    /// How alike two titles must be, from 0 (anything) to 1 (identical
+    /// apart from case, spacing and punctuation)
+    #[serde(default = "default_title_similarity")]
+    pub title_similarity: f64,
+    /// Due dates further apart than this mean different tasks
+    #[serde(default = "default_due_tolerance_hours")]
+    pub due_tolerance_hours: u32,
+    /// Task file read as the `tasks_local` source: JSON as `import` writes
+    /// it, or markdown checkboxes. Defaults to `tasks.json` in the data
+    /// directory, which is read once it exists.
+    #[serde(default)]
+    pub local_file: Option<String>,
+    /// A todo.txt file to read tasks from; tasks checked off from the
+    /// command menu are marked done in it
+    #[serde(default)]
+    pub todo_txt: Option<String>,
+    /// Org-mode files to read TODO headlines from
+    #[serde(default)]
+    pub org_files: Vec<String>,
+}
+
+impl Default for TasksConfig {
+    fn default() -> Self {
+        Self {
+            dedupe: true,
+            prefer: default_task_preference(),
+            title_similarity: default_title_similarity(),
+            due_tolerance_hours: default_due_tolerance_hours(),
+            local_file: None,
+            todo_txt: None,
+            org_files: Vec::new(),
+        }
+    }
+}
+
+fn default_task_preference() -> Vec<String> {
+    vec![
+        "todoist".to_string(),
+        "local".to_string(),
+        "obsidian".to_string(),
+    ]
+}
+
+/// `path` with a leading `~/` replaced by the home directory
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(stripped) => dirs::home_dir()
+            .map(|home| home.join(stripped))
+            .unwrap_or_else(|| PathBuf::from(path)),
+        None => PathBuf::from(path),
+    }
+}
+
+fn default_title_similarity() -> f64 {
+    0.85
+}
+
+fn default_due_tolerance_hours() -> u32 {
+    24
+}
+
+/// What is removed from each context source's text before it reaches the
+/// prompt (see `sanitize::sanitize_context_data`). A source with an entry in
+/// `sources` (by source ID: "obsidian", "weather", "tasks_todoist", ...,
+/// plus "calendar" and "injected") uses that profile instead of `default`.
+/// Entries are laid over the built-in ones rather than replacing them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SanitizationConfig {
+    #[serde(default)]
+    pub default: SanitizationProfile,
+    #[serde(
+        default = "default_sanitization_sources",
+        deserialize_with = "sanitization_sources"
+    )]
+    pub sources: BTreeMap<String, SanitizationProfile>,
+}
+
+impl Default for SanitizationConfig {
+    fn default() -> Self {
+        Self {
+            default: SanitizationProfile::default(),
+            sources: default_sanitization_sources(),
+        }
+    }
+}
+
+/// Personal notes carry contact details that weather and task feeds don't
+fn default_sanitization_sources() -> BTreeMap<String, SanitizationProfile> {
+    BTreeMap::from([(
+        "obsidian".to_string(),
+        SanitizationProfile {
+            level: SanitizationLevel::Standard,
+            redact: Vec::new(),
+        },
+    )])
+}
+
+fn sanitization_sources<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, SanitizationProfile>, D::Error> {
+    over_defaults(deserializer, default_sanitization_sources())
+}
+
+/// A map's built-in entries with the configured ones laid over them, so
+/// configuring one entry doesn't drop the others
+fn over_defaults<'de, D, V>(
+    deserializer: D,
+    defaults: BTreeMap<String, V>,
+) -> Result<BTreeMap<String, V>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let mut merged = defaults;
+    merged.extend(BTreeMap::<String, V>::deserialize(deserializer)?);
+    Ok(merged)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SanitizationProfile {
+    #[serde(default)]
+    pub level: SanitizationLevel,
+    /// Regular expressions whose matches are replaced with "[redacted]"
+    #[serde(default)]
+    pub redact: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SanitizationLevel {
+    /// Text is passed as fetched
+    #[default]
+    Off,
+    /// Email addresses and phone numbers are replaced with "[redacted]"
+    Standard,
+    /// Links and passcodes too
+    Strict,
+}
+
+/// How hard Jasper may hit each external API host. Requests over `qps` wait
+/// for a slot; once a host's `daily` budget is spent its requests fail until
+/// midnight, so a too-short sync interval can't run up a bill or get the
+/// key banned. The AI provider has its own cap in `[api]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitsConfig {
+    /// Requests per second to any host without its own entry
+    #[serde(default = "default_rate_limit_qps")]
+    pub qps: f64,
+    /// Requests per day to any host without its own entry
+    #[serde(default = "default_rate_limit_daily")]
+    pub daily: u32,
+    /// Per-host limits, keyed by host name ("api.todoist.com"); an entry also
+    /// covers subdomains of the host
+    #[serde(
+        default = "default_host_rate_limits",
+        deserialize_with = "host_rate_limits"
+    )]
+    pub hosts: BTreeMap<String, HostRateLimit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HostRateLimit {
+    /// Requests per second
+    pub qps: f64,
+    /// Requests per day
+    pub daily: u32,
+}
+
+impl Default for RateLimitsConfig {
+    fn default() -> Self {
+        Self {
+            qps: default_rate_limit_qps(),
+            daily: default_rate_limit_daily(),
+            hosts: default_host_rate_limits(),
+        }
+    }
+}
+
+impl RateLimitsConfig {
+    /// The limit that applies to `host` and the name it is counted under:
+    /// the host's own entry, the entry of its closest parent domain, or the
+    /// default (counted per host)
+    pub fn for_host<'a>(&self, host: &'a str) -> (&'a str, HostRateLimit) {
+        let mut domain = host;
+        loop {
+            if let Some(limit) = self.hosts.get(domain) {
+                return (domain, *limit);
+            }
+            match domain.split_once('.') {
+                Some((_, parent)) => domain = parent,
+                None => {
+                    let limit = HostRateLimit {
+                        qps: self.qps,
+                        daily: self.daily,
+                    };
+                    return (host, limit);
+                }
+            }
+        }
+    }
+}
+
+fn default_rate_limit_qps() -> f64 {
+    2.0
+}
+
+fn default_rate_limit_daily() -> u32 {
+    5_000
+}
+
+fn default_host_rate_limits() -> BTreeMap<String, HostRateLimit> {
+    // Free tiers: Todoist allows 450 requests per 15 minutes, the Google
+    // Maps Platform APIs bill per call
+    [
+        ("api.todoist.com", 1.0, 2_000),
+        ("weather.googleapis.com", 1.0, 500),
+        ("pollen.googleapis.com", 1.0, 200),
+        ("routes.googleapis.com", 2.0, 1_000),
+        ("api.openaq.org", 1.0, 500),
+    ]
+    .into_iter()
+    .map(|(host, qps, daily)| (host.to_string(), HostRateLimit { qps, daily }))
+    .collect()
+}
+
+fn host_rate_limits<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, HostRateLimit>, D::Error> {
+    over_defaults(deserializer, default_host_rate_limits())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttendancePolicy {
+    /// Treat it like any other event
+    Keep,
+    /// Keep it, marked tentative and left out of conflict checks
+    Demote,
+    /// Leave it out of analysis, agenda and bars
+    Drop,
+}
+
+/// People recognized among event attendees, besides Obsidian people notes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PeopleConfig {
+    /// Household members; meetings with them are framed as family, not work
+    #[serde(default)]
+    pub household: Vec<HouseholdMember>,
+    /// Updating Obsidian people notes after meetings with them
+    #[serde(default)]
+    pub note_updates: PeopleNoteUpdates,
+}
+
+/// What to write to a person's note once a meeting with them ends (see
+/// `people_notes`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeopleNoteUpdates {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Ask with a notification action before writing; without it, write
+    #[serde(default = "default_true")]
+    pub confirm: bool,
+    /// Move frontmatter `last_contact` up to the meeting's date
+    #[serde(default = "default_true")]
+    pub last_contact: bool,
+    /// Append a "- Met on <date>: <event>" line
+    #[serde(default = "default_true")]
+    pub log_line: bool,
+}
+
+impl Default for PeopleNoteUpdates {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            confirm: true,
+            last_contact: true,
+            log_line: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HouseholdMember {
+    pub name: String,
+    /// Addresses they are invited under
+    #[serde(default)]
+    pub emails: Vec<String>,
+    /// Other names they appear under in calendars
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// e.g. "partner", "son"
+    #[serde(default)]
+    pub relation: Option<String>,
+}
+
+/// Suggest (and optionally set) an earlier alarm when tomorrow starts early
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Normal wake-up time, "HH:MM"; only earlier alarms are suggested
+    #[serde(default = "default_usual_wake")]
+    pub usual_wake: String,
+    /// Minutes from waking up to heading out
+    #[serde(default = "default_ready_minutes")]
+    pub ready_minutes: u32,
+    /// Extra minutes when ice or rain is forecast for an in-person first event
+    #[serde(default = "default_bad_weather_buffer_minutes")]
+    pub bad_weather_buffer_minutes: u32,
+    /// Local time, "HH:MM", from which the evening suggestion is made
+    #[serde(default = "default_alarm_remind_after")]
+    pub remind_after: String,
+    /// "none" (insight only), "systemd" (user timer + critical notification)
+    /// or "command"
+    #[serde(default = "default_alarm_backend")]
+    pub backend: String,
+    /// Run through `sh -c` for the "command" backend, with JASPER_ALARM_TIME,
+    /// JASPER_ALARM_AT and JASPER_ALARM_LABEL set
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+impl Default for AlarmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            usual_wake: default_usual_wake(),
+            ready_minutes: default_ready_minutes(),
+            bad_weather_buffer_minutes: default_bad_weather_buffer_minutes(),
+            remind_after: default_alarm_remind_after(),
+            backend: default_alarm_backend(),
+            command: None,
+        }
+    }
+}
+
+/// The login greeting (`greet`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GreetConfig {
+    /// Read the greeting aloud as well as showing it
+    #[serde(default)]
+    pub speak: bool,
+    /// Run through `sh -c` with the text on stdin instead of the system's
+    /// speech tool (spd-say, say or System.Speech), e.g. a piper pipeline
+    #[serde(default)]
+    pub tts_command: Option<String>,
+    /// How long to wait for the daemon, which starts at login too
+    #[serde(default = "default_greet_wait_seconds")]
+    pub wait_seconds: u64,
+}
+
+impl Default for GreetConfig {
+    fn default() -> Self {
+        Self {
+            speak: false,
+            tts_command: None,
+            wait_seconds: default_greet_wait_seconds(),
+        }
+    }
+}
+
+fn default_greet_wait_seconds() -> u64 {
+    30
+}
+
+fn default_usual_wake() -> String {
+    "06:30".to_string()
+}
+fn default_ready_minutes() -> u32 {
+    60
+}
+fn default_bad_weather_buffer_minutes() -> u32 {
+    15
+}
+fn default_alarm_remind_after() -> String {
+    "19:00".to_string()
+}
+fn default_alarm_backend() -> String {
+    "none".to_string()
+}
+fn default_escalation_after_insights() -> u32 {
+    3
+}
+fn default_escalation_min_task_priority() -> i32 {
+    8
+}
+fn default_sendmail_path() -> String {
+    "sendmail".to_string()
+}
+fn default_slack_status_emoji() -> String {
+    ":spiral_calendar_pad:".to_string()
+}
+fn default_true() -> bool {
+    true
+}
+fn default_config_version() -> u32 {
+    config_migration::CURRENT_VERSION
+}
+fn default_key_source() -> String {
+    "keyring".to_string()
+}
+fn default_join_reminder_minutes() -> u32 {
+    2
+}
+fn default_declined_policy() -> AttendancePolicy {
+    AttendancePolicy::Drop
+}
+fn default_tentative_policy() -> AttendancePolicy {
+    AttendancePolicy::Demote
+}
+fn default_check_interval_seconds() -> u64 {
+    60
+}
+fn default_snapshot_max_kb() -> u32 {
+    256
+}
+fn default_snapshot_retention_days() -> u32 {
+    30
+}
+fn default_max_prompt_tokens() -> usize {
+    6000
+}
+fn default_response_cache_ttl_minutes() -> u32 {
+    30
+}
+fn default_alert_aqi() -> u32 {
+    100
+}
+fn default_alert_pollen_index() -> u32 {
+    4
+}
+fn default_heartbeat_timeout_seconds() -> u64 {
+    // Comfortably above the 30s poll interval plus the 60s check interval
+    120
+}
+fn default_stop_grace_seconds() -> u64 {
+    60
+}
+fn default_stale_after_minutes() -> u64 {
+    120
+}
+fn default_min_heartbeats() -> u32 {
+    1
+}
+fn default_max_heartbeats() -> u32 {
+    6
+}
+fn default_ai_provider() -> String {
+    "anthropic".to_string()
+}
+fn default_terminal_template() -> String {
+    crate::terminal_formatter::DEFAULT_TEMPLATE.to_string()
+}
+fn default_units() -> String {
+    "imperial".to_string()
+}
+fn default_travel_mode() -> String {
+    "DRIVE".to_string()
+}
+fn default_lookahead_hours() -> u32 {
+    12
+}
+fn default_cache_duration() -> u32 {
+    30
+}
+fn default_jira_jql() -> String {
+    "assignee = currentUser() AND sprint in openSprints() AND statusCategory != Done ORDER BY duedate"
+        .to_string()
+}
+fn default_jira_cache_minutes() -> u32 {
+    15
+}
+fn default_slack_unanswered_hours() -> u32 {
+    4
+}
+fn default_slack_cache_minutes() -> u32 {
+    10
+}
+fn default_school_alert_max_age_hours() -> u32 {
+    24
+}
+fn default_school_alert_cache_minutes() -> u32 {
+    10
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: config_migration::CURRENT_VERSION,
+            general: GeneralConfig {
+                planning_horizon_days: 7,
+                timezone: "America/Detroit".to_string(),
+                personal_context_file: None,
+                check_for_updates: false,
+                check_interval_seconds: default_check_interval_seconds(),
+                check_jitter_seconds: 0,
+            },
+            ai: AiConfig {
+                model: "claude-sonnet-4-6".to_string(),
+                api_key: None, // Falls back to ANTHROPIC_API_KEY environment variable
+                max_prompt_tokens: default_max_prompt_tokens(),
+                response_cache_ttl_minutes: default_response_cache_ttl_minutes(),
+                provider: default_ai_provider(),
+                calls: AiCallsConfig::default(),
+                fallback_models: Vec::new(),
+                prompt_caching: true,
+                zero_retention: false,
+                day_summary: true,
+            },
+            personality: PersonalityConfig {
+                user_title: "Tom".to_string(),
+                formality: "balanced".to_string(),
+                assistant_persona: "trusted family assistant".to_string(),
+                persona_reference: Some("like Alfred from Batman".to_string()),
+            },
+            google_calendar: Some(GoogleCalendarConfig {
+                enabled: false, // Disabled by default, user must configure
+                client_id: String::new(),
+                client_secret: String::new(),
+                redirect_uri: "http://localhost:8080/auth/callback".to_string(),
+                calendar_ids: vec!["primary".to_string()],
+                sync_interval_minutes: 15,
+            }),
+            context_sources: Some(ContextSourcesConfig {
+                obsidian: Some(ObsidianConfig {
+                    enabled: false,
+                    vault_path: "~/Documents/Obsidian Vault".to_string(),
+                    tomorrow_note: TomorrowNoteConfig::default(),
+                }),
+                weather: Some(WeatherConfig {
+                    enabled: false, // Disabled by default, needs API key
+                    google_api_key: String::new(),
+                    latitude: 42.3314,
+                    longitude: -83.0458,
+                    units: "imperial".to_string(),
+                    cache_duration_minutes: 30,
+                    fixture_path: None,
+                    air_quality: AirQualityConfig::default(),
+                    pollen: PollenConfig::default(),
+                }),
+                travel: Some(TravelConfig {
+                    enabled: false,
+                    google_api_key: String::new(),
+                    home_address: String::new(),
+                    travel_mode: "DRIVE".to_string(),
+                    lookahead_hours: 12,
+                    cache_duration_minutes: 30,
+                }),
+                school_alerts: None,
+                jira: None,
+                slack: None,
+            }),
+            frontends: FrontendsConfig::default(),
+            database: DatabaseConfig::default(),
+            delivery: DeliveryConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            escalation: EscalationConfig::default(),
+            alarm: AlarmConfig::default(),
+            stats: StatsConfig::default(),
+            coalescing: CoalescingConfig::default(),
+            activities: ActivitiesConfig::default(),
+            family_digest: FamilyDigestConfig::default(),
+            people: PeopleConfig::default(),
+            attendance: AttendanceConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            rate_limits: RateLimitsConfig::default(),
+            tasks: TasksConfig::default(),
+            sanitization: SanitizationConfig::default(),
+            greet: GreetConfig::default(),
+            routing: RoutingConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    pub async fn load() -> Result<ConfigHandle> {
+        let config_path = Self::get_config_path()?;
+
+        let config = if config_path.exists() {
+            Self::load_file(&config_path).await?
+        } else {
+            info!("Config file not found, creating default configuration");
+            let default_config = Config::default();
+            default_config.save().await?;
+            default_config.with_overrides()?
+        };
+
+        Ok(ConfigHandle::new(config))
+    }
+
+    /// Read the config file at `path`: migrated to the current layout, with
+    /// SOPS secrets and environment overrides applied, and validated
+    pub async fn load_file(config_path: &Path) -> Result<Config> {
+        let content = fs::read_to_string(config_path)
+            .await
+            .with_context(|| format!("Failed to read config file: {:?}", config_path))?;
+
+        let mut table: toml::Table =
+            toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+        let report = config_migration::migrate(&mut table);
+        if report.changed() {
+            for change in &report.changes {
+                info!("Config migration: {}", change);
+            }
+            // A read-only config (e.g. managed by Nix) still loads, migrated in memory
+            match Self::write_migrated(config_path, &content, &table, &report).await {
+                Ok(backup) => info!(
+                    "Migrated config from version {} to {}; original kept at {:?}",
+                    report.from_version,
+                    config_migration::CURRENT_VERSION,
+                    backup
+                ),
+                Err(e) => warn!("Config migrated in memory only: {:#}", e),
+            }
+        } else if report.from_version > config_migration::CURRENT_VERSION {
+            warn!(
+                "Config file is version {}, newer than this Jasper ({}); unknown settings are ignored",
+                report.from_version,
+                config_migration::CURRENT_VERSION
+            );
+        }
+
+        let config: Config = toml::Value::Table(table)
+            .try_into()
+            .with_context(|| "Failed to parse config file")?;
+        config.with_overrides()
+    }
+
+    /// Apply SOPS secrets and environment overrides, then validate
+    fn with_overrides(mut self) -> Result<Self> {
+        // Load secrets from SOPS and override config values
+        match SopsSecrets::load() {
+            Ok(secrets) => {
+                self.apply_sops_secrets(&secrets);
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to load SOPS secrets, using config file values: {}",
+                    e
+                );
+            }
+        }
+
+        // Apply environment variable overrides (for NixOS sops-nix integration)
+        self.apply_env_overrides();
+
+        // Validate configuration
+        self.validate().context(JasperError::Config {
+            message: "settings are invalid".to_string(),
+        })?;
+
+        Ok(self)
+    }
+
+    /// Apply SOPS secrets to override config values
+    fn apply_sops_secrets(&mut self, secrets: &SopsSecrets) {
+        // Override AI API key
+        if let Some(anthropic_api_key) = secrets.get("services.anthropic_api_key") {
+            debug!("Using Anthropic API key from SOPS");
+            self.ai.api_key = Some(anthropic_api_key.clone());
+        }
+
+        // Override Google Calendar credentials
+        if let Some(google_client_id) = secrets.get("services.google_calendar.client_id") {
+            debug!("Using Google Calendar client ID from SOPS");
+            if let Some(ref mut google_config) = self.google_calendar {
+                google_config.client_id = google_client_id.clone();
+            }
+        }
+
+        if let Some(google_client_secret) = secrets.get("services.google_calendar.client_secret") {
+            debug!("Using Google Calendar client secret from SOPS");
+            if let Some(ref mut google_config) = self.google_calendar {
+                google_config.client_secret = google_client_secret.clone();
+            }
+        }
+
+        // Override Google Weather API key
+        if let Some(weather_api_key) = secrets.get("services.google_weather_api_key") {
+            debug!("Using Google Weather API key from SOPS");
+            if let Some(ref mut context_sources) = self.context_sources {
+                if let Some(ref mut weather_config) = context_sources.weather {
+                    weather_config.google_api_key = weather_api_key.clone();
+                    weather_config.enabled = true;
+                    debug!("Weather context source enabled with Google Weather API key from SOPS");
+                }
+            }
+        }
+
+        // Override Google Routes API key and home address
+        if let Some(ref mut context_sources) = self.context_sources {
+            if let Some(ref mut travel_config) = context_sources.travel {
+                if let Some(routes_api_key) = secrets.get("services.google_routes_api_key") {
+                    debug!("Using Google Routes API key from SOPS");
+                    travel_config.google_api_key = routes_api_key.clone();
+                }
+                if let Some(home_addr) = secrets.get("services.jasper_home_address") {
+                    debug!("Using home address from SOPS");
+                    travel_config.home_address = home_addr.clone();
+                }
+            }
+        }
+
+        if let Some(jira_token) = secrets.get("services.jira_api_token") {
+            debug!("Using Jira API token from SOPS");
+            if let Some(ref mut jira) = self.context_sources.as_mut().and_then(|s| s.jira.as_mut())
+            {
+                jira.api_token = jira_token.clone();
+            }
+        }
+
+        if let Some(slack_token) = secrets.get("services.slack_bot_token") {
+            debug!("Using Slack bot token from SOPS");
+            if let Some(ref mut slack) =
+                self.context_sources.as_mut().and_then(|s| s.slack.as_mut())
+            {
+                slack.token = slack_token.clone();
+            }
+        }
+
+        if let Some(slack_token) = secrets.get("services.slack_token") {
+            debug!("Using Slack token from SOPS");
+            if let Some(ref mut slack) = self.delivery.slack {
+                slack.token = slack_token.clone();
+            }
+        }
+
+        if let Some(telegram_token) = secrets.get("services.telegram_bot_token") {
+            debug!("Using Telegram bot token from SOPS");
+            if let Some(ref mut telegram) = self.delivery.telegram {
+                telegram.bot_token = telegram_token.clone();
+            }
+        }
+
+        info!("Applied SOPS secrets to configuration");
+    }
+
+    /// Upgrade the config file to the current layout, keeping the original
+    /// as `<file>.v<N>.bak`. With `dry_run` the report is produced but nothing
+    /// is written. None if there is no config file.
+    pub async fn migrate_file(dry_run: bool) -> Result<Option<(MigrationReport, Option<PathBuf>)>> {
+        let config_path = Self::get_config_path()?;
+        if !config_path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&config_path)
+            .await
+            .with_context(|| format!("Failed to read config file: {:?}", config_path))?;
+        let mut table: toml::Table =
+            toml::from_str(&content).with_context(|| "Failed to parse config file")?;
+
+        let report = config_migration::migrate(&mut table);
+        let backup = if report.changed() && !dry_run {
+            Some(Self::write_migrated(&config_path, &content, &table, &report).await?)
+        } else {
+            None
+        };
+        Ok(Some((report, backup)))
+    }
+
+    /// Save the original file text next to it, then write the migrated layout
+    async fn write_migrated(
+        config_path: &Path,
+        original: &str,
+        table: &toml::Table,
+        report: &MigrationReport,
+    ) -> Result<PathBuf> {
+        let backup = PathBuf::from(format!(
+            "{}.v{}.bak",
+            config_path.display(),
+            report.from_version
+        ));
+        // An earlier backup of the same version is the truer original
+        if !backup.exists() {
+            fs::write(&backup, original)
+                .await
+                .with_context(|| format!("Failed to back up config file to {:?}", backup))?;
+        }
+
+        let content = toml::to_string_pretty(table).context("Failed to serialize config")?;
+        fs::write(config_path, content)
+            .await
+            .with_context(|| format!("Failed to write config file: {:?}", config_path))?;
+        Ok(backup)
+    }
+
+    /// Read only the `[frontends]` section of the config file.
+    ///
+    /// Bar adapters run once per poll, so this skips SOPS, env overrides,
+    /// validation and default-file creation, and never fails: a missing or
+    /// unparsable file just yields the defaults.
+    pub fn load_frontends() -> FrontendsConfig {
+        #[derive(Deserialize)]
+        struct FrontendsOnly {
+            #[serde(default)]
+            frontends: FrontendsConfig,
+        }
+
+        let Ok(path) = Self::get_config_path() else {
+            return FrontendsConfig::default();
+        };
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return FrontendsConfig::default();
+        };
+
+        match toml::from_str::<FrontendsOnly>(&content) {
+            Ok(parsed) => parsed.frontends,
+            Err(e) => {
+                warn!("Ignoring invalid [frontends] config: {}", e);
+                FrontendsConfig::default()
+            }
+        }
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        let config_path = Self::get_config_path()?;
+
+        // Ensure config directory exists
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create config directory: {:?}", parent))?;
+        }
+
+        let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
+
+        fs::write(&config_path, content)
+            .await
+            .with_context(|| format!("Failed to write config file: {:?}", config_path))?;
+
+        info!("Configuration saved to {:?}", config_path);
+        Ok(())
+    }
+
+    pub fn get_config_path() -> Result<PathBuf> {
+        if let Some(path) = PathOverrides::resolve(|o| o.config.as_ref(), "JASPER_CONFIG") {
+            return Ok(path);
+        }
+
+        let config_dir = dirs::config_dir()
+            .context("Failed to get config directory")?
+            .join("jasper-companion");
+
+        Ok(config_dir.join("config.toml"))
+    }
+
+    /// Path of the SQLite database (`<data_dir>/jasper.db` unless overridden)
+    pub fn get_database_path() -> Result<PathBuf> {
+        if let Some(path) = PathOverrides::resolve(|o| o.db_path.as_ref(), "JASPER_DB_PATH") {
+            return Ok(path);
+        }
+
+        Ok(Self::get_data_dir()?.join("jasper.db"))
+    }
+
+    /// Database path for this config: an explicit `--db-path`/`JASPER_DB_PATH`
+    /// wins, then `[database] ephemeral`, then the default
+    pub fn database_path(&self) -> Result<PathBuf> {
+        if self.database.ephemeral
+            && PathOverrides::resolve(|o| o.db_path.as_ref(), "JASPER_DB_PATH").is_none()
+        {
+            return Ok(PathBuf::from(crate::database::MEMORY_PATH));
+        }
+        Self::get_database_path()
+    }
+
+    pub fn get_data_dir() -> Result<PathBuf> {
+        if let Some(path) = PathOverrides::resolve(|o| o.data_dir.as_ref(), "JASPER_DATA_DIR") {
+            return Ok(path);
+        }
+
+        let data_dir = dirs::data_local_dir()
+            .or_else(|| dirs::home_dir().map(|h| h.join(".local/share")))
+            .ok_or_else(|| anyhow::anyhow!("Unable to determine data directory"))?
+            .join("jasper-companion");
+
+        Ok(data_dir)
+    }
+
+    /// The local task file (`[tasks] local_file`, else `<data_dir>/tasks.json`)
+    pub fn local_tasks_path(&self) -> Result<PathBuf> {
+        match self.tasks.local_file {
+            Some(ref configured) => Ok(expand_home(configured)),
+            None => Ok(Self::get_data_dir()?.join("tasks.json")),
+        }
+    }
+
+    /// The todo.txt file (`[tasks] todo_txt`), if one is configured
+    pub fn todo_txt_path(&self) -> Option<PathBuf> {
+        self.tasks.todo_txt.as_deref().map(expand_home)
+    }
+
+    /// The Org-mode files in `[tasks] org_files`
+    pub fn org_file_paths(&self) -> Vec<PathBuf> {
+        self.tasks
+            .org_files
+            .iter()
+            .map(|path| expand_home(path))
+            .collect()
+    }
+
+    /// Get the personal context file path (explicit config or default convention)
+    pub fn get_personal_context_path() -> Option<PathBuf> {
+        // Lives next to config.toml, so a relocated config brings its context along
+        let config_path = Self::get_config_path().ok()?;
+        Some(config_path.parent()?.join("context.md"))
+    }
+
+    /// Get enhanced personality configuration for prompt generation
+    pub fn get_personality_config(&self) -> (&PersonalityConfig, &str) {
+        (&self.personality, &self.general.timezone)
+    }
+
+    /// Get planning horizon as chrono Duration
+    pub fn get_planning_horizon(&self) -> chrono::Duration {
+        chrono::Duration::days(self.general.planning_horizon_days as i64)
+    }
+
+    /// Database encryption key, or `None` when encryption is off.
+    ///
+    /// `JASPER_DB_KEY` always wins so sops-nix and containers can inject it;
+    /// otherwise the configured source is consulted and a missing key is an
+    /// error rather than a silent fallback to plaintext.
+    pub fn database_key(&self) -> Result<Option<String>> {
+        if !self.database.encrypt {
+            return Ok(None);
+        }
+        if let Ok(key) = std::env::var("JASPER_DB_KEY") {
+            if !key.is_empty() {
+                debug!("Using database key from JASPER_DB_KEY env var");
+                return Ok(Some(key));
+            }
+        }
+
+        let key = match self.database.key_source.as_str() {
+            // The sandbox hides the Secret Service; the portal's app secret stands in
+            source
+                if source == "portal"
+                    || (source == "keyring" && crate::desktop_detection::is_sandboxed()) =>
+            {
+                crate::portal::retrieve_secret()
+                    .context("Failed to retrieve secret from the Secret portal")?
+            }
+            "keyring" => {
+                let output = std::process::Command::new("secret-tool")
+                    .args(["lookup", "service", "jasper-companion", "key", "database"])
+                    .output()
+                    .context("Failed to run secret-tool (is libsecret installed?)")?;
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            }
+            "sops" => SopsSecrets::load()?
+                .get("services.jasper_database_key")
+                .cloned()
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+
+        if key.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Database encryption is enabled but no key was found via '{}'. \
+                 Store one with `secret-tool store --label=Jasper service jasper-companion key database` \
+                 or set JASPER_DB_KEY",
+                self.database.key_source
+            ));
+        }
+        Ok(Some(key))
+    }
+
+    /// Get API key from config or environment variable
+    pub fn get_api_key(&self) -> Option<String> {
+        self.ai
+            .api_key
+            .clone()
+            .or_else(|| std::env::var("ANTHROPIC_API_KEY").ok())
+    }
+
+    /// Apply Google Calendar credentials from environment variables
+    /// Called after config load to allow env vars to fill in empty credentials
+    pub fn apply_env_overrides(&mut self) {
+        if let Some(ref mut slack) = self.delivery.slack {
+            if slack.token.is_empty() {
+                if let Ok(token) = std::env::var("SLACK_TOKEN") {
+                    debug!("Using Slack token from SLACK_TOKEN env var");
+                    slack.token = token;
+                }
+            }
+        }
+
+        if let Some(jira) = self.context_sources.as_mut().and_then(|s| s.jira.as_mut()) {
+            if jira.api_token.is_empty() {
+                if let Ok(token) = std::env::var("JIRA_API_TOKEN") {
+                    debug!("Using Jira API token from JIRA_API_TOKEN env var");
+                    jira.api_token = token;
+                }
+            }
+        }
+
+        if let Some(slack) = self.context_sources.as_mut().and_then(|s| s.slack.as_mut()) {
+            if slack.token.is_empty() {
+                if let Ok(token) = std::env::var("SLACK_BOT_TOKEN") {
+                    debug!("Using Slack bot token from SLACK_BOT_TOKEN env var");
+                    slack.token = token;
+                }
+            }
+        }
+
+        if let Some(ref mut telegram) = self.delivery.telegram {
+            if telegram.bot_token.is_empty() {
+                if let Ok(token) = std::env::var("TELEGRAM_BOT_TOKEN") {
+                    debug!("Using Telegram bot token from TELEGRAM_BOT_TOKEN env var");
+                    telegram.bot_token = token;
+                }
+            }
+        }
+
+        if let Some(ref mut gc) = self.google_calendar {
+            if gc.client_id.is_empty() {
+                if let Ok(id) = std::env::var("GOOGLE_CLIENT_ID") {
+                    debug!("Using Google Calendar client ID from GOOGLE_CLIENT_ID env var");
+                    gc.client_id = id;
+                }
+            }
+            if gc.client_secret.is_empty() {
+                if let Ok(secret) = std::env::var("GOOGLE_CLIENT_SECRET") {
+                    debug!("Using Google Calendar client secret from GOOGLE_CLIENT_SECRET env var");
+                    gc.client_secret = secret;
+                }
+            }
+        }
+
+        // Google Weather API key
+        if let Some(ref mut context_sources) = self.context_sources {
+            if let Some(ref mut weather_config) = context_sources.weather {
+                if weather_config.google_api_key.is_empty() {
+                    if let Ok(key) = std::env::var("GOOGLE_WEATHER_API_KEY") {
+                        debug!("Using Google Weather API key from GOOGLE_WEATHER_API_KEY env var");
+                        weather_config.google_api_key = key;
+                        weather_config.enabled = true;
+                    }
+                }
+                if weather_config.air_quality.openaq_api_key.is_empty() {
+                    if let Ok(key) = std::env::var("OPENAQ_API_KEY") {
+                        debug!("Using OpenAQ API key from OPENAQ_API_KEY env var");
+                        weather_config.air_quality.openaq_api_key = key;
+                    }
+                }
+            }
+
+            // Google Routes API key + home address
+            if let Some(ref mut travel_config) = context_sources.travel {
+                if travel_config.google_api_key.is_empty() {
+                    if let Ok(key) = std::env::var("GOOGLE_ROUTES_API_KEY") {
+                        debug!("Using Google Routes API key from GOOGLE_ROUTES_API_KEY env var");
+                        travel_config.google_api_key = key;
+                    }
+                }
+                if travel_config.home_address.is_empty() {
+                    if let Ok(addr) = std::env::var("HOME_ADDRESS") {
+                        debug!("Using home address from HOME_ADDRESS env var");
+                        travel_config.home_address = addr;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Get timezone as parsed Tz object, falling back to UTC if invalid
+    pub fn get_timezone(&self) -> chrono_tz::Tz {
+        self.general
+            .timezone
+            .parse::<chrono_tz::Tz>()
+            .unwrap_or(chrono_tz::UTC)
+    }
+
+    /// Validate configuration values
+    pub(crate) fn validate(&self) -> Result<()> {
+        self.validate_basic_config()
+            .context("Basic configuration validation failed")?;
+
+        self.validate_network_config()
+            .context("Network configuration validation failed")?;
+
+        self.validate_dependencies()
+            .context("Dependency validation failed")?;
+
+        self.validate_context_sources()
+            .context("Context sources validation failed")?;
+
+        self.validate_security_settings()
+            .context("Security settings validation failed")?;
+
+        Ok(())
+    }
+
+    /// Validate email address format
+    fn validate_email(&self, email: &str, field_name: &str) -> Result<()> {
+        if email.is_empty() {
+            return Err(anyhow::anyhow!("{} cannot be empty", field_name));
+        }
+
+        // Basic email validation without external dependencies
+        let email_parts: Vec<&str> = email.split('@').collect();
+        if email_parts.len() != 2 {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: must contain exactly one @ symbol",
+                field_name,
+                email
+            ));
+        }
+
+        let local = email_parts[0];
+        let domain = email_parts[1];
+
+        // Validate local part
+        if local.is_empty() || local.len() > 64 {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: local part must be 1-64 characters",
+                field_name,
+                email
+            ));
+        }
+
+        // Validate domain part
+        if domain.is_empty() || domain.len() > 255 {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: domain part must be 1-255 characters",
+                field_name,
+                email
+            ));
+        }
+
+        // Basic domain format check
+        if !domain.contains('.') || domain.starts_with('.') || domain.ends_with('.') {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: domain must contain at least one dot and not start/end with dot", 
+                field_name, email
+            ));
+        }
+
+        // Check for invalid characters (basic check)
+        if email.contains(' ') || email.contains('\t') || email.contains('\n') {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: cannot contain whitespace",
+                field_name,
+                email
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Validate URL format
+    fn validate_url(&self, url: &str, field_name: &str) -> Result<()> {
+        if url.is_empty() {
+            return Err(anyhow::anyhow!("{} cannot be empty", field_name));
+        }
+
+        // Basic URL validation without external dependencies
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: must start with http:// or https://",
+                field_name,
+                url
+            ));
+        }
+
+        // Check for basic URL structure
+        let url_without_scheme = if let Some(stripped) = url.strip_prefix("https://") {
+            stripped
+        } else if let Some(stripped) = url.strip_prefix("http://") {
+            stripped
+        } else {
+            url
+        };
+
+        if url_without_scheme.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: missing domain after scheme",
+                field_name,
+                url
+            ));
+        }
+
+        // Basic domain validation (before first slash or end of string)
+        let domain_part = url_without_scheme.split('/').next().unwrap_or("");
+        if domain_part.is_empty() {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: missing domain",
+                field_name,
+                url
+            ));
+        }
+
+        // Check for invalid characters
+        if url.contains(' ') || url.contains('\t') || url.contains('\n') {
+            return Err(anyhow::anyhow!(
+                "{} '{}' is invalid: cannot contain whitespace",
+                field_name,
+                url
+            ));
+        }
+
+        // Check for localhost/IP addresses for security warnings
+        if url.starts_with("http://")
+            && !domain_part.starts_with("localhost")
+            && !domain_part.starts_with("127.0.0.1")
+            && !domain_part.starts_with("::1")
+        {
+            warn!(
+                "{} uses HTTP (not HTTPS) with non-localhost domain: {}",
+                field_name, url
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Validate timezone string
+    fn validate_timezone(&self, timezone: &str, field_name: &str) -> Result<()> {
+        if timezone.is_empty() {
+            return Err(anyhow::anyhow!("{} cannot be empty", field_name));
+        }
+
+        // Use chrono_tz for validation
+        match timezone.parse::<chrono_tz::Tz>() {
+            Ok(_) => Ok(()),
+            Err(_) => {
+                let suggestions = [
+                    "UTC",
+                    "America/New_York",
+                    "Europe/London",
+                    "Asia/Tokyo",
+                    "America/Detroit",
+                    "Europe/Paris",
+                    "Asia/Shanghai",
+                ];
+                Err(anyhow::anyhow!(
+                    "{} '{}' is invalid timezone. Common examples: {}",
+                    field_name,
+                    timezone,
+                    suggestions.join(", ")
+                ))
+            }
+        }
+    }
+
+    /// Latitude and longitude from the weather section, used for daylight times
+    pub fn location(&self) -> Option<(f64, f64)> {
+        self.context_sources
+            .as_ref()?
+            .weather
+            .as_ref()
+            .map(|w| (w.latitude, w.longitude))
+    }
+
+    fn validate_basic_config(&self) -> Result<()> {
+        // Validate timezone using helper function
+        self.validate_timezone(&self.general.timezone, "general.timezone")?;
+
+        crate::heartbeat::HeartbeatSchedule::from_config(&self.heartbeat)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if self.heartbeat.min_per_day > self.heartbeat.max_per_day {
+            return Err(anyhow::anyhow!(
+                "heartbeat.min_per_day ({}) cannot exceed heartbeat.max_per_day ({})",
+                self.heartbeat.min_per_day,
+                self.heartbeat.max_per_day
+            ));
+        }
+
+        for (field, value) in [
+            ("alarm.usual_wake", &self.alarm.usual_wake),
+            ("alarm.remind_after", &self.alarm.remind_after),
+            ("family_digest.send_at", &self.family_digest.send_at),
+            ("maintenance.run_at", &self.maintenance.run_at),
+            ("stats.workday_start", &self.stats.workday_start),
+            ("stats.workday_end", &self.stats.workday_end),
+        ]
+        .into_iter()
+        .chain(self.get_obsidian_config().map(|o| {
+            (
+                "context_sources.obsidian.tomorrow_note.run_at",
+                &o.tomorrow_note.run_at,
+            )
+        })) {
+            if chrono::NaiveTime::parse_from_str(value, "%H:%M").is_err() {
+                return Err(anyhow::anyhow!(
+                    "{} must be a time like \"06:30\" (got: {})",
+                    field,
+                    value
+                ));
+            }
+        }
+        match self.alarm.backend.as_str() {
+            "none" | "systemd" => {}
+            "command" if self.alarm.command.is_some() => {}
+            "command" => {
+                return Err(anyhow::anyhow!(
+                    "alarm.backend = \"command\" needs alarm.command"
+                ))
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "alarm.backend must be \"none\", \"systemd\" or \"command\" (got: {})",
+                    other
+                ))
+            }
+        }
+
+        if self.escalation.after_insights == 0 {
+            return Err(anyhow::anyhow!(
+                "escalation.after_insights must be at least 1"
+            ));
+        }
+
+        if self.family_digest.enabled
+            && self.family_digest.ntfy_url.is_none()
+            && self.family_digest.webhook.is_none()
+        {
+            return Err(anyhow::anyhow!(
+                "family_digest needs ntfy_url or a [family_digest.webhook]"
+            ));
+        }
+
+        let unknown = self.routing.unknown_rows();
+        if !unknown.is_empty() {
+            let known: Vec<&str> = crate::glyphs::CATEGORIES
+                .iter()
+                .map(|(name, _)| *name)
+                .chain([crate::routing::OTHER_CATEGORY, crate::routing::DEFAULT_ROW])
+                .collect();
+            return Err(anyhow::anyhow!(
+                "Unknown routing categories: {} (known: {})",
+                unknown.join(", "),
+                known.join(", ")
+            ));
+        }
+
+        if self.general.check_interval_seconds < 10 {
+            return Err(anyhow::anyhow!(
+                "general.check_interval_seconds must be at least 10 (got: {})",
+                self.general.check_interval_seconds
+            ));
+        }
+        if self.general.check_jitter_seconds >= self.general.check_interval_seconds {
+            return Err(anyhow::anyhow!(
+                "general.check_jitter_seconds must be less than general.check_interval_seconds"
+            ));
+        }
+
+        if self.database.snapshot_max_kb == 0 || self.database.snapshot_retention_days == 0 {
+            return Err(anyhow::anyhow!(
+                "database.snapshot_max_kb and database.snapshot_retention_days must be at least 1"
+            ));
+        }
+
+        if self.activities.horizon_hours == 0 {
+            return Err(anyhow::anyhow!(
+                "activities.horizon_hours must be at least 1"
+            ));
+        }
+
+        if self.coalescing.max_wait_seconds < self.coalescing.settle_seconds {
+            return Err(anyhow::anyhow!(
+                "coalescing.max_wait_seconds must be at least coalescing.settle_seconds ({})",
+                self.coalescing.settle_seconds
+            ));
+        }
+
+        if self.frontends.heartbeat_timeout_seconds < 30 {
+            return Err(anyhow::anyhow!(
+                "frontends.heartbeat_timeout_seconds must be at least 30 (got: {})",
+                self.frontends.heartbeat_timeout_seconds
+            ));
+        }
+
+        if self.frontends.stale_after_minutes == 0 {
+            return Err(anyhow::anyhow!(
+                "frontends.stale_after_minutes must be at least 1"
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.tasks.title_similarity) {
+            return Err(anyhow::anyhow!(
+                "tasks.title_similarity must be between 0 and 1 (got: {})",
+                self.tasks.title_similarity
+            ));
+        }
+
+        for (source, profile) in std::iter::once(("default", &self.sanitization.default)).chain(
+            self.sanitization
+                .sources
+                .iter()
+                .map(|(source, profile)| (source.as_str(), profile)),
+        ) {
+            for rule in &profile.redact {
+                if let Err(e) = regex::Regex::new(rule) {
+                    return Err(anyhow::anyhow!(
+                        "sanitization rule for {} is not a valid regex ({}): {}",
+                        source,
+                        rule,
+                        e
+                    ));
+                }
+            }
+        }
+
+        // Validate planning horizon with reasonable bounds
+        if self.general.planning_horizon_days == 0 {
+            return Err(anyhow::anyhow!("Planning horizon must be at least 1 day"));
+        }
+        if self.general.planning_horizon_days > 365 {
+            return Err(anyhow::anyhow!(
+                "Planning horizon cannot exceed 365 days (got: {}). Consider a more reasonable timeframe for performance.",
+                self.general.planning_horizon_days
+            ));
+        }
+        if self.general.planning_horizon_days > 90 {
+            warn!(
+                "Planning horizon of {} days is quite large and may impact performance",
+                self.general.planning_horizon_days
+            );
+        }
+
+        Ok(())
+    }
+
+    fn validate_network_config(&self) -> Result<()> {
+        if let Some(ref webhook) = self.delivery.webhook {
+            if !webhook.url.starts_with("https://") && !webhook.url.starts_with("http://") {
+                return Err(anyhow::anyhow!(
+                    "delivery.webhook.url must be an http(s) URL (got: {})",
+                    webhook.url
+                ));
+            }
+            crate::webhook::parse_template(webhook.template.as_deref())
+                .map_err(|e| anyhow::anyhow!("{}", e))?;
+        }
+
+        let default_limit = HostRateLimit {
+            qps: self.rate_limits.qps,
+            daily: self.rate_limits.daily,
+        };
+        for (host, limit) in std::iter::once(("default", &default_limit))
+            .chain(self.rate_limits.hosts.iter().map(|(h, l)| (h.as_str(), l)))
+        {
+            if !(limit.qps > 0.0 && limit.qps.is_finite()) || limit.daily == 0 {
+                return Err(anyhow::anyhow!(
+                    "rate_limits for {} must allow at least some requests (qps {}, daily {})",
+                    host,
+                    limit.qps,
+                    limit.daily
+                ));
+            }
+        }
+
+        // Validate Google Calendar OAuth configuration
+        if let Some(ref gc) = self.google_calendar {
+            if gc.enabled {
+                self.validate_oauth_config(&gc.client_id, &gc.client_secret, &gc.redirect_uri)
+                    .context("Google Calendar OAuth validation failed")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_oauth_config(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        redirect_uri: &str,
+    ) -> Result<()> {
+        // Validate client ID format (Google OAuth client IDs have specific patterns)
+        if client_id.is_empty() {
+            return Err(anyhow::anyhow!("OAuth client_id cannot be empty"));
+        }
+        if client_id.len() < 50 || !client_id.ends_with(".googleusercontent.com") {
+            warn!(
+                "client_id '{}' doesn't match expected Google OAuth format",
+                &client_id[..client_id.len().min(20)]
+            );
+        }
+
+        // Validate client secret
+        if client_secret.is_empty() {
+            return Err(anyhow::anyhow!("OAuth client_secret cannot be empty"));
+        }
+        if client_secret.len() < 20 {
+            warn!("client_secret appears to be too short for a valid OAuth secret");
+        }
+
+        // Validate redirect URI
+        if redirect_uri.is_empty() {
+            return Err(anyhow::anyhow!("OAuth redirect_uri cannot be empty"));
+        }
+
+        // Use comprehensive URL validation
+        self.validate_url(redirect_uri, "redirect_uri")?;
+
+        // Security checks for redirect URI
+        if redirect_uri.starts_with("http://")
+            && !redirect_uri.contains("localhost")
+            && !redirect_uri.contains("127.0.0.1")
+        {
+            return Err(anyhow::anyhow!(
+                "HTTP redirect_uri only allowed for localhost/127.0.0.1, got: {}",
+                redirect_uri
+            ));
+        }
+
+        // Check for common OAuth security issues
+        if redirect_uri.contains("#") {
+            warn!("redirect_uri contains fragment (#), which may cause OAuth issues");
+        }
+
+        Ok(())
+    }
+
+    fn validate_dependencies(&self) -> Result<()> {
+        // Check for optional dependencies based on enabled features
+        if let Some(ref sources) = self.context_sources {
+            if let Some(ref obsidian) = sources.obsidian {
+                if obsidian.enabled {
+                    self.validate_obsidian_config(obsidian)
+                        .context("Obsidian configuration validation failed")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_obsidian_config(&self, obsidian: &ObsidianConfig) -> Result<()> {
+        let vault_path = Path::new(&obsidian.vault_path);
+
+        // Check if vault path exists
+        if !vault_path.exists() {
+            return Err(anyhow::anyhow!(
+                "Obsidian vault path does not exist: {}",
+                obsidian.vault_path
+            ));
+        }
+
+        // Check if it's a directory
+        if !vault_path.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Obsidian vault path is not a directory: {}",
+                obsidian.vault_path
+            ));
+        }
+
+        // Check for .obsidian directory (indicates valid Obsidian vault)
+        let obsidian_dir = vault_path.join(".obsidian");
+        if !obsidian_dir.exists() {
+            warn!(
+                "No .obsidian directory found in '{}'. This may not be a valid Obsidian vault.",
+                obsidian.vault_path
+            );
+        }
+
+        // Obsidian config is valid - check basic required folders exist
+        debug!(
+            "Obsidian vault validation successful for: {}",
+            obsidian.vault_path
+        );
+
+        Ok(())
+    }
+
+    fn validate_context_sources(&self) -> Result<()> {
+        if let Some(ref sources) = self.context_sources {
+            let mut enabled_sources = 0;
+
+            // Check each context source (calendar via Google Calendar config)
+            if let Some(ref gc) = self.google_calendar {
+                if gc.enabled {
+                    enabled_sources += 1;
+                }
+            }
+            if let Some(ref obs) = sources.obsidian {
+                if obs.enabled {
+                    enabled_sources += 1;
+                }
+            }
+            if let Some(ref weather) = sources.weather {
+                if weather.enabled {
+                    enabled_sources += 1;
+                }
+                if !["imperial", "metric"]
+                    .iter()
+                    .any(|units| weather.units.eq_ignore_ascii_case(units))
+                {
+                    return Err(anyhow::anyhow!(
+                        "Weather units must be 'imperial' or 'metric', got '{}'",
+                        weather.units
+                    ));
+                }
+            }
+            if let Some(ref travel) = sources.travel {
+                if travel.enabled {
+                    enabled_sources += 1;
+                }
+            }
+            if let Some(ref school) = sources.school_alerts {
+                if school.enabled {
+                    enabled_sources += 1;
+                    if school.feeds.is_empty() {
+                        return Err(anyhow::anyhow!(
+                            "context_sources.school_alerts is enabled but has no feeds"
+                        ));
+                    }
+                }
+                if let Some(feed) = school
+                    .feeds
+                    .iter()
+                    .find(|f| !f.url.starts_with("https://") && !f.url.starts_with("http://"))
+                {
+                    return Err(anyhow::anyhow!(
+                        "School alert feed '{}' must be an http(s) URL, got '{}'",
+                        feed.name,
+                        feed.url
+                    ));
+                }
+                if school.max_age_hours == 0 {
+                    return Err(anyhow::anyhow!(
+                        "context_sources.school_alerts.max_age_hours must be at least 1"
+                    ));
+                }
+            }
+
+            if let Some(ref jira) = sources.jira {
+                if jira.enabled {
+                    enabled_sources += 1;
+                }
+                if !jira.base_url.starts_with("https://") && !jira.base_url.starts_with("http://") {
+                    return Err(anyhow::anyhow!(
+                        "context_sources.jira.base_url must be an http(s) URL, got '{}'",
+                        jira.base_url
+                    ));
+                }
+            }
+
+            if let Some(ref slack) = sources.slack {
+                if slack.enabled {
+                    enabled_sources += 1;
+                }
+                if slack.unanswered_hours == 0 {
+                    return Err(anyhow::anyhow!(
+                        "context_sources.slack.unanswered_hours must be at least 1"
+                    ));
+                }
+            }
+
+            if enabled_sources == 0 {
+                warn!(
+                    "No context sources are enabled. The system will have limited functionality."
+                );
+            }
+
+            debug!("Validated {} enabled context sources", enabled_sources);
+        } else {
+            warn!("No context sources configuration found");
+        }
+
+        Ok(())
+    }
+
+    fn validate_security_settings(&self) -> Result<()> {
+        // Check for insecure configurations
+        if let Some(ref gc) = self.google_calendar {
+            if gc.enabled
+                && gc.redirect_uri.starts_with("http://")
+                && !gc.redirect_uri.contains("localhost")
+                && !gc.redirect_uri.contains("127.0.0.1")
+            {
+                return Err(anyhow::anyhow!(
+                    "HTTP OAuth redirect is insecure for non-localhost URLs: {}",
+                    gc.redirect_uri
+                ));
+            }
+        }
+
+        if let Some(ref url) = self.delivery.ntfy_url {
+            if !url.starts_with("https://") && !url.starts_with("http://localhost") {
+                warn!(
+                    "ntfy_url is not HTTPS; insights will be sent in cleartext: {}",
+                    url
+                );
+            }
+        }
+
+        if !["anthropic", crate::mock_ai::PROVIDER].contains(&self.ai.provider.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Invalid ai.provider '{}': must be 'anthropic' or 'mock'",
+                self.ai.provider
+            ));
+        }
+
+        if self.ai.fallback_models.iter().any(|m| m.trim().is_empty()) {
+            return Err(anyhow::anyhow!(
+                "ai.fallback_models must not contain empty names"
+            ));
+        }
+
+        for call in [
+            AiCallType::Insight,
+            AiCallType::Digest,
+            AiCallType::Reflection,
+        ] {
+            let overrides = self.ai.call(call);
+            if overrides
+                .model
+                .as_deref()
+                .is_some_and(|m| m.trim().is_empty())
+            {
+                return Err(anyhow::anyhow!(
+                    "ai.calls.{}.model must not be empty",
+                    call.name()
+                ));
+            }
+            if let Some(temperature) = overrides.temperature {
+                if !(0.0..=1.0).contains(&temperature) {
+                    return Err(anyhow::anyhow!(
+                        "ai.calls.{}.temperature must be between 0.0 and 1.0 (got: {})",
+                        call.name(),
+                        temperature
+                    ));
+                }
+            }
+            if let Some(max_tokens) = overrides.max_tokens {
+                if !(1..=MAX_OUTPUT_TOKENS).contains(&max_tokens) {
+                    return Err(anyhow::anyhow!(
+                        "ai.calls.{}.max_tokens must be between 1 and {} (got: {})",
+                        call.name(),
+                        MAX_OUTPUT_TOKENS,
+                        max_tokens
+                    ));
+                }
+            }
+        }
+
+        if !["keyring", "portal", "sops", "env"].contains(&self.database.key_source.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Invalid database.key_source '{}': must be 'keyring', 'portal', 'sops' or 'env'",
+                self.database.key_source
+            ));
+        }
+        if self.database.encrypt && !cfg!(feature = "sqlcipher") {
+            return Err(anyhow::anyhow!(
+                "database.encrypt is set but this build lacks the 'sqlcipher' feature"
+            ));
+        }
+
+        // Validate configured paths don't have obvious security issues
+        if let Some(ref sources) = self.context_sources {
+            if let Some(ref obs) = sources.obsidian {
+                if obs.enabled
+                    && (obs.vault_path.contains("../") || obs.vault_path.starts_with("/tmp/"))
+                {
+                    warn!("Potentially insecure vault path: {}", obs.vault_path);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get Obsidian configuration
+    pub fn get_obsidian_config(&self) -> Option<&ObsidianConfig> {
+        self.context_sources.as_ref()?.obsidian.as_ref()
+    }
+
+    /// Get Weather configuration
+    pub fn get_weather_config(&self) -> Option<&WeatherConfig> {
+        self.context_sources.as_ref()?.weather.as_ref()
+    }
+
+    /// Get Travel configuration
+    pub fn get_travel_config(&self) -> Option<&TravelConfig> {
+        self.context_sources.as_ref()?.travel.as_ref()
+    }
+
+    /// Get school alerts configuration
+    pub fn get_school_alerts_config(&self) -> Option<&SchoolAlertsConfig> {
+        self.context_sources.as_ref()?.school_alerts.as_ref()
+    }
+
+    pub fn get_jira_config(&self) -> Option<&JiraConfig> {
+        self.context_sources.as_ref()?.jira.as_ref()
+    }
+
+    pub fn get_slack_source_config(&self) -> Option<&SlackSourceConfig> {
+        self.context_sources.as_ref()?.slack.as_ref()
+    }
+
+    /// Check if a context source is enabled
+    pub fn is_context_source_enabled(&self, source_id: &str) -> bool {
+        match source_id {
+            "obsidian" => self.get_obsidian_config().is_some_and(|c| c.enabled),
+            "weather" => self.get_weather_config().is_some_and(|c| c.enabled),
+            "travel" => self.get_travel_config().is_some_and(|c| c.enabled),
+            "school_alerts" => self.get_school_alerts_config().is_some_and(|c| c.enabled),
+            "jira" => self.get_jira_config().is_some_and(|c| c.enabled),
+            "slack" => self.get_slack_source_config().is_some_and(|c| c.enabled),
+            "calendar" => true, // Always enabled
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configured_sources_add_to_builtin_sanitization() {
+        let config: SanitizationConfig = toml::from_str(
+            r#"
+            [sources.calendar]
+            level = "strict"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.sources["calendar"].level, SanitizationLevel::Strict);
+        // Obsidian keeps its built-in profile
+        assert_eq!(
+            config.sources["obsidian"].level,
+            SanitizationLevel::Standard
+        );
+
+        // and can still be overridden
+        let config: SanitizationConfig =
+            toml::from_str("[sources.obsidian]\nlevel = \"off\"").unwrap();
+        assert_eq!(config.sources["obsidian"].level, SanitizationLevel::Off);
+        let config: SanitizationConfig = toml::from_str("").unwrap();
+        assert_eq!(config.sources.len(), 1);
+    }
+
+    #[test]
+    fn test_configured_hosts_add_to_builtin_rate_limits() {
+        let config: RateLimitsConfig = toml::from_str(
+            r#"
+            [hosts."api.example.com"]
+            qps = 0.5
+            daily = 100
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.for_host("api.example.com").1.daily, 100);
+        assert_eq!(config.for_host("api.todoist.com").1.daily, 2_000);
+
+        let config: RateLimitsConfig =
+            toml::from_str("[hosts.\"api.todoist.com\"]\nqps = 3.0\ndaily = 9000").unwrap();
+        assert_eq!(config.for_host("api.todoist.com").1.daily, 9_000);
+        assert_eq!(config.hosts.len(), default_host_rate_limits().len());
+    }
+
+    #[test]
+    fn test_weather_units_ignore_case() {
+        let validate = |units: &str| {
+            let mut config = Config::default();
+            if let Some(weather) = config
+                .context_sources
+                .as_mut()
+                .and_then(|sources| sources.weather.as_mut())
+            {
+                weather.units = units.to_string();
+            }
+            config.validate_context_sources()
+        };
+        assert!(validate("Metric").is_ok());
+        assert!(validate("IMPERIAL").is_ok());
+        assert!(validate("kelvin").is_err());
+    }
+}
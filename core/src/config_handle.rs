@@ -0,0 +1,141 @@
+//! The live configuration shared by the daemon's services.
+//!
+//! Services read a snapshot with `read()` (an `Arc` clone, never a lock held
+//! across I/O) and `subscribe()` to a watch channel that fires after every
+//! accepted change, whether it came from `update()` or from the config file
+//! being edited while the daemon runs.
+
+use crate::config::Config;
+use crate::errors::JasperError;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+#[derive(Clone)]
+pub struct ConfigHandle {
+    sender: Arc<watch::Sender<Arc<Config>>>,
+}
+
+impl ConfigHandle {
+    pub fn new(config: Config) -> Self {
+        let (sender, _) = watch::channel(Arc::new(config));
+        Self {
+            sender: Arc::new(sender),
+        }
+    }
+
+    /// The current configuration
+    pub fn read(&self) -> Arc<Config> {
+        self.sender.borrow().clone()
+    }
+
+    /// Notified after every change; the value is the new configuration
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Config>> {
+        self.sender.subscribe()
+    }
+
+    /// Change settings in memory and publish them. The result has to pass
+    /// validation; call `save()` on it to persist the change.
+    pub fn update(&self, change: impl FnOnce(&mut Config)) -> Result<Arc<Config>> {
+        let mut config = (*self.read()).clone();
+        change(&mut config);
+        config.validate().context(JasperError::Config {
+            message: "settings are invalid".to_string(),
+        })?;
+        let config = Arc::new(config);
+        self.sender.send_replace(Arc::clone(&config));
+        Ok(config)
+    }
+
+    /// Re-read the config file at `path`, publishing it if anything differs.
+    /// An invalid file is an error and leaves the current configuration alone.
+    pub async fn reload_from(&self, path: &Path) -> Result<bool> {
+        let config = Config::load_file(path).await?;
+        if same(&config, &self.read()) {
+            return Ok(false);
+        }
+        self.sender.send_replace(Arc::new(config));
+        Ok(true)
+    }
+
+    /// Check the config file every `every` and reload it when it changes
+    pub fn watch_file(&self, path: PathBuf, every: Duration) -> tokio::task::JoinHandle<()> {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            let mut seen = modified(&path);
+            let mut ticker = tokio::time::interval(every);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let current = modified(&path);
+                if current == seen {
+                    continue;
+                }
+                seen = current;
+                match handle.reload_from(&path).await {
+                    Ok(true) => info!("Reloaded configuration from {:?}", path),
+                    Ok(false) => {}
+                    Err(e) => warn!("Keeping the running configuration: {:#}", e),
+                }
+            }
+        })
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Config has no PartialEq; its serialized form stands in
+fn same(a: &Config, b: &Config) -> bool {
+    toml::to_string(a).ok() == toml::to_string(b).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_changes_reach_subscribers_and_bad_ones_are_refused() {
+        let handle = ConfigHandle::new(Config::default());
+        let mut changes = handle.subscribe();
+
+        handle
+            .update(|c| c.general.check_interval_seconds = 120)
+            .unwrap();
+        assert!(changes.has_changed().unwrap());
+        assert_eq!(
+            changes.borrow_and_update().general.check_interval_seconds,
+            120
+        );
+
+        // Fails validation: nothing is published
+        assert!(handle
+            .update(|c| c.general.check_interval_seconds = 1)
+            .is_err());
+        assert!(!changes.has_changed().unwrap());
+        assert_eq!(handle.read().general.check_interval_seconds, 120);
+
+        // The file on disk: unchanged content is not a change, an edit is
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, toml::to_string(&*handle.read()).unwrap()).unwrap();
+        handle.reload_from(&path).await.unwrap(); // picks up any env overrides
+        changes.mark_unchanged();
+        assert!(!handle.reload_from(&path).await.unwrap());
+        assert!(!changes.has_changed().unwrap());
+
+        let mut edited = (*handle.read()).clone();
+        edited.general.check_jitter_seconds = 30;
+        std::fs::write(&path, toml::to_string(&edited).unwrap()).unwrap();
+        assert!(handle.reload_from(&path).await.unwrap());
+        assert_eq!(changes.borrow_and_update().general.check_jitter_seconds, 30);
+
+        std::fs::write(&path, "general = 3").unwrap();
+        assert!(handle.reload_from(&path).await.is_err());
+        assert_eq!(handle.read().general.check_jitter_seconds, 30);
+    }
+}
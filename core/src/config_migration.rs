@@ -0,0 +1,299 @@
+//! Upgrades old config file layouts to the current one.
+//!
+//! `config_version` in the file records the layout it was written in. A file
+//! without it is either current or the original layout (top-level
+//! `[obsidian]`, `analysis_interval` in minutes, and the `[insights]`,
+//! `[notifications]` and `[privacy]` sections that were never read), or
+//! version 2 when it still has `delivery.desktop_notifications`, which
+//! `[routing]` replaced. The migration runs on the raw TOML before it is
+//! deserialized, so keys serde would silently ignore are carried over
+//! instead of lost.
+
+use toml::{Table, Value};
+
+/// Layout written by this version of Jasper
+pub const CURRENT_VERSION: u32 = 3;
+
+/// Sections of the original layout with no counterpart today
+const RETIRED_SECTIONS: [&str; 3] = ["insights", "notifications", "privacy"];
+
+/// What a migration did to a config file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    /// One line per change, in the order applied
+    pub changes: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Whether the file has to be rewritten
+    pub fn changed(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+/// The layout version `table` was written in
+pub fn version_of(table: &Table) -> u32 {
+    match table.get("config_version").and_then(Value::as_integer) {
+        Some(version) => version.clamp(0, u32::MAX as i64) as u32,
+        None if is_original_layout(table) => 1,
+        None if desktop_notifications_switch(table).is_some() => 2,
+        None => CURRENT_VERSION,
+    }
+}
+
+fn is_original_layout(table: &Table) -> bool {
+    table.contains_key("obsidian")
+        || RETIRED_SECTIONS.iter().any(|s| table.contains_key(*s))
+        || table
+            .get("general")
+            .and_then(Value::as_table)
+            .is_some_and(|general| general.contains_key("analysis_interval"))
+}
+
+/// The `[delivery]` table's `desktop_notifications` setting, if present
+fn desktop_notifications_switch(table: &Table) -> Option<&Value> {
+    table
+        .get("delivery")?
+        .as_table()?
+        .get("desktop_notifications")
+}
+
+/// Bring `table` up to [`CURRENT_VERSION`] in place. Files from a newer
+/// Jasper are left alone.
+pub fn migrate(table: &mut Table) -> MigrationReport {
+    let from_version = version_of(table);
+    let mut changes = Vec::new();
+
+    if from_version < 2 {
+        migrate_v1(table, &mut changes);
+    }
+    if from_version < 3 {
+        migrate_v2(table, &mut changes);
+    }
+    if from_version < CURRENT_VERSION {
+        table.insert(
+            "config_version".to_string(),
+            Value::Integer(CURRENT_VERSION as i64),
+        );
+        changes.push(format!(
+            "set config_version = {} (was {})",
+            CURRENT_VERSION, from_version
+        ));
+    }
+
+    MigrationReport {
+        from_version,
+        changes,
+    }
+}
+
+/// The original layout to version 2
+fn migrate_v1(table: &mut Table, changes: &mut Vec<String>) {
+    if let Some(obsidian) = table.remove("obsidian") {
+        let sources = table
+            .entry("context_sources")
+            .or_insert_with(|| Value::Table(Table::new()));
+        match sources.as_table_mut() {
+            Some(sources) if !sources.contains_key("obsidian") => {
+                sources.insert("obsidian".to_string(), obsidian);
+                changes.push("moved [obsidian] to [context_sources.obsidian]".to_string());
+            }
+            _ => changes
+                .push("removed [obsidian]: [context_sources.obsidian] is already set".to_string()),
+        }
+    }
+
+    if let Some(general) = table.get_mut("general").and_then(Value::as_table_mut) {
+        if let Some(interval) = general.remove("analysis_interval") {
+            let minutes = interval.as_integer().filter(|m| *m > 0);
+            match minutes {
+                Some(minutes) if !general.contains_key("check_interval_seconds") => {
+                    general.insert(
+                        "check_interval_seconds".to_string(),
+                        Value::Integer(minutes * 60),
+                    );
+                    changes.push(format!(
+                        "replaced general.analysis_interval = {} (minutes) with general.check_interval_seconds = {}",
+                        minutes,
+                        minutes * 60
+                    ));
+                }
+                Some(_) => changes.push(
+                    "removed general.analysis_interval: general.check_interval_seconds is already set"
+                        .to_string(),
+                ),
+                None => changes.push(format!(
+                    "removed general.analysis_interval = {} (not a number of minutes)",
+                    interval
+                )),
+            }
+        }
+    }
+
+    for section in RETIRED_SECTIONS {
+        if table.remove(section).is_some() {
+            changes.push(format!("removed [{}] (no longer used)", section));
+        }
+    }
+}
+
+/// Version 2 to 3: `delivery.desktop_notifications = true` becomes a
+/// `[routing]` default that sends every insight to `notification`, leaving
+/// rows that already route it alone
+fn migrate_v2(table: &mut Table, changes: &mut Vec<String>) {
+    let Some(enabled) = table
+        .get_mut("delivery")
+        .and_then(Value::as_table_mut)
+        .and_then(|delivery| delivery.remove("desktop_notifications"))
+    else {
+        return;
+    };
+    if enabled.as_bool() != Some(true) {
+        changes.push(format!(
+            "removed delivery.desktop_notifications = {} ([routing] turns notifications on)",
+            enabled
+        ));
+        return;
+    }
+
+    let default_row = table
+        .entry("routing")
+        .or_insert_with(|| Value::Table(Table::new()))
+        .as_table_mut()
+        .map(|routing| {
+            routing
+                .entry("default")
+                .or_insert_with(|| Value::Table(Table::new()))
+        })
+        .and_then(Value::as_table_mut);
+    match default_row {
+        Some(row) if !row.contains_key("notification") => {
+            row.insert(
+                "notification".to_string(),
+                Value::String("normal".to_string()),
+            );
+            changes.push(
+                "replaced delivery.desktop_notifications = true with routing.default.notification = \"normal\""
+                    .to_string(),
+            );
+        }
+        _ => changes.push(
+            "removed delivery.desktop_notifications: routing.default.notification is already set"
+                .to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_original_layout_is_migrated_and_current_left_alone() {
+        let mut original: Table = toml::from_str(
+            r#"
+            [general]
+            planning_horizon_days = 7
+            analysis_interval = 30
+            timezone = "America/New_York"
+
+            [obsidian]
+            enabled = true
+            vault_path = "~/Notes"
+
+            [notifications]
+            enabled = true
+
+            [privacy]
+            sanitize_pii = true
+            "#,
+        )
+        .unwrap();
+        let report = migrate(&mut original);
+        assert_eq!(report.from_version, 1);
+        assert_eq!(
+            report.changes,
+            [
+                "moved [obsidian] to [context_sources.obsidian]",
+                "replaced general.analysis_interval = 30 (minutes) with general.check_interval_seconds = 1800",
+                "removed [notifications] (no longer used)",
+                "removed [privacy] (no longer used)",
+                "set config_version = 3 (was 1)",
+            ]
+        );
+        assert_eq!(
+            original["context_sources"]["obsidian"]["vault_path"].as_str(),
+            Some("~/Notes")
+        );
+        assert_eq!(
+            original["general"]["check_interval_seconds"].as_integer(),
+            Some(1800)
+        );
+        // Running again finds nothing left to do
+        assert!(!migrate(&mut original).changed());
+
+        let mut current: Table = toml::from_str("[general]\ntimezone = \"UTC\"\n").unwrap();
+        let before = current.clone();
+        assert!(!migrate(&mut current).changed());
+        assert_eq!(current, before);
+
+        let mut newer: Table = toml::from_str("config_version = 9\n[obsidian]\n").unwrap();
+        assert_eq!(migrate(&mut newer).from_version, 9);
+        assert!(newer.contains_key("obsidian"));
+    }
+
+    #[test]
+    fn test_desktop_notifications_move_into_routing() {
+        let mut switched_on: Table = toml::from_str(
+            r#"
+            [delivery]
+            ntfy_url = "https://ntfy.sh/mine"
+            desktop_notifications = true
+
+            [routing]
+            people = { notification = "off" }
+            "#,
+        )
+        .unwrap();
+        let report = migrate(&mut switched_on);
+        assert_eq!(report.from_version, 2);
+        assert_eq!(
+            report.changes,
+            [
+                "replaced delivery.desktop_notifications = true with routing.default.notification = \"normal\"",
+                "set config_version = 3 (was 2)",
+            ]
+        );
+        assert!(!switched_on["delivery"]
+            .as_table()
+            .unwrap()
+            .contains_key("desktop_notifications"));
+        assert_eq!(
+            switched_on["routing"]["default"]["notification"].as_str(),
+            Some("normal")
+        );
+        assert_eq!(
+            switched_on["routing"]["people"]["notification"].as_str(),
+            Some("off")
+        );
+        assert!(!migrate(&mut switched_on).changed());
+
+        // An explicit default row wins; a switched-off flag just goes
+        let mut routed: Table = toml::from_str(
+            "config_version = 2\n[delivery]\ndesktop_notifications = true\n[routing]\ndefault = { notification = \"high\" }\n",
+        )
+        .unwrap();
+        migrate(&mut routed);
+        assert_eq!(
+            routed["routing"]["default"]["notification"].as_str(),
+            Some("high")
+        );
+        let mut off: Table =
+            toml::from_str("config_version = 2\n[delivery]\ndesktop_notifications = false\n")
+                .unwrap();
+        migrate(&mut off);
+        assert!(!off.contains_key("routing"));
+        assert!(off["delivery"].as_table().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+//! Optional environmental readings under the weather source: air quality
+//! (OpenAQ, PM2.5 converted to US AQI) and pollen (Google Pollen API).
+//!
+//! Each provider is toggled on its own and only speaks up when a reading
+//! crosses its threshold, as an extra line in `WeatherContext.alerts`.
+//! Failures are logged and skipped so they never block the forecast.
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::http_utils;
+
+/// OpenAQ parameter id for PM2.5
+const PM25_PARAMETER_ID: u32 = 2;
+/// Look for a monitoring station within this many metres
+const SEARCH_RADIUS_M: u32 = 25_000;
+
+// ── OpenAQ v3 response types ───────────────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+struct OpenAqResponse<T> {
+    results: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAqLocation {
+    id: u64,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    sensors: Vec<OpenAqSensor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAqSensor {
+    id: u64,
+    parameter: OpenAqParameter,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAqParameter {
+    id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenAqLatest {
+    value: f64,
+    sensors_id: u64,
+}
+
+// ── Google Pollen API response types ───────────────────────────────────
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PollenResponse {
+    #[serde(default)]
+    daily_info: Vec<PollenDay>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PollenDay {
+    #[serde(default)]
+    pollen_type_info: Vec<PollenType>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PollenType {
+    display_name: Option<String>,
+    index_info: Option<PollenIndex>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PollenIndex {
+    value: u32,
+    category: Option<String>,
+}
+
+// ── Providers ──────────────────────────────────────────────────────────
+
+/// PM2.5 from the nearest OpenAQ station
+pub struct AirQualityProvider {
+    pub api_key: String,
+    /// Alert at or above this US AQI
+    pub alert_aqi: u32,
+}
+
+impl AirQualityProvider {
+    pub async fn alerts(
+        &self,
+        client: &Client,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<String>> {
+        let locations: OpenAqResponse<OpenAqLocation> = self
+            .get(
+                client,
+                &format!(
+                    "https://api.openaq.org/v3/locations?coordinates={},{}&radius={}&parameters_id={}&limit=1",
+                    latitude, longitude, SEARCH_RADIUS_M, PM25_PARAMETER_ID
+                ),
+            )
+            .await?;
+        let Some(location) = locations.results.into_iter().next() else {
+            debug!("No OpenAQ station near {}, {}", latitude, longitude);
+            return Ok(Vec::new());
+        };
+        let Some(sensor) = location
+            .sensors
+            .iter()
+            .find(|s| s.parameter.id == PM25_PARAMETER_ID)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let latest: OpenAqResponse<OpenAqLatest> = self
+            .get(
+                client,
+                &format!("https://api.openaq.org/v3/locations/{}/latest", location.id),
+            )
+            .await?;
+        let Some(reading) = latest.results.iter().find(|r| r.sensors_id == sensor.id) else {
+            return Ok(Vec::new());
+        };
+
+        let aqi = pm25_to_aqi(reading.value);
+        debug!(
+            "Air quality at {}: PM2.5 {:.1} µg/m³ = AQI {}",
+            location.name.as_deref().unwrap_or("nearest station"),
+            reading.value,
+            aqi
+        );
+        Ok(air_quality_alert(aqi, self.alert_aqi).into_iter().collect())
+    }
+
+    async fn get<T: serde::de::DeserializeOwned>(&self, client: &Client, url: &str) -> Result<T> {
+        let response = http_utils::send(client.get(url).header("X-API-Key", &self.api_key)).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!("OpenAQ request failed ({}): {}", status, body));
+        }
+        Ok(response.json().await?)
+    }
+}
+
+/// Today's pollen index per plant type
+pub struct PollenProvider {
+    /// Alert at or above this universal pollen index (0-5)
+    pub alert_index: u32,
+}
+
+impl PollenProvider {
+    pub async fn alerts(
+        &self,
+        client: &Client,
+        google_api_key: &str,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Vec<String>> {
+        let url = format!(
+            "https://pollen.googleapis.com/v1/forecast:lookup?\
+             key={}&location.latitude={}&location.longitude={}&days=1",
+            google_api_key, latitude, longitude
+        );
+        let response = http_utils::send(client.get(&url)).await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(anyhow!(
+                "Google Pollen forecast failed ({}): {}",
+                status,
+                body
+            ));
+        }
+        let pollen: PollenResponse = response.json().await?;
+        Ok(pollen_alerts(&pollen, self.alert_index))
+    }
+}
+
+// ── Thresholds ─────────────────────────────────────────────────────────
+
+/// US EPA AQI for a PM2.5 concentration in µg/m³ (2024 breakpoints)
+pub fn pm25_to_aqi(concentration: f64) -> u32 {
+    const BREAKPOINTS: &[(f64, f64, f64, f64)] = &[
+        (0.0, 9.0, 0.0, 50.0),
+        (9.1, 35.4, 51.0, 100.0),
+        (35.5, 55.4, 101.0, 150.0),
+        (55.5, 125.4, 151.0, 200.0),
+        (125.5, 225.4, 201.0, 300.0),
+        (225.5, 325.4, 301.0, 500.0),
+    ];
+    // EPA truncates to one decimal before looking up the breakpoint
+    let c = (concentration.max(0.0) * 10.0).floor() / 10.0;
+    match BREAKPOINTS.iter().find(|(_, c_high, _, _)| c <= *c_high) {
+        Some(&(c_low, c_high, i_low, i_high)) => {
+            ((i_high - i_low) / (c_high - c_low) * (c - c_low) + i_low).round() as u32
+        }
+        None => 500,
+    }
+}
+
+fn aqi_category(aqi: u32) -> &'static str {
+    match aqi {
+        0..=50 => "good",
+        51..=100 => "moderate",
+        101..=150 => "unhealthy for sensitive groups",
+        151..=200 => "unhealthy",
+        201..=300 => "very unhealthy",
+        _ => "hazardous",
+    }
+}
+
+fn air_quality_alert(aqi: u32, threshold: u32) -> Option<String> {
+    (aqi >= threshold).then(|| format!("Air quality AQI {} ({})", aqi, aqi_category(aqi)))
+}
+
+fn pollen_alerts(pollen: &PollenResponse, threshold: u32) -> Vec<String> {
+    pollen
+        .daily_info
+        .first()
+        .map(|day| {
+            day.pollen_type_info
+                .iter()
+                .filter_map(|t| {
+                    let index = t.index_info.as_ref()?;
+                    (index.value >= threshold).then(|| {
+                        format!(
+                            "{} pollen {} ({}/5)",
+                            t.display_name.as_deref().unwrap_or("Unknown"),
+                            index.category.as_deref().unwrap_or("high").to_lowercase(),
+                            index.value
+                        )
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pm25_breakpoints() {
+        assert_eq!(pm25_to_aqi(0.0), 0);
+        assert_eq!(pm25_to_aqi(9.0), 50);
+        assert_eq!(pm25_to_aqi(35.4), 100);
+        assert_eq!(pm25_to_aqi(40.0), 112);
+        assert_eq!(pm25_to_aqi(500.0), 500);
+
+        assert_eq!(air_quality_alert(80, 100), None);
+        assert_eq!(
+            air_quality_alert(112, 100).unwrap(),
+            "Air quality AQI 112 (unhealthy for sensitive groups)"
+        );
+    }
+
+    #[test]
+    fn test_pollen_threshold() {
+        let response: PollenResponse = serde_json::from_str(
+            r#"{"dailyInfo": [{"pollenTypeInfo": [
+                {"code": "GRASS", "displayName": "Grass", "indexInfo": {"value": 4, "category": "High"}},
+                {"code": "TREE", "displayName": "Tree", "indexInfo": {"value": 2, "category": "Low"}},
+                {"code": "WEED", "displayName": "Weed"}
+            ]}]}"#,
+        )
+        .unwrap();
+        assert_eq!(pollen_alerts(&response, 4), ["Grass pollen high (4/5)"]);
+        assert_eq!(pollen_alerts(&response, 5), Vec::<String>::new());
+    }
+}
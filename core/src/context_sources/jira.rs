@@ -0,0 +1,352 @@
+//! Jira issues as tasks, so work deadlines meet the calendar.
+//!
+//! The issues `jql` finds (by default, the open sprint's unfinished issues
+//! assigned to you) become tasks titled with their key, due on their due
+//! date at the end of the day. An issue with an unfinished "is blocked by"
+//! link is Blocked, and the blocking issues are named in its description
+//! along with the ones it blocks. Jira Cloud is reached with the account
+//! email and an API token (`/rest/api/3/search/jql`); without an email the
+//! token is sent as a Data Center personal access token
+//! (`/rest/api/2/search`). One page of results is read, which covers a sprint.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use super::{
+    ContextContent, ContextData, ContextDataType, ContextSource, Task, TaskContext, TaskStatus,
+};
+use crate::config::JiraConfig;
+use crate::http_utils;
+
+/// Same as the other task sources
+const PRIORITY: i32 = 120;
+
+/// Issues read per fetch
+const MAX_RESULTS: u32 = 100;
+
+const FIELDS: &str = "summary,duedate,priority,status,labels,issuelinks";
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    issues: Vec<Issue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    key: String,
+    fields: IssueFields,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueFields {
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    duedate: Option<String>,
+    #[serde(default)]
+    priority: Option<Named>,
+    #[serde(default)]
+    status: Option<Status>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    issuelinks: Vec<IssueLink>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Named {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Status {
+    name: String,
+    #[serde(rename = "statusCategory")]
+    category: Option<StatusCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusCategory {
+    /// "new", "indeterminate" or "done"
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueLink {
+    #[serde(rename = "type")]
+    link_type: Named,
+    #[serde(rename = "inwardIssue")]
+    inward: Option<LinkedIssue>,
+    #[serde(rename = "outwardIssue")]
+    outward: Option<LinkedIssue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkedIssue {
+    key: String,
+    #[serde(default)]
+    fields: Option<LinkedFields>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LinkedFields {
+    status: Option<Status>,
+}
+
+impl Status {
+    fn is_done(&self) -> bool {
+        self.category.as_ref().is_some_and(|c| c.key == "done")
+    }
+}
+
+impl LinkedIssue {
+    fn is_done(&self) -> bool {
+        self.fields
+            .as_ref()
+            .and_then(|f| f.status.as_ref())
+            .is_some_and(Status::is_done)
+    }
+}
+
+/// Jira's priority names, on the 1-10 scale
+fn priority(name: Option<&str>) -> i32 {
+    match name.map(str::to_ascii_lowercase).as_deref() {
+        Some("highest" | "blocker" | "critical") => 10,
+        Some("high" | "major") => 8,
+        Some("low" | "minor") => 3,
+        Some("lowest" | "trivial") => 1,
+        _ => 5,
+    }
+}
+
+/// Tasks from a search response body; finished issues are left out
+pub fn parse(body: &str, tz: Tz) -> Result<Vec<Task>> {
+    let response: SearchResponse = serde_json::from_str(body)?;
+    Ok(response
+        .issues
+        .into_iter()
+        .filter(|issue| !issue.fields.status.as_ref().is_some_and(Status::is_done))
+        .map(|issue| {
+            let fields = issue.fields;
+            // "Blocks" links read "is blocked by" from the inward side
+            let (mut blocked_by, mut blocking) = (Vec::new(), Vec::new());
+            for link in &fields.issuelinks {
+                if !link.link_type.name.eq_ignore_ascii_case("blocks") {
+                    continue;
+                }
+                if let Some(ref inward) = link.inward {
+                    if !inward.is_done() {
+                        blocked_by.push(inward.key.clone());
+                    }
+                }
+                if let Some(ref outward) = link.outward {
+                    if !outward.is_done() {
+                        blocking.push(outward.key.clone());
+                    }
+                }
+            }
+            let description = [("Blocked by", &blocked_by), ("Blocking", &blocking)]
+                .iter()
+                .filter(|(_, keys)| !keys.is_empty())
+                .map(|(label, keys)| format!("{} {}", label, keys.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ");
+
+            let status = match fields.status {
+                _ if !blocked_by.is_empty() => TaskStatus::Blocked,
+                Some(ref s) if s.name.to_lowercase().contains("block") => TaskStatus::Blocked,
+                Some(ref s)
+                    if s.category
+                        .as_ref()
+                        .is_some_and(|c| c.key == "indeterminate") =>
+                {
+                    TaskStatus::InProgress
+                }
+                _ => TaskStatus::Pending,
+            };
+            let due_date = fields
+                .duedate
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .and_then(|d| {
+                    tz.from_local_datetime(&d.and_hms_opt(23, 59, 59)?)
+                        .earliest()
+                })
+                .map(|d| d.to_utc());
+
+            Task {
+                id: format!("jira:{}", issue.key),
+                title: format!("{} {}", issue.key, fields.summary.trim()),
+                description: (!description.is_empty()).then_some(description),
+                due_date,
+                priority: priority(fields.priority.as_ref().map(|p| p.name.as_str())),
+                status,
+                tags: fields.labels,
+                source: "jira".to_string(),
+            }
+        })
+        .collect())
+}
+
+struct CachedIssues {
+    tasks: Vec<Task>,
+    fetched_at: DateTime<Utc>,
+}
+
+pub struct JiraContextSource {
+    config: JiraConfig,
+    timezone: Tz,
+    client: Client,
+    cache: RwLock<Option<CachedIssues>>,
+}
+
+impl JiraContextSource {
+    pub fn new(config: JiraConfig, timezone: Tz) -> Self {
+        Self {
+            config,
+            timezone,
+            client: Client::new(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    async fn fetch_issues(&self) -> Result<Vec<Task>> {
+        if let Some(ref cached) = *self.cache.read().await {
+            let age = Utc::now() - cached.fetched_at;
+            if age.num_minutes() < self.config.cache_duration_minutes as i64 {
+                return Ok(cached.tasks.clone());
+            }
+        }
+
+        let base = self.config.base_url.trim_end_matches('/');
+        let cloud = !self.config.email.is_empty();
+        let url = if cloud {
+            format!("{}/rest/api/3/search/jql", base)
+        } else {
+            format!("{}/rest/api/2/search", base)
+        };
+        let max_results = MAX_RESULTS.to_string();
+        let request = self.client.get(url).query(&[
+            ("jql", self.config.jql.as_str()),
+            ("fields", FIELDS),
+            ("maxResults", max_results.as_str()),
+        ]);
+        let request = if cloud {
+            request.basic_auth(&self.config.email, Some(&self.config.api_token))
+        } else {
+            request.bearer_auth(&self.config.api_token)
+        };
+
+        debug!("Fetching Jira issues from {}", base);
+        let response = http_utils::send(request).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Jira search failed ({})", response.status()));
+        }
+        let tasks = parse(&response.text().await?, self.timezone)?;
+        *self.cache.write().await = Some(CachedIssues {
+            tasks: tasks.clone(),
+            fetched_at: Utc::now(),
+        });
+        Ok(tasks)
+    }
+}
+
+#[async_trait]
+impl ContextSource for JiraContextSource {
+    fn source_id(&self) -> &str {
+        "jira"
+    }
+
+    fn display_name(&self) -> &str {
+        "Jira"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.api_token.is_empty()
+    }
+
+    async fn fetch_context(
+        &self,
+        _start: DateTime<Utc>,
+        _end: DateTime<Utc>,
+    ) -> Result<ContextData> {
+        let tasks = self.fetch_issues().await.map_err(|e| {
+            warn!("Failed to fetch Jira issues: {}", e);
+            e
+        })?;
+        let mut metadata = HashMap::new();
+        metadata.insert("issues".to_string(), tasks.len().to_string());
+        Ok(ContextData {
+            source_id: self.source_id().to_string(),
+            timestamp: Utc::now(),
+            data_type: ContextDataType::Tasks,
+            priority: PRIORITY,
+            content: ContextContent::Tasks(TaskContext::new(tasks, Utc::now())),
+            metadata,
+        })
+    }
+
+    fn priority(&self) -> i32 {
+        PRIORITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_sprint_issues_and_blockers() {
+        let tz: Tz = "Europe/London".parse().unwrap();
+        let body = r#"{"issues": [
+            {"key": "WEB-12", "fields": {
+                "summary": "Checkout redesign ", "duedate": "2026-05-15",
+                "priority": {"name": "High"}, "labels": ["frontend"],
+                "status": {"name": "In Progress", "statusCategory": {"key": "indeterminate"}},
+                "issuelinks": [
+                    {"type": {"name": "Blocks"}, "inwardIssue": {"key": "API-3",
+                        "fields": {"status": {"name": "To Do", "statusCategory": {"key": "new"}}}}},
+                    {"type": {"name": "Blocks"}, "inwardIssue": {"key": "API-1",
+                        "fields": {"status": {"name": "Done", "statusCategory": {"key": "done"}}}}},
+                    {"type": {"name": "Blocks"}, "outwardIssue": {"key": "WEB-20"}},
+                    {"type": {"name": "Relates"}, "outwardIssue": {"key": "OPS-9"}}
+                ]}},
+            {"key": "WEB-14", "fields": {
+                "summary": "Copy review",
+                "status": {"name": "In Progress", "statusCategory": {"key": "indeterminate"}}}},
+            {"key": "WEB-9", "fields": {
+                "summary": "Old bug", "priority": {"name": "Lowest"},
+                "status": {"name": "Done", "statusCategory": {"key": "done"}}}}
+        ]}"#;
+        let tasks = parse(body, tz).unwrap();
+        assert_eq!(tasks.len(), 2);
+
+        let checkout = &tasks[0];
+        assert_eq!(checkout.id, "jira:WEB-12");
+        assert_eq!(checkout.title, "WEB-12 Checkout redesign");
+        assert_eq!(checkout.priority, 8);
+        assert!(matches!(checkout.status, TaskStatus::Blocked));
+        assert_eq!(
+            checkout.description.as_deref(),
+            Some("Blocked by API-3; Blocking WEB-20")
+        );
+        assert_eq!(checkout.tags, ["frontend"]);
+        // British Summer Time
+        assert_eq!(
+            checkout.due_date,
+            Some(Utc.with_ymd_and_hms(2026, 5, 15, 22, 59, 59).unwrap())
+        );
+
+        assert!(matches!(tasks[1].status, TaskStatus::InProgress));
+        assert_eq!(tasks[1].priority, 5);
+        assert_eq!(tasks[1].due_date, None);
+    }
+}
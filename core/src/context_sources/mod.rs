@@ -0,0 +1,813 @@
+#![allow(dead_code)]
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::config::{Config, TasksConfig};
+use environment::{AirQualityProvider, PollenProvider};
+use obsidian::ObsidianVaultSource;
+use school_alerts::{SchoolAlertSource, SchoolFeed};
+use weather::WeatherContextSource;
+
+pub mod environment;
+pub mod jira;
+pub mod note_summary;
+pub mod obsidian;
+pub mod org_mode;
+pub mod school_alerts;
+pub mod slack;
+pub mod task_aggregation;
+pub mod tasks;
+pub mod todo_txt;
+pub mod weather;
+
+/// Core trait for all context sources
+#[async_trait]
+pub trait ContextSource: Send + Sync {
+    /// Get the unique identifier for this context source
+    fn source_id(&self) -> &str;
+
+    /// Get the display name for this context source
+    fn display_name(&self) -> &str;
+
+    /// Check if this context source is enabled and configured
+    fn is_enabled(&self) -> bool;
+
+    /// Fetch context data for the given time range
+    async fn fetch_context(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<ContextData>;
+
+    /// Get the priority of this context source (higher = more important)
+    fn priority(&self) -> i32 {
+        100 // Default priority
+    }
+
+    /// IDs of sources this one builds on or overlaps with. They are fetched
+    /// first, and their results are handed to `reconcile`. A dependency that
+    /// isn't registered or enabled is skipped.
+    fn depends_on(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Adjust this source's freshly fetched `own` data against what its
+    /// dependencies returned: drop what they already cover, or (for a
+    /// derived source) fill it in from them entirely
+    fn reconcile(&self, _own: &mut ContextData, _upstream: &[&ContextData]) {}
+
+    /// Get configuration requirements for this source
+    fn required_config(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Validate configuration for this source
+    fn validate_config(&self, config: &HashMap<String, String>) -> Result<()> {
+        // Check required config keys
+        for key in self.required_config() {
+            if !config.contains_key(&key) {
+                return Err(anyhow::anyhow!("Missing required config key: {}", key));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Context data returned by context sources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextData {
+    pub source_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub data_type: ContextDataType,
+    pub priority: i32,
+    pub content: ContextContent,
+    pub metadata: HashMap<String, String>,
+}
+
+/// Types of context data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ContextDataType {
+    Calendar,
+    Tasks,
+    Notes,
+    Weather,
+    SchoolAlerts,
+    Messages,
+}
+
+/// Content payload for context data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ContextContent {
+    Calendar(CalendarContext),
+    Tasks(TaskContext),
+    Notes(NotesContext),
+    Weather(WeatherContext),
+    SchoolAlerts(SchoolAlertContext),
+    Messages(MessageContext),
+}
+
+/// Calendar-specific context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarContext {
+    pub events: Vec<crate::database::Event>,
+    pub conflicts: Vec<String>,
+    pub upcoming_deadlines: Vec<String>,
+}
+
+/// Task-specific context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskContext {
+    pub tasks: Vec<Task>,
+    pub overdue_count: usize,
+    pub upcoming_count: usize,
+}
+
+/// A task title reduced for comparison across sources: lowercase, single
+/// spaces, no surrounding punctuation
+pub fn normalize_task_title(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| c.is_ascii_punctuation())
+        .to_lowercase()
+}
+
+impl TaskContext {
+    /// Wrap `tasks`, counting the overdue ones and those due within a week
+    pub fn new(tasks: Vec<Task>, now: DateTime<Utc>) -> Self {
+        let open = |t: &&Task| !matches!(t.status, TaskStatus::Completed);
+        let overdue_count = tasks
+            .iter()
+            .filter(open)
+            .filter(|t| t.due_date.is_some_and(|due| due < now))
+            .count();
+        let upcoming_count = tasks
+            .iter()
+            .filter(|t| {
+                t.due_date
+                    .is_some_and(|due| due > now && due <= now + chrono::Duration::days(7))
+            })
+            .count();
+        Self {
+            tasks,
+            overdue_count,
+            upcoming_count,
+        }
+    }
+}
+
+/// Notes-specific context (for Obsidian integration)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotesContext {
+    pub daily_notes: Vec<DailyNote>,
+    pub active_projects: Vec<Project>,
+    pub pending_tasks: Vec<Task>,
+    /// Notes from the people folder, for matching event attendees
+    #[serde(default)]
+    pub people: Vec<PersonNote>,
+}
+
+/// Weather-specific context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherContext {
+    pub current_conditions: String,
+    pub forecast: Vec<WeatherForecast>,
+    pub alerts: Vec<String>,
+    /// Unit system the temperatures above are expressed in
+    #[serde(default)]
+    pub units: UnitSystem,
+}
+
+/// School district alerts context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchoolAlertContext {
+    pub alerts: Vec<SchoolAlert>,
+}
+
+/// A closure, delay or early dismissal announced by a school district
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SchoolAlert {
+    /// Stable across fetches: the feed item's guid or link, else a hash of its text
+    pub id: String,
+    /// District or school the alert came from
+    pub district: String,
+    pub kind: SchoolAlertKind,
+    pub title: String,
+    pub published: Option<DateTime<Utc>>,
+}
+
+/// What a school alert means for the day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchoolAlertKind {
+    Closure,
+    Delay,
+    EarlyDismissal,
+    Other,
+}
+
+impl SchoolAlertKind {
+    /// Short label for prompts, e.g. "Closure"
+    pub fn label(self) -> &'static str {
+        match self {
+            SchoolAlertKind::Closure => "Closure",
+            SchoolAlertKind::Delay => "Delayed start",
+            SchoolAlertKind::EarlyDismissal => "Early dismissal",
+            SchoolAlertKind::Other => "Alert",
+        }
+    }
+}
+
+/// Chat messages waiting on a reply
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageContext {
+    pub waiting: Vec<UnansweredMessage>,
+}
+
+/// A DM or mention the user hasn't answered
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UnansweredMessage {
+    /// Conversation and message, stable across fetches
+    pub id: String,
+    pub kind: MessageKind,
+    /// Sender's display name
+    pub from: String,
+    /// Channel name; None for a DM
+    pub channel: Option<String>,
+    pub text: String,
+    pub sent: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    DirectMessage,
+    Mention,
+}
+
+/// Measurement system for weather values ("imperial" or "metric" in config)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[default]
+    Imperial,
+    Metric,
+}
+
+impl UnitSystem {
+    /// Parse the config string, falling back to imperial for unknown values
+    pub fn from_config(units: &str) -> Self {
+        match units.to_ascii_lowercase().as_str() {
+            "metric" => UnitSystem::Metric,
+            _ => UnitSystem::Imperial,
+        }
+    }
+
+    /// Temperature suffix for display ("°F" / "°C")
+    pub fn temperature_symbol(self) -> &'static str {
+        match self {
+            UnitSystem::Imperial => "°F",
+            UnitSystem::Metric => "°C",
+        }
+    }
+
+    /// Convert a temperature expressed in `self` into `target` units
+    pub fn convert_temperature(self, value: f32, target: UnitSystem) -> f32 {
+        match (self, target) {
+            (UnitSystem::Imperial, UnitSystem::Metric) => (value - 32.0) * 5.0 / 9.0,
+            (UnitSystem::Metric, UnitSystem::Imperial) => value * 9.0 / 5.0 + 32.0,
+            _ => value,
+        }
+    }
+
+    /// Smallest temperature swing (in this unit) worth re-running the AI for.
+    /// 5°F and 3°C are roughly equivalent.
+    pub fn significant_temperature_delta(self) -> i32 {
+        match self {
+            UnitSystem::Imperial => 5,
+            UnitSystem::Metric => 3,
+        }
+    }
+}
+
+/// Task representation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub priority: i32,
+    pub status: TaskStatus,
+    pub tags: Vec<String>,
+    pub source: String,
+}
+
+/// Task status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TaskStatus {
+    Pending,
+    InProgress,
+    Completed,
+    Cancelled,
+    Blocked,
+}
+
+/// Daily note representation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyNote {
+    pub date: DateTime<Utc>,
+    pub title: String,
+    pub content: String,
+    pub tasks: Vec<Task>,
+    pub mood: Option<String>,
+    pub energy_level: Option<i32>,
+    pub focus_areas: Vec<String>,
+}
+
+/// Project representation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub status: ProjectStatus,
+    /// When work began, for judging progress against the deadline
+    #[serde(default)]
+    pub start_date: Option<DateTime<Utc>>,
+    pub due_date: Option<DateTime<Utc>>,
+    pub client: Option<String>,
+    pub priority: i32,
+    pub progress: f32, // 0.0 to 1.0
+    pub tasks: Vec<Task>,
+}
+
+/// A person note from the Obsidian people folder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersonNote {
+    /// Note file name without the extension
+    pub id: String,
+    /// Frontmatter `name`, else the file name
+    pub name: String,
+    /// Frontmatter `email`/`emails`, lowercased
+    #[serde(default)]
+    pub emails: Vec<String>,
+    /// Frontmatter `aliases` (nicknames, calendar display names)
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// Frontmatter `last_contact`
+    #[serde(default)]
+    pub last_contact: Option<NaiveDate>,
+    /// Last contact is older than the relationship alert threshold
+    #[serde(default)]
+    pub overdue: bool,
+}
+
+/// Project status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ProjectStatus {
+    Active,
+    Pending,
+    Completed,
+    OnHold,
+    Cancelled,
+}
+
+/// Weather forecast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherForecast {
+    pub date: DateTime<Utc>,
+    pub temperature_high: f32,
+    pub temperature_low: f32,
+    pub conditions: String,
+    pub precipitation_chance: f32,
+    pub description: String,
+}
+
+/// How the last fetch from a context source went
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceFetch {
+    pub source_id: String,
+    pub display_name: String,
+    /// None until the source has been fetched
+    pub fetched_at: Option<DateTime<Utc>>,
+    /// The error, if that fetch failed
+    pub error: Option<String>,
+}
+
+/// Context source manager
+pub struct ContextSourceManager {
+    sources: Vec<Box<dyn ContextSource>>,
+    /// Outcome of the last fetch, by source ID
+    last_fetch: parking_lot::Mutex<HashMap<String, SourceFetch>>,
+    /// How tasks found in more than one source are merged
+    tasks: TasksConfig,
+}
+
+impl ContextSourceManager {
+    /// Create a new context source manager
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            last_fetch: parking_lot::Mutex::new(HashMap::new()),
+            tasks: TasksConfig::default(),
+        }
+    }
+
+    /// Context sources (weather, Obsidian, school alerts, Jira, Slack, the
+    /// local task file, todo.txt, Org-mode) as configured
+    pub fn from_config(config: &Config) -> Self {
+        let mut context_manager = Self::new();
+        context_manager.tasks = config.tasks.clone();
+
+        // Register weather context source if configured
+        if let Some(weather_config) = config.get_weather_config() {
+            let has_source =
+                !weather_config.google_api_key.is_empty() || weather_config.fixture_path.is_some();
+            if weather_config.enabled && has_source {
+                let mut weather_source = WeatherContextSource::new(
+                    weather_config.google_api_key.clone(),
+                    weather_config.latitude,
+                    weather_config.longitude,
+                    weather_config.units.clone(),
+                    weather_config.cache_duration_minutes,
+                );
+                if let Some(ref fixture) = weather_config.fixture_path {
+                    weather_source = weather_source.with_fixture(PathBuf::from(fixture));
+                }
+                let air_quality = &weather_config.air_quality;
+                if air_quality.enabled {
+                    if air_quality.openaq_api_key.is_empty() {
+                        warn!("Air quality enabled but no OpenAQ API key configured");
+                    } else {
+                        weather_source = weather_source.with_air_quality(AirQualityProvider {
+                            api_key: air_quality.openaq_api_key.clone(),
+                            alert_aqi: air_quality.alert_aqi,
+                        });
+                    }
+                }
+                if weather_config.pollen.enabled {
+                    weather_source = weather_source.with_pollen(PollenProvider {
+                        alert_index: weather_config.pollen.alert_index,
+                    });
+                }
+                context_manager.add_source(Box::new(weather_source));
+                info!(
+                    "Weather context source registered ({}, {})",
+                    weather_config.latitude, weather_config.longitude
+                );
+            }
+        }
+
+        if let Some(obsidian_config) = config.get_obsidian_config() {
+            if obsidian_config.enabled {
+                let source_config = obsidian::ObsidianConfig {
+                    vault_path: obsidian_config.vault_path.clone(),
+                    ..Default::default()
+                };
+                match ObsidianVaultSource::new(source_config) {
+                    Ok(source) => {
+                        context_manager.add_source(Box::new(source));
+                        info!(
+                            "Obsidian context source registered ({})",
+                            obsidian_config.vault_path
+                        );
+                    }
+                    Err(e) => warn!("Obsidian context source not registered: {}", e),
+                }
+            }
+        }
+
+        if let Some(school_config) = config.get_school_alerts_config() {
+            if school_config.enabled && !school_config.feeds.is_empty() {
+                let feeds = school_config
+                    .feeds
+                    .iter()
+                    .map(|f| SchoolFeed {
+                        name: f.name.clone(),
+                        url: f.url.clone(),
+                    })
+                    .collect();
+                context_manager.add_source(Box::new(SchoolAlertSource::new(
+                    feeds,
+                    school_config.keywords.clone(),
+                    school_config.max_age_hours,
+                    school_config.cache_duration_minutes,
+                )));
+                info!(
+                    "School alerts context source registered ({} feeds)",
+                    school_config.feeds.len()
+                );
+            }
+        }
+
+        if let Some(jira_config) = config.get_jira_config() {
+            if jira_config.enabled && jira_config.api_token.is_empty() {
+                warn!("Jira enabled but no API token configured");
+            } else if jira_config.enabled {
+                context_manager.add_source(Box::new(jira::JiraContextSource::new(
+                    jira_config.clone(),
+                    config.get_timezone(),
+                )));
+                info!("Jira context source registered ({})", jira_config.base_url);
+            }
+        }
+
+        if let Some(slack_config) = config.get_slack_source_config() {
+            if slack_config.enabled && slack_config.token.is_empty() {
+                warn!("Slack context source enabled but no token configured");
+            } else if slack_config.enabled {
+                context_manager.add_source(Box::new(slack::SlackContextSource::new(
+                    slack_config.clone(),
+                )));
+                info!(
+                    "Slack context source registered (replies waiting over {}h)",
+                    slack_config.unanswered_hours
+                );
+            }
+        }
+
+        if let Ok(path) = config.local_tasks_path() {
+            if config.tasks.local_file.is_some() || path.exists() {
+                context_manager.add_source(Box::new(tasks::TasksContextSource::new(
+                    tasks::TaskSourceType::LocalFile,
+                    tasks::TasksConfig {
+                        api_key: None,
+                        file_path: Some(path.to_string_lossy().to_string()),
+                        sync_completed: false,
+                        max_tasks: 100,
+                        timezone: config.get_timezone(),
+                    },
+                )));
+                info!("Local task file registered ({})", path.display());
+            }
+        }
+
+        if let Some(path) = config.todo_txt_path() {
+            context_manager.add_source(Box::new(tasks::TasksContextSource::new(
+                tasks::TaskSourceType::TodoTxt,
+                tasks::TasksConfig {
+                    api_key: None,
+                    file_path: Some(path.to_string_lossy().to_string()),
+                    sync_completed: false,
+                    max_tasks: 100,
+                    timezone: config.get_timezone(),
+                },
+            )));
+            info!("todo.txt task source registered ({})", path.display());
+        }
+
+        let org_files = config.org_file_paths();
+        if !org_files.is_empty() {
+            info!(
+                "Org-mode task source registered ({} files)",
+                org_files.len()
+            );
+            context_manager.add_source(Box::new(org_mode::OrgModeSource::new(
+                org_files,
+                config.get_timezone(),
+            )));
+        }
+
+        context_manager
+    }
+
+    /// Add a context source
+    pub fn add_source(&mut self, source: Box<dyn ContextSource>) {
+        self.sources.push(source);
+    }
+
+    /// Get all enabled context sources
+    pub fn get_enabled_sources(&self) -> Vec<&dyn ContextSource> {
+        self.sources
+            .iter()
+            .filter(|s| s.is_enabled())
+            .map(|s| s.as_ref())
+            .collect()
+    }
+
+    /// Enabled sources with every source after the ones it depends on,
+    /// otherwise in registration order. Sources caught in a dependency cycle
+    /// go last, in registration order.
+    pub fn fetch_order(&self) -> Vec<&dyn ContextSource> {
+        let mut pending = self.get_enabled_sources();
+        let mut ordered: Vec<&dyn ContextSource> = Vec::with_capacity(pending.len());
+        loop {
+            // Ready once none of its dependencies is still waiting
+            let ready = pending.iter().position(|source| {
+                source
+                    .depends_on()
+                    .iter()
+                    .all(|dep| !pending.iter().any(|s| s.source_id() == dep))
+            });
+            match ready {
+                Some(index) => ordered.push(pending.remove(index)),
+                None => break,
+            }
+        }
+        if !pending.is_empty() {
+            let ids: Vec<&str> = pending.iter().map(|s| s.source_id()).collect();
+            tracing::warn!("Context sources depend on each other in a cycle: {:?}", ids);
+            ordered.extend(pending);
+        }
+        ordered
+    }
+
+    /// Fetch context from all enabled sources
+    pub async fn fetch_all_context(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<ContextData>> {
+        let mut all_context: Vec<ContextData> = Vec::new();
+
+        for source in self.fetch_order() {
+            let result = source.fetch_context(start, end).await;
+            self.last_fetch.lock().insert(
+                source.source_id().to_string(),
+                SourceFetch {
+                    source_id: source.source_id().to_string(),
+                    display_name: source.display_name().to_string(),
+                    fetched_at: Some(Utc::now()),
+                    error: result.as_ref().err().map(|e| format!("{:#}", e)),
+                },
+            );
+            match result {
+                Ok(mut context) => {
+                    let depends_on = source.depends_on();
+                    let upstream: Vec<&ContextData> = all_context
+                        .iter()
+                        .filter(|c| depends_on.contains(&c.source_id))
+                        .collect();
+                    if !upstream.is_empty() {
+                        source.reconcile(&mut context, &upstream);
+                    }
+                    all_context.push(context);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to fetch context from {}: {}", source.source_id(), e);
+                }
+            }
+        }
+
+        let merged = task_aggregation::dedupe(&mut all_context, &self.tasks, Utc::now());
+        if merged > 0 {
+            tracing::debug!("Merged {} tasks found in more than one source", merged);
+        }
+
+        // Sort by priority (higher priority first)
+        all_context.sort_by_key(|c| std::cmp::Reverse(c.priority));
+
+        Ok(all_context)
+    }
+
+    /// The last fetch result of every enabled source
+    pub fn last_fetches(&self) -> Vec<SourceFetch> {
+        let last_fetch = self.last_fetch.lock();
+        self.get_enabled_sources()
+            .into_iter()
+            .map(|source| {
+                last_fetch
+                    .get(source.source_id())
+                    .cloned()
+                    .unwrap_or_else(|| SourceFetch {
+                        source_id: source.source_id().to_string(),
+                        display_name: source.display_name().to_string(),
+                        fetched_at: None,
+                        error: None,
+                    })
+            })
+            .collect()
+    }
+
+    /// Carry the fetch results of `previous` over (when it is replaced after
+    /// a config change), for the sources both have
+    pub fn keep_fetches_from(&self, previous: &ContextSourceManager) {
+        let previous = previous.last_fetch.lock();
+        let mut last_fetch = self.last_fetch.lock();
+        for source in &self.sources {
+            if let Some(fetch) = previous.get(source.source_id()) {
+                last_fetch.insert(source.source_id().to_string(), fetch.clone());
+            }
+        }
+    }
+}
+
+impl Default for ContextSourceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns its tasks; drops any a dependency already has
+    struct TaskList {
+        id: &'static str,
+        depends_on: Vec<String>,
+        titles: Vec<&'static str>,
+    }
+
+    #[async_trait]
+    impl ContextSource for TaskList {
+        fn source_id(&self) -> &str {
+            self.id
+        }
+
+        fn display_name(&self) -> &str {
+            self.id
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        async fn fetch_context(&self, _: DateTime<Utc>, _: DateTime<Utc>) -> Result<ContextData> {
+            let tasks = self
+                .titles
+                .iter()
+                .map(|title| Task {
+                    id: title.to_string(),
+                    title: title.to_string(),
+                    description: None,
+                    due_date: None,
+                    priority: 5,
+                    status: TaskStatus::Pending,
+                    tags: vec![],
+                    source: self.id.to_string(),
+                })
+                .collect();
+            Ok(ContextData {
+                source_id: self.id.to_string(),
+                timestamp: Utc::now(),
+                data_type: ContextDataType::Tasks,
+                priority: 100,
+                content: ContextContent::Tasks(TaskContext::new(tasks, Utc::now())),
+                metadata: HashMap::new(),
+            })
+        }
+
+        fn depends_on(&self) -> Vec<String> {
+            self.depends_on.clone()
+        }
+
+        fn reconcile(&self, own: &mut ContextData, upstream: &[&ContextData]) {
+            let ContextContent::Tasks(own) = &mut own.content else {
+                return;
+            };
+            for data in upstream {
+                if let ContextContent::Tasks(theirs) = &data.content {
+                    own.tasks.retain(|t| {
+                        !theirs.tasks.iter().any(|o| {
+                            normalize_task_title(&o.title) == normalize_task_title(&t.title)
+                        })
+                    });
+                }
+            }
+        }
+    }
+
+    fn source(id: &'static str, depends_on: &[&str], titles: &[&'static str]) -> Box<TaskList> {
+        Box::new(TaskList {
+            id,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+            titles: titles.to_vec(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sources_fetch_after_dependencies_and_reconcile() {
+        let mut manager = ContextSourceManager::new();
+        manager.add_source(source(
+            "vault",
+            &["todoist", "missing"],
+            &["Call  plumber.", "Buy milk"],
+        ));
+        manager.add_source(source("loop_a", &["loop_b"], &[]));
+        manager.add_source(source("todoist", &[], &["call plumber"]));
+        manager.add_source(source("loop_b", &["loop_a"], &[]));
+
+        let order: Vec<&str> = manager
+            .fetch_order()
+            .iter()
+            .map(|s| s.source_id())
+            .collect();
+        assert_eq!(order, ["todoist", "vault", "loop_a", "loop_b"]);
+
+        let context = manager
+            .fetch_all_context(Utc::now(), Utc::now())
+            .await
+            .unwrap();
+        let vault = context.iter().find(|c| c.source_id == "vault").unwrap();
+        let ContextContent::Tasks(tasks) = &vault.content else {
+            panic!("expected tasks");
+        };
+        let titles: Vec<&str> = tasks.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["Buy milk"]);
+    }
+}
@@ -0,0 +1,112 @@
+//! Local extractive summaries of markdown notes.
+//!
+//! Daily notes can run to pages; only their skeleton is useful to the AI.
+//! The summary keeps headings, open tasks and the first few top-level bullets
+//! under each heading, capped at a character budget, so note bodies never
+//! reach the cloud model verbatim.
+
+/// Top-level bullets kept per heading
+const BULLETS_PER_HEADING: usize = 3;
+
+/// Reduce markdown to headings, open tasks and leading bullets, at most `max_chars` long
+pub fn summarize_markdown(content: &str, max_chars: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut bullets_under_heading = 0;
+
+    for raw in content.lines() {
+        let line = raw.trim_end();
+        let trimmed = line.trim_start();
+        let indented = line.len() != trimmed.len();
+
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let heading = heading.trim_start_matches('#').trim();
+            if !heading.is_empty() {
+                lines.push(format!("{}:", heading));
+            }
+            bullets_under_heading = 0;
+        } else if let Some(task) = trimmed
+            .strip_prefix("- [ ] ")
+            .or_else(|| trimmed.strip_prefix("* [ ] "))
+        {
+            // Open tasks are always worth keeping, even when nested
+            lines.push(format!("- [ ] {}", task.trim()));
+        } else if trimmed.starts_with("- [x] ") || trimmed.starts_with("- [X] ") {
+            continue;
+        } else if let Some(bullet) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            if !indented && bullets_under_heading < BULLETS_PER_HEADING && !bullet.trim().is_empty()
+            {
+                lines.push(format!("- {}", bullet.trim()));
+                bullets_under_heading += 1;
+            }
+        }
+    }
+
+    // Drop headings that ended up with nothing under them
+    let mut kept: Vec<&str> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let is_heading = line.ends_with(':') && !line.starts_with('-');
+        let has_body = lines
+            .get(i + 1)
+            .map(|next| next.starts_with('-'))
+            .unwrap_or(false);
+        if !is_heading || has_body {
+            kept.push(line);
+        }
+    }
+
+    let mut summary = String::new();
+    for line in kept {
+        let needed = line.chars().count() + usize::from(!summary.is_empty());
+        if summary.chars().count() + needed > max_chars {
+            if !summary.is_empty() {
+                summary.push_str("\n…");
+            }
+            break;
+        }
+        if !summary.is_empty() {
+            summary.push('\n');
+        }
+        summary.push_str(line);
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTE: &str = "# 2026-10-16\n\
+        Long paragraph about the day that should never be sent as-is.\n\
+        ## Meetings\n\
+        - Standup went long\n\
+        \x20\x20- nested detail\n\
+        - 1:1 with Sam\n\
+        - Design review\n\
+        - Lunch\n\
+        ## Todo\n\
+        - [x] Send invoice\n\
+        - [ ] Book dentist\n\
+        \x20\x20- [ ] Call insurance\n\
+        ## Journal\n\
+        Felt good.\n";
+
+    #[test]
+    fn test_summary_keeps_structure_and_open_tasks() {
+        let summary = summarize_markdown(NOTE, 1000);
+        assert_eq!(
+            summary,
+            "Meetings:\n- Standup went long\n- 1:1 with Sam\n- Design review\n\
+             Todo:\n- [ ] Book dentist\n- [ ] Call insurance"
+        );
+    }
+
+    #[test]
+    fn test_summary_respects_cap() {
+        let summary = summarize_markdown(NOTE, 40);
+        assert!(summary.chars().count() <= 42);
+        assert!(summary.ends_with('…'));
+    }
+}
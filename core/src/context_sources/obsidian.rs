@@ -20,7 +20,7 @@ struct ObsidianRegexes {
 fn obsidian_regexes() -> &'static ObsidianRegexes {
     static REGEXES: OnceLock<ObsidianRegexes> = OnceLock::new();
     REGEXES.get_or_init(|| ObsidianRegexes {
-        frontmatter: Regex::new(r"^---\n(.*?)\n---\n(.*)$").unwrap(),
+        frontmatter: Regex::new(r"(?s)^---\n(.*?)\n---\n(.*)$").unwrap(),
         task: Regex::new(r"^\s*- \[([ x])\] (.+)$").unwrap(),
         item: Regex::new(r"^\s*[-*]\s+(.+)$").unwrap(),
         focus_patterns: vec![
@@ -31,9 +31,10 @@ fn obsidian_regexes() -> &'static ObsidianRegexes {
     })
 }
 
+use super::note_summary::summarize_markdown;
 use super::{
-    ContextContent, ContextData, ContextDataType, ContextSource, DailyNote, NotesContext, Project,
-    ProjectStatus, Task, TaskStatus,
+    ContextContent, ContextData, ContextDataType, ContextSource, DailyNote, NotesContext,
+    PersonNote, Project, ProjectStatus, Task, TaskStatus,
 };
 
 /// Obsidian vault context source
@@ -58,6 +59,13 @@ pub struct ObsidianConfig {
     pub relationship_alert_days: i64,
     pub ignored_folders: Vec<String>,
     pub ignored_files: Vec<String>,
+    /// Daily notes are reduced locally to at most this many characters
+    #[serde(default = "default_max_note_summary_chars")]
+    pub max_note_summary_chars: usize,
+}
+
+fn default_max_note_summary_chars() -> usize {
+    600
 }
 
 impl Default for ObsidianConfig {
@@ -75,6 +83,7 @@ impl Default for ObsidianConfig {
             relationship_alert_days: 21,
             ignored_folders: vec![".obsidian".to_string(), ".trash".to_string()],
             ignored_files: vec![".DS_Store".to_string()],
+            max_note_summary_chars: default_max_note_summary_chars(),
         }
     }
 }
@@ -84,6 +93,8 @@ impl Default for ObsidianConfig {
 pub struct FrontMatter {
     pub name: Option<String>,
     pub status: Option<String>,
+    /// `start_date`, or failing that `created`
+    pub start_date: Option<NaiveDate>,
     pub due_date: Option<NaiveDate>,
     pub priority: Option<i32>,
     pub client: Option<String>,
@@ -143,6 +154,13 @@ impl ObsidianVaultSource {
                             .get("status")
                             .and_then(|v| v.as_str())
                             .map(|s| s.to_string()),
+                        start_date: ["start_date", "created"]
+                            .iter()
+                            .filter_map(|key| yaml_map.get(*key).and_then(|v| v.as_str()))
+                            .find_map(|s| {
+                                // `created` is often a full timestamp
+                                NaiveDate::parse_from_str(s.get(..10).unwrap_or(s), "%Y-%m-%d").ok()
+                            }),
                         due_date: yaml_map
                             .get("due_date")
                             .and_then(|v| v.as_str())
@@ -268,7 +286,11 @@ impl ObsidianVaultSource {
                                 })?
                                 .and_utc(),
                             title: filename,
-                            content: markdown_content,
+                            // Only a capped local summary leaves the machine
+                            content: summarize_markdown(
+                                &markdown_content,
+                                self.config.max_note_summary_chars,
+                            ),
                             tasks,
                             mood,
                             energy_level,
@@ -362,6 +384,9 @@ impl ObsidianVaultSource {
                                 name: project_name,
                                 description: None, // Could extract from content
                                 status,
+                                start_date: fm
+                                    .start_date
+                                    .and_then(|d| d.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc())),
                                 due_date: fm
                                     .due_date
                                     .and_then(|d| d.and_hms_opt(23, 59, 59).map(|dt| dt.and_utc())),
@@ -381,6 +406,125 @@ impl ObsidianVaultSource {
 
         Ok(projects)
     }
+
+    /// People notes, for recognizing who an event is with. Every note counts,
+    /// with or without frontmatter; the file name stands in for a name.
+    async fn get_people(&self) -> Result<Vec<PersonNote>> {
+        let mut people = Vec::new();
+        let people_path = self.vault_path.join(&self.config.people_folder);
+
+        if !people_path.exists() {
+            debug!("People folder does not exist: {:?}", people_path);
+            return Ok(people);
+        }
+
+        let today = Utc::now().date_naive();
+        let mut entries = async_fs::read_dir(&people_path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "md") {
+                continue;
+            }
+            let content = match async_fs::read_to_string(&path).await {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to read person note {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            let id = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            let (frontmatter, _) = Self::parse_frontmatter(&content)?;
+            let strings = |key: &str| -> Vec<String> {
+                match frontmatter.as_ref().and_then(|fm| fm.other.get(key)) {
+                    Some(serde_yaml::Value::String(s)) => vec![s.clone()],
+                    Some(serde_yaml::Value::Sequence(items)) => items
+                        .iter()
+                        .filter_map(|v| v.as_str().map(str::to_string))
+                        .collect(),
+                    _ => Vec::new(),
+                }
+            };
+
+            let mut emails = strings("email");
+            emails.extend(strings("emails"));
+            let last_contact = strings("last_contact")
+                .first()
+                .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+            people.push(PersonNote {
+                name: frontmatter
+                    .as_ref()
+                    .and_then(|fm| fm.name.clone())
+                    .unwrap_or_else(|| id.clone()),
+                id,
+                emails: emails.iter().map(|e| e.trim().to_lowercase()).collect(),
+                aliases: strings("aliases"),
+                last_contact,
+                overdue: last_contact
+                    .is_some_and(|d| (today - d).num_days() > self.config.relationship_alert_days),
+            });
+        }
+
+        Ok(people)
+    }
+
+    /// Check off a task, by the ID `extract_tasks` gave it, in its daily note
+    /// or project file. Returns the file it was found in.
+    pub async fn complete_task(&self, task_id: &str) -> Result<PathBuf> {
+        // "<file name>:<line>:<first 20 chars>"; the line is counted after the
+        // frontmatter, so the text is what finds the task
+        let mut parts = task_id.splitn(3, ':');
+        let (Some(file_name), Some(_), Some(prefix)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(anyhow!("Not an Obsidian task ID: {}", task_id));
+        };
+
+        for folder in [
+            &self.config.daily_notes_folder,
+            &self.config.projects_folder,
+        ] {
+            let path = self.vault_path.join(folder).join(file_name);
+            if !path.is_file() {
+                continue;
+            }
+            let content = async_fs::read_to_string(&path).await?;
+            if let Some(updated) = Self::check_off(&content, prefix) {
+                async_fs::write(&path, updated).await?;
+                info!("Completed task in {:?}", path);
+                return Ok(path);
+            }
+        }
+        Err(anyhow!(
+            "No open task \"{}\" in {}",
+            prefix.trim(),
+            file_name
+        ))
+    }
+
+    /// `content` with the first open task starting with `prefix` checked off
+    fn check_off(content: &str, prefix: &str) -> Option<String> {
+        let task_regex = &obsidian_regexes().task;
+        let mut found = false;
+        let lines: Vec<String> = content
+            .split_inclusive('\n')
+            .map(|line| {
+                let open = !found
+                    && task_regex
+                        .captures(line.trim_end_matches(['\r', '\n']))
+                        .is_some_and(|c| &c[1] == " " && c[2].starts_with(prefix));
+                if open {
+                    found = true;
+                    line.replacen("- [ ]", "- [x]", 1)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        found.then(|| lines.concat())
+    }
 }
 
 #[async_trait]
@@ -405,6 +549,7 @@ impl ContextSource for ObsidianVaultSource {
 
         let daily_notes = self.get_daily_notes(start, end).await?;
         let active_projects = self.get_active_projects().await?;
+        let people = self.get_people().await?;
 
         // Extract all tasks from daily notes and projects
         let mut all_tasks = Vec::new();
@@ -422,6 +567,7 @@ impl ContextSource for ObsidianVaultSource {
                 .into_iter()
                 .filter(|t| matches!(t.status, TaskStatus::Pending))
                 .collect(),
+            people,
         };
 
         Ok(ContextData {
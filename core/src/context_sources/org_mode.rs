@@ -0,0 +1,301 @@
+//! Tasks from Org-mode files, for Emacs users.
+//!
+//! Every headline with a TODO keyword in the configured `.org` files is a
+//! task. The keywords are Org's own TODO/DONE plus the ones most setups add
+//! (NEXT, STARTED, WAITING, HOLD, CANCELLED), and any a file declares with
+//! `#+TODO:`, `#+SEQ_TODO:` or `#+TYP_TODO:` (done keywords after the `|`).
+//! A DEADLINE is the task's due date, or failing that its SCHEDULED date;
+//! `[#A]`-`[#C]` set the priority and `:tag:` groups the tags. Done headlines
+//! are left out.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tokio::fs;
+use tracing::{debug, warn};
+
+use super::{
+    ContextContent, ContextData, ContextDataType, ContextSource, Task, TaskContext, TaskStatus,
+};
+
+/// Same as the other task sources
+const PRIORITY: i32 = 120;
+
+/// Open keywords known without a `#+TODO:` line, and the status each means
+const OPEN_KEYWORDS: &[(&str, TaskStatus)] = &[
+    ("TODO", TaskStatus::Pending),
+    ("NEXT", TaskStatus::InProgress),
+    ("STARTED", TaskStatus::InProgress),
+    ("WAITING", TaskStatus::Blocked),
+    ("HOLD", TaskStatus::Blocked),
+];
+
+/// Done keywords known without a `#+TODO:` line
+const DONE_KEYWORDS: &[&str] = &["DONE", "CANCELLED", "CANCELED"];
+
+struct Patterns {
+    headline: Regex,
+    todo_setting: Regex,
+    planning: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        headline: Regex::new(
+            r"^\*+\s+(\S+)\s+(?:\[#([A-Za-z0-9])\]\s+)?(.*?)(?:\s+(:[^\s:]+(?::[^\s:]+)*:))?\s*$",
+        )
+        .unwrap(),
+        todo_setting: Regex::new(r"(?i)^#\+(?:SEQ_|TYP_)?TODO:\s*(.*)$").unwrap(),
+        planning: Regex::new(
+            r"(SCHEDULED|DEADLINE):\s*<(\d{4}-\d{2}-\d{2})(?:\s+[^\s>\d][^\s>]*)?(?:\s+(\d{1,2}:\d{2}))?[^>]*>",
+        )
+        .unwrap(),
+    })
+}
+
+/// A timestamp's date and optional time in `tz`; dates alone count as the
+/// end of the day, so a task isn't overdue until the day is over
+fn timestamp(date: &str, time: Option<&str>, tz: Tz) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let time = match time {
+        Some(time) => NaiveTime::parse_from_str(time, "%H:%M").ok()?,
+        None => NaiveTime::from_hms_opt(23, 59, 59)?,
+    };
+    Some(
+        tz.from_local_datetime(&date.and_time(time))
+            .earliest()?
+            .to_utc(),
+    )
+}
+
+/// Open tasks in the Org document `text`, from a file called `file_name`
+pub fn parse(text: &str, file_name: &str, tz: Tz) -> Vec<Task> {
+    let patterns = patterns();
+
+    // Keywords from the file's settings, on top of the usual ones
+    let mut open: HashMap<String, TaskStatus> = OPEN_KEYWORDS
+        .iter()
+        .map(|(k, s)| (k.to_string(), s.clone()))
+        .collect();
+    let mut done: Vec<String> = DONE_KEYWORDS.iter().map(|k| k.to_string()).collect();
+    for line in text.lines() {
+        let Some(caps) = patterns.todo_setting.captures(line.trim()) else {
+            continue;
+        };
+        // Without a "|" the last keyword is the done one
+        let words: Vec<&str> = caps[1].split_whitespace().collect();
+        let split = words
+            .iter()
+            .position(|w| *w == "|")
+            .unwrap_or(words.len().saturating_sub(1));
+        // "TODO(t)" and "DONE(d@/!)" carry fast-access keys
+        let keyword = |w: &&str| w.split('(').next().unwrap_or(w).to_string();
+        for word in &words[..split] {
+            open.entry(keyword(word)).or_insert(TaskStatus::Pending);
+        }
+        done.extend(words[split..].iter().filter(|w| **w != "|").map(keyword));
+    }
+
+    let mut tasks: Vec<Task> = Vec::new();
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        let Some(caps) = patterns.headline.captures(line) else {
+            continue;
+        };
+        let keyword = &caps[1];
+        let Some(status) = open.get(keyword) else {
+            if !done.iter().any(|d| d == keyword) {
+                continue;
+            }
+            // Skip the done task's planning line along with it
+            lines.next_if(|l| patterns.planning.is_match(l));
+            continue;
+        };
+        let title = caps[3].trim().to_string();
+        if title.is_empty() {
+            continue;
+        }
+
+        // SCHEDULED and DEADLINE go on the line after the headline
+        let (mut scheduled, mut deadline) = (None, None);
+        if let Some(planning) = lines.next_if(|l| patterns.planning.is_match(l)) {
+            for caps in patterns.planning.captures_iter(planning) {
+                let at = timestamp(&caps[2], caps.get(3).map(|m| m.as_str()), tz);
+                match &caps[1] {
+                    "DEADLINE" => deadline = at,
+                    _ => scheduled = at,
+                }
+            }
+        }
+        let description = match (scheduled, deadline) {
+            (Some(scheduled), Some(_)) => Some(format!(
+                "Scheduled for {}",
+                scheduled.with_timezone(&tz).format("%a %-d %b")
+            )),
+            _ => None,
+        };
+
+        tasks.push(Task {
+            id: format!(
+                "org:{}:{:x}",
+                file_name,
+                md5::compute(format!("{} {}", keyword, title))
+            ),
+            title,
+            description,
+            due_date: deadline.or(scheduled),
+            // Org treats a headline without a cookie as [#B]
+            priority: match caps
+                .get(2)
+                .map(|m| m.as_str().to_ascii_uppercase())
+                .as_deref()
+            {
+                Some("A") => 10,
+                Some("C") => 3,
+                _ => 5,
+            },
+            status: status.clone(),
+            tags: caps
+                .get(4)
+                .map(|m| {
+                    m.as_str()
+                        .split(':')
+                        .filter(|t| !t.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            source: "org".to_string(),
+        });
+    }
+    tasks
+}
+
+/// Org-mode files as a task source
+pub struct OrgModeSource {
+    files: Vec<PathBuf>,
+    timezone: Tz,
+}
+
+impl OrgModeSource {
+    pub fn new(files: Vec<PathBuf>, timezone: Tz) -> Self {
+        Self { files, timezone }
+    }
+}
+
+#[async_trait]
+impl ContextSource for OrgModeSource {
+    fn source_id(&self) -> &str {
+        "org_mode"
+    }
+
+    fn display_name(&self) -> &str {
+        "Org-mode"
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.files.is_empty()
+    }
+
+    async fn fetch_context(
+        &self,
+        _start: DateTime<Utc>,
+        _end: DateTime<Utc>,
+    ) -> Result<ContextData> {
+        let mut tasks = Vec::new();
+        for path in &self.files {
+            // One missing or unreadable file shouldn't hide the others
+            let text = match fs::read_to_string(path).await {
+                Ok(text) => text,
+                Err(e) => {
+                    warn!("Failed to read Org file {:?}: {}", path, e);
+                    continue;
+                }
+            };
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let found = parse(&text, &file_name, self.timezone);
+            debug!("{} open tasks in {:?}", found.len(), path);
+            tasks.extend(found);
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert("files".to_string(), self.files.len().to_string());
+        Ok(ContextData {
+            source_id: self.source_id().to_string(),
+            timestamp: Utc::now(),
+            data_type: ContextDataType::Tasks,
+            priority: PRIORITY,
+            content: ContextContent::Tasks(TaskContext::new(tasks, Utc::now())),
+            metadata,
+        })
+    }
+
+    fn priority(&self) -> i32 {
+        PRIORITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_keywords_planning_and_priorities() {
+        let tz: Tz = "Europe/Berlin".parse().unwrap();
+        let org = "#+TITLE: Home\n\
+                   #+TODO: TODO(t) REVIEW | SHIPPED(s@)\n\
+                   * Projects\n\
+                   ** TODO [#A] Renew passport :admin:travel:\n\
+                   DEADLINE: <2026-05-20 Wed> SCHEDULED: <2026-05-14 Thu 09:30 +1w>\n\
+                   Bring two photos.\n\
+                   ** REVIEW Quarterly budget\n\
+                   SCHEDULED: <2026-05-13 Wed>\n\
+                   ** WAITING [#C] Plumber quote\n\
+                   ** SHIPPED Website refresh\n\
+                   DEADLINE: <2026-05-01 Fri>\n\
+                   ** DONE Taxes\n\
+                   ** Heading that mentions TODO later\n";
+        let tasks = parse(org, "home.org", tz);
+        let titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            ["Renew passport", "Quarterly budget", "Plumber quote"]
+        );
+
+        let passport = &tasks[0];
+        assert_eq!(passport.priority, 10);
+        assert_eq!(passport.tags, ["admin", "travel"]);
+        assert_eq!(
+            passport.due_date,
+            Some(
+                tz.with_ymd_and_hms(2026, 5, 20, 23, 59, 59)
+                    .unwrap()
+                    .to_utc()
+            )
+        );
+        assert_eq!(
+            passport.description.as_deref(),
+            Some("Scheduled for Thu 14 May")
+        );
+        assert!(passport.id.starts_with("org:home.org:"));
+
+        // Scheduled only: that's the due date
+        assert_eq!(
+            tasks[1].due_date,
+            Some(
+                tz.with_ymd_and_hms(2026, 5, 13, 23, 59, 59)
+                    .unwrap()
+                    .to_utc()
+            )
+        );
+        assert!(matches!(tasks[1].status, TaskStatus::Pending));
+        assert!(matches!(tasks[2].status, TaskStatus::Blocked));
+        assert_eq!(tasks[2].priority, 3);
+    }
+}
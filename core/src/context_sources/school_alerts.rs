@@ -0,0 +1,431 @@
+//! School district alerts: closures, delays and early dismissals.
+//!
+//! Each configured feed is either an RSS/Atom feed or a plain web page (a
+//! district's alert banner), detected from the response. Feed items, or the
+//! page's lines of text, are kept only when they mention a closure keyword,
+//! so an ordinary district news feed can be used as-is. A new alert makes
+//! the significance engine fire straight away: a closure reshuffles the
+//! whole family day.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use super::{
+    ContextContent, ContextData, ContextDataType, ContextSource, SchoolAlert, SchoolAlertContext,
+    SchoolAlertKind,
+};
+use crate::http_utils;
+
+/// Ahead of every other context source
+const PRIORITY: i32 = 1000;
+/// Lines kept from a scraped page, so a page full of old notices can't flood the prompt
+const MAX_PAGE_ALERTS: usize = 3;
+/// Longer lines of page text are paragraphs of boilerplate, not alerts
+const MAX_PAGE_LINE_CHARS: usize = 300;
+
+/// Keywords an item must mention when `keywords` isn't configured
+pub const DEFAULT_KEYWORDS: &[&str] = &[
+    "closed",
+    "closure",
+    "closing",
+    "cancel",
+    "no school",
+    "snow day",
+    "delay",
+    "late start",
+    "early dismissal",
+    "early release",
+    "dismiss early",
+    "remote learning",
+    "virtual learning",
+    "shelter in place",
+    "lockdown",
+];
+
+/// One district feed or alert page
+#[derive(Debug, Clone)]
+pub struct SchoolFeed {
+    /// District or school name shown with its alerts
+    pub name: String,
+    pub url: String,
+}
+
+struct CachedAlerts {
+    alerts: Vec<SchoolAlert>,
+    fetched_at: DateTime<Utc>,
+}
+
+pub struct SchoolAlertSource {
+    feeds: Vec<SchoolFeed>,
+    /// Lowercase; an item must contain one of them
+    keywords: Vec<String>,
+    /// Feed items published longer ago than this are old news
+    max_age_hours: u32,
+    cache_duration_minutes: u32,
+    client: Client,
+    cache: RwLock<Option<CachedAlerts>>,
+}
+
+impl SchoolAlertSource {
+    pub fn new(
+        feeds: Vec<SchoolFeed>,
+        keywords: Vec<String>,
+        max_age_hours: u32,
+        cache_duration_minutes: u32,
+    ) -> Self {
+        let keywords = if keywords.is_empty() {
+            DEFAULT_KEYWORDS.iter().map(|k| k.to_string()).collect()
+        } else {
+            keywords.iter().map(|k| k.to_lowercase()).collect()
+        };
+        Self {
+            feeds,
+            keywords,
+            max_age_hours,
+            cache_duration_minutes,
+            client: Client::new(),
+            cache: RwLock::new(None),
+        }
+    }
+
+    async fn fetch_feed(&self, feed: &SchoolFeed) -> Result<String> {
+        debug!("Fetching school alerts for {}", feed.name);
+        let response = http_utils::send(self.client.get(&feed.url)).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "School alert feed {} failed ({})",
+                feed.name,
+                response.status()
+            ));
+        }
+        Ok(response.text().await?)
+    }
+
+    /// Alerts from every feed; a feed that fails is skipped
+    async fn fetch_alerts(&self) -> Vec<SchoolAlert> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(ref cached) = *cache {
+                let age = Utc::now() - cached.fetched_at;
+                if age.num_minutes() < self.cache_duration_minutes as i64 {
+                    return cached.alerts.clone();
+                }
+            }
+        }
+
+        let bodies =
+            futures_util::future::join_all(self.feeds.iter().map(|f| self.fetch_feed(f))).await;
+        let now = Utc::now();
+        let mut alerts = Vec::new();
+        for (feed, body) in self.feeds.iter().zip(bodies) {
+            match body {
+                Ok(body) => alerts.extend(parse(
+                    &feed.name,
+                    &body,
+                    &self.keywords,
+                    now - Duration::hours(self.max_age_hours as i64),
+                )),
+                Err(e) => warn!("Failed to fetch school alerts (non-fatal): {}", e),
+            }
+        }
+
+        *self.cache.write().await = Some(CachedAlerts {
+            alerts: alerts.clone(),
+            fetched_at: now,
+        });
+        alerts
+    }
+}
+
+#[async_trait]
+impl ContextSource for SchoolAlertSource {
+    fn source_id(&self) -> &str {
+        "school_alerts"
+    }
+
+    fn display_name(&self) -> &str {
+        "School Alerts"
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.feeds.is_empty()
+    }
+
+    async fn fetch_context(
+        &self,
+        _start: DateTime<Utc>,
+        _end: DateTime<Utc>,
+    ) -> Result<ContextData> {
+        let alerts = self.fetch_alerts().await;
+        let mut metadata = HashMap::new();
+        metadata.insert("alert_count".to_string(), alerts.len().to_string());
+        Ok(ContextData {
+            source_id: self.source_id().to_string(),
+            timestamp: Utc::now(),
+            data_type: ContextDataType::SchoolAlerts,
+            priority: PRIORITY,
+            content: ContextContent::SchoolAlerts(SchoolAlertContext { alerts }),
+            metadata,
+        })
+    }
+
+    fn priority(&self) -> i32 {
+        PRIORITY
+    }
+}
+
+struct Patterns {
+    item: Regex,
+    title: Regex,
+    id: Regex,
+    link: Regex,
+    summary: Regex,
+    date: Regex,
+    cdata: Regex,
+    hidden: Regex,
+    tag: Regex,
+    block: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        item: Regex::new(r"(?is)<(item|entry)\b.*?</(item|entry)>").unwrap(),
+        title: Regex::new(r"(?is)<title\b[^>]*>(.*?)</title>").unwrap(),
+        id: Regex::new(r"(?is)<(guid|id)\b[^>]*>(.*?)</(guid|id)>").unwrap(),
+        link: Regex::new(r#"(?is)<link\b[^>]*?(?:href="([^"]*)"[^>]*)?>(?:(.*?)</link>)?"#)
+            .unwrap(),
+        summary: Regex::new(r"(?is)<(description|summary|content)\b[^>]*>(.*?)</(description|summary|content)>")
+            .unwrap(),
+        date: Regex::new(r"(?is)<(pubDate|published|updated|dc:date)\b[^>]*>(.*?)</(pubDate|published|updated|dc:date)>")
+            .unwrap(),
+        cdata: Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").unwrap(),
+        hidden: Regex::new(r"(?is)<(script|style|head|nav|footer)\b.*?</(script|style|head|nav|footer)>")
+            .unwrap(),
+        tag: Regex::new(r"(?s)<[^>]*>").unwrap(),
+        block: Regex::new(r"(?i)<(br|/p|/div|/li|/h[1-6]|/tr)\b[^>]*>").unwrap(),
+    })
+}
+
+/// Alerts in a fetched feed or page, newest first. Items published before
+/// `since` are dropped; items without a date are kept.
+pub fn parse(
+    district: &str,
+    body: &str,
+    keywords: &[String],
+    since: DateTime<Utc>,
+) -> Vec<SchoolAlert> {
+    let p = patterns();
+    let items: Vec<&str> = p.item.find_iter(body).map(|m| m.as_str()).collect();
+    if items.is_empty() && !body.contains("<rss") && !body.contains("<feed") {
+        return scrape_page(district, body, keywords);
+    }
+
+    let mut alerts: Vec<SchoolAlert> = items
+        .into_iter()
+        .filter_map(|item| {
+            let field = |re: &Regex, group: usize| {
+                re.captures(item)
+                    .and_then(|c| c.get(group))
+                    .map(|m| text(m.as_str()))
+                    .filter(|t| !t.is_empty())
+            };
+            let title = field(&p.title, 1)?;
+            let summary = field(&p.summary, 2).unwrap_or_default();
+            let haystack = format!("{} {}", title, summary).to_lowercase();
+            if !keywords.iter().any(|k| haystack.contains(k.as_str())) {
+                return None;
+            }
+            let published = field(&p.date, 2).and_then(|d| parse_date(&d));
+            if published.is_some_and(|d| d < since) {
+                return None;
+            }
+            let id = field(&p.id, 2)
+                .or_else(|| field(&p.link, 1))
+                .or_else(|| field(&p.link, 2))
+                .unwrap_or_else(|| fingerprint(district, &title));
+            Some(SchoolAlert {
+                id,
+                district: district.to_string(),
+                kind: SchoolAlertKind::classify(&haystack),
+                title,
+                published,
+            })
+        })
+        .collect();
+    alerts.sort_by_key(|a| std::cmp::Reverse(a.published));
+    alerts
+}
+
+/// Lines of page text mentioning a keyword; a page has no dates, so whatever
+/// the banner says now is current
+fn scrape_page(district: &str, html: &str, keywords: &[String]) -> Vec<SchoolAlert> {
+    let p = patterns();
+    let visible = p.hidden.replace_all(html, " ");
+    let visible = p.block.replace_all(&visible, "\n");
+    let visible = decode_entities(&p.tag.replace_all(&visible, " "));
+    visible
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty() && line.chars().count() <= MAX_PAGE_LINE_CHARS)
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            keywords.iter().any(|k| lower.contains(k.as_str()))
+        })
+        .take(MAX_PAGE_ALERTS)
+        .map(|line| SchoolAlert {
+            id: fingerprint(district, &line),
+            district: district.to_string(),
+            kind: SchoolAlertKind::classify(&line.to_lowercase()),
+            title: line,
+            published: None,
+        })
+        .collect()
+}
+
+/// Plain text of an element's content: CDATA unwrapped, tags dropped, entities decoded
+fn text(raw: &str) -> String {
+    let p = patterns();
+    let unwrapped = p.cdata.replace_all(raw, "$1");
+    let decoded = decode_entities(&unwrapped);
+    // Descriptions are often escaped HTML, so strip tags after decoding
+    let stripped = p.tag.replace_all(&decoded, " ");
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// RSS dates are RFC 2822, Atom dates RFC 3339
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(date)
+        .or_else(|_| DateTime::parse_from_rfc3339(date))
+        .ok()
+        .map(|d| d.to_utc())
+}
+
+/// Stable ID for an alert without one of its own
+fn fingerprint(district: &str, text: &str) -> String {
+    format!("{:x}", md5::compute(format!("{}\n{}", district, text)))
+}
+
+impl SchoolAlertKind {
+    /// Kind of alert from its lowercase text
+    pub fn classify(text: &str) -> Self {
+        if [
+            "early dismissal",
+            "early release",
+            "dismiss early",
+            "dismissed early",
+        ]
+        .iter()
+        .any(|k| text.contains(k))
+        {
+            SchoolAlertKind::EarlyDismissal
+        } else if ["delay", "late start", "late opening"]
+            .iter()
+            .any(|k| text.contains(k))
+        {
+            SchoolAlertKind::Delay
+        } else if [
+            "closed",
+            "closure",
+            "closing",
+            "cancel",
+            "no school",
+            "snow day",
+            "remote learning",
+            "virtual learning",
+        ]
+        .iter()
+        .any(|k| text.contains(k))
+        {
+            SchoolAlertKind::Closure
+        } else {
+            SchoolAlertKind::Other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords() -> Vec<String> {
+        DEFAULT_KEYWORDS.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_rss_keeps_recent_closure_items() {
+        let rss = r#"<?xml version="1.0"?>
+<rss version="2.0"><channel><title>Lincoln USD News</title>
+<item>
+  <title>All schools closed Tuesday due to ice</title>
+  <guid>https://lincoln.example/news/412</guid>
+  <pubDate>Tue, 12 May 2026 05:30:00 GMT</pubDate>
+</item>
+<item>
+  <title><![CDATA[Early dismissal &amp; no PM activities]]></title>
+  <description>&lt;p&gt;Students will be dismissed at 12:30.&lt;/p&gt;</description>
+  <pubDate>Mon, 11 May 2026 18:00:00 GMT</pubDate>
+</item>
+<item>
+  <title>Spring concert tickets on sale</title>
+  <pubDate>Tue, 12 May 2026 06:00:00 GMT</pubDate>
+</item>
+<item>
+  <title>Schools closed for winter break</title>
+  <pubDate>Mon, 22 Dec 2025 12:00:00 GMT</pubDate>
+</item>
+</channel></rss>"#;
+        let since = DateTime::parse_from_rfc3339("2026-05-11T00:00:00Z")
+            .unwrap()
+            .to_utc();
+
+        let alerts = parse("Lincoln USD", rss, &keywords(), since);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].title, "All schools closed Tuesday due to ice");
+        assert_eq!(alerts[0].id, "https://lincoln.example/news/412");
+        assert_eq!(alerts[0].kind, SchoolAlertKind::Closure);
+        assert_eq!(alerts[1].title, "Early dismissal & no PM activities");
+        assert_eq!(alerts[1].kind, SchoolAlertKind::EarlyDismissal);
+        // No guid or link: a stable fingerprint stands in
+        assert_eq!(
+            alerts[1].id,
+            fingerprint("Lincoln USD", "Early dismissal & no PM activities")
+        );
+    }
+
+    #[test]
+    fn test_scrape_page_finds_alert_banner() {
+        let html = r#"<html><head><title>Closures</title></head><body>
+<nav>Closings | Calendar | Menus</nav>
+<div class="alert">Two-hour delay for all schools on Wednesday, May 13.</div>
+<p>Welcome to the district website.</p>
+<script>var closed = true;</script>
+</body></html>"#;
+
+        let alerts = parse("Lincoln USD", html, &keywords(), Utc::now());
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            alerts[0].title,
+            "Two-hour delay for all schools on Wednesday, May 13."
+        );
+        assert_eq!(alerts[0].kind, SchoolAlertKind::Delay);
+        assert_eq!(alerts[0].published, None);
+    }
+}
@@ -0,0 +1,479 @@
+//! Slack DMs and mentions still waiting on a reply.
+//!
+//! Every conversation the token is a member of is read back
+//! [`LOOKBACK_DAYS`]. A DM is waiting when someone else wrote last, and a
+//! mention of `user_id` in a channel is waiting until the user replies in
+//! its thread or posts in the channel after it. Only messages older than
+//! `unanswered_hours` are surfaced, as communication alerts next to the
+//! people notes that are overdue for a catch-up; a quick reply never shows
+//! up. A bot token (`xoxb-…`) sees the channels the bot was added to and
+//! DMs with the bot; a user token (`xoxp-…`) with the same scopes
+//! (`channels:history`, `groups:history`, `im:history`, `users:read`) sees
+//! your own DMs too.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use super::{
+    ContextContent, ContextData, ContextDataType, ContextSource, MessageContext, MessageKind,
+    UnansweredMessage,
+};
+use crate::config::SlackSourceConfig;
+use crate::http_utils;
+
+const API_BASE: &str = "https://slack.com/api";
+
+/// Alongside the people notes the alerts sit next to
+const PRIORITY: i32 = 110;
+
+/// How far back conversations are read
+pub const LOOKBACK_DAYS: i64 = 7;
+
+/// Conversations read per fetch, so a workspace with hundreds of channels
+/// stays within Slack's rate limits
+const MAX_CONVERSATIONS: usize = 40;
+
+/// Longer messages are cut, the prompt only needs the gist
+const MAX_TEXT_CHARS: usize = 160;
+
+#[derive(Debug, Deserialize)]
+struct SlackReply<T> {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(flatten)]
+    body: Option<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConversationList {
+    #[serde(default)]
+    channels: Vec<Conversation>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Conversation {
+    pub id: String,
+    /// Channel name; DMs have none
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub is_im: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct History {
+    #[serde(default)]
+    messages: Vec<Message>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub text: String,
+    pub ts: String,
+    /// Joins, topic changes and the like
+    #[serde(default)]
+    pub subtype: Option<String>,
+    #[serde(default)]
+    pub bot_id: Option<String>,
+    /// Who has replied in the message's thread
+    #[serde(default)]
+    pub reply_users: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+    user: SlackUser,
+}
+
+#[derive(Debug, Deserialize)]
+struct SlackUser {
+    #[serde(default)]
+    real_name: Option<String>,
+    #[serde(default)]
+    profile: Option<Profile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Profile {
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+impl Message {
+    fn sent(&self) -> Option<DateTime<Utc>> {
+        let (secs, micros) = self.ts.split_once('.').unwrap_or((&self.ts, "0"));
+        DateTime::from_timestamp(secs.parse().ok()?, micros.parse::<u32>().ok()? * 1000)
+    }
+
+    /// Written by a person rather than an integration or Slack itself
+    fn is_human(&self) -> bool {
+        self.user.is_some() && self.bot_id.is_none() && self.subtype.is_none()
+    }
+}
+
+/// Messages in `conversation` still waiting on `me`, once they are at least
+/// `min_age` old. `history` is newest first, as Slack returns it.
+pub fn unanswered<'a>(
+    conversation: &Conversation,
+    history: &'a [Message],
+    me: &str,
+    now: DateTime<Utc>,
+    min_age: Duration,
+) -> Vec<(MessageKind, &'a Message)> {
+    let old_enough = |m: &Message| m.sent().is_some_and(|sent| now - sent >= min_age);
+    let is_mine = |m: &Message| m.user.as_deref() == Some(me);
+    // Everything since the user last wrote here
+    let since_mine = history
+        .iter()
+        .take_while(|m| !is_mine(m))
+        .filter(|m| m.is_human());
+
+    if conversation.is_im {
+        // One alert per DM, dated by the first message left unanswered
+        return since_mine
+            .last()
+            .filter(|m| old_enough(m))
+            .map(|m| vec![(MessageKind::DirectMessage, m)])
+            .unwrap_or_default();
+    }
+    let mention = format!("<@{}>", me);
+    since_mine
+        .filter(|m| m.text.contains(&mention) && !m.reply_users.iter().any(|u| u == me))
+        .filter(|m| old_enough(m))
+        .map(|m| (MessageKind::Mention, m))
+        .collect()
+}
+
+/// Slack's markup as plain text: `<@U1>` becomes "@name" through `name_of`,
+/// links their label (or address), and the result is cut to
+/// [`MAX_TEXT_CHARS`]
+pub fn plain_text(text: &str, name_of: impl Fn(&str) -> String) -> String {
+    static MARKUP: OnceLock<Regex> = OnceLock::new();
+    let markup = MARKUP.get_or_init(|| Regex::new(r"<([@#!]?)([^>|]+)(?:\|([^>]*))?>").unwrap());
+    let plain = markup.replace_all(text, |caps: &regex::Captures| {
+        let label = caps.get(3).map(|l| l.as_str()).filter(|l| !l.is_empty());
+        match (&caps[1], label) {
+            ("@", _) => format!("@{}", name_of(&caps[2])),
+            ("#", Some(label)) => format!("#{}", label),
+            ("!", _) => format!("@{}", label.unwrap_or(&caps[2])),
+            (_, Some(label)) => label.to_string(),
+            (_, None) => caps[2].to_string(),
+        }
+    });
+    let plain = plain
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if plain.chars().count() > MAX_TEXT_CHARS {
+        let cut: String = plain.chars().take(MAX_TEXT_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        plain
+    }
+}
+
+struct CachedMessages {
+    waiting: Vec<UnansweredMessage>,
+    fetched_at: DateTime<Utc>,
+}
+
+pub struct SlackContextSource {
+    config: SlackSourceConfig,
+    client: Client,
+    cache: RwLock<Option<CachedMessages>>,
+    /// Display names by user ID; people rarely rename themselves
+    names: RwLock<HashMap<String, String>>,
+}
+
+impl SlackContextSource {
+    pub fn new(config: SlackSourceConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+            cache: RwLock::new(None),
+            names: RwLock::new(HashMap::new()),
+        }
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, query: &[(&str, &str)]) -> Result<T> {
+        let request = self
+            .client
+            .get(format!("{}/{}", API_BASE, method))
+            .bearer_auth(&self.config.token)
+            .query(query);
+        let response = http_utils::send(request).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Slack {} failed ({})", method, response.status()));
+        }
+        let reply: SlackReply<T> = response.json().await?;
+        match reply.body {
+            Some(body) if reply.ok => Ok(body),
+            _ => Err(anyhow!(
+                "Slack {}: {}",
+                method,
+                reply.error.as_deref().unwrap_or("unknown error")
+            )),
+        }
+    }
+
+    /// The user's display name, else their full name, else the ID
+    async fn name_of(&self, user: &str) -> String {
+        if let Some(name) = self.names.read().await.get(user) {
+            return name.clone();
+        }
+        let name = match self.call::<UserInfo>("users.info", &[("user", user)]).await {
+            Ok(info) => info
+                .user
+                .profile
+                .and_then(|p| p.display_name)
+                .filter(|n| !n.is_empty())
+                .or(info.user.real_name)
+                .unwrap_or_else(|| user.to_string()),
+            Err(e) => {
+                debug!("Slack user {} not looked up: {}", user, e);
+                return user.to_string();
+            }
+        };
+        self.names
+            .write()
+            .await
+            .insert(user.to_string(), name.clone());
+        name
+    }
+
+    /// Conversations to read: DMs first, then channels (only those named in
+    /// `channels`, when set)
+    async fn conversations(&self) -> Result<Vec<Conversation>> {
+        let list: ConversationList = self
+            .call(
+                "users.conversations",
+                &[
+                    ("types", "im,public_channel,private_channel"),
+                    ("exclude_archived", "true"),
+                    ("limit", "200"),
+                ],
+            )
+            .await?;
+        let watched = |c: &Conversation| {
+            c.is_im
+                || self.config.channels.is_empty()
+                || self.config.channels.iter().any(|w| {
+                    let w = w.trim_start_matches('#');
+                    w == c.id || c.name.as_deref() == Some(w)
+                })
+        };
+        let mut conversations: Vec<Conversation> =
+            list.channels.into_iter().filter(watched).collect();
+        conversations.sort_by_key(|c| !c.is_im);
+        conversations.truncate(MAX_CONVERSATIONS);
+        Ok(conversations)
+    }
+
+    async fn fetch_waiting(&self) -> Result<Vec<UnansweredMessage>> {
+        if let Some(ref cached) = *self.cache.read().await {
+            let age = Utc::now() - cached.fetched_at;
+            if age.num_minutes() < self.config.cache_duration_minutes as i64 {
+                return Ok(cached.waiting.clone());
+            }
+        }
+
+        let now = Utc::now();
+        let oldest = (now - Duration::days(LOOKBACK_DAYS))
+            .timestamp()
+            .to_string();
+        let min_age = Duration::hours(self.config.unanswered_hours as i64);
+        let me = self.config.user_id.as_str();
+        let mut waiting = Vec::new();
+        for conversation in self.conversations().await? {
+            let history: History = match self
+                .call(
+                    "conversations.history",
+                    &[
+                        ("channel", conversation.id.as_str()),
+                        ("oldest", oldest.as_str()),
+                        ("limit", "100"),
+                    ],
+                )
+                .await
+            {
+                Ok(history) => history,
+                Err(e) => {
+                    debug!("Skipping Slack conversation {}: {}", conversation.id, e);
+                    continue;
+                }
+            };
+            for (kind, message) in unanswered(&conversation, &history.messages, me, now, min_age) {
+                let from = self
+                    .name_of(message.user.as_deref().unwrap_or_default())
+                    .await;
+                let mut names = HashMap::new();
+                for caps in mention_ids(&message.text) {
+                    names.insert(caps.clone(), self.name_of(&caps).await);
+                }
+                waiting.push(UnansweredMessage {
+                    id: format!("{}:{}", conversation.id, message.ts),
+                    kind,
+                    from,
+                    channel: conversation.name.clone(),
+                    text: plain_text(&message.text, |id| {
+                        names.get(id).cloned().unwrap_or_else(|| id.to_string())
+                    }),
+                    sent: message.sent().unwrap_or(now),
+                });
+            }
+        }
+        waiting.sort_by_key(|m| m.sent);
+
+        *self.cache.write().await = Some(CachedMessages {
+            waiting: waiting.clone(),
+            fetched_at: now,
+        });
+        Ok(waiting)
+    }
+}
+
+/// User IDs mentioned in `text`
+fn mention_ids(text: &str) -> Vec<String> {
+    static MENTION: OnceLock<Regex> = OnceLock::new();
+    MENTION
+        .get_or_init(|| Regex::new(r"<@([A-Z0-9]+)(?:\|[^>]*)?>").unwrap())
+        .captures_iter(text)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+#[async_trait]
+impl ContextSource for SlackContextSource {
+    fn source_id(&self) -> &str {
+        "slack"
+    }
+
+    fn display_name(&self) -> &str {
+        "Slack"
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.token.is_empty() && !self.config.user_id.is_empty()
+    }
+
+    async fn fetch_context(
+        &self,
+        _start: DateTime<Utc>,
+        _end: DateTime<Utc>,
+    ) -> Result<ContextData> {
+        let waiting = self.fetch_waiting().await.map_err(|e| {
+            warn!("Failed to fetch Slack messages: {}", e);
+            e
+        })?;
+        let mut metadata = HashMap::new();
+        metadata.insert("unanswered".to_string(), waiting.len().to_string());
+        Ok(ContextData {
+            source_id: self.source_id().to_string(),
+            timestamp: Utc::now(),
+            data_type: ContextDataType::Messages,
+            priority: PRIORITY,
+            content: ContextContent::Messages(MessageContext { waiting }),
+            metadata,
+        })
+    }
+
+    fn priority(&self) -> i32 {
+        PRIORITY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_finds_dms_and_mentions_left_unanswered() {
+        let now = Utc.with_ymd_and_hms(2026, 5, 12, 17, 0, 0).unwrap();
+        // Newest first; 1778590700 is 12:58, just over four hours before now
+        let history = |body: &str| serde_json::from_str::<History>(body).unwrap().messages;
+        let dm = Conversation {
+            id: "D1".to_string(),
+            name: None,
+            is_im: true,
+        };
+        let four_hours = Duration::hours(4);
+
+        let waiting = history(
+            r#"{"messages": [
+                {"user": "U2", "text": "also, lunch Thursday?", "ts": "1778594400.000200"},
+                {"user": "U2", "text": "can you review the deck?", "ts": "1778590700.000100"},
+                {"user": "ME", "text": "morning!", "ts": "1778580000.000000"},
+                {"user": "U2", "text": "hi", "ts": "1778570000.000000"}
+            ]}"#,
+        );
+        let found = unanswered(&dm, &waiting, "ME", now, four_hours);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, MessageKind::DirectMessage);
+        assert_eq!(found[0].1.text, "can you review the deck?");
+        // Not yet four hours old
+        assert!(unanswered(&dm, &waiting, "ME", now, Duration::hours(5)).is_empty());
+        // Answered
+        let answered = history(
+            r#"{"messages": [
+                {"user": "ME", "text": "sure", "ts": "1778594400.000000"},
+                {"user": "U2", "text": "can you review?", "ts": "1778590800.000000"}
+            ]}"#,
+        );
+        assert!(unanswered(&dm, &answered, "ME", now, four_hours).is_empty());
+
+        let channel = Conversation {
+            id: "C1".to_string(),
+            name: Some("launch".to_string()),
+            is_im: false,
+        };
+        let channel_history = history(
+            r#"{"messages": [
+                {"user": "U3", "text": "<@ME> sign-off needed", "ts": "1778580000.000000"},
+                {"user": "U4", "text": "<@ME> answered in thread", "ts": "1778579000.000000",
+                 "reply_users": ["ME"]},
+                {"bot_id": "B1", "user": "U9", "text": "<@ME> deploy done", "ts": "1778578000.000000"},
+                {"user": "U2", "text": "no mention here", "ts": "1778577000.000000"}
+            ]}"#,
+        );
+        let mentions = unanswered(&channel, &channel_history, "ME", now, four_hours);
+        assert_eq!(mentions.len(), 1);
+        assert_eq!(mentions[0].0, MessageKind::Mention);
+        assert_eq!(
+            mentions[0].1.sent(),
+            Utc.timestamp_opt(1778580000, 0).single()
+        );
+
+        assert_eq!(
+            plain_text(
+                "<@ME> see <https://example.com/deck|the deck> in <#C1|launch> &amp; <!here>",
+                |id| if id == "ME" {
+                    "Sam".to_string()
+                } else {
+                    id.to_string()
+                }
+            ),
+            "@Sam see the deck in #launch & @here"
+        );
+        assert_eq!(mention_ids("<@U1> and <@U2|priya>"), ["U1", "U2"]);
+        let long = plain_text(&"word ".repeat(60), |id| id.to_string());
+        assert_eq!(long.chars().count(), MAX_TEXT_CHARS);
+        assert!(long.ends_with('…'));
+    }
+}
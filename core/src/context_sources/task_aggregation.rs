@@ -0,0 +1,256 @@
+//! One list of tasks out of every source that has them.
+//!
+//! Todoist tasks, local task files and vault checkboxes overlap: people
+//! mirror the same to-do in two places, often with slightly different
+//! wording. Left alone, each copy shows up in the prompt and in the overdue
+//! counts. After every fetch the manager runs [`dedupe`], which keeps the
+//! copy from the most preferred source and drops the rest.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+
+use super::{normalize_task_title, ContextContent, ContextData, Task, TaskContext};
+use crate::config::TasksConfig;
+
+/// Dice coefficient over character bigrams of the normalized titles: 1 for
+/// titles equal apart from case, spacing and punctuation, near 0 for
+/// unrelated ones
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_task_title(a), normalize_task_title(b));
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| -> Vec<(char, char)> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect()
+    };
+    let (a, b) = (bigrams(&a), bigrams(&b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let mut unmatched = b.clone();
+    let shared = a
+        .iter()
+        .filter(|pair| match unmatched.iter().position(|p| p == *pair) {
+            Some(i) => {
+                unmatched.swap_remove(i);
+                true
+            }
+            None => false,
+        })
+        .count();
+    2.0 * shared as f64 / (a.len() + b.len()) as f64
+}
+
+/// Whether `a` and `b` are the same task: close titles, and due dates that
+/// don't disagree (a missing date agrees with any)
+fn same_task(a: &Task, b: &Task, config: &TasksConfig) -> bool {
+    let dates_agree = match (a.due_date, b.due_date) {
+        (Some(x), Some(y)) => (x - y).abs() <= Duration::hours(config.due_tolerance_hours as i64),
+        _ => true,
+    };
+    dates_agree && title_similarity(&a.title, &b.title) >= config.title_similarity
+}
+
+/// Where a task sits in the fetched context
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Slot {
+    context: usize,
+    task: usize,
+}
+
+fn tasks_of(data: &ContextData) -> Option<&Vec<Task>> {
+    match &data.content {
+        ContextContent::Tasks(tasks) => Some(&tasks.tasks),
+        ContextContent::Notes(notes) => Some(&notes.pending_tasks),
+        _ => None,
+    }
+}
+
+fn tasks_of_mut(data: &mut ContextData) -> Option<&mut Vec<Task>> {
+    match &mut data.content {
+        ContextContent::Tasks(tasks) => Some(&mut tasks.tasks),
+        ContextContent::Notes(notes) => Some(&mut notes.pending_tasks),
+        _ => None,
+    }
+}
+
+/// Drop tasks that duplicate one from another, more preferred source.
+/// Duplicates within one source are its own business and stay. A kept task
+/// without a due date takes the date of a copy it replaced. Returns how many
+/// tasks were dropped.
+pub fn dedupe(context: &mut [ContextData], config: &TasksConfig, now: DateTime<Utc>) -> usize {
+    if !config.dedupe {
+        return 0;
+    }
+    let rank = |source: &str| {
+        config
+            .prefer
+            .iter()
+            .position(|p| p.eq_ignore_ascii_case(source))
+            .unwrap_or(config.prefer.len())
+    };
+
+    let mut slots: Vec<(Slot, &Task)> = context
+        .iter()
+        .enumerate()
+        .filter_map(|(c, data)| tasks_of(data).map(|tasks| (c, tasks)))
+        .flat_map(|(c, tasks)| {
+            tasks.iter().enumerate().map(move |(t, task)| {
+                (
+                    Slot {
+                        context: c,
+                        task: t,
+                    },
+                    task,
+                )
+            })
+        })
+        .collect();
+    // Stable: equally ranked sources keep fetch order
+    slots.sort_by_key(|(_, task)| rank(&task.source));
+
+    let mut kept: Vec<(Slot, &Task)> = Vec::new();
+    let mut dropped = HashSet::new();
+    let mut inherited_due = Vec::new();
+    for (slot, task) in slots {
+        let original = kept
+            .iter()
+            .find(|(_, k)| k.source != task.source && same_task(k, task, config));
+        match original {
+            Some((kept_slot, kept_task)) => {
+                dropped.insert(slot);
+                if kept_task.due_date.is_none() && task.due_date.is_some() {
+                    inherited_due.push((*kept_slot, task.due_date));
+                }
+            }
+            None => kept.push((slot, task)),
+        }
+    }
+    if dropped.is_empty() {
+        return 0;
+    }
+
+    for (slot, due) in inherited_due {
+        if let Some(task) = tasks_of_mut(&mut context[slot.context])
+            .and_then(|tasks| tasks.get_mut(slot.task))
+            .filter(|task| task.due_date.is_none())
+        {
+            task.due_date = due;
+        }
+    }
+    for (c, data) in context.iter_mut().enumerate() {
+        let Some(tasks) = tasks_of_mut(data) else {
+            continue;
+        };
+        let mut index = 0;
+        tasks.retain(|_| {
+            let keep = !dropped.contains(&Slot {
+                context: c,
+                task: index,
+            });
+            index += 1;
+            keep
+        });
+        if let ContextContent::Tasks(task_context) = &mut data.content {
+            *task_context = TaskContext::new(std::mem::take(&mut task_context.tasks), now);
+        }
+    }
+    dropped.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_sources::{ContextDataType, NotesContext, TaskStatus};
+    use std::collections::HashMap;
+
+    fn task(title: &str, source: &str, due: Option<DateTime<Utc>>) -> Task {
+        Task {
+            id: format!("{}:{}", source, title),
+            title: title.to_string(),
+            description: None,
+            due_date: due,
+            priority: 5,
+            status: TaskStatus::Pending,
+            tags: vec![],
+            source: source.to_string(),
+        }
+    }
+
+    fn data(source_id: &str, content: ContextContent) -> ContextData {
+        ContextData {
+            source_id: source_id.to_string(),
+            timestamp: Utc::now(),
+            data_type: ContextDataType::Tasks,
+            priority: 100,
+            content,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_duplicates_across_sources_keep_the_preferred_copy() {
+        let now = Utc::now();
+        let yesterday = Some(now - Duration::days(1));
+        let next_week = Some(now + Duration::days(7));
+        let todoist = vec![
+            task("Renew passport", "todoist", None),
+            task("Pay water bill", "todoist", yesterday),
+            task("Pay water bill", "todoist", yesterday),
+        ];
+        let vault = vec![
+            task("renew passport.", "obsidian", yesterday),
+            task("Pay the water bill", "obsidian", yesterday),
+            task("Pay water bill", "obsidian", next_week),
+            task("Book dentist", "obsidian", None),
+        ];
+        let mut context = vec![
+            data(
+                "obsidian",
+                ContextContent::Notes(NotesContext {
+                    daily_notes: vec![],
+                    active_projects: vec![],
+                    pending_tasks: vault,
+                    people: vec![],
+                }),
+            ),
+            data(
+                "tasks_todoist",
+                ContextContent::Tasks(TaskContext::new(todoist, now)),
+            ),
+        ];
+
+        let config = TasksConfig::default();
+        assert_eq!(dedupe(&mut context, &config, now), 2);
+
+        let ContextContent::Tasks(todoist) = &context[1].content else {
+            unreachable!()
+        };
+        // Same-source duplicates stay; the passport task took the vault's date
+        assert_eq!(todoist.tasks.len(), 3);
+        assert_eq!(todoist.tasks[0].due_date, yesterday);
+        assert_eq!(todoist.overdue_count, 3);
+        let vault: Vec<&str> = tasks_of(&context[0])
+            .unwrap()
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        // A week apart is a different bill
+        assert_eq!(vault, ["Pay water bill", "Book dentist"]);
+
+        // Preferring the vault flips which copy survives
+        let mut flipped = context.clone();
+        tasks_of_mut(&mut flipped[0])
+            .unwrap()
+            .push(task("Renew passport", "obsidian", None));
+        let prefer_vault = TasksConfig {
+            prefer: vec!["obsidian".to_string()],
+            ..TasksConfig::default()
+        };
+        assert_eq!(dedupe(&mut flipped, &prefer_vault, now), 1);
+        assert_eq!(tasks_of(&flipped[1]).unwrap().len(), 2);
+
+        assert!(title_similarity("Call plumber", "Walk the dog") < 0.3);
+    }
+}
@@ -10,6 +10,7 @@ use tracing::{info, warn};
 use super::{
     ContextContent, ContextData, ContextDataType, ContextSource, Task, TaskContext, TaskStatus,
 };
+use crate::http_utils;
 
 /// Tasks context source (placeholder implementation)
 pub struct TasksContextSource {
@@ -24,6 +25,8 @@ pub struct TasksContextSource {
 pub enum TaskSourceType {
     Todoist,
     LocalFile,
+    /// A todo.txt file (see [`super::todo_txt`])
+    TodoTxt,
 }
 
 /// Configuration for tasks
@@ -33,6 +36,8 @@ pub struct TasksConfig {
     pub file_path: Option<String>,
     pub sync_completed: bool,
     pub max_tasks: usize,
+    /// Where dates without a time (todo.txt `due:`) fall
+    pub timezone: chrono_tz::Tz,
 }
 
 /// Todoist API response structures
@@ -101,7 +106,7 @@ impl TasksContextSource {
     pub fn new(source_type: TaskSourceType, config: TasksConfig) -> Self {
         let enabled = match source_type {
             TaskSourceType::Todoist => config.api_key.is_some(),
-            TaskSourceType::LocalFile => config.file_path.is_some(),
+            TaskSourceType::LocalFile | TaskSourceType::TodoTxt => config.file_path.is_some(),
         };
 
         Self {
@@ -134,9 +139,33 @@ impl TasksContextSource {
         match self.source_type {
             TaskSourceType::Todoist => self.fetch_todoist_tasks().await,
             TaskSourceType::LocalFile => self.fetch_local_tasks().await,
+            TaskSourceType::TodoTxt => self.fetch_todo_txt_tasks().await,
         }
     }
 
+    /// Fetch tasks from a todo.txt file; done tasks only with `sync_completed`
+    async fn fetch_todo_txt_tasks(&self) -> Result<Vec<Task>> {
+        let file_path = self
+            .config
+            .file_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("todo.txt path not configured"))?;
+
+        if !Path::new(file_path).exists() {
+            warn!("todo.txt file does not exist: {}", file_path);
+            return Ok(vec![]);
+        }
+
+        let content = fs::read_to_string(file_path).await?;
+        let tasks: Vec<Task> = super::todo_txt::parse(&content, self.config.timezone)
+            .into_iter()
+            .filter(|t| self.config.sync_completed || !matches!(t.status, TaskStatus::Completed))
+            .take(self.config.max_tasks)
+            .collect();
+        info!("Parsed {} tasks from {}", tasks.len(), file_path);
+        Ok(tasks)
+    }
+
     /// Fetch tasks from Todoist API
     async fn fetch_todoist_tasks(&self) -> Result<Vec<Task>> {
         let api_key = self
@@ -148,12 +177,12 @@ impl TasksContextSource {
         info!("Fetching tasks from Todoist API");
 
         // First, get projects for context
-        let projects_response = self
-            .client
-            .get("https://api.todoist.com/rest/v2/projects")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .send()
-            .await?;
+        let projects_response = http_utils::send(
+            self.client
+                .get("https://api.todoist.com/rest/v2/projects")
+                .header("Authorization", format!("Bearer {}", api_key)),
+        )
+        .await?;
 
         if !projects_response.status().is_success() {
             return Err(anyhow!(
@@ -167,12 +196,12 @@ impl TasksContextSource {
             projects.into_iter().map(|p| (p.id, p.name)).collect();
 
         // Then get tasks
-        let tasks_response = self
-            .client
-            .get("https://api.todoist.com/rest/v2/tasks")
-            .header("Authorization", format!("Bearer {}", api_key))
-            .send()
-            .await?;
+        let tasks_response = http_utils::send(
+            self.client
+                .get("https://api.todoist.com/rest/v2/tasks")
+                .header("Authorization", format!("Bearer {}", api_key)),
+        )
+        .await?;
 
         if !tasks_response.status().is_success() {
             return Err(anyhow!(
@@ -379,12 +408,77 @@ impl TasksContextSource {
     }
 }
 
+/// Add `tasks` to the JSON task file at `path`, creating it if needed. A task
+/// already in the file (by id) is updated in place and keeps its creation
+/// time. Returns how many tasks were new. A markdown task file can't be
+/// merged into and is an error.
+pub fn add_to_local_file(path: &Path, tasks: &[Task], now: DateTime<Utc>) -> Result<usize> {
+    let mut file = match std::fs::read_to_string(path) {
+        Ok(content) if !content.trim().is_empty() => {
+            serde_json::from_str::<LocalTaskFile>(&content).map_err(|_| {
+                anyhow!(
+                    "{} isn't a JSON task file; point [tasks] local_file elsewhere",
+                    path.display()
+                )
+            })?
+        }
+        Ok(_) => LocalTaskFile {
+            tasks: Vec::new(),
+            last_updated: now,
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => LocalTaskFile {
+            tasks: Vec::new(),
+            last_updated: now,
+        },
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut added = 0;
+    for task in tasks {
+        let status = match task.status {
+            TaskStatus::Pending => "pending",
+            TaskStatus::InProgress => "in_progress",
+            TaskStatus::Completed => "completed",
+            TaskStatus::Cancelled => "cancelled",
+            TaskStatus::Blocked => "blocked",
+        };
+        let created_at = match file.tasks.iter().position(|t| t.id == task.id) {
+            Some(i) => file.tasks.remove(i).created_at,
+            None => {
+                added += 1;
+                now
+            }
+        };
+        file.tasks.push(LocalTask {
+            id: task.id.clone(),
+            title: task.title.clone(),
+            description: task.description.clone(),
+            due_date: task.due_date,
+            priority: task.priority,
+            status: status.to_string(),
+            tags: task.tags.clone(),
+            created_at,
+            updated_at: now,
+        });
+    }
+    file.last_updated = now;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(&file)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(added)
+}
+
 #[async_trait]
 impl ContextSource for TasksContextSource {
     fn source_id(&self) -> &str {
         match self.source_type {
             TaskSourceType::Todoist => "tasks_todoist",
             TaskSourceType::LocalFile => "tasks_local",
+            TaskSourceType::TodoTxt => "tasks_todotxt",
         }
     }
 
@@ -392,6 +486,7 @@ impl ContextSource for TasksContextSource {
         match self.source_type {
             TaskSourceType::Todoist => "Todoist Tasks",
             TaskSourceType::LocalFile => "Local Task File",
+            TaskSourceType::TodoTxt => "todo.txt",
         }
     }
 
@@ -404,33 +499,7 @@ impl ContextSource for TasksContextSource {
 
         let tasks = self.fetch_tasks(start, end).await?;
 
-        let overdue_count = tasks
-            .iter()
-            .filter(|t| {
-                if let Some(due_date) = t.due_date {
-                    due_date < Utc::now() && !matches!(t.status, TaskStatus::Completed)
-                } else {
-                    false
-                }
-            })
-            .count();
-
-        let upcoming_count = tasks
-            .iter()
-            .filter(|t| {
-                if let Some(due_date) = t.due_date {
-                    due_date > Utc::now() && due_date <= Utc::now() + chrono::Duration::days(7)
-                } else {
-                    false
-                }
-            })
-            .count();
-
-        let task_context = TaskContext {
-            tasks,
-            overdue_count,
-            upcoming_count,
-        };
+        let task_context = TaskContext::new(tasks, Utc::now());
 
         Ok(ContextData {
             source_id: self.source_id().to_string(),
@@ -453,7 +522,9 @@ impl ContextSource for TasksContextSource {
     fn required_config(&self) -> Vec<String> {
         match self.source_type {
             TaskSourceType::Todoist => vec!["api_key".to_string()],
-            TaskSourceType::LocalFile => vec!["file_path".to_string()],
+            TaskSourceType::LocalFile | TaskSourceType::TodoTxt => {
+                vec!["file_path".to_string()]
+            }
         }
     }
 }
@@ -0,0 +1,178 @@
+//! The todo.txt format (<https://github.com/todotxt/todo.txt>).
+//!
+//! Each line is a task: "x " marks it done, "(A)" is its priority, `due:`
+//! its due date, and `+project`/`@context` words become tags. A task's ID is
+//! a hash of its line, so it holds until the line is edited; checking a task
+//! off finds the line by that hash and rewrites the file through a temp file,
+//! so a reader never sees half of it.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use std::path::Path;
+
+use super::{Task, TaskStatus};
+
+/// Prefix of the IDs of tasks read from a todo.txt file
+pub const ID_PREFIX: &str = "todotxt:";
+
+/// The ID of the task on `line`
+fn line_id(line: &str) -> String {
+    format!("{}{:x}", ID_PREFIX, md5::compute(line.trim()))
+}
+
+/// Tasks in `text`, with due dates at the end of the day in `tz`
+pub fn parse(text: &str, tz: Tz) -> Vec<Task> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(|line| {
+            let (done, rest) = match line.strip_prefix("x ") {
+                Some(rest) => (true, rest),
+                None => (false, line),
+            };
+            let mut words: Vec<&str> = rest.split_whitespace().collect();
+            let priority = match words.first().map(|w| w.as_bytes()) {
+                Some([b'(', p @ b'A'..=b'Z', b')']) => {
+                    let p = *p;
+                    words.remove(0);
+                    10 - 2 * (p - b'A').min(3) as i32
+                }
+                _ => 5,
+            };
+            // Completion and creation dates
+            while words
+                .first()
+                .is_some_and(|w| NaiveDate::parse_from_str(w, "%Y-%m-%d").is_ok())
+            {
+                words.remove(0);
+            }
+            let due = words
+                .iter()
+                .find_map(|w| w.strip_prefix("due:"))
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                .and_then(|d| {
+                    tz.from_local_datetime(&d.and_hms_opt(23, 59, 59)?)
+                        .earliest()
+                })
+                .map(|d| d.to_utc());
+            // Other key:value tags (pri:, t:, rec:) aren't part of the title
+            words.retain(|w| !w.contains(':') || w.contains("://"));
+            let mut tags = Vec::new();
+            let title = words
+                .into_iter()
+                .filter(|w| match w.strip_prefix(['+', '@']) {
+                    Some(tag) if !tag.is_empty() => {
+                        tags.push(tag.to_string());
+                        false
+                    }
+                    _ => true,
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            Task {
+                id: line_id(line),
+                title,
+                description: None,
+                due_date: due,
+                priority,
+                status: if done {
+                    TaskStatus::Completed
+                } else {
+                    TaskStatus::Pending
+                },
+                tags,
+                source: "todotxt".to_string(),
+            }
+        })
+        .filter(|t| !t.title.is_empty())
+        .collect()
+}
+
+/// `content` with the open task `task_id` marked done on `today`, or None
+/// when no open line has that ID. As todo.txt clients do, the priority moves
+/// to a `pri:` tag.
+pub fn complete(content: &str, task_id: &str, today: NaiveDate) -> Option<String> {
+    let mut found = false;
+    let lines: Vec<String> = content
+        .split_inclusive('\n')
+        .map(|line| {
+            let text = line.trim_end_matches(['\r', '\n']);
+            if found || text.trim().is_empty() || text.starts_with("x ") || line_id(text) != task_id
+            {
+                return line.to_string();
+            }
+            found = true;
+            let ending = &line[text.len()..];
+            let done = match text.as_bytes() {
+                [b'(', p @ b'A'..=b'Z', b')', b' ', ..] => {
+                    format!("{} pri:{}", text[4..].trim_start(), *p as char)
+                }
+                _ => text.trim_start().to_string(),
+            };
+            format!("x {} {}{}", today.format("%Y-%m-%d"), done, ending)
+        })
+        .collect();
+    found.then(|| lines.concat())
+}
+
+/// Mark the task `task_id` done in the file at `path`
+pub fn complete_in_file(path: &Path, task_id: &str, today: NaiveDate) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let updated = complete(&content, task_id, today)
+        .ok_or_else(|| anyhow!("No open task {} in {}", task_id, path.display()))?;
+    let tmp = path.with_extension("txt.tmp");
+    std::fs::write(&tmp, updated).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completing_rewrites_only_that_line() {
+        let tz: Tz = "America/Chicago".parse().unwrap();
+        let content = "(B) 2026-05-01 Call plumber +house @phone due:2026-05-14\r\n\
+                       x 2026-05-02 File taxes\r\n\
+                       Water plants t:2026-05-10\r\n";
+        let tasks = parse(content, tz);
+        assert_eq!(tasks.len(), 3);
+        assert_eq!(tasks[0].title, "Call plumber");
+        assert_eq!(tasks[0].priority, 8);
+        assert_eq!(tasks[0].tags, ["house", "phone"]);
+        assert_eq!(
+            tasks[0].due_date,
+            Some(
+                tz.with_ymd_and_hms(2026, 5, 14, 23, 59, 59)
+                    .unwrap()
+                    .to_utc()
+            )
+        );
+        assert!(matches!(tasks[1].status, TaskStatus::Completed));
+        assert_eq!(tasks[2].title, "Water plants");
+
+        let today = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let updated = complete(content, &tasks[0].id, today).unwrap();
+        assert_eq!(
+            updated,
+            "x 2026-05-12 2026-05-01 Call plumber +house @phone due:2026-05-14 pri:B\r\n\
+             x 2026-05-02 File taxes\r\n\
+             Water plants t:2026-05-10\r\n"
+        );
+        // Already done, or never there
+        assert_eq!(complete(&updated, &tasks[0].id, today), None);
+        assert_eq!(complete(content, &tasks[1].id, today), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("todo.txt");
+        std::fs::write(&path, content).unwrap();
+        complete_in_file(&path, &tasks[2].id, today).unwrap();
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .ends_with("x 2026-05-12 Water plants t:2026-05-10\r\n"));
+        assert!(complete_in_file(&path, &tasks[2].id, today).is_err());
+    }
+}
@@ -4,12 +4,16 @@ use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
+use super::environment::{AirQualityProvider, PollenProvider};
 use super::{
-    ContextContent, ContextData, ContextDataType, ContextSource, WeatherContext, WeatherForecast,
+    ContextContent, ContextData, ContextDataType, ContextSource, UnitSystem, WeatherContext,
+    WeatherForecast,
 };
+use crate::http_utils;
 
 // ── Google Weather API response types ──────────────────────────────────
 
@@ -120,9 +124,13 @@ pub struct WeatherContextSource {
     longitude: f64,
     enabled: bool,
     client: Client,
-    units: String,
+    units: UnitSystem,
     cache_duration_minutes: u32,
     cache: RwLock<Option<CachedWeather>>,
+    /// Serve a canned `WeatherContext` from this JSON file instead of calling the API
+    fixture_path: Option<PathBuf>,
+    air_quality: Option<AirQualityProvider>,
+    pollen: Option<PollenProvider>,
 }
 
 impl WeatherContextSource {
@@ -140,12 +148,66 @@ impl WeatherContextSource {
             longitude,
             enabled,
             client: Client::new(),
-            units,
+            units: UnitSystem::from_config(&units),
             cache_duration_minutes,
             cache: RwLock::new(None),
+            fixture_path: None,
+            air_quality: None,
+            pollen: None,
         }
     }
 
+    /// Add an air quality alert when the nearest station's AQI crosses a threshold
+    pub fn with_air_quality(mut self, provider: AirQualityProvider) -> Self {
+        self.air_quality = Some(provider);
+        self
+    }
+
+    /// Add pollen alerts for plant types at or above a threshold
+    pub fn with_pollen(mut self, provider: PollenProvider) -> Self {
+        self.pollen = Some(provider);
+        self
+    }
+
+    /// Air quality and pollen alerts; each provider is best-effort
+    async fn fetch_environment_alerts(&self) -> Vec<String> {
+        let air_quality = async {
+            match self.air_quality {
+                Some(ref provider) => provider
+                    .alerts(&self.client, self.latitude, self.longitude)
+                    .await
+                    .map_err(|e| warn!("Failed to fetch air quality (non-fatal): {}", e))
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        };
+        let pollen = async {
+            match self.pollen {
+                Some(ref provider) => provider
+                    .alerts(
+                        &self.client,
+                        &self.google_api_key,
+                        self.latitude,
+                        self.longitude,
+                    )
+                    .await
+                    .map_err(|e| warn!("Failed to fetch pollen (non-fatal): {}", e))
+                    .unwrap_or_default(),
+                None => Vec::new(),
+            }
+        };
+        let (mut alerts, pollen) = tokio::join!(air_quality, pollen);
+        alerts.extend(pollen);
+        alerts
+    }
+
+    /// Read weather from a JSON fixture (demo profiles, offline testing)
+    pub fn with_fixture(mut self, path: PathBuf) -> Self {
+        self.fixture_path = Some(path);
+        self.enabled = true;
+        self
+    }
+
     /// Fetch current conditions from Google Weather API
     async fn fetch_current_weather(&self) -> Result<CurrentConditionsResponse> {
         let units_system = self.google_units_system();
@@ -157,7 +219,7 @@ impl WeatherContextSource {
 
         debug!("Fetching current weather from Google Weather API");
 
-        let response = self.client.get(&url).send().await?;
+        let response = http_utils::send(self.client.get(&url)).await?;
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -182,7 +244,7 @@ impl WeatherContextSource {
 
         debug!("Fetching weather forecast from Google Weather API");
 
-        let response = self.client.get(&url).send().await?;
+        let response = http_utils::send(self.client.get(&url)).await?;
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -206,7 +268,7 @@ impl WeatherContextSource {
 
         debug!("Fetching weather alerts from Google Weather API");
 
-        let response = self.client.get(&url).send().await?;
+        let response = http_utils::send(self.client.get(&url)).await?;
         if !response.status().is_success() {
             // Alerts endpoint may 404 if no alerts — that's fine
             let status = response.status();
@@ -226,19 +288,15 @@ impl WeatherContextSource {
 
     /// Map config units to Google API unitsSystem parameter
     fn google_units_system(&self) -> &str {
-        match self.units.as_str() {
-            "imperial" => "IMPERIAL",
-            "metric" => "METRIC",
-            _ => "IMPERIAL",
+        match self.units {
+            UnitSystem::Imperial => "IMPERIAL",
+            UnitSystem::Metric => "METRIC",
         }
     }
 
     /// Unit suffix for temperature display
     fn temp_unit(&self) -> &str {
-        match self.units.as_str() {
-            "metric" => "°C",
-            _ => "°F",
-        }
+        self.units.temperature_symbol()
     }
 
     /// Convert Google forecast days to our WeatherForecast format
@@ -295,6 +353,14 @@ impl WeatherContextSource {
 
     /// Fetch and assemble all weather data with TTL-based caching
     async fn fetch_weather_data(&self) -> Result<WeatherContext> {
+        if let Some(ref path) = self.fixture_path {
+            let json = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| anyhow!("Failed to read weather fixture {:?}: {}", path, e))?;
+            return serde_json::from_str(&json)
+                .map_err(|e| anyhow!("Invalid weather fixture {:?}: {}", path, e));
+        }
+
         if !self.enabled {
             return Err(anyhow!(
                 "Weather API not enabled (no Google API key configured)"
@@ -317,10 +383,11 @@ impl WeatherContextSource {
         }
 
         // Fetch current conditions, forecast, and alerts in parallel
-        let (current_result, forecast_result, alerts_result) = tokio::join!(
+        let (current_result, forecast_result, alerts_result, environment_alerts) = tokio::join!(
             self.fetch_current_weather(),
             self.fetch_forecast(),
-            self.fetch_alerts()
+            self.fetch_alerts(),
+            self.fetch_environment_alerts()
         );
 
         let current = current_result?;
@@ -389,7 +456,7 @@ impl WeatherContextSource {
 
         // Also generate simple temperature-based alerts like the old implementation
         if let Some(temp_deg) = current.temperature.as_ref().and_then(|t| t.degrees) {
-            if self.units == "imperial" {
+            if self.units == UnitSystem::Imperial {
                 if temp_deg < 32.0 {
                     alerts.push("Freezing temperatures — dress warmly".to_string());
                 } else if temp_deg > 95.0 {
@@ -401,6 +468,7 @@ impl WeatherContextSource {
                 alerts.push("Extreme heat — stay hydrated".to_string());
             }
         }
+        alerts.extend(environment_alerts);
 
         info!("Weather data fetched from Google Weather API");
 
@@ -408,6 +476,7 @@ impl WeatherContextSource {
             current_conditions,
             forecast,
             alerts,
+            units: self.units,
         };
 
         // Cache the result
@@ -442,7 +511,14 @@ impl ContextSource for WeatherContextSource {
         _start: DateTime<Utc>,
         _end: DateTime<Utc>,
     ) -> Result<ContextData> {
-        info!("Fetching weather context from Google Weather API");
+        info!(
+            "Fetching weather context from {}",
+            if self.fixture_path.is_some() {
+                "fixture"
+            } else {
+                "Google Weather API"
+            }
+        );
 
         let weather_context = self.fetch_weather_data().await?;
 
@@ -456,7 +532,15 @@ impl ContextSource for WeatherContextSource {
                 let mut metadata = HashMap::new();
                 metadata.insert("latitude".to_string(), self.latitude.to_string());
                 metadata.insert("longitude".to_string(), self.longitude.to_string());
-                metadata.insert("source_type".to_string(), "google_weather".to_string());
+                metadata.insert(
+                    "source_type".to_string(),
+                    if self.fixture_path.is_some() {
+                        "fixture"
+                    } else {
+                        "google_weather"
+                    }
+                    .to_string(),
+                );
                 metadata
             },
         })
@@ -0,0 +1,352 @@
+//! How Jasper connects the week, as a graph for `graph`.
+//!
+//! Nodes are the events in range, the people invited to them and the open
+//! tasks from the latest context snapshot. Edges are the relationships
+//! stored between events (their confidence as stored; unscored ones count as
+//! [`UNSCORED_CONFIDENCE`], and ones the user rejected are left out), plus the
+//! links Jasper draws itself: who attends what, and which tasks look like
+//! they're about an event or name a person (the same title matching that
+//! raises a task's urgency). The graph prints as Graphviz DOT or JSON.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::database::{Event, EventRelationship};
+use crate::errors::JasperResult;
+use crate::insight_detail;
+use crate::people;
+use crate::significance_engine::{ContextSnapshot, TaskSummary};
+use crate::snapshot_codec;
+use crate::storage::StorageBackend;
+use crate::task_urgency;
+
+/// Confidence of a stored relationship that was never scored
+pub const UNSCORED_CONFIDENCE: f64 = 0.5;
+
+/// Confidence of a link found by matching titles and names
+const MATCHED_CONFIDENCE: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    Event,
+    Task,
+    Person,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    /// "event:<row id>", "task:<task id>" or "person:<email or name>"
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    /// When the event starts or the task is due
+    pub at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    /// A stored relationship type, or "attends" or "mentions"
+    pub relation: String,
+    /// 0.0-1.0
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+fn event_node(event: &Event) -> Node {
+    Node {
+        id: format!("event:{}", event.id),
+        kind: NodeKind::Event,
+        label: event
+            .title
+            .clone()
+            .unwrap_or_else(|| "(untitled)".to_string()),
+        at: DateTime::from_timestamp(event.start_time, 0),
+    }
+}
+
+/// The graph of `events`, the `relationships` stored between them and the
+/// open `tasks`
+pub fn build(
+    events: &[Event],
+    relationships: &[EventRelationship],
+    tasks: &[TaskSummary],
+) -> Graph {
+    let mut graph = Graph::default();
+    // People by node id, so someone invited to several events is one node
+    let mut persons: BTreeMap<String, Node> = BTreeMap::new();
+
+    for event in events {
+        let node = event_node(event);
+        for attendee in event
+            .participants
+            .as_deref()
+            .map(people::parse_participants)
+            .unwrap_or_default()
+        {
+            let key = attendee
+                .email
+                .clone()
+                .unwrap_or_else(|| attendee.label())
+                .to_lowercase();
+            let person = persons
+                .entry(format!("person:{}", key))
+                .or_insert_with(|| Node {
+                    id: format!("person:{}", key),
+                    kind: NodeKind::Person,
+                    label: attendee.label(),
+                    at: None,
+                });
+            graph.edges.push(Edge {
+                from: person.id.clone(),
+                to: node.id.clone(),
+                relation: "attends".to_string(),
+                confidence: 1.0,
+            });
+        }
+        graph.nodes.push(node);
+    }
+
+    let in_graph = |id: i64| events.iter().any(|e| e.id == id);
+    for relationship in relationships {
+        if relationship.user_confirmed == Some(false)
+            || !in_graph(relationship.event1_id)
+            || !in_graph(relationship.event2_id)
+        {
+            continue;
+        }
+        graph.edges.push(Edge {
+            from: format!("event:{}", relationship.event1_id),
+            to: format!("event:{}", relationship.event2_id),
+            relation: relationship.relationship_type.clone(),
+            confidence: relationship
+                .confidence
+                .unwrap_or(UNSCORED_CONFIDENCE)
+                .clamp(0.0, 1.0),
+        });
+    }
+
+    for task in tasks.iter().filter(|t| !t.completed) {
+        let id = format!("task:{}", task.id);
+        let mut linked = false;
+        for event in events {
+            let title = event.title.as_deref().unwrap_or_default();
+            if task_urgency::related_titles(&task.title, title) {
+                graph.edges.push(Edge {
+                    from: id.clone(),
+                    to: format!("event:{}", event.id),
+                    relation: "mentions".to_string(),
+                    confidence: MATCHED_CONFIDENCE,
+                });
+                linked = true;
+            }
+        }
+        for person in persons.values() {
+            if insight_detail::mentions(&task.title, &person.label) {
+                graph.edges.push(Edge {
+                    from: id.clone(),
+                    to: person.id.clone(),
+                    relation: "mentions".to_string(),
+                    confidence: MATCHED_CONFIDENCE,
+                });
+                linked = true;
+            }
+        }
+        // A task connected to nothing would only be clutter
+        if linked {
+            graph.nodes.push(Node {
+                id,
+                kind: NodeKind::Task,
+                label: task.title.clone(),
+                at: task.due,
+            });
+        }
+    }
+
+    graph.nodes.extend(persons.into_values());
+    graph
+}
+
+/// The graph for the events between `start` and `end`, with the tasks from
+/// the latest insight's context snapshot
+pub fn load(
+    db: &dyn StorageBackend,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> JasperResult<Graph> {
+    let events = db.get_events_in_range(start, end)?;
+    let relationships = db.get_event_relationships(start, end)?;
+    let tasks = match db.get_latest_insight()? {
+        Some(insight) => snapshot_codec::load_for_insight(db, insight.id)?
+            .and_then(|value| serde_json::from_value::<ContextSnapshot>(value).ok())
+            .map(|snapshot| snapshot.tasks)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    Ok(build(&events, &relationships, &tasks))
+}
+
+/// `text` as the inside of a DOT quoted string
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The graph in Graphviz DOT, with times in `tz`: events are boxes, tasks
+/// notes and people ellipses; fainter edges are less certain
+pub fn to_dot<Tz: TimeZone>(graph: &Graph, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut out =
+        String::from("graph jasper {\n  rankdir=LR;\n  node [fontname=\"sans-serif\"];\n");
+    for node in &graph.nodes {
+        let shape = match node.kind {
+            NodeKind::Event => "box",
+            NodeKind::Task => "note",
+            NodeKind::Person => "ellipse",
+        };
+        // Graphviz breaks the line at an escaped "\n"
+        let label = match node.at {
+            Some(at) => format!(
+                "{}\\n{}",
+                escape(&node.label),
+                at.with_timezone(tz).format("%a %-d %b %H:%M")
+            ),
+            None => escape(&node.label),
+        };
+        out.push_str(&format!(
+            "  \"{}\" [shape={}, label=\"{}\"];\n",
+            escape(&node.id),
+            shape,
+            label
+        ));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "  \"{}\" -- \"{}\" [label=\"{} {:.0}%\", penwidth={:.1}, color=\"#000000{:02x}\"];\n",
+            escape(&edge.from),
+            escape(&edge.to),
+            escape(&edge.relation),
+            edge.confidence * 100.0,
+            0.5 + 2.0 * edge.confidence,
+            (64.0 + 191.0 * edge.confidence) as u8
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(id: i64, title: &str, participants: Option<&str>) -> Event {
+        Event {
+            id,
+            source_id: format!("g{}", id),
+            calendar_id: 1,
+            title: Some(title.to_string()),
+            description: None,
+            start_time: 1_778_572_800 + id * 3600,
+            end_time: None,
+            location: None,
+            event_type: None,
+            participants: participants.map(str::to_string),
+            raw_data_json: None,
+            is_all_day: Some(false),
+            meeting_url: None,
+            links: None,
+            my_response: None,
+        }
+    }
+
+    fn task(id: &str, title: &str) -> TaskSummary {
+        TaskSummary {
+            id: id.to_string(),
+            title: title.to_string(),
+            due: None,
+            completed: false,
+            priority: 5,
+            urgency: 5,
+            source: "todoist".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_graph_links_events_people_and_tasks() {
+        let events = [
+            event(
+                1,
+                "Budget review",
+                Some(r#"[{"name":"Priya Shah","email":"Priya@example.com"}]"#),
+            ),
+            event(2, "Budget review prep", Some(r#"["priya@example.com"]"#)),
+            event(3, "Dentist", None),
+        ];
+        let relationships = [
+            EventRelationship {
+                event1_id: 2,
+                event2_id: 1,
+                relationship_type: "prepares_for".to_string(),
+                confidence: Some(0.9),
+                user_confirmed: None,
+            },
+            EventRelationship {
+                event1_id: 3,
+                event2_id: 1,
+                relationship_type: "conflicts".to_string(),
+                confidence: None,
+                user_confirmed: Some(false),
+            },
+        ];
+        let tasks = [
+            task("t1", "Send Priya the budget review numbers"),
+            task("t2", "Water plants"),
+        ];
+        let graph = build(&events, &relationships, &tasks);
+
+        // One Priya for both events, and no node for the unrelated task
+        let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "event:1",
+                "event:2",
+                "event:3",
+                "task:t1",
+                "person:priya@example.com"
+            ]
+        );
+        let edges: Vec<(&str, &str, &str)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.relation.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("person:priya@example.com", "event:1", "attends"),
+                ("person:priya@example.com", "event:2", "attends"),
+                ("event:2", "event:1", "prepares_for"),
+                ("task:t1", "event:1", "mentions"),
+                ("task:t1", "event:2", "mentions"),
+                ("task:t1", "person:priya@example.com", "mentions"),
+            ]
+        );
+
+        let dot = to_dot(&graph, &Utc);
+        assert!(dot.starts_with("graph jasper {\n"));
+        assert!(dot
+            .contains("  \"event:1\" [shape=box, label=\"Budget review\\nTue 12 May 09:00\"];\n"));
+        assert!(dot.contains("  \"event:2\" -- \"event:1\" [label=\"prepares_for 90%\""));
+    }
+}
@@ -0,0 +1,605 @@
+//! First-run imports from files people already have, as `import` does them.
+//!
+//! - An iCalendar dump (.ics) becomes a calendar of its own in the events
+//!   table, next to synced Google calendars. Importing into the same
+//!   calendar again replaces its events. Recurring events aren't expanded and
+//!   are skipped; sync the calendar for those.
+//! - A Todoist CSV export or a todo.txt file adds its open tasks to the local
+//!   task file (`tasks.local_file`, or `tasks.json` in the data directory),
+//!   which is read as the `tasks_local` source.
+//! - A vCard file (.vcf) becomes Obsidian people notes, with emails and
+//!   birthdays in the frontmatter, and the coming year's birthdays become
+//!   all-day events in a "Birthdays" calendar. Existing notes are left alone.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::context_sources::{todo_txt, Task, TaskStatus};
+use crate::database::Event;
+
+/// Calendar ID prefix for imported calendars, so they never clash with Google's
+pub const CALENDAR_PREFIX: &str = "import:";
+
+/// Calendar the birthdays from contacts go into
+pub const BIRTHDAYS_CALENDAR: &str = "Birthdays";
+
+/// Join folded lines (RFC 5545/6350: a line starting with a space or tab
+/// continues the one before)
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// A content line split into its upper-cased name, parameters and value
+struct Property {
+    name: String,
+    params: Vec<(String, String)>,
+    value: String,
+}
+
+impl Property {
+    fn parse(line: &str) -> Option<Self> {
+        // The value starts at the first colon outside a quoted parameter
+        let mut quoted = false;
+        let split = line.char_indices().find_map(|(i, c)| match c {
+            '"' => {
+                quoted = !quoted;
+                None
+            }
+            ':' if !quoted => Some(i),
+            _ => None,
+        })?;
+        let (head, value) = (&line[..split], &line[split + 1..]);
+        let mut parts = head.split(';');
+        // Drop vCard group prefixes ("item1.EMAIL")
+        let name = parts.next()?;
+        let name = name.rsplit('.').next().unwrap_or(name).to_uppercase();
+        let params = parts
+            .filter_map(|p| p.split_once('='))
+            .map(|(k, v)| (k.to_uppercase(), v.trim_matches('"').to_string()))
+            .collect();
+        Some(Self {
+            name,
+            params,
+            value: value.to_string(),
+        })
+    }
+
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// The value with text escapes undone
+    fn text(&self) -> String {
+        let mut text = String::with_capacity(self.value.len());
+        let mut chars = self.value.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some('n' | 'N')) => {
+                    chars.next();
+                    text.push('\n');
+                }
+                ('\\', Some(next @ (',' | ';' | '\\'))) => {
+                    chars.next();
+                    text.push(next);
+                }
+                _ => text.push(c),
+            }
+        }
+        text.trim().to_string()
+    }
+}
+
+/// A DTSTART/DTEND value as UTC, and whether it is a whole day. Dates and
+/// floating times are in `tz`, as are TZIDs chrono-tz doesn't know.
+fn parse_time(property: &Property, tz: Tz) -> Option<(DateTime<Utc>, bool)> {
+    let value = property.value.trim();
+    if property.param("VALUE") == Some("DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        let midnight = tz
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()?;
+        return Some((midnight.to_utc(), true));
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some((time.and_utc(), false));
+    }
+    let time = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    let zone = property
+        .param("TZID")
+        .and_then(|id| id.parse::<Tz>().ok())
+        .unwrap_or(tz);
+    Some((zone.from_local_datetime(&time).earliest()?.to_utc(), false))
+}
+
+/// Events parsed from an iCalendar file
+#[derive(Debug, Default)]
+pub struct IcsImport {
+    pub events: Vec<Event>,
+    /// Recurring events, which aren't imported
+    pub recurring_skipped: usize,
+}
+
+/// Parse the VEVENTs in `text`, with floating times and dates in `tz`.
+/// Events without a start are skipped.
+pub fn parse_ics(text: &str, tz: Tz) -> IcsImport {
+    let mut import = IcsImport::default();
+    let mut current: Option<Vec<Property>> = None;
+    for line in unfold(text) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+        match (
+            property.name.as_str(),
+            property.value.to_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VEVENT") => current = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                let Some(properties) = current.take() else {
+                    continue;
+                };
+                let get = |name: &str| properties.iter().find(|p| p.name == name);
+                if get("RRULE").is_some() {
+                    import.recurring_skipped += 1;
+                    continue;
+                }
+                let Some((start, all_day)) = get("DTSTART").and_then(|p| parse_time(p, tz)) else {
+                    continue;
+                };
+                let end = get("DTEND")
+                    .and_then(|p| parse_time(p, tz))
+                    .map(|(end, _)| end)
+                    .or(all_day.then(|| start + Duration::days(1)));
+                let title = get("SUMMARY").map(Property::text);
+                let uid = get("UID").map(Property::text).unwrap_or_else(|| {
+                    format!(
+                        "{:x}",
+                        md5::compute(format!("{:?}{}", title, start.timestamp()))
+                    )
+                });
+                import.events.push(Event {
+                    id: 0,
+                    source_id: format!("ics:{}", uid),
+                    calendar_id: 0,
+                    title,
+                    description: get("DESCRIPTION")
+                        .map(Property::text)
+                        .filter(|d| !d.is_empty()),
+                    start_time: start.timestamp(),
+                    end_time: end.map(|t| t.timestamp()),
+                    location: get("LOCATION")
+                        .map(Property::text)
+                        .filter(|l| !l.is_empty()),
+                    event_type: Some("imported".to_string()),
+                    participants: None,
+                    raw_data_json: None,
+                    is_all_day: Some(all_day),
+                    meeting_url: None,
+                    links: None,
+                    my_response: None,
+                });
+            }
+            _ => {
+                if let Some(properties) = current.as_mut() {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    import
+}
+
+/// A due date given as YYYY-MM-DD (end of that day, as for Todoist) or
+/// YYYY-MM-DD HH:MM in `tz`
+fn parse_due(value: &str, tz: Tz) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let local = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(23, 59, 59)
+        })?;
+    Some(tz.from_local_datetime(&local).earliest()?.to_utc())
+}
+
+/// Fields of one CSV record, with quoted fields unquoted
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let (mut record, mut field) = (Vec::new(), String::new());
+    let (mut quoted, mut chars) = (false, text.chars().peekable());
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => record.push(std::mem::take(&mut field)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+/// Tasks in a Todoist CSV export (one per TYPE=task row). The export's
+/// PRIORITY runs from 1 (p1, the highest) to 4; due dates Todoist wrote as
+/// plain phrases ("every day") are dropped.
+pub fn parse_todoist_csv(text: &str, tz: Tz) -> Result<Vec<Task>> {
+    let mut records = csv_records(text.trim_start_matches('\u{feff}')).into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or_else(|| anyhow!("Empty file"))?
+        .iter()
+        .map(|h| h.trim().to_uppercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let (Some(kind), Some(content)) = (column("TYPE"), column("CONTENT")) else {
+        return Err(anyhow!("Not a Todoist export: no TYPE and CONTENT columns"));
+    };
+    let (description, priority, date) = (column("DESCRIPTION"), column("PRIORITY"), column("DATE"));
+
+    let field = |record: &[String], i: Option<usize>| -> Option<String> {
+        i.and_then(|i| record.get(i))
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    Ok(records
+        .filter(|r| field(r, Some(kind)).as_deref() == Some("task"))
+        .filter_map(|r| {
+            let content = field(&r, Some(content))?;
+            let (title, tags) = split_tags(&content, '@');
+            Some(Task {
+                id: format!("todoist_import:{:x}", md5::compute(&content)),
+                title,
+                description: field(&r, description),
+                due_date: field(&r, date).and_then(|d| parse_due(&d, tz)),
+                priority: match field(&r, priority).as_deref() {
+                    Some("1") => 10,
+                    Some("2") => 8,
+                    Some("3") => 5,
+                    _ => 3,
+                },
+                status: TaskStatus::Pending,
+                tags,
+                source: "local".to_string(),
+            })
+        })
+        .collect())
+}
+
+/// `text` without words starting with one of `markers`, and those words
+/// (without the marker) as tags
+fn split_tags(text: &str, marker: char) -> (String, Vec<String>) {
+    let (tags, words): (Vec<&str>, Vec<&str>) = text
+        .split_whitespace()
+        .partition(|w| w.len() > 1 && w.starts_with(marker));
+    (
+        words.join(" "),
+        tags.iter().map(|t| t[1..].to_string()).collect(),
+    )
+}
+
+/// Tasks in todo.txt format (see [`todo_txt`]), as local tasks
+pub fn parse_todo_txt(text: &str, tz: Tz) -> Vec<Task> {
+    todo_txt::parse(text, tz)
+        .into_iter()
+        .map(|task| Task {
+            id: task.id.replacen(todo_txt::ID_PREFIX, "todotxt_import:", 1),
+            source: "local".to_string(),
+            ..task
+        })
+        .collect()
+}
+
+/// A birthday, with the year when the card gives one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Birthday {
+    pub year: Option<i32>,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Birthday {
+    /// Accepts 1990-04-12, 19900412, --04-12 and --0412
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let value = value.get(..10).filter(|v| v.len() == 10).unwrap_or(value);
+        if let Some(rest) = value.strip_prefix("--") {
+            let digits: String = rest.chars().filter(char::is_ascii_digit).collect();
+            let date = NaiveDate::parse_from_str(&format!("2000{}", digits), "%Y%m%d").ok()?;
+            return Some(Self {
+                year: None,
+                month: date.month(),
+                day: date.day(),
+            });
+        }
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .or_else(|_| NaiveDate::parse_from_str(value, "%Y%m%d"))
+            .ok()?;
+        Some(Self {
+            year: Some(date.year()),
+            month: date.month(),
+            day: date.day(),
+        })
+    }
+
+    /// The first birthday on or after `today` (Feb 29 falls on Feb 28 in other years)
+    pub fn next(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let on = |year| {
+            NaiveDate::from_ymd_opt(year, self.month, self.day)
+                .or_else(|| NaiveDate::from_ymd_opt(year, self.month, self.day - 1))
+        };
+        on(today.year())
+            .filter(|d| *d >= today)
+            .or_else(|| on(today.year() + 1))
+    }
+}
+
+/// A person from a vCard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Contact {
+    pub name: String,
+    pub emails: Vec<String>,
+    pub birthday: Option<Birthday>,
+}
+
+/// Contacts in a vCard file; cards without a name are skipped
+pub fn parse_vcf(text: &str) -> Vec<Contact> {
+    let mut contacts = Vec::new();
+    let mut current: Option<Vec<Property>> = None;
+    for line in unfold(text) {
+        let Some(property) = Property::parse(&line) else {
+            continue;
+        };
+        match (
+            property.name.as_str(),
+            property.value.to_uppercase().as_str(),
+        ) {
+            ("BEGIN", "VCARD") => current = Some(Vec::new()),
+            ("END", "VCARD") => {
+                let Some(properties) = current.take() else {
+                    continue;
+                };
+                let get = |name: &str| properties.iter().find(|p| p.name == name);
+                // FN, else "Given Family" from N
+                let name = get("FN")
+                    .map(Property::text)
+                    .filter(|n| !n.is_empty())
+                    .or_else(|| {
+                        let n = get("N")?.value.clone();
+                        let mut parts = n.split(';');
+                        let (family, given) = (parts.next()?, parts.next().unwrap_or(""));
+                        Some(format!("{} {}", given, family).trim().to_string())
+                    })
+                    .filter(|n| !n.is_empty());
+                let Some(name) = name else {
+                    continue;
+                };
+                contacts.push(Contact {
+                    name,
+                    emails: properties
+                        .iter()
+                        .filter(|p| p.name == "EMAIL")
+                        .map(|p| p.text().to_lowercase())
+                        .filter(|e| e.contains('@'))
+                        .collect(),
+                    birthday: get("BDAY").and_then(|p| Birthday::parse(&p.value)),
+                });
+            }
+            _ => {
+                if let Some(properties) = current.as_mut() {
+                    properties.push(property);
+                }
+            }
+        }
+    }
+    contacts
+}
+
+#[derive(Serialize)]
+struct PersonFrontmatter<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    emails: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    birthday: Option<String>,
+}
+
+/// A new people note for `contact`, in the form the Obsidian source reads
+pub fn person_note(contact: &Contact) -> Result<String> {
+    let birthday = contact.birthday.map(|b| match b.year {
+        Some(year) => format!("{:04}-{:02}-{:02}", year, b.month, b.day),
+        None => format!("--{:02}-{:02}", b.month, b.day),
+    });
+    let frontmatter = serde_yaml::to_string(&PersonFrontmatter {
+        name: &contact.name,
+        emails: &contact.emails,
+        birthday,
+    })?;
+    Ok(format!("---\n{}---\n# {}\n", frontmatter, contact.name))
+}
+
+/// Where `contact`'s note goes: the name, minus characters file systems or
+/// Obsidian links reject
+pub fn person_note_path(people_folder: &Path, contact: &Contact) -> PathBuf {
+    let file: String = contact
+        .name
+        .chars()
+        .filter(|c| {
+            !matches!(
+                c,
+                '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']'
+            )
+        })
+        .collect();
+    people_folder.join(format!("{}.md", file.trim()))
+}
+
+/// Write people notes for `contacts` into `people_folder`, never replacing an
+/// existing note. Returns the notes written.
+pub fn write_people_notes(people_folder: &Path, contacts: &[Contact]) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(people_folder)
+        .with_context(|| format!("Failed to create {}", people_folder.display()))?;
+    let mut written = Vec::new();
+    for contact in contacts {
+        let path = person_note_path(people_folder, contact);
+        if path.exists() {
+            continue;
+        }
+        std::fs::write(&path, person_note(contact)?)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// All-day events for each contact's next birthday after `today`, in `tz`
+pub fn birthday_events(contacts: &[Contact], today: NaiveDate, tz: Tz) -> Vec<Event> {
+    contacts
+        .iter()
+        .filter_map(|contact| {
+            let birthday = contact.birthday?;
+            let date = birthday.next(today)?;
+            let start = tz
+                .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+                .earliest()?
+                .to_utc();
+            let title = match birthday.year {
+                Some(year) => format!("{}'s birthday ({})", contact.name, date.year() - year),
+                None => format!("{}'s birthday", contact.name),
+            };
+            Some(Event {
+                id: 0,
+                source_id: format!(
+                    "vcf-birthday:{:x}:{}",
+                    md5::compute(&contact.name),
+                    date.year()
+                ),
+                calendar_id: 0,
+                title: Some(title),
+                description: None,
+                start_time: start.timestamp(),
+                end_time: Some((start + Duration::days(1)).timestamp()),
+                location: None,
+                event_type: Some("birthday".to_string()),
+                participants: None,
+                raw_data_json: None,
+                is_all_day: Some(true),
+                meeting_url: None,
+                links: None,
+                my_response: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_imports_parse_common_exports() {
+        let tz: Tz = "Europe/London".parse().unwrap();
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:abc@example.com\r\n\
+                   SUMMARY:Dentist\\, check-up\r\nDTSTART;TZID=America/New_York:20260512T090000\r\n\
+                   DTEND;TZID=America/New_York:20260512T100000\r\nLOCATION:Main St\r\n\
+                   DESCRIPTION:Bring the\r\n  forms\\nand card\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nSUMMARY:Holiday\r\nDTSTART;VALUE=DATE:20260525\r\nEND:VEVENT\r\n\
+                   BEGIN:VEVENT\r\nSUMMARY:Standup\r\nDTSTART:20260101T090000Z\r\nRRULE:FREQ=DAILY\r\n\
+                   END:VEVENT\r\nEND:VCALENDAR\r\n";
+        let import = parse_ics(ics, tz);
+        assert_eq!(import.recurring_skipped, 1);
+        let [dentist, holiday] = &import.events[..] else {
+            panic!("{:?}", import.events);
+        };
+        assert_eq!(dentist.source_id, "ics:abc@example.com");
+        assert_eq!(dentist.title.as_deref(), Some("Dentist, check-up"));
+        assert_eq!(
+            dentist.description.as_deref(),
+            Some("Bring the forms\nand card")
+        );
+        // 09:00 in New York is 13:00 UTC in May
+        assert_eq!(
+            dentist.start_time,
+            Utc.with_ymd_and_hms(2026, 5, 12, 13, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+        assert_eq!(holiday.is_all_day, Some(true));
+        assert_eq!(holiday.end_time, Some(holiday.start_time + 86_400));
+
+        let csv = "TYPE,CONTENT,DESCRIPTION,PRIORITY,INDENT,DATE\n\
+                   section,Errands,,,,\n\
+                   task,\"Buy milk, eggs @shop\",,1,1,2026-05-12\n\
+                   task,Water plants,\"Back, then front\",4,1,every day\n";
+        let tasks = parse_todoist_csv(csv, tz).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].title, "Buy milk, eggs");
+        assert_eq!(tasks[0].tags, ["shop"]);
+        assert_eq!(tasks[0].priority, 10);
+        assert!(tasks[0].due_date.is_some());
+        assert_eq!(tasks[1].description.as_deref(), Some("Back, then front"));
+        assert_eq!(tasks[1].due_date, None);
+
+        let todo = "(A) 2026-05-01 Call plumber +house @phone due:2026-05-14\n\
+                    x 2026-05-02 2026-04-30 File taxes\n\
+                    Read https://example.com/article\n";
+        let tasks = parse_todo_txt(todo, tz);
+        assert_eq!(tasks[0].title, "Call plumber");
+        assert_eq!(tasks[0].tags, ["house", "phone"]);
+        assert_eq!(tasks[0].priority, 10);
+        assert!(tasks[0].due_date.is_some());
+        assert_eq!(tasks[1].title, "File taxes");
+        assert!(matches!(tasks[1].status, TaskStatus::Completed));
+        assert_eq!(tasks[2].title, "Read https://example.com/article");
+
+        let vcf = "BEGIN:VCARD\nVERSION:3.0\nFN:Priya Shah\nitem1.EMAIL;TYPE=INTERNET:Priya@Example.com\n\
+                   BDAY:1992-02-29\nEND:VCARD\nBEGIN:VCARD\nN:Lee;Sam;;;\nBDAY:--0704\nEND:VCARD\n";
+        let contacts = parse_vcf(vcf);
+        assert_eq!(contacts[0].emails, ["priya@example.com"]);
+        assert_eq!(contacts[1].name, "Sam Lee");
+        assert_eq!(
+            person_note(&contacts[0]).unwrap(),
+            "---\nname: Priya Shah\nemails:\n- priya@example.com\nbirthday: 1992-02-29\n---\n# Priya Shah\n"
+        );
+
+        let today = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let events = birthday_events(&contacts, today, tz);
+        assert_eq!(
+            events[0].title.as_deref(),
+            Some("Priya Shah's birthday (35)")
+        );
+        // No Feb 29 in 2027
+        assert_eq!(
+            events[0].start_time,
+            tz.with_ymd_and_hms(2027, 2, 28, 0, 0, 0)
+                .unwrap()
+                .timestamp()
+        );
+        assert_eq!(events[1].title.as_deref(), Some("Sam Lee's birthday"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Erasing what Jasper has stored, as `wipe-data` does it.
+//!
+//! Stored data is split into classes that can be wiped on their own:
+//! calendar events, insights and what produced them, and the cache of
+//! context read from notes and task sources. `--all` takes those and
+//! everything else: usage and load history, reminders, registered
+//! frontends and the Google Calendar token, which can also be revoked with
+//! Google. Rows are deleted with SQLite's secure delete and the database is
+//! rebuilt afterwards, and files are overwritten before they are removed, so
+//! nothing wiped lingers in free pages. API keys in the configuration are
+//! the user's to remove and are only pointed out.
+
+use serde::Serialize;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A kind of stored data that can be wiped on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataClass {
+    /// Insights, digests, replies, the context snapshots behind them and the
+    /// trigger log
+    Insights,
+    /// Day summaries, cached AI responses and tasks read from sources
+    NotesCache,
+    /// Synced calendar events and the meeting log
+    Events,
+    /// Usage, weekly stats, reminders, calendars, frontends and the rest
+    Other,
+}
+
+impl DataClass {
+    /// Every class, in the order they are wiped (rows before what they refer to)
+    pub const ALL: [DataClass; 4] = [
+        DataClass::Insights,
+        DataClass::NotesCache,
+        DataClass::Events,
+        DataClass::Other,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DataClass::Insights => "insights",
+            DataClass::NotesCache => "notes cache",
+            DataClass::Events => "events",
+            DataClass::Other => "other history",
+        }
+    }
+}
+
+/// What a wipe removed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WipeReport {
+    /// Rows deleted per class
+    pub rows: Vec<(DataClass, usize)>,
+    /// Files overwritten and removed
+    pub files_removed: Vec<PathBuf>,
+    /// Services whose tokens were revoked with the provider
+    pub revoked: Vec<String>,
+    /// Things left for the user to do, or that went wrong
+    pub notes: Vec<String>,
+}
+
+/// Overwrite the file at `path` with zeros, flush it to disk and remove it.
+/// Returns false if there was no file. On copy-on-write and journaling file
+/// systems the old blocks may survive; the overwrite is best effort.
+pub fn shred(path: &Path) -> std::io::Result<bool> {
+    let len = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let zeros = [0u8; 4096];
+    let mut left = len;
+    while left > 0 {
+        let n = left.min(zeros.len() as u64) as usize;
+        file.write_all(&zeros[..n])?;
+        left -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+    std::fs::remove_file(path)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseInner;
+    use crate::storage::StorageBackend;
+
+    #[tokio::test]
+    async fn test_wipe_removes_only_the_chosen_classes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+        let insight_id = db.store_insight("📅", "Busy morning", None).unwrap();
+        db.store_digest(insight_id, "Three meetings").unwrap();
+        db.store_day_summary("2026-05-12", "{}").unwrap();
+        let now = chrono::Utc::now();
+        db.add_jasper_item("reminder", "Call mum", None, now, None)
+            .unwrap();
+
+        let rows = db
+            .wipe(&[DataClass::NotesCache, DataClass::Insights])
+            .unwrap();
+        assert_eq!(rows, [(DataClass::Insights, 2), (DataClass::NotesCache, 1)]);
+        assert!(db.get_latest_insight().unwrap().is_none());
+        assert!(db.get_latest_digest().unwrap().is_none());
+        assert!(db.get_day_summary("2026-05-12").unwrap().is_none());
+        assert_eq!(db.get_jasper_items().unwrap().len(), 1);
+
+        let token = dir.path().join("token.json");
+        std::fs::write(&token, "secret").unwrap();
+        assert!(shred(&token).unwrap());
+        assert!(!token.exists());
+        assert!(!shred(&token).unwrap());
+    }
+}
@@ -0,0 +1,3311 @@
+use crate::data_wipe::DataClass;
+use crate::deep_links;
+use crate::errors::{JasperError, JasperResult};
+use crate::storage::StorageBackend;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub type Database = Arc<dyn StorageBackend>;
+
+/// Read-only connections kept alongside the writer. WAL mode lets these
+/// run concurrently with each other and with an in-flight write.
+const READER_POOL_SIZE: usize = 4;
+
+/// Database path meaning "ephemeral": a throwaway store, removed on drop
+pub const MEMORY_PATH: &str = ":memory:";
+
+/// Days of trigger decisions kept in `trigger_audit`
+const TRIGGER_AUDIT_RETENTION_DAYS: i64 = 14;
+
+/// Days of insight signatures kept in `insight_memory` for the prompt
+const INSIGHT_MEMORY_RETENTION_DAYS: i64 = 30;
+
+/// Days of meeting and task history kept for `weekly_stats`
+const LOAD_LOG_RETENTION_DAYS: i64 = 190;
+
+/// Days of per-call token usage kept in `api_usage`
+const API_USAGE_RETENTION_DAYS: i64 = 90;
+
+/// Nightly maintenance runs kept in `maintenance_runs`
+const MAINTENANCE_RUNS_KEPT: i64 = 30;
+
+/// The SQLite storage backend
+pub struct DatabaseInner {
+    /// Sole connection used for writes and migrations
+    writer: Mutex<Connection>,
+    /// Read-only connections, handed out round-robin
+    readers: Vec<Mutex<Connection>>,
+    next_reader: AtomicUsize,
+    db_path: PathBuf,
+    /// SQLCipher key, applied to every connection before anything else
+    key: Option<String>,
+    /// Directory of an ephemeral database, deleted with it
+    ephemeral_dir: Option<PathBuf>,
+}
+
+impl Drop for DatabaseInner {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.ephemeral_dir {
+            if let Err(e) = std::fs::remove_dir_all(dir) {
+                warn!("Failed to remove ephemeral database {:?}: {}", dir, e);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: i64,
+    pub source_id: String,
+    pub calendar_id: i64,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub start_time: i64,
+    pub end_time: Option<i64>,
+    pub location: Option<String>,
+    pub event_type: Option<String>,
+    pub participants: Option<String>, // JSON
+    pub raw_data_json: Option<String>,
+    pub is_all_day: Option<bool>,
+    /// Video call to join, found during sync (see `meeting_links`)
+    pub meeting_url: Option<String>,
+    /// Attachments and description links as JSON (see `attachments`)
+    pub links: Option<String>,
+    /// The user's own answer when invited, e.g. "needsAction" or "accepted"
+    pub my_response: Option<String>,
+}
+
+impl Event {
+    /// Hash of the fields a user would notice changing, used to skip no-op updates during sync
+    pub fn content_hash(&self) -> String {
+        let fields = [
+            self.title.clone().unwrap_or_default(),
+            self.description.clone().unwrap_or_default(),
+            self.start_time.to_string(),
+            self.end_time.map(|t| t.to_string()).unwrap_or_default(),
+            self.location.clone().unwrap_or_default(),
+            self.event_type.clone().unwrap_or_default(),
+            self.participants.clone().unwrap_or_default(),
+            self.is_all_day.unwrap_or(false).to_string(),
+        ];
+        let mut joined = fields.join("\u{1f}");
+        // Only when set, so events without a call keep the hash they had
+        if let Some(ref url) = self.meeting_url {
+            joined.push('\u{1f}');
+            joined.push_str(url);
+        }
+        if let Some(ref links) = self.links {
+            joined.push('\u{1f}');
+            joined.push_str(links);
+        }
+        if let Some(ref response) = self.my_response {
+            joined.push('\u{1f}');
+            joined.push_str(response);
+        }
+        // The Google Calendar page is only kept in the raw data; hashing it
+        // rewrites events stored before it was kept
+        if let Some(link) = self
+            .raw_data_json
+            .as_deref()
+            .and_then(deep_links::event_link)
+        {
+            joined.push('\u{1f}');
+            joined.push_str(&link);
+        }
+        format!("{:x}", md5::compute(joined))
+    }
+}
+
+/// An event the user is invited to, for answering it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invitation {
+    pub source_id: String,
+    pub title: String,
+    pub start_time: i64,
+    /// Google Calendar ID of the calendar it's on
+    pub calendar_id: String,
+    /// The user's answer so far (see `invitations`)
+    pub response: String,
+}
+
+/// An event's video call, for joining it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeetingLink {
+    pub source_id: String,
+    pub title: String,
+    pub start_time: i64,
+    pub url: String,
+}
+
+/// A calendar's events in a time range, with the calendar's metadata
+#[derive(Debug, Clone)]
+pub struct CalendarEvents {
+    pub calendar_id: i64,
+    pub calendar_name: String,
+    pub access_role: Option<String>,
+    pub is_primary: bool,
+    /// e.g. "#9fe1e7": the Google Calendar color, else one inferred from the ID
+    pub color: Option<String>,
+    pub events: Vec<Event>,
+}
+
+/// What happened to an event during a calendar sync
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventChangeKind {
+    Added,
+    Updated {
+        previous_start: i64,
+        previous_location: Option<String>,
+    },
+    Removed,
+}
+
+/// Per-event change record produced by `sync_calendar_events`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventChange {
+    pub event_id: i64,
+    pub source_id: String,
+    pub title: String,
+    pub start_time: i64,
+    pub location: Option<String>,
+    pub kind: EventChangeKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Insight {
+    pub id: i64,
+    pub emoji: String,
+    pub insight: String,
+    pub context_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    /// Model that generated the insight (None for locally produced ones)
+    pub model: Option<String>,
+    /// Generated by a fallback model because the primary was unavailable
+    pub fallback: bool,
+}
+
+/// Longer morning summary generated alongside an insight
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Digest {
+    pub id: i64,
+    pub insight_id: Option<i64>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A stored link between two events, e.g. a prep meeting and the meeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRelationship {
+    pub event1_id: i64,
+    pub event2_id: i64,
+    pub relationship_type: String,
+    /// 0.0-1.0; None when it wasn't scored
+    pub confidence: Option<f64>,
+    /// Some(false) when the user rejected it
+    pub user_confirmed: Option<bool>,
+}
+
+/// Why an insight was (or was not) generated at a given moment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerAuditEntry {
+    pub id: i64,
+    /// "heartbeat:<phase>" or "context_change"
+    pub trigger: String,
+    /// "fired" or "skipped: <reason>"
+    pub decision: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Compact signature of a past insight, for "already told you" prompt memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsightMemory {
+    pub insight_id: i64,
+    pub emoji: String,
+    /// Space-separated content keywords
+    pub keywords: String,
+    /// First sentence of the insight
+    pub summary: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// How long a critical item has gone unaddressed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationRecord {
+    /// "task:<id>" or "conflict:<id>+<id>"
+    pub item_key: String,
+    pub label: String,
+    /// Consecutive insights the item has been present for
+    pub streak: u32,
+    /// Critical notification (and email) already sent
+    pub notified: bool,
+    /// Acknowledged with `dismiss`; no further escalation
+    pub dismissed: bool,
+    pub first_seen: DateTime<Utc>,
+}
+
+/// A meeting seen on the user's own calendar, kept after sync drops it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingLogEntry {
+    pub event_id: String,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+}
+
+/// A habit learned from the meeting log (see `schedule_patterns`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserPattern {
+    /// "usual_start" or "routine"
+    pub pattern_type: String,
+    /// The pattern as JSON
+    pub pattern_data: String,
+    /// Days it was seen on
+    pub occurrences: u32,
+    pub last_seen: DateTime<Utc>,
+    /// 0.0-1.0
+    pub confidence: f64,
+}
+
+/// A task with a due date, and when it was seen completed or removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskLogEntry {
+    pub task_id: String,
+    pub title: String,
+    pub due_time: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Urgency when last seen open (see `task_urgency`)
+    #[serde(default)]
+    pub urgency: i32,
+}
+
+/// Meeting load for one Monday-to-Sunday week (see `load_stats`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeekStats {
+    pub week_start: chrono::NaiveDate,
+    pub meetings: u32,
+    pub meeting_minutes: i64,
+    /// Most meetings in a row with no more than a 5 minute gap
+    pub longest_back_to_back: u32,
+    /// Meetings outside working hours or on a weekend
+    pub after_hours_meetings: u32,
+    pub tasks_due: u32,
+    /// Tasks due this week that have since been completed (or removed)
+    pub tasks_done: u32,
+}
+
+/// Token usage reported by the provider for one AI call
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiUsage {
+    pub model: String,
+    /// Uncached input tokens
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Input tokens written to the prompt cache
+    pub cache_creation_tokens: u64,
+    /// Input tokens served from the prompt cache
+    pub cache_read_tokens: u64,
+}
+
+/// One step of a nightly maintenance run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceTask {
+    /// "prune", "vacuum", "checkpoint", "caches" or "auth"
+    pub task: String,
+    pub ok: bool,
+    /// What it did, or why it failed
+    pub detail: String,
+}
+
+/// A nightly maintenance run, as shown by `health`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub tasks: Vec<MaintenanceTask>,
+}
+
+impl MaintenanceRun {
+    pub fn failures(&self) -> usize {
+        self.tasks.iter().filter(|t| !t.ok).count()
+    }
+}
+
+/// Totals over a period of `api_usage`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiUsageSummary {
+    pub calls: u64,
+    /// Calls that read at least part of the prompt from the cache
+    pub cache_hits: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_tokens: u64,
+    pub cache_read_tokens: u64,
+}
+
+impl ApiUsageSummary {
+    /// Share of calls that hit the prompt cache, None when there were no calls
+    pub fn cache_hit_rate(&self) -> Option<f32> {
+        (self.calls > 0).then(|| self.cache_hits as f32 / self.calls as f32)
+    }
+}
+
+/// A reminder or time block created through Jasper (not synced from a calendar)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JasperItem {
+    pub id: i64,
+    /// "reminder" or "block"
+    pub kind: String,
+    pub title: String,
+    pub notes: Option<String>,
+    pub start_time: DateTime<Utc>,
+    /// Set for time blocks; reminders are a single moment
+    pub end_time: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A context snapshot as stored: plain JSON (older rows), compressed, or a
+/// compressed patch against the keyframe `base_id`; see `snapshot_codec`
+#[derive(Debug, Clone)]
+pub struct StoredSnapshot {
+    pub id: i64,
+    pub encoding: String,
+    pub data: Vec<u8>,
+    pub base_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActiveFrontend {
+    pub id: String,
+    pub pid: Option<i32>,
+    /// Insight format requested at registration ("plain", "pango", "markdown", "html")
+    pub format: String,
+    /// "full", or "guest" for shared displays shown only family-safe insights
+    pub access: String,
+    /// Unique bus name the frontend registered from, when known
+    pub bus_name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub last_heartbeat: DateTime<Utc>,
+}
+
+impl DatabaseInner {
+    /// Open the database, encrypted with `key` when one is given.
+    ///
+    /// An existing plaintext database at `db_path` is converted to an
+    /// encrypted one first, so turning on encryption needs no manual step.
+    /// A `db_path` of [`MEMORY_PATH`] opens an ephemeral database instead.
+    pub async fn new(db_path: &Path, key: Option<String>) -> JasperResult<Arc<Self>> {
+        if key.is_some() && !cfg!(feature = "sqlcipher") {
+            return Err(JasperError::Config {
+                message: "A database key was given but this build lacks the 'sqlcipher' feature"
+                    .to_string(),
+            });
+        }
+
+        // The reader pool needs every connection to reach the same database,
+        // which separate `:memory:` connections can't; a private file in the
+        // temp dir behaves exactly like the real store and goes away on drop
+        let ephemeral_dir = (db_path.as_os_str() == MEMORY_PATH).then(|| {
+            std::env::temp_dir().join(format!("jasper-ephemeral-{}", uuid::Uuid::new_v4()))
+        });
+        let db_path = &match &ephemeral_dir {
+            Some(dir) => dir.join("jasper.db"),
+            None => db_path.to_path_buf(),
+        };
+
+        // Ensure data directory exists
+        if let Some(parent) = db_path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create data directory: {:?}", parent))?;
+        }
+
+        if let Some(ref key) = key {
+            Self::encrypt_plaintext_database(db_path, key)
+                .context("Failed to encrypt existing plaintext database")?;
+        }
+
+        let connection = Connection::open(db_path)
+            .with_context(|| format!("Failed to open database: {:?}", db_path))?;
+        Self::apply_key(&connection, key.as_deref())?;
+
+        // Configure connection for optimal performance and resilience
+        Self::configure_connection(&connection)
+            .context("Failed to configure initial database connection")?;
+
+        // Migrations run on the writer alone so readers always see the final schema
+        Self::run_migrations(&connection).context("Failed to run database migrations")?;
+
+        let readers = (0..READER_POOL_SIZE)
+            .map(|_| Self::open_reader(db_path, key.as_deref()).map(Mutex::new))
+            .collect::<JasperResult<Vec<_>>>()?;
+
+        let db = Arc::new(DatabaseInner {
+            writer: Mutex::new(connection),
+            readers,
+            next_reader: AtomicUsize::new(0),
+            db_path: db_path.clone(),
+            key,
+            ephemeral_dir,
+        });
+        info!("Database initialized at {:?}", db_path);
+
+        Ok(db)
+    }
+
+    /// Open an ephemeral database, for tests and throwaway runs
+    pub async fn ephemeral() -> JasperResult<Arc<Self>> {
+        Self::new(&PathBuf::from(MEMORY_PATH), None).await
+    }
+
+    /// Configure a SQLite connection with optimal settings for performance and resilience
+    fn configure_connection(connection: &Connection) -> JasperResult<()> {
+        connection
+            .execute_batch(
+                "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous = NORMAL;
+             PRAGMA cache_size = 1000000;
+             PRAGMA foreign_keys = ON;
+             PRAGMA temp_store = MEMORY;
+             PRAGMA busy_timeout = 30000;
+             PRAGMA wal_autocheckpoint = 1000;
+             PRAGMA mmap_size = 268435456;
+             PRAGMA optimize;",
+            )
+            .context("Failed to execute PRAGMA configuration statements")?;
+        Ok(())
+    }
+
+    /// Set the SQLCipher key; must be the first statement on a new connection
+    fn apply_key(connection: &Connection, key: Option<&str>) -> JasperResult<()> {
+        if let Some(key) = key {
+            connection
+                .pragma_update(None, "key", key)
+                .context("Failed to set database encryption key")?;
+        }
+        Ok(())
+    }
+
+    /// Re-create a plaintext database file as an encrypted one, in place.
+    ///
+    /// Does nothing if the file is missing or already encrypted (SQLCipher
+    /// files lack the plaintext "SQLite format 3" header).
+    fn encrypt_plaintext_database(db_path: &Path, key: &str) -> JasperResult<()> {
+        let mut header = [0u8; 16];
+        let is_plaintext = std::fs::File::open(db_path)
+            .and_then(|mut file| file.read_exact(&mut header))
+            .map(|_| &header == b"SQLite format 3\0")
+            .unwrap_or(false);
+        if !is_plaintext {
+            return Ok(());
+        }
+
+        info!("Encrypting existing plaintext database at {:?}", db_path);
+        let encrypted_path = db_path.with_extension("db.encrypting");
+        let _ = std::fs::remove_file(&encrypted_path);
+
+        {
+            let plain = Connection::open(db_path)?;
+            plain.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
+            plain.execute(
+                "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+                params![encrypted_path.to_string_lossy(), key],
+            )?;
+            plain.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+            plain.execute("DETACH DATABASE encrypted", [])?;
+        }
+
+        // The checkpoint emptied the WAL; drop it so it can't be replayed onto the new file
+        for suffix in ["-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", db_path.display(), suffix));
+        }
+        std::fs::rename(&encrypted_path, db_path)?;
+        info!("Database encrypted");
+        Ok(())
+    }
+
+    /// Open a read-only connection for the reader pool
+    fn open_reader(db_path: &PathBuf, key: Option<&str>) -> JasperResult<Connection> {
+        let connection = Connection::open_with_flags(
+            db_path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY
+                | OpenFlags::SQLITE_OPEN_NO_MUTEX
+                | OpenFlags::SQLITE_OPEN_URI,
+        )
+        .with_context(|| format!("Failed to open read-only connection: {:?}", db_path))?;
+        Self::apply_key(&connection, key)?;
+
+        // journal_mode is persistent and set by the writer; readers only need
+        // their own cache and timeout settings
+        connection
+            .execute_batch(
+                "PRAGMA cache_size = 10000;
+             PRAGMA foreign_keys = ON;
+             PRAGMA temp_store = MEMORY;
+             PRAGMA busy_timeout = 30000;
+             PRAGMA mmap_size = 268435456;",
+            )
+            .context("Failed to configure read-only connection")?;
+        Ok(connection)
+    }
+
+    /// Recover from database connection issues by reopening the connection
+    fn recover_connection(&self) -> JasperResult<()> {
+        warn!("Attempting to recover database connection");
+
+        let new_connection = Connection::open(&self.db_path)
+            .with_context(|| format!("Failed to reopen database: {:?}", self.db_path))?;
+        Self::apply_key(&new_connection, self.key.as_deref())?;
+
+        // Configure the connection with optimized settings for resilience
+        Self::configure_connection(&new_connection)
+            .context("Failed to configure recovered database connection")?;
+
+        let mut conn_guard = self.writer.lock();
+        *conn_guard = new_connection;
+
+        info!("Database connection recovered successfully");
+        Ok(())
+    }
+
+    /// Replace a broken reader with a fresh read-only connection
+    fn recover_reader(&self, index: usize) -> JasperResult<()> {
+        warn!("Attempting to recover read-only connection {}", index);
+        let new_connection = Self::open_reader(&self.db_path, self.key.as_deref())?;
+        *self.readers[index].lock() = new_connection;
+        Ok(())
+    }
+
+    /// Lock an idle reader if one is free, otherwise wait on the next in turn
+    fn acquire_reader(&self) -> (usize, parking_lot::MutexGuard<'_, Connection>) {
+        let start = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.readers.len() {
+            let index = (start + offset) % self.readers.len();
+            if let Some(guard) = self.readers[index].try_lock() {
+                return (index, guard);
+            }
+        }
+        let index = start % self.readers.len();
+        (index, self.readers[index].lock())
+    }
+
+    /// Execute a read-only query on the reader pool, retrying once on connection failure
+    fn with_reader_retry<F, R>(&self, operation: F) -> JasperResult<R>
+    where
+        F: Fn(&Connection) -> JasperResult<R> + Copy,
+    {
+        let index = {
+            let (index, conn) = self.acquire_reader();
+            match operation(&conn) {
+                Ok(result) => return Ok(result),
+                Err(e) if self.is_connection_error(&e) => {
+                    warn!("Read-only connection error detected: {}", e);
+                    index
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        self.recover_reader(index)?;
+
+        let conn = self.readers[index].lock();
+        operation(&conn)
+    }
+
+    /// Execute a database operation with automatic retry on connection failure
+    fn with_connection_retry<F, R>(&self, operation: F) -> JasperResult<R>
+    where
+        F: Fn(&Connection) -> JasperResult<R> + Copy,
+    {
+        // First attempt
+        {
+            let conn = self.writer.lock();
+            match operation(&conn) {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    // Check if this is a connection-related error
+                    if self.is_connection_error(&e) {
+                        warn!("Database connection error detected: {}", e);
+                        drop(conn); // Release the mutex before recovery
+                    } else {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+
+        // Attempt recovery
+        self.recover_connection()?;
+
+        // Retry the operation
+        let conn = self.writer.lock();
+        operation(&conn)
+    }
+
+    /// Check if an error indicates a connection issue
+    fn is_connection_error(&self, error: &JasperError) -> bool {
+        let error_msg = error.to_string().to_lowercase();
+
+        // SQLite connection-related errors
+        error_msg.contains("database is locked")
+            || error_msg.contains("disk i/o error")
+            || error_msg.contains("database disk image is malformed")
+            || error_msg.contains("not a database")
+            || error_msg.contains("database is busy")
+            || error_msg.contains("cannot open database")
+            || error_msg.contains("unable to open database file")
+            || error_msg.contains("sql logic error")
+            || error_msg.contains("database or disk is full")
+            || error_msg.contains("file is not a database")
+            || error_msg.contains("attempt to write a readonly database")
+    }
+
+    fn run_migrations(conn: &Connection) -> JasperResult<()> {
+        // Enable foreign keys
+        conn.execute("PRAGMA foreign_keys = ON", [])?;
+
+        // Create accounts table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS accounts (
+                id INTEGER PRIMARY KEY,
+                service_name TEXT NOT NULL UNIQUE,
+                user_identifier TEXT,
+                encrypted_refresh_token BLOB NOT NULL,
+                last_sync_timestamp INTEGER
+            )",
+            [],
+        )?;
+
+        // Create calendars table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS calendars (
+                id INTEGER PRIMARY KEY,
+                account_id INTEGER REFERENCES accounts(id),
+                calendar_id TEXT NOT NULL,
+                calendar_name TEXT NOT NULL,
+                calendar_type TEXT,
+                color TEXT,
+                metadata TEXT
+            )",
+            [],
+        )?;
+
+        // Create events table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY,
+                source_id TEXT NOT NULL,
+                calendar_id INTEGER REFERENCES calendars(id),
+                title TEXT,
+                description TEXT,
+                start_time INTEGER NOT NULL,
+                end_time INTEGER,
+                location TEXT,
+                event_type TEXT,
+                participants TEXT,
+                raw_data_json TEXT,
+                is_all_day INTEGER DEFAULT 0
+            )",
+            [],
+        )?;
+
+        // Add is_all_day column if it doesn't exist (for existing databases)
+        conn.execute(
+            "ALTER TABLE events ADD COLUMN is_all_day INTEGER DEFAULT 0",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Add content_hash column to events (change detection for upsert sync)
+        conn.execute("ALTER TABLE events ADD COLUMN content_hash TEXT", [])
+            .ok(); // Ignore error if column already exists
+
+        // Add meeting_url column to events (video call join link)
+        conn.execute("ALTER TABLE events ADD COLUMN meeting_url TEXT", [])
+            .ok(); // Ignore error if column already exists
+
+        // Add links column to events (attachments and description links, JSON)
+        conn.execute("ALTER TABLE events ADD COLUMN links TEXT", [])
+            .ok(); // Ignore error if column already exists
+
+        // Add my_response column to events (the user's answer to an invitation)
+        conn.execute("ALTER TABLE events ADD COLUMN my_response TEXT", [])
+            .ok(); // Ignore error if column already exists
+
+        // Add access_role column to calendars (owner, reader, writer, freeBusyReader)
+        conn.execute("ALTER TABLE calendars ADD COLUMN access_role TEXT", [])
+            .ok(); // Ignore error if column already exists
+
+        // Add is_primary column to calendars (distinguishes user's main calendar from other owned calendars)
+        conn.execute(
+            "ALTER TABLE calendars ADD COLUMN is_primary INTEGER DEFAULT 0",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Add Google Calendar list visibility to calendars
+        conn.execute(
+            "ALTER TABLE calendars ADD COLUMN hidden INTEGER DEFAULT 0",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+        conn.execute(
+            "ALTER TABLE calendars ADD COLUMN selected INTEGER DEFAULT 1",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Create indexes for events table to optimize time-based queries
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_start_time ON events(start_time)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_calendar_start_time ON events(calendar_id, start_time)",
+            [],
+        )?;
+
+        // Additional indexes for common query patterns identified by senior code review
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_time_range ON events(start_time, end_time)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_source_id ON events(source_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_end_time ON events(end_time)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_events_calendar_source_id ON events(calendar_id, source_id)",
+            [],
+        )?;
+
+        // Create tasks table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY,
+                source_id TEXT NOT NULL,
+                account_id INTEGER REFERENCES accounts(id),
+                title TEXT,
+                description TEXT,
+                due_date INTEGER,
+                priority INTEGER,
+                project TEXT,
+                tags TEXT,
+                completed BOOLEAN DEFAULT FALSE,
+                raw_data_json TEXT
+            )",
+            [],
+        )?;
+
+        // Indexes for tasks table to optimize common queries
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_due_date ON tasks(due_date)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_priority ON tasks(priority)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_account_id ON tasks(account_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_tasks_source_id ON tasks(source_id)",
+            [],
+        )?;
+
+        // Create event_relationships table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS event_relationships (
+                id INTEGER PRIMARY KEY,
+                event1_id INTEGER REFERENCES events(id),
+                event2_id INTEGER REFERENCES events(id),
+                relationship_type TEXT,
+                discovered_at INTEGER,
+                confidence_score REAL,
+                user_confirmed BOOLEAN DEFAULT NULL,
+                notes TEXT
+            )",
+            [],
+        )?;
+
+        // Indexes for event_relationships table
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_event_relationships_event1 ON event_relationships(event1_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_event_relationships_event2 ON event_relationships(event2_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_event_relationships_type ON event_relationships(relationship_type)",
+            [],
+        )?;
+
+        // Create user_patterns table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS user_patterns (
+                id INTEGER PRIMARY KEY,
+                pattern_type TEXT NOT NULL,
+                pattern_data TEXT NOT NULL,
+                occurrences INTEGER DEFAULT 1,
+                last_seen INTEGER,
+                confidence_score REAL
+            )",
+            [],
+        )?;
+
+        // Indexes for user_patterns table
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_user_patterns_type ON user_patterns(pattern_type)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_user_patterns_last_seen ON user_patterns(last_seen)",
+            [],
+        )?;
+
+        // Create insights table for the new simplified architecture
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS insights (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                emoji TEXT NOT NULL,
+                insight TEXT NOT NULL,
+                context_hash TEXT,
+                created_at INTEGER DEFAULT (strftime('%s', 'now')),
+                expires_at INTEGER,
+                is_active INTEGER DEFAULT 1
+            )",
+            [],
+        )?;
+
+        // Add model/fallback columns to insights (which model answered, and whether it was a fallback)
+        conn.execute("ALTER TABLE insights ADD COLUMN model TEXT", [])
+            .ok(); // Ignore error if column already exists
+        conn.execute(
+            "ALTER TABLE insights ADD COLUMN fallback INTEGER DEFAULT 0",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Indexes for insights table
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_insights_created_at ON insights(created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_insights_active ON insights(is_active)",
+            [],
+        )?;
+
+        // Create context_snapshots table to track what triggered each insight
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS context_snapshots (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                insight_id INTEGER REFERENCES insights(id) ON DELETE CASCADE,
+                source TEXT NOT NULL,
+                snapshot_json TEXT NOT NULL,
+                significance_score REAL,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Indexes for context_snapshots table
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_context_snapshots_insight_id ON context_snapshots(insight_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_context_snapshots_source ON context_snapshots(source)",
+            [],
+        )?;
+
+        // Compressed snapshots: encoding of `data`, and the keyframe a patch applies to
+        conn.execute(
+            "ALTER TABLE context_snapshots ADD COLUMN encoding TEXT DEFAULT 'json'",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+        conn.execute("ALTER TABLE context_snapshots ADD COLUMN data BLOB", [])
+            .ok(); // Ignore error if column already exists
+        conn.execute(
+            "ALTER TABLE context_snapshots ADD COLUMN base_id INTEGER",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Context sources each insight's prompt drew on, with how many items each gave
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS insight_sources (
+                insight_id INTEGER REFERENCES insights(id) ON DELETE CASCADE,
+                source TEXT NOT NULL,
+                items INTEGER NOT NULL,
+                PRIMARY KEY (insight_id, source)
+            )",
+            [],
+        )?;
+
+        // Things each insight mentions, as (label, URI) actions frontends can open
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS insight_actions (
+                insight_id INTEGER REFERENCES insights(id) ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                label TEXT NOT NULL,
+                uri TEXT NOT NULL,
+                PRIMARY KEY (insight_id, position)
+            )",
+            [],
+        )?;
+
+        // Inline replies to insight notifications, fed back into the next prompt
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS insight_replies (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                insight_id INTEGER REFERENCES insights(id) ON DELETE CASCADE,
+                reply TEXT NOT NULL,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Create digests table for the morning digest produced with the first insight
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS digests (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                insight_id INTEGER REFERENCES insights(id) ON DELETE SET NULL,
+                content TEXT NOT NULL,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_digests_created_at ON digests(created_at)",
+            [],
+        )?;
+
+        // Cache of raw AI responses keyed by a hash of the exact request sent
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ai_response_cache (
+                prompt_hash TEXT PRIMARY KEY,
+                response TEXT NOT NULL,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Trigger decisions (fired or skipped, and why), kept for a couple of weeks
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trigger_audit (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                trigger TEXT NOT NULL,
+                decision TEXT NOT NULL,
+                detail TEXT,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_trigger_audit_created_at ON trigger_audit(created_at)",
+            [],
+        )?;
+
+        // Signatures of past insights, kept for a month to avoid repeating topics
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS insight_memory (
+                insight_id INTEGER PRIMARY KEY REFERENCES insights(id) ON DELETE CASCADE,
+                emoji TEXT NOT NULL,
+                keywords TEXT NOT NULL,
+                summary TEXT NOT NULL,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Rolling summary of the current day (JSON), keyed by local date
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS day_summaries (
+                day TEXT PRIMARY KEY,
+                summary TEXT NOT NULL,
+                updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Token usage per AI call, including prompt cache writes and reads
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS api_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                model TEXT NOT NULL,
+                input_tokens INTEGER NOT NULL DEFAULT 0,
+                output_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_creation_tokens INTEGER NOT NULL DEFAULT 0,
+                cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Critical items and how many insights in a row they've survived
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS escalations (
+                item_key TEXT PRIMARY KEY,
+                label TEXT NOT NULL,
+                streak INTEGER NOT NULL DEFAULT 1,
+                notified INTEGER NOT NULL DEFAULT 0,
+                dismissed INTEGER NOT NULL DEFAULT 0,
+                first_seen INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Meeting and task history for weekly load stats (calendar sync forgets past events)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS meeting_log (
+                event_id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                start_time INTEGER NOT NULL,
+                end_time INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS task_log (
+                task_id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                due_time INTEGER NOT NULL,
+                closed_at INTEGER
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS weekly_stats (
+                week_start TEXT PRIMARY KEY,
+                meetings INTEGER NOT NULL,
+                meeting_minutes INTEGER NOT NULL,
+                longest_back_to_back INTEGER NOT NULL,
+                after_hours_meetings INTEGER NOT NULL,
+                tasks_due INTEGER NOT NULL,
+                tasks_done INTEGER NOT NULL,
+                updated_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "ALTER TABLE task_log ADD COLUMN urgency INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Reminders and time blocks created through Jasper, exported as .ics
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS jasper_items (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                title TEXT NOT NULL,
+                notes TEXT,
+                start_time INTEGER NOT NULL,
+                end_time INTEGER,
+                created_at INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Create active_frontends table to track which frontends are running
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS active_frontends (
+                id TEXT PRIMARY KEY,
+                pid INTEGER,
+                started_at INTEGER DEFAULT (strftime('%s', 'now')),
+                last_heartbeat INTEGER DEFAULT (strftime('%s', 'now'))
+            )",
+            [],
+        )?;
+
+        // Add format column to active_frontends (markup the frontend wants insights in)
+        conn.execute(
+            "ALTER TABLE active_frontends ADD COLUMN format TEXT DEFAULT 'plain'",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+
+        // Add access and bus_name columns to active_frontends (guest displays)
+        conn.execute(
+            "ALTER TABLE active_frontends ADD COLUMN access TEXT DEFAULT 'full'",
+            [],
+        )
+        .ok(); // Ignore error if column already exists
+        conn.execute("ALTER TABLE active_frontends ADD COLUMN bus_name TEXT", [])
+            .ok(); // Ignore error if column already exists
+
+        // Index for active_frontends table
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_active_frontends_heartbeat ON active_frontends(last_heartbeat)",
+            [],
+        )?;
+
+        // Nightly maintenance results, for `health`
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS maintenance_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at INTEGER NOT NULL,
+                finished_at INTEGER NOT NULL,
+                tasks_json TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Additional indexes for calendars table
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_calendars_account_id ON calendars(account_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_calendars_calendar_id ON calendars(calendar_id)",
+            [],
+        )?;
+
+        info!("Database migrations completed with comprehensive indexes");
+        Ok(())
+    }
+
+    /// Ensure Google account record exists
+    fn ensure_google_account(&self, conn: &rusqlite::Connection) -> JasperResult<i64> {
+        // Try to find existing Google account
+        let existing_id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM accounts WHERE service_name = 'google'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if let Some(id) = existing_id {
+            Ok(id)
+        } else {
+            // Create new Google account record
+            conn.execute(
+                "INSERT INTO accounts (service_name, user_identifier, encrypted_refresh_token, last_sync_timestamp)
+                 VALUES ('google', 'authenticated_user', 'stored_in_token_file', ?)",
+                params![chrono::Utc::now().timestamp()]
+            )?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+
+    /// Infer calendar color from ID patterns
+    fn infer_calendar_color(calendar_id: &str) -> &'static str {
+        match calendar_id {
+            "primary" => "#4285F4",                                          // Blue
+            id if id.contains("family") => "#0F9D58",                        // Green
+            id if id.contains("house") || id.contains("home") => "#F4B400",  // Yellow
+            id if id.contains("work") || id.contains("office") => "#DB4437", // Red
+            id if id.contains("holiday") => "#9C27B0",                       // Purple
+            _ => "#757575",                                                  // Grey for unknown
+        }
+    }
+
+    fn query_snapshot(
+        &self,
+        filter: &str,
+        args: &[&dyn rusqlite::ToSql],
+    ) -> JasperResult<Option<StoredSnapshot>> {
+        self.with_reader_retry(|conn| {
+            let snapshot = conn
+                .query_row(
+                    &format!(
+                        "SELECT id, COALESCE(encoding, 'json'), COALESCE(data, CAST(snapshot_json AS BLOB)), base_id
+                         FROM context_snapshots {}",
+                        filter
+                    ),
+                    args,
+                    |row| {
+                        Ok(StoredSnapshot {
+                            id: row.get(0)?,
+                            encoding: row.get(1)?,
+                            data: row.get(2)?,
+                            base_id: row.get(3)?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(snapshot)
+        })
+    }
+}
+
+/// Tables holding each class of data, each before any table it refers to
+fn wipe_tables(class: DataClass) -> &'static [&'static str] {
+    match class {
+        DataClass::Insights => &[
+            "insight_sources",
+            "insight_actions",
+            "insight_replies",
+            "insight_memory",
+            "digests",
+            "context_snapshots",
+            "trigger_audit",
+            "escalations",
+            "insights",
+        ],
+        DataClass::NotesCache => &["day_summaries", "ai_response_cache", "tasks", "task_log"],
+        DataClass::Events => &["event_relationships", "meeting_log", "events"],
+        DataClass::Other => &[
+            "api_usage",
+            "weekly_stats",
+            "user_patterns",
+            "jasper_items",
+            "active_frontends",
+            "maintenance_runs",
+            "calendars",
+            "accounts",
+        ],
+    }
+}
+
+impl StorageBackend for DatabaseInner {
+    fn is_ephemeral(&self) -> bool {
+        self.ephemeral_dir.is_some()
+    }
+
+    fn get_events_in_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<Event>> {
+        // Use pagination internally to limit memory usage
+        self.get_events_in_range_paginated(start, end, None, None)
+    }
+
+    fn get_events_in_range_paginated(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> JasperResult<Vec<Event>> {
+        self.with_reader_retry(|conn| {
+            let base_query =
+                "SELECT id, source_id, calendar_id, title, description, start_time, end_time, 
+                                    location, event_type, participants, raw_data_json, is_all_day, meeting_url,
+                                    links, my_response
+                             FROM events 
+                             WHERE (start_time >= ?1 OR end_time > ?1) AND start_time <= ?2
+                             ORDER BY start_time";
+
+            let query = match (limit, offset) {
+                (Some(limit), Some(offset)) => {
+                    format!("{} LIMIT {} OFFSET {}", base_query, limit, offset)
+                }
+                (Some(limit), None) => format!("{} LIMIT {}", base_query, limit),
+                (None, Some(offset)) => format!("{} OFFSET {}", base_query, offset),
+                (None, None) => {
+                    // Default limit to prevent excessive memory usage
+                    format!("{} LIMIT 10000", base_query)
+                }
+            };
+
+            let mut stmt = conn.prepare(&query)?;
+
+            let events = stmt
+                .query_map(params![start.timestamp(), end.timestamp()], |row| {
+                    Ok(Event {
+                        id: row.get(0)?,
+                        source_id: row.get(1)?,
+                        calendar_id: row.get(2)?,
+                        title: row.get(3)?,
+                        description: row.get(4)?,
+                        start_time: row.get(5)?,
+                        end_time: row.get(6)?,
+                        location: row.get(7)?,
+                        event_type: row.get(8)?,
+                        participants: row.get(9)?,
+                        raw_data_json: row.get(10)?,
+                        is_all_day: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+                        meeting_url: row.get(12)?,
+                        links: row.get(13)?,
+                        my_response: row.get(14)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(events)
+        })
+    }
+
+    fn get_events_by_calendar(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        calendar_ids: &[i64],
+        access_roles: &[&str],
+    ) -> JasperResult<Vec<CalendarEvents>> {
+        self.with_reader_retry(|conn| {
+            let mut sql = String::from(
+                "SELECT e.id, e.source_id, e.calendar_id, e.title, e.description, e.start_time, e.end_time,
+                        e.location, e.event_type, e.participants, e.raw_data_json, e.is_all_day,
+                        e.meeting_url, e.links, e.my_response, c.calendar_name, c.access_role,
+                        c.is_primary, c.color
+                 FROM events e
+                 LEFT JOIN calendars c ON e.calendar_id = c.id
+                 WHERE (e.start_time >= ? OR e.end_time > ?) AND e.start_time <= ?",
+            );
+            // Events already under way when the range starts count too
+            let mut values: Vec<rusqlite::types::Value> = vec![
+                start.timestamp().into(),
+                start.timestamp().into(),
+                end.timestamp().into(),
+            ];
+
+            if !calendar_ids.is_empty() {
+                sql.push_str(&format!(
+                    " AND e.calendar_id IN ({})",
+                    vec!["?"; calendar_ids.len()].join(", ")
+                ));
+                values.extend(calendar_ids.iter().map(|&id| id.into()));
+            }
+            if !access_roles.is_empty() {
+                sql.push_str(&format!(
+                    " AND c.access_role IN ({})",
+                    vec!["?"; access_roles.len()].join(", ")
+                ));
+                values.extend(access_roles.iter().map(|&role| role.to_string().into()));
+            }
+            // The cap drops the latest events, whichever calendar they're on
+            sql.push_str(" ORDER BY e.start_time LIMIT 10000");
+
+            let mut stmt = conn.prepare(&sql)?;
+            let mut rows = stmt.query(rusqlite::params_from_iter(values))?;
+
+            let mut groups: BTreeMap<i64, CalendarEvents> = BTreeMap::new();
+            while let Some(row) = rows.next()? {
+                let event = Event {
+                    id: row.get(0)?,
+                    source_id: row.get(1)?,
+                    calendar_id: row.get(2)?,
+                    title: row.get(3)?,
+                    description: row.get(4)?,
+                    start_time: row.get(5)?,
+                    end_time: row.get(6)?,
+                    location: row.get(7)?,
+                    event_type: row.get(8)?,
+                    participants: row.get(9)?,
+                    raw_data_json: row.get(10)?,
+                    is_all_day: row.get::<_, Option<i32>>(11)?.map(|v| v != 0),
+                    meeting_url: row.get(12)?,
+                    links: row.get(13)?,
+                    my_response: row.get(14)?,
+                };
+
+                match groups.get_mut(&event.calendar_id) {
+                    Some(group) => group.events.push(event),
+                    None => {
+                        let group = CalendarEvents {
+                            calendar_id: event.calendar_id,
+                        calendar_name: row
+                            .get::<_, Option<String>>(15)?
+                            .unwrap_or_else(|| "Unknown".to_string()),
+                        access_role: row.get(16)?,
+                        is_primary: row
+                            .get::<_, Option<i32>>(17)?
+                            .map(|v| v != 0)
+                            .unwrap_or(false),
+                        color: row.get(18)?,
+                        events: vec![event],
+                        };
+                        groups.insert(group.calendar_id, group);
+                    }
+                }
+            }
+
+            Ok(groups.into_values().collect())
+        })
+    }
+
+    fn get_event_relationships(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<EventRelationship>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT r.event1_id, r.event2_id, r.relationship_type, r.confidence_score, r.user_confirmed
+                 FROM event_relationships r
+                 JOIN events e1 ON e1.id = r.event1_id
+                 JOIN events e2 ON e2.id = r.event2_id
+                 WHERE e1.start_time >= ?1 AND e1.start_time <= ?2
+                   AND e2.start_time >= ?1 AND e2.start_time <= ?2
+                 ORDER BY r.id",
+            )?;
+            let relationships = stmt
+                .query_map(params![start.timestamp(), end.timestamp()], |row| {
+                    Ok(EventRelationship {
+                        event1_id: row.get(0)?,
+                        event2_id: row.get(1)?,
+                        relationship_type: row
+                            .get::<_, Option<String>>(2)?
+                            .unwrap_or_else(|| "related".to_string()),
+                        confidence: row.get(3)?,
+                        user_confirmed: row.get(4)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(relationships)
+        })
+    }
+
+    fn sync_calendar_events(
+        &self,
+        calendar_db_id: i64,
+        events: &[Event],
+    ) -> JasperResult<Vec<EventChange>> {
+        let mut conn = self.writer.lock();
+        let tx = conn.transaction()?;
+        let mut changes = Vec::new();
+
+        {
+            struct Stored {
+                id: i64,
+                title: Option<String>,
+                start_time: i64,
+                location: Option<String>,
+                content_hash: Option<String>,
+            }
+
+            // Existing rows for this calendar, keyed by source_id
+            let mut existing: HashMap<String, Stored> = HashMap::new();
+            {
+                let mut stmt = tx.prepare(
+                    "SELECT source_id, id, title, start_time, location, content_hash
+                     FROM events WHERE calendar_id = ?",
+                )?;
+                let rows = stmt.query_map(params![calendar_db_id], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        Stored {
+                            id: row.get(1)?,
+                            title: row.get(2)?,
+                            start_time: row.get(3)?,
+                            location: row.get(4)?,
+                            content_hash: row.get(5)?,
+                        },
+                    ))
+                })?;
+                for row in rows {
+                    let (source_id, stored) = row?;
+                    existing.insert(source_id, stored);
+                }
+            }
+
+            let mut insert_stmt = tx.prepare(
+                "INSERT INTO events (source_id, calendar_id, title, description, start_time, end_time,
+                                    location, event_type, participants, raw_data_json, is_all_day, content_hash, meeting_url,
+                                    links, my_response)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            let mut update_stmt = tx.prepare(
+                "UPDATE events SET title = ?, description = ?, start_time = ?, end_time = ?, location = ?,
+                                  event_type = ?, participants = ?, raw_data_json = ?, is_all_day = ?, content_hash = ?,
+                                  meeting_url = ?, links = ?, my_response = ?
+                 WHERE id = ?",
+            )?;
+
+            let mut seen = HashSet::new();
+            for event in events {
+                // Google can list the same instance twice; the first copy wins
+                if !seen.insert(event.source_id.as_str()) {
+                    continue;
+                }
+
+                let hash = event.content_hash();
+                let is_all_day = event.is_all_day.map(|v| if v { 1 } else { 0 });
+
+                match existing.remove(&event.source_id) {
+                    Some(stored) if stored.content_hash.as_deref() == Some(hash.as_str()) => {}
+                    Some(stored) => {
+                        update_stmt.execute(params![
+                            event.title,
+                            event.description,
+                            event.start_time,
+                            event.end_time,
+                            event.location,
+                            event.event_type,
+                            event.participants,
+                            event.raw_data_json,
+                            is_all_day,
+                            hash,
+                            event.meeting_url,
+                            event.links,
+                            event.my_response,
+                            stored.id,
+                        ])?;
+                        changes.push(EventChange {
+                            event_id: stored.id,
+                            source_id: event.source_id.clone(),
+                            title: event.title.clone().unwrap_or_default(),
+                            start_time: event.start_time,
+                            location: event.location.clone(),
+                            kind: EventChangeKind::Updated {
+                                previous_start: stored.start_time,
+                                previous_location: stored.location,
+                            },
+                        });
+                    }
+                    None => {
+                        insert_stmt.execute(params![
+                            event.source_id,
+                            calendar_db_id,
+                            event.title,
+                            event.description,
+                            event.start_time,
+                            event.end_time,
+                            event.location,
+                            event.event_type,
+                            event.participants,
+                            event.raw_data_json,
+                            is_all_day,
+                            hash,
+                            event.meeting_url,
+                            event.links,
+                            event.my_response,
+                        ])?;
+                        changes.push(EventChange {
+                            event_id: tx.last_insert_rowid(),
+                            source_id: event.source_id.clone(),
+                            title: event.title.clone().unwrap_or_default(),
+                            start_time: event.start_time,
+                            location: event.location.clone(),
+                            kind: EventChangeKind::Added,
+                        });
+                    }
+                }
+            }
+
+            // Whatever is left was not in the fetch; drop it along with its relationships
+            for (source_id, stored) in existing {
+                tx.execute(
+                    "DELETE FROM event_relationships WHERE event1_id = ?1 OR event2_id = ?1",
+                    params![stored.id],
+                )?;
+                tx.execute("DELETE FROM events WHERE id = ?", params![stored.id])?;
+                changes.push(EventChange {
+                    event_id: stored.id,
+                    source_id,
+                    title: stored.title.unwrap_or_default(),
+                    start_time: stored.start_time,
+                    location: stored.location,
+                    kind: EventChangeKind::Removed,
+                });
+            }
+        }
+
+        tx.commit()?;
+        Ok(changes)
+    }
+
+    fn create_or_update_calendar(
+        &self,
+        calendar_id: &str,
+        calendar_name: &str,
+        calendar_type: Option<&str>,
+        access_role: Option<&str>,
+        is_primary: bool,
+    ) -> JasperResult<i64> {
+        let conn = self.writer.lock();
+
+        // First, ensure we have an account record for Google Calendar
+        let account_id = self.ensure_google_account(&conn)?;
+
+        // Try to find existing calendar
+        let existing_id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM calendars WHERE calendar_id = ? AND account_id = ?",
+                params![calendar_id, account_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        if let Some(id) = existing_id {
+            // Update existing calendar
+            conn.execute(
+                "UPDATE calendars SET calendar_name = ?, calendar_type = ?, access_role = ?, is_primary = ? WHERE id = ?",
+                params![calendar_name, calendar_type, access_role, is_primary, id],
+            )?;
+            Ok(id)
+        } else {
+            // Create new calendar
+            conn.execute(
+                "INSERT INTO calendars (account_id, calendar_id, calendar_name, calendar_type, color, access_role, is_primary)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![account_id, calendar_id, calendar_name, calendar_type, Self::infer_calendar_color(calendar_id), access_role, is_primary]
+            )?;
+            Ok(conn.last_insert_rowid())
+        }
+    }
+
+    fn set_calendar_appearance(
+        &self,
+        calendar_db_id: i64,
+        color: Option<&str>,
+        hidden: bool,
+        selected: bool,
+    ) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "UPDATE calendars SET color = COALESCE(?, color), hidden = ?, selected = ? WHERE id = ?",
+                params![color, hidden, selected, calendar_db_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn store_insight(
+        &self,
+        emoji: &str,
+        insight: &str,
+        context_hash: Option<&str>,
+    ) -> JasperResult<i64> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT INTO insights (emoji, insight, context_hash) VALUES (?, ?, ?)",
+                params![emoji, insight, context_hash],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    fn store_insight_sources(
+        &self,
+        insight_id: i64,
+        sources: &BTreeMap<String, u32>,
+    ) -> JasperResult<()> {
+        if sources.is_empty() {
+            return Ok(());
+        }
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            for (source, items) in sources {
+                tx.execute(
+                    "INSERT OR REPLACE INTO insight_sources (insight_id, source, items) VALUES (?, ?, ?)",
+                    params![insight_id, source, items],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn get_insight_sources(&self, insight_id: i64) -> JasperResult<BTreeMap<String, u32>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt =
+                conn.prepare("SELECT source, items FROM insight_sources WHERE insight_id = ?")?;
+            let sources = stmt
+                .query_map(params![insight_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<BTreeMap<String, u32>, _>>()?;
+            Ok(sources)
+        })
+    }
+
+    fn store_insight_actions(
+        &self,
+        insight_id: i64,
+        actions: &[(String, String)],
+    ) -> JasperResult<()> {
+        if actions.is_empty() {
+            return Ok(());
+        }
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            for (position, (label, uri)) in actions.iter().enumerate() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO insight_actions (insight_id, position, label, uri) VALUES (?, ?, ?, ?)",
+                    params![insight_id, position as i64, label, uri],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn get_insight_actions(&self, insight_id: i64) -> JasperResult<Vec<(String, String)>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT label, uri FROM insight_actions WHERE insight_id = ? ORDER BY position",
+            )?;
+            let actions = stmt
+                .query_map(params![insight_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(actions)
+        })
+    }
+
+    fn store_insight_reply(&self, insight_id: i64, reply: &str) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT INTO insight_replies (insight_id, reply) VALUES (?, ?)",
+                params![insight_id, reply],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_insight_replies_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> JasperResult<Vec<(String, String, DateTime<Utc>)>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT i.insight, r.reply, r.created_at
+                 FROM insight_replies r JOIN insights i ON i.id = r.insight_id
+                 WHERE r.created_at >= ?
+                 ORDER BY r.created_at, r.id",
+            )?;
+            let replies = stmt
+                .query_map(params![since.timestamp()], |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        DateTime::from_timestamp(row.get::<_, i64>(2)?, 0).unwrap_or_default(),
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(replies)
+        })
+    }
+
+    fn get_event_raw_data(&self, source_id: &str) -> JasperResult<Option<String>> {
+        self.with_reader_retry(|conn| {
+            let raw = conn
+                .query_row(
+                    "SELECT raw_data_json FROM events WHERE source_id = ? AND raw_data_json IS NOT NULL LIMIT 1",
+                    params![source_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(raw)
+        })
+    }
+
+    fn get_invitations(&self, since: DateTime<Utc>) -> JasperResult<Vec<Invitation>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT e.source_id, e.title, e.start_time, c.calendar_id, e.my_response
+                 FROM events e
+                 JOIN calendars c ON e.calendar_id = c.id
+                 WHERE e.my_response IS NOT NULL AND e.start_time >= ?
+                 ORDER BY e.start_time
+                 LIMIT 1000",
+            )?;
+            let invitations = stmt
+                .query_map(params![since.timestamp()], |row| {
+                    Ok(Invitation {
+                        source_id: row.get(0)?,
+                        title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                        start_time: row.get(2)?,
+                        calendar_id: row.get(3)?,
+                        response: row.get(4)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(invitations)
+        })
+    }
+
+    fn set_event_response(&self, source_id: &str, response: &str) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "UPDATE events SET my_response = ? WHERE source_id = ?",
+                params![response, source_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_next_meeting(&self, now: DateTime<Utc>) -> JasperResult<Option<MeetingLink>> {
+        self.with_reader_retry(|conn| {
+            let meeting = conn
+                .query_row(
+                    "SELECT source_id, title, start_time, meeting_url FROM events
+                     WHERE meeting_url IS NOT NULL AND COALESCE(is_all_day, 0) = 0
+                       AND COALESCE(my_response, '') != 'declined'
+                       AND COALESCE(end_time, start_time + 3600) > ?
+                     ORDER BY start_time LIMIT 1",
+                    params![now.timestamp()],
+                    |row| {
+                        Ok(MeetingLink {
+                            source_id: row.get(0)?,
+                            title: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                            start_time: row.get(2)?,
+                            url: row.get(3)?,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(meeting)
+        })
+    }
+
+    fn set_insight_model(&self, insight_id: i64, model: &str, fallback: bool) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "UPDATE insights SET model = ?, fallback = ? WHERE id = ?",
+                params![model, fallback as i64, insight_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_latest_insight(&self) -> JasperResult<Option<Insight>> {
+        self.with_reader_retry(|conn| {
+            let insight = conn
+                .query_row(
+                    "SELECT id, emoji, insight, context_hash, created_at, expires_at, is_active, model, fallback
+                 FROM insights 
+                 WHERE is_active = 1 
+                 ORDER BY created_at DESC 
+                 LIMIT 1",
+                    [],
+                    |row| {
+                        Ok(Insight {
+                            id: row.get(0)?,
+                            emoji: row.get(1)?,
+                            insight: row.get(2)?,
+                            context_hash: row.get(3)?,
+                            created_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                                .unwrap_or_default(),
+                            expires_at: row
+                                .get::<_, Option<i64>>(5)?
+                                .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default()),
+                            is_active: row.get::<_, i64>(6)? != 0,
+                            model: row.get(7)?,
+                            fallback: row.get::<_, Option<i64>>(8)?.unwrap_or(0) != 0,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(insight)
+        })
+    }
+
+    fn get_insight_by_id(&self, insight_id: i64) -> JasperResult<Option<Insight>> {
+        self.with_reader_retry(|conn| {
+            let insight = conn
+                .query_row(
+                    "SELECT id, emoji, insight, context_hash, created_at, expires_at, is_active, model, fallback
+                 FROM insights 
+                 WHERE id = ?",
+                    params![insight_id],
+                    |row| {
+                        Ok(Insight {
+                            id: row.get(0)?,
+                            emoji: row.get(1)?,
+                            insight: row.get(2)?,
+                            context_hash: row.get(3)?,
+                            created_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                                .unwrap_or_default(),
+                            expires_at: row
+                                .get::<_, Option<i64>>(5)?
+                                .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default()),
+                            is_active: row.get::<_, i64>(6)? != 0,
+                            model: row.get(7)?,
+                            fallback: row.get::<_, Option<i64>>(8)?.unwrap_or(0) != 0,
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(insight)
+        })
+    }
+
+    fn get_recent_insights(&self, limit: u32) -> JasperResult<Vec<Insight>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, emoji, insight, context_hash, created_at, expires_at, is_active, model, fallback
+                 FROM insights
+                 ORDER BY created_at DESC
+                 LIMIT ?",
+            )?;
+            let insights = stmt
+                .query_map(params![limit], |row| {
+                    Ok(Insight {
+                        id: row.get(0)?,
+                        emoji: row.get(1)?,
+                        insight: row.get(2)?,
+                        context_hash: row.get(3)?,
+                        created_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                            .unwrap_or_default(),
+                        expires_at: row
+                            .get::<_, Option<i64>>(5)?
+                            .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default()),
+                        is_active: row.get::<_, i64>(6)? != 0,
+                        model: row.get(7)?,
+                        fallback: row.get::<_, Option<i64>>(8)?.unwrap_or(0) != 0,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(insights)
+        })
+    }
+
+    fn store_digest(&self, insight_id: i64, content: &str) -> JasperResult<i64> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT INTO digests (insight_id, content) VALUES (?, ?)",
+                params![insight_id, content],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    fn get_latest_digest(&self) -> JasperResult<Option<Digest>> {
+        self.with_reader_retry(|conn| {
+            let digest = conn
+                .query_row(
+                    "SELECT id, insight_id, content, created_at
+                 FROM digests
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT 1",
+                    [],
+                    |row| {
+                        Ok(Digest {
+                            id: row.get(0)?,
+                            insight_id: row.get(1)?,
+                            content: row.get(2)?,
+                            created_at: DateTime::from_timestamp(row.get::<_, i64>(3)?, 0)
+                                .unwrap_or_default(),
+                        })
+                    },
+                )
+                .optional()?;
+            Ok(digest)
+        })
+    }
+
+    fn get_cached_response(
+        &self,
+        prompt_hash: &str,
+        max_age: chrono::Duration,
+    ) -> JasperResult<Option<String>> {
+        let cutoff = (Utc::now() - max_age).timestamp();
+        self.with_reader_retry(|conn| {
+            let response = conn
+                .query_row(
+                    "SELECT response FROM ai_response_cache WHERE prompt_hash = ? AND created_at >= ?",
+                    params![prompt_hash, cutoff],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(response)
+        })
+    }
+
+    fn store_cached_response(
+        &self,
+        prompt_hash: &str,
+        response: &str,
+        max_age: chrono::Duration,
+    ) -> JasperResult<()> {
+        let cutoff = (Utc::now() - max_age).timestamp();
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "DELETE FROM ai_response_cache WHERE created_at < ?",
+                params![cutoff],
+            )?;
+            conn.execute(
+                "INSERT OR REPLACE INTO ai_response_cache (prompt_hash, response, created_at)
+                 VALUES (?, ?, strftime('%s', 'now'))",
+                params![prompt_hash, response],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn record_trigger(
+        &self,
+        trigger: &str,
+        decision: &str,
+        detail: Option<&str>,
+    ) -> JasperResult<i64> {
+        let cutoff =
+            (Utc::now() - chrono::Duration::days(TRIGGER_AUDIT_RETENTION_DAYS)).timestamp();
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "DELETE FROM trigger_audit WHERE created_at < ?",
+                params![cutoff],
+            )?;
+            conn.execute(
+                "INSERT INTO trigger_audit (trigger, decision, detail) VALUES (?, ?, ?)",
+                params![trigger, decision, detail],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    fn record_insight_memory(
+        &self,
+        insight_id: i64,
+        emoji: &str,
+        keywords: &str,
+        summary: &str,
+    ) -> JasperResult<()> {
+        let cutoff =
+            (Utc::now() - chrono::Duration::days(INSIGHT_MEMORY_RETENTION_DAYS)).timestamp();
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "DELETE FROM insight_memory WHERE created_at < ?",
+                params![cutoff],
+            )?;
+            conn.execute(
+                "INSERT OR REPLACE INTO insight_memory (insight_id, emoji, keywords, summary) VALUES (?, ?, ?, ?)",
+                params![insight_id, emoji, keywords, summary],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_day_summary(&self, day: &str) -> JasperResult<Option<String>> {
+        self.with_reader_retry(|conn| {
+            let summary = conn
+                .query_row(
+                    "SELECT summary FROM day_summaries WHERE day = ?",
+                    params![day],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(summary)
+        })
+    }
+
+    fn store_day_summary(&self, day: &str, summary: &str) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute("DELETE FROM day_summaries WHERE day <> ?", params![day])?;
+            conn.execute(
+                "INSERT OR REPLACE INTO day_summaries (day, summary, updated_at)
+                 VALUES (?, ?, strftime('%s', 'now'))",
+                params![day, summary],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_insight_memory(&self) -> JasperResult<Vec<InsightMemory>> {
+        let cutoff =
+            (Utc::now() - chrono::Duration::days(INSIGHT_MEMORY_RETENTION_DAYS)).timestamp();
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT insight_id, emoji, keywords, summary, created_at
+                 FROM insight_memory
+                 WHERE created_at >= ?
+                 ORDER BY created_at, insight_id",
+            )?;
+            let memories = stmt
+                .query_map(params![cutoff], |row| {
+                    Ok(InsightMemory {
+                        insight_id: row.get(0)?,
+                        emoji: row.get(1)?,
+                        keywords: row.get(2)?,
+                        summary: row.get(3)?,
+                        created_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                            .unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(memories)
+        })
+    }
+
+    fn advance_escalations(
+        &self,
+        items: &[(String, String)],
+    ) -> JasperResult<Vec<EscalationRecord>> {
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let known: Vec<String> = tx
+                .prepare("SELECT item_key FROM escalations")?
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            for key in known {
+                if !items.iter().any(|(k, _)| *k == key) {
+                    tx.execute("DELETE FROM escalations WHERE item_key = ?", params![key])?;
+                }
+            }
+            for (key, label) in items {
+                tx.execute(
+                    "INSERT INTO escalations (item_key, label) VALUES (?, ?)
+                     ON CONFLICT(item_key) DO UPDATE SET streak = streak + 1, label = excluded.label",
+                    params![key, label],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })?;
+        self.get_escalations()
+    }
+
+    fn mark_escalation_notified(&self, item_key: &str) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "UPDATE escalations SET notified = 1 WHERE item_key = ?",
+                params![item_key],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn dismiss_escalations(&self, item_key: Option<&str>) -> JasperResult<usize> {
+        self.with_connection_retry(|conn| {
+            let dismissed = match item_key {
+                Some(key) => conn.execute(
+                    "UPDATE escalations SET dismissed = 1 WHERE item_key = ? AND dismissed = 0",
+                    params![key],
+                )?,
+                None => conn.execute(
+                    "UPDATE escalations SET dismissed = 1 WHERE dismissed = 0",
+                    [],
+                )?,
+            };
+            Ok(dismissed)
+        })
+    }
+
+    fn get_escalations(&self) -> JasperResult<Vec<EscalationRecord>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT item_key, label, streak, notified, dismissed, first_seen
+                 FROM escalations
+                 ORDER BY streak DESC, item_key",
+            )?;
+            let records = stmt
+                .query_map([], |row| {
+                    Ok(EscalationRecord {
+                        item_key: row.get(0)?,
+                        label: row.get(1)?,
+                        streak: row.get(2)?,
+                        notified: row.get(3)?,
+                        dismissed: row.get(4)?,
+                        first_seen: DateTime::from_timestamp(row.get::<_, i64>(5)?, 0)
+                            .unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(records)
+        })
+    }
+
+    fn log_meetings(&self, meetings: &[MeetingLogEntry], now: DateTime<Utc>) -> JasperResult<()> {
+        let cutoff = (now - chrono::Duration::days(LOAD_LOG_RETENTION_DAYS)).timestamp();
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "DELETE FROM meeting_log WHERE start_time >= ? OR start_time < ?",
+                params![now.timestamp(), cutoff],
+            )?;
+            for meeting in meetings {
+                tx.execute(
+                    "INSERT OR REPLACE INTO meeting_log (event_id, title, start_time, end_time) VALUES (?, ?, ?, ?)",
+                    params![
+                        meeting.event_id,
+                        meeting.title,
+                        meeting.start_time.timestamp(),
+                        meeting.end_time.timestamp()
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn log_tasks(&self, tasks: &[TaskLogEntry], now: DateTime<Utc>) -> JasperResult<()> {
+        let cutoff = (now - chrono::Duration::days(LOAD_LOG_RETENTION_DAYS)).timestamp();
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM task_log WHERE due_time < ?", params![cutoff])?;
+            let open: Vec<String> = tx
+                .prepare("SELECT task_id FROM task_log WHERE closed_at IS NULL")?
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            for task_id in open {
+                if !tasks.iter().any(|t| t.task_id == task_id) {
+                    tx.execute(
+                        "UPDATE task_log SET closed_at = ? WHERE task_id = ?",
+                        params![now.timestamp(), task_id],
+                    )?;
+                }
+            }
+            for task in tasks {
+                tx.execute(
+                    "INSERT INTO task_log (task_id, title, due_time, closed_at, urgency) VALUES (?, ?, ?, ?, ?)
+                     ON CONFLICT(task_id) DO UPDATE SET title = excluded.title, due_time = excluded.due_time,
+                         closed_at = COALESCE(task_log.closed_at, excluded.closed_at),
+                         urgency = CASE WHEN task_log.closed_at IS NULL THEN excluded.urgency ELSE task_log.urgency END",
+                    params![
+                        task.task_id,
+                        task.title,
+                        task.due_time.timestamp(),
+                        task.closed_at.map(|t| t.timestamp()),
+                        task.urgency
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn replace_user_patterns(&self, patterns: &[UserPattern]) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            tx.execute("DELETE FROM user_patterns", [])?;
+            for pattern in patterns {
+                tx.execute(
+                    "INSERT INTO user_patterns (pattern_type, pattern_data, occurrences, last_seen, confidence_score)
+                     VALUES (?, ?, ?, ?, ?)",
+                    params![
+                        pattern.pattern_type,
+                        pattern.pattern_data,
+                        pattern.occurrences,
+                        pattern.last_seen.timestamp(),
+                        pattern.confidence
+                    ],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    fn get_user_patterns(&self) -> JasperResult<Vec<UserPattern>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT pattern_type, pattern_data, occurrences, last_seen, confidence_score
+                 FROM user_patterns ORDER BY id",
+            )?;
+            let patterns = stmt
+                .query_map([], |row| {
+                    Ok(UserPattern {
+                        pattern_type: row.get(0)?,
+                        pattern_data: row.get(1)?,
+                        occurrences: row.get::<_, Option<u32>>(2)?.unwrap_or(1),
+                        last_seen: DateTime::from_timestamp(
+                            row.get::<_, Option<i64>>(3)?.unwrap_or_default(),
+                            0,
+                        )
+                        .unwrap_or_default(),
+                        confidence: row.get::<_, Option<f64>>(4)?.unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(patterns)
+        })
+    }
+
+    fn get_meeting_log(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<MeetingLogEntry>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT event_id, title, start_time, end_time FROM meeting_log
+                 WHERE start_time >= ? AND start_time < ? ORDER BY start_time",
+            )?;
+            let entries = stmt
+                .query_map(params![start.timestamp(), end.timestamp()], |row| {
+                    Ok(MeetingLogEntry {
+                        event_id: row.get(0)?,
+                        title: row.get(1)?,
+                        start_time: DateTime::from_timestamp(row.get::<_, i64>(2)?, 0)
+                            .unwrap_or_default(),
+                        end_time: DateTime::from_timestamp(row.get::<_, i64>(3)?, 0)
+                            .unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+    }
+
+    fn get_task_log(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<TaskLogEntry>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT task_id, title, due_time, closed_at, urgency FROM task_log
+                 WHERE due_time >= ? AND due_time < ? ORDER BY due_time",
+            )?;
+            let entries = stmt
+                .query_map(params![start.timestamp(), end.timestamp()], |row| {
+                    Ok(TaskLogEntry {
+                        task_id: row.get(0)?,
+                        title: row.get(1)?,
+                        due_time: DateTime::from_timestamp(row.get::<_, i64>(2)?, 0)
+                            .unwrap_or_default(),
+                        closed_at: row
+                            .get::<_, Option<i64>>(3)?
+                            .and_then(|t| DateTime::from_timestamp(t, 0)),
+                        urgency: row.get(4)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+    }
+
+    fn store_week_stats(&self, stats: &WeekStats) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO weekly_stats (week_start, meetings, meeting_minutes,
+                     longest_back_to_back, after_hours_meetings, tasks_due, tasks_done)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+                params![
+                    stats.week_start.to_string(),
+                    stats.meetings,
+                    stats.meeting_minutes,
+                    stats.longest_back_to_back,
+                    stats.after_hours_meetings,
+                    stats.tasks_due,
+                    stats.tasks_done
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_week_stats(&self, weeks: u32) -> JasperResult<Vec<WeekStats>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT week_start, meetings, meeting_minutes, longest_back_to_back,
+                        after_hours_meetings, tasks_due, tasks_done
+                 FROM weekly_stats ORDER BY week_start DESC LIMIT ?",
+            )?;
+            let mut stats = stmt
+                .query_map(params![weeks], |row| {
+                    Ok(WeekStats {
+                        week_start: row.get::<_, String>(0)?.parse().unwrap_or_default(),
+                        meetings: row.get(1)?,
+                        meeting_minutes: row.get(2)?,
+                        longest_back_to_back: row.get(3)?,
+                        after_hours_meetings: row.get(4)?,
+                        tasks_due: row.get(5)?,
+                        tasks_done: row.get(6)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            stats.reverse();
+            Ok(stats)
+        })
+    }
+
+    fn last_trigger_fired(&self, trigger: &str) -> JasperResult<Option<DateTime<Utc>>> {
+        self.with_reader_retry(|conn| {
+            let last = conn
+                .query_row(
+                    "SELECT MAX(created_at) FROM trigger_audit WHERE trigger = ? AND decision = 'fired'",
+                    params![trigger],
+                    |row| row.get::<_, Option<i64>>(0),
+                )?
+                .and_then(|t| DateTime::from_timestamp(t, 0));
+            Ok(last)
+        })
+    }
+
+    fn prune_expired(
+        &self,
+        now: DateTime<Utc>,
+        snapshot_retention_days: u32,
+        response_cache_ttl: Option<chrono::Duration>,
+    ) -> JasperResult<usize> {
+        let days_ago = |days: i64| (now - chrono::Duration::days(days)).timestamp();
+        let trigger_cutoff = days_ago(TRIGGER_AUDIT_RETENTION_DAYS);
+        let memory_cutoff = days_ago(INSIGHT_MEMORY_RETENTION_DAYS);
+        let load_cutoff = days_ago(LOAD_LOG_RETENTION_DAYS);
+        let usage_cutoff = days_ago(API_USAGE_RETENTION_DAYS);
+        let snapshot_cutoff = days_ago(snapshot_retention_days as i64);
+        // With the cache off, every cached response is stale
+        let cache_cutoff = response_cache_ttl.map_or(i64::MAX, |ttl| (now - ttl).timestamp());
+        self.with_connection_retry(|conn| {
+            let tx = conn.unchecked_transaction()?;
+            let mut removed = 0;
+            for (sql, cutoff) in [
+                ("DELETE FROM trigger_audit WHERE created_at < ?", trigger_cutoff),
+                ("DELETE FROM insight_memory WHERE created_at < ?", memory_cutoff),
+                ("DELETE FROM meeting_log WHERE start_time < ?", load_cutoff),
+                ("DELETE FROM task_log WHERE due_time < ?", load_cutoff),
+                ("DELETE FROM api_usage WHERE created_at < ?", usage_cutoff),
+                ("DELETE FROM ai_response_cache WHERE created_at < ?", cache_cutoff),
+                (
+                    "DELETE FROM context_snapshots WHERE created_at < ?1 AND id NOT IN
+                         (SELECT base_id FROM context_snapshots WHERE base_id IS NOT NULL AND created_at >= ?1)",
+                    snapshot_cutoff,
+                ),
+            ] {
+                removed += tx.execute(sql, params![cutoff])?;
+            }
+            tx.commit()?;
+            Ok(removed)
+        })
+    }
+
+    fn vacuum(&self) -> JasperResult<u64> {
+        self.with_connection_retry(|conn| {
+            let size = |conn: &Connection| -> rusqlite::Result<i64> {
+                conn.query_row(
+                    "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                    [],
+                    |row| row.get(0),
+                )
+            };
+            let before = size(conn)?;
+            conn.execute_batch("VACUUM;")?;
+            Ok(before.saturating_sub(size(conn)?).max(0) as u64)
+        })
+    }
+
+    fn checkpoint_wal(&self) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
+            Ok(())
+        })
+    }
+
+    fn wipe(&self, classes: &[DataClass]) -> JasperResult<Vec<(DataClass, usize)>> {
+        let removed = self.with_connection_retry(|conn| {
+            // Zero deleted content rather than just unlinking the pages
+            conn.execute_batch("PRAGMA secure_delete = ON;")?;
+            let tx = conn.unchecked_transaction()?;
+            let mut removed = Vec::new();
+            for class in DataClass::ALL.into_iter().filter(|c| classes.contains(c)) {
+                let mut rows = 0;
+                for table in wipe_tables(class) {
+                    rows += tx.execute(&format!("DELETE FROM {}", table), [])?;
+                }
+                removed.push((class, rows));
+            }
+            tx.commit()?;
+            conn.execute_batch("PRAGMA secure_delete = OFF;")?;
+            Ok(removed)
+        })?;
+        self.vacuum()?;
+        self.checkpoint_wal()?;
+        info!("Wiped {:?}", removed);
+        Ok(removed)
+    }
+
+    fn record_maintenance_run(&self, run: &MaintenanceRun) -> JasperResult<()> {
+        let tasks_json = serde_json::to_string(&run.tasks)?;
+        let tasks_json = tasks_json.as_str();
+        let (started_at, finished_at) = (run.started_at.timestamp(), run.finished_at.timestamp());
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT INTO maintenance_runs (started_at, finished_at, tasks_json) VALUES (?, ?, ?)",
+                params![started_at, finished_at, tasks_json],
+            )?;
+            conn.execute(
+                "DELETE FROM maintenance_runs WHERE id NOT IN
+                     (SELECT id FROM maintenance_runs ORDER BY id DESC LIMIT ?)",
+                params![MAINTENANCE_RUNS_KEPT],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn latest_maintenance_run(&self) -> JasperResult<Option<MaintenanceRun>> {
+        let row = self.with_reader_retry(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT started_at, finished_at, tasks_json FROM maintenance_runs
+                     ORDER BY id DESC LIMIT 1",
+                    [],
+                    |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, i64>(1)?,
+                            row.get::<_, String>(2)?,
+                        ))
+                    },
+                )
+                .optional()?)
+        })?;
+        let Some((started_at, finished_at, tasks_json)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(MaintenanceRun {
+            started_at: DateTime::from_timestamp(started_at, 0).unwrap_or_default(),
+            finished_at: DateTime::from_timestamp(finished_at, 0).unwrap_or_default(),
+            tasks: serde_json::from_str(&tasks_json)?,
+        }))
+    }
+
+    fn record_api_usage(&self, usage: &ApiUsage) -> JasperResult<()> {
+        let cutoff = (Utc::now() - chrono::Duration::days(API_USAGE_RETENTION_DAYS)).timestamp();
+        self.with_connection_retry(|conn| {
+            conn.execute("DELETE FROM api_usage WHERE created_at < ?", params![cutoff])?;
+            conn.execute(
+                "INSERT INTO api_usage (model, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens)
+                 VALUES (?, ?, ?, ?, ?)",
+                params![
+                    usage.model,
+                    usage.input_tokens as i64,
+                    usage.output_tokens as i64,
+                    usage.cache_creation_tokens as i64,
+                    usage.cache_read_tokens as i64
+                ],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_api_usage_summary(&self, since: DateTime<Utc>) -> JasperResult<ApiUsageSummary> {
+        self.with_reader_retry(|conn| {
+            let summary = conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(cache_read_tokens > 0), 0),
+                        COALESCE(SUM(input_tokens), 0),
+                        COALESCE(SUM(output_tokens), 0),
+                        COALESCE(SUM(cache_creation_tokens), 0),
+                        COALESCE(SUM(cache_read_tokens), 0)
+                 FROM api_usage WHERE created_at >= ?",
+                params![since.timestamp()],
+                |row| {
+                    Ok(ApiUsageSummary {
+                        calls: row.get::<_, i64>(0)? as u64,
+                        cache_hits: row.get::<_, i64>(1)? as u64,
+                        input_tokens: row.get::<_, i64>(2)? as u64,
+                        output_tokens: row.get::<_, i64>(3)? as u64,
+                        cache_creation_tokens: row.get::<_, i64>(4)? as u64,
+                        cache_read_tokens: row.get::<_, i64>(5)? as u64,
+                    })
+                },
+            )?;
+            Ok(summary)
+        })
+    }
+
+    fn get_trigger_audit(&self, limit: u32) -> JasperResult<Vec<TriggerAuditEntry>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, trigger, decision, detail, created_at
+                 FROM trigger_audit
+                 ORDER BY created_at DESC, id DESC
+                 LIMIT ?",
+            )?;
+            let entries = stmt
+                .query_map(params![limit], |row| {
+                    Ok(TriggerAuditEntry {
+                        id: row.get(0)?,
+                        trigger: row.get(1)?,
+                        decision: row.get(2)?,
+                        detail: row.get(3)?,
+                        created_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                            .unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entries)
+        })
+    }
+
+    fn get_fired_trigger(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> JasperResult<Option<TriggerAuditEntry>> {
+        let (since, until) = (since.timestamp(), until.timestamp());
+        self.with_reader_retry(|conn| {
+            Ok(conn
+                .query_row(
+                    "SELECT id, trigger, decision, detail, created_at
+                     FROM trigger_audit
+                     WHERE decision = 'fired' AND created_at BETWEEN ? AND ?
+                     ORDER BY created_at DESC, id DESC
+                     LIMIT 1",
+                    params![since, until],
+                    |row| {
+                        Ok(TriggerAuditEntry {
+                            id: row.get(0)?,
+                            trigger: row.get(1)?,
+                            decision: row.get(2)?,
+                            detail: row.get(3)?,
+                            created_at: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                                .unwrap_or_default(),
+                        })
+                    },
+                )
+                .optional()?)
+        })
+    }
+
+    fn add_jasper_item(
+        &self,
+        kind: &str,
+        title: &str,
+        notes: Option<&str>,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> JasperResult<i64> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT INTO jasper_items (kind, title, notes, start_time, end_time) VALUES (?, ?, ?, ?, ?)",
+                params![
+                    kind,
+                    title,
+                    notes,
+                    start_time.timestamp(),
+                    end_time.map(|t| t.timestamp())
+                ],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    fn delete_jasper_item(&self, id: i64) -> JasperResult<bool> {
+        self.with_connection_retry(|conn| {
+            let removed = conn.execute("DELETE FROM jasper_items WHERE id = ?", params![id])?;
+            Ok(removed > 0)
+        })
+    }
+
+    fn get_jasper_items(&self) -> JasperResult<Vec<JasperItem>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT id, kind, title, notes, start_time, end_time, created_at
+                 FROM jasper_items
+                 ORDER BY start_time, id",
+            )?;
+            let items = stmt
+                .query_map([], |row| {
+                    Ok(JasperItem {
+                        id: row.get(0)?,
+                        kind: row.get(1)?,
+                        title: row.get(2)?,
+                        notes: row.get(3)?,
+                        start_time: DateTime::from_timestamp(row.get::<_, i64>(4)?, 0)
+                            .unwrap_or_default(),
+                        end_time: row
+                            .get::<_, Option<i64>>(5)?
+                            .and_then(|t| DateTime::from_timestamp(t, 0)),
+                        created_at: DateTime::from_timestamp(row.get::<_, i64>(6)?, 0)
+                            .unwrap_or_default(),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(items)
+        })
+    }
+
+    fn store_context_snapshot(
+        &self,
+        insight_id: i64,
+        source: &str,
+        encoding: &str,
+        data: &[u8],
+        base_id: Option<i64>,
+        retention_days: u32,
+    ) -> JasperResult<i64> {
+        let cutoff = (Utc::now() - chrono::Duration::days(retention_days as i64)).timestamp();
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "DELETE FROM context_snapshots WHERE created_at < ?1 AND id NOT IN
+                     (SELECT base_id FROM context_snapshots WHERE base_id IS NOT NULL AND created_at >= ?1)",
+                params![cutoff],
+            )?;
+            conn.execute(
+                "INSERT INTO context_snapshots (insight_id, source, snapshot_json, encoding, data, base_id)
+                 VALUES (?, ?, '', ?, ?, ?)",
+                params![insight_id, source, encoding, data, base_id],
+            )?;
+            Ok(conn.last_insert_rowid())
+        })
+    }
+
+    fn get_snapshot_keyframe(&self, source: &str) -> JasperResult<Option<(StoredSnapshot, u32)>> {
+        let keyframe = self.query_snapshot(
+            "WHERE source = ? AND base_id IS NULL ORDER BY id DESC LIMIT 1",
+            params![source],
+        )?;
+        let Some(keyframe) = keyframe else {
+            return Ok(None);
+        };
+        let patches = self.with_reader_retry(|conn| {
+            Ok(conn.query_row(
+                "SELECT COUNT(*) FROM context_snapshots WHERE base_id = ?",
+                params![keyframe.id],
+                |row| row.get::<_, u32>(0),
+            )?)
+        })?;
+        Ok(Some((keyframe, patches)))
+    }
+
+    fn get_context_snapshot(&self, id: i64) -> JasperResult<Option<StoredSnapshot>> {
+        self.query_snapshot("WHERE id = ?", params![id])
+    }
+
+    fn get_insight_snapshot(&self, insight_id: i64) -> JasperResult<Option<StoredSnapshot>> {
+        self.query_snapshot(
+            "WHERE insight_id = ? ORDER BY id DESC LIMIT 1",
+            params![insight_id],
+        )
+    }
+
+    fn register_frontend(
+        &self,
+        frontend_id: &str,
+        pid: Option<i32>,
+        format: &str,
+        access: &str,
+        bus_name: Option<&str>,
+    ) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO active_frontends (id, pid, format, access, bus_name) VALUES (?, ?, ?, ?, ?)",
+                params![frontend_id, pid, format, access, bus_name],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn is_guest_frontend(
+        &self,
+        frontend_id: Option<&str>,
+        bus_name: Option<&str>,
+    ) -> JasperResult<bool> {
+        self.with_reader_retry(|conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM active_frontends WHERE access = 'guest' AND (id = ? OR bus_name = ?)",
+                params![frontend_id, bus_name],
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        })
+    }
+
+    fn has_guest_frontends(&self) -> JasperResult<bool> {
+        self.with_reader_retry(|conn| {
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM active_frontends WHERE access = 'guest'",
+                [],
+                |row| row.get(0),
+            )?;
+            Ok(count > 0)
+        })
+    }
+
+    fn unregister_frontend(&self, frontend_id: &str) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "DELETE FROM active_frontends WHERE id = ?",
+                params![frontend_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn update_frontend_heartbeat(&self, frontend_id: &str) -> JasperResult<()> {
+        self.with_connection_retry(|conn| {
+            conn.execute(
+                "UPDATE active_frontends SET last_heartbeat = strftime('%s', 'now') WHERE id = ?",
+                params![frontend_id],
+            )?;
+            Ok(())
+        })
+    }
+
+    fn get_active_frontends(&self) -> JasperResult<Vec<ActiveFrontend>> {
+        self.with_reader_retry(|conn| {
+            let mut stmt =
+                conn.prepare("SELECT id, pid, started_at, last_heartbeat, COALESCE(format, 'plain'), COALESCE(access, 'full'), bus_name FROM active_frontends")?;
+
+            let frontends = stmt
+                .query_map([], |row| {
+                    Ok(ActiveFrontend {
+                        id: row.get(0)?,
+                        pid: row.get(1)?,
+                        started_at: DateTime::from_timestamp(row.get::<_, i64>(2)?, 0)
+                            .unwrap_or_default(),
+                        last_heartbeat: DateTime::from_timestamp(row.get::<_, i64>(3)?, 0)
+                            .unwrap_or_default(),
+                        format: row.get(4)?,
+                        access: row.get(5)?,
+                        bus_name: row.get(6)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(frontends)
+        })
+    }
+
+    fn cleanup_expired_frontends(&self, timeout_secs: u64) -> JasperResult<usize> {
+        let dead: Vec<String> = self
+            .get_active_frontends()?
+            .into_iter()
+            .filter(|f| f.pid.is_some_and(|pid| pid > 0 && !process_alive(pid)))
+            .map(|f| f.id)
+            .collect();
+
+        self.with_connection_retry(|conn| {
+            let mut count = conn.execute(
+                "DELETE FROM active_frontends WHERE last_heartbeat < strftime('%s', 'now') - ?",
+                params![timeout_secs as i64],
+            )?;
+            for id in &dead {
+                warn!("Frontend {} exited without unregistering", id);
+                count += conn.execute("DELETE FROM active_frontends WHERE id = ?", params![id])?;
+            }
+            Ok(count)
+        })
+    }
+
+    fn has_active_frontends(&self, timeout_secs: u64) -> JasperResult<bool> {
+        // Clean up expired frontends first
+        self.cleanup_expired_frontends(timeout_secs)?;
+
+        self.with_reader_retry(|conn| {
+            let count: i64 =
+                conn.query_row("SELECT COUNT(*) FROM active_frontends", [], |row| {
+                    row.get(0)
+                })?;
+            Ok(count > 0)
+        })
+    }
+}
+
+/// Whether a registered frontend's process still exists. Only Linux can
+/// tell cheaply (via /proc); elsewhere heartbeats alone decide.
+fn process_alive(pid: i32) -> bool {
+    let proc = Path::new("/proc");
+    !proc.is_dir() || proc.join(pid.to_string()).exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_readers_see_writes_and_cannot_write() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+
+        let id = db.store_insight("📅", "Standup at 10", None).unwrap();
+        for _ in 0..READER_POOL_SIZE {
+            let latest = db.get_latest_insight().unwrap().unwrap();
+            assert_eq!(latest.id, id);
+        }
+
+        let (_, reader) = db.acquire_reader();
+        assert!(reader.execute("DELETE FROM insights", []).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_frontends_with_dead_pids_are_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id() as i32;
+        child.wait().unwrap();
+
+        db.register_frontend("crashed", Some(dead_pid), "plain", "full", None)
+            .unwrap();
+        db.register_frontend(
+            "applet",
+            Some(std::process::id() as i32),
+            "plain",
+            "full",
+            None,
+        )
+        .unwrap();
+        db.register_frontend("polled", None, "plain", "full", None)
+            .unwrap();
+
+        assert_eq!(db.cleanup_expired_frontends(120).unwrap(), 1);
+        let mut ids: Vec<String> = db
+            .get_active_frontends()
+            .unwrap()
+            .into_iter()
+            .map(|f| f.id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["applet", "polled"]);
+    }
+
+    #[tokio::test]
+    async fn test_guest_frontend_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+        db.register_frontend("kitchen", None, "html", "guest", Some(":1.42"))
+            .unwrap();
+        db.register_frontend("waybar", None, "pango", "full", Some(":1.7"))
+            .unwrap();
+
+        assert!(db.has_guest_frontends().unwrap());
+        assert!(db.is_guest_frontend(Some("kitchen"), None).unwrap());
+        // The guest's connection stays a guest whatever frontend ID it asks as
+        assert!(db.is_guest_frontend(Some("waybar"), Some(":1.42")).unwrap());
+        assert!(!db.is_guest_frontend(Some("waybar"), Some(":1.7")).unwrap());
+
+        db.unregister_frontend("kitchen").unwrap();
+        assert!(!db.has_guest_frontends().unwrap());
+    }
+
+    #[cfg(not(feature = "sqlcipher"))]
+    #[tokio::test]
+    async fn test_key_without_sqlcipher_is_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jasper.db");
+        let result = DatabaseInner::new(&path, Some("secret".to_string())).await;
+        assert!(matches!(result, Err(JasperError::Config { .. })));
+        // Refused before anything touched the disk, so nothing was left in plaintext
+        assert!(!path.exists());
+    }
+
+    fn event(source_id: &str, title: &str, start_time: i64) -> Event {
+        Event {
+            id: 0,
+            source_id: source_id.to_string(),
+            calendar_id: 0,
+            title: Some(title.to_string()),
+            description: None,
+            start_time,
+            end_time: None,
+            location: None,
+            event_type: None,
+            participants: None,
+            raw_data_json: None,
+            is_all_day: Some(false),
+            meeting_url: None,
+            links: None,
+            my_response: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_databases_are_private_and_removed() {
+        let db = DatabaseInner::ephemeral().await.unwrap();
+        let other = DatabaseInner::new(&PathBuf::from(MEMORY_PATH), None)
+            .await
+            .unwrap();
+        assert!(db.is_ephemeral() && other.is_ephemeral());
+
+        // Fully migrated, and readers see the writer's rows
+        let calendar = db
+            .create_or_update_calendar("primary", "Primary", None, Some("owner"), true)
+            .unwrap();
+        // Still going when the range starts, and over before it
+        let mut conference = event("b", "Conference", -86_400);
+        conference.end_time = Some(86_400);
+        let mut ended = event("c", "Retro", -7200);
+        ended.end_time = Some(-3600);
+        db.sync_calendar_events(calendar, &[event("a", "Standup", 1000), conference, ended])
+            .unwrap();
+        let range = |db: &DatabaseInner| {
+            db.get_events_in_range(
+                DateTime::from_timestamp(0, 0).unwrap(),
+                DateTime::from_timestamp(2000, 0).unwrap(),
+            )
+            .unwrap()
+        };
+        let titles: Vec<Option<String>> = range(&db).into_iter().map(|e| e.title).collect();
+        assert_eq!(
+            titles,
+            [Some("Conference".to_string()), Some("Standup".to_string())]
+        );
+        assert!(range(&other).is_empty());
+
+        let dir = db.ephemeral_dir.clone().unwrap();
+        assert!(dir.join("jasper.db").exists());
+        drop(db);
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_sync_upserts_and_preserves_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+        let calendar = db
+            .create_or_update_calendar("primary", "Primary", None, Some("owner"), true)
+            .unwrap();
+
+        let first = db
+            .sync_calendar_events(
+                calendar,
+                &[event("a", "Standup", 1000), event("b", "Lunch", 2000)],
+            )
+            .unwrap();
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|c| c.kind == EventChangeKind::Added));
+        let standup_id = first.iter().find(|c| c.source_id == "a").unwrap().event_id;
+
+        // Unchanged events produce no records
+        let again = db
+            .sync_calendar_events(
+                calendar,
+                &[event("a", "Standup", 1000), event("b", "Lunch", 2000)],
+            )
+            .unwrap();
+        assert!(again.is_empty());
+
+        let mut moved = event("a", "Standup", 1000 + 7200);
+        moved.location = Some("Room 4".to_string());
+        moved.meeting_url = Some("https://meet.google.com/abc-defg-hij".to_string());
+        let changes = db.sync_calendar_events(calendar, &[moved]).unwrap();
+        assert_eq!(changes.len(), 2);
+
+        let updated = changes.iter().find(|c| c.source_id == "a").unwrap();
+        assert_eq!(updated.event_id, standup_id);
+        assert_eq!(
+            updated.kind,
+            EventChangeKind::Updated {
+                previous_start: 1000,
+                previous_location: None,
+            }
+        );
+        let removed = changes.iter().find(|c| c.source_id == "b").unwrap();
+        assert_eq!(removed.kind, EventChangeKind::Removed);
+
+        let next = db
+            .get_next_meeting(DateTime::from_timestamp(0, 0).unwrap())
+            .unwrap()
+            .unwrap();
+        assert_eq!(next.source_id, "a");
+        assert_eq!(next.url, "https://meet.google.com/abc-defg-hij");
+        // Over an hour past a start with no end time, it's gone
+        assert_eq!(
+            db.get_next_meeting(DateTime::from_timestamp(1000 + 7200 + 3600, 0).unwrap())
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_event_page_link_is_part_of_the_hash() {
+        let plain = event("a", "Standup", 1000);
+        let mut linked = plain.clone();
+        linked.raw_data_json =
+            Some(r#"{"htmlLink": "https://www.google.com/calendar/event?eid=abc"}"#.to_string());
+        assert_ne!(linked.content_hash(), plain.content_hash());
+        // Raw data without a page keeps the hash events always had
+        linked.raw_data_json = Some(r#"{"status": "confirmed"}"#.to_string());
+        assert_eq!(linked.content_hash(), plain.content_hash());
+    }
+
+    thread_local! {
+        static STATEMENTS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    fn count_statement(_sql: &str) {
+        STATEMENTS.with(|n| n.set(n.get() + 1));
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_grouped_read_is_one_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+
+        let mut calendars = Vec::new();
+        for (name, role, primary) in [
+            ("primary", "owner", true),
+            ("family", "owner", false),
+            ("partner", "reader", false),
+        ] {
+            let id = db
+                .create_or_update_calendar(name, name, None, Some(role), primary)
+                .unwrap();
+            let events: Vec<Event> = (0..5)
+                .map(|n| event(&format!("{}-{}", name, n), "Event", 1000 + n * 60))
+                .collect();
+            db.sync_calendar_events(id, &events).unwrap();
+            calendars.push(id);
+        }
+        // Google's color replaces the inferred one; without one it stays
+        db.set_calendar_appearance(calendars[1], None, false, true)
+            .unwrap();
+        db.set_calendar_appearance(calendars[2], Some("#9fe1e7"), true, false)
+            .unwrap();
+
+        for reader in &db.readers {
+            reader.lock().trace(Some(count_statement));
+        }
+        let range = (
+            DateTime::from_timestamp(0, 0).unwrap(),
+            DateTime::from_timestamp(10_000, 0).unwrap(),
+        );
+
+        // Fifteen events across three calendars: one statement, not one per event
+        STATEMENTS.with(|n| n.set(0));
+        let groups = db
+            .get_events_by_calendar(range.0, range.1, &[], &[])
+            .unwrap();
+        assert_eq!(STATEMENTS.with(|n| n.get()), 1);
+        assert_eq!(groups.len(), 3);
+        assert!(groups.iter().all(|g| g.events.len() == 5));
+        assert!(groups[0].is_primary);
+        assert_eq!(groups[1].color.as_deref(), Some("#0F9D58"));
+        assert_eq!(groups[2].color.as_deref(), Some("#9fe1e7"));
+
+        let owned = db
+            .get_events_by_calendar(range.0, range.1, &[], &["owner"])
+            .unwrap();
+        assert_eq!(owned.len(), 2);
+        let family = db
+            .get_events_by_calendar(range.0, range.1, &calendars[1..2], &[])
+            .unwrap();
+        assert_eq!(family.len(), 1);
+        assert_eq!(family[0].calendar_name, "family");
+    }
+
+    #[tokio::test]
+    async fn test_escalation_streaks_reset_when_items_go_away() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+        let item = |key: &str| (key.to_string(), format!("Overdue: {}", key));
+
+        db.advance_escalations(&[item("task:taxes"), item("task:slip")])
+            .unwrap();
+        let records = db
+            .advance_escalations(&[item("task:taxes"), item("task:slip")])
+            .unwrap();
+        assert!(records.iter().all(|r| r.streak == 2 && !r.dismissed));
+
+        // The slip was signed; the taxes keep piling up
+        let records = db.advance_escalations(&[item("task:taxes")]).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].streak, 3);
+
+        assert_eq!(db.dismiss_escalations(Some("task:taxes")).unwrap(), 1);
+        assert_eq!(db.dismiss_escalations(None).unwrap(), 0);
+        let records = db
+            .advance_escalations(&[item("task:taxes"), item("task:slip")])
+            .unwrap();
+        assert!(records[0].dismissed && records[0].streak == 4);
+        assert!(!records[1].dismissed && records[1].streak == 1);
+    }
+
+    #[tokio::test]
+    async fn test_api_usage_counts_cache_hits() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+        let since = Utc::now() - chrono::Duration::minutes(1);
+        assert_eq!(db.get_api_usage_summary(since).unwrap().calls, 0);
+
+        let call = |creation, read| ApiUsage {
+            model: "claude-sonnet-4-6".to_string(),
+            input_tokens: 400,
+            output_tokens: 40,
+            cache_creation_tokens: creation,
+            cache_read_tokens: read,
+        };
+        db.record_api_usage(&call(1500, 0)).unwrap();
+        db.record_api_usage(&call(0, 1500)).unwrap();
+        db.record_api_usage(&call(0, 1500)).unwrap();
+
+        let summary = db.get_api_usage_summary(since).unwrap();
+        assert_eq!(summary.calls, 3);
+        assert_eq!(summary.cache_hits, 2);
+        assert_eq!(summary.input_tokens, 1200);
+        assert_eq!(summary.cache_read_tokens, 3000);
+        assert_eq!(summary.cache_creation_tokens, 1500);
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_prunes_and_records_runs() {
+        let db = DatabaseInner::ephemeral().await.unwrap();
+        db.record_trigger("heartbeat", "fired", None).unwrap();
+        db.store_cached_response("hash", "response", chrono::Duration::hours(1))
+            .unwrap();
+
+        // Nothing is old yet; with the response cache off its entry goes
+        let now = Utc::now();
+        assert_eq!(
+            db.prune_expired(now, 7, Some(chrono::Duration::hours(1)))
+                .unwrap(),
+            0
+        );
+        assert_eq!(db.prune_expired(now, 7, None).unwrap(), 1);
+        // A month from now the trigger decision is past its retention
+        let later = now + chrono::Duration::days(30);
+        assert_eq!(db.prune_expired(later, 7, None).unwrap(), 1);
+
+        db.vacuum().unwrap();
+        db.checkpoint_wal().unwrap();
+
+        assert!(db.latest_maintenance_run().unwrap().is_none());
+        let run = |ok| MaintenanceRun {
+            started_at: DateTime::from_timestamp(now.timestamp(), 0).unwrap(),
+            finished_at: DateTime::from_timestamp(now.timestamp() + 2, 0).unwrap(),
+            tasks: vec![MaintenanceTask {
+                task: "auth".to_string(),
+                ok,
+                detail: "token".to_string(),
+            }],
+        };
+        db.record_maintenance_run(&run(true)).unwrap();
+        db.record_maintenance_run(&run(false)).unwrap();
+        let latest = db.latest_maintenance_run().unwrap().unwrap();
+        assert_eq!(latest, run(false));
+        assert_eq!(latest.failures(), 1);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_blocking_does_not_stall_the_runtime() {
+        let dir = tempfile::tempdir().unwrap();
+        let db: Database = DatabaseInner::new(&dir.path().join("jasper.db"), None)
+            .await
+            .unwrap();
+
+        // A slow query on the blocking pool must not hold up other tasks on
+        // this single-threaded runtime: the short timer has to fire first.
+        let start = std::time::Instant::now();
+        let slow = db.blocking(|db| {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            db.get_recent_insights(5)
+        });
+        let tick = async {
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            start.elapsed()
+        };
+        let (insights, ticked_after) = tokio::join!(slow, tick);
+
+        assert!(insights.unwrap().is_empty());
+        assert!(
+            ticked_after < std::time::Duration::from_millis(150),
+            "timer was blocked for {:?}",
+            ticked_after
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Rolling "day so far" summary for same-day prompts.
+//!
+//! Most context barely changes between one insight and the next: the
+//! weather, project notes and daily notes sent at 9 AM are usually the same
+//! at 11. The daemon keeps one summary per local day, updated locally after
+//! each insight, with no extra API call. It records what Jasper has said
+//! today and a fingerprint and headline of each low-priority context
+//! section it has already sent. Later prompts that day list today's insights
+//! from the summary in place of the recent-insights list, and any of those
+//! sections that hasn't changed is left out of the context, with its headline
+//! standing in.
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::insight_memory;
+use crate::text_budget;
+use crate::token_budget::{priority, PromptSection};
+
+/// Insights of the day listed individually; older ones are only counted
+const MAX_INSIGHTS: usize = 12;
+/// Longest headline kept for a folded section
+const HEADLINE_CHARS: usize = 100;
+/// Sections below this priority may be replaced by their headline.
+/// Calendars, tasks and schedule situations are time-sensitive and always sent.
+const FOLDABLE_BELOW: u32 = priority::SHARED_CALENDARS;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DaySummary {
+    pub date: NaiveDate,
+    /// Today's insights not listed individually any more
+    #[serde(default)]
+    pub earlier_insights: u32,
+    #[serde(default)]
+    pub insights: Vec<SummaryInsight>,
+    #[serde(default)]
+    pub sections: Vec<FoldedSection>,
+}
+
+/// One insight, cut to its first sentence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SummaryInsight {
+    /// Local time shown, e.g. "9:10 AM"
+    pub time: String,
+    pub emoji: String,
+    pub gist: String,
+}
+
+/// A context section already sent today
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoldedSection {
+    pub name: String,
+    pub fingerprint: String,
+    /// First line of the section, standing in for the rest when unchanged
+    pub headline: String,
+    /// Local time the section was last sent in full
+    pub since: String,
+}
+
+impl DaySummary {
+    pub fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            earlier_insights: 0,
+            insights: Vec::new(),
+            sections: Vec::new(),
+        }
+    }
+
+    /// The summary to build on today: `stored` if it is from `date`, else a fresh one
+    pub fn for_day(stored: Option<DaySummary>, date: NaiveDate) -> Self {
+        stored
+            .filter(|summary| summary.date == date)
+            .unwrap_or_else(|| Self::new(date))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.insights.is_empty() && self.earlier_insights == 0
+    }
+
+    /// Add an insight shown at `time`
+    pub fn record_insight(&mut self, time: &str, emoji: &str, text: &str) {
+        self.insights.push(SummaryInsight {
+            time: time.to_string(),
+            emoji: emoji.to_string(),
+            gist: insight_memory::summarize(text),
+        });
+        if self.insights.len() > MAX_INSIGHTS {
+            let dropped = self.insights.len() - MAX_INSIGHTS;
+            self.insights.drain(..dropped);
+            self.earlier_insights += dropped as u32;
+        }
+    }
+
+    /// Split `sections` into those to send and the folded sections standing
+    /// in for the ones left out because they haven't changed since they were sent
+    pub fn compress(
+        &self,
+        sections: Vec<PromptSection>,
+    ) -> (Vec<PromptSection>, Vec<FoldedSection>) {
+        let mut kept = Vec::with_capacity(sections.len());
+        let mut unchanged = Vec::new();
+        for section in sections {
+            let folded = (section.priority < FOLDABLE_BELOW)
+                .then(|| self.folded(&section.name))
+                .flatten()
+                .filter(|folded| folded.fingerprint == fingerprint(&section.text));
+            match folded {
+                Some(folded) => unchanged.push(folded.clone()),
+                None => kept.push(section),
+            }
+        }
+        (kept, unchanged)
+    }
+
+    /// Remember the foldable sections sent in full at `time`
+    pub fn fold(&mut self, sections: &[PromptSection], time: &str) {
+        for section in sections.iter().filter(|s| s.priority < FOLDABLE_BELOW) {
+            let fingerprint = fingerprint(&section.text);
+            if self
+                .folded(&section.name)
+                .is_some_and(|folded| folded.fingerprint == fingerprint)
+            {
+                continue;
+            }
+            self.sections.retain(|folded| folded.name != section.name);
+            self.sections.push(FoldedSection {
+                name: section.name.clone(),
+                fingerprint,
+                headline: headline(&section.text),
+                since: time.to_string(),
+            });
+        }
+    }
+
+    /// Today's insights, one line each. None before the first insight of the day.
+    pub fn render(&self) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let mut lines = Vec::new();
+        if self.earlier_insights > 0 {
+            lines.push(format!(
+                "- ({} earlier insight{})",
+                self.earlier_insights,
+                if self.earlier_insights == 1 { "" } else { "s" }
+            ));
+        }
+        lines.extend(
+            self.insights
+                .iter()
+                .map(|i| format!("- {} {} {}", i.time, i.emoji, i.gist)),
+        );
+        Some(lines.join("\n"))
+    }
+
+    fn folded(&self, name: &str) -> Option<&FoldedSection> {
+        self.sections.iter().find(|folded| folded.name == name)
+    }
+}
+
+/// Prompt section listing the headlines of sections left out by `compress`
+pub fn unchanged_section(unchanged: &[FoldedSection]) -> Option<PromptSection> {
+    if unchanged.is_empty() {
+        return None;
+    }
+    let mut text = String::from("\nContext unchanged since it was sent earlier today:");
+    for folded in unchanged {
+        text.push_str(&format!(
+            "\n- {} (since {}): {}",
+            folded.name, folded.since, folded.headline
+        ));
+    }
+    Some(PromptSection::new(
+        "unchanged context",
+        priority::REQUIRED,
+        text,
+    ))
+}
+
+fn fingerprint(text: &str) -> String {
+    format!("{:x}", md5::compute(text))
+}
+
+/// The start of a section on one line, e.g. "Weather: 12°C, light rain; Rain until 2 PM"
+fn headline(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim().trim_start_matches("- "))
+        .filter(|line| !line.is_empty())
+        .collect();
+    let joined = lines.join("; ").replace(":;", ":");
+    text_budget::fit(&joined, Some(HEADLINE_CHARS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 5, 12).unwrap()
+    }
+
+    fn sections(weather: &str) -> Vec<PromptSection> {
+        vec![
+            PromptSection::new("trigger", priority::REQUIRED, "Trigger: heartbeat"),
+            PromptSection::new(
+                "primary calendar",
+                priority::PRIMARY_CALENDAR,
+                "\nYour calendar:\n- 3 PM Dentist",
+            ),
+            PromptSection::new("weather", priority::WEATHER, weather),
+        ]
+    }
+
+    #[test]
+    fn test_unchanged_sections_are_replaced_by_headline() {
+        let mut summary = DaySummary::new(day());
+        let weather = "\nWeather: 12°C, light rain\n- Rain until 2 PM";
+        let (kept, unchanged) = summary.compress(sections(weather));
+        assert_eq!(kept.len(), 3);
+        assert!(unchanged.is_empty());
+        summary.fold(&kept, "9:10 AM");
+        summary.record_insight(
+            "9:10 AM",
+            "🌧️",
+            "Rain until 2 — take the umbrella. Dentist at 3.",
+        );
+
+        let (kept, unchanged) = summary.compress(sections(weather));
+        let names: Vec<_> = kept.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["trigger", "primary calendar"]);
+        assert_eq!(
+            summary.render().unwrap(),
+            "- 9:10 AM 🌧️ Rain until 2 — take the umbrella."
+        );
+        let stand_in = unchanged_section(&unchanged).unwrap();
+        assert!(stand_in
+            .text
+            .contains("- weather (since 9:10 AM): Weather: 12°C, light rain"));
+
+        // A changed section is sent again in full
+        let (kept, unchanged) = summary.compress(sections("\nWeather: 18°C, sunny"));
+        assert_eq!(kept.len(), 3);
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn test_new_day_starts_fresh() {
+        let mut summary = DaySummary::new(day());
+        summary.record_insight("9:10 AM", "☕", "Quiet morning.");
+        assert!(
+            DaySummary::for_day(Some(summary.clone()), day())
+                .insights
+                .len()
+                == 1
+        );
+
+        let tomorrow = day().succ_opt().unwrap();
+        let fresh = DaySummary::for_day(Some(summary), tomorrow);
+        assert!(fresh.is_empty());
+        assert_eq!(fresh.render(), None);
+    }
+
+    #[test]
+    fn test_old_insights_are_counted() {
+        let mut summary = DaySummary::new(day());
+        for i in 0..MAX_INSIGHTS + 2 {
+            summary.record_insight(&format!("{}:00", i), "📋", "Something.");
+        }
+        assert_eq!(summary.insights.len(), MAX_INSIGHTS);
+        assert_eq!(summary.earlier_insights, 2);
+        assert!(summary
+            .render()
+            .unwrap()
+            .starts_with("- (2 earlier insights)"));
+    }
+}
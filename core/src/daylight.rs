@@ -0,0 +1,175 @@
+//! Sunrise and sunset for the configured location.
+//!
+//! Uses the standard sunrise equation (about a minute of accuracy, plenty for
+//! "your run will finish after dark"), with the weather section's latitude and
+//! longitude. Feeds a prompt section flagging outdoor events that cross dusk or
+//! dawn, and the `{sunrise}`, `{sunset}` and `{daylight}` template fields.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+
+use crate::activity::ActivityClassifier;
+use crate::significance_engine::CalendarEventSummary;
+
+const J2000: f64 = 2_451_545.0;
+const UNIX_EPOCH_JD: f64 = 2_440_587.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunTimes {
+    pub sunrise: DateTime<Utc>,
+    pub sunset: DateTime<Utc>,
+}
+
+impl SunTimes {
+    pub fn is_daylight(&self, at: DateTime<Utc>) -> bool {
+        self.sunrise <= at && at < self.sunset
+    }
+}
+
+/// Sunrise and sunset on `date` (local calendar date) at this location.
+/// None during polar day or night, when the sun doesn't cross the horizon.
+pub fn sun_times(date: NaiveDate, latitude: f64, longitude: f64) -> Option<SunTimes> {
+    let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1)?).num_days() as f64;
+    // Mean solar noon, in days since J2000 (longitude east-positive)
+    let solar_noon = days + 0.0008 - longitude / 360.0;
+    let anomaly = (357.5291 + 0.985_600_28 * solar_noon).rem_euclid(360.0);
+    let m = anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.0200 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit = J2000 + solar_noon + 0.0053 * m.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+    let declination = (ecliptic_longitude.sin() * 23.4397f64.to_radians().sin()).asin();
+
+    let phi = latitude.to_radians();
+    let cos_hour_angle = ((-0.833f64).to_radians().sin() - phi.sin() * declination.sin())
+        / (phi.cos() * declination.cos());
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+
+    Some(SunTimes {
+        sunrise: from_julian(transit - half_day)?,
+        sunset: from_julian(transit + half_day)?,
+    })
+}
+
+fn from_julian(jd: f64) -> Option<DateTime<Utc>> {
+    Utc.timestamp_opt(((jd - UNIX_EPOCH_JD) * 86_400.0).round() as i64, 0)
+        .single()
+}
+
+/// Outdoor events that start in daylight and end after sunset, or start
+/// before sunrise, as prompt lines ("Evening run (6:30 PM) finishes after dark — sunset 6:52 PM")
+pub fn dark_event_notes(
+    events: &[CalendarEventSummary],
+    latitude: f64,
+    longitude: f64,
+    activities: &ActivityClassifier,
+    now: &DateTime<FixedOffset>,
+) -> Vec<String> {
+    let tz = now.timezone();
+    let now_utc = now.to_utc();
+    let time = |t: DateTime<Utc>| t.with_timezone(&tz).format("%-I:%M %p").to_string();
+
+    events
+        .iter()
+        .filter(|e| !e.is_all_day && e.start_time > now_utc)
+        .filter(|e| activities.is_outdoor(e))
+        .filter_map(|event| {
+            let day = event.start_time.with_timezone(&tz).date_naive();
+            let sun = sun_times(day, latitude, longitude)?;
+            let end = event.end_time.unwrap_or(event.start_time);
+            if event.start_time < sun.sunrise {
+                Some(format!(
+                    "{} ({}) starts before sunrise — sunrise {}",
+                    event.title,
+                    time(event.start_time),
+                    time(sun.sunrise)
+                ))
+            } else if event.start_time < sun.sunset && end > sun.sunset {
+                Some(format!(
+                    "{} ({}) finishes after dark — sunset {}",
+                    event.title,
+                    time(event.start_time),
+                    time(sun.sunset)
+                ))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minutes_apart(a: DateTime<Utc>, b: DateTime<Utc>) -> i64 {
+        (a - b).num_minutes().abs()
+    }
+
+    #[test]
+    fn test_sun_times_match_almanac() {
+        // New York, summer solstice: sunrise 5:25 AM EDT, sunset 8:31 PM EDT
+        let date = NaiveDate::from_ymd_opt(2026, 6, 21).unwrap();
+        let sun = sun_times(date, 40.7128, -74.0060).unwrap();
+        let expected_rise = Utc.with_ymd_and_hms(2026, 6, 21, 9, 25, 0).unwrap();
+        let expected_set = Utc.with_ymd_and_hms(2026, 6, 22, 0, 31, 0).unwrap();
+        assert!(
+            minutes_apart(sun.sunrise, expected_rise) <= 3,
+            "{}",
+            sun.sunrise
+        );
+        assert!(
+            minutes_apart(sun.sunset, expected_set) <= 3,
+            "{}",
+            sun.sunset
+        );
+        assert!(sun.is_daylight(Utc.with_ymd_and_hms(2026, 6, 21, 18, 0, 0).unwrap()));
+
+        // Tromsø in December: polar night
+        let december = NaiveDate::from_ymd_opt(2026, 12, 21).unwrap();
+        assert!(sun_times(december, 69.65, 18.96).is_none());
+    }
+
+    #[test]
+    fn test_run_that_ends_after_sunset_is_flagged() {
+        // Detroit, early November: sunset around 5:20 PM EST
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 11, 9, 9, 0, 0).unwrap();
+        let at = |h, m| tz.with_ymd_and_hms(2026, 11, 9, h, m, 0).unwrap().to_utc();
+        let event = |title: &str, start, end| CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(end),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let events = vec![
+            event("Evening run", at(17, 0), at(18, 0)),
+            event("Team dinner", at(17, 0), at(19, 0)),
+            event("Lunch walk", at(12, 0), at(12, 30)),
+        ];
+
+        let notes = dark_event_notes(
+            &events,
+            42.3314,
+            -83.0458,
+            &ActivityClassifier::default(),
+            &now,
+        );
+        assert_eq!(notes.len(), 1, "{:?}", notes);
+        assert!(notes[0].starts_with("Evening run (5:00 PM) finishes after dark — sunset 5:"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Links from an insight to the things it mentions.
+//!
+//! Once an insight is generated, the calendar events, tasks and notes whose
+//! titles appear in its text become actions: the event in Google Calendar,
+//! the task in Todoist, the note in Obsidian. They are matched locally, so
+//! the model never supplies a URI. Actions are stored with the insight and
+//! travel with it over D-Bus, as the desktop notification's click action and
+//! as the ntfy click URL. An event's first attachment or description link
+//! (see `attachments`) is offered alongside it.
+
+use crate::attachments::EventLink;
+use crate::significance_engine::ContextSnapshot;
+
+/// Most actions attached to one insight
+const MAX_ACTIONS: usize = 3;
+/// Shorter titles ("Gym", "1:1") turn up in too much ordinary text
+const MIN_TITLE_CHARS: usize = 4;
+
+/// Something to open from an insight
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InsightAction {
+    /// e.g. "Open \"Dentist\" in Google Calendar"
+    pub label: String,
+    pub uri: String,
+}
+
+/// Something an insight mentions that can be opened
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subject {
+    /// Calendar event, by its Google event ID
+    Event {
+        id: String,
+        title: String,
+    },
+    TodoistTask {
+        id: String,
+        title: String,
+    },
+    /// Obsidian note, by file name without the extension
+    Note {
+        file: String,
+        title: String,
+    },
+}
+
+impl Subject {
+    pub fn title(&self) -> &str {
+        match self {
+            Subject::Event { title, .. }
+            | Subject::TodoistTask { title, .. }
+            | Subject::Note { title, .. } => title,
+        }
+    }
+
+    /// The action opening this subject. Events need their Google Calendar
+    /// page, notes the Obsidian vault name.
+    pub fn action(&self, event_link: Option<&str>, vault: Option<&str>) -> Option<InsightAction> {
+        let (app, uri) = match self {
+            Subject::Event { .. } => ("Google Calendar", event_link?.to_string()),
+            Subject::TodoistTask { id, .. } => (
+                "Todoist",
+                format!(
+                    "https://app.todoist.com/app/task/{}",
+                    urlencoding::encode(id)
+                ),
+            ),
+            Subject::Note { file, .. } => (
+                "Obsidian",
+                format!(
+                    "obsidian://open?vault={}&file={}",
+                    urlencoding::encode(vault?),
+                    urlencoding::encode(file)
+                ),
+            ),
+        };
+        Some(InsightAction {
+            label: format!("Open \"{}\" in {}", self.title(), app),
+            uri,
+        })
+    }
+}
+
+/// Events, tasks and projects from `context` whose titles appear in `text`,
+/// in the order they are mentioned. Where titles overlap ("Review" inside
+/// "Design review") the longer one wins.
+pub fn subjects(text: &str, context: &ContextSnapshot) -> Vec<Subject> {
+    let mut candidates: Vec<Subject> = Vec::new();
+    for event in &context.calendar_events {
+        candidates.push(Subject::Event {
+            id: event.id.clone(),
+            title: event.title.clone(),
+        });
+    }
+    for task in context.tasks.iter().filter(|t| !t.completed) {
+        match task.source.as_str() {
+            "todoist" => candidates.push(Subject::TodoistTask {
+                id: task.id.clone(),
+                title: task.title.clone(),
+            }),
+            // Obsidian task IDs are "<file>.md:<line>:<text>"
+            "obsidian" => {
+                if let Some((file, _)) = task.id.split_once(".md:") {
+                    candidates.push(Subject::Note {
+                        file: file.to_string(),
+                        title: task.title.clone(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(ref notes) = context.notes_context {
+        for project in &notes.active_projects {
+            candidates.push(Subject::Note {
+                file: project.id.clone(),
+                title: project.name.clone(),
+            });
+        }
+    }
+
+    let haystack = text.to_lowercase();
+    candidates.retain(|c| c.title().trim().chars().count() >= MIN_TITLE_CHARS);
+    // Longest first, so a shorter title inside a longer one can't claim its span
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.title().len()));
+
+    let mut matched: Vec<(usize, usize, Subject)> = Vec::new();
+    for candidate in candidates {
+        let needle = candidate.title().trim().to_lowercase();
+        let Some(start) = haystack.find(&needle) else {
+            continue;
+        };
+        let end = start + needle.len();
+        let overlaps = matched.iter().any(|(s, e, _)| start < *e && *s < end);
+        // A recurring event matches once, for its first occurrence
+        let repeated = matched
+            .iter()
+            .any(|(_, _, m)| m.title() == candidate.title());
+        if !overlaps && !repeated {
+            matched.push((start, end, candidate));
+        }
+    }
+    matched.sort_by_key(|(start, _, _)| *start);
+    matched
+        .into_iter()
+        .map(|(_, _, subject)| subject)
+        .take(MAX_ACTIONS)
+        .collect()
+}
+
+/// The action opening a link on an event, e.g. its agenda doc
+pub fn attachment_action(event_title: &str, link: &EventLink) -> InsightAction {
+    InsightAction {
+        label: format!("Open \"{}\" for \"{}\"", link.label(), event_title),
+        uri: link.url.clone(),
+    }
+}
+
+/// Google Calendar page of an event, from its stored API data
+pub fn event_link(raw_data_json: &str) -> Option<String> {
+    let raw: serde_json::Value = serde_json::from_str(raw_data_json).ok()?;
+    raw["htmlLink"]
+        .as_str()
+        .filter(|link| link.starts_with("https://"))
+        .map(str::to_string)
+}
+
+/// Vault name Obsidian URIs refer to: the vault folder's name
+pub fn vault_name(vault_path: &str) -> Option<String> {
+    std::path::Path::new(vault_path.trim_end_matches('/'))
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::significance_engine::{CalendarEventSummary, TaskSummary};
+    use chrono::Utc;
+
+    fn event(id: &str, title: &str) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: id.to_string(),
+            title: title.to_string(),
+            start_time: Utc::now(),
+            end_time: None,
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    fn task(id: &str, title: &str, source: &str) -> TaskSummary {
+        TaskSummary {
+            id: id.to_string(),
+            title: title.to_string(),
+            due: None,
+            completed: false,
+            priority: 5,
+            urgency: 5,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_subjects_follow_the_insight_text() {
+        let context = ContextSnapshot {
+            calendar_events: vec![
+                event("ev1", "Review"),
+                event("ev2", "Design review"),
+                event("ev3", "Dentist"),
+                event("ev4", "Gym"),
+            ],
+            weather: None,
+            tasks: vec![
+                task("8812", "File quarterly taxes", "todoist"),
+                task(
+                    "2026-05-12.md:4:Call the plumber",
+                    "Call the plumber",
+                    "obsidian",
+                ),
+            ],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: String::new(),
+        };
+        let text = "Dentist overlaps the design review — call the plumber after Gym, \
+                    and file quarterly taxes tonight.";
+
+        let subjects = subjects(text, &context);
+        let titles: Vec<&str> = subjects.iter().map(Subject::title).collect();
+        assert_eq!(titles, vec!["Dentist", "Design review", "Call the plumber"]);
+
+        let note = subjects[2].action(None, Some("My Vault")).unwrap();
+        assert_eq!(note.uri, "obsidian://open?vault=My%20Vault&file=2026-05-12");
+        assert_eq!(note.label, "Open \"Call the plumber\" in Obsidian");
+        // No Google Calendar page stored, no action
+        assert_eq!(subjects[0].action(None, None), None);
+        assert_eq!(
+            event_link(
+                r#"{"id":"ev3","htmlLink":"https://www.google.com/calendar/event?eid=abc"}"#
+            )
+            .as_deref(),
+            Some("https://www.google.com/calendar/event?eid=abc")
+        );
+        assert_eq!(
+            vault_name("~/Documents/My Vault/").as_deref(),
+            Some("My Vault")
+        );
+    }
+}
@@ -0,0 +1,559 @@
+//! Demo profiles seeded with realistic, made-up data.
+//!
+//! `demo --scenario <name>` writes a self-contained profile (config, database,
+//! Obsidian-style vault and a weather fixture) so the whole pipeline can run
+//! without Google, weather or Obsidian accounts. All times are relative to the
+//! moment the profile is seeded.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, Timelike};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::context_sources::{UnitSystem, WeatherContext, WeatherForecast};
+use crate::database::{Database, DatabaseInner, Event};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Scenario {
+    /// Family logistics: overlapping pickups, appointments, school tasks
+    BusyParent,
+    /// A work trip tomorrow: flight, hotel, a meeting that collides with travel
+    Traveler,
+    /// Back-to-back meetings in the run-up to a launch deadline
+    CrunchWeek,
+}
+
+struct DemoCalendar {
+    id: &'static str,
+    name: &'static str,
+    access_role: &'static str,
+    is_primary: bool,
+}
+
+struct DemoEvent {
+    calendar: &'static str,
+    title: &'static str,
+    /// Minutes after the top of the current hour
+    starts_in: i64,
+    duration: i64,
+    location: Option<&'static str>,
+}
+
+struct DemoWeather {
+    current: &'static str,
+    /// (high, low, conditions, precipitation chance 0-1) for today and the next two days
+    days: [(f32, f32, &'static str, f32); 3],
+    alerts: &'static [&'static str],
+}
+
+const HOUR: i64 = 60;
+const DAY: i64 = 24 * HOUR;
+
+impl Scenario {
+    pub fn name(self) -> &'static str {
+        match self {
+            Scenario::BusyParent => "busy-parent",
+            Scenario::Traveler => "traveler",
+            Scenario::CrunchWeek => "crunch-week",
+        }
+    }
+
+    fn calendars(self) -> &'static [DemoCalendar] {
+        const PRIMARY: DemoCalendar = DemoCalendar {
+            id: "demo-primary",
+            name: "Personal",
+            access_role: "owner",
+            is_primary: true,
+        };
+        match self {
+            Scenario::BusyParent => &[
+                PRIMARY,
+                DemoCalendar {
+                    id: "demo-family",
+                    name: "Family",
+                    access_role: "owner",
+                    is_primary: false,
+                },
+                DemoCalendar {
+                    id: "demo-partner",
+                    name: "Alex",
+                    access_role: "reader",
+                    is_primary: false,
+                },
+            ],
+            Scenario::Traveler => &[
+                PRIMARY,
+                DemoCalendar {
+                    id: "demo-trips",
+                    name: "Trips",
+                    access_role: "owner",
+                    is_primary: false,
+                },
+            ],
+            Scenario::CrunchWeek => &[
+                PRIMARY,
+                DemoCalendar {
+                    id: "demo-team",
+                    name: "Platform Team",
+                    access_role: "writer",
+                    is_primary: false,
+                },
+            ],
+        }
+    }
+
+    fn events(self) -> Vec<DemoEvent> {
+        let event = |calendar, title, starts_in, duration, location| DemoEvent {
+            calendar,
+            title,
+            starts_in,
+            duration,
+            location,
+        };
+        match self {
+            Scenario::BusyParent => vec![
+                event("demo-primary", "Team sync", HOUR, 30, Some("Video call")),
+                event(
+                    "demo-family",
+                    "Maya soccer practice",
+                    3 * HOUR,
+                    90,
+                    Some("Riverside Park, Field 2"),
+                ),
+                event(
+                    "demo-partner",
+                    "Client dinner",
+                    3 * HOUR + 30,
+                    120,
+                    Some("Marrow, 2338 Gratiot Ave"),
+                ),
+                event(
+                    "demo-family",
+                    "Leo dentist checkup",
+                    DAY + 2 * HOUR,
+                    45,
+                    Some("Bright Smiles Dental"),
+                ),
+                event(
+                    "demo-family",
+                    "School bake sale drop-off",
+                    DAY + 23 * HOUR,
+                    30,
+                    Some("Lincoln Elementary"),
+                ),
+                event(
+                    "demo-family",
+                    "Grandma's birthday lunch",
+                    2 * DAY + 4 * HOUR,
+                    120,
+                    Some("Grandma's house"),
+                ),
+            ],
+            Scenario::Traveler => vec![
+                event(
+                    "demo-primary",
+                    "Hand-off notes to Priya",
+                    2 * HOUR,
+                    30,
+                    Some("Video call"),
+                ),
+                event(
+                    "demo-trips",
+                    "Flight DL 1432 DTW → SEA",
+                    DAY - 2 * HOUR,
+                    300,
+                    Some("DTW McNamara Terminal"),
+                ),
+                event(
+                    "demo-primary",
+                    "Weekly 1:1 with Sam",
+                    DAY,
+                    30,
+                    Some("Video call"),
+                ),
+                event(
+                    "demo-trips",
+                    "Hotel check-in: The Maxwell",
+                    DAY + 5 * HOUR,
+                    30,
+                    Some("300 Roy St, Seattle"),
+                ),
+                event(
+                    "demo-primary",
+                    "Customer workshop",
+                    2 * DAY,
+                    240,
+                    Some("Contoso HQ, Seattle"),
+                ),
+                event(
+                    "demo-trips",
+                    "Flight DL 2210 SEA → DTW",
+                    3 * DAY + 2 * HOUR,
+                    270,
+                    Some("SEA-TAC Airport"),
+                ),
+            ],
+            Scenario::CrunchWeek => vec![
+                event("demo-team", "Standup", HOUR, 15, Some("Video call")),
+                event(
+                    "demo-team",
+                    "Design review: billing v2",
+                    HOUR + 15,
+                    60,
+                    Some("Room 4B"),
+                ),
+                event(
+                    "demo-primary",
+                    "Incident retro",
+                    2 * HOUR + 15,
+                    45,
+                    Some("Video call"),
+                ),
+                event(
+                    "demo-team",
+                    "Roadmap planning",
+                    3 * HOUR,
+                    60,
+                    Some("Room 4B"),
+                ),
+                event(
+                    "demo-primary",
+                    "Interview: backend candidate",
+                    DAY + HOUR,
+                    60,
+                    Some("Video call"),
+                ),
+                event(
+                    "demo-team",
+                    "Release go/no-go",
+                    2 * DAY + 2 * HOUR,
+                    30,
+                    Some("Room 4B"),
+                ),
+                event("demo-team", "Billing v2 launch", 3 * DAY, 60, None),
+            ],
+        }
+    }
+
+    fn daily_note(self) -> &'static str {
+        match self {
+            Scenario::BusyParent => {
+                "---\nmood: frazzled\nenergy: 2\n---\n\
+                 # Today\n\n\
+                 ## Focus\n\
+                 - Sort out who picks up Maya from soccer\n\
+                 - Bake sale prep\n\n\
+                 ## Todo\n\
+                 - [ ] Sign Leo's field trip permission slip\n\
+                 - [ ] Buy cupcake ingredients\n\
+                 - [ ] RSVP to the Patel birthday party\n\
+                 - [x] Pay swim lesson invoice\n\n\
+                 ## Notes\n\
+                 - Alex has a client dinner tonight, can't do pickup\n\
+                 - Leo mentioned a loose tooth\n"
+            }
+            Scenario::Traveler => {
+                "---\nmood: focused\nenergy: 3\n---\n\
+                 # Today\n\n\
+                 ## Focus\n\
+                 - Wrap up before the Seattle trip\n\n\
+                 ## Todo\n\
+                 - [ ] Online check-in for DL 1432\n\
+                 - [ ] Pack laptop charger and clicker\n\
+                 - [ ] Ask neighbour to water plants\n\
+                 - [ ] Print workshop agenda\n\
+                 - [x] Book airport parking\n\n\
+                 ## Notes\n\
+                 - Sam 1:1 lands while I'm in the air; move it or cancel\n"
+            }
+            Scenario::CrunchWeek => {
+                "---\nmood: stretched\nenergy: 2\n---\n\
+                 # Today\n\n\
+                 ## Focus\n\
+                 - Billing v2 launch readiness\n\n\
+                 ## Todo\n\
+                 - [ ] Finish migration guide\n\
+                 - [ ] Review rate-limit PR\n\
+                 - [ ] Prepare interview questions\n\
+                 - [ ] Update launch checklist\n\
+                 - [x] Write incident timeline\n\n\
+                 ## Notes\n\
+                 - No lunch break between design review and retro\n\
+                 - Load test still flaky on staging\n"
+            }
+        }
+    }
+
+    /// (file stem, days until due, progress, body)
+    fn project(self) -> (&'static str, i64, f32, &'static str) {
+        match self {
+            Scenario::BusyParent => (
+                "Kitchen Renovation",
+                10,
+                0.3,
+                "- [ ] Pick cabinet colour\n- [ ] Confirm contractor start date\n",
+            ),
+            Scenario::Traveler => (
+                "Seattle Customer Workshop",
+                2,
+                0.7,
+                "- [ ] Finalise demo script\n- [ ] Send attendee list to security desk\n",
+            ),
+            Scenario::CrunchWeek => (
+                "Billing v2 Launch",
+                3,
+                0.6,
+                "- [ ] Close remaining P1 bugs\n- [ ] Sign-off from finance\n- [ ] Status page copy\n",
+            ),
+        }
+    }
+
+    fn weather(self) -> DemoWeather {
+        match self {
+            Scenario::BusyParent => DemoWeather {
+                current: "Light rain, 54°F",
+                days: [
+                    (57.0, 46.0, "Rain", 0.8),
+                    (61.0, 48.0, "Cloudy", 0.3),
+                    (66.0, 50.0, "Sunny", 0.05),
+                ],
+                alerts: &[],
+            },
+            Scenario::Traveler => DemoWeather {
+                current: "Clear, 41°F",
+                days: [
+                    (48.0, 35.0, "Clear", 0.0),
+                    (45.0, 38.0, "Heavy rain", 0.9),
+                    (47.0, 40.0, "Showers", 0.6),
+                ],
+                alerts: &["Wind Advisory"],
+            },
+            Scenario::CrunchWeek => DemoWeather {
+                current: "Partly cloudy, 68°F",
+                days: [
+                    (72.0, 55.0, "Partly cloudy", 0.1),
+                    (75.0, 58.0, "Sunny", 0.0),
+                    (70.0, 57.0, "Thunderstorms", 0.7),
+                ],
+                alerts: &[],
+            },
+        }
+    }
+}
+
+/// Paths of a seeded demo profile
+pub struct DemoProfile {
+    pub config_path: PathBuf,
+    pub data_dir: PathBuf,
+    pub events: usize,
+}
+
+/// Default location for a fresh profile: a new directory under the system temp dir
+pub fn default_profile_dir(scenario: Scenario) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "jasper-demo-{}-{}",
+        scenario.name(),
+        Local::now().format("%Y%m%d%H%M%S")
+    ))
+}
+
+/// Write a complete profile for `scenario` into `dir`, which must be empty or missing
+pub async fn seed_profile(
+    scenario: Scenario,
+    dir: &Path,
+    now: DateTime<Local>,
+) -> Result<DemoProfile> {
+    if dir.exists()
+        && std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read {:?}", dir))?
+            .next()
+            .is_some()
+    {
+        return Err(anyhow::anyhow!(
+            "{:?} is not empty; pick another --dir or remove it first",
+            dir
+        ));
+    }
+
+    let data_dir = dir.join("data");
+    let vault = dir.join("vault");
+    for sub in [
+        data_dir.clone(),
+        vault.join(".obsidian"),
+        vault.join("Work/Daily"),
+        vault.join("Work/Projects"),
+    ] {
+        std::fs::create_dir_all(&sub).with_context(|| format!("Failed to create {:?}", sub))?;
+    }
+
+    let base = now
+        .with_minute(0)
+        .and_then(|t| t.with_second(0))
+        .and_then(|t| t.with_nanosecond(0))
+        .unwrap_or(now);
+
+    // Calendar events
+    let database: Database = DatabaseInner::new(&data_dir.join("jasper.db"), None)
+        .await
+        .context("Failed to create demo database")?;
+    let scenario_events = scenario.events();
+    for calendar in scenario.calendars() {
+        let calendar_db_id = database.create_or_update_calendar(
+            calendar.id,
+            calendar.name,
+            Some("google"),
+            Some(calendar.access_role),
+            calendar.is_primary,
+        )?;
+        let events: Vec<Event> = scenario_events
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.calendar == calendar.id)
+            .map(|(n, e)| {
+                let start = base + Duration::minutes(e.starts_in);
+                Event {
+                    id: 0,
+                    source_id: format!("demo-{}-{}", scenario.name(), n),
+                    calendar_id: calendar_db_id,
+                    title: Some(e.title.to_string()),
+                    description: None,
+                    start_time: start.timestamp(),
+                    end_time: Some((start + Duration::minutes(e.duration)).timestamp()),
+                    location: e.location.map(str::to_string),
+                    event_type: None,
+                    participants: None,
+                    raw_data_json: None,
+                    is_all_day: Some(false),
+                    meeting_url: None,
+                    links: None,
+                    my_response: None,
+                }
+            })
+            .collect();
+        database.sync_calendar_events(calendar_db_id, &events)?;
+    }
+
+    // Obsidian-style notes
+    let today = now.date_naive();
+    std::fs::write(
+        vault.join(format!("Work/Daily/{}.md", today.format("%Y-%m-%d"))),
+        scenario.daily_note(),
+    )?;
+    let (project, due_in_days, progress, body) = scenario.project();
+    std::fs::write(
+        vault.join(format!("Work/Projects/{}.md", project)),
+        format!(
+            "---\nname: {}\nstatus: Active\ndue_date: \"{}\"\npriority: 8\nprogress: {}\n---\n{}",
+            project,
+            (today + Duration::days(due_in_days)).format("%Y-%m-%d"),
+            progress,
+            body
+        ),
+    )?;
+
+    // Weather fixture
+    let weather = scenario.weather();
+    let fixture = WeatherContext {
+        current_conditions: weather.current.to_string(),
+        forecast: weather
+            .days
+            .iter()
+            .enumerate()
+            .map(
+                |(offset, &(high, low, conditions, precipitation))| WeatherForecast {
+                    // Noon UTC of the local date, as the weather API reports days
+                    date: (today + Duration::days(offset as i64))
+                        .and_hms_opt(12, 0, 0)
+                        .unwrap()
+                        .and_utc(),
+                    temperature_high: high,
+                    temperature_low: low,
+                    conditions: conditions.to_string(),
+                    precipitation_chance: precipitation,
+                    description: format!("{}, high {:.0}°F", conditions, high),
+                },
+            )
+            .collect(),
+        alerts: weather.alerts.iter().map(|a| a.to_string()).collect(),
+        units: UnitSystem::Imperial,
+    };
+    let fixture_path = dir.join("weather.json");
+    std::fs::write(&fixture_path, serde_json::to_string_pretty(&fixture)?)?;
+
+    // Config pointing everything at the files above
+    let mut config = Config::default();
+    config.ai.provider = crate::mock_ai::PROVIDER.to_string();
+    if let Some(ref mut gc) = config.google_calendar {
+        gc.enabled = false;
+    }
+    if let Some(ref mut sources) = config.context_sources {
+        if let Some(ref mut obsidian) = sources.obsidian {
+            obsidian.enabled = true;
+            obsidian.vault_path = vault.to_string_lossy().to_string();
+        }
+        if let Some(ref mut weather) = sources.weather {
+            weather.enabled = true;
+            weather.fixture_path = Some(fixture_path.to_string_lossy().to_string());
+        }
+    }
+    let config_path = dir.join("config.toml");
+    std::fs::write(
+        &config_path,
+        toml::to_string_pretty(&config).context("Failed to serialize demo config")?,
+    )?;
+
+    Ok(DemoProfile {
+        config_path,
+        data_dir,
+        events: scenario_events.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[tokio::test]
+    async fn test_seeded_profile_is_self_contained() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Local::now();
+        let profile = seed_profile(Scenario::BusyParent, dir.path(), now)
+            .await
+            .unwrap();
+
+        let database: Database = DatabaseInner::new(&profile.data_dir.join("jasper.db"), None)
+            .await
+            .unwrap();
+        let grouped = database
+            .get_events_by_calendar(
+                now.with_timezone(&Utc) - Duration::hours(1),
+                now.with_timezone(&Utc) + Duration::days(7),
+                &[],
+                &[],
+            )
+            .unwrap();
+        assert_eq!(grouped.len(), 3);
+        assert_eq!(
+            grouped.iter().map(|g| g.events.len()).sum::<usize>(),
+            profile.events
+        );
+
+        let note = dir
+            .path()
+            .join(format!("vault/Work/Daily/{}.md", now.format("%Y-%m-%d")));
+        assert!(std::fs::read_to_string(note).unwrap().contains("- [ ] "));
+
+        let config: Config =
+            toml::from_str(&std::fs::read_to_string(&profile.config_path).unwrap()).unwrap();
+        let weather = config.get_weather_config().unwrap();
+        let fixture = std::fs::read_to_string(weather.fixture_path.as_ref().unwrap()).unwrap();
+        assert!(serde_json::from_str::<WeatherContext>(&fixture).is_ok());
+
+        // Seeding over an existing profile is refused rather than mixing scenarios
+        assert!(seed_profile(Scenario::Traveler, dir.path(), now)
+            .await
+            .is_err());
+    }
+}
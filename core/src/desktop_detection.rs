@@ -0,0 +1,65 @@
+//! Detects what kind of session the daemon is running in.
+//!
+//! On a desktop the daemon serves frontends over the session D-Bus. On a
+//! server or in a container there is no session bus, so the daemon runs
+//! headless instead of failing at startup.
+
+use std::path::Path;
+
+/// Whether a session D-Bus is reachable (explicit address or the systemd user bus socket)
+pub fn session_bus_available() -> bool {
+    let runtime_bus = std::env::var_os("XDG_RUNTIME_DIR")
+        .map(|dir| Path::new(&dir).join("bus").exists())
+        .unwrap_or(false);
+    bus_available(
+        std::env::var_os("DBUS_SESSION_BUS_ADDRESS").as_deref(),
+        runtime_bus,
+    )
+}
+
+fn bus_available(address: Option<&std::ffi::OsStr>, runtime_bus: bool) -> bool {
+    address.map(|a| !a.is_empty()).unwrap_or(false) || runtime_bus
+}
+
+/// Application sandbox the daemon is running inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sandbox {
+    Flatpak,
+    Snap,
+}
+
+/// Detect a sandbox; inside one, host tools and the keyring must go through portals
+pub fn sandbox() -> Option<Sandbox> {
+    if Path::new("/.flatpak-info").exists() || std::env::var_os("FLATPAK_ID").is_some() {
+        Some(Sandbox::Flatpak)
+    } else if std::env::var_os("SNAP").is_some() {
+        Some(Sandbox::Snap)
+    } else {
+        None
+    }
+}
+
+pub fn is_sandboxed() -> bool {
+    sandbox().is_some()
+}
+
+/// Run without D-Bus and frontends when there is no session bus to serve them on
+pub fn is_headless_environment() -> bool {
+    !session_bus_available()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bus_detection() {
+        assert!(bus_available(
+            Some(std::ffi::OsStr::new("unix:path=/run/user/1000/bus")),
+            false
+        ));
+        assert!(bus_available(None, true));
+        assert!(!bus_available(Some(std::ffi::OsStr::new("")), false));
+        assert!(!bus_available(None, false));
+    }
+}
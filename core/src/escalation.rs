@@ -0,0 +1,216 @@
+//! Escalation for critical items that keep coming up without action.
+//!
+//! Critical items are overdue high-priority tasks and double-bookings that
+//! involve the user's own calendar. Each generated insight advances a streak
+//! for every critical item still in the context; completing the task,
+//! resolving the conflict or `jasper-companion-daemon dismiss` ends it. Once
+//! a streak reaches `[escalation] after_insights`, the prompt asks for blunter
+//! wording, a critical-urgency notification is shown and, if configured, an
+//! email is sent — each only once per item.
+
+use chrono::{DateTime, FixedOffset};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::config::EscalationConfig;
+use crate::database::EscalationRecord;
+use crate::errors::{JasperError, JasperResult};
+use crate::significance_engine::ContextSnapshot;
+
+/// Something serious enough to escalate if it keeps being ignored
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CriticalItem {
+    /// Stable identity across checks: "task:<id>" or "conflict:<id>+<id>"
+    pub key: String,
+    /// Human-readable description, e.g. "Overdue: File quarterly taxes (due Mon 12 May)"
+    pub label: String,
+}
+
+/// Critical items in the current context, per the policy
+pub fn critical_items(
+    context: &ContextSnapshot,
+    now: &DateTime<FixedOffset>,
+    policy: &EscalationConfig,
+) -> Vec<CriticalItem> {
+    let tz = now.timezone();
+    let now_utc = now.to_utc();
+    let mut items: Vec<CriticalItem> = context
+        .tasks
+        .iter()
+        .filter(|t| !t.completed && t.priority >= policy.min_task_priority)
+        .filter_map(|t| {
+            let due = t.due.filter(|due| *due < now_utc)?;
+            Some(CriticalItem {
+                key: format!("task:{}", t.id),
+                label: format!(
+                    "Overdue: {} (due {})",
+                    t.title,
+                    due.with_timezone(&tz).format("%a %-d %b")
+                ),
+            })
+        })
+        .collect();
+
+    if policy.include_conflicts {
+        let upcoming: Vec<_> = context
+            .calendar_events
+            .iter()
+            .filter(|e| !e.is_all_day && e.is_own_calendar && e.start_time > now_utc)
+            .collect();
+        for (i, a) in upcoming.iter().enumerate() {
+            for b in &upcoming[i + 1..] {
+                let (Some(a_end), Some(b_end)) = (a.end_time, b.end_time) else {
+                    continue;
+                };
+                // Only the user's own double-bookings; family overlaps are logistics
+                let overlaps = a.start_time < b_end && b.start_time < a_end;
+                if overlaps && a.is_primary_calendar && b.is_primary_calendar {
+                    let (first, second) = if a.id <= b.id { (a, b) } else { (b, a) };
+                    items.push(CriticalItem {
+                        key: format!("conflict:{}+{}", first.id, second.id),
+                        label: format!(
+                            "Conflict: {} and {} ({})",
+                            first.title,
+                            second.title,
+                            a.start_time
+                                .max(b.start_time)
+                                .with_timezone(&tz)
+                                .format("%a %-I:%M %p")
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    items
+}
+
+/// Records past the threshold that haven't been dismissed
+pub fn escalated<'a>(
+    records: &'a [EscalationRecord],
+    policy: &EscalationConfig,
+) -> Vec<&'a EscalationRecord> {
+    records
+        .iter()
+        .filter(|r| !r.dismissed && r.streak >= policy.after_insights)
+        .collect()
+}
+
+/// System prompt addition asking for stronger wording
+pub fn prompt_section(escalated: &[&EscalationRecord], title: &str) -> Option<String> {
+    if escalated.is_empty() {
+        return None;
+    }
+    let items = escalated
+        .iter()
+        .map(|r| format!("- {} (raised in {} insights in a row)", r.label, r.streak))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(format!(
+        "ESCALATE — these critical items keep coming up and {title} hasn't acted on them:\n{items}\n\
+         Lead with the most serious one. Be direct rather than gentle: say plainly what happens if it \
+         slips further and the single concrete step to take now. This overrides the usual \
+         don't-repeat guidance for these items."
+    ))
+}
+
+/// Hand an email to the local MTA (`sendmail -t`), which reads the recipients from the headers
+pub fn send_email(sendmail: &str, to: &str, subject: &str, body: &str) -> JasperResult<()> {
+    let message = format!(
+        "To: {}\nSubject: {}\nContent-Type: text/plain; charset=utf-8\n\n{}\n",
+        to, subject, body
+    );
+    let mut child = Command::new(sendmail)
+        .args(["-t", "-i"])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| JasperError::Internal {
+            message: format!("Failed to run {}: {}", sendmail, e),
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(message.as_bytes())
+            .map_err(|e| JasperError::Internal {
+                message: format!("Failed to write to {}: {}", sendmail, e),
+            })?;
+    }
+    let status = child.wait().map_err(|e| JasperError::Internal {
+        message: format!("{} did not finish: {}", sendmail, e),
+    })?;
+    if !status.success() {
+        return Err(JasperError::Internal {
+            message: format!("{} exited with {}", sendmail, status),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::significance_engine::{CalendarEventSummary, TaskSummary};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_critical_items_are_overdue_priority_tasks_and_own_conflicts() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 5, 14, 9, 0, 0).unwrap();
+        let at = |day, h| tz.with_ymd_and_hms(2026, 5, day, h, 0, 0).unwrap().to_utc();
+        let task = |id: &str, priority, due| TaskSummary {
+            id: id.to_string(),
+            title: id.to_string(),
+            due: Some(due),
+            completed: false,
+            priority,
+            urgency: priority,
+            source: "local".to_string(),
+        };
+        let event = |id: &str, start, primary| CalendarEventSummary {
+            id: id.to_string(),
+            title: id.to_string(),
+            start_time: start,
+            end_time: Some(start + chrono::Duration::hours(1)),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: primary,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let context = ContextSnapshot {
+            calendar_events: vec![
+                event("Dentist", at(14, 14), true),
+                event("Client call", at(14, 14), true),
+                event("Soccer", at(14, 14), false),
+            ],
+            weather: None,
+            tasks: vec![
+                task("Taxes", 10, at(12, 17)),
+                task("Tidy garage", 3, at(12, 17)),
+                task("Renew passport", 8, at(20, 17)),
+            ],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: String::new(),
+        };
+
+        let items = critical_items(&context, &now, &EscalationConfig::default());
+        let keys: Vec<&str> = items.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(keys, ["task:Taxes", "conflict:Client call+Dentist"]);
+        assert_eq!(items[0].label, "Overdue: Taxes (due Tue 12 May)");
+        assert_eq!(
+            items[1].label,
+            "Conflict: Client call and Dentist (Thu 2:00 PM)"
+        );
+    }
+}
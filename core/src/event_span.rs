@@ -0,0 +1,192 @@
+//! Events that run past midnight or over several days.
+//!
+//! A conference or trip is stored as one event from its first day to its
+//! last (an all-day event ends at the exclusive midnight after). The
+//! calendar is read by overlap, so such an event stays in context while
+//! it's under way, and the prompt says where today falls in it ("day 2 of
+//! 3") rather than an "all day" that reads as today only. A timed event that
+//! crosses midnight but lasts under a day (a late flight) isn't multi-day;
+//! its end time just names the day it falls on.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+
+use crate::significance_engine::CalendarEventSummary;
+
+/// The local dates a multi-day event covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl Span {
+    /// Days covered, counting both ends
+    pub fn days(&self) -> i64 {
+        (self.last - self.first).num_days() + 1
+    }
+
+    /// Which day of the span `date` is (1-based), if it's in it
+    pub fn day_of(&self, date: NaiveDate) -> Option<i64> {
+        (self.first <= date && date <= self.last).then(|| (date - self.first).num_days() + 1)
+    }
+}
+
+/// The dates `event` covers in `tz`, when it's multi-day: all-day over
+/// more than one date, or timed and lasting a day or more
+pub fn multi_day<Tz: TimeZone>(event: &CalendarEventSummary, tz: &Tz) -> Option<Span> {
+    let end = event.end_time?;
+    if !event.is_all_day && end - event.start_time < Duration::days(1) {
+        return None;
+    }
+    // An all-day end is the midnight after the last day
+    let last_instant = if event.is_all_day {
+        end - Duration::seconds(1)
+    } else {
+        end
+    };
+    let span = Span {
+        first: event.start_time.with_timezone(tz).date_naive(),
+        last: last_instant.with_timezone(tz).date_naive(),
+    };
+    (span.days() > 1).then_some(span)
+}
+
+/// `date` relative to `today`: "today", "tomorrow", a weekday within the
+/// week, else the date
+fn day_label(today: NaiveDate, date: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "today".to_string(),
+        1 => "tomorrow".to_string(),
+        2..=6 => date.format("%a").to_string(),
+        _ => date.format("%a %-d %b").to_string(),
+    }
+}
+
+/// `at` as a clock time, followed by its day when that isn't today:
+/// "2:00 AM tomorrow"
+pub fn clock_from(now: &DateTime<FixedOffset>, at: &DateTime<Utc>) -> String {
+    let local = at.with_timezone(&now.timezone());
+    let clock = local.format("%-I:%M %p").to_string();
+    match day_label(now.date_naive(), local.date_naive()).as_str() {
+        "today" => clock,
+        "tomorrow" => format!("{} tomorrow", clock),
+        day => format!("{} {}", clock, day),
+    }
+}
+
+/// Timing of a multi-day event as of `now`, for the prompt: "day 2 of 3,
+/// through Wed" while it's on, "starts tomorrow, 3 days through Thu" before
+/// it. None for other events and ones that are over.
+pub fn describe(event: &CalendarEventSummary, now: &DateTime<FixedOffset>) -> Option<String> {
+    let span = multi_day(event, &now.timezone())?;
+    let today = now.date_naive();
+    let end = event.end_time?;
+    // A timed event's last day ends with the event, not at midnight
+    let until = |prefix: &str| {
+        if event.is_all_day {
+            format!("{} {}", prefix, day_label(today, span.last))
+        } else {
+            format!("until {}", clock_from(now, &end))
+        }
+    };
+    if let Some(day) = span.day_of(today).filter(|_| end > now.to_utc()) {
+        let rest = if day == span.days() && event.is_all_day {
+            "last day".to_string()
+        } else {
+            until("through")
+        };
+        return Some(format!("day {} of {}, {}", day, span.days(), rest));
+    }
+    if span.first > today {
+        let starts = if event.is_all_day {
+            day_label(today, span.first)
+        } else {
+            clock_from(now, &event.start_time)
+        };
+        return Some(format!(
+            "starts {}, {} days {}",
+            starts,
+            span.days(),
+            until("through")
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(
+        title: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        all_day: bool,
+    ) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_lowercase(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(end),
+            location: None,
+            is_all_day: all_day,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_multi_day_events_say_which_day_it_is() {
+        // UTC-6 (Denver in May); Tuesday 12 May, 10 AM
+        let tz = FixedOffset::west_opt(6 * 3600).unwrap();
+        let at = |d: u32, h: u32| tz.with_ymd_and_hms(2026, 5, d, h, 0, 0).unwrap().to_utc();
+        let now = at(12, 10).with_timezone(&tz);
+
+        // All day Mon-Wed: Google's end is Thursday midnight
+        let conference = summary("Conference", at(11, 0), at(14, 0), true);
+        assert_eq!(
+            multi_day(&conference, &tz),
+            Some(Span {
+                first: NaiveDate::from_ymd_opt(2026, 5, 11).unwrap(),
+                last: NaiveDate::from_ymd_opt(2026, 5, 13).unwrap(),
+            })
+        );
+        assert_eq!(
+            describe(&conference, &now).as_deref(),
+            Some("day 2 of 3, through tomorrow")
+        );
+        let last_day = at(13, 8).with_timezone(&tz);
+        assert_eq!(
+            describe(&conference, &last_day).as_deref(),
+            Some("day 3 of 3, last day")
+        );
+        assert_eq!(describe(&conference, &at(14, 9).with_timezone(&tz)), None);
+
+        // Timed, Thursday 9 AM to Saturday 2 PM
+        let trip = summary("Trip", at(14, 9), at(16, 14), false);
+        assert_eq!(
+            describe(&trip, &now).as_deref(),
+            Some("starts 9:00 AM Thu, 3 days until 2:00 PM Sat")
+        );
+        let all_day_trip = summary("Lake house", at(15, 0), at(18, 0), true);
+        assert_eq!(
+            describe(&all_day_trip, &now).as_deref(),
+            Some("starts Fri, 3 days through Sun")
+        );
+
+        // A single all-day event, and a late flight over midnight, aren't multi-day
+        let holiday = summary("Holiday", at(12, 0), at(13, 0), true);
+        assert_eq!(multi_day(&holiday, &tz), None);
+        let flight = summary("Red-eye", at(12, 22), at(13, 6), false);
+        assert_eq!(multi_day(&flight, &tz), None);
+        assert_eq!(clock_from(&now, &at(13, 6)), "6:00 AM tomorrow");
+        assert_eq!(clock_from(&now, &at(12, 15)), "3:00 PM");
+    }
+}
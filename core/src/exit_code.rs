@@ -0,0 +1,185 @@
+//! Exit codes for the command-line interface.
+//!
+//! Failures map onto the sysexits(3) codes so scripts and service managers can
+//! tell a bad config from a daemon that isn't running or a network that is
+//! down. Invalid arguments are rejected by clap with its own code, 2.
+
+use crate::errors::JasperError;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitStatus {
+    /// Anything not classified below
+    Failure,
+    /// Input that could not be parsed or makes no sense
+    DataError,
+    /// The daemon, D-Bus or a remote service is not available
+    Unavailable,
+    /// A bug: something that shouldn't happen did
+    Internal,
+    /// Reading or writing a file or the database failed
+    IoError,
+    /// Worth retrying later: network trouble, timeouts, a failed sync
+    TempFailure,
+    /// Not signed in, or not allowed
+    NoPermission,
+    /// The config file is missing something or is invalid
+    Config,
+}
+
+impl ExitStatus {
+    pub const ALL: [ExitStatus; 8] = [
+        ExitStatus::Failure,
+        ExitStatus::DataError,
+        ExitStatus::Unavailable,
+        ExitStatus::Internal,
+        ExitStatus::IoError,
+        ExitStatus::TempFailure,
+        ExitStatus::NoPermission,
+        ExitStatus::Config,
+    ];
+
+    pub fn code(self) -> u8 {
+        match self {
+            ExitStatus::Failure => 1,
+            ExitStatus::DataError => 65,
+            ExitStatus::Unavailable => 69,
+            ExitStatus::Internal => 70,
+            ExitStatus::IoError => 74,
+            ExitStatus::TempFailure => 75,
+            ExitStatus::NoPermission => 77,
+            ExitStatus::Config => 78,
+        }
+    }
+
+    /// One line for `--help`
+    pub fn describe(self) -> &'static str {
+        match self {
+            ExitStatus::Failure => "other failure",
+            ExitStatus::DataError => "invalid input data",
+            ExitStatus::Unavailable => "daemon, D-Bus or remote service unavailable",
+            ExitStatus::Internal => "internal error",
+            ExitStatus::IoError => "file or database error",
+            ExitStatus::TempFailure => "temporary failure (network, timeout); try again",
+            ExitStatus::NoPermission => "authentication needed or permission denied",
+            ExitStatus::Config => "configuration error",
+        }
+    }
+}
+
+/// The exit status for `error`: the first error in its chain that says what
+/// went wrong decides
+pub fn classify(error: &anyhow::Error) -> ExitStatus {
+    // Finds JasperError attached as context too, not just as a source
+    if let Some(error) = error.downcast_ref::<JasperError>() {
+        return from_jasper_error(error);
+    }
+
+    error
+        .chain()
+        .find_map(|cause| {
+            if let Some(error) = cause.downcast_ref::<JasperError>() {
+                Some(from_jasper_error(error))
+            } else if cause.is::<toml::de::Error>() {
+                Some(ExitStatus::Config)
+            } else if cause.is::<zbus::Error>() {
+                Some(ExitStatus::Unavailable)
+            } else if let Some(error) = cause.downcast_ref::<reqwest::Error>() {
+                Some(if error.is_timeout() || error.is_connect() {
+                    ExitStatus::TempFailure
+                } else {
+                    ExitStatus::Unavailable
+                })
+            } else if cause.is::<rusqlite::Error>() {
+                Some(ExitStatus::IoError)
+            } else if cause.is::<serde_json::Error>() {
+                Some(ExitStatus::DataError)
+            } else {
+                cause.downcast_ref::<std::io::Error>().map(|error| {
+                    if error.kind() == std::io::ErrorKind::PermissionDenied {
+                        ExitStatus::NoPermission
+                    } else {
+                        ExitStatus::IoError
+                    }
+                })
+            }
+        })
+        .unwrap_or(ExitStatus::Failure)
+}
+
+fn from_jasper_error(error: &JasperError) -> ExitStatus {
+    match error {
+        JasperError::Config { .. } => ExitStatus::Config,
+        JasperError::Database { .. } | JasperError::FileSystem { .. } => ExitStatus::IoError,
+        JasperError::CalendarSync { .. }
+        | JasperError::Network { .. }
+        | JasperError::Timeout { .. } => ExitStatus::TempFailure,
+        JasperError::Authentication { .. } => ExitStatus::NoPermission,
+        JasperError::Api { .. } | JasperError::ServiceUnavailable { .. } => ExitStatus::Unavailable,
+        JasperError::Parsing { .. } | JasperError::Validation { .. } => ExitStatus::DataError,
+        JasperError::Internal { .. } => ExitStatus::Internal,
+    }
+}
+
+/// How a failed command reports itself with `--json`
+#[derive(Debug, Serialize)]
+pub struct ErrorReport {
+    pub error: String,
+    pub status: ExitStatus,
+    pub exit_code: u8,
+}
+
+impl ErrorReport {
+    pub fn new(error: &anyhow::Error) -> Self {
+        let status = classify(error);
+        Self {
+            error: format!("{:#}", error).trim_end().to_string(),
+            status,
+            exit_code: status.code(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_errors_are_classified_through_context() {
+        let config: anyhow::Result<()> = Err(anyhow::anyhow!("timezone is empty"))
+            .context(JasperError::Config {
+                message: "config.toml is invalid".to_string(),
+            })
+            .context("Failed to load configuration");
+        assert_eq!(classify(&config.unwrap_err()), ExitStatus::Config);
+
+        let toml_error = toml::from_str::<toml::Table>("general = ").unwrap_err();
+        let parse = anyhow::Error::new(toml_error).context("Failed to parse config");
+        assert_eq!(classify(&parse), ExitStatus::Config);
+
+        let denied = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            .context("Failed to write token");
+        assert_eq!(classify(&denied), ExitStatus::NoPermission);
+
+        let plain = anyhow::anyhow!("No meeting with a video link in the next 12 hours");
+        assert_eq!(classify(&plain), ExitStatus::Failure);
+
+        let report = ErrorReport::new(&anyhow::Error::new(JasperError::ServiceUnavailable {
+            service: "daemon".to_string(),
+        }));
+        assert_eq!(report.exit_code, 69);
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["status"],
+            "unavailable"
+        );
+
+        // Codes are distinct, and none collides with success or clap's usage code
+        let mut codes: Vec<u8> = ExitStatus::ALL.iter().map(|s| s.code()).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), ExitStatus::ALL.len());
+        assert!(!codes.contains(&0) && !codes.contains(&2));
+    }
+}
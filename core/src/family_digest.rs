@@ -0,0 +1,299 @@
+//! Daily family digest for a shared channel.
+//!
+//! Once a morning, the day's events from the family calendars are listed with
+//! the logistics worth knowing (two events at once, rain during soccer) and
+//! sent to a channel the rest of the household reads: a family ntfy topic, or
+//! a group chat through a webhook. It is built locally, never by the model, so
+//! nothing from the user's own calendar, tasks or notes can end up in it, and
+//! every title and location goes through strict sanitization.
+
+use chrono::{DateTime, FixedOffset, Utc};
+use tracing::warn;
+
+use crate::activity::ActivityClassifier;
+use crate::config::FamilyDigestConfig;
+use crate::context_sources::WeatherContext;
+use crate::errors::JasperResult;
+use crate::ntfy::NtfyPublisher;
+use crate::sanitize;
+use crate::significance_engine::CalendarEventSummary;
+use crate::weather_gear;
+use crate::webhook::{WebhookEvent, WebhookPublisher};
+
+const EMOJI: &str = "👪";
+
+/// Whether an event belongs in the digest: on one of `calendars`, or on any
+/// owned calendar other than the primary one when none are listed
+pub fn is_family_event(event: &CalendarEventSummary, calendars: &[String]) -> bool {
+    if calendars.is_empty() {
+        return event.is_own_calendar && !event.is_primary_calendar;
+    }
+    event
+        .calendar_name
+        .as_deref()
+        .is_some_and(|name| calendars.iter().any(|c| c.eq_ignore_ascii_case(name)))
+}
+
+/// Today's family events and logistics flags, or None if there are no events
+pub fn build(
+    events: &[CalendarEventSummary],
+    calendars: &[String],
+    weather: Option<&WeatherContext>,
+    activities: &ActivityClassifier,
+    now: &DateTime<FixedOffset>,
+) -> Option<String> {
+    let tz = now.timezone();
+    let today = now.date_naive();
+    let mut family: Vec<CalendarEventSummary> = events
+        .iter()
+        .filter(|e| is_family_event(e, calendars))
+        .filter(|e| e.start_time.with_timezone(&tz).date_naive() == today)
+        .map(sanitized)
+        .collect();
+    if family.is_empty() {
+        return None;
+    }
+    family.sort_by_key(|e| (!e.is_all_day, e.start_time));
+
+    let time = |t: DateTime<Utc>| t.with_timezone(&tz).format("%-I:%M %p").to_string();
+    let mut text = format!("Family plan for {}", now.format("%A, %B %-d"));
+    for event in &family {
+        let when = if event.is_all_day {
+            "All day".to_string()
+        } else {
+            time(event.start_time)
+        };
+        let who = event
+            .calendar_name
+            .as_deref()
+            .map(|name| format!(" ({})", name))
+            .unwrap_or_default();
+        let place = event
+            .location
+            .as_deref()
+            .map(|l| format!(" at {}", l))
+            .unwrap_or_default();
+        text.push_str(&format!("\n- {} {}{}{}", when, event.title, who, place));
+    }
+
+    let mut flags: Vec<String> = overlaps(&family)
+        .into_iter()
+        .map(|(a, b)| {
+            let apart = match (&a.location, &b.location) {
+                (Some(x), Some(y)) if x != y => " — two places at once",
+                _ => "",
+            };
+            format!(
+                "⚠️ {} and {} overlap at {}{}",
+                a.title,
+                b.title,
+                time(b.start_time),
+                apart
+            )
+        })
+        .collect();
+    if let Some(weather) = weather {
+        flags.extend(
+            weather_gear::notes_for_events(&family, weather, activities, now)
+                .iter()
+                .map(|note| format!("{} {}", note.flag.emoji(), note.describe())),
+        );
+    }
+    if !flags.is_empty() {
+        text.push_str("\n\nHeads-up:");
+        for flag in flags {
+            text.push_str(&format!("\n- {}", flag));
+        }
+    }
+    Some(text)
+}
+
+/// The event with only what may be shared: sanitized title and place name
+pub fn sanitized(event: &CalendarEventSummary) -> CalendarEventSummary {
+    CalendarEventSummary {
+        title: sanitize::strict(&event.title),
+        location: event.location.as_deref().and_then(sanitize::location),
+        travel_time: None,
+        attendees: vec![],
+        links: vec![],
+        awaiting_reply: false,
+        tentative: false,
+        ..event.clone()
+    }
+}
+
+/// Pairs of timed events (sorted by start) that overlap
+fn overlaps(
+    events: &[CalendarEventSummary],
+) -> Vec<(&CalendarEventSummary, &CalendarEventSummary)> {
+    let timed: Vec<&CalendarEventSummary> = events
+        .iter()
+        .filter(|e| !e.is_all_day && !e.tentative)
+        .collect();
+    let mut pairs = Vec::new();
+    for (i, a) in timed.iter().enumerate() {
+        let a_end = a.end_time.unwrap_or(a.start_time);
+        for b in &timed[i + 1..] {
+            if b.start_time < a_end {
+                pairs.push((*a, *b));
+            }
+        }
+    }
+    pairs
+}
+
+/// The digest's own channels, separate from `[delivery]`
+pub struct FamilyDigestPublisher {
+    ntfy: Option<NtfyPublisher>,
+    webhook: Option<WebhookPublisher>,
+}
+
+impl FamilyDigestPublisher {
+    /// None unless the digest is enabled with at least one working channel
+    pub fn from_config(client: reqwest::Client, config: &FamilyDigestConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let ntfy = config
+            .ntfy_url
+            .clone()
+            .map(|url| NtfyPublisher::new(client.clone(), url, config.ntfy_token.clone()));
+        let webhook = config.webhook.as_ref().and_then(|wc| {
+            WebhookPublisher::new(client, wc)
+                .map_err(|e| warn!("Family digest webhook disabled: {}", e))
+                .ok()
+        });
+        (ntfy.is_some() || webhook.is_some()).then_some(Self { ntfy, webhook })
+    }
+
+    /// Send to every configured channel; the first error is returned after all were tried
+    pub async fn publish(&self, text: &str) -> JasperResult<()> {
+        let mut result = Ok(());
+        if let Some(ref ntfy) = self.ntfy {
+            result = result.and(ntfy.publish(EMOJI, text, None).await);
+        }
+        if let Some(ref webhook) = self.webhook {
+            let sent = webhook
+                .publish(WebhookEvent::FamilyDigest, EMOJI, text, None)
+                .await;
+            result = result.and(sent);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    fn event(
+        title: &str,
+        calendar: &str,
+        location: Option<&str>,
+        start: DateTime<Utc>,
+        primary: bool,
+    ) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + chrono::Duration::hours(1)),
+            location: location.map(str::to_string),
+            is_all_day: false,
+            calendar_name: Some(calendar.to_string()),
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: primary,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_sanitized_matches_golden_cases() {
+        let start = Utc.with_ymd_and_hms(2026, 5, 12, 13, 0, 0).unwrap();
+        let golden = include_str!("../testdata/sanitized_events.tsv");
+        let cases = golden
+            .lines()
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+        let mut checked = 0;
+        for line in cases {
+            let columns: Vec<&str> = line.split('\t').collect();
+            let [title, location, want_title, want_location] = columns[..] else {
+                panic!("malformed golden line: {:?}", line);
+            };
+            let location = (!location.is_empty()).then_some(location);
+            let shared = sanitized(&event(title, "Family", location, start, false));
+            assert_eq!(shared.title, want_title, "title of {:?}", title);
+            assert_eq!(
+                shared.location.as_deref().unwrap_or_default(),
+                want_location,
+                "location of {:?}",
+                title
+            );
+            checked += 1;
+        }
+        assert!(checked >= 10, "golden file has only {} cases", checked);
+    }
+
+    #[test]
+    fn test_digest_lists_family_events_only() {
+        let tz = FixedOffset::west_opt(4 * 3600).unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let at = |hour, minute| {
+            tz.from_local_datetime(&day.and_hms_opt(hour, minute, 0).unwrap())
+                .unwrap()
+        };
+        let now = at(7, 0);
+        let events = vec![
+            event(
+                "1:1 with manager https://zoom.us/j/42",
+                "Work",
+                None,
+                at(9, 0).to_utc(),
+                true,
+            ),
+            event(
+                "Soccer practice",
+                "Kieran",
+                Some("Rec Center, 12 Oak St"),
+                at(16, 0).to_utc(),
+                false,
+            ),
+            event(
+                "Piano lesson (call 555-123-4567)",
+                "Mia",
+                Some("Music School"),
+                at(16, 30).to_utc(),
+                false,
+            ),
+        ];
+
+        let digest = build(&events, &[], None, &ActivityClassifier::default(), &now).unwrap();
+        assert!(digest.starts_with("Family plan for Tuesday, May 12"));
+        assert!(digest.contains("- 4:00 PM Soccer practice (Kieran) at Rec Center"));
+        assert!(digest.contains("- 4:30 PM Piano lesson (Mia) at Music School"));
+        assert!(digest.contains(
+            "⚠️ Soccer practice and Piano lesson overlap at 4:30 PM — two places at once"
+        ));
+        assert!(!digest.contains("manager"));
+        assert!(!digest.contains("555"));
+        assert!(!digest.contains("Oak St"));
+
+        // Listing calendars narrows it further
+        let only_mia = build(
+            &events,
+            &["mia".to_string()],
+            None,
+            &ActivityClassifier::default(),
+            &now,
+        )
+        .unwrap();
+        assert!(!only_mia.contains("Soccer"));
+        assert!(!only_mia.contains("Heads-up"));
+    }
+}
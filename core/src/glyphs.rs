@@ -0,0 +1,193 @@
+//! Emoji → Nerd Font glyph mapping for bar frontends.
+//!
+//! The AI picks a free-form emoji for each insight, which many bar fonts
+//! render as tofu or at the wrong width. When `[frontends.glyphs]` is
+//! enabled the emoji is looked up (exact match first, then by category)
+//! and replaced with a single-width Nerd Font glyph.
+
+use crate::config::GlyphConfig;
+use std::collections::BTreeMap;
+
+/// Categories an insight emoji can belong to, with their default glyphs
+pub const CATEGORIES: &[(&str, &str)] = &[
+    ("urgent", "\u{f0e7}"),   // nf-fa-bolt
+    ("warning", "\u{f071}"),  // nf-fa-warning
+    ("time", "\u{f017}"),     // nf-fa-clock_o
+    ("action", "\u{f084}"),   // nf-fa-key
+    ("calendar", "\u{f073}"), // nf-fa-calendar
+    ("tasks", "\u{f0ae}"),    // nf-fa-tasks
+    ("weather", "\u{e30d}"),  // nf-weather-day_sunny
+    ("travel", "\u{f1b9}"),   // nf-fa-car
+    ("status", "\u{f002}"),   // nf-fa-search
+    ("people", "\u{f0c0}"),   // nf-fa-users
+];
+
+/// Used when an emoji matches no mapping
+pub const DEFAULT_FALLBACK_GLYPH: &str = "\u{f0eb}"; // nf-fa-lightbulb_o
+
+/// Strip variation selectors and joiners so "⚠️" and "⚠" compare equal
+fn normalize(emoji: &str) -> String {
+    emoji
+        .trim()
+        .chars()
+        .filter(|c| !matches!(c, '\u{FE00}'..='\u{FE0F}' | '\u{200D}'))
+        .collect()
+}
+
+/// Category of an insight emoji, if it is one the daemon knows about
+pub fn emoji_category(emoji: &str) -> Option<&'static str> {
+    let category = match normalize(emoji).as_str() {
+        "🚨" | "❗" | "‼" => "urgent",
+        "⚠" => "warning",
+        "⏰" | "⏳" | "⌛" => "time",
+        "🔑" => "action",
+        "📅" | "🗓" => "calendar",
+        "📋" | "✅" | "📝" => "tasks",
+        "🌧" | "☔" | "⛈" | "🌩" | "❄" | "🌨" | "☀" | "🌤" | "⛅" | "🌡" | "🥶" | "🥵" => {
+            "weather"
+        }
+        "🚗" | "🚙" | "🚌" | "🚆" | "✈" => "travel",
+        "🔍" => "status",
+        "👥" | "👤" | "🤝" | "💬" | "🎂" | "📞" | "☎" | "💌" => "people",
+        _ => return None,
+    };
+    Some(category)
+}
+
+/// How pressing an insight is, by its emoji: "critical", "high" or "normal"
+pub fn emoji_urgency(emoji: &str) -> &'static str {
+    match emoji_category(emoji) {
+        Some("urgent") => "critical",
+        Some("warning") | Some("time") => "high",
+        _ => "normal",
+    }
+}
+
+pub struct GlyphMapper {
+    enabled: bool,
+    by_emoji: BTreeMap<String, String>,
+    by_category: BTreeMap<String, String>,
+    fallback: String,
+}
+
+impl GlyphMapper {
+    pub fn from_config(config: &GlyphConfig) -> Self {
+        let mut by_category: BTreeMap<String, String> = CATEGORIES
+            .iter()
+            .map(|(name, glyph)| (name.to_string(), glyph.to_string()))
+            .collect();
+        by_category.extend(config.categories.clone());
+
+        Self {
+            enabled: config.enabled,
+            by_emoji: config
+                .emoji
+                .iter()
+                .map(|(emoji, glyph)| (normalize(emoji), glyph.clone()))
+                .collect(),
+            by_category,
+            fallback: config
+                .fallback
+                .clone()
+                .unwrap_or_else(|| DEFAULT_FALLBACK_GLYPH.to_string()),
+        }
+    }
+
+    /// Mapper that passes emoji through untouched
+    pub fn disabled() -> Self {
+        Self::from_config(&GlyphConfig::default())
+    }
+
+    /// Glyph to display for an insight emoji (the emoji itself when disabled)
+    pub fn map(&self, emoji: &str) -> String {
+        if !self.enabled || emoji.is_empty() {
+            return emoji.to_string();
+        }
+
+        if let Some(glyph) = self.by_emoji.get(&normalize(emoji)) {
+            return glyph.clone();
+        }
+
+        emoji_category(emoji)
+            .and_then(|category| self.by_category.get(category))
+            .cloned()
+            .unwrap_or_else(|| self.fallback.clone())
+    }
+
+    /// Problems with the user's mapping table, for the validation command
+    pub fn validate(config: &GlyphConfig) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for name in config.categories.keys() {
+            if !CATEGORIES.iter().any(|(known, _)| known == name) {
+                let known: Vec<&str> = CATEGORIES.iter().map(|(n, _)| *n).collect();
+                problems.push(format!(
+                    "Unknown category '{}' (known: {})",
+                    name,
+                    known.join(", ")
+                ));
+            }
+        }
+
+        let entries = config
+            .categories
+            .iter()
+            .chain(config.emoji.iter())
+            .map(|(key, glyph)| (key.as_str(), glyph.as_str()))
+            .chain(config.fallback.as_deref().map(|g| ("fallback", g)));
+        for (key, glyph) in entries {
+            if glyph.trim().is_empty() {
+                problems.push(format!("Glyph for '{}' is empty", key));
+            } else if glyph.chars().count() > 2 {
+                problems.push(format!(
+                    "Glyph for '{}' is {} characters; bars expect a single glyph",
+                    key,
+                    glyph.chars().count()
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mapping_precedence() {
+        let mut config = GlyphConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        config.emoji.insert("☕".to_string(), "C".to_string());
+        config
+            .categories
+            .insert("weather".to_string(), "W".to_string());
+        let mapper = GlyphMapper::from_config(&config);
+
+        assert_eq!(mapper.map("☕"), "C");
+        assert_eq!(mapper.map("🌧️"), "W");
+        assert_eq!(mapper.map("📅"), "\u{f073}");
+        assert_eq!(mapper.map("🦄"), DEFAULT_FALLBACK_GLYPH);
+
+        assert_eq!(GlyphMapper::disabled().map("🌧️"), "🌧️");
+
+        assert_eq!(emoji_urgency("🚨"), "critical");
+        assert_eq!(emoji_urgency("⚠️"), "high");
+        assert_eq!(emoji_urgency("📅"), "normal");
+    }
+
+    #[test]
+    fn test_validate_flags_bad_entries() {
+        let mut config = GlyphConfig::default();
+        config
+            .categories
+            .insert("meetings".to_string(), "M".to_string());
+        config.emoji.insert("☕".to_string(), String::new());
+
+        let problems = GlyphMapper::validate(&config);
+        assert_eq!(problems.len(), 2);
+    }
+}
@@ -5,15 +5,25 @@ use oauth2::{
     RedirectUrl, RefreshToken, Scope, TokenResponse, TokenUrl,
 };
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::attachments;
 use crate::database::Event;
 use crate::http_utils::{
-    handle_google_api_response, handle_oauth2_response_with_text, parse_json_response,
+    self, handle_google_api_response, handle_oauth2_response_with_text, parse_json_response,
 };
+use crate::meeting_links;
+use crate::people::Attendee;
+
+const READONLY_SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+/// Lets `rsvp` answer invitations; tokens from before it was asked for lack it
+const EVENTS_SCOPE: &str = "https://www.googleapis.com/auth/calendar.events";
+
+/// The OAuth token's file in the data directory
+pub const TOKEN_FILE: &str = "google_calendar_token.json";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GoogleCalendarConfig {
@@ -60,6 +70,11 @@ pub struct GoogleCalendarListEntry {
     pub access_role: Option<String>,
     pub primary: Option<bool>,
     pub selected: Option<bool>,
+    /// Hidden from the Google Calendar list
+    pub hidden: Option<bool>,
+    /// The user's color for the calendar, e.g. "#9fe1e7"
+    #[serde(rename = "backgroundColor")]
+    pub background_color: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -72,6 +87,39 @@ struct GoogleEvent {
     end: Option<GoogleEventDateTime>,
     status: Option<String>,
     attendees: Option<Vec<GoogleEventAttendee>>,
+    /// The event's page in Google Calendar, kept for insight deep links
+    #[serde(rename = "htmlLink", skip_serializing_if = "Option::is_none")]
+    html_link: Option<String>,
+    /// Google Meet link
+    #[serde(rename = "hangoutLink", skip_serializing_if = "Option::is_none")]
+    hangout_link: Option<String>,
+    /// Conferencing attached in Google Calendar (Meet, or a Zoom or Teams add-on)
+    #[serde(rename = "conferenceData", skip_serializing_if = "Option::is_none")]
+    conference_data: Option<GoogleConferenceData>,
+    /// Google Drive files attached to the event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attachments: Option<Vec<GoogleAttachment>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct GoogleAttachment {
+    #[serde(rename = "fileUrl")]
+    file_url: Option<String>,
+    title: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct GoogleConferenceData {
+    #[serde(rename = "entryPoints", default)]
+    entry_points: Vec<GoogleEntryPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct GoogleEntryPoint {
+    /// "video", "phone", "sip" or "more"
+    #[serde(rename = "entryPointType")]
+    entry_point_type: Option<String>,
+    uri: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -91,6 +139,10 @@ struct GoogleEventAttendee {
     /// Whether this attendee is the authenticated user (Google API `self` field)
     #[serde(rename = "self")]
     is_self: Option<bool>,
+    #[serde(rename = "responseStatus")]
+    response_status: Option<String>,
+    /// Meeting rooms and other resources
+    resource: Option<bool>,
 }
 
 /// Metadata about a calendar from the calendarList endpoint
@@ -100,6 +152,10 @@ pub struct CalendarMetadata {
     pub display_name: String,
     pub access_role: String,
     pub is_primary: bool,
+    pub color: Option<String>,
+    pub hidden: bool,
+    /// Checked (shown) in Google Calendar's calendar list
+    pub selected: bool,
 }
 
 pub struct GoogleCalendarService {
@@ -115,7 +171,7 @@ impl GoogleCalendarService {
         data_dir: PathBuf,
         user_timezone: chrono_tz::Tz,
     ) -> Self {
-        let token_file_path = data_dir.join("google_calendar_token.json");
+        let token_file_path = data_dir.join(TOKEN_FILE);
 
         Self {
             config,
@@ -144,9 +200,8 @@ impl GoogleCalendarService {
 
         let (auth_url, csrf_token) = client
             .authorize_url(CsrfToken::new_random)
-            .add_scope(Scope::new(
-                "https://www.googleapis.com/auth/calendar.readonly".to_string(),
-            ))
+            .add_scope(Scope::new(READONLY_SCOPE.to_string()))
+            .add_scope(Scope::new(EVENTS_SCOPE.to_string()))
             .url();
 
         Ok((auth_url.to_string(), csrf_token))
@@ -165,7 +220,7 @@ impl GoogleCalendarService {
             expires_at: token_response
                 .expires_in
                 .map(|seconds| Utc::now() + Duration::seconds(seconds)),
-            scopes: vec!["https://www.googleapis.com/auth/calendar.readonly".to_string()],
+            scopes: vec![READONLY_SCOPE.to_string(), EVENTS_SCOPE.to_string()],
         };
 
         self.store_token(&stored_token).await?;
@@ -256,17 +311,75 @@ impl GoogleCalendarService {
         Ok(events_by_calendar)
     }
 
+    /// Whether the stored token may answer invitations
+    pub async fn can_answer_invitations(&self) -> bool {
+        self.load_stored_token()
+            .await
+            .is_ok_and(|token| token.scopes.iter().any(|s| s == EVENTS_SCOPE))
+    }
+
+    /// Answer an invitation: set the user's own `responseStatus` on the
+    /// event's guest list, and let the organizer know
+    pub async fn respond_to_invitation(
+        &self,
+        calendar_id: &str,
+        event_id: &str,
+        status: &str,
+    ) -> Result<()> {
+        let token = self.get_valid_token().await?;
+        if !token.scopes.iter().any(|s| s == EVENTS_SCOPE) {
+            return Err(anyhow!(
+                "Answering invitations needs permission Jasper didn't ask for before; \
+                 run 'auth-google' again to grant it"
+            ));
+        }
+        let url = format!(
+            "https://www.googleapis.com/calendar/v3/calendars/{}/events/{}",
+            urlencoding::encode(calendar_id),
+            urlencoding::encode(event_id)
+        );
+
+        let response =
+            http_utils::send(self.http_client.get(&url).bearer_auth(&token.access_token))
+                .await
+                .map_err(|e| anyhow!("Google Calendar API request failed: {}", e))?;
+        let response = handle_google_api_response(response).await?;
+        let event: serde_json::Value =
+            parse_json_response(response, "Google Calendar event response").await?;
+
+        // The whole guest list goes back, with only the user's entry changed
+        let mut attendees = event["attendees"].as_array().cloned().unwrap_or_default();
+        let me = attendees
+            .iter_mut()
+            .find(|a| a["self"].as_bool() == Some(true))
+            .ok_or_else(|| anyhow!("You aren't on this event's guest list"))?;
+        me["responseStatus"] = serde_json::Value::from(status);
+
+        let response = http_utils::send(
+            self.http_client
+                .patch(&url)
+                .bearer_auth(&token.access_token)
+                .query(&[("sendUpdates", "all")])
+                .json(&serde_json::json!({ "attendees": attendees })),
+        )
+        .await
+        .map_err(|e| anyhow!("Google Calendar API request failed: {}", e))?;
+        handle_google_api_response(response).await?;
+        info!("Answered invitation {} with {}", event_id, status);
+        Ok(())
+    }
+
     /// Fetch the list of all calendars on the authenticated account
     pub async fn fetch_calendar_list(&self) -> Result<Vec<GoogleCalendarListEntry>> {
         let token = self.get_valid_token().await?;
 
-        let response = self
-            .http_client
-            .get("https://www.googleapis.com/calendar/v3/users/me/calendarList")
-            .bearer_auth(&token.access_token)
-            .send()
-            .await
-            .map_err(|e| anyhow!("Google Calendar list API request failed: {}", e))?;
+        let response = http_utils::send(
+            self.http_client
+                .get("https://www.googleapis.com/calendar/v3/users/me/calendarList")
+                .bearer_auth(&token.access_token),
+        )
+        .await
+        .map_err(|e| anyhow!("Google Calendar list API request failed: {}", e))?;
 
         let response = handle_google_api_response(response).await?;
         let list: GoogleCalendarListResponse =
@@ -301,6 +414,9 @@ impl GoogleCalendarService {
                 display_name: entry.summary.clone().unwrap_or_else(|| entry.id.clone()),
                 access_role: entry.access_role.clone().unwrap_or_default(),
                 is_primary: entry.primary.unwrap_or(false),
+                color: entry.background_color.clone(),
+                hidden: entry.hidden.unwrap_or(false),
+                selected: entry.selected.unwrap_or(false),
             };
             map.insert(entry.id.clone(), meta.clone());
             // Also map "primary" alias so callers using that ID get the metadata
@@ -377,14 +493,14 @@ impl GoogleCalendarService {
                 query_params.push(("pageToken", token.clone()));
             }
 
-            let response = self
-                .http_client
-                .get(&url)
-                .bearer_auth(access_token)
-                .query(&query_params)
-                .send()
-                .await
-                .map_err(|e| anyhow!("Google Calendar API request failed: {}", e))?;
+            let response = http_utils::send(
+                self.http_client
+                    .get(&url)
+                    .bearer_auth(access_token)
+                    .query(&query_params),
+            )
+            .await
+            .map_err(|e| anyhow!("Google Calendar API request failed: {}", e))?;
 
             let response = handle_google_api_response(response).await?;
             let events_response: GoogleEventsResponse =
@@ -498,16 +614,53 @@ impl GoogleCalendarService {
             None
         };
 
-        let participants = if let Some(attendees) = &gcal_event.attendees {
-            let attendee_emails: Vec<String> =
-                attendees.iter().filter_map(|a| a.email.clone()).collect();
-            if !attendee_emails.is_empty() {
-                Some(serde_json::to_string(&attendee_emails)?)
-            } else {
-                None
-            }
+        // Everyone but the user, rooms and those who declined
+        let participants: Vec<Attendee> = gcal_event
+            .attendees
+            .iter()
+            .flatten()
+            .filter(|a| !a.is_self.unwrap_or(false) && !a.resource.unwrap_or(false))
+            .filter(|a| a.response_status.as_deref() != Some("declined"))
+            .filter(|a| a.email.is_some() || a.display_name.is_some())
+            .map(|a| Attendee {
+                name: a.display_name.clone(),
+                email: a.email.clone(),
+            })
+            .collect();
+        let participants = if participants.is_empty() {
+            None
         } else {
+            Some(serde_json::to_string(&participants)?)
+        };
+
+        // The user's own answer, when they're on the guest list
+        let my_response = gcal_event
+            .attendees
+            .iter()
+            .flatten()
+            .find(|a| a.is_self.unwrap_or(false))
+            .and_then(|a| a.response_status.clone());
+
+        let conference = gcal_event
+            .conference_data
+            .iter()
+            .flat_map(|data| &data.entry_points)
+            .find(|entry| entry.entry_point_type.as_deref() == Some("video"))
+            .and_then(|entry| entry.uri.as_deref())
+            .or(gcal_event.hangout_link.as_deref());
+        let meeting_url =
+            meeting_links::join_url(conference, location.as_deref(), description.as_deref());
+        let attached: Vec<(Option<&str>, &str)> = gcal_event
+            .attachments
+            .iter()
+            .flatten()
+            .filter_map(|a| Some((a.title.as_deref(), a.file_url.as_deref()?)))
+            .collect();
+        let links = attachments::extract(&attached, description.as_deref());
+        let links = if links.is_empty() {
             None
+        } else {
+            Some(serde_json::to_string(&links)?)
         };
 
         Ok(Event {
@@ -523,6 +676,9 @@ impl GoogleCalendarService {
             participants,
             raw_data_json: Some(raw_data),
             is_all_day: Some(is_all_day),
+            meeting_url,
+            links,
+            my_response,
         })
     }
 
@@ -556,12 +712,13 @@ impl GoogleCalendarService {
         ];
 
         debug!("Sending token exchange request to Google OAuth2 endpoint");
-        let response = client
-            .post("https://oauth2.googleapis.com/token")
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+        let response = http_utils::send(
+            client
+                .post("https://oauth2.googleapis.com/token")
+                .form(&params),
+        )
+        .await
+        .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
 
         // Handle OAuth2 response and get text for debugging
         let response_text = handle_oauth2_response_with_text(response).await?;
@@ -581,6 +738,33 @@ impl GoogleCalendarService {
     }
 }
 
+/// Revoke the token stored in `token_file` with Google, so it stops working
+/// wherever a copy of it is kept. The file itself is left for the caller.
+/// Returns false when there is no stored token.
+pub async fn revoke_stored_token(token_file: &Path) -> Result<bool> {
+    let token_data = match fs::read_to_string(token_file).await {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    let token: StoredToken = serde_json::from_str(&token_data)?;
+    // Revoking the refresh token revokes the access tokens issued with it
+    let secret = token
+        .refresh_token
+        .as_deref()
+        .unwrap_or(&token.access_token);
+    let response = http_utils::send(
+        reqwest::Client::new()
+            .post("https://oauth2.googleapis.com/revoke")
+            .form(&[("token", secret)]),
+    )
+    .await
+    .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+    handle_oauth2_response_with_text(response).await?;
+    info!("Google Calendar token revoked");
+    Ok(true)
+}
+
 impl Default for GoogleCalendarConfig {
     fn default() -> Self {
         Self {
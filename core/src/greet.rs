@@ -0,0 +1,97 @@
+//! The login greeting, as `greet` gives it.
+//!
+//! Meant for autostart rather than the daemon's own loop: `greet` shows
+//! today's morning digest once, as a notification and optionally read aloud,
+//! then exits. When there is no digest for today yet, it asks the running
+//! daemon for a refresh (which writes one in the morning) and falls back to
+//! today's latest insight. A marker file in the data directory keeps later
+//! logins the same day quiet.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+use std::path::Path;
+
+use crate::accessibility::is_decorative_glyph;
+
+/// Marker file, in the data directory, holding the day of the last greeting
+pub const MARKER_FILE: &str = "greeted";
+
+/// Whether the greeting was already given on `today`
+pub fn greeted_on(marker: &Path, today: NaiveDate) -> bool {
+    std::fs::read_to_string(marker)
+        .ok()
+        .and_then(|s| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok())
+        == Some(today)
+}
+
+/// Record that the greeting was given on `today`
+pub fn mark_greeted(marker: &Path, today: NaiveDate) -> Result<()> {
+    if let Some(parent) = marker.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(marker, format!("{}\n", today.format("%Y-%m-%d")))?;
+    Ok(())
+}
+
+/// Whether `created_at` falls on `today` in `tz`
+pub fn is_on<Tz: chrono::TimeZone>(created_at: DateTime<Utc>, today: NaiveDate, tz: &Tz) -> bool {
+    created_at.with_timezone(tz).date_naive() == today
+}
+
+/// `text` as it should be read aloud: no emoji or icons, no markdown
+/// bullets, headings or emphasis, one line per sentence or item
+pub fn speakable(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line: String = line
+                .chars()
+                .filter(|c| !is_decorative_glyph(*c) && !matches!(c, '*' | '_' | '`'))
+                .collect();
+            let line = line.trim().trim_start_matches('#').trim();
+            let line = line
+                .strip_prefix("- ")
+                .or_else(|| line.strip_prefix("+ "))
+                .unwrap_or(line)
+                .trim();
+            // Items without a full stop run into each other when spoken
+            match line.chars().last() {
+                Some(c) if c.is_alphanumeric() || c == ')' => format!("{}.", line),
+                _ => line.to_string(),
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_greets_once_a_day_and_speaks_plain_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join(MARKER_FILE);
+        let today = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        assert!(!greeted_on(&marker, today));
+        mark_greeted(&marker, today).unwrap();
+        assert!(greeted_on(&marker, today));
+        assert!(!greeted_on(&marker, today.succ_opt().unwrap()));
+
+        // 23:30 in New York is already the next day in UTC
+        let tz: chrono_tz::Tz = "America/New_York".parse().unwrap();
+        let late = tz
+            .with_ymd_and_hms(2026, 5, 12, 23, 30, 0)
+            .unwrap()
+            .to_utc();
+        assert!(is_on(late, today, &tz));
+
+        assert_eq!(
+            speakable(
+                "## ☀️ Today\n\n- **Standup** at 9:30\n- Dentist at 4 (leave by 3:40)\nRain later!"
+            ),
+            "Today.\nStandup at 9:30.\nDentist at 4 (leave by 3:40).\nRain later!"
+        );
+    }
+}
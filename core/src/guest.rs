@@ -0,0 +1,207 @@
+//! Guest mode for shared displays.
+//!
+//! A kitchen tablet or hallway kiosk registers with `RegisterGuestFrontend`.
+//! From then on every insight method called from its bus connection, or under
+//! its frontend ID, gets a guest insight instead of the user's own: the next
+//! family event and the weather, built locally from the latest context and
+//! strictly sanitized. Digests, task counts and events from the user's own
+//! calendar are never returned to a guest.
+
+use chrono::{DateTime, FixedOffset, Utc};
+
+use crate::family_digest;
+use crate::significance_engine::{CalendarEventSummary, WeatherSummary};
+
+/// What a registered frontend may be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontendAccess {
+    /// The user's own insights, digests and tasks
+    Full,
+    /// Family-safe, sanitized insights only
+    Guest,
+}
+
+impl FrontendAccess {
+    pub fn name(self) -> &'static str {
+        match self {
+            FrontendAccess::Full => "full",
+            FrontendAccess::Guest => "guest",
+        }
+    }
+}
+
+/// The insight shown on guest displays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestInsight {
+    pub emoji: String,
+    pub text: String,
+}
+
+impl GuestInsight {
+    /// Stands in for the context hash, so displays can tell when it changed
+    pub fn hash(&self) -> String {
+        format!(
+            "{:x}",
+            md5::compute(format!("{} {}", self.emoji, self.text))
+        )
+    }
+}
+
+/// The family event on now or next today, sanitized; all-day events only
+/// when nothing timed is left
+pub fn next_family_event(
+    events: &[CalendarEventSummary],
+    calendars: &[String],
+    now: &DateTime<FixedOffset>,
+) -> Option<CalendarEventSummary> {
+    let tz = now.timezone();
+    let today = now.date_naive();
+    let now_utc = now.to_utc();
+    events
+        .iter()
+        .filter(|e| family_digest::is_family_event(e, calendars))
+        .filter(|e| e.start_time.with_timezone(&tz).date_naive() == today)
+        .filter(|e| e.is_all_day || e.end_time.unwrap_or(e.start_time) > now_utc)
+        .min_by_key(|e| (e.is_all_day, e.start_time))
+        .map(family_digest::sanitized)
+}
+
+/// Next family event and current weather, e.g.
+/// "Soccer practice (Kieran) at 4:00 PM, Rec Center · 72°F, Sunny"
+pub fn insight(
+    events: &[CalendarEventSummary],
+    calendars: &[String],
+    weather: Option<&WeatherSummary>,
+    now: &DateTime<FixedOffset>,
+) -> GuestInsight {
+    let weather = weather.map(|w| {
+        format!(
+            "{}{}, {}",
+            w.temperature,
+            w.units.temperature_symbol(),
+            w.condition
+        )
+    });
+    let Some(event) = next_family_event(events, calendars, now) else {
+        return match weather {
+            Some(weather) => GuestInsight {
+                emoji: "🌤️".to_string(),
+                text: format!("Nothing on the family calendar · {}", weather),
+            },
+            None => GuestInsight {
+                emoji: "🏠".to_string(),
+                text: "Nothing on the family calendar today".to_string(),
+            },
+        };
+    };
+
+    let time = |t: DateTime<Utc>| t.with_timezone(&now.timezone()).format("%-I:%M %p");
+    let when = if event.is_all_day {
+        " today".to_string()
+    } else if event.start_time <= now.to_utc() {
+        match event.end_time {
+            Some(end) => format!(" until {}", time(end)),
+            None => " now".to_string(),
+        }
+    } else {
+        format!(" at {}", time(event.start_time))
+    };
+    let who = event
+        .calendar_name
+        .as_deref()
+        .map(|name| format!(" ({})", name))
+        .unwrap_or_default();
+    let mut text = format!("{}{}{}", event.title, who, when);
+    if let Some(ref place) = event.location {
+        text.push_str(&format!(", {}", place));
+    }
+    if let Some(weather) = weather {
+        text.push_str(&format!(" · {}", weather));
+    }
+    GuestInsight {
+        emoji: "📅".to_string(),
+        text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_sources::UnitSystem;
+    use chrono::{Duration, NaiveDate, TimeZone};
+
+    fn event(
+        title: &str,
+        calendar: &str,
+        location: Option<&str>,
+        start: DateTime<Utc>,
+        primary: bool,
+    ) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::hours(1)),
+            location: location.map(str::to_string),
+            is_all_day: false,
+            calendar_name: Some(calendar.to_string()),
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: primary,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_guest_insight_shows_family_events_only() {
+        let tz = FixedOffset::west_opt(4 * 3600).unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let at = |hour, minute| {
+            tz.from_local_datetime(&day.and_hms_opt(hour, minute, 0).unwrap())
+                .unwrap()
+        };
+        let events = vec![
+            event(
+                "Therapy appointment",
+                "Personal",
+                Some("Dr. Lee, 4 Elm St"),
+                at(15, 0).to_utc(),
+                true,
+            ),
+            event(
+                "Soccer practice (call 555-123-4567)",
+                "Kieran",
+                Some("Rec Center, 12 Oak St"),
+                at(16, 0).to_utc(),
+                false,
+            ),
+        ];
+        let weather = WeatherSummary {
+            condition: "Sunny".to_string(),
+            temperature: 72,
+            feels_like: 72,
+            units: UnitSystem::Imperial,
+        };
+
+        let morning = insight(&events, &[], Some(&weather), &at(9, 0));
+        assert_eq!(morning.emoji, "📅");
+        assert_eq!(
+            morning.text,
+            "Soccer practice (Kieran) at 4:00 PM, Rec Center · 72°F, Sunny"
+        );
+
+        let during = insight(&events, &[], None, &at(16, 30));
+        assert_eq!(
+            during.text,
+            "Soccer practice (Kieran) until 5:00 PM, Rec Center"
+        );
+
+        let evening = insight(&events, &[], Some(&weather), &at(18, 0));
+        assert_eq!(evening.text, "Nothing on the family calendar · 72°F, Sunny");
+        assert_ne!(evening.hash(), morning.hash());
+    }
+}
@@ -0,0 +1,244 @@
+//! Heartbeat phases: the times of day a check-in insight is generated even
+//! when nothing has changed.
+//!
+//! Phases come from `[[heartbeat.phases]]` (name, "HH:MM" start and end, and
+//! optional days). A phase whose end is earlier than its start runs past
+//! midnight, for night shifts. Phases are laid out on a minute-of-week grid so
+//! overlaps are caught at config load rather than silently shadowing each other.
+//!
+//! With `adaptive = true` the number of heartbeats per day follows how much is
+//! coming up: an empty day gets `min_per_day`, a packed one up to `max_per_day`,
+//! with the quiet gap between them shrinking so extra check-ins fit inside the
+//! configured phases.
+
+use chrono::{NaiveTime, Timelike, Weekday};
+
+use crate::config::HeartbeatConfig;
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const MINUTES_PER_WEEK: u32 = 7 * MINUTES_PER_DAY;
+
+/// No heartbeat within this many minutes of the last insight
+pub const QUIET_GAP_MINUTES: i64 = 90;
+/// Shortest quiet gap adaptive mode will shrink to
+const MIN_QUIET_GAP_MINUTES: i64 = 30;
+
+/// Heartbeats today's load calls for: one per two upcoming events or four
+/// open tasks, clamped to the configured bounds
+pub fn adaptive_target(upcoming_events: usize, open_tasks: usize, min: u32, max: u32) -> u32 {
+    let load = (upcoming_events * 2 + open_tasks) as u32;
+    load.div_ceil(4).clamp(min, max)
+}
+
+/// Quiet gap for a day with `target` heartbeats spread over `phase_count` phases
+pub fn quiet_gap_minutes(target: u32, phase_count: usize) -> i64 {
+    if target as usize <= phase_count || target == 0 {
+        QUIET_GAP_MINUTES
+    } else {
+        (QUIET_GAP_MINUTES * phase_count as i64 / target as i64).max(MIN_QUIET_GAP_MINUTES)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Phase {
+    name: String,
+    /// Minutes after midnight
+    start: u32,
+    /// Length in minutes; may run into the next day
+    length: u32,
+    /// Bit 0 = Monday .. bit 6 = Sunday
+    days: u8,
+}
+
+impl Phase {
+    fn covers(&self, minute_of_week: u32) -> bool {
+        (0..7u32).filter(|d| self.days & (1 << d) != 0).any(|d| {
+            let start = d * MINUTES_PER_DAY + self.start;
+            (minute_of_week + MINUTES_PER_WEEK - start) % MINUTES_PER_WEEK < self.length
+        })
+    }
+}
+
+/// Validated heartbeat schedule
+#[derive(Debug, Clone)]
+pub struct HeartbeatSchedule {
+    phases: Vec<Phase>,
+}
+
+impl HeartbeatSchedule {
+    /// Parse and check the configured phases
+    pub fn from_config(config: &HeartbeatConfig) -> Result<Self, String> {
+        let mut phases = Vec::new();
+        for phase in &config.phases {
+            if phase.name.trim().is_empty() {
+                return Err("Heartbeat phase names cannot be empty".to_string());
+            }
+            let start = parse_time(&phase.start).ok_or_else(|| {
+                format!(
+                    "Heartbeat phase '{}': invalid start '{}' (expected HH:MM)",
+                    phase.name, phase.start
+                )
+            })?;
+            let end = parse_time(&phase.end).ok_or_else(|| {
+                format!(
+                    "Heartbeat phase '{}': invalid end '{}' (expected HH:MM)",
+                    phase.name, phase.end
+                )
+            })?;
+            if start == end {
+                return Err(format!(
+                    "Heartbeat phase '{}' starts and ends at the same time",
+                    phase.name
+                ));
+            }
+            let length = (end + MINUTES_PER_DAY - start) % MINUTES_PER_DAY;
+            let days = parse_days(&phase.days).map_err(|day| {
+                format!("Heartbeat phase '{}': unknown day '{}'", phase.name, day)
+            })?;
+            phases.push(Phase {
+                name: phase.name.clone(),
+                start,
+                length,
+                days,
+            });
+        }
+
+        // Every minute of the week may belong to at most one phase
+        let mut owner: Vec<Option<usize>> = vec![None; MINUTES_PER_WEEK as usize];
+        for (i, phase) in phases.iter().enumerate() {
+            for minute in 0..MINUTES_PER_WEEK {
+                if !phase.covers(minute) {
+                    continue;
+                }
+                if let Some(other) = owner[minute as usize] {
+                    return Err(format!(
+                        "Heartbeat phases '{}' and '{}' overlap",
+                        phases[other].name, phase.name
+                    ));
+                }
+                owner[minute as usize] = Some(i);
+            }
+        }
+
+        Ok(Self { phases })
+    }
+
+    /// The phase covering this local weekday and time, if any
+    pub fn phase_at(&self, weekday: Weekday, time: NaiveTime) -> Option<&str> {
+        let minute_of_week =
+            weekday.num_days_from_monday() * MINUTES_PER_DAY + time.hour() * 60 + time.minute();
+        self.phases
+            .iter()
+            .find(|p| p.covers(minute_of_week))
+            .map(|p| p.name.as_str())
+    }
+}
+
+fn parse_time(value: &str) -> Option<u32> {
+    let time = NaiveTime::parse_from_str(value.trim(), "%H:%M").ok()?;
+    Some(time.hour() * 60 + time.minute())
+}
+
+/// Day names ("mon", "Tuesday"), "weekdays" or "weekends"; empty means every day
+fn parse_days(days: &[String]) -> Result<u8, String> {
+    if days.is_empty() {
+        return Ok(0b111_1111);
+    }
+    let mut mask = 0u8;
+    for day in days {
+        mask |= match day.trim().to_ascii_lowercase().as_str() {
+            "weekdays" => 0b001_1111,
+            "weekends" => 0b110_0000,
+            other => {
+                let weekday: Weekday = other.parse().map_err(|_| day.clone())?;
+                1 << weekday.num_days_from_monday()
+            }
+        };
+    }
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HeartbeatPhase;
+
+    fn phase(name: &str, start: &str, end: &str, days: &[&str]) -> HeartbeatPhase {
+        HeartbeatPhase {
+            name: name.to_string(),
+            start: start.to_string(),
+            end: end.to_string(),
+            days: days.iter().map(|d| d.to_string()).collect(),
+        }
+    }
+
+    fn config(phases: Vec<HeartbeatPhase>) -> HeartbeatConfig {
+        HeartbeatConfig {
+            phases,
+            ..HeartbeatConfig::default()
+        }
+    }
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_default_phases_match_previous_windows() {
+        let schedule = HeartbeatSchedule::from_config(&HeartbeatConfig::default()).unwrap();
+        assert_eq!(schedule.phase_at(Weekday::Wed, at(7, 0)), Some("morning"));
+        assert_eq!(schedule.phase_at(Weekday::Wed, at(8, 59)), Some("morning"));
+        assert_eq!(schedule.phase_at(Weekday::Wed, at(9, 0)), None);
+        assert_eq!(schedule.phase_at(Weekday::Sun, at(13, 30)), Some("midday"));
+        assert_eq!(schedule.phase_at(Weekday::Sat, at(19, 0)), Some("evening"));
+    }
+
+    #[test]
+    fn test_overnight_phase_on_selected_days() {
+        let schedule_config = config(vec![
+            phase("shift start", "22:00", "01:00", &["fri", "sat"]),
+            phase("wake", "14:00", "15:00", &["weekends"]),
+        ]);
+        let schedule = HeartbeatSchedule::from_config(&schedule_config).unwrap();
+        assert_eq!(
+            schedule.phase_at(Weekday::Fri, at(23, 0)),
+            Some("shift start")
+        );
+        // Saturday's shift spills into Sunday morning
+        assert_eq!(
+            schedule.phase_at(Weekday::Sun, at(0, 30)),
+            Some("shift start")
+        );
+        assert_eq!(schedule.phase_at(Weekday::Thu, at(23, 0)), None);
+        assert_eq!(schedule.phase_at(Weekday::Sat, at(14, 10)), Some("wake"));
+        assert_eq!(schedule.phase_at(Weekday::Mon, at(14, 10)), None);
+    }
+
+    #[test]
+    fn test_adaptive_target_follows_load() {
+        assert_eq!(adaptive_target(0, 0, 1, 6), 1);
+        assert_eq!(adaptive_target(3, 2, 1, 6), 2);
+        assert_eq!(adaptive_target(6, 0, 1, 6), 3);
+        assert_eq!(adaptive_target(20, 10, 1, 6), 6);
+
+        assert_eq!(quiet_gap_minutes(2, 3), QUIET_GAP_MINUTES);
+        assert_eq!(quiet_gap_minutes(6, 3), 45);
+        assert_eq!(quiet_gap_minutes(20, 3), 30);
+    }
+
+    #[test]
+    fn test_invalid_schedules_rejected() {
+        let overlapping = config(vec![
+            phase("late", "22:00", "02:00", &[]),
+            phase("early", "01:00", "03:00", &["tue"]),
+        ]);
+        let err = HeartbeatSchedule::from_config(&overlapping).unwrap_err();
+        assert!(err.contains("overlap"), "{}", err);
+
+        let bad_day = config(vec![phase("x", "07:00", "08:00", &["someday"])]);
+        assert!(HeartbeatSchedule::from_config(&bad_day).is_err());
+
+        let bad_time = config(vec![phase("x", "7am", "08:00", &[])]);
+        assert!(HeartbeatSchedule::from_config(&bad_time).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! HTTP utility functions for consistent error handling across API clients,
+//! and the per-host rate limits every third-party request goes through
+
+use crate::config::RateLimitsConfig;
+use crate::errors::JasperError;
+use anyhow::{anyhow, Result};
+use chrono::{Local, NaiveDate};
+use parking_lot::Mutex;
+use reqwest::{RequestBuilder, Response};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Budgets shared by every client in the process
+static RATE_LIMITER: OnceLock<Mutex<RateLimiter>> = OnceLock::new();
+
+fn rate_limiter() -> &'static Mutex<RateLimiter> {
+    RATE_LIMITER.get_or_init(|| Mutex::new(RateLimiter::new(RateLimitsConfig::default())))
+}
+
+/// Replace the limits; what each host has used today still counts
+pub fn set_rate_limits(limits: &RateLimitsConfig) {
+    rate_limiter().lock().limits = limits.clone();
+}
+
+/// Send `request` once its host's rate limit allows. Fails without sending
+/// when the host's daily budget is used up.
+pub async fn send(request: RequestBuilder) -> Result<Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    if let Some(host) = request.url().host_str() {
+        let wait =
+            rate_limiter()
+                .lock()
+                .reserve(host, Instant::now(), Local::now().date_naive())?;
+        if !wait.is_zero() {
+            debug!("Rate limit: waiting {:?} before calling {}", wait, host);
+            tokio::time::sleep(wait).await;
+        }
+    }
+    Ok(client.execute(request).await?)
+}
+
+struct RateLimiter {
+    limits: RateLimitsConfig,
+    usage: HashMap<String, HostUsage>,
+}
+
+struct HostUsage {
+    /// Earliest time the next request may go out
+    next_slot: Instant,
+    day: NaiveDate,
+    used_today: u32,
+}
+
+impl RateLimiter {
+    fn new(limits: RateLimitsConfig) -> Self {
+        Self {
+            limits,
+            usage: HashMap::new(),
+        }
+    }
+
+    /// Count a request to `host` and return how long it has to wait
+    fn reserve(
+        &mut self,
+        host: &str,
+        now: Instant,
+        today: NaiveDate,
+    ) -> std::result::Result<Duration, JasperError> {
+        let (counted_as, limit) = self.limits.for_host(host);
+        let usage = self
+            .usage
+            .entry(counted_as.to_string())
+            .or_insert(HostUsage {
+                next_slot: now,
+                day: today,
+                used_today: 0,
+            });
+        if usage.day != today {
+            usage.day = today;
+            usage.used_today = 0;
+        }
+        if usage.used_today >= limit.daily {
+            return Err(JasperError::Api {
+                service: counted_as.to_string(),
+                message: format!(
+                    "daily budget of {} requests used up; requests resume tomorrow",
+                    limit.daily
+                ),
+            });
+        }
+        usage.used_today += 1;
+        let slot = usage.next_slot.max(now);
+        usage.next_slot = slot + Duration::from_secs_f64(1.0 / limit.qps);
+        Ok(slot - now)
+    }
+}
+
+/// Handle Google API response errors with consistent logging and error formatting
+pub async fn handle_google_api_response(response: Response) -> Result<Response> {
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await?;
+        warn!("Google Calendar API error: {} - {}", status, error_text);
+        return Err(anyhow!(
+            "Google Calendar API error: {} - {}",
+            status,
+            error_text
+        ));
+    }
+    Ok(response)
+}
+
+/// Handle OAuth2 response errors and return response text for debugging
+pub async fn handle_oauth2_response_with_text(response: Response) -> Result<String> {
+    let status = response.status();
+    let response_text = response.text().await?;
+
+    if !status.is_success() {
+        return Err(anyhow!(
+            "Google OAuth2 error: {} - {}",
+            status,
+            response_text
+        ));
+    }
+
+    Ok(response_text)
+}
+
+/// Parse JSON response with consistent error handling
+pub async fn parse_json_response<T>(response: Response, context: &str) -> Result<T>
+where
+    T: serde::de::DeserializeOwned,
+{
+    response
+        .json()
+        .await
+        .map_err(|e| anyhow!("Failed to parse {}: {}", context, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HostRateLimit;
+
+    #[test]
+    fn test_requests_are_spaced_and_capped_per_host() {
+        let mut limits = RateLimitsConfig::default();
+        limits.hosts.insert(
+            "googleapis.com".to_string(),
+            HostRateLimit { qps: 2.0, daily: 3 },
+        );
+        let mut limiter = RateLimiter::new(limits);
+        let now = Instant::now();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+
+        // Subdomains share their parent's entry: spaced 500ms apart, then cut off
+        let waits: Vec<Duration> = [
+            "www.googleapis.com",
+            "oauth2.googleapis.com",
+            "www.googleapis.com",
+        ]
+        .iter()
+        .map(|host| limiter.reserve(host, now, today).unwrap())
+        .collect();
+        assert_eq!(
+            waits,
+            [
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_millis(1000)
+            ]
+        );
+        let error = limiter
+            .reserve("www.googleapis.com", now, today)
+            .unwrap_err();
+        assert!(
+            matches!(error, JasperError::Api { ref service, .. } if service == "googleapis.com")
+        );
+
+        // Other hosts have their own budgets, and a new day restores this one
+        assert_eq!(
+            limiter.reserve("api.todoist.com", now, today).unwrap(),
+            Duration::ZERO
+        );
+        let later = now + Duration::from_secs(5);
+        assert_eq!(
+            limiter
+                .reserve("www.googleapis.com", later, today.succ_opt().unwrap())
+                .unwrap(),
+            Duration::ZERO
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! iCalendar (RFC 5545) export of the reminders and time blocks Jasper creates.
+//!
+//! `export-ics` prints or writes the feed on demand; with `[delivery] ics_path`
+//! set, the file is rewritten whenever an item is added or removed, so a
+//! calendar app subscribed to it (or to a synced copy) stays current.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+
+use crate::config::Config;
+use crate::database::JasperItem;
+use crate::storage::StorageBackend;
+
+/// Content lines longer than this many octets are folded
+const MAX_LINE_OCTETS: usize = 75;
+
+/// The whole feed as a VCALENDAR
+pub fn render(items: &[JasperItem], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Jasper Companion//Jasper//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Jasper".to_string(),
+    ];
+    for item in items {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!(
+            "UID:jasper-{}-{}@jasper-companion",
+            item.kind, item.id
+        ));
+        lines.push(format!("DTSTAMP:{}", timestamp(now)));
+        lines.push(format!("CREATED:{}", timestamp(item.created_at)));
+        lines.push(format!("DTSTART:{}", timestamp(item.start_time)));
+        match item.end_time {
+            Some(end) => {
+                lines.push(format!("DTEND:{}", timestamp(end)));
+                lines.push("TRANSP:OPAQUE".to_string());
+            }
+            None => lines.push("TRANSP:TRANSPARENT".to_string()),
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&item.title)));
+        if let Some(ref notes) = item.notes {
+            lines.push(format!("DESCRIPTION:{}", escape_text(notes)));
+        }
+        lines.push(format!("CATEGORIES:{}", item.kind.to_uppercase()));
+        if item.end_time.is_none() {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{}", escape_text(&item.title)));
+            lines.push("TRIGGER:PT0M".to_string());
+            lines.push("END:VALARM".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+/// Rewrite `[delivery] ics_path` from the database, if configured
+pub fn regenerate(config: &Config, database: &dyn StorageBackend) -> Result<Option<PathBuf>> {
+    let Some(ref configured) = config.delivery.ics_path else {
+        return Ok(None);
+    };
+    let path = match configured.strip_prefix("~/") {
+        Some(stripped) => dirs::home_dir()
+            .map(|home| home.join(stripped))
+            .unwrap_or_else(|| PathBuf::from(configured)),
+        None => PathBuf::from(configured),
+    };
+    write(&path, &database.get_jasper_items()?)?;
+    Ok(Some(path))
+}
+
+/// Write the feed atomically, so a subscriber never reads half a file
+pub fn write(path: &std::path::Path, items: &[JasperItem]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("ics.tmp");
+    std::fs::write(&tmp, render(items, Utc::now()))
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+fn timestamp(t: DateTime<Utc>) -> String {
+    t.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// TEXT value escaping: backslash, semicolon, comma and newlines
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Split lines over 75 octets, continuing with a leading space, without
+/// breaking a UTF-8 character
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut octets = 0;
+    for ch in line.chars() {
+        if octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts toward the continuation line
+            octets = 1;
+        }
+        folded.push(ch);
+        octets += ch.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_reminders_and_blocks_render_as_events() {
+        let at = |h, m| Utc.with_ymd_and_hms(2026, 5, 12, h, m, 0).unwrap();
+        let items = vec![
+            JasperItem {
+                id: 1,
+                kind: "reminder".to_string(),
+                title: "Call the school, re: field trip".to_string(),
+                notes: Some("Permission slip\nand $5".to_string()),
+                start_time: at(13, 0),
+                end_time: None,
+                created_at: at(8, 0),
+            },
+            JasperItem {
+                id: 2,
+                kind: "block".to_string(),
+                title: "Focus: quarterly report ".repeat(4),
+                notes: None,
+                start_time: at(14, 0),
+                end_time: Some(at(15, 30)),
+                created_at: at(8, 5),
+            },
+        ];
+
+        let ics = render(&items, at(9, 0));
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert!(ics.contains("UID:jasper-reminder-1@jasper-companion\r\n"));
+        assert!(ics.contains("SUMMARY:Call the school\\, re: field trip\r\n"));
+        assert!(ics.contains("DESCRIPTION:Permission slip\\nand $5\r\n"));
+        assert!(ics.contains("TRIGGER:PT0M"));
+        assert!(ics.contains("DTSTART:20260512T140000Z\r\nDTEND:20260512T153000Z\r\n"));
+        assert_eq!(ics.matches("BEGIN:VALARM").count(), 1);
+        assert!(ics.split("\r\n").all(|line| line.len() <= MAX_LINE_OCTETS));
+        // The long block title was folded onto a continuation line
+        assert!(ics.contains("\r\n "));
+    }
+}
@@ -0,0 +1,169 @@
+//! Short-lived context pushed in from outside over D-Bus.
+//!
+//! A script calls `InjectContext(source_id, json, ttl)` to tell Jasper
+//! something no configured source knows ("deploy in progress", "kid home
+//! sick"). The payload is included in every analysis until its TTL runs
+//! out; injecting under the same `source_id` again replaces it, and a TTL
+//! of 0 withdraws it. Nothing here is written to disk, so a restart
+//! forgets all of it.
+
+use crate::errors::{JasperError, JasperResult};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Longest payload accepted, in bytes of compact JSON
+pub const MAX_PAYLOAD_BYTES: usize = 4_096;
+
+/// Longest TTL accepted
+pub const MAX_TTL_SECONDS: u32 = 7 * 24 * 60 * 60;
+
+/// At most this many sources at once; a new one beyond it is refused
+pub const MAX_SOURCES: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct InjectedContext {
+    pub source_id: String,
+    /// The payload as compact JSON
+    pub content: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl InjectedContext {
+    /// The payload as the prompt shows it: a JSON string unquoted, anything
+    /// else as JSON
+    pub fn summary(&self) -> String {
+        match serde_json::from_str::<serde_json::Value>(&self.content) {
+            Ok(serde_json::Value::String(text)) => text,
+            _ => self.content.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct InjectedContextStore {
+    entries: parking_lot::Mutex<BTreeMap<String, InjectedContext>>,
+}
+
+impl InjectedContextStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `json` under `source_id` for `ttl_seconds`, replacing what was
+    /// there. A TTL of 0 removes the entry and returns None.
+    pub fn inject(
+        &self,
+        source_id: &str,
+        json: &str,
+        ttl_seconds: u32,
+        now: DateTime<Utc>,
+    ) -> JasperResult<Option<InjectedContext>> {
+        let source_id = source_id.trim();
+        if source_id.is_empty() || source_id.chars().any(char::is_control) {
+            return Err(JasperError::Validation {
+                field: "source_id".to_string(),
+                message: "must be non-empty printable text".to_string(),
+            });
+        }
+
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| entry.expires_at > now);
+        if ttl_seconds == 0 {
+            entries.remove(source_id);
+            return Ok(None);
+        }
+        if ttl_seconds > MAX_TTL_SECONDS {
+            return Err(JasperError::Validation {
+                field: "ttl".to_string(),
+                message: format!("must be at most {} seconds", MAX_TTL_SECONDS),
+            });
+        }
+
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| JasperError::Parsing {
+                format: "JSON".to_string(),
+                message: format!("context from {}: {}", source_id, e),
+            })?;
+        let content = value.to_string();
+        if content.len() > MAX_PAYLOAD_BYTES {
+            return Err(JasperError::Validation {
+                field: "json".to_string(),
+                message: format!(
+                    "payload is {} bytes; the limit is {}",
+                    content.len(),
+                    MAX_PAYLOAD_BYTES
+                ),
+            });
+        }
+        if !entries.contains_key(source_id) && entries.len() >= MAX_SOURCES {
+            return Err(JasperError::Validation {
+                field: "source_id".to_string(),
+                message: format!(
+                    "already holding context from {} sources; let one expire first",
+                    MAX_SOURCES
+                ),
+            });
+        }
+
+        let entry = InjectedContext {
+            source_id: source_id.to_string(),
+            content,
+            expires_at: now + Duration::seconds(ttl_seconds as i64),
+        };
+        entries.insert(source_id.to_string(), entry.clone());
+        Ok(Some(entry))
+    }
+
+    /// Entries still live at `now`, by source ID
+    pub fn active(&self, now: DateTime<Utc>) -> Vec<InjectedContext> {
+        let mut entries = self.entries.lock();
+        entries.retain(|_, entry| entry.expires_at > now);
+        entries.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_replace_expire_and_withdraw() {
+        let store = InjectedContextStore::new();
+        let now = Utc::now();
+
+        store
+            .inject(
+                "deploy",
+                r#"{"service": "api", "state": "rolling out"}"#,
+                600,
+                now,
+            )
+            .unwrap();
+        let sick = store
+            .inject(" family ", r#""Kid home sick today""#, 3600, now)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sick.source_id, "family");
+        assert_eq!(sick.summary(), "Kid home sick today");
+
+        // Same source replaces; payloads are stored compact
+        store
+            .inject("deploy", r#"{ "state" : "done" }"#, 60, now)
+            .unwrap();
+        let active = store.active(now);
+        assert_eq!(active.len(), 2);
+        assert_eq!(active[0].summary(), r#"{"state":"done"}"#);
+
+        // Expiry and a TTL of 0 both drop entries
+        assert_eq!(store.active(now + Duration::seconds(61)).len(), 1);
+        assert!(store.inject("family", "null", 0, now).unwrap().is_none());
+        assert!(store.active(now).is_empty());
+
+        assert!(store.inject("", "1", 60, now).is_err());
+        assert!(store.inject("x", "not json", 60, now).is_err());
+        assert!(store.inject("x", "1", MAX_TTL_SECONDS + 1, now).is_err());
+        let huge = serde_json::to_string(&"a".repeat(MAX_PAYLOAD_BYTES)).unwrap();
+        assert!(store.inject("x", &huge, 60, now).is_err());
+    }
+}
@@ -0,0 +1,320 @@
+//! Everything behind one insight, as `show` lays it out.
+//!
+//! An insight is shown with when it was generated and by which model, the
+//! trigger that led to it, the sources its prompt drew on and its links,
+//! along with the evidence kept in its context snapshot: the events and tasks
+//! it mentions (or, when it names none, the next few of each) and the
+//! weather. The trigger audit isn't keyed by insight, so the trigger is the
+//! last one that fired in the [`TRIGGER_WINDOW_MINUTES`] before the insight
+//! was stored; manual refreshes aren't audited and show none.
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::database::{Insight, TriggerAuditEntry};
+use crate::errors::JasperResult;
+use crate::significance_engine::{
+    CalendarEventSummary, ContextSnapshot, TaskSummary, WeatherSummary,
+};
+use crate::snapshot_codec;
+use crate::storage::StorageBackend;
+
+/// How long before an insight its trigger may have fired (collection and the
+/// AI call happen in between)
+pub const TRIGGER_WINDOW_MINUTES: i64 = 10;
+
+/// Events or tasks shown when the insight mentions none
+const FALLBACK_ITEMS: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InsightDetail {
+    pub insight: Insight,
+    pub trigger: Option<TriggerAuditEntry>,
+    /// Items each context source contributed to the prompt
+    pub sources: BTreeMap<String, u32>,
+    /// (label, URI) links, in display order
+    pub actions: Vec<(String, String)>,
+    /// Whether the context snapshot was kept; without it there's no evidence
+    pub snapshot_kept: bool,
+    pub events: Vec<CalendarEventSummary>,
+    pub tasks: Vec<TaskSummary>,
+    pub weather: Option<WeatherSummary>,
+}
+
+/// Whether `text` mentions `title`: it contains one of the title's words of
+/// four letters or more
+pub(crate) fn mentions(text: &str, title: &str) -> bool {
+    let words: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .collect();
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 4)
+        .any(|w| words.contains(&w.to_lowercase()))
+}
+
+/// The events and tasks `text` mentions, or when it mentions none of either,
+/// the next events and the most urgent open tasks
+fn evidence(
+    text: &str,
+    snapshot: &ContextSnapshot,
+) -> (Vec<CalendarEventSummary>, Vec<TaskSummary>) {
+    let mut events: Vec<CalendarEventSummary> = snapshot
+        .calendar_events
+        .iter()
+        .filter(|e| mentions(text, &e.title))
+        .cloned()
+        .collect();
+    if events.is_empty() {
+        events = snapshot
+            .calendar_events
+            .iter()
+            .filter(|e| e.end_time.unwrap_or(e.start_time) >= snapshot.timestamp)
+            .take(FALLBACK_ITEMS)
+            .cloned()
+            .collect();
+    }
+    let open = || snapshot.tasks.iter().filter(|t| !t.completed);
+    let mut tasks: Vec<TaskSummary> = open()
+        .filter(|t| mentions(text, &t.title))
+        .cloned()
+        .collect();
+    if tasks.is_empty() {
+        tasks = open().take(FALLBACK_ITEMS).cloned().collect();
+    }
+    (events, tasks)
+}
+
+/// Gather the detail for `insight_id`; None if there is no such insight
+pub fn load(db: &dyn StorageBackend, insight_id: i64) -> JasperResult<Option<InsightDetail>> {
+    let Some(insight) = db.get_insight_by_id(insight_id)? else {
+        return Ok(None);
+    };
+    let trigger = db.get_fired_trigger(
+        insight.created_at - Duration::minutes(TRIGGER_WINDOW_MINUTES),
+        insight.created_at,
+    )?;
+    // Snapshots from before a field was added may not parse; they just show no evidence
+    let snapshot: Option<ContextSnapshot> = snapshot_codec::load_for_insight(db, insight_id)?
+        .and_then(|value| serde_json::from_value(value).ok());
+    let (events, tasks) = snapshot
+        .as_ref()
+        .map(|s| evidence(&insight.insight, s))
+        .unwrap_or_default();
+
+    Ok(Some(InsightDetail {
+        sources: db.get_insight_sources(insight_id)?,
+        actions: db.get_insight_actions(insight_id)?,
+        snapshot_kept: snapshot.is_some(),
+        weather: snapshot.and_then(|s| s.weather),
+        events,
+        tasks,
+        trigger,
+        insight,
+    }))
+}
+
+/// The detail as text for a terminal, with times in `tz`
+pub fn render<Tz: TimeZone>(detail: &InsightDetail, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let when = |t: &DateTime<Utc>| t.with_timezone(tz).format("%a %-d %b %H:%M").to_string();
+    let insight = &detail.insight;
+    let mut out = format!("{} {}\n\n", insight.emoji, insight.insight);
+
+    let mut field = |label: &str, value: String| {
+        out.push_str(&format!("{:<11}{}\n", label, value));
+    };
+    field("Insight", format!("#{}", insight.id));
+    let model = match (&insight.model, insight.fallback) {
+        (Some(model), true) => format!(" by {} (fallback)", model),
+        (Some(model), false) => format!(" by {}", model),
+        (None, _) => " locally".to_string(),
+    };
+    field(
+        "Generated",
+        format!("{}{}", when(&insight.created_at), model),
+    );
+    if let Some(expires) = &insight.expires_at {
+        field("Expires", when(expires));
+    }
+    let trigger = match &detail.trigger {
+        Some(t) => match &t.detail {
+            Some(d) => format!("{} at {}: {}", t.trigger, when(&t.created_at), d),
+            None => format!("{} at {}", t.trigger, when(&t.created_at)),
+        },
+        None => "manual refresh, or not recorded".to_string(),
+    };
+    field("Trigger", trigger);
+    if !detail.sources.is_empty() {
+        let sources: Vec<String> = detail
+            .sources
+            .iter()
+            .map(|(source, items)| format!("{} ({})", source, items))
+            .collect();
+        field("Sources", sources.join(", "));
+    }
+
+    if !detail.snapshot_kept {
+        out.push_str("\nNo context snapshot was kept for this insight.\n");
+    }
+    if !detail.events.is_empty() {
+        out.push_str("\nEvents\n");
+        for e in &detail.events {
+            let time = if e.is_all_day {
+                format!(
+                    "{} all day",
+                    e.start_time.with_timezone(tz).format("%a %-d %b")
+                )
+            } else {
+                let end = e
+                    .end_time
+                    .map(|end| end.with_timezone(tz).format("–%H:%M").to_string())
+                    .unwrap_or_default();
+                format!("{}{}", when(&e.start_time), end)
+            };
+            match &e.location {
+                Some(location) if !location.is_empty() => {
+                    out.push_str(&format!("  {:<24} {} ({})\n", time, e.title, location))
+                }
+                _ => out.push_str(&format!("  {:<24} {}\n", time, e.title)),
+            }
+        }
+    }
+    if !detail.tasks.is_empty() {
+        out.push_str("\nTasks\n");
+        for t in &detail.tasks {
+            let due = t
+                .due
+                .map(|due| format!(", due {}", when(&due)))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "  {} (urgency {}/10{}, {})\n",
+                t.title, t.urgency, due, t.source
+            ));
+        }
+    }
+    if let Some(w) = &detail.weather {
+        let symbol = w.units.temperature_symbol();
+        out.push_str(&format!(
+            "\nWeather\n  {}, {}{} (feels like {}{})\n",
+            w.condition, w.temperature, symbol, w.feels_like, symbol
+        ));
+    }
+    if !detail.actions.is_empty() {
+        out.push_str("\nLinks\n");
+        for (label, uri) in &detail.actions {
+            out.push_str(&format!("  {}: {}\n", label, uri));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_sources::UnitSystem;
+
+    fn event(title: &str, start: DateTime<Utc>) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::hours(1)),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_detail_shows_the_mentioned_evidence() {
+        let now = Utc.with_ymd_and_hms(2026, 5, 12, 8, 0, 0).unwrap();
+        let snapshot = ContextSnapshot {
+            calendar_events: vec![
+                event("Standup", now + Duration::hours(1)),
+                event("Dentist appointment", now + Duration::hours(5)),
+            ],
+            weather: Some(WeatherSummary {
+                condition: "Showers".to_string(),
+                temperature: 14,
+                feels_like: 12,
+                units: UnitSystem::Metric,
+            }),
+            tasks: vec![TaskSummary {
+                id: "1".to_string(),
+                title: "Fill in dentist forms".to_string(),
+                due: None,
+                completed: false,
+                priority: 4,
+                urgency: 6,
+                source: "todoist".to_string(),
+            }],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: now,
+            context_hash: String::new(),
+        };
+        let text = "Leave by 12:30 for the dentist; the forms are still open.";
+        let (events, tasks) = evidence(text, &snapshot);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].title, "Dentist appointment");
+        assert_eq!(tasks.len(), 1);
+        // Nothing named: the next events stand in
+        let (events, _) = evidence("Quiet morning ahead.", &snapshot);
+        assert_eq!(events.len(), 2);
+
+        let detail = InsightDetail {
+            insight: Insight {
+                id: 7,
+                emoji: "🦷".to_string(),
+                insight: text.to_string(),
+                context_hash: None,
+                created_at: now,
+                expires_at: None,
+                is_active: true,
+                model: Some("claude-sonnet".to_string()),
+                fallback: false,
+            },
+            trigger: Some(TriggerAuditEntry {
+                id: 1,
+                trigger: "heartbeat:morning".to_string(),
+                decision: "fired".to_string(),
+                detail: None,
+                created_at: now - Duration::minutes(1),
+            }),
+            sources: BTreeMap::from([("calendar".to_string(), 2)]),
+            actions: vec![],
+            snapshot_kept: true,
+            weather: snapshot.weather.clone(),
+            events: vec![snapshot.calendar_events[1].clone()],
+            tasks: snapshot.tasks.clone(),
+        };
+        assert_eq!(
+            render(&detail, &Utc),
+            "🦷 Leave by 12:30 for the dentist; the forms are still open.\n\n\
+             Insight    #7\n\
+             Generated  Tue 12 May 08:00 by claude-sonnet\n\
+             Trigger    heartbeat:morning at Tue 12 May 07:59\n\
+             Sources    calendar (2)\n\n\
+             Events\n  Tue 12 May 13:00–14:00   Dentist appointment\n\n\
+             Tasks\n  Fill in dentist forms (urgency 6/10, todoist)\n\n\
+             Weather\n  Showers, 14°C (feels like 12°C)\n"
+        );
+    }
+}
@@ -0,0 +1,108 @@
+//! Insight payloads pre-rendered for each frontend's markup.
+//!
+//! Frontends declare a format when they register (`RegisterFrontendWithFormat`)
+//! and the `InsightUpdated` signal carries the insight rendered in every
+//! format a registered frontend asked for, so applets don't each carry their
+//! own escaping rules. Plain text is always included.
+
+/// Markup a frontend can display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum InsightFormat {
+    Plain,
+    /// GTK / GNOME Shell / Waybar markup
+    Pango,
+    Markdown,
+    Html,
+}
+
+impl InsightFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "plain" | "" => Some(InsightFormat::Plain),
+            "pango" => Some(InsightFormat::Pango),
+            "markdown" => Some(InsightFormat::Markdown),
+            "html" => Some(InsightFormat::Html),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InsightFormat::Plain => "plain",
+            InsightFormat::Pango => "pango",
+            InsightFormat::Markdown => "markdown",
+            InsightFormat::Html => "html",
+        }
+    }
+
+    /// Emoji and insight text as a single payload in this format
+    pub fn render(self, emoji: &str, text: &str) -> String {
+        match self {
+            InsightFormat::Html => format!(
+                "<span class=\"jasper-emoji\">{}</span> <span class=\"jasper-insight\">{}</span>",
+                emoji,
+                self.escape(text)
+            ),
+            _ => format!("{} {}", emoji, self.escape(text)),
+        }
+    }
+
+    /// Escape text so it displays literally in this format
+    pub fn escape(self, text: &str) -> String {
+        match self {
+            InsightFormat::Plain => text.to_string(),
+            InsightFormat::Pango | InsightFormat::Html => escape_markup(text),
+            InsightFormat::Markdown => escape_markdown(text),
+        }
+    }
+}
+
+/// Escaping shared by Pango and HTML
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(
+            ch,
+            '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_format_escapes_its_own_markup() {
+        let text = "Pick up <Maya> & Leo_s *snacks*";
+        assert_eq!(
+            InsightFormat::Plain.render("🚗", text),
+            "🚗 Pick up <Maya> & Leo_s *snacks*"
+        );
+        assert_eq!(
+            InsightFormat::Pango.render("🚗", text),
+            "🚗 Pick up &lt;Maya&gt; &amp; Leo_s *snacks*"
+        );
+        assert_eq!(
+            InsightFormat::Markdown.render("🚗", text),
+            "🚗 Pick up \\<Maya\\> & Leo\\_s \\*snacks\\*"
+        );
+        assert!(InsightFormat::Html
+            .render("🚗", text)
+            .contains(">Pick up &lt;Maya&gt; &amp; Leo_s *snacks*</span>"));
+        assert_eq!(InsightFormat::from_name("HTML"), Some(InsightFormat::Html));
+        assert_eq!(InsightFormat::from_name("rtf"), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! Longer-term "already told you" memory for the insight prompt.
+//!
+//! The prompt quotes the last few insights verbatim, which does nothing for
+//! the permission slip mentioned on Monday and again on Wednesday. Each
+//! insight is also kept for a month as a compact signature (emoji, content
+//! keywords, first sentence). Signatures that overlap are grouped
+//! into topics, and the prompt gets one line per topic, saying how often and
+//! when it came up, so the model escalates or adds something new instead of
+//! repeating itself.
+
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::BTreeSet;
+
+use crate::database::InsightMemory;
+
+/// Topics listed in the prompt, most recent first
+pub const MAX_TOPICS: usize = 8;
+/// Longest summary kept per insight
+const SUMMARY_CHARS: usize = 80;
+/// Share of the smaller keyword set that must match for the same topic
+const SAME_TOPIC_OVERLAP: f32 = 0.5;
+
+const STOPWORDS: &[&str] = &[
+    "the",
+    "and",
+    "for",
+    "you",
+    "your",
+    "with",
+    "that",
+    "this",
+    "are",
+    "but",
+    "not",
+    "has",
+    "have",
+    "had",
+    "was",
+    "will",
+    "can",
+    "all",
+    "just",
+    "now",
+    "get",
+    "got",
+    "out",
+    "off",
+    "its",
+    "it's",
+    "from",
+    "into",
+    "about",
+    "before",
+    "after",
+    "today",
+    "tomorrow",
+    "tonight",
+    "morning",
+    "afternoon",
+    "evening",
+    "minutes",
+    "hour",
+    "hours",
+    "don't",
+    "make",
+    "sure",
+    "still",
+    "there",
+    "their",
+    "them",
+    "they",
+    "what",
+    "when",
+    "then",
+    "than",
+    "some",
+    "maybe",
+    "might",
+    "could",
+    "would",
+    "should",
+    "time",
+    "head",
+    "heads",
+    "up",
+    "keep",
+    "mind",
+    "quick",
+    "reminder",
+    "remember",
+    "also",
+    "again",
+    "only",
+    "less",
+    "more",
+    "need",
+    "needs",
+];
+
+/// Distinct content words, lowercased and lightly stemmed, for topic matching
+pub fn keywords(text: &str) -> Vec<String> {
+    let words: BTreeSet<String> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '\''))
+        .map(|w| w.trim_matches('\'').to_lowercase())
+        .filter(|w| w.chars().count() >= 3 && !w.chars().all(|c| c.is_ascii_digit()))
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .map(|w| {
+            if let Some(stem) = w.strip_suffix("'s") {
+                return stem.to_string();
+            }
+            match w.strip_suffix('s') {
+                Some(stem) if stem.len() >= 3 && !stem.ends_with('s') => stem.to_string(),
+                _ => w,
+            }
+        })
+        .collect();
+    words.into_iter().collect()
+}
+
+/// First sentence of an insight, cut to a prompt-friendly length
+pub fn summarize(text: &str) -> String {
+    let sentence = text
+        .split_inclusive(['.', '!', '?'])
+        .next()
+        .unwrap_or(text)
+        .trim();
+    if sentence.chars().count() <= SUMMARY_CHARS {
+        sentence.to_string()
+    } else {
+        let cut: String = sentence.chars().take(SUMMARY_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// Insights about the same thing, with when each was shown
+#[derive(Debug, Clone)]
+pub struct Topic {
+    pub emoji: String,
+    /// Summary of the most recent mention
+    pub summary: String,
+    pub keywords: BTreeSet<String>,
+    /// Oldest first
+    pub mentions: Vec<DateTime<Utc>>,
+}
+
+/// Group memories (any order) into topics, most recently mentioned first
+pub fn topics(memories: &[InsightMemory]) -> Vec<Topic> {
+    let mut sorted: Vec<&InsightMemory> = memories.iter().collect();
+    sorted.sort_by_key(|m| m.created_at);
+
+    let mut topics: Vec<Topic> = Vec::new();
+    for memory in sorted {
+        let words: BTreeSet<String> = memory
+            .keywords
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+        let existing = topics.iter_mut().find(|t| same_topic(&t.keywords, &words));
+        match existing {
+            Some(topic) => {
+                topic.emoji = memory.emoji.clone();
+                topic.summary = memory.summary.clone();
+                topic.keywords.extend(words);
+                topic.mentions.push(memory.created_at);
+            }
+            None => topics.push(Topic {
+                emoji: memory.emoji.clone(),
+                summary: memory.summary.clone(),
+                keywords: words,
+                mentions: vec![memory.created_at],
+            }),
+        }
+    }
+    topics.sort_by_key(|t| std::cmp::Reverse(t.mentions.last().copied()));
+    topics
+}
+
+/// At least two shared keywords, covering half of the smaller set
+fn same_topic(a: &BTreeSet<String>, b: &BTreeSet<String>) -> bool {
+    let shared = a.intersection(b).count();
+    let smaller = a.len().min(b.len());
+    shared >= 2 && shared as f32 >= smaller as f32 * SAME_TOPIC_OVERLAP
+}
+
+/// Prompt lines, e.g. `- 📋 "Maya's permission slip is due Friday." — told 2× (Mon, today)`
+pub fn render(topics: &[Topic], now: &DateTime<FixedOffset>) -> Option<String> {
+    if topics.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = topics
+        .iter()
+        .take(MAX_TOPICS)
+        .map(|topic| {
+            let days: Vec<String> = topic.mentions.iter().map(|t| day_label(t, now)).fold(
+                Vec::new(),
+                |mut days, day| {
+                    if days.last() != Some(&day) {
+                        days.push(day);
+                    }
+                    days
+                },
+            );
+            let count = if topic.mentions.len() == 1 {
+                "told once".to_string()
+            } else {
+                format!("told {}×", topic.mentions.len())
+            };
+            format!(
+                "- {} \"{}\" — {} ({})",
+                topic.emoji,
+                topic.summary,
+                count,
+                days.join(", ")
+            )
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// "today", "yesterday", a weekday within the last week, else "Oct 3"
+fn day_label(at: &DateTime<Utc>, now: &DateTime<FixedOffset>) -> String {
+    let day = at.with_timezone(&now.timezone()).date_naive();
+    match (now.date_naive() - day).num_days() {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..=6 => day.format("%a").to_string(),
+        _ => day.format("%b %-d").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn memory(id: i64, emoji: &str, text: &str, at: DateTime<Utc>) -> InsightMemory {
+        InsightMemory {
+            insight_id: id,
+            emoji: emoji.to_string(),
+            keywords: keywords(text).join(" "),
+            summary: summarize(text),
+            created_at: at,
+        }
+    }
+
+    #[test]
+    fn test_repeated_reminders_collapse_into_one_topic() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        // Friday afternoon
+        let now = tz.with_ymd_and_hms(2026, 5, 15, 15, 0, 0).unwrap();
+        let at = |day, h| tz.with_ymd_and_hms(2026, 5, day, h, 0, 0).unwrap().to_utc();
+        let memories = vec![
+            memory(
+                1,
+                "📋",
+                "Maya's field trip permission slip is due Friday — sign it tonight.",
+                at(11, 18),
+            ),
+            memory(
+                2,
+                "🌧️",
+                "Rain moves in around 4, so soccer practice might get muddy.",
+                at(13, 9),
+            ),
+            memory(
+                3,
+                "📋",
+                "Still need to sign Maya's permission slip for the field trip!",
+                at(13, 19),
+            ),
+            memory(
+                4,
+                "⏰",
+                "Permission slip for Maya's field trip is due today.",
+                at(15, 8),
+            ),
+        ];
+
+        let topics = topics(&memories);
+        assert_eq!(topics.len(), 2, "{:#?}", topics);
+        assert_eq!(topics[0].mentions.len(), 3);
+        assert_eq!(topics[0].emoji, "⏰");
+
+        let prompt = render(&topics, &now).unwrap();
+        let lines: Vec<&str> = prompt.lines().collect();
+        assert_eq!(
+            lines[0],
+            "- ⏰ \"Permission slip for Maya's field trip is due today.\" — told 3× (Mon, Wed, today)"
+        );
+        assert!(lines[1].contains("told once (Wed)"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn test_summary_keeps_first_sentence() {
+        assert_eq!(summarize("Leave by 5. Traffic is heavy."), "Leave by 5.");
+        assert_eq!(
+            summarize(&"word ".repeat(40)).chars().count(),
+            SUMMARY_CHARS
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Invitations and the user's answers to them.
+//!
+//! During sync each event keeps the user's own response from its guest list,
+//! "needsAction" until they answer. Upcoming events still waiting on an
+//! answer get their own prompt section, so insights can nag about them, and
+//! `rsvp` (CLI and D-Bus) answers one through the Google Calendar API.
+//! Declined and tentative events are dropped or demoted per `[attendance]`
+//! when context is collected, so it applies whatever backend stored them.
+
+use chrono::{DateTime, FixedOffset, Utc};
+use clap::ValueEnum;
+
+use crate::config::{AttendanceConfig, AttendancePolicy};
+use crate::database::Invitation;
+use crate::significance_engine::CalendarEventSummary;
+
+/// Google's response status for an invitation not yet answered
+pub const NEEDS_ACTION: &str = "needsAction";
+/// Invitations listed in the prompt, soonest first
+const MAX_LISTED: usize = 8;
+
+/// An answer to an invitation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Response {
+    Accept,
+    Decline,
+    Tentative,
+}
+
+impl Response {
+    /// "accept", "decline" or "tentative" (past tenses and "maybe" too)
+    pub fn parse(answer: &str) -> Option<Self> {
+        match answer.trim().to_lowercase().as_str() {
+            "accept" | "accepted" | "yes" => Some(Response::Accept),
+            "decline" | "declined" | "no" => Some(Response::Decline),
+            "tentative" | "maybe" => Some(Response::Tentative),
+            _ => None,
+        }
+    }
+
+    /// The attendee `responseStatus` this answer sets
+    pub fn status(self) -> &'static str {
+        match self {
+            Response::Accept => "accepted",
+            Response::Decline => "declined",
+            Response::Tentative => "tentative",
+        }
+    }
+}
+
+/// What to do with an event the user answered `response` to
+pub fn policy(config: &AttendanceConfig, response: Option<&str>) -> AttendancePolicy {
+    match response {
+        Some("declined") => config.declined,
+        Some("tentative") => config.tentative,
+        _ => AttendancePolicy::Keep,
+    }
+}
+
+/// The invitation `query` names: an event ID, or part of a title. Ones still
+/// unanswered win over ones already answered; of several occurrences of a
+/// recurring event, the first.
+pub fn pick<'a>(invitations: &'a [Invitation], query: &str) -> Result<&'a Invitation, String> {
+    if let Some(exact) = invitations.iter().find(|i| i.source_id == query) {
+        return Ok(exact);
+    }
+    let needle = query.trim().to_lowercase();
+    let mut matches: Vec<&Invitation> = invitations
+        .iter()
+        .filter(|i| i.title.to_lowercase().contains(&needle))
+        .collect();
+    if matches.iter().any(|i| i.response == NEEDS_ACTION) {
+        matches.retain(|i| i.response == NEEDS_ACTION);
+    }
+    let mut titles: Vec<&str> = matches.iter().map(|i| i.title.as_str()).collect();
+    titles.sort_unstable();
+    titles.dedup();
+    match titles.len() {
+        0 => Err(format!("No upcoming invitation matches \"{}\"", query)),
+        1 => Ok(matches[0]),
+        _ => Err(format!(
+            "\"{}\" matches several invitations ({}); be more specific",
+            query,
+            titles.join(", ")
+        )),
+    }
+}
+
+/// One line per upcoming event still awaiting the user's answer
+pub fn prompt_lines(
+    events: &[CalendarEventSummary],
+    local_now: &DateTime<FixedOffset>,
+) -> Vec<String> {
+    let now = local_now.with_timezone(&Utc);
+    let mut pending: Vec<&CalendarEventSummary> = events
+        .iter()
+        .filter(|e| e.awaiting_reply && e.start_time > now)
+        .collect();
+    pending.sort_by_key(|e| e.start_time);
+    pending
+        .into_iter()
+        .take(MAX_LISTED)
+        .map(|event| {
+            let start = event.start_time.with_timezone(&local_now.timezone());
+            let when = if event.is_all_day {
+                start.format("%a %-d %b, all day").to_string()
+            } else {
+                start.format("%a %-d %b %-I:%M %p").to_string()
+            };
+            format!("- \"{}\" — {}", event.title, when)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_pick_prefers_unanswered_invitations() {
+        let invitation = |id: &str, title: &str, start_time: i64, response: &str| Invitation {
+            source_id: id.to_string(),
+            title: title.to_string(),
+            start_time,
+            calendar_id: "primary".to_string(),
+            response: response.to_string(),
+        };
+        let invitations = [
+            invitation("a1", "Design review", 1000, "accepted"),
+            invitation("b1", "Budget sync", 2000, NEEDS_ACTION),
+            invitation("b2", "Budget sync", 3000, NEEDS_ACTION),
+            invitation("c1", "Design review (follow-up)", 4000, NEEDS_ACTION),
+        ];
+        assert_eq!(pick(&invitations, "budget").unwrap().source_id, "b1");
+        assert_eq!(pick(&invitations, "b2").unwrap().source_id, "b2");
+        // The answered review drops out in favour of the unanswered one
+        assert_eq!(pick(&invitations, "design").unwrap().source_id, "c1");
+        assert!(pick(&invitations, "lunch").is_err());
+        let ambiguous = [
+            invitation("x", "Team lunch", 1000, NEEDS_ACTION),
+            invitation("y", "Team offsite", 2000, NEEDS_ACTION),
+        ];
+        assert!(pick(&ambiguous, "team")
+            .unwrap_err()
+            .contains("Team lunch, Team offsite"));
+        assert_eq!(Response::parse("Maybe"), Some(Response::Tentative));
+        assert_eq!(Response::Decline.status(), "declined");
+
+        let attendance = AttendanceConfig::default();
+        assert_eq!(
+            policy(&attendance, Some("declined")),
+            AttendancePolicy::Drop
+        );
+        assert_eq!(
+            policy(&attendance, Some("tentative")),
+            AttendancePolicy::Demote
+        );
+        assert_eq!(
+            policy(&attendance, Some(NEEDS_ACTION)),
+            AttendancePolicy::Keep
+        );
+        assert_eq!(policy(&attendance, None), AttendancePolicy::Keep);
+
+        let local_now = FixedOffset::west_opt(5 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2026, 3, 2, 9, 0, 0)
+            .unwrap();
+        let event = |title: &str, hour: u32, awaiting_reply: bool| CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: Utc.with_ymd_and_hms(2026, 3, 3, hour, 0, 0).unwrap(),
+            end_time: None,
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply,
+            tentative: false,
+        };
+        assert_eq!(
+            prompt_lines(
+                &[
+                    event("Budget sync", 20, true),
+                    event("Standup", 14, false),
+                    event("Design review", 15, true),
+                ],
+                &local_now
+            ),
+            vec![
+                "- \"Design review\" — Tue 3 Mar 10:00 AM",
+                "- \"Budget sync\" — Tue 3 Mar 3:00 PM",
+            ]
+        );
+    }
+}
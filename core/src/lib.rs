@@ -0,0 +1,82 @@
+//! The Jasper engine: context collection, significance detection, the AI
+//! insight loop, the local database, sanitization, the D-Bus service and the
+//! frontend formatters.
+//!
+//! `jasper-companion-daemon` is a thin CLI over this crate. Tests, frontends
+//! and other hosts can link it directly instead of going through D-Bus.
+
+pub mod accessibility;
+pub mod activity;
+pub mod agenda;
+pub mod ai_check;
+pub mod ai_response;
+pub mod api_manager;
+pub mod attachments;
+pub mod command_menu;
+pub mod config;
+pub mod config_handle;
+pub mod config_migration;
+pub mod context_sources;
+pub mod correlation_graph;
+pub mod data_import;
+pub mod data_wipe;
+pub mod database;
+pub mod day_summary;
+pub mod daylight;
+pub mod deep_links;
+pub mod demo;
+pub mod desktop_detection;
+pub mod errors;
+pub mod escalation;
+pub mod event_span;
+pub mod exit_code;
+pub mod family_digest;
+pub mod glyphs;
+pub mod google_calendar;
+pub mod greet;
+pub mod guest;
+pub mod heartbeat;
+pub mod http_utils;
+pub mod ics_export;
+pub mod injected_context;
+pub mod insight_detail;
+pub mod insight_format;
+pub mod insight_memory;
+pub mod invitations;
+pub mod lineage;
+pub mod load_stats;
+pub mod maintenance;
+pub mod meeting_links;
+pub mod mock_ai;
+pub mod new_daemon_core;
+pub mod new_dbus_service;
+pub mod noctalia_adapter;
+pub mod notification_server;
+pub mod ntfy;
+pub mod people;
+pub mod people_notes;
+pub mod platform;
+pub mod portal;
+pub mod prefilter;
+pub mod privacy_audit;
+pub mod project_risk;
+pub mod routing;
+pub mod sanitize;
+pub mod schedule_patterns;
+pub mod significance_engine;
+pub mod slack;
+pub mod snapshot_codec;
+pub mod sops_integration;
+pub mod storage;
+pub mod task_urgency;
+pub mod telegram;
+pub mod terminal_formatter;
+pub mod text_budget;
+pub mod token_budget;
+pub mod tomorrow_note;
+pub mod travel;
+pub mod update_check;
+pub mod wake_alarm;
+pub mod waybar_adapter;
+pub mod weather_gear;
+pub mod webhook;
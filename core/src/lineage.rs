@@ -0,0 +1,109 @@
+//! Which context sources each insight's prompt drew on.
+//!
+//! After the prompt is fitted to its token budget, the sections that made it
+//! in are tallied by the source they came from, with the number of items
+//! (events, tasks, projects, notes) each contributed. The tally is stored
+//! with the insight and shown by `history --verbose` and `GetInsightSources`,
+//! so a source that never makes it into a prompt, or never matters, can be
+//! spotted and switched off.
+
+use std::collections::BTreeMap;
+
+use crate::day_summary::FoldedSection;
+use crate::token_budget::PromptSection;
+
+/// The context source a prompt section was built from; None for sections
+/// that aren't context (the trigger, the empty-schedule notice)
+pub fn source_of(section: &str) -> Option<&'static str> {
+    match section {
+        "tasks" => Some("tasks"),
+        "weather" | "weather gear" => Some("weather"),
+        "daylight" => Some("daylight"),
+        "school alerts" => Some("school"),
+        "unanswered messages" => Some("slack"),
+        "projects" => Some("notes"),
+        name if name.starts_with("daily note ") => Some("notes"),
+        "schedule situations" | "schedule anomalies" | "people" | "invitations" => Some("calendar"),
+        name if name.ends_with(" calendar") || name.starts_with("shared calendar ") => {
+            Some("calendar")
+        }
+        _ => None,
+    }
+}
+
+/// Items each source contributed to the prompt: one per bullet line (a daily
+/// note counts once), at least one per section. Sections left out because
+/// the day summary stood in for them count once, for their headline.
+pub fn tally(sections: &[PromptSection], unchanged: &[FoldedSection]) -> BTreeMap<String, u32> {
+    let mut sources = BTreeMap::new();
+    for section in sections {
+        let Some(source) = source_of(&section.name) else {
+            continue;
+        };
+        let items = if section.name.starts_with("daily note ") {
+            1
+        } else {
+            section
+                .text
+                .lines()
+                .map(str::trim_start)
+                .filter(|line| line.starts_with("- ") && !line.starts_with("- ("))
+                .count()
+                .max(1) as u32
+        };
+        *sources.entry(source.to_string()).or_insert(0) += items;
+    }
+    for folded in unchanged {
+        if let Some(source) = source_of(&folded.name) {
+            *sources.entry(source.to_string()).or_insert(0) += 1;
+        }
+    }
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_budget::priority;
+
+    #[test]
+    fn test_tally_counts_items_per_source() {
+        let sections = vec![
+            PromptSection::new("trigger", priority::REQUIRED, "Trigger: heartbeat"),
+            PromptSection::new(
+                "primary calendar",
+                priority::PRIMARY_CALENDAR,
+                "\nYour calendar (next 24h):\n- \"Standup\" — in 1h\n- \"Dentist\" — at 3 PM",
+            ),
+            PromptSection::new(
+                "Kieran calendar",
+                priority::FAMILY_CALENDARS,
+                "\nKieran calendar:\n- \"Soccer\" — at 4 PM",
+            ),
+            PromptSection::new(
+                "tasks",
+                priority::TASKS,
+                "\nTasks:\n- Taxes (due today)\n- (3 more omitted)",
+            ),
+            PromptSection::new(
+                "daily note 2026-05-12",
+                priority::DAILY_NOTES,
+                "\nToday's daily note:\n- Focus: launch\n- Focus: hiring",
+            ),
+        ];
+        let unchanged = vec![FoldedSection {
+            name: "weather".to_string(),
+            fingerprint: String::new(),
+            headline: "Weather: 12°C".to_string(),
+            since: "9:10 AM".to_string(),
+        }];
+
+        let sources = tally(&sections, &unchanged);
+        let expected: BTreeMap<String, u32> =
+            [("calendar", 3), ("notes", 1), ("tasks", 1), ("weather", 1)]
+                .into_iter()
+                .map(|(s, n)| (s.to_string(), n))
+                .collect();
+        assert_eq!(sources, expected);
+    }
+}
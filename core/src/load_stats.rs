@@ -0,0 +1,191 @@
+//! Weekly meeting load and burnout indicators.
+//!
+//! Calendar sync only keeps events from now on, so meetings are logged as they
+//! are seen (future ones are re-logged each time, so cancellations drop out)
+//! and tasks with a due date are logged until they close. Weeks (Monday to
+//! Sunday) are computed from those logs into `weekly_stats`, shown by `stats`,
+//! and summarized once a month as a reflective insight.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Utc, Weekday};
+
+use crate::database::{MeetingLogEntry, TaskLogEntry, WeekStats};
+use crate::significance_engine::ContextSnapshot;
+
+/// Meetings this close together count as back-to-back
+const BACK_TO_BACK_GAP_MINUTES: i64 = 5;
+
+impl WeekStats {
+    pub fn completion_rate(&self) -> Option<f32> {
+        (self.tasks_due > 0).then(|| self.tasks_done as f32 / self.tasks_due as f32)
+    }
+
+    /// e.g. "Week of May 11: 14 meetings (11.5h), longest back-to-back run 4, 2 after hours, 6/8 tasks done"
+    pub fn summary(&self) -> String {
+        let tasks = match self.tasks_due {
+            0 => "no tasks due".to_string(),
+            due => format!("{}/{} tasks done", self.tasks_done, due),
+        };
+        format!(
+            "Week of {}: {} meetings ({:.1}h), longest back-to-back run {}, {} after hours, {}",
+            self.week_start.format("%b %-d"),
+            self.meetings,
+            self.meeting_minutes as f64 / 60.0,
+            self.longest_back_to_back,
+            self.after_hours_meetings,
+            tasks
+        )
+    }
+}
+
+/// Monday of the week containing `date`
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Timed events on the user's own calendar, as meeting log entries
+pub fn observed_meetings(context: &ContextSnapshot) -> Vec<MeetingLogEntry> {
+    context
+        .calendar_events
+        .iter()
+        .filter(|e| !e.is_all_day && e.is_own_calendar && e.is_primary_calendar)
+        .filter_map(|e| {
+            Some(MeetingLogEntry {
+                event_id: e.id.clone(),
+                title: e.title.clone(),
+                start_time: e.start_time,
+                end_time: e.end_time.filter(|end| *end > e.start_time)?,
+            })
+        })
+        .collect()
+}
+
+/// Stats for the week starting `monday`, from the logs
+pub fn compute_week<Tz: TimeZone>(
+    monday: NaiveDate,
+    meetings: &[MeetingLogEntry],
+    tasks: &[TaskLogEntry],
+    tz: &Tz,
+    workday: (NaiveTime, NaiveTime),
+) -> WeekStats {
+    let in_week = |t: &DateTime<Utc>| week_start(t.with_timezone(tz).date_naive()) == monday;
+    let mut week: Vec<&MeetingLogEntry> =
+        meetings.iter().filter(|m| in_week(&m.start_time)).collect();
+    week.sort_by_key(|m| m.start_time);
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous_end: Option<DateTime<Utc>> = None;
+    for meeting in &week {
+        run = match previous_end {
+            Some(end)
+                if meeting.start_time - end <= Duration::minutes(BACK_TO_BACK_GAP_MINUTES) =>
+            {
+                run + 1
+            }
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous_end = Some(previous_end.map_or(meeting.end_time, |end| end.max(meeting.end_time)));
+    }
+
+    let after_hours = week
+        .iter()
+        .filter(|m| {
+            let start = m.start_time.with_timezone(tz);
+            let end = m.end_time.with_timezone(tz);
+            matches!(start.weekday(), Weekday::Sat | Weekday::Sun)
+                || start.time() < workday.0
+                || end.time() > workday.1
+                || end.date_naive() != start.date_naive()
+        })
+        .count();
+
+    let due: Vec<&TaskLogEntry> = tasks.iter().filter(|t| in_week(&t.due_time)).collect();
+
+    WeekStats {
+        week_start: monday,
+        meetings: week.len() as u32,
+        meeting_minutes: week
+            .iter()
+            .map(|m| (m.end_time - m.start_time).num_minutes())
+            .sum(),
+        longest_back_to_back: longest,
+        after_hours_meetings: after_hours as u32,
+        tasks_due: due.len() as u32,
+        tasks_done: due.iter().filter(|t| t.closed_at.is_some()).count() as u32,
+    }
+}
+
+/// Trigger text for the monthly reflective insight
+pub fn reflection_prompt(weeks: &[WeekStats]) -> String {
+    let lines = weeks
+        .iter()
+        .map(|w| format!("- {}", w.summary()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "Trigger: Monthly reflection. Instead of a situational insight, look back at the \
+         past month's workload and write one warm, non-judgmental sentence about it — a trend \
+         worth noticing (rising meeting hours, long back-to-back runs, evenings eaten by \
+         meetings, tasks slipping) and, if it helps, one small thing to protect next month.\n\
+         Weekly stats (oldest first):\n{}",
+        lines
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_week_stats_count_runs_after_hours_and_tasks() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let at = |day, h, m| tz.with_ymd_and_hms(2026, 5, day, h, m, 0).unwrap().to_utc();
+        let meeting = |id: &str, start, end| MeetingLogEntry {
+            event_id: id.to_string(),
+            title: id.to_string(),
+            start_time: start,
+            end_time: end,
+        };
+        let meetings = vec![
+            meeting("standup", at(11, 9, 0), at(11, 9, 30)),
+            meeting("planning", at(11, 9, 30), at(11, 10, 30)),
+            meeting("1:1", at(11, 10, 35), at(11, 11, 0)),
+            meeting("review", at(11, 14, 0), at(11, 15, 0)),
+            meeting("late call", at(13, 18, 0), at(13, 19, 0)),
+            // Saturday, and the following week
+            meeting("offsite prep", at(16, 10, 0), at(16, 11, 0)),
+            meeting("next week", at(18, 9, 0), at(18, 10, 0)),
+        ];
+        let task = |id: &str, due, closed: bool| TaskLogEntry {
+            task_id: id.to_string(),
+            title: id.to_string(),
+            due_time: due,
+            closed_at: closed.then_some(due),
+            urgency: 5,
+        };
+        let tasks = vec![
+            task("report", at(12, 17, 0), true),
+            task("expenses", at(14, 17, 0), false),
+            task("taxes", at(20, 17, 0), false),
+        ];
+        let workday = (
+            NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        );
+
+        let monday = week_start(NaiveDate::from_ymd_opt(2026, 5, 14).unwrap());
+        assert_eq!(monday, NaiveDate::from_ymd_opt(2026, 5, 11).unwrap());
+        let stats = compute_week(monday, &meetings, &tasks, &tz, workday);
+        assert_eq!(stats.meetings, 6);
+        assert_eq!(stats.meeting_minutes, 30 + 60 + 25 + 60 + 60 + 60);
+        assert_eq!(stats.longest_back_to_back, 3);
+        assert_eq!(stats.after_hours_meetings, 2);
+        assert_eq!(stats.completion_rate(), Some(0.5));
+        assert_eq!(
+            stats.summary(),
+            "Week of May 11: 6 meetings (4.9h), longest back-to-back run 3, 2 after hours, 1/2 tasks done"
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! When the nightly maintenance window is open.
+//!
+//! Maintenance (pruning, vacuum, WAL checkpoint, cache eviction, token check)
+//! runs once per local day, from `maintenance.run_at` until the window
+//! closes. A machine asleep through the whole window skips that night rather
+//! than vacuuming in the middle of the day.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+/// Hours after `maintenance.run_at` during which the run may still start
+pub const WINDOW_HOURS: i64 = 3;
+
+/// Whether maintenance should start at `now` (local), given the local day
+/// it last started on
+pub fn is_due(run_at: NaiveTime, now: NaiveDateTime, last_run: Option<NaiveDate>) -> bool {
+    let elapsed = now.time() - run_at;
+    elapsed >= chrono::Duration::zero()
+        && elapsed < chrono::Duration::hours(WINDOW_HOURS)
+        && last_run != Some(now.date())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_once_inside_the_window() {
+        let run_at = NaiveTime::from_hms_opt(3, 0, 0).unwrap();
+        let day = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let at = |h, m| day.and_hms_opt(h, m, 0).unwrap();
+
+        assert!(!is_due(run_at, at(2, 59), None));
+        assert!(is_due(run_at, at(3, 0), None));
+        assert!(is_due(run_at, at(5, 30), day.pred_opt()));
+        assert!(!is_due(run_at, at(3, 5), Some(day)));
+        // Window closed: wait for tomorrow night
+        assert!(!is_due(run_at, at(6, 0), None));
+        assert!(!is_due(run_at, at(14, 0), None));
+    }
+}
@@ -0,0 +1,77 @@
+//! Video-call links on calendar events.
+//!
+//! During sync each event's join link is picked out once and stored with it:
+//! the conference Google Calendar attached (Meet, or a Zoom or Teams add-on)
+//! if there is one, else the first Meet, Zoom, Teams or Webex URL in the
+//! location and then the description. The daemon offers the next one as
+//! "join next meeting" and in a notification shortly before it starts.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Join URLs of the conferencing services people paste into invites
+fn meeting_url() -> &'static Regex {
+    static MEETING_URL: OnceLock<Regex> = OnceLock::new();
+    MEETING_URL.get_or_init(|| {
+        Regex::new(
+            r#"(?i)https://(?:meet\.google\.com/[a-z]{3}-[a-z]{4}-[a-z]{3}|(?:[\w-]+\.)?zoom\.us/(?:j|my|w)/[^\s<>"']+|teams\.microsoft\.com/l/meetup-join/[^\s<>"']+|teams\.live\.com/meet/[^\s<>"']+|[\w-]+\.webex\.com/[^\s<>"']+)"#,
+        )
+        .unwrap()
+    })
+}
+
+/// The link to join an event's call. `conference` is what Google Calendar
+/// attached to the event; `location` and `description` are searched after it.
+pub fn join_url(
+    conference: Option<&str>,
+    location: Option<&str>,
+    description: Option<&str>,
+) -> Option<String> {
+    if let Some(uri) = conference.filter(|uri| uri.starts_with("https://")) {
+        return Some(uri.to_string());
+    }
+    [location, description].into_iter().flatten().find_map(find)
+}
+
+/// First meeting URL in free text, HTML descriptions included
+pub fn find(text: &str) -> Option<String> {
+    let found = meeting_url().find(text)?.as_str();
+    let url = found
+        .replace("&amp;", "&")
+        .trim_end_matches(['.', ',', ';', ')', ']', '>'])
+        .to_string();
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_url_prefers_the_attached_conference() {
+        let description = r#"Agenda below.<br><a href="https://us02web.zoom.us/j/8812345678?pwd=Ab12&amp;from=addon">Join Zoom Meeting</a>"#;
+        assert_eq!(
+            join_url(None, Some("Room 4B"), Some(description)).as_deref(),
+            Some("https://us02web.zoom.us/j/8812345678?pwd=Ab12&from=addon")
+        );
+        assert_eq!(
+            join_url(
+                Some("https://meet.google.com/abc-defg-hij"),
+                None,
+                Some(description)
+            )
+            .as_deref(),
+            Some("https://meet.google.com/abc-defg-hij")
+        );
+        assert_eq!(
+            find("Dial in or join at https://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0.")
+                .as_deref(),
+            Some("https://teams.microsoft.com/l/meetup-join/19%3ameeting_x/0")
+        );
+        assert_eq!(
+            join_url(None, Some("123 Main St"), Some("Bring snacks")),
+            None
+        );
+    }
+}
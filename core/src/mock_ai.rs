@@ -0,0 +1,132 @@
+//! Deterministic stand-in for the AI provider.
+//!
+//! Selected with `[ai] provider = "mock"`. Instead of calling the API it reads
+//! the prompt the daemon built and answers with simple rules: the first
+//! schedule situation, else the next event, else an open task, else the
+//! weather. The same prompt always gets the same answer, so demo profiles and
+//! CI can run the whole loop without network access or an API key.
+
+/// Name of the mock provider in `[ai] provider`
+pub const PROVIDER: &str = "mock";
+
+/// Produce a response in the same format the real model is asked for
+pub fn respond(request_body: &serde_json::Value) -> String {
+    // A plain string, or content blocks when prompt caching splits it
+    let system = match request_body.get("system") {
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        Some(system) => system.as_str().unwrap_or_default().to_string(),
+        None => String::new(),
+    };
+    let user = request_body
+        .pointer("/messages/0/content")
+        .and_then(|c| c.as_str())
+        .unwrap_or_default();
+
+    let (emoji, text) = pick_insight(user);
+
+    // The first morning call asks for a digest in the same response
+    if system.contains("Digest:") {
+        let bullets: Vec<String> = section_bullets(user, "Your calendar")
+            .into_iter()
+            .chain(section_bullets(user, "Tasks:"))
+            .chain(open_tasks(user))
+            .take(5)
+            .map(|b| format!("- {}", b))
+            .collect();
+        let digest = if bullets.is_empty() {
+            "- Nothing scheduled today".to_string()
+        } else {
+            bullets.join("\n")
+        };
+        format!("Insight: {} {}\nDigest:\n{}", emoji, text, digest)
+    } else {
+        format!("Emoji: {}\nInsight: {}", emoji, text)
+    }
+}
+
+fn pick_insight(user: &str) -> (&'static str, String) {
+    if let Some(situation) = section_bullets(user, "Schedule situations").first() {
+        return ("⚠️", situation.clone());
+    }
+    if let Some(event) = section_bullets(user, "Your calendar").first() {
+        return ("📅", format!("Next up: {}", event.replace('"', "")));
+    }
+    if let Some(task) = section_bullets(user, "Tasks:")
+        .into_iter()
+        .chain(open_tasks(user))
+        .next()
+    {
+        return ("📋", format!("Don't forget: {}", task));
+    }
+    if let Some(weather) = user
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Weather: "))
+    {
+        return ("🌤️", weather.to_string());
+    }
+    (
+        "☕",
+        "Nothing on the schedule — enjoy the breathing room.".to_string(),
+    )
+}
+
+/// Bullets directly under the first line containing `header`
+fn section_bullets(user: &str, header: &str) -> Vec<String> {
+    user.lines()
+        .skip_while(|l| !l.contains(header))
+        .skip(1)
+        .take_while(|l| l.starts_with("- "))
+        .map(|l| l.trim_start_matches("- ").trim().to_string())
+        .collect()
+}
+
+/// Open checkbox tasks from daily note summaries
+fn open_tasks(user: &str) -> Vec<String> {
+    user.lines()
+        .filter_map(|l| l.trim().strip_prefix("- [ ] "))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(system: &str, user: &str) -> serde_json::Value {
+        serde_json::json!({
+            "system": system,
+            "messages": [{"role": "user", "content": user}],
+        })
+    }
+
+    #[test]
+    fn test_situations_win_and_answers_are_stable() {
+        let user = "Trigger: Regular morning check-in.\n\
+                    \nSchedule situations (cross-calendar analysis — prioritize these):\n\
+                    - Soccer pickup overlaps Alex's client dinner\n\
+                    \nYour calendar (next 24h):\n\
+                    - \"Team sync\" — in 1 hour";
+        let body = request("You are Jasper", user);
+        assert_eq!(
+            respond(&body),
+            "Emoji: ⚠️\nInsight: Soccer pickup overlaps Alex's client dinner"
+        );
+        assert_eq!(respond(&body), respond(&body));
+    }
+
+    #[test]
+    fn test_digest_requested() {
+        let user = "Trigger: Regular morning check-in.\n\
+                    \nToday's daily note:\n  Todo:\n  - [ ] Buy cupcake ingredients";
+        let response = respond(&request("...\nDigest:\n- <bullets>", user));
+        assert_eq!(
+            response,
+            "Insight: 📋 Don't forget: Buy cupcake ingredients\n\
+             Digest:\n- Buy cupcake ingredients"
+        );
+    }
+}
@@ -0,0 +1,4524 @@
+use crate::accessibility;
+use crate::activity::{ActivityClassifier, WeatherRules};
+use crate::ai_response;
+use crate::api_manager::ApiManager;
+use crate::config::{AiCallType, AttendancePolicy, AutoStopPolicy, Config};
+use crate::config_handle::ConfigHandle;
+use crate::context_sources::{self, ContextSourceManager, SourceFetch};
+use crate::database::{
+    ActiveFrontend, ApiUsage, Database, EscalationRecord, Insight, Invitation, MaintenanceRun,
+    MaintenanceTask, MeetingLink, TaskLogEntry,
+};
+use crate::day_summary::{self, DaySummary};
+use crate::daylight;
+use crate::deep_links::{self, InsightAction};
+use crate::errors::{JasperError, JasperResult};
+use crate::escalation;
+use crate::event_span;
+use crate::family_digest::{self, FamilyDigestPublisher};
+use crate::google_calendar::GoogleCalendarService;
+use crate::guest::{self, FrontendAccess, GuestInsight};
+use crate::heartbeat::{self, HeartbeatSchedule};
+use crate::http_utils;
+use crate::injected_context::InjectedContextStore;
+use crate::insight_format::InsightFormat;
+use crate::insight_memory;
+use crate::invitations;
+use crate::lineage;
+use crate::load_stats;
+use crate::maintenance;
+use crate::mock_ai;
+use crate::new_dbus_service::DbusSignalEmitter;
+use crate::ntfy::NtfyPublisher;
+use crate::people;
+use crate::people_notes;
+use crate::platform::{self, Notifier, Urgency};
+use crate::prefilter;
+use crate::project_risk;
+use crate::routing::{Channel, RoutingConfig};
+use crate::sanitize;
+use crate::schedule_patterns;
+use crate::significance_engine::{
+    ContextSnapshot as ContextSnapshotSummary, SignificanceEngine, SignificantChange,
+    TravelTimeInfo,
+};
+use crate::slack::{self, MeetingStatus, SlackClient};
+use crate::snapshot_codec;
+use crate::storage::StorageBackend;
+use crate::task_urgency;
+use crate::telegram::TelegramPublisher;
+use crate::text_budget;
+use crate::token_budget::{self, priority, PromptSection, TokenBudgeter};
+use crate::tomorrow_note;
+use crate::travel::TravelTimeService;
+use crate::update_check;
+use crate::wake_alarm;
+use crate::weather_gear;
+use crate::webhook::{self, WebhookEvent, WebhookPublisher};
+
+use chrono::{DateTime, Datelike, TimeZone, Timelike, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tokio::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Events that ended within this window stay in context for follow-up insights
+const RECENTLY_ENDED_GRACE_MINUTES: i64 = 30;
+
+/// How often meetings and tasks are logged for the weekly load stats
+const LOAD_LOG_INTERVAL_MINUTES: i64 = 15;
+
+/// Context tokens guaranteed to the user message even if the system prompt is huge
+const MIN_CONTEXT_TOKENS: usize = 500;
+
+/// How long ago an insight was generated, e.g. "just now", "12m ago", "3h ago"
+pub fn insight_age(created_at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    match (now - created_at).num_minutes().max(0) {
+        0 => "just now".to_string(),
+        m if m < 60 => format!("{}m ago", m),
+        m if m < 60 * 24 => format!("{}h ago", m / 60),
+        m => format!("{}d ago", m / (60 * 24)),
+    }
+}
+
+/// Seconds since an insight was generated, and whether that is past
+/// `stale_after_minutes`
+fn freshness(
+    created_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    stale_after_minutes: u64,
+) -> (i64, bool) {
+    let age_seconds = (now - created_at).num_seconds().max(0);
+    (age_seconds, age_seconds >= stale_after_minutes as i64 * 60)
+}
+
+/// `base` moved by a random amount of at most `jitter` either way
+fn jittered(base: Duration, jitter: Duration) -> Duration {
+    if jitter.is_zero() {
+        return base;
+    }
+    let span = jitter.as_millis() as u64 * 2;
+    let offset = uuid::Uuid::new_v4().as_u64_pair().0 % (span + 1);
+    (base + Duration::from_millis(offset)).saturating_sub(jitter)
+}
+
+/// Seconds frontends get to register before the auto-stop policy applies
+const STARTUP_GRACE_SECS: u64 = 45;
+/// Source name context snapshots are stored under
+const SNAPSHOT_SOURCE: &str = "combined";
+/// Hours after `family_digest.send_at` during which the digest may still go out
+const FAMILY_DIGEST_WINDOW_HOURS: i64 = 3;
+/// Hours a reply to an insight notification stays in the prompt
+const REPLY_MEMORY_HOURS: i64 = 12;
+
+/// Where insights and alerts are delivered besides D-Bus, rebuilt from
+/// `[delivery]` and `[family_digest]` whenever the config changes
+#[derive(Default)]
+struct Delivery {
+    // Push delivery to an ntfy topic (None if not configured)
+    ntfy: Option<NtfyPublisher>,
+
+    // Outbound webhook (None if not configured)
+    webhook: Option<WebhookPublisher>,
+
+    // Slack DMs and status (None if not configured)
+    slack: Option<SlackClient>,
+
+    // Family digest channels (None unless enabled)
+    family_digest: Option<FamilyDigestPublisher>,
+
+    // Native desktop notifications for the host OS (None unless enabled, and
+    // always when headless: there is no desktop to show them on)
+    notifier: Option<Arc<dyn Notifier>>,
+
+    // Telegram bot messages (None if not configured)
+    telegram: Option<TelegramPublisher>,
+
+    // Recipient and MTA for insight email (None if not configured)
+    email: Option<(String, String)>,
+
+    // Which of the above each insight category reaches
+    routing: RoutingConfig,
+}
+
+impl Delivery {
+    fn from_config(config: &Config, headless: bool) -> Self {
+        let ntfy = config.delivery.ntfy_url.clone().map(|url| {
+            let token = config
+                .delivery
+                .ntfy_token
+                .clone()
+                .or_else(|| std::env::var("NTFY_TOKEN").ok());
+            NtfyPublisher::new(reqwest::Client::new(), url, token)
+        });
+
+        let webhook = config.delivery.webhook.as_ref().and_then(|wc| {
+            WebhookPublisher::new(reqwest::Client::new(), wc)
+                .map_err(|e| warn!("Webhook disabled: {}", e))
+                .ok()
+        });
+
+        let family_digest =
+            FamilyDigestPublisher::from_config(reqwest::Client::new(), &config.family_digest);
+
+        let slack = config
+            .delivery
+            .slack
+            .as_ref()
+            .and_then(|sc| SlackClient::from_config(reqwest::Client::new(), sc));
+
+        let notifier: Option<Arc<dyn Notifier>> = (!headless
+            && config.routing.enables(Channel::Notification))
+        .then(|| Arc::from(platform::notifier()));
+
+        let telegram = config
+            .delivery
+            .telegram
+            .as_ref()
+            .and_then(|tc| TelegramPublisher::from_config(reqwest::Client::new(), tc));
+
+        let email = config
+            .delivery
+            .email
+            .as_ref()
+            .map(|ec| (ec.to.clone(), ec.sendmail_path.clone()));
+
+        Self {
+            ntfy,
+            webhook,
+            slack,
+            family_digest,
+            notifier,
+            telegram,
+            email,
+            routing: config.routing.clone(),
+        }
+    }
+}
+
+/// Weather rules and coalescing windows for the significance engine
+fn significance_settings(
+    config: &Config,
+) -> (
+    Option<WeatherRules>,
+    Option<(chrono::Duration, chrono::Duration)>,
+) {
+    let coalescing = config.coalescing.enabled.then(|| {
+        (
+            chrono::Duration::seconds(config.coalescing.settle_seconds as i64),
+            chrono::Duration::seconds(config.coalescing.max_wait_seconds as i64),
+        )
+    });
+    (
+        Some(WeatherRules::from_config(&config.activities)),
+        coalescing,
+    )
+}
+
+/// The new simplified daemon core that only handles backend processing
+pub struct SimplifiedDaemonCore {
+    database: Database,
+    significance_engine: SignificanceEngine,
+    context_manager: Arc<tokio::sync::RwLock<ContextSourceManager>>,
+    api_manager: ApiManager,
+    config: ConfigHandle,
+    http_client: reqwest::Client,
+
+    // Google Calendar sync
+    calendar_service: Option<Arc<GoogleCalendarService>>,
+    last_calendar_sync: Arc<RwLock<Option<DateTime<Utc>>>>,
+
+    // State
+    is_running: Arc<RwLock<bool>>,
+
+    // D-Bus signal emitter (initialized when D-Bus connection is ready)
+    signal_emitter: Arc<tokio::sync::RwLock<Option<DbusSignalEmitter>>>,
+
+    // Track whether we've already emitted an auth warning (avoid spamming every sync cycle)
+    auth_warning_emitted: Arc<RwLock<bool>>,
+
+    // Scheduled insights are held until then (manual refreshes still run)
+    snoozed_until: Arc<RwLock<Option<DateTime<Utc>>>>,
+
+    // Cached personal context file: (file_mtime, contents)
+    personal_context_cache: Arc<RwLock<Option<(SystemTime, String)>>>,
+
+    // Travel time enrichment service (None if not configured)
+    travel_service: RwLock<Option<Arc<TravelTimeService>>>,
+
+    // Context scripts pushed over D-Bus, until each one's TTL runs out
+    injected_context: InjectedContextStore,
+
+    // Running without D-Bus frontends (server/container); never exits for lack of frontends
+    headless: bool,
+
+    // Screen-reader text for notifications and push channels (`start --accessible`),
+    // on top of [frontends] accessible
+    accessible: bool,
+
+    // Delivery channels, swapped as a whole when the config changes
+    delivery: RwLock<Arc<Delivery>>,
+
+    // The Slack status we last set
+    slack_status: Arc<RwLock<Option<MeetingStatus>>>,
+
+    // Critical items past the escalation threshold, for stronger prompt wording
+    escalated: Arc<RwLock<Vec<EscalationRecord>>>,
+
+    // When meetings and tasks were last logged for the weekly load stats
+    last_load_log: Arc<RwLock<Option<DateTime<Utc>>>>,
+
+    // Day an earlier alarm was last suggested for, so the rule fires once per evening
+    alarm_suggested_for: Arc<RwLock<Option<chrono::NaiveDate>>>,
+
+    // Day the family digest was last sent
+    family_digest_sent: Arc<RwLock<Option<chrono::NaiveDate>>>,
+
+    // Local day tomorrow's daily note was last created on
+    tomorrow_note_written: Arc<RwLock<Option<chrono::NaiveDate>>>,
+
+    // Hash of the guest insight last signalled, so guest displays update only on change
+    guest_insight_sent: Arc<RwLock<Option<String>>>,
+
+    // High-urgency alerts already sent to the webhook or Slack, so each fires once
+    announced_alerts: Arc<RwLock<HashSet<String>>>,
+
+    // Ended events already considered for people note updates, with their end
+    people_noted: Arc<RwLock<HashMap<String, DateTime<Utc>>>>,
+
+    // Meeting (event source ID, start) last offered to join, so each is offered once
+    join_offered: Arc<RwLock<Option<(String, i64)>>>,
+
+    // Update check: when it last ran and the newer version it found, if any
+    last_update_check: Arc<RwLock<Option<DateTime<Utc>>>>,
+    available_update: Arc<RwLock<Option<String>>>,
+
+    // Heartbeats fired on the given local day, and the last heartbeat outcome audited
+    heartbeats_today: Arc<RwLock<(chrono::NaiveDate, u32)>>,
+    last_heartbeat_audit: Arc<RwLock<Option<String>>>,
+
+    // ForceRefresh request IDs, and the one currently running
+    refreshes: Arc<RwLock<RefreshState>>,
+
+    // Held for each context check, so a refresh never runs alongside the main loop's
+    analysis_lock: Arc<tokio::sync::Mutex<()>>,
+
+    // Rolling day summary as it will stand once the insight being generated is stored
+    pending_day_summary: Arc<RwLock<Option<DaySummary>>>,
+}
+
+#[derive(Debug, Default)]
+struct RefreshState {
+    last_id: u64,
+    in_flight: Option<u64>,
+}
+
+impl SimplifiedDaemonCore {
+    pub fn new(
+        database: Database,
+        context_manager: ContextSourceManager,
+        api_manager: ApiManager,
+        config: ConfigHandle,
+        calendar_service: Option<GoogleCalendarService>,
+        travel_service: Option<TravelTimeService>,
+    ) -> Self {
+        let (delivery, significance_engine) = {
+            let cfg = config.read();
+            let engine = SignificanceEngine::new();
+            let (weather_rules, coalescing) = significance_settings(&cfg);
+            engine.reconfigure(weather_rules, coalescing);
+            http_utils::set_rate_limits(&cfg.rate_limits);
+            (Delivery::from_config(&cfg, false), engine)
+        };
+
+        Self {
+            database,
+            significance_engine,
+            context_manager: Arc::new(tokio::sync::RwLock::new(context_manager)),
+            api_manager,
+            config,
+            calendar_service: calendar_service.map(Arc::new),
+            last_calendar_sync: Arc::new(RwLock::new(None)),
+            http_client: reqwest::Client::new(),
+            is_running: Arc::new(RwLock::new(false)),
+            signal_emitter: Arc::new(tokio::sync::RwLock::new(None)),
+            auth_warning_emitted: Arc::new(RwLock::new(false)),
+            snoozed_until: Arc::new(RwLock::new(None)),
+            personal_context_cache: Arc::new(RwLock::new(None)),
+            travel_service: RwLock::new(travel_service.map(Arc::new)),
+            injected_context: InjectedContextStore::new(),
+            headless: false,
+            accessible: false,
+            delivery: RwLock::new(Arc::new(delivery)),
+            slack_status: Arc::new(RwLock::new(None)),
+            announced_alerts: Arc::new(RwLock::new(HashSet::new())),
+            people_noted: Arc::new(RwLock::new(HashMap::new())),
+            escalated: Arc::new(RwLock::new(Vec::new())),
+            alarm_suggested_for: Arc::new(RwLock::new(None)),
+            family_digest_sent: Arc::new(RwLock::new(None)),
+            tomorrow_note_written: Arc::new(RwLock::new(None)),
+            guest_insight_sent: Arc::new(RwLock::new(None)),
+            last_load_log: Arc::new(RwLock::new(None)),
+            join_offered: Arc::new(RwLock::new(None)),
+            last_update_check: Arc::new(RwLock::new(None)),
+            available_update: Arc::new(RwLock::new(None)),
+            heartbeats_today: Arc::new(RwLock::new((chrono::NaiveDate::MIN, 0))),
+            last_heartbeat_audit: Arc::new(RwLock::new(None)),
+            refreshes: Arc::new(RwLock::new(RefreshState::default())),
+            analysis_lock: Arc::new(tokio::sync::Mutex::new(())),
+            pending_day_summary: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Run without D-Bus frontends: keep going when none are registered
+    pub fn with_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        let delivery = Arc::new(Delivery::from_config(&self.config.read(), headless));
+        *self.delivery.write() = Arc::clone(&delivery);
+        if headless
+            && delivery.ntfy.is_none()
+            && delivery.webhook.is_none()
+            && delivery.notifier.is_none()
+            && delivery.telegram.is_none()
+            && delivery.email.is_none()
+        {
+            warn!("Running headless with no [delivery] channel; insights will only be stored");
+        }
+        self
+    }
+
+    /// Deliver notifications and push messages as screen-reader text whatever
+    /// the config says
+    pub fn with_accessible(mut self, accessible: bool) -> Self {
+        self.accessible = accessible;
+        self
+    }
+
+    /// The delivery channels for the current configuration
+    fn delivery(&self) -> Arc<Delivery> {
+        Arc::clone(&self.delivery.read())
+    }
+
+    /// Base interval between context checks, and the jitter applied to it
+    fn check_timing(&self) -> (Duration, Duration) {
+        let cfg = self.config.read();
+        (
+            Duration::from_secs(cfg.general.check_interval_seconds),
+            Duration::from_secs(cfg.general.check_jitter_seconds),
+        )
+    }
+
+    /// Minimum time between Google Calendar syncs
+    fn calendar_sync_interval(&self) -> chrono::Duration {
+        let minutes = self
+            .config
+            .read()
+            .google_calendar
+            .as_ref()
+            .map(|gc| gc.sync_interval_minutes)
+            .unwrap_or(15);
+        chrono::Duration::minutes(minutes as i64)
+    }
+
+    /// Rebuild everything derived from the configuration after it changed:
+    /// context sources, travel times, delivery channels and significance
+    /// settings. The calendar service keeps its OAuth session until restart.
+    pub async fn apply_config(&self) {
+        let config = self.config.read();
+        let context_manager = ContextSourceManager::from_config(&config);
+        let mut current = self.context_manager.write().await;
+        context_manager.keep_fetches_from(&current);
+        *current = context_manager;
+        drop(current);
+        *self.travel_service.write() = TravelTimeService::from_config(&config).map(Arc::new);
+        *self.delivery.write() = Arc::new(Delivery::from_config(&config, self.headless));
+        http_utils::set_rate_limits(&config.rate_limits);
+        let (weather_rules, coalescing) = significance_settings(&config);
+        self.significance_engine
+            .reconfigure(weather_rules, coalescing);
+        info!("Applied configuration changes");
+    }
+
+    /// Initialize the D-Bus signal emitter (call after D-Bus connection is established)
+    pub async fn init_signal_emitter(&self) -> JasperResult<()> {
+        match DbusSignalEmitter::new().await {
+            Ok(emitter) => {
+                *self.signal_emitter.write().await = Some(emitter);
+                info!("D-Bus signal emitter initialized");
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to initialize D-Bus signal emitter: {}", e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Emit an insight updated signal
+    async fn emit_insight_signal(
+        &self,
+        insight_id: i64,
+        emoji: &str,
+        preview: &str,
+        actions: &[InsightAction],
+    ) {
+        let first_action = actions.first().cloned();
+        let delivery = self.delivery();
+        let routed = |channel| delivery.routing.delivers(emoji, channel);
+        // Screen-reader text for what reaches the user directly; bar adapters
+        // do their own with --accessible
+        let (shown_emoji, shown) = if self.accessible || self.config.read().frontends.accessible {
+            ("", accessibility::plain_insight(emoji, preview))
+        } else {
+            (emoji, preview.to_string())
+        };
+        if let Some(ntfy) = delivery.ntfy.as_ref().filter(|_| routed(Channel::Push)) {
+            let click = first_action.as_ref().map(|a| a.uri.as_str());
+            if let Err(e) = ntfy.publish(shown_emoji, &shown, click).await {
+                warn!("Failed to publish insight to ntfy: {}", e);
+            }
+        }
+
+        if let Some(webhook) = delivery
+            .webhook
+            .as_ref()
+            .filter(|_| routed(Channel::Webhook))
+        {
+            if let Err(e) = webhook
+                .publish(WebhookEvent::Insight, emoji, preview, Some(insight_id))
+                .await
+            {
+                warn!("Failed to send insight webhook: {}", e);
+            }
+        }
+
+        if let Some(slack) = delivery
+            .slack
+            .as_ref()
+            .filter(|s| s.can_dm() && routed(Channel::Slack))
+        {
+            if let Err(e) = slack.send_dm(&format!("{} {}", emoji, preview)).await {
+                warn!("Failed to DM insight on Slack: {}", e);
+            }
+        }
+
+        if let Some(telegram) = delivery
+            .telegram
+            .as_ref()
+            .filter(|_| routed(Channel::Telegram))
+        {
+            if let Err(e) = telegram.publish(shown_emoji, &shown).await {
+                warn!("Failed to send insight to Telegram: {}", e);
+            }
+        }
+
+        if let Some((to, sendmail)) = delivery.email.clone().filter(|_| routed(Channel::Email)) {
+            let subject = format!("Jasper: {}", text_budget::fit(&shown, Some(60)));
+            let body = format!("{} {}", shown_emoji, shown).trim().to_string();
+            let sent = tokio::task::spawn_blocking(move || {
+                escalation::send_email(&sendmail, &to, &subject, &body)
+            })
+            .await;
+            if let Ok(Err(e)) = sent {
+                warn!("Failed to email insight: {}", e);
+            }
+        }
+
+        if let Some(notifier) = delivery
+            .notifier
+            .as_ref()
+            .filter(|_| routed(Channel::Notification))
+        {
+            self.show_insight_notification(
+                Arc::clone(notifier),
+                insight_id,
+                format!("{} Jasper", shown_emoji).trim().to_string(),
+                shown.clone(),
+                first_action,
+            );
+        }
+
+        if routed(Channel::Bar) {
+            self.emit_dbus_insight(insight_id, emoji, preview, Utc::now(), actions)
+                .await;
+        }
+    }
+
+    /// Emit InsightUpdated to D-Bus frontends only
+    async fn emit_dbus_insight(
+        &self,
+        insight_id: i64,
+        emoji: &str,
+        preview: &str,
+        created_at: DateTime<Utc>,
+        actions: &[InsightAction],
+    ) {
+        if let Some(ref emitter) = *self.signal_emitter.read().await {
+            let formatted = self.formatted_payloads(emoji, preview).await;
+            let (age_seconds, stale) = self.insight_freshness(created_at);
+            let actions: Vec<(String, String)> = actions
+                .iter()
+                .map(|a| (a.label.clone(), a.uri.clone()))
+                .collect();
+            if let Err(e) = emitter
+                .emit_insight_updated(
+                    insight_id,
+                    emoji,
+                    preview,
+                    &formatted,
+                    age_seconds,
+                    stale,
+                    &actions,
+                )
+                .await
+            {
+                warn!("Failed to emit InsightUpdated signal: {}", e);
+            }
+        } else {
+            debug!("Signal emitter not initialized, skipping signal emission");
+        }
+    }
+
+    /// Show the last stored insight, marked with its age, to frontends that
+    /// connected before the first check of this run. Push channels aren't
+    /// sent it again.
+    async fn publish_last_insight(&self) {
+        match self.database.get_latest_insight() {
+            Ok(Some(insight)) => {
+                let age = insight_age(insight.created_at, Utc::now());
+                let preview = format!("{} (as of {})", insight.insight, age);
+                info!(
+                    "Serving insight {} from {} until the first check",
+                    insight.id, age
+                );
+                let actions: Vec<InsightAction> = self
+                    .database
+                    .get_insight_actions(insight.id)
+                    .unwrap_or_else(|e| {
+                        warn!("Failed to load insight actions: {}", e);
+                        Vec::new()
+                    })
+                    .into_iter()
+                    .map(|(label, uri)| InsightAction { label, uri })
+                    .collect();
+                self.emit_dbus_insight(
+                    insight.id,
+                    &insight.emoji,
+                    &preview,
+                    insight.created_at,
+                    &actions,
+                )
+                .await;
+            }
+            Ok(None) => debug!("No stored insight to serve at startup"),
+            Err(e) => warn!("Failed to load the last insight: {}", e),
+        }
+    }
+
+    /// Show an insight's notification. It may wait for a click or a reply,
+    /// so it runs detached; a reply is stored for the next prompt.
+    fn show_insight_notification(
+        &self,
+        notifier: Arc<dyn Notifier>,
+        insight_id: i64,
+        title: String,
+        body: String,
+        action: Option<InsightAction>,
+    ) {
+        let database = Arc::clone(&self.database);
+        tokio::task::spawn_blocking(move || {
+            let link = action.as_ref().map(|a| (a.label.as_str(), a.uri.as_str()));
+            match notifier.notify_insight(&title, &body, link) {
+                Ok(Some(reply)) if !reply.trim().is_empty() => {
+                    info!("Reply to insight {}: {}", insight_id, reply);
+                    if let Err(e) = database.store_insight_reply(insight_id, reply.trim()) {
+                        warn!("Failed to store insight reply: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to show {} notification: {}", notifier.name(), e),
+            }
+        });
+    }
+
+    /// Show a native notification off the async runtime (the backends shell out)
+    async fn show_notification(
+        notifier: Arc<dyn Notifier>,
+        title: String,
+        body: String,
+        urgency: Urgency,
+    ) {
+        let notifier_name = notifier.name();
+        let shown =
+            tokio::task::spawn_blocking(move || notifier.notify(&title, &body, urgency)).await;
+        if let Ok(Err(e)) = shown {
+            warn!("Failed to show {} notification: {}", notifier_name, e);
+        }
+    }
+
+    /// The insight rendered in plain text plus every format a registered frontend asked for
+    async fn formatted_payloads(&self, emoji: &str, text: &str) -> HashMap<String, String> {
+        let mut formats = BTreeSet::from([InsightFormat::Plain]);
+        match self.database.blocking(|db| db.get_active_frontends()).await {
+            Ok(frontends) => formats.extend(
+                frontends
+                    .iter()
+                    .filter_map(|f| InsightFormat::from_name(&f.format)),
+            ),
+            Err(e) => warn!("Failed to read frontend formats: {}", e),
+        }
+        formats
+            .into_iter()
+            .map(|format| (format.name().to_string(), format.render(emoji, text)))
+            .collect()
+    }
+
+    /// Newer release found by the update check, if any
+    pub fn available_update(&self) -> Option<String> {
+        self.available_update.read().clone()
+    }
+
+    /// Look for a newer GitHub release once per day when enabled in config
+    async fn check_for_update_if_needed(&self) {
+        if !self.config.read().general.check_for_updates {
+            return;
+        }
+        if let Some(last) = *self.last_update_check.read() {
+            if Utc::now() - last < chrono::Duration::hours(update_check::CHECK_INTERVAL_HOURS) {
+                return;
+            }
+        }
+        *self.last_update_check.write() = Some(Utc::now());
+
+        match update_check::latest_release(&self.http_client).await {
+            Ok(latest) if update_check::is_newer(&latest, update_check::CURRENT_VERSION) => {
+                info!(
+                    "Update available: {} (running {})",
+                    latest,
+                    update_check::CURRENT_VERSION
+                );
+                *self.available_update.write() = Some(latest);
+            }
+            Ok(_) => {
+                debug!("Running the latest release");
+                *self.available_update.write() = None;
+            }
+            Err(e) => warn!("Update check failed: {}", e),
+        }
+    }
+
+    /// Once a night, inside the `[maintenance]` window, tidy the database and
+    /// caches and check the calendar token. Results are kept for `health`.
+    async fn run_maintenance_if_due(&self) {
+        let (enabled, run_at, tz) = {
+            let cfg = self.config.read();
+            (
+                cfg.maintenance.enabled,
+                cfg.maintenance.run_at.clone(),
+                cfg.get_timezone(),
+            )
+        };
+        let Ok(run_at) = chrono::NaiveTime::parse_from_str(&run_at, "%H:%M") else {
+            return;
+        };
+        let now = Utc::now().with_timezone(&tz).naive_local();
+        if !enabled || !maintenance::is_due(run_at, now, None) {
+            return;
+        }
+        let last_run = match self.database.latest_maintenance_run() {
+            Ok(run) => run.map(|r| r.started_at.with_timezone(&tz).date_naive()),
+            Err(e) => {
+                warn!("Failed to read the last maintenance run: {}", e);
+                return;
+            }
+        };
+        if maintenance::is_due(run_at, now, last_run) {
+            self.run_maintenance().await;
+        }
+    }
+
+    /// Each evening from `tomorrow_note.run_at`, create tomorrow's daily note
+    /// in the vault unless it already exists
+    async fn write_tomorrow_note_if_due(&self) {
+        let (vault, note_config, tz) = {
+            let cfg = self.config.read();
+            let Some(obsidian) = cfg.get_obsidian_config().filter(|o| o.enabled) else {
+                return;
+            };
+            (
+                obsidian.vault_path.clone(),
+                obsidian.tomorrow_note.clone(),
+                cfg.get_timezone(),
+            )
+        };
+        let Ok(run_at) = chrono::NaiveTime::parse_from_str(&note_config.run_at, "%H:%M") else {
+            return;
+        };
+        let local_now = Utc::now().with_timezone(&tz);
+        let last_run = *self.tomorrow_note_written.read();
+        if !note_config.enabled || !maintenance::is_due(run_at, local_now.naive_local(), last_run) {
+            return;
+        }
+        *self.tomorrow_note_written.write() = Some(local_now.date_naive());
+
+        // Folders as the Obsidian source reads them
+        let folders = context_sources::obsidian::ObsidianConfig::default();
+        let vault = std::path::PathBuf::from(vault);
+        let Some(date) = local_now.date_naive().succ_opt() else {
+            return;
+        };
+        let path = tomorrow_note::note_path(&vault, &folders.daily_notes_folder, date);
+        if path.exists() {
+            debug!("Tomorrow's note already exists: {:?}", path);
+            return;
+        }
+
+        let Some(start) = tz
+            .from_local_datetime(&date.and_time(chrono::NaiveTime::MIN))
+            .earliest()
+            .map(|t| t.to_utc())
+        else {
+            return;
+        };
+        let end = start + chrono::Duration::days(1);
+        let context = match self.agenda_context(start, end).await {
+            Ok(context) => context,
+            Err(e) => {
+                warn!(
+                    "Failed to gather tomorrow's context for the daily note: {}",
+                    e
+                );
+                return;
+            }
+        };
+        let events: Vec<_> = context
+            .calendar_events
+            .iter()
+            .filter(|e| e.start_time >= start && e.start_time < end)
+            .cloned()
+            .collect();
+        let tasks: Vec<_> = context
+            .tasks
+            .iter()
+            .filter(|t| !t.completed && t.due.is_some_and(|due| due < end))
+            .cloned()
+            .collect();
+        let tomorrow = tomorrow_note::Tomorrow {
+            date,
+            events: &events,
+            tasks: &tasks,
+            weather: context.weather_context.as_ref(),
+        };
+        let written =
+            tomorrow_note::load_template(&vault, &folders.templates_folder, &note_config.template)
+                .and_then(|template| {
+                    let note = tomorrow_note::render(&template, &tomorrow, &local_now);
+                    tomorrow_note::create(&path, &note)
+                });
+        match written {
+            Ok(true) => {
+                info!("Created tomorrow's daily note at {:?}", path);
+                self.audit_trigger("tomorrow_note", "fired", None).await;
+            }
+            Ok(false) => debug!("Tomorrow's note appeared meanwhile: {:?}", path),
+            Err(e) => warn!("Failed to create tomorrow's daily note: {:#}", e),
+        }
+    }
+
+    /// Run every maintenance task and record the results
+    pub async fn run_maintenance(&self) -> MaintenanceRun {
+        info!("Starting nightly maintenance");
+        let started_at = Utc::now();
+        let (snapshot_retention_days, cache_ttl) = (
+            self.config.read().database.snapshot_retention_days,
+            self.response_cache_ttl(),
+        );
+        let task = |name: &str, result: JasperResult<String>| {
+            let (ok, detail) = match result {
+                Ok(detail) => (true, detail),
+                Err(e) => {
+                    warn!("Maintenance task {} failed: {}", name, e);
+                    (false, e.to_string())
+                }
+            };
+            MaintenanceTask {
+                task: name.to_string(),
+                ok,
+                detail,
+            }
+        };
+
+        let mut tasks = Vec::new();
+        let pruned = self
+            .database
+            .blocking(move |db| db.prune_expired(Utc::now(), snapshot_retention_days, cache_ttl))
+            .await;
+        tasks.push(task(
+            "prune",
+            pruned.map(|rows| format!("{} old rows removed", rows)),
+        ));
+        let vacuumed = self.database.blocking(|db| db.vacuum()).await;
+        tasks.push(task(
+            "vacuum",
+            vacuumed.map(|bytes| format!("{} KiB freed", bytes / 1024)),
+        ));
+        let checkpointed = self.database.blocking(|db| db.checkpoint_wal()).await;
+        tasks.push(task(
+            "checkpoint",
+            checkpointed.map(|()| "write-ahead log truncated".to_string()),
+        ));
+
+        let tz = self.config.read().get_timezone();
+        let learned = self
+            .database
+            .blocking(move |db| {
+                let now = Utc::now();
+                let meetings = db.get_meeting_log(
+                    now - chrono::Duration::weeks(schedule_patterns::LOOKBACK_WEEKS),
+                    now,
+                )?;
+                let patterns = schedule_patterns::learn(&meetings, &tz);
+                db.replace_user_patterns(&patterns)?;
+                Ok(patterns.len())
+            })
+            .await;
+        tasks.push(task(
+            "patterns",
+            learned.map(|count| format!("{} schedule patterns learned", count)),
+        ));
+
+        let travel_service = self.travel_service.read().clone();
+        let caches = match travel_service {
+            Some(travel) => format!("{} expired travel routes dropped", travel.refresh().await),
+            None => "nothing cached".to_string(),
+        };
+        tasks.push(task("caches", Ok(caches)));
+
+        if let Some(ref calendar_service) = self.calendar_service {
+            let auth = if calendar_service.is_authenticated().await {
+                Ok("Google Calendar token valid".to_string())
+            } else {
+                Err(JasperError::Authentication {
+                    service: "Google Calendar".to_string(),
+                    message: "token expired or revoked; run `auth-google`".to_string(),
+                })
+            };
+            tasks.push(task("auth", auth));
+        }
+
+        let run = MaintenanceRun {
+            started_at,
+            finished_at: Utc::now(),
+            tasks,
+        };
+        info!(
+            "Maintenance finished in {}s with {} failed task(s)",
+            (run.finished_at - run.started_at).num_seconds(),
+            run.failures()
+        );
+        let record = run.clone();
+        if let Err(e) = self
+            .database
+            .blocking(move |db| db.record_maintenance_run(&record))
+            .await
+        {
+            warn!("Failed to record maintenance run: {}", e);
+        }
+        run
+    }
+
+    /// Shortly before a video call starts, show a notification that joins it
+    /// when clicked. Each meeting is offered once.
+    async fn offer_to_join_if_due(&self) {
+        let lead_minutes = self.config.read().delivery.join_reminder_minutes;
+        let Some(notifier) = self
+            .delivery()
+            .notifier
+            .clone()
+            .filter(|_| lead_minutes > 0)
+        else {
+            return;
+        };
+        let now = Utc::now();
+        let meeting = match self
+            .database
+            .blocking(move |db| db.get_next_meeting(now))
+            .await
+        {
+            Ok(Some(meeting)) => meeting,
+            Ok(None) => return,
+            Err(e) => {
+                warn!("Failed to look up the next meeting: {}", e);
+                return;
+            }
+        };
+        let starts_in = meeting.start_time - now.timestamp();
+        if !(0..=lead_minutes as i64 * 60).contains(&starts_in) {
+            return;
+        }
+        let key = (meeting.source_id.clone(), meeting.start_time);
+        if self.join_offered.read().as_ref() == Some(&key) {
+            return;
+        }
+        *self.join_offered.write() = Some(key);
+
+        info!("Offering to join {}", meeting.title);
+        let minutes = (starts_in + 59) / 60;
+        let body = match minutes {
+            0 | 1 => "Starting now".to_string(),
+            m => format!("Starts in {} minutes", m),
+        };
+        // Waits for the click, so it runs on its own
+        tokio::task::spawn_blocking(move || {
+            let title = format!("📹 {}", meeting.title);
+            if let Err(e) =
+                notifier.notify_with_link(&title, &body, Urgency::Normal, "Join", &meeting.url)
+            {
+                warn!("Failed to show {} notification: {}", notifier.name(), e);
+            }
+        });
+    }
+
+    /// The next video call, or the one under way
+    pub async fn next_meeting(&self) -> JasperResult<Option<MeetingLink>> {
+        let now = Utc::now();
+        self.database
+            .blocking(move |db| db.get_next_meeting(now))
+            .await
+    }
+
+    /// Open the next video call in the browser or its app; returns it, if
+    /// there was one
+    pub async fn join_next_meeting(&self) -> JasperResult<Option<MeetingLink>> {
+        let Some(meeting) = self.next_meeting().await? else {
+            return Ok(None);
+        };
+        info!("Joining {}", meeting.title);
+        let url = meeting.url.clone();
+        tokio::task::spawn_blocking(move || platform::open_uri(&url))
+            .await
+            .map_err(|e| JasperError::Internal {
+                message: format!("Open meeting task failed: {}", e),
+            })??;
+        Ok(Some(meeting))
+    }
+
+    /// Answer the upcoming invitation `query` names (see `invitations::pick`)
+    /// through Google Calendar; returns the invitation answered
+    pub async fn rsvp(
+        &self,
+        query: &str,
+        response: invitations::Response,
+    ) -> JasperResult<Invitation> {
+        let service =
+            self.calendar_service
+                .clone()
+                .ok_or_else(|| JasperError::ServiceUnavailable {
+                    service: "Google Calendar".to_string(),
+                })?;
+        let since = Utc::now() - chrono::Duration::hours(1);
+        let candidates = self
+            .database
+            .blocking(move |db| db.get_invitations(since))
+            .await?;
+        let invitation = invitations::pick(&candidates, query)
+            .map_err(|message| JasperError::Validation {
+                field: "event".to_string(),
+                message,
+            })?
+            .clone();
+
+        service
+            .respond_to_invitation(
+                &invitation.calendar_id,
+                &invitation.source_id,
+                response.status(),
+            )
+            .await
+            .map_err(|e| JasperError::Api {
+                service: "google_calendar".to_string(),
+                message: e.to_string(),
+            })?;
+        let source_id = invitation.source_id.clone();
+        self.database
+            .blocking(move |db| db.set_event_response(&source_id, response.status()))
+            .await?;
+        Ok(invitation)
+    }
+
+    /// Start the daemon main loop
+    /// Takes an Arc to self so it can release locks between iterations
+    pub async fn start_with_arc(daemon: Arc<tokio::sync::RwLock<Self>>) -> JasperResult<()> {
+        // Check and set running state
+        {
+            let d = daemon.read().await;
+            let mut running = d.is_running.write();
+            if *running {
+                warn!("Daemon is already running");
+                return Ok(());
+            }
+            *running = true;
+        }
+
+        info!("Starting simplified daemon core");
+
+        // Apply config edits as they are published
+        let mut config_changes = daemon.read().await.config.subscribe();
+        let watcher = Arc::clone(&daemon);
+        tokio::spawn(async move {
+            while config_changes.changed().await.is_ok() {
+                watcher.read().await.apply_config().await;
+            }
+        });
+
+        // Serve the last insight straight away and refresh in the background,
+        // so bars have something to show while frontends connect
+        daemon.read().await.publish_last_insight().await;
+        let refresher = Arc::clone(&daemon);
+        tokio::spawn(async move {
+            let d = refresher.read().await;
+            let _analysis = d.analysis_lock.lock().await;
+            if let Err(e) = d.check_and_analyze(None).await {
+                error!("Error during startup context check: {}", e);
+            }
+        });
+
+        // Give initial grace period for frontends to connect before the
+        // auto-stop policy applies. Must be longer than the Noctalia plugin
+        // poll interval (30s) so the plugin has time to register via D-Bus.
+        info!("Waiting for frontends to connect...");
+        tokio::time::sleep(tokio::time::Duration::from_secs(STARTUP_GRACE_SECS)).await;
+
+        // Get check interval from daemon (briefly acquire lock)
+        let (check_interval, _) = daemon.read().await.check_timing();
+        // The startup refresh stands in for the first scheduled check
+        let mut next_check = tokio::time::Instant::now() + check_interval
+            - Duration::from_secs(STARTUP_GRACE_SECS).min(check_interval);
+        // When the last frontend went away, for the auto-stop grace period
+        let mut idle_since: Option<std::time::Instant> = None;
+
+        loop {
+            // Check if we should still be running (briefly acquire lock)
+            {
+                let d = daemon.read().await;
+                if !*d.is_running.read() {
+                    info!("Daemon stop requested");
+                    break;
+                }
+            }
+
+            // Drop crashed or silent frontends, then apply the auto-stop policy
+            // (headless has no frontends by design)
+            let (database, headless, frontends) = {
+                let d = daemon.read().await;
+                let frontends = d.config.read().frontends.clone();
+                (d.database.clone(), d.headless, frontends)
+            };
+            let timeout = frontends.heartbeat_timeout_seconds;
+            let has_frontends = database
+                .blocking(move |db| db.has_active_frontends(timeout))
+                .await
+                .unwrap_or(true);
+
+            if has_frontends || headless || frontends.auto_stop == AutoStopPolicy::Never {
+                idle_since = None;
+            } else {
+                let since = *idle_since.get_or_insert_with(|| {
+                    info!(
+                        "No active frontends - stopping in {}s unless one reconnects",
+                        frontends.stop_grace_seconds
+                    );
+                    std::time::Instant::now()
+                });
+                if since.elapsed().as_secs() >= frontends.stop_grace_seconds {
+                    info!("No frontend reconnected - stopping daemon");
+                    break;
+                }
+            }
+
+            tokio::time::sleep_until(next_check).await;
+            // Re-read each cycle so an edited interval applies from the next check
+            let (check_interval, check_jitter) = daemon.read().await.check_timing();
+            next_check = tokio::time::Instant::now() + jittered(check_interval, check_jitter);
+
+            // Sync Google Calendar events if interval has elapsed
+            {
+                let d = daemon.read().await;
+                d.sync_calendar_if_needed().await;
+            }
+
+            {
+                let d = daemon.read().await;
+                d.check_for_update_if_needed().await;
+            }
+
+            {
+                let d = daemon.read().await;
+                d.offer_to_join_if_due().await;
+            }
+
+            {
+                let d = daemon.read().await;
+                d.run_maintenance_if_due().await;
+            }
+
+            {
+                let d = daemon.read().await;
+                d.write_tomorrow_note_if_due().await;
+            }
+
+            // Perform context check and analysis (this acquires locks internally as needed)
+            {
+                let d = daemon.read().await;
+                let _analysis = d.analysis_lock.lock().await;
+                if let Err(e) = d.check_and_analyze(None).await {
+                    error!("Error during context check and analysis: {}", e);
+                }
+            }
+        }
+
+        // Clear running state
+        {
+            let d = daemon.read().await;
+            let mut running = d.is_running.write();
+            *running = false;
+        }
+
+        info!("Simplified daemon core stopped");
+        Ok(())
+    }
+
+    /// Stop the daemon (available for graceful shutdown)
+    #[allow(dead_code)]
+    pub fn stop(&self) {
+        info!("Stopping daemon");
+        let mut running = self.is_running.write();
+        *running = false;
+    }
+
+    /// Sync Google Calendar events if the sync interval has elapsed
+    async fn sync_calendar_if_needed(&self) {
+        let calendar_service = match &self.calendar_service {
+            Some(svc) => svc.clone(),
+            None => return,
+        };
+
+        // Check if sync interval has elapsed
+        {
+            let last_sync = self.last_calendar_sync.read();
+            if let Some(last) = *last_sync {
+                if Utc::now() - last < self.calendar_sync_interval() {
+                    return;
+                }
+            }
+        }
+
+        // Check if authenticated (this now attempts token refresh if expired)
+        if !calendar_service.is_authenticated().await {
+            warn!("Google Calendar not authenticated — token refresh failed or no credentials");
+
+            // Surface auth failure to user (once, not every sync cycle)
+            if !*self.auth_warning_emitted.read() {
+                *self.auth_warning_emitted.write() = true;
+                let emoji = "🔑";
+                let text = "Google Calendar authentication expired — run `jasper-companion-daemon auth-google` to re-authenticate.";
+                if let Ok(insight_id) = self
+                    .database
+                    .blocking(move |db| db.store_insight(emoji, text, None))
+                    .await
+                {
+                    self.emit_insight_signal(insight_id, emoji, text, &[]).await;
+                    warn!("Auth warning insight emitted to frontends");
+                }
+            }
+            return;
+        }
+
+        // Auth succeeded — clear warning flag if it was set
+        if *self.auth_warning_emitted.read() {
+            *self.auth_warning_emitted.write() = false;
+        }
+
+        info!("Starting Google Calendar sync");
+
+        let now = Utc::now();
+        let planning_horizon = self.config.read().get_planning_horizon();
+        let end_time = now + planning_horizon;
+
+        // Fetch calendar metadata (display names, access roles) once per sync cycle
+        let calendar_metadata = match calendar_service.fetch_calendar_metadata().await {
+            Ok(meta) => {
+                debug!("Fetched metadata for {} calendars", meta.len());
+                meta
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to fetch calendar metadata, falling back to IDs: {}",
+                    e
+                );
+                HashMap::new()
+            }
+        };
+
+        match calendar_service.fetch_events(now, end_time).await {
+            Ok(events_by_calendar) => {
+                let mut total_events = 0usize;
+                let mut total_changes = 0usize;
+
+                for (google_calendar_id, events) in &events_by_calendar {
+                    // Look up display name and access role from metadata
+                    let (calendar_name, access_role, is_primary) =
+                        if let Some(meta) = calendar_metadata.get(google_calendar_id) {
+                            (
+                                meta.display_name.clone(),
+                                Some(meta.access_role.as_str()),
+                                meta.is_primary,
+                            )
+                        } else {
+                            (google_calendar_id.clone(), None, false)
+                        };
+                    let appearance = calendar_metadata
+                        .get(google_calendar_id)
+                        .map(|meta| (meta.color.clone(), meta.hidden, meta.selected));
+                    let calendar_id = google_calendar_id.clone();
+                    let access_role = access_role.map(str::to_string);
+                    let events = events.clone();
+                    let events_len = events.len();
+                    let synced = self
+                        .database
+                        .blocking(move |db| {
+                            let db_calendar_id = db.create_or_update_calendar(
+                                &calendar_id,
+                                &calendar_name,
+                                Some("google_calendar"),
+                                access_role.as_deref(),
+                                is_primary,
+                            )?;
+                            if let Some((color, hidden, selected)) = appearance {
+                                db.set_calendar_appearance(
+                                    db_calendar_id,
+                                    color.as_deref(),
+                                    hidden,
+                                    selected,
+                                )?;
+                            }
+
+                            db.sync_calendar_events(db_calendar_id, &events)
+                        })
+                        .await;
+
+                    match synced {
+                        Ok(changes) => {
+                            total_events += events_len;
+                            total_changes += changes.len();
+                            debug!(
+                                "Synced {} events for calendar {} ({} changed)",
+                                events_len,
+                                google_calendar_id,
+                                changes.len()
+                            );
+                            self.significance_engine.record_event_changes(changes);
+                        }
+                        Err(e) => {
+                            warn!("Failed to sync calendar {}: {}", google_calendar_id, e);
+                        }
+                    }
+                }
+
+                info!(
+                    "Google Calendar sync complete: {} events, {} changed",
+                    total_events, total_changes
+                );
+
+                // Update last sync timestamp
+                *self.last_calendar_sync.write() = Some(Utc::now());
+            }
+            Err(e) => {
+                warn!("Google Calendar sync failed: {}", e);
+            }
+        }
+    }
+
+    /// Determine the current heartbeat phase from the configured `[heartbeat]` schedule.
+    /// Returns the phase name if the local time falls inside one, or None otherwise.
+    fn should_fire_heartbeat(&self) -> Option<String> {
+        let (tz, schedule) = {
+            let cfg = self.config.read();
+            (
+                cfg.get_timezone(),
+                HeartbeatSchedule::from_config(&cfg.heartbeat),
+            )
+        };
+        let schedule = match schedule {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                warn!("Invalid heartbeat schedule, skipping heartbeats: {}", e);
+                return None;
+            }
+        };
+        let local_now = Utc::now().with_timezone(&tz);
+
+        schedule
+            .phase_at(local_now.weekday(), local_now.time())
+            .map(|p| p.to_string())
+    }
+
+    /// Decide whether a heartbeat fires in this phase. A heartbeat waits out the
+    /// quiet gap after the last insight and, in adaptive mode, stops once today's
+    /// density-based target is reached. Each new outcome goes to the trigger audit.
+    async fn decide_heartbeat(
+        &self,
+        phase: String,
+        context: &ContextSnapshotSummary,
+    ) -> Option<InsightTrigger> {
+        let (heartbeat, tz) = {
+            let cfg = self.config.read();
+            (cfg.heartbeat.clone(), cfg.get_timezone())
+        };
+        let now = Utc::now();
+        let today = now.with_timezone(&tz).date_naive();
+        let fired_today = match *self.heartbeats_today.read() {
+            (date, count) if date == today => count,
+            _ => 0,
+        };
+
+        let (target, quiet_gap, detail) = if heartbeat.adaptive {
+            let events = context
+                .calendar_events
+                .iter()
+                .filter(|e| e.start_time > now)
+                .count();
+            let tasks = context.tasks.iter().filter(|t| !t.completed).count()
+                + context
+                    .notes_context
+                    .as_ref()
+                    .map(|n| n.pending_tasks.len())
+                    .unwrap_or(0);
+            let target = heartbeat::adaptive_target(
+                events,
+                tasks,
+                heartbeat.min_per_day,
+                heartbeat.max_per_day,
+            );
+            let gap = heartbeat::quiet_gap_minutes(target, heartbeat.phases.len());
+            let detail = format!(
+                "{} upcoming events, {} open tasks: {} heartbeats today ({} so far), {} min gap",
+                events, tasks, target, fired_today, gap
+            );
+            (Some(target), gap, Some(detail))
+        } else {
+            (None, heartbeat::QUIET_GAP_MINUTES, None)
+        };
+
+        let last_insight_age = self
+            .database
+            .blocking(|db| db.get_recent_insights(1))
+            .await
+            .unwrap_or_default()
+            .first()
+            .map(|i| (now - i.created_at).num_minutes());
+
+        let decision = if target.is_some_and(|t| fired_today >= t) {
+            "skipped: daily target reached"
+        } else if last_insight_age.is_some_and(|age| age < quiet_gap) {
+            "skipped: recent insight still fresh"
+        } else {
+            "fired"
+        };
+
+        // Audit each outcome once per phase per day rather than every minute
+        let audit_key = format!("{}|{}|{}", today, phase, decision);
+        let is_new = self.last_heartbeat_audit.read().as_deref() != Some(audit_key.as_str());
+        if is_new {
+            *self.last_heartbeat_audit.write() = Some(audit_key);
+            self.audit_trigger(&format!("heartbeat:{}", phase), decision, detail)
+                .await;
+        }
+
+        if decision != "fired" {
+            debug!("Heartbeat {} phase {}", phase, decision);
+            return None;
+        }
+
+        info!("Heartbeat trigger: {} phase", phase);
+        *self.heartbeats_today.write() = (today, fired_today + 1);
+        // Record this as an AI call in the significance engine so it respects the cooldown
+        self.significance_engine.record_ai_call();
+        Some(InsightTrigger::Heartbeat(phase))
+    }
+
+    /// Early in the month (days 1-7, daytime), once per month: the trigger
+    /// text for a reflective insight over the last weeks' load stats
+    async fn monthly_reflection(&self) -> Option<String> {
+        if !self.config.read().stats.monthly_reflection {
+            return None;
+        }
+        let (_, local_now) = self.get_time_of_day_phase();
+        if local_now.day() > 7 || !(9..=17).contains(&local_now.hour()) {
+            return None;
+        }
+        let last = self
+            .database
+            .blocking(|db| db.last_trigger_fired("monthly_reflection"))
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to read trigger audit: {}", e);
+                None
+            });
+        let this_month = (local_now.year(), local_now.month());
+        if last.is_some_and(|t| {
+            let t = t.with_timezone(&local_now.timezone());
+            (t.year(), t.month()) == this_month
+        }) {
+            return None;
+        }
+
+        let weeks = self
+            .database
+            .blocking(|db| db.get_week_stats(5))
+            .await
+            .unwrap_or_default();
+        // The current week is partial; a reflection needs a few complete ones
+        let current_week = load_stats::week_start(local_now.date_naive());
+        let weeks: Vec<_> = weeks
+            .into_iter()
+            .filter(|w| w.week_start < current_week)
+            .collect();
+        if weeks.len() < 2 {
+            return None;
+        }
+
+        info!("Monthly reflection trigger over {} weeks", weeks.len());
+        self.audit_trigger(
+            "monthly_reflection",
+            "fired",
+            weeks.last().map(|w| w.summary()),
+        )
+        .await;
+        self.significance_engine.record_ai_call();
+        Some(load_stats::reflection_prompt(&weeks))
+    }
+
+    /// Log meetings and tasks and refresh this week's (and last week's) load
+    /// stats, every few minutes
+    async fn record_load(&self, context: &ContextSnapshotSummary) {
+        let now = Utc::now();
+        if self
+            .last_load_log
+            .read()
+            .is_some_and(|last| now - last < chrono::Duration::minutes(LOAD_LOG_INTERVAL_MINUTES))
+        {
+            return;
+        }
+        *self.last_load_log.write() = Some(now);
+
+        let (tz, workday) = {
+            let cfg = self.config.read();
+            (cfg.get_timezone(), cfg.stats.workday())
+        };
+        let meetings = load_stats::observed_meetings(context);
+        let tasks: Vec<TaskLogEntry> = context
+            .tasks
+            .iter()
+            .filter_map(|t| {
+                Some(TaskLogEntry {
+                    task_id: t.id.clone(),
+                    title: t.title.clone(),
+                    due_time: t.due?,
+                    closed_at: t.completed.then_some(now),
+                    urgency: t.urgency,
+                })
+            })
+            .collect();
+
+        let this_week = load_stats::week_start(now.with_timezone(&tz).date_naive());
+        let result = self
+            .database
+            .blocking(move |db| {
+                db.log_meetings(&meetings, now)?;
+                // An empty list is more likely a failed source than a cleared one
+                if !tasks.is_empty() {
+                    db.log_tasks(&tasks, now)?;
+                }
+                for monday in [this_week - chrono::Duration::weeks(1), this_week] {
+                    let start = tz
+                        .from_local_datetime(&monday.and_time(chrono::NaiveTime::MIN))
+                        .earliest()
+                        .map(|t| t.to_utc())
+                        .unwrap_or(now);
+                    let end = start + chrono::Duration::weeks(1);
+                    let stats = load_stats::compute_week(
+                        monday,
+                        &db.get_meeting_log(start, end)?,
+                        &db.get_task_log(start, end)?,
+                        &tz,
+                        workday,
+                    );
+                    db.store_week_stats(&stats)?;
+                }
+                Ok(())
+            })
+            .await;
+        if let Err(e) = result {
+            warn!("Failed to update load stats: {}", e);
+        }
+    }
+
+    /// Best-effort write to the trigger audit
+    async fn audit_trigger(&self, trigger: &str, decision: &str, detail: Option<String>) {
+        let (trigger, decision) = (trigger.to_string(), decision.to_string());
+        if let Err(e) = self
+            .database
+            .blocking(move |db| db.record_trigger(&trigger, &decision, detail.as_deref()))
+            .await
+        {
+            warn!("Failed to record trigger decision: {}", e);
+        }
+    }
+
+    /// Check context for changes and analyze if significant.
+    /// Uses a dual trigger model: heartbeat (time-of-day phases) + event-driven (context changes).
+    /// Returns the new insight's ID, if one was generated; `refresh` is the
+    /// ForceRefresh request to report progress for.
+    async fn check_and_analyze(&self, refresh: Option<u64>) -> JasperResult<Option<i64>> {
+        debug!("Checking context for significant changes");
+
+        // Collect current context from all sources
+        self.report_refresh(refresh, RefreshStage::Collecting, "")
+            .await;
+        let mut current_context = self.collect_current_context().await?;
+        self.announce_urgent_alerts(&current_context).await;
+        self.update_slack_status(&current_context).await;
+        self.record_load(&current_context).await;
+
+        // Determine trigger: context change or heartbeat
+        let last_call = self.significance_engine.last_ai_call();
+        let (mut is_significant, changes) = self
+            .significance_engine
+            .analyze_context(current_context.clone());
+
+        // Score the change locally; a low score keeps the new context but
+        // skips the AI call (manual refreshes always make it)
+        let score = is_significant.then(|| {
+            let (_, local_now) = self.get_time_of_day_phase();
+            prefilter::score(&changes, &current_context, &local_now)
+        });
+        if let Some(ref score) = score {
+            let min_score = self.config.read().coalescing.min_score;
+            if refresh.is_none() && !prefilter::passes(&changes, score, min_score) {
+                info!(
+                    "Changes scored {} (below {}) — skipping AI call",
+                    score, min_score
+                );
+                self.significance_engine.restore_ai_call(last_call);
+                self.audit_trigger(
+                    "context_change",
+                    "prefiltered",
+                    Some(format!("score {}: {:?}", score, changes)),
+                )
+                .await;
+                is_significant = false;
+            }
+        }
+
+        // Enrich calendar events with travel times (after significance check so
+        // traffic fluctuations don't trigger unnecessary AI calls)
+        self.enrich_travel_times(&mut current_context).await;
+        self.check_wake_alarm(&current_context).await;
+        self.send_family_digest(&current_context).await;
+        self.update_people_notes(&current_context);
+        self.update_guest_displays().await;
+
+        let trigger = if is_significant {
+            info!("Significant changes detected: {:?}", changes);
+            let detail = match score {
+                Some(score) => format!("score {}: {:?}", score, changes),
+                None => format!("{:?}", changes),
+            };
+            self.audit_trigger("context_change", "fired", Some(detail))
+                .await;
+            Some(InsightTrigger::ContextChange(changes))
+        } else if self.significance_engine.is_settling() {
+            // The burst's insight is minutes away; don't add a heartbeat to it
+            debug!("Context changes settling — holding other triggers");
+            None
+        } else if let Some(prompt) = self.monthly_reflection().await {
+            Some(InsightTrigger::MonthlyReflection(prompt))
+        } else if let Some(phase) = self.should_fire_heartbeat() {
+            self.decide_heartbeat(phase, &current_context).await
+        } else {
+            None
+        };
+
+        let Some(trigger) = trigger else {
+            debug!("No trigger fired — skipping AI call");
+            return Ok(None);
+        };
+        self.advance_escalations(&current_context).await;
+
+        if let (None, Some(until)) = (refresh, self.snoozed_until()) {
+            self.audit_trigger("snooze", "suppressed", Some(format!("until {}", until)))
+                .await;
+            return Ok(None);
+        }
+
+        // Call AI for analysis with full context and trigger info
+        self.report_refresh(refresh, RefreshStage::Analyzing, "")
+            .await;
+        let insight = self.analyze_with_ai(&current_context, &trigger).await?;
+
+        // Store the insight, its digest and the context snapshot that triggered it
+        let (snapshot_max_bytes, snapshot_retention_days, zero_retention) = {
+            let cfg = self.config.read();
+            (
+                cfg.database.snapshot_max_kb as usize * 1024,
+                cfg.database.snapshot_retention_days,
+                cfg.ai.zero_retention,
+            )
+        };
+        let snapshot =
+            (!zero_retention).then(|| serde_json::to_value(&current_context).unwrap_or_default());
+        let day_summary = self.pending_day_summary.write().take().map(|mut summary| {
+            let (_, local_now) = self.get_time_of_day_phase();
+            summary.record_insight(
+                &local_now.format("%-I:%M %p").to_string(),
+                &insight.emoji,
+                &insight.text,
+            );
+            summary
+        });
+        // What the insight mentions, to open from it
+        let subjects = deep_links::subjects(&insight.text, &current_context);
+        // First link on each mentioned event, e.g. its agenda doc
+        let attached: HashMap<String, crate::attachments::EventLink> = current_context
+            .calendar_events
+            .iter()
+            .filter_map(|e| Some((e.id.clone(), e.links.first()?.clone())))
+            .collect();
+        let vault = self
+            .config
+            .read()
+            .get_obsidian_config()
+            .filter(|c| c.enabled)
+            .and_then(|c| deep_links::vault_name(&c.vault_path));
+        let (stored, actions) = {
+            let insight = insight.clone();
+            self.database
+                .blocking(move |db| {
+                    let insight_id = db.store_insight(
+                        &insight.emoji,
+                        &insight.text,
+                        Some(&insight.context_hash),
+                    )?;
+                    info!("Stored new insight with ID: {}", insight_id);
+
+                    if let Some(ref model) = insight.model {
+                        if let Err(e) = db.set_insight_model(insight_id, model, insight.fallback) {
+                            warn!("Failed to record insight model: {}", e);
+                        }
+                    }
+
+                    if let Err(e) = db.store_insight_sources(insight_id, &insight.sources) {
+                        warn!("Failed to record insight sources: {}", e);
+                    }
+
+                    if let Err(e) = db.record_insight_memory(
+                        insight_id,
+                        &insight.emoji,
+                        &insight_memory::keywords(&insight.text).join(" "),
+                        &insight_memory::summarize(&insight.text),
+                    ) {
+                        warn!("Failed to record insight memory: {}", e);
+                    }
+
+                    if let Some(ref digest) = insight.digest {
+                        match db.store_digest(insight_id, digest) {
+                            Ok(digest_id) => {
+                                info!("Stored morning digest {}", digest_id)
+                            }
+                            Err(e) => warn!("Failed to store digest: {}", e),
+                        }
+                    }
+
+                    if let Some(snapshot) = snapshot {
+                        if let Err(e) = Self::store_snapshot(
+                            db,
+                            insight_id,
+                            snapshot,
+                            snapshot_max_bytes,
+                            snapshot_retention_days,
+                        ) {
+                            warn!("Failed to store context snapshot: {}", e);
+                        }
+                    }
+
+                    if let Some(summary) = day_summary {
+                        let saved = serde_json::to_string(&summary)
+                            .map_err(|e| e.to_string())
+                            .and_then(|json| {
+                                db.store_day_summary(&summary.date.to_string(), &json)
+                                    .map_err(|e| e.to_string())
+                            });
+                        if let Err(e) = saved {
+                            warn!("Failed to store day summary: {}", e);
+                        }
+                    }
+
+                    let actions: Vec<InsightAction> = subjects
+                        .iter()
+                        .flat_map(|subject| {
+                            let (link, attachment) = match subject {
+                                deep_links::Subject::Event { id, .. } => (
+                                    db.get_event_raw_data(id)
+                                        .ok()
+                                        .flatten()
+                                        .and_then(|raw| deep_links::event_link(&raw)),
+                                    attached.get(id),
+                                ),
+                                _ => (None, None),
+                            };
+                            let attachment = attachment
+                                .map(|a| deep_links::attachment_action(subject.title(), a));
+                            subject
+                                .action(link.as_deref(), vault.as_deref())
+                                .into_iter()
+                                .chain(attachment)
+                        })
+                        .collect();
+                    let pairs: Vec<(String, String)> = actions
+                        .iter()
+                        .map(|a| (a.label.clone(), a.uri.clone()))
+                        .collect();
+                    if let Err(e) = db.store_insight_actions(insight_id, &pairs) {
+                        warn!("Failed to store insight actions: {}", e);
+                    }
+                    Ok((insight_id, actions))
+                })
+                .await
+        }?;
+
+        // Emit D-Bus signal to notify frontends of new insight
+        self.emit_insight_signal(stored, &insight.emoji, &insight.text, &actions)
+            .await;
+        Ok(Some(stored))
+    }
+
+    /// Hold scheduled insights for `minutes`; 0 resumes them. Returns when
+    /// the snooze ends.
+    pub fn snooze(&self, minutes: u32) -> Option<DateTime<Utc>> {
+        let until = (minutes > 0).then(|| Utc::now() + chrono::Duration::minutes(minutes as i64));
+        *self.snoozed_until.write() = until;
+        match until {
+            Some(until) => info!("Insights snoozed until {}", until),
+            None => info!("Insights resumed"),
+        }
+        until
+    }
+
+    /// Include `json` from `source_id` in analyses for `ttl_seconds`; 0
+    /// withdraws it. Returns when it expires, None when withdrawn.
+    pub fn inject_context(
+        &self,
+        source_id: &str,
+        json: &str,
+        ttl_seconds: u32,
+    ) -> JasperResult<Option<DateTime<Utc>>> {
+        let entry = self
+            .injected_context
+            .inject(source_id, json, ttl_seconds, Utc::now())?;
+        match &entry {
+            Some(entry) => info!(
+                "Context from {} injected until {}",
+                entry.source_id, entry.expires_at
+            ),
+            None => info!("Context from {} withdrawn", source_id.trim()),
+        }
+        Ok(entry.map(|entry| entry.expires_at))
+    }
+
+    /// When the current snooze ends, if insights are snoozed
+    pub fn snoozed_until(&self) -> Option<DateTime<Utc>> {
+        (*self.snoozed_until.read()).filter(|until| *until > Utc::now())
+    }
+
+    /// Start a ForceRefresh, or join the one already running. Returns the
+    /// request ID progress is reported under, and whether the caller should
+    /// go on to `run_refresh` it.
+    pub fn begin_refresh(&self) -> (u64, bool) {
+        let mut refreshes = self.refreshes.write();
+        if let Some(id) = refreshes.in_flight {
+            info!("Refresh {} already running; joining it", id);
+            return (id, false);
+        }
+        refreshes.last_id += 1;
+        refreshes.in_flight = Some(refreshes.last_id);
+        (refreshes.last_id, true)
+    }
+
+    /// Run a refresh begun with `begin_refresh`, emitting RefreshProgress
+    /// for each stage. Waits for a scheduled check already in progress.
+    pub async fn run_refresh(&self, request_id: u64) -> JasperResult<Option<i64>> {
+        info!("Forcing immediate context refresh ({})", request_id);
+        let _analysis = match self.analysis_lock.try_lock() {
+            Ok(guard) => guard,
+            Err(_) => {
+                self.report_refresh(
+                    Some(request_id),
+                    RefreshStage::Queued,
+                    "waiting for the scheduled check",
+                )
+                .await;
+                self.analysis_lock.lock().await
+            }
+        };
+        let result = self.check_and_analyze(Some(request_id)).await;
+        self.refreshes.write().in_flight = None;
+
+        match &result {
+            Ok(Some(insight_id)) => {
+                let detail = format!("insight {}", insight_id);
+                self.report_refresh(Some(request_id), RefreshStage::Done, &detail)
+                    .await
+            }
+            Ok(None) => {
+                self.report_refresh(
+                    Some(request_id),
+                    RefreshStage::Done,
+                    "nothing significant changed",
+                )
+                .await
+            }
+            Err(e) => {
+                self.report_refresh(Some(request_id), RefreshStage::Failed, &e.to_string())
+                    .await
+            }
+        }
+        result
+    }
+
+    /// Emit RefreshProgress for a ForceRefresh request (no-op outside one)
+    async fn report_refresh(&self, refresh: Option<u64>, stage: RefreshStage, detail: &str) {
+        let Some(request_id) = refresh else {
+            return;
+        };
+        debug!("Refresh {}: {} {}", request_id, stage.name(), detail);
+        if let Some(ref emitter) = *self.signal_emitter.read().await {
+            if let Err(e) = emitter
+                .emit_refresh_progress(request_id, stage.name(), detail)
+                .await
+            {
+                warn!("Failed to emit RefreshProgress: {}", e);
+            }
+        }
+    }
+
+    /// Count this insight against every critical item still in the context.
+    /// Items that cross the threshold get a critical notification and, if
+    /// configured, an email — once each — and stronger wording in the prompt.
+    async fn advance_escalations(&self, context: &ContextSnapshotSummary) {
+        let policy = self.config.read().escalation.clone();
+        if !policy.enabled {
+            self.escalated.write().clear();
+            return;
+        }
+        let (_, local_now) = self.get_time_of_day_phase();
+        let items: Vec<(String, String)> = escalation::critical_items(context, &local_now, &policy)
+            .into_iter()
+            .map(|item| (item.key, item.label))
+            .collect();
+        let records = match self
+            .database
+            .blocking(move |db| db.advance_escalations(&items))
+            .await
+        {
+            Ok(records) => records,
+            Err(e) => {
+                warn!("Failed to update escalations: {}", e);
+                return;
+            }
+        };
+
+        let escalated: Vec<EscalationRecord> = escalation::escalated(&records, &policy)
+            .into_iter()
+            .cloned()
+            .collect();
+        for record in escalated.iter().filter(|r| !r.notified) {
+            info!(
+                "Escalating {} after {} insights: {}",
+                record.item_key, record.streak, record.label
+            );
+            let body = format!(
+                "{} — still unaddressed after {} insights. Run `jasper-companion-daemon dismiss {}` to stop these alerts.",
+                record.label, record.streak, record.item_key
+            );
+            if policy.critical_notification {
+                // Headless there is no desktop to alert; email still goes out
+                let notifier = self.delivery().notifier.clone().or_else(|| {
+                    (!self.headless).then(|| Arc::<dyn Notifier>::from(platform::notifier()))
+                });
+                if let Some(notifier) = notifier {
+                    Self::show_notification(
+                        notifier,
+                        "🚨 Jasper: needs attention".to_string(),
+                        body.clone(),
+                        Urgency::Critical,
+                    )
+                    .await;
+                }
+            }
+            if let Some(to) = policy.email_to.clone() {
+                let sendmail = policy.sendmail_path.clone();
+                let subject = format!("Jasper: {}", record.label);
+                let sent = tokio::task::spawn_blocking(move || {
+                    escalation::send_email(&sendmail, &to, &subject, &body)
+                })
+                .await;
+                if let Ok(Err(e)) = sent {
+                    warn!("Failed to email escalation: {}", e);
+                }
+            }
+            let key = record.item_key.clone();
+            if let Err(e) = self
+                .database
+                .blocking(move |db| db.mark_escalation_notified(&key))
+                .await
+            {
+                warn!("Failed to mark escalation as sent: {}", e);
+            }
+        }
+        *self.escalated.write() = escalated;
+    }
+
+    /// In the evening, suggest an earlier alarm when tomorrow's first event
+    /// needs one, and set it through the configured backend
+    async fn check_wake_alarm(&self, context: &ContextSnapshotSummary) {
+        let alarm = self.config.read().alarm.clone();
+        if !alarm.enabled {
+            return;
+        }
+        let (_, local_now) = self.get_time_of_day_phase();
+        let Ok(remind_after) = chrono::NaiveTime::parse_from_str(&alarm.remind_after, "%H:%M")
+        else {
+            return;
+        };
+        let tomorrow = local_now.date_naive().succ_opt();
+        if local_now.time() < remind_after || *self.alarm_suggested_for.read() == tomorrow {
+            return;
+        }
+        let Some(suggestion) = wake_alarm::suggest(
+            &context.calendar_events,
+            context.weather_context.as_ref(),
+            &local_now,
+            &alarm,
+        ) else {
+            return;
+        };
+        *self.alarm_suggested_for.write() = tomorrow;
+
+        let text = suggestion.describe();
+        info!("Wake alarm: {}", text);
+        if let Some(backend) = wake_alarm::backend(&alarm) {
+            let (wake_at, label) = (
+                suggestion.wake_at,
+                format!(
+                    "{} at {}",
+                    suggestion.event_title,
+                    suggestion.event_start.format("%-I:%M %p")
+                ),
+            );
+            let backend_name = backend.name();
+            let set = tokio::task::spawn_blocking(move || backend.set(&wake_at, &label)).await;
+            match set {
+                Ok(Ok(())) => info!(
+                    "Alarm set for {} via {}",
+                    suggestion.wake_at.format("%-I:%M %p"),
+                    backend_name
+                ),
+                Ok(Err(e)) => warn!("Failed to set alarm via {}: {}", backend_name, e),
+                Err(e) => warn!("Alarm task failed: {}", e),
+            }
+        }
+        self.audit_trigger("alarm", "fired", Some(text.clone()))
+            .await;
+
+        let emoji = "⏰";
+        let stored = {
+            let text = text.clone();
+            self.database
+                .blocking(move |db| db.store_insight(emoji, &text, None))
+                .await
+        };
+        match stored {
+            Ok(insight_id) => {
+                self.emit_insight_signal(insight_id, emoji, &text, &[])
+                    .await
+            }
+            Err(e) => error!("Failed to store alarm insight: {}", e),
+        }
+    }
+
+    /// When an event with people who have notes ends, record the meeting in
+    /// their notes (see `people_notes`), asking first if so configured
+    fn update_people_notes(&self, context: &ContextSnapshotSummary) {
+        let (updates, vault, tz) = {
+            let cfg = self.config.read();
+            let Some(obsidian) = cfg.get_obsidian_config().filter(|o| o.enabled) else {
+                return;
+            };
+            (
+                cfg.people.note_updates.clone(),
+                obsidian.vault_path.clone(),
+                cfg.get_timezone(),
+            )
+        };
+        let Some(ref notes) = context.notes_context else {
+            return;
+        };
+        if !updates.enabled {
+            return;
+        }
+        let now = Utc::now();
+        let notifier = self.delivery().notifier.clone();
+        let directory = people::Directory {
+            household: &[],
+            notes: &notes.people,
+        };
+        // Folders as the Obsidian source reads them
+        let people_folder = std::path::PathBuf::from(vault)
+            .join(context_sources::obsidian::ObsidianConfig::default().people_folder);
+
+        let mut noted = self.people_noted.write();
+        noted.retain(|_, end| now - *end < chrono::Duration::days(1));
+        for event in &context.calendar_events {
+            let Some(end) = event
+                .end_time
+                .filter(|end| *end <= now && !event.is_all_day)
+            else {
+                continue;
+            };
+            let key = format!("{}@{}", event.id, event.start_time.timestamp());
+            if noted.insert(key, end).is_some() {
+                continue;
+            }
+            let mut people: Vec<&context_sources::PersonNote> = Vec::new();
+            for attendee in &event.attendees {
+                if let Some(people::Known::Note(note)) = directory.resolve(attendee) {
+                    if !people.iter().any(|p| p.id == note.id) {
+                        people.push(note);
+                    }
+                }
+            }
+            if people.is_empty() {
+                continue;
+            }
+
+            let names: Vec<&str> = people.iter().map(|p| p.name.as_str()).collect();
+            let body = format!(
+                "Record \"{}\" in the notes for {}",
+                event.title,
+                names.join(", ")
+            );
+            let paths: Vec<_> = people
+                .iter()
+                .map(|p| people_folder.join(format!("{}.md", p.id)))
+                .collect();
+            let date = event.start_time.with_timezone(&tz).date_naive();
+            let (title, updates, notifier) =
+                (event.title.clone(), updates.clone(), notifier.clone());
+            // Waits for the answer, so it runs on its own
+            tokio::task::spawn_blocking(move || {
+                if updates.confirm {
+                    let Some(notifier) = notifier else {
+                        return;
+                    };
+                    match notifier.confirm("📝 Update people notes?", &body, "Update") {
+                        Ok(true) => {}
+                        Ok(false) => return,
+                        Err(e) => {
+                            warn!("Failed to show {} notification: {}", notifier.name(), e);
+                            return;
+                        }
+                    }
+                }
+                for path in paths {
+                    match people_notes::update_note(&path, date, &title, &updates) {
+                        Ok(true) => info!("Recorded {} in {:?}", title, path),
+                        Ok(false) => {}
+                        Err(e) => warn!("Failed to update people note: {:#}", e),
+                    }
+                }
+            });
+        }
+    }
+
+    /// Once a morning, send the family calendars' day to the family channel.
+    /// Not sent late: a restart in the afternoon shouldn't re-announce the morning.
+    async fn send_family_digest(&self, context: &ContextSnapshotSummary) {
+        let delivery = self.delivery();
+        let Some(ref publisher) = delivery.family_digest else {
+            return;
+        };
+        let (send_at, calendars, activities) = {
+            let cfg = self.config.read();
+            (
+                cfg.family_digest.send_at.clone(),
+                cfg.family_digest.calendars.clone(),
+                ActivityClassifier::from_config(&cfg.activities),
+            )
+        };
+        let (_, local_now) = self.get_time_of_day_phase();
+        let Ok(send_at) = chrono::NaiveTime::parse_from_str(&send_at, "%H:%M") else {
+            return;
+        };
+        let today = local_now.date_naive();
+        let elapsed = local_now.time() - send_at;
+        if elapsed < chrono::Duration::zero()
+            || elapsed >= chrono::Duration::hours(FAMILY_DIGEST_WINDOW_HOURS)
+            || *self.family_digest_sent.read() == Some(today)
+        {
+            return;
+        }
+        *self.family_digest_sent.write() = Some(today);
+
+        let Some(text) = family_digest::build(
+            &context.calendar_events,
+            &calendars,
+            context.weather_context.as_ref(),
+            &activities,
+            &local_now,
+        ) else {
+            debug!("No family events today; family digest skipped");
+            return;
+        };
+        match publisher.publish(&text).await {
+            Ok(()) => {
+                info!("Family digest sent");
+                self.audit_trigger("family_digest", "fired", None).await;
+            }
+            Err(e) => warn!("Family digest delivery failed: {}", e),
+        }
+    }
+
+    /// Signal the guest insight to shared displays when it has changed
+    async fn update_guest_displays(&self) {
+        match self.database.has_guest_frontends() {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => {
+                warn!("Failed to look up guest frontends: {}", e);
+                return;
+            }
+        }
+        let Some((_, insight)) = self.guest_insight() else {
+            return;
+        };
+        let hash = insight.hash();
+        if self.guest_insight_sent.read().as_deref() == Some(hash.as_str()) {
+            return;
+        }
+        if let Some(ref emitter) = *self.signal_emitter.read().await {
+            match emitter
+                .emit_guest_insight_updated(&insight.emoji, &insight.text)
+                .await
+            {
+                Ok(()) => *self.guest_insight_sent.write() = Some(hash),
+                Err(e) => warn!("Failed to emit GuestInsightUpdated signal: {}", e),
+            }
+        }
+    }
+
+    /// Send newly issued high-urgency alerts to the webhook and Slack. Alerts
+    /// that clear are forgotten, so a reissued warning is sent again.
+    async fn announce_urgent_alerts(&self, context: &ContextSnapshotSummary) {
+        let delivery = self.delivery();
+        let webhook = delivery.webhook.as_ref().filter(|w| w.wants_alerts());
+        let slack = delivery.slack.as_ref().filter(|s| s.can_dm());
+        if webhook.is_none() && slack.is_none() {
+            return;
+        }
+        let urgent: HashSet<String> = context
+            .weather_context
+            .iter()
+            .flat_map(|w| w.alerts.iter())
+            .filter(|alert| webhook::is_high_urgency(alert))
+            .cloned()
+            .collect();
+        let new_alerts: Vec<String> = {
+            let mut announced = self.announced_alerts.write();
+            let new_alerts = urgent.difference(&announced).cloned().collect();
+            *announced = urgent;
+            new_alerts
+        };
+        for alert in new_alerts {
+            info!("Announcing high-urgency alert: {}", alert);
+            if let Some(webhook) = webhook {
+                if let Err(e) = webhook
+                    .publish(WebhookEvent::Alert, "⚠️", &alert, None)
+                    .await
+                {
+                    warn!("Failed to send alert webhook: {}", e);
+                }
+            }
+            if let Some(slack) = slack {
+                if let Err(e) = slack.send_dm(&format!("⚠️ {}", alert)).await {
+                    warn!("Failed to DM alert on Slack: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Keep the Slack status in step with the meeting in progress. Only a
+    /// status this daemon set is ever cleared.
+    async fn update_slack_status(&self, context: &ContextSnapshotSummary) {
+        let delivery = self.delivery();
+        let Some(slack) = delivery.slack.as_ref().filter(|s| s.sets_status()) else {
+            return;
+        };
+        let tz = self.config.read().get_timezone();
+        let desired = slack::meeting_status(&context.calendar_events, Utc::now(), &tz);
+        if *self.slack_status.read() == desired {
+            return;
+        }
+        match slack.update_status(desired.as_ref()).await {
+            Ok(()) => {
+                debug!(
+                    "Slack status set to {:?}",
+                    desired.as_ref().map(|s| &s.text)
+                );
+                *self.slack_status.write() = desired;
+            }
+            Err(e) => warn!("Failed to update Slack status: {}", e),
+        }
+    }
+
+    /// Whether an event from the lookback window still belongs in the context:
+    /// upcoming, in progress, or ended recently enough to prompt a follow-up.
+    fn is_event_still_relevant(
+        event: &crate::significance_engine::CalendarEventSummary,
+        now: DateTime<Utc>,
+    ) -> bool {
+        let cutoff = now - chrono::Duration::minutes(RECENTLY_ENDED_GRACE_MINUTES);
+        match event.end_time {
+            Some(end) => end > cutoff,
+            // No end time: treat all-day events as lasting the day, others as instants
+            None if event.is_all_day => event.start_time + chrono::Duration::hours(24) > now,
+            None => event.start_time > cutoff,
+        }
+    }
+
+    /// Describe when an event happens relative to now, flagging in-progress
+    /// and just-ended events so the AI doesn't treat them as upcoming
+    fn format_event_timing(
+        now: &DateTime<chrono::FixedOffset>,
+        event: &crate::significance_engine::CalendarEventSummary,
+    ) -> String {
+        let timing = match event_span::describe(event, now) {
+            Some(span) => span,
+            None if event.is_all_day => "all day".to_string(),
+            None => Self::format_timed_event_timing(now, event),
+        };
+        if event.tentative {
+            format!("{}, tentative — may not happen", timing)
+        } else {
+            timing
+        }
+    }
+
+    /// Timing of an event that isn't all day
+    fn format_timed_event_timing(
+        now: &DateTime<chrono::FixedOffset>,
+        event: &crate::significance_engine::CalendarEventSummary,
+    ) -> String {
+        let now_utc = now.to_utc();
+        match event.end_time {
+            Some(end) if event.start_time <= now_utc && end > now_utc => format!(
+                "happening now (until {})",
+                event_span::clock_from(now, &end)
+            ),
+            Some(end) if end <= now_utc => format!(
+                "ended {} — only relevant for follow-ups",
+                Self::format_relative_time(now, &end)
+            ),
+            _ => Self::format_relative_time(now, &event.start_time),
+        }
+    }
+
+    /// Collect current context from all sources
+    async fn collect_current_context(&self) -> JasperResult<ContextSnapshotSummary> {
+        let now = Utc::now();
+        let end_time = now + chrono::Duration::hours(24);
+
+        // In-progress events come back however long ago they started (the range
+        // is read by overlap), so a conference doesn't drop out on its second day
+        // and look cancelled to the significance engine. Look back 12 hours for
+        // events that ended recently; the rest are pruned.
+        let lookback_start = now - chrono::Duration::hours(12);
+        self.collect_context_window(lookback_start, end_time, true)
+            .await
+    }
+
+    /// Context for the agenda: every event in the window (including ones that
+    /// already ended), enriched with travel times the same way as for the AI
+    pub async fn agenda_context(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<ContextSnapshotSummary> {
+        let mut context = self.collect_context_window(start, end, false).await?;
+        self.enrich_travel_times(&mut context).await;
+        Ok(context)
+    }
+
+    /// Events from all calendars plus context sources for `start..end`.
+    /// `live` drops events that have ended, as the insight loop wants.
+    async fn collect_context_window(
+        &self,
+        start: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        live: bool,
+    ) -> JasperResult<ContextSnapshotSummary> {
+        let now = Utc::now();
+
+        // Get calendar events in the window (with calendar context)
+        let attendance = self.config.read().attendance;
+        let mut calendar_events: Vec<_> = self
+            .database
+            .blocking(move |db| db.get_events_by_calendar(start, end_time, &[], &[]))
+            .await?
+            .into_iter()
+            .flat_map(|calendar| {
+                let is_own = calendar.access_role.as_deref() == Some("owner");
+                let calendar_name = calendar.calendar_name;
+                let is_primary = calendar.is_primary;
+                let color = calendar.color;
+                calendar.events.into_iter().filter_map(move |event| {
+                    let policy = invitations::policy(&attendance, event.my_response.as_deref());
+                    if policy == AttendancePolicy::Drop {
+                        return None;
+                    }
+                    Some(crate::significance_engine::CalendarEventSummary {
+                        id: event.source_id,
+                        title: event.title.unwrap_or_default(),
+                        start_time: DateTime::from_timestamp(event.start_time, 0)
+                            .unwrap_or_default(),
+                        end_time: event
+                            .end_time
+                            .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default()),
+                        location: event.location,
+                        is_all_day: event.is_all_day.unwrap_or(false),
+                        calendar_name: Some(calendar_name.clone()),
+                        calendar_color: color.clone(),
+                        is_own_calendar: is_own,
+                        is_primary_calendar: is_primary,
+                        travel_time: None,
+                        attendees: event
+                            .participants
+                            .as_deref()
+                            .map(crate::people::parse_participants)
+                            .unwrap_or_default(),
+                        links: event
+                            .links
+                            .as_deref()
+                            .map(crate::attachments::parse)
+                            .unwrap_or_default(),
+                        awaiting_reply: event.my_response.as_deref()
+                            == Some(invitations::NEEDS_ACTION),
+                        tentative: policy == AttendancePolicy::Demote,
+                    })
+                })
+            })
+            .filter(|event| !live || Self::is_event_still_relevant(event, now))
+            .collect();
+        calendar_events.sort_by_key(|event| event.start_time);
+
+        // Collect additional context from all enabled context sources
+        let mut context_data = match self
+            .context_manager
+            .read()
+            .await
+            .fetch_all_context(if live { now } else { start }, end_time)
+            .await
+        {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to fetch context from sources: {}", e);
+                Vec::new()
+            }
+        };
+        {
+            let cfg = self.config.read();
+            sanitize::sanitize_context_data(&mut context_data, &cfg.sanitization);
+            if cfg.ai.zero_retention {
+                context_data
+                    .iter_mut()
+                    .for_each(|data| data.metadata.clear());
+            }
+        }
+
+        // Extract weather, tasks, and full notes context from context data
+        let mut weather: Option<crate::significance_engine::WeatherSummary> = None;
+        let mut tasks: Vec<crate::significance_engine::TaskSummary> = Vec::new();
+        let mut notes_context: Option<context_sources::NotesContext> = None;
+        let mut weather_context: Option<context_sources::WeatherContext> = None;
+        let mut school_alerts: Vec<context_sources::SchoolAlert> = Vec::new();
+        let mut unanswered: Vec<context_sources::UnansweredMessage> = Vec::new();
+
+        for ctx in &context_data {
+            match &ctx.content {
+                context_sources::ContextContent::Weather(weather_ctx) => {
+                    weather_context = Some(weather_ctx.clone());
+                    // Parse temperature from forecast or conditions
+                    if let Some(forecast) = weather_ctx.forecast.first() {
+                        weather = Some(crate::significance_engine::WeatherSummary {
+                            condition: forecast.conditions.clone(),
+                            temperature: forecast.temperature_high as i32,
+                            feels_like: forecast.temperature_high as i32,
+                            units: weather_ctx.units,
+                        });
+                    }
+                }
+                context_sources::ContextContent::Tasks(task_ctx) => {
+                    tasks.extend(task_ctx.tasks.iter().map(|t| {
+                        crate::significance_engine::TaskSummary {
+                            id: t.id.clone(),
+                            title: t.title.clone(),
+                            due: t.due_date,
+                            completed: matches!(t.status, context_sources::TaskStatus::Completed),
+                            priority: t.priority,
+                            urgency: t.priority,
+                            source: t.source.clone(),
+                        }
+                    }));
+                }
+                context_sources::ContextContent::Notes(notes_ctx) => {
+                    notes_context = Some(notes_ctx.clone());
+                    // Also extract tasks from notes for the significance engine
+                    tasks.extend(notes_ctx.pending_tasks.iter().map(|t| {
+                        crate::significance_engine::TaskSummary {
+                            id: t.id.clone(),
+                            title: t.title.clone(),
+                            due: t.due_date,
+                            completed: matches!(t.status, context_sources::TaskStatus::Completed),
+                            priority: t.priority,
+                            urgency: t.priority,
+                            source: t.source.clone(),
+                        }
+                    }));
+                }
+                context_sources::ContextContent::SchoolAlerts(school_ctx) => {
+                    school_alerts.extend(school_ctx.alerts.iter().cloned());
+                }
+                context_sources::ContextContent::Messages(messages) => {
+                    unanswered.extend(messages.waiting.iter().cloned());
+                }
+                _ => {}
+            }
+        }
+
+        let mut injected = self.injected_context.active(Utc::now());
+        {
+            let cfg = self.config.read();
+            sanitize::sanitize_calendar_events(&mut calendar_events, &cfg.sanitization);
+            sanitize::sanitize_injected(&mut injected, &cfg.sanitization);
+        }
+        task_urgency::apply(&mut tasks, &calendar_events, now);
+
+        let schedule_anomalies = if live {
+            let patterns = self
+                .database
+                .blocking(|db| db.get_user_patterns())
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to read schedule patterns: {}", e);
+                    Vec::new()
+                });
+            let tz = self.config.read().get_timezone();
+            schedule_patterns::detect(
+                &schedule_patterns::parse(&patterns),
+                &calendar_events,
+                now,
+                &tz,
+            )
+        } else {
+            Vec::new()
+        };
+
+        // Create context hash for comparison
+        let context_hash = format!("{:x}", {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            calendar_events.hash(&mut hasher);
+            weather.hash(&mut hasher);
+            tasks.hash(&mut hasher);
+            school_alerts.hash(&mut hasher);
+            injected.hash(&mut hasher);
+            schedule_anomalies.hash(&mut hasher);
+            unanswered.hash(&mut hasher);
+            hasher.finish()
+        });
+
+        Ok(ContextSnapshotSummary {
+            calendar_events,
+            weather,
+            tasks,
+            notes_context,
+            weather_context,
+            school_alerts,
+            injected,
+            schedule_anomalies,
+            unanswered,
+            timestamp: now,
+            context_hash,
+        })
+    }
+
+    /// Attach travel times from home to in-person events, when travel is configured
+    async fn enrich_travel_times(&self, context: &mut ContextSnapshotSummary) {
+        let Some(travel_service) = self.travel_service.read().clone() else {
+            return;
+        };
+        let travel_times = travel_service
+            .get_travel_times_for_events(&context.calendar_events)
+            .await;
+        for event in &mut context.calendar_events {
+            if let Some(tt) = travel_times.get(&event.id) {
+                event.travel_time = Some(TravelTimeInfo {
+                    duration_minutes: (tt.duration_seconds / 60) as i32,
+                    duration_in_traffic_minutes: tt
+                        .duration_in_traffic_seconds
+                        .map(|s| (s / 60) as i32),
+                    distance_km: tt.distance_meters as f32 / 1000.0,
+                    origin_label: "home".to_string(),
+                    travel_mode_label: travel_service.travel_mode_label().to_string(),
+                });
+            }
+        }
+    }
+
+    /// Call AI service for analysis, with automatic retry on transient failures
+    async fn analyze_with_ai(
+        &self,
+        context: &ContextSnapshotSummary,
+        trigger: &InsightTrigger,
+    ) -> JasperResult<AiInsight> {
+        debug!("Calling AI service for context analysis");
+
+        // Build the request body once so retries reuse it
+        let request_body = self.build_anthropic_request(context, trigger)?;
+
+        // An identical prompt (e.g. right after a restart) gets the same answer without an API call
+        if let Some(ttl) = self.response_cache_ttl() {
+            let prompt_hash = Self::prompt_hash(&request_body);
+            let lookup_hash = prompt_hash.clone();
+            match self
+                .database
+                .blocking(move |db| db.get_cached_response(&lookup_hash, ttl))
+                .await
+            {
+                Ok(Some(cached)) => {
+                    info!("Serving cached AI response for prompt {}", prompt_hash);
+                    let mut insight = self.insight_from_response(&cached, &context.context_hash);
+                    insight.model = request_body["model"].as_str().map(str::to_string);
+                    insight.sources = Self::prompt_sources(&request_body);
+                    return Ok(insight);
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Response cache lookup failed: {}", e),
+            }
+        }
+
+        match self
+            .api_manager
+            .execute_with_retry(|| {
+                let body = request_body.clone();
+                async move {
+                    self.send_with_fallback(&body)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("{}", e))
+                }
+            })
+            .await
+        {
+            Ok((insight, tokens_used)) => {
+                self.api_manager.record_api_call(tokens_used);
+                Ok(insight)
+            }
+            Err(e) => {
+                // A concrete weather-gear note beats a generic fallback
+                if let Some(insight) = self.local_gear_insight(context) {
+                    warn!("AI unavailable ({}), using local weather insight", e);
+                    return Ok(insight);
+                }
+                // If rate-limited / circuit-broken, return fallback
+                if e.to_string().contains("Daily API limit")
+                    || e.to_string().contains("Circuit breaker")
+                {
+                    warn!("API call blocked: {}", e);
+                    Ok(AiInsight {
+                        emoji: "⏳".to_string(),
+                        text: "Rate limited - check back later for fresh insights".to_string(),
+                        context_hash: context.context_hash.clone(),
+                        digest: None,
+                        model: None,
+                        fallback: false,
+                        sources: BTreeMap::new(),
+                    })
+                } else {
+                    Err(crate::errors::JasperError::Internal {
+                        message: format!("AI analysis failed: {}", e),
+                    })
+                }
+            }
+        }
+    }
+
+    /// First weather-gear note as an insight, for when the AI can't be reached
+    fn local_gear_insight(&self, context: &ContextSnapshotSummary) -> Option<AiInsight> {
+        let weather = context.weather_context.as_ref()?;
+        let (_, local_now) = self.get_time_of_day_phase();
+        let activities = ActivityClassifier::from_config(&self.config.read().activities);
+        let note = weather_gear::notes_for_events(
+            &context.calendar_events,
+            weather,
+            &activities,
+            &local_now,
+        )
+        .into_iter()
+        .next()?;
+        Some(AiInsight {
+            emoji: note.flag.emoji().to_string(),
+            text: note.describe(),
+            context_hash: context.context_hash.clone(),
+            digest: None,
+            model: None,
+            fallback: false,
+            sources: BTreeMap::new(),
+        })
+    }
+
+    /// Load personal context from the user's context file, with mtime-based caching
+    fn load_personal_context(&self) -> Option<String> {
+        // Resolve path: explicit config override, or default convention
+        let path = {
+            let cfg = self.config.read();
+            if let Some(ref explicit) = cfg.general.personal_context_file {
+                if let Some(stripped) = explicit.strip_prefix("~/") {
+                    if let Some(home) = dirs::home_dir() {
+                        home.join(stripped)
+                    } else {
+                        std::path::PathBuf::from(explicit)
+                    }
+                } else {
+                    std::path::PathBuf::from(explicit)
+                }
+            } else {
+                Config::get_personal_context_path()?
+            }
+        };
+
+        let metadata = std::fs::metadata(&path).ok()?;
+        let mtime = metadata.modified().ok()?;
+
+        // Check cache: return cached content if file hasn't changed
+        {
+            let cache = self.personal_context_cache.read();
+            if let Some((cached_mtime, ref content)) = *cache {
+                if cached_mtime == mtime {
+                    return Some(content.clone());
+                }
+            }
+        }
+
+        // File changed (or first read) — reload
+        match std::fs::read_to_string(&path) {
+            Ok(content) if !content.trim().is_empty() => {
+                let trimmed = content.trim().to_string();
+                *self.personal_context_cache.write() = Some((mtime, trimmed.clone()));
+                info!(
+                    "Loaded personal context from {:?} ({} bytes)",
+                    path,
+                    trimmed.len()
+                );
+                Some(trimmed)
+            }
+            _ => None,
+        }
+    }
+
+    /// Determine the current time-of-day phase for the user's timezone
+    fn get_time_of_day_phase(&self) -> (&'static str, DateTime<chrono::FixedOffset>) {
+        let tz = self.config.read().get_timezone();
+        let local_now = Utc::now().with_timezone(&tz);
+        // Convert to FixedOffset for storage
+        let fixed_now = local_now.fixed_offset();
+        let hour = fixed_now.hour();
+        let phase = match hour {
+            5..=8 => "early morning — you're starting your day",
+            9..=11 => "morning — your day is underway",
+            12..=13 => "midday",
+            14..=16 => "afternoon",
+            17..=19 => "evening — winding down the day",
+            20..=22 => "night — wrapping up",
+            _ => "late night",
+        };
+        (phase, fixed_now)
+    }
+
+    /// Format a datetime as a human-relative time string (e.g., "in 45 minutes", "tomorrow at 3pm")
+    fn format_relative_time(now: &DateTime<chrono::FixedOffset>, target: &DateTime<Utc>) -> String {
+        let target_local = target.with_timezone(&now.timezone());
+        let diff = target_local.signed_duration_since(*now);
+        let minutes = diff.num_minutes();
+        let hours = diff.num_hours();
+
+        if minutes < 0 {
+            let abs_min = minutes.abs();
+            if abs_min < 60 {
+                format!("{} minutes ago", abs_min)
+            } else if abs_min < 1440 {
+                format!("{} hours ago", (abs_min / 60))
+            } else {
+                format!("{} days ago", (abs_min / 1440))
+            }
+        } else if minutes < 60 {
+            format!("in {} minutes", minutes)
+        } else if hours < 24 {
+            if hours == 1 {
+                format!("in about an hour ({})", target_local.format("%I:%M %p"))
+            } else {
+                format!("in {} hours ({})", hours, target_local.format("%I:%M %p"))
+            }
+        } else {
+            let days = hours / 24;
+            if days == 1 {
+                format!("tomorrow at {}", target_local.format("%I:%M %p"))
+            } else {
+                format!("in {} days ({})", days, target_local.format("%a %I:%M %p"))
+            }
+        }
+    }
+
+    /// Format a relative deadline for tasks (e.g., "due in 2 days", "OVERDUE by 3 days")
+    fn format_relative_deadline(
+        now: &DateTime<chrono::FixedOffset>,
+        due: &DateTime<Utc>,
+    ) -> String {
+        let due_local = due.with_timezone(&now.timezone());
+        let diff = due_local.signed_duration_since(*now);
+        let hours = diff.num_hours();
+        let days = diff.num_days();
+
+        if hours < 0 {
+            let abs_days = days.abs();
+            if abs_days == 0 {
+                "OVERDUE (due today)".to_string()
+            } else if abs_days == 1 {
+                "OVERDUE by 1 day".to_string()
+            } else {
+                format!("OVERDUE by {} days", abs_days)
+            }
+        } else if hours < 24 {
+            "due today".to_string()
+        } else if days == 1 {
+            "due tomorrow".to_string()
+        } else if days <= 7 {
+            format!("due in {} days", days)
+        } else {
+            format!("due in {} weeks", days / 7)
+        }
+    }
+
+    /// Format travel time info for inclusion in the AI prompt
+    fn format_travel_time(travel: &Option<TravelTimeInfo>) -> String {
+        let Some(tt) = travel else {
+            return String::new();
+        };
+
+        let base = format!(
+            "{} min {} from {}",
+            tt.duration_minutes, tt.travel_mode_label, tt.origin_label
+        );
+
+        if let Some(traffic_min) = tt.duration_in_traffic_minutes {
+            // Only mention traffic if it adds 5+ minutes over base
+            if traffic_min > tt.duration_minutes + 5 {
+                format!(" ({}, ~{} min in current traffic)", base, traffic_min)
+            } else {
+                format!(" ({})", base)
+            }
+        } else {
+            format!(" ({})", base)
+        }
+    }
+
+    /// What's attached to an event, by title only; the links stay local
+    fn format_attached(links: &[crate::attachments::EventLink]) -> String {
+        let titles: Vec<&str> = links.iter().filter_map(|l| l.title.as_deref()).collect();
+        match (links.len(), titles.is_empty()) {
+            (0, _) => String::new(),
+            (_, true) => " [has agenda attached]".to_string(),
+            (_, false) => format!(" [has agenda attached: {}]", titles.join(", ")),
+        }
+    }
+
+    /// Format an event label for schedule situation output (e.g. "Kieran: Soccer (5:30-7:00 PM, at Rec Center)")
+    fn format_situation_event_label(
+        event: &crate::significance_engine::CalendarEventSummary,
+        tz: &chrono::FixedOffset,
+    ) -> String {
+        let cal_prefix = if !event.is_primary_calendar {
+            event
+                .calendar_name
+                .as_deref()
+                .map(|n| format!("{}: ", n))
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+
+        let start_local = event.start_time.with_timezone(tz);
+        let time_range = if let Some(end) = event.end_time {
+            let end_local = end.with_timezone(tz);
+            format!(
+                "{}-{}",
+                start_local.format("%-I:%M %p"),
+                end_local.format("%-I:%M %p")
+            )
+        } else {
+            format!("{}", start_local.format("%-I:%M %p"))
+        };
+
+        let location = event
+            .location
+            .as_deref()
+            .map(|l| format!(", at {}", l))
+            .unwrap_or_default();
+
+        format!(
+            "\"{}{}\" ({}{})",
+            cal_prefix, event.title, time_range, location
+        )
+    }
+
+    /// Detect cross-event schedule situations worth surfacing to the AI.
+    /// Finds time overlaps between owned calendars and tight timing on the user's primary calendar.
+    pub fn detect_schedule_situations(
+        events: &[crate::significance_engine::CalendarEventSummary],
+        now: &DateTime<chrono::FixedOffset>,
+    ) -> Vec<String> {
+        let mut situations = Vec::new();
+        let tz = now.timezone();
+        let now_utc = now.to_utc();
+
+        // Filter to future/in-progress, non-all-day events on owned calendars only
+        let relevant: Vec<&crate::significance_engine::CalendarEventSummary> = events
+            .iter()
+            .filter(|e| !e.is_all_day)
+            .filter(|e| !e.tentative)
+            .filter(|e| e.is_own_calendar) // primary + owned non-primary
+            .filter(|e| {
+                // Keep events that haven't ended yet
+                e.end_time.map(|end| end > now_utc).unwrap_or(true)
+            })
+            .collect();
+
+        // Compare all pairs (N is small — typically <20 events in 24h window)
+        for i in 0..relevant.len() {
+            for j in (i + 1)..relevant.len() {
+                let a = relevant[i];
+                let b = relevant[j];
+
+                let a_end = match a.end_time {
+                    Some(end) => end,
+                    None => continue,
+                };
+                let b_end = match b.end_time {
+                    Some(end) => end,
+                    None => continue,
+                };
+
+                // Hard overlap: A starts before B ends AND B starts before A ends
+                let overlaps = a.start_time < b_end && b.start_time < a_end;
+
+                if overlaps {
+                    // Flag cross-calendar overlaps or same-person double-bookings
+                    let cross_calendar = a.calendar_name != b.calendar_name
+                        || a.is_primary_calendar
+                        || b.is_primary_calendar;
+
+                    if cross_calendar {
+                        let a_label = Self::format_situation_event_label(a, &tz);
+                        let b_label = Self::format_situation_event_label(b, &tz);
+                        situations.push(format!(
+                            "OVERLAP: {} and {} — both need someone there",
+                            a_label, b_label
+                        ));
+                    }
+                } else {
+                    // Check for tight timing: gap < 15 minutes between consecutive events
+                    let gap = if a_end <= b.start_time {
+                        (b.start_time - a_end).num_minutes()
+                    } else if b_end <= a.start_time {
+                        (a.start_time - b_end).num_minutes()
+                    } else {
+                        continue;
+                    };
+
+                    if (0..=15).contains(&gap) {
+                        // Only flag tight timing when the user's primary calendar is involved
+                        if a.is_primary_calendar || b.is_primary_calendar {
+                            let (first, second) = if a.start_time <= b.start_time {
+                                (a, b)
+                            } else {
+                                (b, a)
+                            };
+                            let first_label = Self::format_situation_event_label(first, &tz);
+                            let second_label = Self::format_situation_event_label(second, &tz);
+                            situations.push(format!(
+                                "TIGHT: {} then {} ({} min gap)",
+                                first_label, second_label, gap
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        situations
+    }
+
+    /// Build the Anthropic API request body from context (no I/O, can be reused for retries)
+    fn build_anthropic_request(
+        &self,
+        context: &ContextSnapshotSummary,
+        trigger: &InsightTrigger,
+    ) -> JasperResult<serde_json::Value> {
+        let (time_phase, local_now) = self.get_time_of_day_phase();
+
+        // --- Build the system message with personality and guidance ---
+        let (personality, _timezone_str) = {
+            let cfg = self.config.read();
+            let (p, tz) = cfg.get_personality_config();
+            (p.clone(), tz.to_string())
+        };
+
+        let persona_desc = personality
+            .persona_reference
+            .as_deref()
+            .map(|r| format!(" ({})", r))
+            .unwrap_or_default();
+
+        // Same-day prompts build on the rolling summary of the day so far
+        let day_summary = self
+            .config
+            .read()
+            .ai
+            .day_summary
+            .then(|| self.load_day_summary(local_now.date_naive()));
+
+        // Get recent insights for deduplication; today's come from the day summary
+        let recent_insights = self.database.get_recent_insights(5).unwrap_or_default();
+        let recent_insights_text =
+            if let Some(today) = day_summary.as_ref().and_then(DaySummary::render) {
+                today
+            } else if recent_insights.is_empty() {
+                "None yet — this is your first insight of the session.".to_string()
+            } else {
+                recent_insights
+                    .iter()
+                    .map(|i| {
+                        format!(
+                            "- {} {} ({})",
+                            i.emoji,
+                            i.insight,
+                            Self::format_relative_time(&local_now, &i.created_at)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+
+        // Topics covered over the last month, so repeats escalate instead of echoing
+        let memory_text = self
+            .database
+            .get_insight_memory()
+            .map(|memories| insight_memory::render(&insight_memory::topics(&memories), &local_now))
+            .unwrap_or_else(|e| {
+                warn!("Failed to load insight memory: {}", e);
+                None
+            })
+            .map(|topics| {
+                format!(
+                    "\n\nAlready told {title} over the past month:\n{topics}\n                     If one of these is still relevant, escalate — say what changed, what's now at stake,                      or the concrete next step — rather than repeating it in similar words.",
+                    title = personality.user_title,
+                )
+            })
+            .unwrap_or_default();
+
+        // What was typed into recent insights' notifications
+        let replies_text = self
+            .database
+            .get_insight_replies_since(Utc::now() - chrono::Duration::hours(REPLY_MEMORY_HOURS))
+            .unwrap_or_else(|e| {
+                warn!("Failed to load insight replies: {}", e);
+                Vec::new()
+            });
+        let replies_text = if replies_text.is_empty() {
+            String::new()
+        } else {
+            let lines: Vec<String> = replies_text
+                .iter()
+                .map(|(insight, reply, at)| {
+                    format!(
+                        "- To \"{}\" ({}): \"{}\"",
+                        insight,
+                        Self::format_relative_time(&local_now, at),
+                        reply
+                    )
+                })
+                .collect();
+            format!(
+                "\n\n{title} replied to recent insights:\n{lines}\n\
+                 Take these replies into account.",
+                title = personality.user_title,
+                lines = lines.join("\n"),
+            )
+        };
+
+        // Critical items ignored across several insights get blunter treatment
+        let escalation_text = {
+            let escalated = self.escalated.read();
+            let records: Vec<&EscalationRecord> = escalated.iter().collect();
+            escalation::prompt_section(&records, &personality.user_title)
+                .map(|section| format!("\n\n{}", section))
+                .unwrap_or_default()
+        };
+
+        // The instructions and personal context rarely change, so they form a
+        // separate block the provider can cache; time and history follow it
+        let instructions = format!(
+            "You are Jasper, a {persona}{persona_ref}. \
+You provide a single glanceable insight for {title}'s status bar — like Android's At a Glance widget, but smarter.\n\n\
+Your job: Surface the ONE most useful situation {title} needs to know about right now. \
+An insight is NOT just a single event — it could be a conflict between events, a logistics problem, \
+a timing crunch, or a pattern across the schedule that needs attention. \
+Think about what a thoughtful family coordinator who can see everyone's calendars would tap them on the shoulder about.\n\n\
+Prioritize (in rough order):\n\
+1. Logistics conflicts — two family members need to be in different places at the same time, or {title} is double-booked\n\
+2. Tight timing — back-to-back events that leave no margin, especially with travel time\n\
+3. Things that need action or preparation in the next 1-2 hours\n\
+4. Travel logistics — if an event has travel time, remind them when they need to leave (e.g. \"you should head out in 20 minutes for your 2pm meeting\")\n\
+5. Tasks or deadlines that are creeping up and easy to forget (the kind assigned weeks ago that slip through the cracks)\n\
+6. Cross-domain connections (a task relates to an upcoming event, weather affects plans)\n\n\
+Do NOT:\n\
+- Simply restate a calendar entry (\"You have a meeting at 3pm\") — add value beyond what a calendar shows\n\
+- Focus on weather unless it meaningfully impacts plans or activities\n\
+- Repeat something you've already surfaced recently (see recent insights below)\n\
+- Be robotic or generic — write like someone who knows {title} personally, with warmth\n\
+- Use any name or title other than \"{title}\" when addressing the user — always call them \"{title}\", never \"Sir\", \"Ma'am\", or any other title\n\
+- NEVER invent, fabricate, or assume events, tasks, or appointments that are not listed in the context below — if the schedule is empty, it's empty\n\
+- NEVER assume {title} is involved in events from shared calendars — those are shown for awareness only. Never say \"you were briefed on\" or \"your call with\" for shared events. Frame as: \"Christen has a call at 3pm\" not \"you have a call at 3pm\"\n\
+- Don't mention travel time if it's trivially short (under 5 minutes) — only surface it when it's actionable\n\
+- Don't focus on a single event in isolation when there's a more interesting relationship between events \
+(e.g. don't just say \"Kieran has soccer at 6\" when the real insight is that two kids have overlapping events at different locations)\n\n\
+Tone: {formality}. Keep it to ONE concise sentence. Warm and familiar, not stiff.\n\
+Start your response with a single emoji that captures the mood or topic (e.g. ☕ for morning routines, ⏰ for time-sensitive items, 🌧️ for weather impacts, 📋 for tasks). Vary it — don't reuse the same emoji back-to-back.",
+            persona = personality.assistant_persona,
+            persona_ref = persona_desc,
+            title = personality.user_title,
+            formality = personality.formality,
+        );
+
+        // Append personal context if the user has a context.md file
+        let instructions = if let Some(personal_ctx) = self.load_personal_context() {
+            let title = &personality.user_title;
+            format!(
+                "{instructions}\n\n\
+                 Personal context about {title} (provided by them — use this to understand their life, \
+                 relationships, and routines when interpreting calendar events and generating insights):\n\
+                 {personal_ctx}"
+            )
+        } else {
+            instructions
+        };
+
+        let system_message = format!(
+            "Current time: {now} ({phase}).\n\n\
+             Recent insights (DO NOT repeat these):\n{recent_insights}{memory}{replies}{escalation}",
+            now = local_now.format("%A, %B %-d at %-I:%M %p"),
+            phase = time_phase,
+            recent_insights = recent_insights_text,
+            memory = memory_text,
+            replies = replies_text,
+            escalation = escalation_text,
+        );
+
+        // First call of the morning returns the digest too, so it costs one API call instead of two
+        let wants_digest = self.wants_digest();
+        let system_message = if wants_digest {
+            format!(
+                "{system_message}\n\n\
+                 This is the first check-in of the morning, so also write {title}'s daily digest. \
+                 Respond in exactly this format:\n\
+                 Insight: <emoji> <the one glanceable sentence>\n\
+                 Digest:\n\
+                 - <3 to 6 short bullets covering today's schedule, logistics, deadlines and anything to prepare>",
+                title = personality.user_title,
+            )
+        } else {
+            system_message
+        };
+
+        // --- Build the context (user message) with full data and relative times ---
+        let mut context_parts: Vec<PromptSection> = Vec::new();
+
+        // Trigger reason
+        let trigger_text = match trigger {
+            InsightTrigger::Heartbeat(phase) => format!("Trigger: Regular {} check-in.", phase),
+            InsightTrigger::MonthlyReflection(prompt) => prompt.clone(),
+            InsightTrigger::ContextChange(changes) => {
+                let change_descriptions: Vec<String> = changes
+                    .iter()
+                    .map(|c| match c {
+                        SignificantChange::NewCalendarEvent(title) => {
+                            format!("New event added: \"{}\"", title)
+                        }
+                        SignificantChange::CancelledCalendarEvent(title) => {
+                            format!("Event cancelled: \"{}\"", title)
+                        }
+                        SignificantChange::EventTimeChanged {
+                            event_id,
+                            time_diff_hours,
+                        } => format!("Event {} moved by {:.1} hours", event_id, time_diff_hours),
+                        SignificantChange::EventLocationChanged { event_id } => {
+                            format!("Event {} location changed", event_id)
+                        }
+                        SignificantChange::WeatherConditionChanged { from, to } => {
+                            format!("Weather changed: {} → {}", from, to)
+                        }
+                        SignificantChange::WeatherTemperatureChanged { diff, units } => {
+                            format!(
+                                "Temperature shifted by {}{}",
+                                diff,
+                                units.temperature_symbol()
+                            )
+                        }
+                        SignificantChange::NewTask(title) => format!("New task: \"{}\"", title),
+                        SignificantChange::TaskCompleted(title) => {
+                            format!("Task completed: \"{}\"", title)
+                        }
+                        SignificantChange::TaskDueChanged {
+                            task_id,
+                            time_diff_hours,
+                        } => format!(
+                            "Task {} due date moved by {:.1} hours",
+                            task_id, time_diff_hours
+                        ),
+                        SignificantChange::SchoolAlert { district, title } => {
+                            format!("School alert from {}: \"{}\"", district, title)
+                        }
+                        SignificantChange::InjectedContext { source_id } => {
+                            format!("New context from {}", source_id)
+                        }
+                        SignificantChange::ScheduleAnomaly(description) => {
+                            format!("Out of the ordinary: {}", description)
+                        }
+                        SignificantChange::UnansweredMessage { from } => {
+                            format!("{} is still waiting on a reply", from)
+                        }
+                        SignificantChange::InitialContext => {
+                            "Initial startup — first look at the day.".to_string()
+                        }
+                    })
+                    .collect();
+                format!(
+                    "Trigger: Context changed — {}",
+                    change_descriptions.join("; ")
+                )
+            }
+        };
+        context_parts.push(PromptSection::new(
+            "trigger",
+            priority::REQUIRED,
+            trigger_text,
+        ));
+
+        // Detect when there is no real data at all
+        let has_calendar = !context.calendar_events.is_empty();
+        let has_tasks = !context.tasks.is_empty();
+        let has_weather = context.weather.is_some() || context.weather_context.is_some();
+        let has_notes = context.notes_context.is_some();
+
+        let has_school = !context.school_alerts.is_empty();
+        let has_injected = !context.injected.is_empty();
+        let has_messages = !context.unanswered.is_empty();
+
+        if !has_calendar
+            && !has_tasks
+            && !has_weather
+            && !has_notes
+            && !has_school
+            && !has_injected
+            && !has_messages
+        {
+            context_parts.push(PromptSection::new(
+                "empty schedule",
+                priority::REQUIRED,
+                "\nNo calendar events, tasks, weather, or notes are available. \
+                The schedule is completely clear. Do NOT invent or assume any events — \
+                provide a genuine observation about having a clear schedule.",
+            ));
+        }
+
+        // School closures and dismissals reshuffle the family day; lead with them
+        if !context.school_alerts.is_empty() {
+            let mut section = String::from(
+                "\nSchool alerts (closures, delays, early dismissals — these override the usual schedule):",
+            );
+            for alert in &context.school_alerts {
+                section.push_str(&format!(
+                    "\n- {} ({}): {}",
+                    alert.kind.label(),
+                    alert.district,
+                    alert.title
+                ));
+            }
+            context_parts.push(PromptSection::new(
+                "school alerts",
+                priority::SCHOOL_ALERTS,
+                section,
+            ));
+        }
+
+        // Told to us directly, so it's current and it matters
+        if !context.injected.is_empty() {
+            let mut section = String::from(
+                "\nCurrent situation (reported by the user's own scripts; true until it expires):",
+            );
+            for entry in &context.injected {
+                section.push_str(&format!("\n- {}: {}", entry.source_id, entry.summary()));
+            }
+            context_parts.push(PromptSection::new(
+                "injected context",
+                priority::INJECTED_CONTEXT,
+                section,
+            ));
+        }
+
+        // Pre-computed schedule analysis — surfaces cross-event situations for the AI
+        let situations = Self::detect_schedule_situations(&context.calendar_events, &local_now);
+        if !situations.is_empty() {
+            let mut section =
+                String::from("\nSchedule situations (cross-calendar analysis — prioritize these):");
+            for sit in &situations {
+                section.push_str(&format!("\n- {}", sit));
+            }
+            context_parts.push(PromptSection::new(
+                "schedule situations",
+                priority::SCHEDULE_SITUATIONS,
+                section,
+            ));
+        }
+
+        // Learned habits the coming day breaks — found locally, not left to the AI
+        if !context.schedule_anomalies.is_empty() {
+            let mut section =
+                String::from("\nOut of the ordinary (compared with the user's usual weeks):");
+            for anomaly in &context.schedule_anomalies {
+                section.push_str(&format!("\n- {}", anomaly.description));
+            }
+            context_parts.push(PromptSection::new(
+                "schedule anomalies",
+                priority::SCHEDULE_ANOMALIES,
+                section,
+            ));
+        }
+
+        // Forecast matched against in-person events — concrete gear to mention
+        let activities = ActivityClassifier::from_config(&self.config.read().activities);
+        if let Some(weather_ctx) = &context.weather_context {
+            let notes = weather_gear::notes_for_events(
+                &context.calendar_events,
+                weather_ctx,
+                &activities,
+                &local_now,
+            );
+            if !notes.is_empty() {
+                let mut section = String::from(
+                    "
+Weather gear (forecast vs. in-person events):",
+                );
+                for note in &notes {
+                    section.push_str(&format!(
+                        "
+- {}",
+                        note.describe()
+                    ));
+                }
+                context_parts.push(PromptSection::new(
+                    "weather gear",
+                    priority::WEATHER_GEAR,
+                    section,
+                ));
+            }
+        }
+
+        // Calendar events with relative times — 3-way partition:
+        // 1. Primary calendar (user's personal events) → "Your calendar"
+        // 2. Owned non-primary calendars (family calendars user created) → grouped by calendar name
+        // 3. Shared calendars (reader/writer access) → "Shared calendar — X (awareness only)"
+        if !context.calendar_events.is_empty() {
+            let mut primary_events = Vec::new();
+            let mut owned_non_primary: BTreeMap<
+                &str,
+                Vec<&crate::significance_engine::CalendarEventSummary>,
+            > = BTreeMap::new();
+            let mut shared_events: BTreeMap<
+                &str,
+                Vec<&crate::significance_engine::CalendarEventSummary>,
+            > = BTreeMap::new();
+
+            for event in &context.calendar_events {
+                if event.is_primary_calendar {
+                    primary_events.push(event);
+                } else if event.is_own_calendar {
+                    // Owned but not primary — family calendars like "Kieran Thomas", "House"
+                    let name = event.calendar_name.as_deref().unwrap_or("Other");
+                    owned_non_primary.entry(name).or_default().push(event);
+                } else {
+                    let name = event.calendar_name.as_deref().unwrap_or("Shared");
+                    shared_events.entry(name).or_default().push(event);
+                }
+            }
+
+            // 1. Primary calendar events
+            if !primary_events.is_empty() {
+                let mut cal_section = String::from("\nYour calendar (next 24h):");
+                for event in &primary_events {
+                    let timing = Self::format_event_timing(&local_now, event);
+                    let location = event
+                        .location
+                        .as_ref()
+                        .map(|l| format!(", at {}", l))
+                        .unwrap_or_default();
+                    let travel = Self::format_travel_time(&event.travel_time);
+                    let attached = Self::format_attached(&event.links);
+                    cal_section.push_str(&format!(
+                        "\n- \"{}\" — {}{}{}{}",
+                        event.title, timing, location, travel, attached
+                    ));
+                }
+                context_parts.push(PromptSection::new(
+                    "primary calendar",
+                    priority::PRIMARY_CALENDAR,
+                    cal_section,
+                ));
+            }
+
+            // 2. Owned non-primary calendars, grouped by calendar name
+            for (cal_name, events) in &owned_non_primary {
+                let mut section = format!(
+                    "\n{} calendar (these are {}'s events, NOT {}'s own):",
+                    cal_name, cal_name, personality.user_title
+                );
+                for event in events {
+                    let timing = Self::format_event_timing(&local_now, event);
+                    let location = event
+                        .location
+                        .as_ref()
+                        .map(|l| format!(", at {}", l))
+                        .unwrap_or_default();
+                    let travel = Self::format_travel_time(&event.travel_time);
+                    let attached = Self::format_attached(&event.links);
+                    section.push_str(&format!(
+                        "\n- \"{}\" — {}{}{}{}",
+                        event.title, timing, location, travel, attached
+                    ));
+                }
+                context_parts.push(PromptSection::new(
+                    format!("{} calendar", cal_name),
+                    priority::FAMILY_CALENDARS,
+                    section,
+                ));
+            }
+
+            // 3. Shared calendar events, grouped by calendar name
+            for (cal_name, events) in &shared_events {
+                let mut section = format!(
+                    "\nShared calendar — {} (awareness only, NOT {}'s events):",
+                    cal_name, personality.user_title,
+                );
+                for event in events {
+                    let timing = Self::format_event_timing(&local_now, event);
+                    let location = event
+                        .location
+                        .as_ref()
+                        .map(|l| format!(", at {}", l))
+                        .unwrap_or_default();
+                    section.push_str(&format!("\n- \"{}\" — {}{}", event.title, timing, location));
+                }
+                context_parts.push(PromptSection::new(
+                    format!("shared calendar {}", cal_name),
+                    priority::SHARED_CALENDARS,
+                    section,
+                ));
+            }
+        }
+
+        // Tasks with relative deadlines, most urgent first
+        if !context.tasks.is_empty() {
+            let mut task_section = String::from("\nTasks:");
+            for task in &context.tasks {
+                if task.completed {
+                    continue;
+                }
+                let deadline = task
+                    .due
+                    .as_ref()
+                    .map(|d| format!(" ({})", Self::format_relative_deadline(&local_now, d)))
+                    .unwrap_or_else(|| " (no due date)".to_string());
+                task_section.push_str(&format!(
+                    "\n- {}{} — urgency {}/{}",
+                    task.title,
+                    deadline,
+                    task.urgency,
+                    task_urgency::MAX_URGENCY
+                ));
+            }
+            context_parts.push(PromptSection::new("tasks", priority::TASKS, task_section));
+        }
+
+        // Full weather context (if available)
+        if let Some(weather_ctx) = &context.weather_context {
+            let mut weather_section = format!("\nWeather: {}", weather_ctx.current_conditions);
+            if !weather_ctx.forecast.is_empty() {
+                let today = &weather_ctx.forecast[0];
+                let unit = weather_ctx.units.temperature_symbol();
+                weather_section.push_str(&format!(
+                    " (High: {:.0}{unit}, Low: {:.0}{unit}, {}% chance of precipitation)",
+                    today.temperature_high,
+                    today.temperature_low,
+                    (today.precipitation_chance * 100.0) as i32
+                ));
+            }
+            if !weather_ctx.alerts.is_empty() {
+                weather_section.push_str(&format!(
+                    "\nWeather alerts: {}",
+                    weather_ctx.alerts.join(", ")
+                ));
+            }
+            context_parts.push(PromptSection::new(
+                "weather",
+                priority::WEATHER,
+                weather_section,
+            ));
+        } else if let Some(weather) = &context.weather {
+            context_parts.push(PromptSection::new(
+                "weather",
+                priority::WEATHER,
+                format!(
+                    "\nWeather: {} ({}{})",
+                    weather.condition,
+                    weather.temperature,
+                    weather.units.temperature_symbol()
+                ),
+            ));
+        }
+
+        // Daylight: sunrise/sunset and outdoor events that cross them
+        let location = self.config.read().location();
+        if let Some((latitude, longitude)) = location {
+            if let Some(sun) = daylight::sun_times(local_now.date_naive(), latitude, longitude) {
+                let tz = local_now.timezone();
+                let time = |t: DateTime<Utc>| t.with_timezone(&tz).format("%-I:%M %p").to_string();
+                let mut section = format!(
+                    "\nDaylight: sunrise {}, sunset {}",
+                    time(sun.sunrise),
+                    time(sun.sunset)
+                );
+                for note in daylight::dark_event_notes(
+                    &context.calendar_events,
+                    latitude,
+                    longitude,
+                    &activities,
+                    &local_now,
+                ) {
+                    section.push_str(&format!("\n- {}", note));
+                }
+                context_parts.push(PromptSection::new("daylight", priority::DAYLIGHT, section));
+            }
+        }
+
+        // Invitations still waiting on an answer
+        let invitations = invitations::prompt_lines(&context.calendar_events, &local_now);
+        if !invitations.is_empty() {
+            context_parts.push(PromptSection::new(
+                "invitations",
+                priority::INVITATIONS,
+                format!(
+                    "\nInvitations {} hasn't answered yet:\n{}",
+                    personality.user_title,
+                    invitations.join("\n")
+                ),
+            ));
+        }
+
+        // Who upcoming meetings are with, and who among them Jasper knows
+        let household = self.config.read().people.household.clone();
+        let directory = people::Directory {
+            household: &household,
+            notes: context
+                .notes_context
+                .as_ref()
+                .map_or(&[], |notes| notes.people.as_slice()),
+        };
+        let meetings = people::meeting_lines(&context.calendar_events, &directory, &local_now);
+        if !meetings.is_empty() {
+            context_parts.push(PromptSection::new(
+                "people",
+                priority::PEOPLE,
+                format!("\nWho you're meeting:\n{}", meetings.join("\n")),
+            ));
+        }
+
+        // Communication alerts: people left waiting, next to the ones to catch up with
+        if !context.unanswered.is_empty() {
+            let mut section = String::from("\nWaiting on a reply (Slack):");
+            for message in &context.unanswered {
+                let hours = (local_now.to_utc() - message.sent).num_hours();
+                let place = match (message.kind, &message.channel) {
+                    (context_sources::MessageKind::Mention, Some(channel)) => {
+                        format!("mention in #{}", channel)
+                    }
+                    (context_sources::MessageKind::Mention, None) => "mention".to_string(),
+                    (context_sources::MessageKind::DirectMessage, _) => "DM".to_string(),
+                };
+                section.push_str(&format!(
+                    "\n- {} ({}, {}h ago): \"{}\"",
+                    message.from, place, hours, message.text
+                ));
+            }
+            context_parts.push(PromptSection::new(
+                "unanswered messages",
+                priority::UNANSWERED_MESSAGES,
+                section,
+            ));
+        }
+
+        // Notes context: projects, relationships, focus areas
+        if let Some(notes) = &context.notes_context {
+            // Only projects falling behind their deadlines
+            let at_risk = project_risk::at_risk(&notes.active_projects, local_now.to_utc());
+            if !at_risk.is_empty() {
+                let mut proj_section =
+                    String::from("\nProjects at risk of missing their deadline:");
+                for (project, score) in &at_risk {
+                    let deadline = project
+                        .due_date
+                        .as_ref()
+                        .map(|d| format!(" ({})", Self::format_relative_deadline(&local_now, d)))
+                        .unwrap_or_default();
+                    proj_section.push_str(&format!(
+                        "\n- {}{}, {:.0}% complete, risk {:.1}",
+                        project.name,
+                        deadline,
+                        project.progress.clamp(0.0, 1.0) * 100.0,
+                        score
+                    ));
+                }
+                context_parts.push(PromptSection::new(
+                    "projects",
+                    priority::PROJECTS,
+                    proj_section,
+                ));
+            }
+
+            // Daily notes (focus areas + local summary), one section per note so
+            // older notes are the first to go when the prompt is over budget
+            for note in notes
+                .daily_notes
+                .iter()
+                .filter(|n| !n.focus_areas.is_empty() || !n.content.is_empty())
+            {
+                let age_days = (Utc::now() - note.date).num_days().clamp(0, 100) as u32;
+                let heading = if age_days == 0 {
+                    "Today's daily note".to_string()
+                } else {
+                    format!("Daily note from {}", note.date.format("%A %b %-d"))
+                };
+                let mut focus_section = format!("\n{}:", heading);
+                for area in &note.focus_areas {
+                    focus_section.push_str(&format!("\n- Focus: {}", area));
+                }
+                // Content is already a locally-produced summary, not the raw note
+                for line in note.content.lines() {
+                    focus_section.push_str(&format!("\n  {}", line));
+                }
+                context_parts.push(PromptSection::new(
+                    format!("daily note {}", note.title),
+                    priority::DAILY_NOTES - age_days,
+                    focus_section,
+                ));
+            }
+        }
+
+        // Context already sent today and unchanged since is replaced by its headline
+        let (context_parts, unchanged) = match day_summary {
+            Some(mut summary) => {
+                let (mut kept, unchanged) = summary.compress(context_parts);
+                if !unchanged.is_empty() {
+                    debug!(
+                        "Day summary stands in for unchanged sections: {:?}",
+                        unchanged.iter().map(|s| &s.name).collect::<Vec<_>>()
+                    );
+                }
+                summary.fold(&kept, &local_now.format("%-I:%M %p").to_string());
+                *self.pending_day_summary.write() = Some(summary);
+                kept.extend(day_summary::unchanged_section(&unchanged));
+                (kept, unchanged)
+            }
+            None => (context_parts, Vec::new()),
+        };
+
+        // Leave room for the system message within the overall prompt budget
+        let (max_prompt_tokens, prompt_caching, zero_retention) = {
+            let cfg = self.config.read();
+            (
+                cfg.ai.max_prompt_tokens,
+                cfg.ai.uses_prompt_cache(),
+                cfg.ai.zero_retention,
+            )
+        };
+        let context_budget = max_prompt_tokens
+            .saturating_sub(token_budget::estimate_tokens(&instructions))
+            .saturating_sub(token_budget::estimate_tokens(&system_message))
+            .max(MIN_CONTEXT_TOKENS);
+        let fitted = TokenBudgeter::new(context_budget).fit(context_parts);
+        let sources = lineage::tally(&fitted, &unchanged);
+        let user_message = fitted
+            .into_iter()
+            .map(|s| s.text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !zero_retention {
+            debug!("AI prompt user message:\n{}", user_message);
+        }
+
+        let call = match trigger {
+            InsightTrigger::MonthlyReflection(_) => AiCallType::Reflection,
+            _ if wants_digest => AiCallType::Digest,
+            _ => AiCallType::Insight,
+        };
+        let params = self.config.read().ai.params(call);
+        debug!("AI {} call: {:?}", call.name(), params);
+
+        Ok(serde_json::json!({
+            "model": params.model,
+            "max_tokens": params.max_tokens,
+            "temperature": params.temperature,
+            "system": Self::system_blocks(&instructions, &system_message, prompt_caching),
+            "messages": [{
+                "role": "user",
+                "content": user_message
+            }],
+            "_context_hash": context.context_hash,
+            "_sources": sources
+        }))
+    }
+
+    /// Store the context snapshot behind an insight, compressed and as a patch
+    /// against the latest full snapshot where that's smaller
+    fn store_snapshot(
+        db: &dyn StorageBackend,
+        insight_id: i64,
+        snapshot: serde_json::Value,
+        max_bytes: usize,
+        retention_days: u32,
+    ) -> JasperResult<()> {
+        let keyframe = match db.get_snapshot_keyframe(SNAPSHOT_SOURCE)? {
+            Some((stored, patches)) => {
+                match snapshot_codec::decode(&stored.encoding, &stored.data, None) {
+                    Ok(snapshot) => Some(snapshot_codec::Keyframe {
+                        id: stored.id,
+                        snapshot,
+                        patches,
+                    }),
+                    Err(e) => {
+                        warn!("Latest snapshot unreadable, storing a full one: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        match snapshot_codec::encode(snapshot, keyframe.as_ref(), max_bytes)? {
+            Some(encoded) => {
+                db.store_context_snapshot(
+                    insight_id,
+                    SNAPSHOT_SOURCE,
+                    encoded.encoding,
+                    &encoded.data,
+                    encoded.base_id,
+                    retention_days,
+                )?;
+                debug!(
+                    "Stored context snapshot ({}, {} bytes)",
+                    encoded.encoding,
+                    encoded.data.len()
+                );
+            }
+            None => warn!(
+                "Context snapshot over {} KiB even trimmed; not stored",
+                max_bytes / 1024
+            ),
+        }
+        Ok(())
+    }
+
+    /// The rolling summary stored for `date`, or a fresh one
+    fn load_day_summary(&self, date: chrono::NaiveDate) -> DaySummary {
+        let stored = self
+            .database
+            .get_day_summary(&date.to_string())
+            .unwrap_or_else(|e| {
+                warn!("Failed to load day summary: {}", e);
+                None
+            })
+            .and_then(|json| serde_json::from_str(&json).ok());
+        DaySummary::for_day(stored, date)
+    }
+
+    /// System prompt as content blocks: the stable instructions (marked for
+    /// prompt caching when enabled) followed by the per-call part
+    fn system_blocks(instructions: &str, per_call: &str, cache: bool) -> serde_json::Value {
+        let mut stable = serde_json::json!({ "type": "text", "text": instructions });
+        if cache {
+            stable["cache_control"] = serde_json::json!({ "type": "ephemeral" });
+        }
+        serde_json::json!([stable, { "type": "text", "text": per_call }])
+    }
+
+    /// Send the request, then resend it to each of `ai.fallback_models` in turn
+    /// while the provider reports an error (overloaded, model unavailable)
+    async fn send_with_fallback(
+        &self,
+        request_body: &serde_json::Value,
+    ) -> JasperResult<(AiInsight, u64)> {
+        let fallbacks = self.config.read().ai.fallback_models.clone();
+        let mut result = self.send_ai_request(request_body).await;
+        for model in fallbacks {
+            let Err(crate::errors::JasperError::Api { ref message, .. }) = result else {
+                break;
+            };
+            warn!(
+                "Model {} failed ({}), falling back to {}",
+                request_body["model"], message, model
+            );
+            let mut body = request_body.clone();
+            body["model"] = serde_json::json!(model);
+            body["_fallback"] = serde_json::json!(true);
+            result = self.send_ai_request(&body).await;
+        }
+        result
+    }
+
+    /// Send the request to the configured provider. Returns insight and tokens used.
+    async fn send_ai_request(
+        &self,
+        request_body: &serde_json::Value,
+    ) -> JasperResult<(AiInsight, u64)> {
+        let (mut insight, tokens_used) = if self.config.read().ai.provider == mock_ai::PROVIDER {
+            let context_hash = request_body
+                .get("_context_hash")
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            let content = mock_ai::respond(request_body);
+            debug!("Mock AI response:\n{}", content);
+            (self.insight_from_response(&content, context_hash), 0)
+        } else {
+            self.send_anthropic_request(request_body).await?
+        };
+        insight.model = request_body["model"].as_str().map(str::to_string);
+        insight.fallback = request_body["_fallback"].as_bool().unwrap_or(false);
+        insight.sources = Self::prompt_sources(request_body);
+        Ok((insight, tokens_used))
+    }
+
+    /// The source tally `build_anthropic_request` stored in the request body
+    fn prompt_sources(request_body: &serde_json::Value) -> BTreeMap<String, u32> {
+        serde_json::from_value(request_body["_sources"].clone()).unwrap_or_default()
+    }
+
+    /// Send the pre-built request body to the Anthropic API. Returns insight and tokens used.
+    async fn send_anthropic_request(
+        &self,
+        request_body: &serde_json::Value,
+    ) -> JasperResult<(AiInsight, u64)> {
+        let api_key = self.config.read().get_api_key()
+            .ok_or_else(|| crate::errors::JasperError::Authentication { service: "anthropic".into(), message: "API key not configured. Set via config, SOPS secrets, or ANTHROPIC_API_KEY environment variable.".into() })?;
+
+        // Strip our internal fields before sending
+        let mut body = request_body.clone();
+        let context_hash = body
+            .get("_context_hash")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+        if let Some(obj) = body.as_object_mut() {
+            obj.retain(|key, _| !key.starts_with('_'));
+        }
+
+        let response = self
+            .http_client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| crate::errors::JasperError::Internal {
+                message: format!("API request failed: {}", e),
+            })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            let message = format!("API call failed with status {}: {}", status, error_text);
+            // Another model won't fix a bad key; anything else may be model-specific
+            return Err(match status.as_u16() {
+                401 | 403 => crate::errors::JasperError::Authentication {
+                    service: "anthropic".into(),
+                    message,
+                },
+                _ => crate::errors::JasperError::Api {
+                    service: "anthropic".into(),
+                    message,
+                },
+            });
+        }
+
+        let response_json: serde_json::Value =
+            response
+                .json()
+                .await
+                .map_err(|e| crate::errors::JasperError::Internal {
+                    message: format!("Failed to parse response: {}", e),
+                })?;
+
+        let content = response_json
+            .get("content")
+            .and_then(|c| c.as_array())
+            .and_then(|arr| arr.first())
+            .and_then(|item| item.get("text"))
+            .and_then(|text| text.as_str())
+            .ok_or_else(|| crate::errors::JasperError::Internal {
+                message: "Invalid API response format".to_string(),
+            })?;
+
+        let usage_field = |name: &str| {
+            response_json
+                .pointer(&format!("/usage/{}", name))
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0)
+        };
+        let usage = ApiUsage {
+            model: body["model"].as_str().unwrap_or_default().to_string(),
+            input_tokens: usage_field("input_tokens"),
+            output_tokens: usage_field("output_tokens"),
+            cache_creation_tokens: usage_field("cache_creation_input_tokens"),
+            cache_read_tokens: usage_field("cache_read_input_tokens"),
+        };
+        let tokens_used = usage.input_tokens + usage.output_tokens;
+        if usage.cache_read_tokens > 0 {
+            debug!("Prompt cache hit: {} tokens", usage.cache_read_tokens);
+        }
+        if let Err(e) = self
+            .database
+            .blocking(move |db| db.record_api_usage(&usage))
+            .await
+        {
+            warn!("Failed to record API usage: {}", e);
+        }
+
+        // A fallback's answer isn't cached, so the next identical prompt tries the primary again
+        let fallback = request_body["_fallback"].as_bool().unwrap_or(false);
+        if let Some(ttl) = self.response_cache_ttl().filter(|_| !fallback) {
+            let prompt_hash = Self::prompt_hash(request_body);
+            let response = content.to_string();
+            if let Err(e) = self
+                .database
+                .blocking(move |db| db.store_cached_response(&prompt_hash, &response, ttl))
+                .await
+            {
+                warn!("Failed to cache AI response: {}", e);
+            }
+        }
+
+        Ok((
+            self.insight_from_response(content, &context_hash),
+            tokens_used,
+        ))
+    }
+
+    /// Turn raw response text into an insight (plus digest, if present)
+    fn insight_from_response(&self, content: &str, context_hash: &str) -> AiInsight {
+        let (insight_part, digest) = ai_response::split_digest(content);
+        let (emoji, text) = ai_response::parse_insight(&insight_part);
+        AiInsight {
+            emoji,
+            text,
+            context_hash: context_hash.to_string(),
+            digest,
+            model: None,
+            fallback: false,
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// How long cached responses stay valid, or None when caching is disabled
+    fn response_cache_ttl(&self) -> Option<chrono::Duration> {
+        match self.config.read().ai.response_cache_minutes() {
+            0 => None,
+            minutes => Some(chrono::Duration::minutes(minutes as i64)),
+        }
+    }
+
+    /// Canonical hash of the request exactly as it is sent (internal fields excluded)
+    fn prompt_hash(request_body: &serde_json::Value) -> String {
+        let mut body = request_body.clone();
+        if let Some(obj) = body.as_object_mut() {
+            obj.retain(|key, _| !key.starts_with('_'));
+        }
+        // serde_json maps are sorted by key, so serialization is canonical
+        format!("{:x}", md5::compute(body.to_string()))
+    }
+
+    /// Whether the next AI call should also produce the morning digest:
+    /// it's morning locally and no digest has been generated yet today.
+    fn wants_digest(&self) -> bool {
+        let tz = self.config.read().get_timezone();
+        let local_now = Utc::now().with_timezone(&tz);
+        if !(5..=11).contains(&local_now.hour()) {
+            return false;
+        }
+
+        match self.database.get_latest_digest() {
+            Ok(Some(digest)) => {
+                digest.created_at.with_timezone(&tz).date_naive() != local_now.date_naive()
+            }
+            Ok(None) => true,
+            Err(e) => {
+                warn!("Failed to check latest digest: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Get the most recent morning digest
+    pub fn get_latest_digest(&self) -> JasperResult<Option<crate::database::Digest>> {
+        self.database.get_latest_digest()
+    }
+
+    /// Get the latest insight from database
+    pub fn get_latest_insight(&self) -> JasperResult<Option<Insight>> {
+        self.database.get_latest_insight()
+    }
+
+    /// Seconds since an insight generated at `created_at`, and whether that is
+    /// past `frontends.stale_after_minutes`
+    pub fn insight_freshness(&self, created_at: DateTime<Utc>) -> (i64, bool) {
+        let stale_after = self.config.read().frontends.stale_after_minutes;
+        freshness(created_at, Utc::now(), stale_after)
+    }
+
+    /// Freshness of the latest insight (the guest insight's context for
+    /// guests), or None if there is none
+    pub fn latest_freshness(&self, guest: bool) -> JasperResult<Option<(i64, bool)>> {
+        let created_at = if guest {
+            self.guest_insight()
+                .and_then(|(timestamp, _)| DateTime::from_timestamp(timestamp, 0))
+        } else {
+            self.database
+                .get_latest_insight()?
+                .map(|insight| insight.created_at)
+        };
+        Ok(created_at.map(|created_at| self.insight_freshness(created_at)))
+    }
+
+    /// Values for the terminal template placeholders other than emoji/text.
+    /// Guests get no age or task count, and only family events.
+    pub fn template_fields(&self, guest: bool) -> JasperResult<BTreeMap<String, String>> {
+        let now = Utc::now();
+        let tz = self.config.read().get_timezone();
+        let mut fields = BTreeMap::new();
+
+        let latest = if guest {
+            None
+        } else {
+            self.database.get_latest_insight()?
+        };
+        let age = latest
+            .as_ref()
+            .map(|insight| insight_age(insight.created_at, now))
+            .unwrap_or_default();
+        fields.insert("age".to_string(), age);
+        let stale = latest.is_some_and(|insight| self.insight_freshness(insight.created_at).1);
+        fields.insert(
+            "stale".to_string(),
+            if stale { "stale" } else { "" }.to_string(),
+        );
+
+        let next_event = if guest {
+            self.guest_next_event()
+        } else {
+            self.next_event()
+        };
+        let next_event = next_event
+            .map(|e| {
+                format!(
+                    "{} at {}",
+                    e.title,
+                    e.start_time.with_timezone(&tz).format("%-I:%M %p")
+                )
+            })
+            .unwrap_or_default();
+        fields.insert("next_event".to_string(), next_event);
+
+        if !guest {
+            let pending_tasks = self
+                .significance_engine
+                .last_snapshot()
+                .map(|s| s.tasks.iter().filter(|t| !t.completed).count())
+                .unwrap_or(0);
+            fields.insert("tasks".to_string(), pending_tasks.to_string());
+        }
+
+        let today = now.with_timezone(&tz).date_naive();
+        let location = self.config.read().location();
+        let sun = location.and_then(|(lat, lon)| daylight::sun_times(today, lat, lon));
+        let time = |t: DateTime<Utc>| t.with_timezone(&tz).format("%-I:%M %p").to_string();
+        fields.insert(
+            "sunrise".to_string(),
+            sun.map(|s| time(s.sunrise)).unwrap_or_default(),
+        );
+        fields.insert(
+            "sunset".to_string(),
+            sun.map(|s| time(s.sunset)).unwrap_or_default(),
+        );
+        let daylight = match sun {
+            Some(s) if s.is_daylight(now) => "day",
+            Some(_) => "night",
+            None => "",
+        };
+        fields.insert("daylight".to_string(), daylight.to_string());
+
+        Ok(fields)
+    }
+
+    /// The next timed event that hasn't started yet, from the latest context snapshot
+    pub fn next_event(&self) -> Option<NextEvent> {
+        let now = Utc::now();
+        let snapshot = self.significance_engine.last_snapshot()?;
+        snapshot
+            .calendar_events
+            .iter()
+            .filter(|e| !e.is_all_day && e.start_time > now)
+            .min_by_key(|e| e.start_time)
+            .map(|e| NextEvent {
+                title: e.title.clone(),
+                location: e.location.clone(),
+                start_time: e.start_time,
+            })
+    }
+
+    /// Each calendar in the latest context snapshot by name, with its color
+    pub fn calendar_colors(&self) -> BTreeMap<String, String> {
+        let Some(snapshot) = self.significance_engine.last_snapshot() else {
+            return BTreeMap::new();
+        };
+        snapshot
+            .calendar_events
+            .iter()
+            .filter_map(|e| Some((e.calendar_name.clone()?, e.calendar_color.clone()?)))
+            .collect()
+    }
+
+    /// The next timed family event that hasn't started yet, sanitized for guest displays
+    pub fn guest_next_event(&self) -> Option<NextEvent> {
+        let now = Utc::now();
+        let snapshot = self.significance_engine.last_snapshot()?;
+        let calendars = self.config.read().family_digest.calendars.clone();
+        snapshot
+            .calendar_events
+            .iter()
+            .filter(|e| family_digest::is_family_event(e, &calendars))
+            .filter(|e| !e.is_all_day && e.start_time > now)
+            .min_by_key(|e| e.start_time)
+            .map(family_digest::sanitized)
+            .map(|e| NextEvent {
+                title: e.title,
+                location: e.location,
+                start_time: e.start_time,
+            })
+    }
+
+    /// The guest insight from the latest context snapshot, with the snapshot's
+    /// time (unix seconds) standing in for an insight ID; None before the first check
+    pub fn guest_insight(&self) -> Option<(i64, GuestInsight)> {
+        let snapshot = self.significance_engine.last_snapshot()?;
+        let calendars = self.config.read().family_digest.calendars.clone();
+        let (_, local_now) = self.get_time_of_day_phase();
+        let insight = guest::insight(
+            &snapshot.calendar_events,
+            &calendars,
+            snapshot.weather.as_ref(),
+            &local_now,
+        );
+        Some((snapshot.timestamp.timestamp(), insight))
+    }
+
+    /// Whether a caller must be treated as a guest display: a guest frontend
+    /// is registered under `frontend_id` or from `bus_name`. Fails closed.
+    pub fn is_guest(&self, frontend_id: Option<&str>, bus_name: Option<&str>) -> bool {
+        self.database
+            .is_guest_frontend(frontend_id, bus_name)
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Failed to look up frontend access, answering as guest: {}",
+                    e
+                );
+                true
+            })
+    }
+
+    /// Length budget for a frontend, in characters (None = unlimited)
+    pub fn frontend_length_budget(&self, frontend_id: &str) -> Option<usize> {
+        text_budget::budget_for(frontend_id, &self.config.read().frontends.max_length)
+    }
+
+    /// Items each context source contributed to an insight's prompt
+    pub fn get_insight_sources(&self, insight_id: i64) -> JasperResult<BTreeMap<String, u32>> {
+        self.database.get_insight_sources(insight_id)
+    }
+
+    /// (label, URI) of each thing an insight links to, in mention order
+    pub fn get_insight_actions(&self, insight_id: i64) -> JasperResult<Vec<(String, String)>> {
+        self.database.get_insight_actions(insight_id)
+    }
+
+    /// Get insight by ID
+    pub fn get_insight_by_id(&self, insight_id: i64) -> JasperResult<Option<Insight>> {
+        self.database.get_insight_by_id(insight_id)
+    }
+
+    /// Register a frontend as active
+    pub fn register_frontend(
+        &self,
+        frontend_id: &str,
+        pid: Option<i32>,
+        format: InsightFormat,
+        access: FrontendAccess,
+        bus_name: Option<&str>,
+    ) -> JasperResult<()> {
+        info!(
+            "Registering frontend: {} ({} format, {} access)",
+            frontend_id,
+            format.name(),
+            access.name()
+        );
+        if access == FrontendAccess::Guest {
+            // Send the new display the current guest insight on the next check
+            *self.guest_insight_sent.write() = None;
+        }
+        self.database
+            .register_frontend(frontend_id, pid, format.name(), access.name(), bus_name)
+    }
+
+    /// Unregister a frontend
+    pub fn unregister_frontend(&self, frontend_id: &str) -> JasperResult<()> {
+        info!("Unregistering frontend: {}", frontend_id);
+        self.database.unregister_frontend(frontend_id)
+    }
+
+    /// Update frontend heartbeat
+    pub fn update_frontend_heartbeat(&self, frontend_id: &str) -> JasperResult<()> {
+        self.database.update_frontend_heartbeat(frontend_id)
+    }
+
+    /// Reset significance engine (useful after cache clear)
+    #[allow(dead_code)]
+    pub fn reset_significance_engine(&self) {
+        info!("Resetting significance engine");
+        self.significance_engine.reset();
+    }
+}
+
+/// Stages reported by RefreshProgress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshStage {
+    /// Waiting for a scheduled check that was already running
+    Queued,
+    Collecting,
+    Analyzing,
+    Done,
+    Failed,
+}
+
+impl RefreshStage {
+    pub fn name(self) -> &'static str {
+        match self {
+            RefreshStage::Queued => "queued",
+            RefreshStage::Collecting => "collecting",
+            RefreshStage::Analyzing => "analyzing",
+            RefreshStage::Done => "done",
+            RefreshStage::Failed => "failed",
+        }
+    }
+}
+
+/// What triggered the insight generation
+#[derive(Debug, Clone)]
+enum InsightTrigger {
+    /// Regular time-of-day heartbeat (morning, afternoon, evening)
+    Heartbeat(String),
+    /// Detected context change from significance engine
+    ContextChange(Vec<SignificantChange>),
+    /// Once a month: reflect on the weekly load stats (the trigger text)
+    MonthlyReflection(String),
+}
+
+/// Simplified AI insight result
+#[derive(Debug, Clone)]
+struct AiInsight {
+    emoji: String,
+    text: String,
+    context_hash: String,
+    /// Morning digest returned in the same response, when one was requested
+    digest: Option<String>,
+    /// Model that answered; None for locally produced insights
+    model: Option<String>,
+    /// Answered by one of `ai.fallback_models`
+    fallback: bool,
+    /// Items each context source contributed to the prompt; empty for local insights
+    sources: BTreeMap<String, u32>,
+}
+
+/// Upcoming event for bar countdowns
+#[derive(Debug, Clone)]
+pub struct NextEvent {
+    pub title: String,
+    pub location: Option<String>,
+    pub start_time: DateTime<Utc>,
+}
+
+impl NextEvent {
+    /// "Standup in 12m", relative to `now`
+    pub fn countdown(&self, now: DateTime<Utc>) -> String {
+        let seconds = (self.start_time - now).num_seconds();
+        if seconds < 60 {
+            return format!("{} now", self.title);
+        }
+        let minutes = seconds / 60;
+        let left = match minutes {
+            m if m < 60 => format!("{}m", m),
+            m if m < 60 * 24 && m % 60 == 0 => format!("{}h", m / 60),
+            m if m < 60 * 24 => format!("{}h {}m", m / 60, m % 60),
+            m => format!("{}d", m / (60 * 24)),
+        };
+        format!("{} in {}", self.title, left)
+    }
+}
+
+/// Daemon status information
+#[derive(Debug)]
+pub struct DaemonStatus {
+    pub is_running: bool,
+    pub active_frontends: usize,
+    #[allow(dead_code)] // TODO: Track last analysis time
+    pub last_analysis: Option<DateTime<Utc>>,
+    pub insights_count: i64,
+}
+
+/// What `status --verbose` shows
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusDetails {
+    /// Last successful Google Calendar sync (None if not configured or not yet synced)
+    pub last_calendar_sync: Option<DateTime<Utc>>,
+    pub sources: Vec<SourceFetch>,
+    pub api_calls_remaining_today: u32,
+    pub frontends: Vec<ActiveFrontend>,
+    /// Seconds since the latest insight was generated
+    pub insight_age_seconds: Option<i64>,
+    /// When the significance engine's cooldown ends, while it is cooling down
+    pub cooldown_until: Option<DateTime<Utc>>,
+}
+
+impl SimplifiedDaemonCore {
+    /// Sync, sources, API budget, frontends and insight age in one place
+    pub async fn status_details(&self) -> JasperResult<StatusDetails> {
+        let frontends = self.database.get_active_frontends()?;
+        let insight_age_seconds = self
+            .database
+            .get_latest_insight()?
+            .map(|insight| (Utc::now() - insight.created_at).num_seconds().max(0));
+        let sources = self.context_manager.read().await.last_fetches();
+        Ok(StatusDetails {
+            last_calendar_sync: *self.last_calendar_sync.read(),
+            sources,
+            api_calls_remaining_today: self.api_manager.calls_remaining_today(),
+            frontends,
+            insight_age_seconds,
+            cooldown_until: self.significance_engine.cooldown_until(),
+        })
+    }
+
+    /// Get daemon status
+    pub async fn get_status(&self) -> JasperResult<DaemonStatus> {
+        let is_running = *self.is_running.read();
+        let active_frontends = self.database.get_active_frontends()?.len();
+
+        // Get insights count from database
+        let insights_count = 0; // TODO: Add method to count insights
+
+        Ok(DaemonStatus {
+            is_running,
+            active_frontends,
+            last_analysis: None, // TODO: Track last analysis time
+            insights_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::DatabaseInner;
+    use crate::demo::{seed_profile, Scenario};
+
+    #[tokio::test]
+    async fn test_full_loop_with_mock_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = seed_profile(Scenario::CrunchWeek, dir.path(), chrono::Local::now())
+            .await
+            .unwrap();
+        let database = DatabaseInner::new(&profile.data_dir.join("jasper.db"), None)
+            .await
+            .unwrap();
+
+        let mut config = Config::default();
+        config.ai.provider = mock_ai::PROVIDER.to_string();
+        config.ai.api_key = None;
+        let core = SimplifiedDaemonCore::new(
+            database.clone(),
+            ContextSourceManager::new(),
+            ApiManager::new(),
+            ConfigHandle::new(config),
+            None,
+            None,
+        );
+
+        let (request_id, started) = core.begin_refresh();
+        assert!(started);
+        assert_eq!(core.begin_refresh(), (request_id, false));
+        let insight_id = core.run_refresh(request_id).await.unwrap();
+        assert!(insight_id.is_some());
+        assert_eq!(core.begin_refresh(), (request_id + 1, true));
+        let insights = database.get_recent_insights(1).unwrap();
+        assert_eq!(insights.len(), 1, "mock provider should produce an insight");
+        assert!(!insights[0].insight.is_empty());
+        assert_eq!(insights[0].model.as_deref(), Some("claude-sonnet-4-6"));
+        assert!(!insights[0].fallback);
+
+        let details = core.status_details().await.unwrap();
+        assert!(details.insight_age_seconds.is_some());
+        assert!(details.last_calendar_sync.is_none());
+        assert!(details.frontends.is_empty());
+    }
+
+    #[test]
+    fn test_headless_delivery_has_no_desktop_notifier() {
+        let config = Config {
+            routing: toml::from_str("default = { notification = \"normal\" }").unwrap(),
+            ..Config::default()
+        };
+        assert!(Delivery::from_config(&config, false).notifier.is_some());
+        assert!(Delivery::from_config(&config, true).notifier.is_none());
+    }
+
+    #[test]
+    fn test_next_event_countdown() {
+        let now = Utc::now();
+        let event = |minutes| NextEvent {
+            title: "Standup".to_string(),
+            location: None,
+            start_time: now + chrono::Duration::minutes(minutes),
+        };
+        assert_eq!(event(0).countdown(now), "Standup now");
+        assert_eq!(event(12).countdown(now), "Standup in 12m");
+        assert_eq!(event(120).countdown(now), "Standup in 2h");
+        assert_eq!(event(95).countdown(now), "Standup in 1h 35m");
+        assert_eq!(event(3 * 24 * 60).countdown(now), "Standup in 3d");
+    }
+
+    #[test]
+    fn test_insight_age() {
+        let now = Utc::now();
+        assert_eq!(insight_age(now, now), "just now");
+        assert_eq!(
+            insight_age(now - chrono::Duration::minutes(12), now),
+            "12m ago"
+        );
+        assert_eq!(insight_age(now - chrono::Duration::hours(9), now), "9h ago");
+        assert_eq!(insight_age(now - chrono::Duration::days(2), now), "2d ago");
+    }
+
+    #[test]
+    fn test_freshness_flags_stale_insights() {
+        let now = Utc::now();
+        assert_eq!(freshness(now, now, 120), (0, false));
+        assert_eq!(
+            freshness(now - chrono::Duration::minutes(119), now, 120),
+            (119 * 60, false)
+        );
+        assert_eq!(
+            freshness(now - chrono::Duration::minutes(120), now, 120),
+            (120 * 60, true)
+        );
+        // Clock skew never yields a negative age
+        assert_eq!(
+            freshness(now + chrono::Duration::seconds(5), now, 120),
+            (0, false)
+        );
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        let base = Duration::from_secs(60);
+        assert_eq!(jittered(base, Duration::ZERO), base);
+        let jitter = Duration::from_secs(15);
+        let waits: Vec<Duration> = (0..200).map(|_| jittered(base, jitter)).collect();
+        assert!(waits
+            .iter()
+            .all(|w| *w >= base - jitter && *w <= base + jitter));
+        assert!(waits.iter().any(|w| *w != waits[0]));
+    }
+
+    #[test]
+    fn test_prompt_hash_ignores_internal_fields() {
+        let a = serde_json::json!({"model": "m", "system": "s", "_context_hash": "one"});
+        let b = serde_json::json!({"system": "s", "model": "m", "_context_hash": "two"});
+        let c = serde_json::json!({"model": "m", "system": "different"});
+        assert_eq!(
+            SimplifiedDaemonCore::prompt_hash(&a),
+            SimplifiedDaemonCore::prompt_hash(&b)
+        );
+        assert_ne!(
+            SimplifiedDaemonCore::prompt_hash(&a),
+            SimplifiedDaemonCore::prompt_hash(&c)
+        );
+    }
+}
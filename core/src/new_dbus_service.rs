@@ -0,0 +1,758 @@
+use crate::errors::JasperResult;
+use crate::guest::FrontendAccess;
+use crate::insight_format::InsightFormat;
+use crate::new_daemon_core::SimplifiedDaemonCore;
+use crate::text_budget;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+use zbus::message::Header;
+use zbus::{interface, Connection, ConnectionBuilder, SignalContext};
+
+/// Simplified D-Bus service for frontend communication
+pub struct SimplifiedDbusService {
+    daemon: Arc<RwLock<SimplifiedDaemonCore>>,
+}
+
+impl SimplifiedDbusService {
+    pub fn new(daemon: Arc<RwLock<SimplifiedDaemonCore>>) -> Self {
+        Self { daemon }
+    }
+
+    /// Start the D-Bus service
+    pub async fn start(daemon: Arc<RwLock<SimplifiedDaemonCore>>) -> JasperResult<()> {
+        let service = SimplifiedDbusService::new(daemon);
+
+        let _connection = ConnectionBuilder::session()
+            .unwrap()
+            .name("org.jasper.Daemon")?
+            .serve_at("/org/jasper/Daemon", service)?
+            .build()
+            .await?;
+
+        info!("D-Bus service started at org.jasper.Daemon");
+
+        // Keep the service running
+        std::future::pending::<()>().await;
+        Ok(())
+    }
+
+    /// Whether the caller is a guest display, by its bus connection or the
+    /// frontend ID it gives
+    async fn is_guest(&self, hdr: &Header<'_>, frontend_id: Option<&str>) -> bool {
+        let sender = hdr.sender().map(|s| s.to_string());
+        self.daemon
+            .read()
+            .await
+            .is_guest(frontend_id, sender.as_deref())
+    }
+
+    async fn latest_insight(&self) -> (i64, String, String, String) {
+        match self.daemon.read().await.get_latest_insight() {
+            Ok(Some(insight)) => (
+                insight.id,
+                insight.emoji,
+                insight.insight,
+                insight.context_hash.unwrap_or_default(),
+            ),
+            Ok(None) => no_insight(),
+            Err(e) => {
+                warn!("Failed to get latest insight: {}", e);
+                (
+                    0,
+                    "⚠️".to_string(),
+                    "Error retrieving insights".to_string(),
+                    "".to_string(),
+                )
+            }
+        }
+    }
+
+    /// The family-safe insight every insight method gives guest displays
+    async fn guest_insight(&self) -> (i64, String, String, String) {
+        match self.daemon.read().await.guest_insight() {
+            Some((id, insight)) => {
+                let hash = insight.hash();
+                (id, insight.emoji, insight.text, hash)
+            }
+            None => no_insight(),
+        }
+    }
+
+    /// Record a frontend registration along with the caller's bus name. A
+    /// guest display can't register itself back to full access while its
+    /// guest registration stands; it has to unregister first.
+    async fn register(
+        &self,
+        frontend_id: String,
+        pid: i32,
+        format: String,
+        access: FrontendAccess,
+        hdr: &Header<'_>,
+    ) -> bool {
+        let pid_option = if pid > 0 { Some(pid) } else { None };
+        let Some(format) = InsightFormat::from_name(&format) else {
+            warn!(
+                "Frontend {} asked for unknown insight format '{}'",
+                frontend_id, format
+            );
+            return false;
+        };
+        if access == FrontendAccess::Full && self.is_guest(hdr, Some(&frontend_id)).await {
+            warn!(
+                "Guest frontend {} asked for full access; still a guest",
+                frontend_id
+            );
+            return false;
+        }
+        let sender = hdr.sender().map(|s| s.to_string());
+
+        match self.daemon.read().await.register_frontend(
+            &frontend_id,
+            pid_option,
+            format,
+            access,
+            sender.as_deref(),
+        ) {
+            Ok(()) => {
+                info!(
+                    "Frontend registered: {} (PID: {:?}, {} access)",
+                    frontend_id,
+                    pid_option,
+                    access.name()
+                );
+                true
+            }
+            Err(e) => {
+                error!("Failed to register frontend {}: {}", frontend_id, e);
+                false
+            }
+        }
+    }
+}
+
+fn no_insight() -> (i64, String, String, String) {
+    (
+        0,
+        "🔍".to_string(),
+        "No insights available".to_string(),
+        "".to_string(),
+    )
+}
+
+#[interface(name = "org.jasper.Daemon1")]
+impl SimplifiedDbusService {
+    /// Get the latest insight (the guest insight for guest displays)
+    async fn get_latest_insight(
+        &self,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> (i64, String, String, String) {
+        if self.is_guest(&hdr, None).await {
+            return self.guest_insight().await;
+        }
+        self.latest_insight().await
+    }
+
+    /// Get the latest insight shortened to the calling frontend's length budget
+    async fn get_insight_for_frontend(
+        &self,
+        frontend_id: String,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> (i64, String, String, String) {
+        let budget = self
+            .daemon
+            .read()
+            .await
+            .frontend_length_budget(&frontend_id);
+        let (id, emoji, insight, context_hash) = if self.is_guest(&hdr, Some(&frontend_id)).await {
+            self.guest_insight().await
+        } else {
+            self.latest_insight().await
+        };
+        (id, emoji, text_budget::fit(&insight, budget), context_hash)
+    }
+
+    /// Freshness of the latest insight as (age_seconds, stale), stale once it
+    /// is older than `frontends.stale_after_minutes`; age -1 if there is none
+    async fn get_insight_freshness(&self, #[zbus(header)] hdr: Header<'_>) -> (i64, bool) {
+        let guest = self.is_guest(&hdr, None).await;
+        match self.daemon.read().await.latest_freshness(guest) {
+            Ok(Some(freshness)) => freshness,
+            Ok(None) => (-1, false),
+            Err(e) => {
+                warn!("Failed to get insight freshness: {}", e);
+                (-1, false)
+            }
+        }
+    }
+
+    /// Values for terminal template placeholders (age, stale, next_event, tasks).
+    /// Guest displays get no age or tasks, and only family events.
+    async fn get_template_fields(
+        &self,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> std::collections::HashMap<String, String> {
+        let guest = self.is_guest(&hdr, None).await;
+        match self.daemon.read().await.template_fields(guest) {
+            Ok(fields) => fields.into_iter().collect(),
+            Err(e) => {
+                warn!("Failed to build template fields: {}", e);
+                std::collections::HashMap::new()
+            }
+        }
+    }
+
+    /// Next upcoming event as (title, location, starts_at unix seconds,
+    /// starts_in_seconds, countdown like "Standup in 12m"); empty title if none.
+    /// Bars can tick the countdown themselves from starts_at. Guest displays
+    /// only see family events.
+    async fn get_next_event(
+        &self,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> (String, String, i64, i64, String) {
+        let next = if self.is_guest(&hdr, None).await {
+            self.daemon.read().await.guest_next_event()
+        } else {
+            self.daemon.read().await.next_event()
+        };
+        match next {
+            Some(event) => {
+                let now = chrono::Utc::now();
+                (
+                    event.title.clone(),
+                    event.location.clone().unwrap_or_default(),
+                    event.start_time.timestamp(),
+                    (event.start_time - now).num_seconds(),
+                    event.countdown(now),
+                )
+            }
+            None => (String::new(), String::new(), 0, 0, String::new()),
+        }
+    }
+
+    /// Get the latest morning digest as (id, content, created_at unix seconds);
+    /// id 0 if none, and always for guest displays
+    async fn get_latest_digest(&self, #[zbus(header)] hdr: Header<'_>) -> (i64, String, i64) {
+        if self.is_guest(&hdr, None).await {
+            return (0, String::new(), 0);
+        }
+        match self.daemon.read().await.get_latest_digest() {
+            Ok(Some(digest)) => (digest.id, digest.content, digest.created_at.timestamp()),
+            Ok(None) => (0, String::new(), 0),
+            Err(e) => {
+                warn!("Failed to get latest digest: {}", e);
+                (0, String::new(), 0)
+            }
+        }
+    }
+
+    /// Get insight by ID (the guest insight for guest displays)
+    async fn get_insight_by_id(
+        &self,
+        insight_id: i64,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> (i64, String, String, String) {
+        if self.is_guest(&hdr, None).await {
+            return self.guest_insight().await;
+        }
+        match self.daemon.read().await.get_insight_by_id(insight_id) {
+            Ok(Some(insight)) => (
+                insight.id,
+                insight.emoji,
+                insight.insight,
+                insight.context_hash.unwrap_or_default(),
+            ),
+            Ok(None) => (
+                0,
+                "❓".to_string(),
+                "Insight not found".to_string(),
+                "".to_string(),
+            ),
+            Err(e) => {
+                warn!("Failed to get insight by ID {}: {}", insight_id, e);
+                (
+                    0,
+                    "⚠️".to_string(),
+                    "Error retrieving insight".to_string(),
+                    "".to_string(),
+                )
+            }
+        }
+    }
+
+    /// Items each context source ("calendar", "tasks", "weather", "notes",
+    /// "daylight") contributed to an insight's prompt; empty for local
+    /// insights, and always for guest displays
+    async fn get_insight_sources(
+        &self,
+        insight_id: i64,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> HashMap<String, u32> {
+        if self.is_guest(&hdr, None).await {
+            return HashMap::new();
+        }
+        match self.daemon.read().await.get_insight_sources(insight_id) {
+            Ok(sources) => sources.into_iter().collect(),
+            Err(e) => {
+                warn!("Failed to get sources for insight {}: {}", insight_id, e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// (label, URI) pairs for what an insight mentions: the calendar event in
+    /// Google Calendar, the Todoist task, the Obsidian note. The first is the
+    /// natural click action. Always empty for guest displays.
+    async fn get_insight_actions(
+        &self,
+        insight_id: i64,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> Vec<(String, String)> {
+        if self.is_guest(&hdr, None).await {
+            return Vec::new();
+        }
+        match self.daemon.read().await.get_insight_actions(insight_id) {
+            Ok(actions) => actions,
+            Err(e) => {
+                warn!("Failed to get actions for insight {}: {}", insight_id, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Register a frontend as active (plain text insights)
+    async fn register_frontend(
+        &self,
+        frontend_id: String,
+        pid: i32,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> bool {
+        self.register(
+            frontend_id,
+            pid,
+            "plain".to_string(),
+            FrontendAccess::Full,
+            &hdr,
+        )
+        .await
+    }
+
+    /// Register a frontend along with the markup it displays: "plain", "pango",
+    /// "markdown" or "html". InsightUpdated then carries a payload in that format.
+    async fn register_frontend_with_format(
+        &self,
+        frontend_id: String,
+        pid: i32,
+        format: String,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> bool {
+        self.register(frontend_id, pid, format, FrontendAccess::Full, &hdr)
+            .await
+    }
+
+    /// Register a shared display (kitchen tablet, kiosk) as a guest. Every
+    /// insight method called from its connection or under its ID then answers
+    /// with the family-safe guest insight, and GuestInsightUpdated signals it.
+    async fn register_guest_frontend(
+        &self,
+        frontend_id: String,
+        pid: i32,
+        format: String,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> bool {
+        self.register(frontend_id, pid, format, FrontendAccess::Guest, &hdr)
+            .await
+    }
+
+    /// Unregister a frontend
+    async fn unregister_frontend(&self, frontend_id: String) -> bool {
+        match self.daemon.read().await.unregister_frontend(&frontend_id) {
+            Ok(()) => {
+                info!("Frontend unregistered: {}", frontend_id);
+                true
+            }
+            Err(e) => {
+                error!("Failed to unregister frontend {}: {}", frontend_id, e);
+                false
+            }
+        }
+    }
+
+    /// Update frontend heartbeat
+    async fn heartbeat(&self, frontend_id: String) -> bool {
+        match self
+            .daemon
+            .read()
+            .await
+            .update_frontend_heartbeat(&frontend_id)
+        {
+            Ok(()) => {
+                debug!("Heartbeat updated for frontend: {}", frontend_id);
+                true
+            }
+            Err(e) => {
+                warn!("Failed to update heartbeat for {}: {}", frontend_id, e);
+                false
+            }
+        }
+    }
+
+    /// Start an immediate context refresh and analysis; returns a request ID
+    /// that RefreshProgress signals carry. While one refresh is running, further
+    /// calls return its ID instead of starting another.
+    async fn force_refresh(&self) -> u64 {
+        let (request_id, started) = self.daemon.read().await.begin_refresh();
+        if started {
+            let daemon = Arc::clone(&self.daemon);
+            tokio::spawn(async move {
+                match daemon.read().await.run_refresh(request_id).await {
+                    Ok(_) => info!("Forced context refresh {} completed", request_id),
+                    Err(e) => error!("Forced context refresh {} failed: {}", request_id, e),
+                }
+            });
+        }
+        request_id
+    }
+
+    /// Hold scheduled insights for `minutes` (0 resumes them); ForceRefresh
+    /// still runs. Returns the Unix time the snooze ends, 0 when not snoozed.
+    async fn snooze(&self, minutes: u32) -> i64 {
+        self.daemon
+            .read()
+            .await
+            .snooze(minutes)
+            .map_or(0, |until| until.timestamp())
+    }
+
+    /// Unix time the current snooze ends, 0 when insights aren't snoozed
+    async fn get_snoozed_until(&self) -> i64 {
+        self.daemon
+            .read()
+            .await
+            .snoozed_until()
+            .map_or(0, |until| until.timestamp())
+    }
+
+    /// Include `json` from `source_id` (e.g. "deploy", "family") in every
+    /// analysis for `ttl` seconds; injecting again under the same ID replaces
+    /// it, and a `ttl` of 0 withdraws it. Returns the Unix time it expires,
+    /// 0 when withdrawn or refused. Guest displays can't inject.
+    async fn inject_context(
+        &self,
+        #[zbus(header)] hdr: Header<'_>,
+        source_id: String,
+        json: String,
+        ttl: u32,
+    ) -> i64 {
+        if self.is_guest(&hdr, None).await {
+            return 0;
+        }
+        match self
+            .daemon
+            .read()
+            .await
+            .inject_context(&source_id, &json, ttl)
+        {
+            Ok(expires_at) => expires_at.map_or(0, |at| at.timestamp()),
+            Err(e) => {
+                warn!("Refused context from {}: {}", source_id, e);
+                0
+            }
+        }
+    }
+
+    /// (title, Unix start time, join URL) of the next video call, or the one
+    /// under way; empty when there is none, and always for guest displays
+    async fn get_next_meeting(&self, #[zbus(header)] hdr: Header<'_>) -> (String, i64, String) {
+        if self.is_guest(&hdr, None).await {
+            return (String::new(), 0, String::new());
+        }
+        match self.daemon.read().await.next_meeting().await {
+            Ok(Some(meeting)) => (meeting.title, meeting.start_time, meeting.url),
+            Ok(None) => (String::new(), 0, String::new()),
+            Err(e) => {
+                warn!("Failed to get the next meeting: {}", e);
+                (String::new(), 0, String::new())
+            }
+        }
+    }
+
+    /// Open the next video call; returns its join URL, empty when there was
+    /// none to join. Guest displays can't join.
+    async fn join_next_meeting(&self, #[zbus(header)] hdr: Header<'_>) -> String {
+        if self.is_guest(&hdr, None).await {
+            return String::new();
+        }
+        match self.daemon.read().await.join_next_meeting().await {
+            Ok(meeting) => meeting.map(|m| m.url).unwrap_or_default(),
+            Err(e) => {
+                warn!("Failed to join the next meeting: {}", e);
+                String::new()
+            }
+        }
+    }
+
+    /// Answer an upcoming invitation, named by event ID or part of its
+    /// title, with "accept", "decline" or "tentative". Returns the title of
+    /// the event answered; empty when nothing was, and always for guest displays.
+    async fn rsvp(
+        &self,
+        #[zbus(header)] hdr: Header<'_>,
+        event: String,
+        response: String,
+    ) -> String {
+        if self.is_guest(&hdr, None).await {
+            return String::new();
+        }
+        let Some(response) = crate::invitations::Response::parse(&response) else {
+            warn!("Unknown invitation response: {}", response);
+            return String::new();
+        };
+        match self.daemon.read().await.rsvp(&event, response).await {
+            Ok(invitation) => invitation.title,
+            Err(e) => {
+                warn!("Failed to answer invitation {}: {}", event, e);
+                String::new()
+            }
+        }
+    }
+
+    /// Calendar name to color (e.g. "#9fe1e7") for every calendar with
+    /// events in view, for per-calendar styling; empty for guest displays
+    async fn get_calendar_colors(
+        &self,
+        #[zbus(header)] hdr: Header<'_>,
+    ) -> std::collections::HashMap<String, String> {
+        if self.is_guest(&hdr, None).await {
+            return std::collections::HashMap::new();
+        }
+        self.daemon
+            .read()
+            .await
+            .calendar_colors()
+            .into_iter()
+            .collect()
+    }
+
+    /// Get daemon status
+    async fn get_status(&self) -> (bool, u32, i64) {
+        match self.daemon.read().await.get_status().await {
+            Ok(status) => (
+                status.is_running,
+                status.active_frontends as u32,
+                status.insights_count,
+            ),
+            Err(e) => {
+                error!("Failed to get daemon status: {}", e);
+                (false, 0, 0)
+            }
+        }
+    }
+
+    /// `status --verbose` as JSON (see `StatusDetails`); empty for guest displays
+    async fn get_status_details(&self, #[zbus(header)] hdr: Header<'_>) -> String {
+        if self.is_guest(&hdr, None).await {
+            return String::new();
+        }
+        match self.daemon.read().await.status_details().await {
+            Ok(details) => serde_json::to_string(&details).unwrap_or_default(),
+            Err(e) => {
+                error!("Failed to get status details: {}", e);
+                String::new()
+            }
+        }
+    }
+
+    /// Daemon version
+    #[zbus(property)]
+    async fn version(&self) -> String {
+        crate::update_check::CURRENT_VERSION.to_string()
+    }
+
+    /// Newer release version if the (opt-in) update check found one, else empty
+    #[zbus(property)]
+    async fn update_available(&self) -> String {
+        self.daemon
+            .read()
+            .await
+            .available_update()
+            .unwrap_or_default()
+    }
+
+    /// Model that generated the latest insight; empty for locally produced ones
+    #[zbus(property)]
+    async fn latest_insight_model(&self) -> String {
+        match self.daemon.read().await.get_latest_insight() {
+            Ok(Some(insight)) => insight.model.unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    /// Whether the latest insight came from one of `ai.fallback_models`
+    #[zbus(property)]
+    async fn latest_insight_fallback(&self) -> bool {
+        matches!(
+            self.daemon.read().await.get_latest_insight(),
+            Ok(Some(insight)) if insight.fallback
+        )
+    }
+
+    // TODO: Add signal methods
+    // These would be called by the daemon when new insights are available
+
+    /// Signal emitted when a new insight is available. `formatted` maps each
+    /// format requested by a registered frontend (and "plain") to a ready-to-show payload.
+    /// `age_seconds` is how long ago it was generated and `stale` whether that
+    /// is past `frontends.stale_after_minutes`; poll GetInsightFreshness to
+    /// notice it going stale later. `actions` are the insight's (label, URI)
+    /// links, as returned by GetInsightActions.
+    #[zbus(signal)]
+    #[allow(clippy::too_many_arguments)]
+    async fn insight_updated(
+        signal_ctxt: &SignalContext<'_>,
+        insight_id: i64,
+        emoji: String,
+        preview: String,
+        formatted: HashMap<String, String>,
+        age_seconds: i64,
+        stale: bool,
+        actions: Vec<(String, String)>,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when the family-safe guest insight changes while a guest
+    /// display is registered
+    #[zbus(signal)]
+    async fn guest_insight_updated(
+        signal_ctxt: &SignalContext<'_>,
+        emoji: String,
+        text: String,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted as a ForceRefresh request progresses. `stage` is "queued",
+    /// "collecting", "analyzing", then "done" or "failed"; `detail` says which
+    /// insight was generated, why none was, or why the refresh failed.
+    #[zbus(signal)]
+    async fn refresh_progress(
+        signal_ctxt: &SignalContext<'_>,
+        request_id: u64,
+        stage: String,
+        detail: String,
+    ) -> zbus::Result<()>;
+
+    /// Signal emitted when daemon is stopping
+    #[zbus(signal)]
+    async fn daemon_stopping(signal_ctxt: &SignalContext<'_>) -> zbus::Result<()>;
+}
+
+/// Helper struct to emit signals from the daemon
+pub struct DbusSignalEmitter {
+    connection: Connection,
+}
+
+impl DbusSignalEmitter {
+    pub async fn new() -> JasperResult<Self> {
+        let connection = Connection::session().await?;
+        Ok(Self { connection })
+    }
+
+    /// Emit insight updated signal
+    #[allow(clippy::too_many_arguments)]
+    pub async fn emit_insight_updated(
+        &self,
+        insight_id: i64,
+        emoji: &str,
+        preview: &str,
+        formatted: &HashMap<String, String>,
+        age_seconds: i64,
+        stale: bool,
+        actions: &[(String, String)],
+    ) -> JasperResult<()> {
+        let object_path = "/org/jasper/Daemon";
+        let interface_name = "org.jasper.Daemon1";
+
+        self.connection
+            .emit_signal(
+                None::<&str>,
+                object_path,
+                interface_name,
+                "InsightUpdated",
+                &(
+                    insight_id,
+                    emoji,
+                    preview,
+                    formatted,
+                    age_seconds,
+                    stale,
+                    actions,
+                ),
+            )
+            .await?;
+
+        debug!("Emitted InsightUpdated signal for insight {}", insight_id);
+        Ok(())
+    }
+
+    /// Emit guest insight updated signal
+    pub async fn emit_guest_insight_updated(&self, emoji: &str, text: &str) -> JasperResult<()> {
+        let object_path = "/org/jasper/Daemon";
+        let interface_name = "org.jasper.Daemon1";
+
+        self.connection
+            .emit_signal(
+                None::<&str>,
+                object_path,
+                interface_name,
+                "GuestInsightUpdated",
+                &(emoji, text),
+            )
+            .await?;
+
+        debug!("Emitted GuestInsightUpdated signal");
+        Ok(())
+    }
+
+    /// Emit refresh progress signal
+    pub async fn emit_refresh_progress(
+        &self,
+        request_id: u64,
+        stage: &str,
+        detail: &str,
+    ) -> JasperResult<()> {
+        let object_path = "/org/jasper/Daemon";
+        let interface_name = "org.jasper.Daemon1";
+
+        self.connection
+            .emit_signal(
+                None::<&str>,
+                object_path,
+                interface_name,
+                "RefreshProgress",
+                &(request_id, stage, detail),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Emit daemon stopping signal (available for graceful shutdown)
+    #[allow(dead_code)]
+    pub async fn emit_daemon_stopping(&self) -> JasperResult<()> {
+        let object_path = "/org/jasper/Daemon";
+        let interface_name = "org.jasper.Daemon1";
+
+        self.connection
+            .emit_signal(
+                None::<&str>,
+                object_path,
+                interface_name,
+                "DaemonStopping",
+                &(),
+            )
+            .await?;
+
+        info!("Emitted DaemonStopping signal");
+        Ok(())
+    }
+}
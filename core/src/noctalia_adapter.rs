@@ -0,0 +1,246 @@
+use crate::accessibility;
+use crate::config::Config;
+use crate::glyphs::GlyphMapper;
+use crate::text_budget;
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+use zbus::{proxy, Connection};
+
+/// How long `refresh_and_get` waits for the daemon to finish a refresh
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Noctalia adapter — connects to Jasper daemon via D-Bus and outputs
+/// JSON with separate fields for the Noctalia bar widget plugin.
+///
+/// Unlike the Waybar adapter, this does NOT unregister on exit.
+/// The "noctalia" frontend stays registered so the daemon knows a
+/// display is active between polls. The daemon auto-expires frontends
+/// after 60 s of missed heartbeats.
+
+#[proxy(
+    interface = "org.jasper.Daemon1",
+    default_service = "org.jasper.Daemon",
+    default_path = "/org/jasper/Daemon"
+)]
+trait JasperDaemon {
+    async fn get_latest_insight(&self) -> zbus::Result<(i64, String, String, String)>;
+    async fn register_frontend(&self, frontend_id: String, pid: i32) -> zbus::Result<bool>;
+    async fn heartbeat(&self, frontend_id: String) -> zbus::Result<bool>;
+    async fn force_refresh(&self) -> zbus::Result<u64>;
+    async fn get_status(&self) -> zbus::Result<(bool, u32, i64)>;
+    async fn get_insight_freshness(&self) -> zbus::Result<(i64, bool)>;
+
+    #[zbus(signal)]
+    fn refresh_progress(&self, request_id: u64, stage: String, detail: String) -> zbus::Result<()>;
+}
+
+pub struct NoctaliaAdapter {
+    proxy: Option<JasperDaemonProxy<'static>>,
+    /// Screen-reader friendly output: empty emoji, insight as a full sentence
+    accessible: bool,
+    glyphs: GlyphMapper,
+    /// Maximum insight length in characters (None = unlimited)
+    max_length: Option<usize>,
+}
+
+impl NoctaliaAdapter {
+    pub async fn new() -> Self {
+        Self {
+            proxy: None,
+            accessible: false,
+            glyphs: GlyphMapper::disabled(),
+            max_length: text_budget::default_budget("noctalia"),
+        }
+    }
+
+    /// Enable or disable accessible (plain text) output
+    pub fn with_accessible(mut self, accessible: bool) -> Self {
+        self.accessible = accessible;
+        self
+    }
+
+    /// Map insight emoji to bar glyphs before display
+    pub fn with_glyphs(mut self, glyphs: GlyphMapper) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Override the insight length budget
+    pub fn with_max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Connect to daemon, register (idempotent) and send heartbeat.
+    pub async fn connect(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let connection = Connection::session().await?;
+        let proxy = JasperDaemonProxy::new(&connection).await?;
+
+        // Register — the daemon treats duplicate registrations as no-ops.
+        // Each poll is a short-lived process, so no PID: liveness comes from heartbeats.
+        let ok = proxy.register_frontend("noctalia".into(), 0).await?;
+        if !ok {
+            error!("Failed to register noctalia frontend");
+            return Err("Registration failed".into());
+        }
+
+        // Heartbeat keeps the frontend alive between polls
+        let _ = proxy.heartbeat("noctalia".into()).await;
+
+        self.proxy = Some(proxy);
+        debug!("Noctalia adapter connected");
+        Ok(())
+    }
+
+    /// Get latest insight as Noctalia-friendly JSON.
+    pub async fn get_output(&self) -> Value {
+        let Some(ref proxy) = self.proxy else {
+            return Self::make_output(0, "", "", "error");
+        };
+
+        match proxy.get_latest_insight().await {
+            Ok((id, emoji, insight, _hash)) => {
+                let state = if id > 0 { "active" } else { "waiting" };
+                let insight = text_budget::fit(&insight, self.max_length);
+                let mut output = if self.accessible {
+                    let sentence = accessibility::plain_insight(&emoji, &insight);
+                    Self::make_output(id.max(0), "", &sentence, state)
+                } else {
+                    Self::make_output(id.max(0), &self.glyphs.map(&emoji), &insight, state)
+                };
+                if id > 0 {
+                    // Older daemons lack GetInsightFreshness; treat as fresh
+                    let (age_seconds, stale) =
+                        proxy.get_insight_freshness().await.unwrap_or((-1, false));
+                    output["age_seconds"] = json!(age_seconds);
+                    output["stale"] = json!(stale);
+                }
+                output
+            }
+            Err(e) => {
+                error!("D-Bus GetLatestInsight failed: {}", e);
+                Self::make_output(0, "", "", "error")
+            }
+        }
+    }
+
+    /// Trigger a forced refresh, then return the (possibly new) insight.
+    pub async fn refresh_and_get(&self) -> Value {
+        let Some(ref proxy) = self.proxy else {
+            return Self::make_output(0, "", "", "error");
+        };
+
+        // Subscribe first so the final stage can't be missed
+        let mut progress = match proxy.receive_refresh_progress().await {
+            Ok(progress) => progress,
+            Err(e) => {
+                error!("Failed to subscribe to RefreshProgress: {}", e);
+                return self.get_output().await;
+            }
+        };
+        let request_id = match proxy.force_refresh().await {
+            Ok(id) => id,
+            Err(e) => {
+                error!("Force refresh failed: {}", e);
+                return self.get_output().await;
+            }
+        };
+
+        let finished = async {
+            while let Some(signal) = progress.next().await {
+                let Ok(args) = signal.args() else { continue };
+                if args.request_id == request_id && matches!(args.stage.as_str(), "done" | "failed")
+                {
+                    return Some((args.stage, args.detail));
+                }
+            }
+            None
+        };
+        match tokio::time::timeout(REFRESH_TIMEOUT, finished).await {
+            Ok(Some((stage, detail))) if stage == "done" => {
+                info!("Force refresh completed: {}", detail)
+            }
+            Ok(Some((_, detail))) => error!("Force refresh failed: {}", detail),
+            Ok(None) => warn!("RefreshProgress stream ended early"),
+            Err(_) => warn!("Force refresh still running after {:?}", REFRESH_TIMEOUT),
+        }
+
+        self.get_output().await
+    }
+
+    fn make_output(id: i64, emoji: &str, insight: &str, state: &str) -> Value {
+        json!({
+            "id": id,
+            "emoji": emoji,
+            "insight": insight,
+            "state": state,
+            "age_seconds": -1,
+            "stale": false
+        })
+    }
+}
+
+// ── Public entry points called from main.rs ────────────────────────
+
+/// `jasper-companion-daemon noctalia`: the JSON line to print
+pub async fn run_noctalia_mode(accessible: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let frontends = Config::load_frontends();
+    let mut adapter = NoctaliaAdapter::new()
+        .await
+        .with_accessible(accessible)
+        .with_glyphs(GlyphMapper::from_config(&frontends.glyphs))
+        .with_max_length(text_budget::budget_for("noctalia", &frontends.max_length));
+
+    let output = match adapter.connect().await {
+        Ok(()) => adapter.get_output().await,
+        Err(_) => NoctaliaAdapter::make_output(0, "", "", "offline"),
+    };
+
+    Ok(serde_json::to_string(&output)?)
+}
+
+/// `jasper-companion-daemon noctalia-refresh`: the JSON line to print
+pub async fn run_noctalia_refresh(accessible: bool) -> Result<String, Box<dyn std::error::Error>> {
+    let frontends = Config::load_frontends();
+    let mut adapter = NoctaliaAdapter::new()
+        .await
+        .with_accessible(accessible)
+        .with_glyphs(GlyphMapper::from_config(&frontends.glyphs))
+        .with_max_length(text_budget::budget_for("noctalia", &frontends.max_length));
+
+    let output = match adapter.connect().await {
+        Ok(()) => adapter.refresh_and_get().await,
+        Err(_) => NoctaliaAdapter::make_output(0, "", "", "offline"),
+    };
+
+    Ok(serde_json::to_string(&output)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_adapter_creation() {
+        let adapter = NoctaliaAdapter::new().await;
+        assert!(adapter.proxy.is_none());
+    }
+
+    #[test]
+    fn test_make_output() {
+        let out = NoctaliaAdapter::make_output(42, "🎯", "Big meeting at 3 PM", "active");
+        assert_eq!(out["id"], 42);
+        assert_eq!(out["emoji"], "🎯");
+        assert_eq!(out["insight"], "Big meeting at 3 PM");
+        assert_eq!(out["state"], "active");
+    }
+
+    #[test]
+    fn test_offline_output() {
+        let out = NoctaliaAdapter::make_output(0, "", "", "offline");
+        assert_eq!(out["state"], "offline");
+        assert_eq!(out["id"], 0);
+    }
+}
@@ -0,0 +1,403 @@
+//! Desktop notifications straight to `org.freedesktop.Notifications`, tuned
+//! to whichever notification daemon answers.
+//!
+//! notify-send can neither update a notification in place nor take a reply,
+//! so on Linux the daemon talks to the notification server itself. The
+//! server's name (mako, dunst, SwayNC, GNOME Shell, Plasma) and advertised
+//! capabilities decide whether bodies go out as escaped Pango markup, which
+//! hint makes a new insight replace the one on screen instead of stacking,
+//! and whether a Reply field is offered. Without a reachable server it falls
+//! back to notify-send.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use tracing::{debug, info};
+use zbus::zvariant::Value;
+
+use crate::errors::JasperResult;
+use crate::insight_format::InsightFormat;
+use crate::platform::{self, Notifier, NotifySend, Urgency};
+
+const DESTINATION: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+const INTERFACE: &str = "org.freedesktop.Notifications";
+/// Stack tag shared by every insight notification
+const INSIGHT_TAG: &str = "jasper-insight";
+
+/// Notification daemons with behavior worth tuning for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationServer {
+    Mako,
+    Dunst,
+    SwayNc,
+    GnomeShell,
+    Plasma,
+    Other,
+}
+
+impl NotificationServer {
+    /// From the name GetServerInformation reports
+    pub fn from_name(name: &str) -> Self {
+        match name.to_lowercase().as_str() {
+            "mako" => NotificationServer::Mako,
+            "dunst" => NotificationServer::Dunst,
+            "swaync" | "swaynotificationcenter" => NotificationServer::SwayNc,
+            "gnome-shell" => NotificationServer::GnomeShell,
+            "plasma" => NotificationServer::Plasma,
+            _ => NotificationServer::Other,
+        }
+    }
+}
+
+/// How insight notifications are sent to a particular server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tuning {
+    /// The server renders body markup, so bodies must be escaped
+    pub markup: bool,
+    /// Offer a Reply field on insight notifications
+    pub inline_reply: bool,
+    /// Attach the click action to insights that link somewhere
+    pub actions: bool,
+    /// Hint that replaces the previous insight even across daemon restarts,
+    /// when replaces_id no longer knows it
+    pub stack_hint: Option<&'static str>,
+}
+
+impl Tuning {
+    pub fn new(server: NotificationServer, capabilities: &[String]) -> Self {
+        let has = |capability: &str| capabilities.iter().any(|c| c == capability);
+        Self {
+            markup: has("body-markup"),
+            // SwayNC and Plasma advertise it; mako, dunst and GNOME Shell don't
+            inline_reply: has("inline-reply"),
+            actions: has("actions"),
+            stack_hint: match server {
+                NotificationServer::Dunst => Some("x-dunst-stack-tag"),
+                NotificationServer::Mako | NotificationServer::SwayNc => {
+                    Some("x-canonical-private-synchronous")
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+struct Session {
+    connection: zbus::blocking::Connection,
+    tuning: Tuning,
+}
+
+pub struct FreedesktopNotifier {
+    /// Connected on first use; None when no notification server answered
+    session: OnceLock<Option<Session>>,
+    /// ID of the insight notification on screen, and how many insights
+    /// have been shown (a newer one supersedes any that is waiting)
+    current: Mutex<(u32, u64)>,
+}
+
+impl Default for FreedesktopNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FreedesktopNotifier {
+    pub fn new() -> Self {
+        Self {
+            session: OnceLock::new(),
+            current: Mutex::new((0, 0)),
+        }
+    }
+
+    fn session(&self) -> Option<&Session> {
+        self.session
+            .get_or_init(|| match Self::connect() {
+                Ok(session) => Some(session),
+                Err(e) => {
+                    info!("No notification server on D-Bus ({}); using notify-send", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
+    fn connect() -> JasperResult<Session> {
+        let connection = zbus::blocking::Connection::session()?;
+        let (name, vendor, version, _spec): (String, String, String, String) = connection
+            .call_method(
+                Some(DESTINATION),
+                PATH,
+                Some(INTERFACE),
+                "GetServerInformation",
+                &(),
+            )?
+            .body()
+            .deserialize()?;
+        let capabilities: Vec<String> = connection
+            .call_method(
+                Some(DESTINATION),
+                PATH,
+                Some(INTERFACE),
+                "GetCapabilities",
+                &(),
+            )?
+            .body()
+            .deserialize()?;
+        let tuning = Tuning::new(NotificationServer::from_name(&name), &capabilities);
+        info!(
+            "Notification server: {} {} ({}), {:?}",
+            name, version, vendor, tuning
+        );
+        Ok(Session { connection, tuning })
+    }
+
+    fn send(
+        session: &Session,
+        title: &str,
+        body: &str,
+        urgency: Urgency,
+        replaces_id: u32,
+        actions: &[&str],
+        insight: bool,
+    ) -> JasperResult<u32> {
+        let body = if session.tuning.markup {
+            InsightFormat::Pango.escape(body)
+        } else {
+            body.to_string()
+        };
+        let mut hints: HashMap<&str, Value> = HashMap::new();
+        let level: u8 = match urgency {
+            Urgency::Normal => 1,
+            Urgency::Critical => 2,
+        };
+        hints.insert("urgency", level.into());
+        if let Some(hint) = session.tuning.stack_hint.filter(|_| insight) {
+            hints.insert(hint, INSIGHT_TAG.into());
+        }
+        let id: u32 = session
+            .connection
+            .call_method(
+                Some(DESTINATION),
+                PATH,
+                Some(INTERFACE),
+                "Notify",
+                &(
+                    "Jasper",
+                    replaces_id,
+                    "",
+                    title,
+                    body.as_str(),
+                    actions,
+                    hints,
+                    -1i32,
+                ),
+            )?
+            .body()
+            .deserialize()?;
+        Ok(id)
+    }
+
+    /// Wait for what happens to notification `id`: open `uri` on click, or
+    /// return a reply. An insight notification (shown as insight number
+    /// `shown`) stops early once a newer insight replaces it.
+    fn wait(
+        &self,
+        signals: zbus::blocking::proxy::SignalIterator<'_>,
+        id: u32,
+        shown: Option<u64>,
+        uri: Option<&str>,
+    ) -> JasperResult<Option<String>> {
+        for message in signals {
+            if shown.is_some_and(|shown| self.current.lock().map(|c| c.1).unwrap_or(shown) != shown)
+            {
+                debug!("Insight notification {} superseded", id);
+                return Ok(None);
+            }
+            let header = message.header();
+            let Some(member) = header.member() else {
+                continue;
+            };
+            match member.as_str() {
+                "ActionInvoked" => {
+                    let (signal_id, key): (u32, String) = message.body().deserialize()?;
+                    if signal_id == id && key == "default" {
+                        if let Some(uri) = uri {
+                            platform::open_uri(uri)?;
+                        }
+                        return Ok(None);
+                    }
+                }
+                "NotificationReplied" => {
+                    let (signal_id, reply): (u32, String) = message.body().deserialize()?;
+                    if signal_id == id {
+                        return Ok(Some(reply));
+                    }
+                }
+                "NotificationClosed" => {
+                    let (signal_id, _reason): (u32, u32) = message.body().deserialize()?;
+                    if signal_id == id {
+                        return Ok(None);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Notifier for FreedesktopNotifier {
+    fn name(&self) -> &'static str {
+        "freedesktop notifications"
+    }
+
+    fn notify(&self, title: &str, body: &str, urgency: Urgency) -> JasperResult<()> {
+        match self.session() {
+            Some(session) => Self::send(session, title, body, urgency, 0, &[], false).map(|_| ()),
+            None => NotifySend.notify(title, body, urgency),
+        }
+    }
+
+    fn notify_with_link(
+        &self,
+        title: &str,
+        body: &str,
+        urgency: Urgency,
+        label: &str,
+        uri: &str,
+    ) -> JasperResult<()> {
+        let Some(session) = self.session() else {
+            return NotifySend.notify_with_link(title, body, urgency, label, uri);
+        };
+        if !session.tuning.actions {
+            return Self::send(session, title, body, urgency, 0, &[], false).map(|_| ());
+        }
+        let proxy = zbus::blocking::Proxy::new(&session.connection, DESTINATION, PATH, INTERFACE)?;
+        let signals = proxy.receive_all_signals()?;
+        let id = Self::send(session, title, body, urgency, 0, &["default", label], false)?;
+        self.wait(signals, id, None, Some(uri)).map(|_| ())
+    }
+
+    fn confirm(&self, title: &str, body: &str, label: &str) -> JasperResult<bool> {
+        let Some(session) = self.session() else {
+            return NotifySend.confirm(title, body, label);
+        };
+        if !session.tuning.actions {
+            return Self::send(session, title, body, Urgency::Normal, 0, &[], false).map(|_| false);
+        }
+        let proxy = zbus::blocking::Proxy::new(&session.connection, DESTINATION, PATH, INTERFACE)?;
+        let signals = proxy.receive_all_signals()?;
+        let id = Self::send(
+            session,
+            title,
+            body,
+            Urgency::Normal,
+            0,
+            &["confirm", label],
+            false,
+        )?;
+        for message in signals {
+            let header = message.header();
+            match header.member().map(|m| m.as_str()) {
+                Some("ActionInvoked") => {
+                    let (signal_id, key): (u32, String) = message.body().deserialize()?;
+                    if signal_id == id {
+                        return Ok(key == "confirm");
+                    }
+                }
+                Some("NotificationClosed") => {
+                    let (signal_id, _reason): (u32, u32) = message.body().deserialize()?;
+                    if signal_id == id {
+                        return Ok(false);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(false)
+    }
+
+    fn notify_insight(
+        &self,
+        title: &str,
+        body: &str,
+        link: Option<(&str, &str)>,
+    ) -> JasperResult<Option<String>> {
+        let Some(session) = self.session() else {
+            return NotifySend.notify_insight(title, body, link);
+        };
+        let tuning = session.tuning;
+        let mut actions: Vec<&str> = Vec::new();
+        if let Some((label, _)) = link.filter(|_| tuning.actions) {
+            actions.extend(["default", label]);
+        }
+        if tuning.inline_reply {
+            actions.extend(["inline-reply", "Reply"]);
+        }
+
+        // Subscribe before sending so a quick click isn't missed
+        let signals = if actions.is_empty() {
+            None
+        } else {
+            let proxy =
+                zbus::blocking::Proxy::new(&session.connection, DESTINATION, PATH, INTERFACE)?;
+            Some(proxy.receive_all_signals()?)
+        };
+        let (replaces_id, shown) = {
+            let current = self.current.lock().map(|c| *c).unwrap_or_default();
+            (current.0, current.1 + 1)
+        };
+        let id = Self::send(
+            session,
+            title,
+            body,
+            Urgency::Normal,
+            replaces_id,
+            &actions,
+            true,
+        )?;
+        if let Ok(mut current) = self.current.lock() {
+            *current = (id, shown);
+        }
+
+        match signals {
+            Some(signals) => self.wait(signals, id, Some(shown), link.map(|(_, uri)| uri)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tuning_follows_server_and_capabilities() {
+        let caps = |list: &[&str]| list.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        let dunst = Tuning::new(
+            NotificationServer::from_name("dunst"),
+            &caps(&["body", "body-markup", "actions"]),
+        );
+        assert!(dunst.markup && dunst.actions && !dunst.inline_reply);
+        assert_eq!(dunst.stack_hint, Some("x-dunst-stack-tag"));
+
+        let swaync = Tuning::new(
+            NotificationServer::from_name("SwayNotificationCenter"),
+            &caps(&["actions", "body", "body-markup", "inline-reply"]),
+        );
+        assert!(swaync.inline_reply);
+        assert_eq!(swaync.stack_hint, Some("x-canonical-private-synchronous"));
+
+        // mako with markup turned off shows text as is, so it isn't escaped
+        let mako = Tuning::new(NotificationServer::from_name("mako"), &caps(&["body"]));
+        assert!(!mako.markup && !mako.actions);
+
+        let gnome = Tuning::new(
+            NotificationServer::from_name("gnome-shell"),
+            &caps(&["actions", "body", "body-markup"]),
+        );
+        assert_eq!(gnome.stack_hint, None);
+    }
+}
@@ -0,0 +1,46 @@
+//! Push insights to an ntfy topic.
+//!
+//! Used as the delivery channel when the daemon runs headless (no D-Bus
+//! frontends), and alongside D-Bus whenever `[delivery] ntfy_url` is set.
+
+use crate::errors::{JasperError, JasperResult};
+
+pub struct NtfyPublisher {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl NtfyPublisher {
+    pub fn new(client: reqwest::Client, url: String, token: Option<String>) -> Self {
+        Self { client, url, token }
+    }
+
+    /// Publish one insight. The emoji stays in the body because ntfy headers
+    /// are only reliable for ASCII.
+    pub async fn publish(&self, emoji: &str, text: &str, click: Option<&str>) -> JasperResult<()> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Title", "Jasper")
+            .body(format!("{} {}", emoji, text).trim().to_string());
+        // Tapping the notification opens what the insight is about
+        if let Some(click) = click {
+            request = request.header("Click", click);
+        }
+        if let Some(ref token) = self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request.send().await.map_err(|e| JasperError::Network {
+            message: format!("ntfy publish failed: {}", e),
+        })?;
+        if !response.status().is_success() {
+            return Err(JasperError::Api {
+                service: "ntfy".to_string(),
+                message: format!("HTTP {}", response.status()),
+            });
+        }
+        Ok(())
+    }
+}
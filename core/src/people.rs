@@ -0,0 +1,259 @@
+//! Who an event is with.
+//!
+//! Google Calendar attendees are stored with each event as JSON. They are
+//! matched here to people Jasper knows: household members from `[people]`
+//! and Obsidian people notes, by email first and then by name or alias. The
+//! prompt gets a "who you're meeting" section built from the matches. A
+//! person note whose last contact is past the relationship alert threshold
+//! is flagged next to the meeting, as the chance to catch up.
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::HouseholdMember;
+use crate::context_sources::PersonNote;
+use crate::significance_engine::CalendarEventSummary;
+
+/// Events described, soonest first
+const MAX_EVENTS: usize = 8;
+/// Attendees named per event; the rest are counted
+const MAX_NAMED: usize = 4;
+
+/// Someone invited to an event (never the user; see `google_calendar`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Attendee {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+impl Attendee {
+    /// Display name, else the part of the address before the @
+    pub fn label(&self) -> String {
+        match (&self.name, &self.email) {
+            (Some(name), _) => name.clone(),
+            (None, Some(email)) => email.split('@').next().unwrap_or(email).to_string(),
+            (None, None) => "someone".to_string(),
+        }
+    }
+}
+
+/// Attendees from an event's stored `participants` JSON. Events synced
+/// before names were kept store a plain list of addresses.
+pub fn parse_participants(json: &str) -> Vec<Attendee> {
+    if let Ok(attendees) = serde_json::from_str::<Vec<Attendee>>(json) {
+        return attendees;
+    }
+    serde_json::from_str::<Vec<String>>(json)
+        .map(|emails| {
+            emails
+                .into_iter()
+                .map(|email| Attendee {
+                    name: None,
+                    email: Some(email),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Someone an attendee turned out to be
+#[derive(Debug, Clone, Copy)]
+pub enum Known<'a> {
+    Household(&'a HouseholdMember),
+    Note(&'a PersonNote),
+}
+
+/// Everyone Jasper can recognize among attendees
+pub struct Directory<'a> {
+    pub household: &'a [HouseholdMember],
+    pub notes: &'a [PersonNote],
+}
+
+impl<'a> Directory<'a> {
+    /// Match by email, then by name or alias; household members first
+    pub fn resolve(&self, attendee: &Attendee) -> Option<Known<'a>> {
+        let same = |a: &str, b: &str| a.trim().eq_ignore_ascii_case(b.trim());
+        if let Some(ref email) = attendee.email {
+            let by_email = self
+                .household
+                .iter()
+                .find(|m| m.emails.iter().any(|e| same(e, email)))
+                .map(Known::Household)
+                .or_else(|| {
+                    self.notes
+                        .iter()
+                        .find(|n| n.emails.iter().any(|e| same(e, email)))
+                        .map(Known::Note)
+                });
+            if by_email.is_some() {
+                return by_email;
+            }
+        }
+        let name = attendee.name.as_deref()?;
+        self.household
+            .iter()
+            .find(|m| same(&m.name, name) || m.aliases.iter().any(|a| same(a, name)))
+            .map(Known::Household)
+            .or_else(|| {
+                self.notes
+                    .iter()
+                    .find(|n| same(&n.name, name) || n.aliases.iter().any(|a| same(a, name)))
+                    .map(Known::Note)
+            })
+    }
+}
+
+/// One line per upcoming event with attendees: who it's with, who among
+/// them Jasper knows, and anyone it's been too long since seeing
+pub fn meeting_lines(
+    events: &[CalendarEventSummary],
+    directory: &Directory,
+    local_now: &DateTime<FixedOffset>,
+) -> Vec<String> {
+    let now = local_now.with_timezone(&Utc);
+    let mut upcoming: Vec<&CalendarEventSummary> = events
+        .iter()
+        .filter(|e| !e.attendees.is_empty() && e.start_time > now)
+        .collect();
+    upcoming.sort_by_key(|e| e.start_time);
+
+    upcoming
+        .into_iter()
+        .take(MAX_EVENTS)
+        .map(|event| {
+            let mut named: Vec<String> = event
+                .attendees
+                .iter()
+                .take(MAX_NAMED)
+                .map(|attendee| describe(attendee, directory.resolve(attendee), local_now))
+                .collect();
+            if event.attendees.len() > MAX_NAMED {
+                named.push(format!("+{} more", event.attendees.len() - MAX_NAMED));
+            }
+            format!(
+                "- {} ({}): {}",
+                event.title,
+                event
+                    .start_time
+                    .with_timezone(&local_now.timezone())
+                    .format("%a %-I:%M %p"),
+                named.join(", ")
+            )
+        })
+        .collect()
+}
+
+fn describe(
+    attendee: &Attendee,
+    known: Option<Known>,
+    local_now: &DateTime<FixedOffset>,
+) -> String {
+    match known {
+        Some(Known::Household(member)) => match member.relation {
+            Some(ref relation) => format!("{} [household, {}]", member.name, relation),
+            None => format!("{} [household]", member.name),
+        },
+        Some(Known::Note(note)) => {
+            let contact = note.last_contact.map(|date| {
+                let days = (local_now.date_naive() - date).num_days();
+                if note.overdue {
+                    format!("; last in touch {} days ago, overdue to catch up", days)
+                } else {
+                    format!("; last in touch {} days ago", days)
+                }
+            });
+            format!(
+                "{} [has a people note{}]",
+                note.name,
+                contact.unwrap_or_default()
+            )
+        }
+        None => attendee.label(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[test]
+    fn test_attendees_are_matched_to_known_people() {
+        // Events synced before names were kept
+        assert_eq!(
+            parse_participants(r#"["priya@example.com"]"#),
+            vec![Attendee {
+                name: None,
+                email: Some("priya@example.com".to_string()),
+            }]
+        );
+        let attendees = parse_participants(
+            r#"[{"name":"Priya S.","email":"Priya@Example.com"},
+                {"name":"Chris"},
+                {"email":"sam.lee@example.com"},
+                {"name":"A"},{"name":"B"},{"name":"C"}]"#,
+        );
+        assert_eq!(attendees.len(), 6);
+
+        let household = [HouseholdMember {
+            name: "Christen".to_string(),
+            emails: vec![],
+            aliases: vec!["Chris".to_string()],
+            relation: Some("partner".to_string()),
+        }];
+        let notes = [PersonNote {
+            id: "Priya Shah".to_string(),
+            name: "Priya Shah".to_string(),
+            emails: vec!["priya@example.com".to_string()],
+            aliases: vec![],
+            last_contact: NaiveDate::from_ymd_opt(2026, 2, 1),
+            overdue: true,
+        }];
+        let directory = Directory {
+            household: &household,
+            notes: &notes,
+        };
+        assert!(matches!(
+            directory.resolve(&attendees[0]),
+            Some(Known::Note(note)) if note.name == "Priya Shah"
+        ));
+        assert!(matches!(
+            directory.resolve(&attendees[1]),
+            Some(Known::Household(member)) if member.name == "Christen"
+        ));
+        assert!(directory.resolve(&attendees[2]).is_none());
+
+        let local_now = FixedOffset::west_opt(5 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2026, 3, 2, 9, 0, 0)
+            .unwrap();
+        let event = CalendarEventSummary {
+            id: "ev1".to_string(),
+            title: "Design review".to_string(),
+            start_time: Utc.with_ymd_and_hms(2026, 3, 2, 19, 0, 0).unwrap(),
+            end_time: None,
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees,
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        assert_eq!(
+            meeting_lines(&[event], &directory, &local_now),
+            vec![
+                "- Design review (Mon 2:00 PM): Priya Shah [has a people note; last in touch \
+                 29 days ago, overdue to catch up], Christen [household, partner], sam.lee, A, \
+                 +2 more"
+                    .to_string()
+            ]
+        );
+    }
+}
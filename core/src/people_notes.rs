@@ -0,0 +1,131 @@
+//! Keeping people notes current after meetings.
+//!
+//! When an event with someone who has an Obsidian people note ends, the
+//! note's `last_contact` frontmatter is moved up to the meeting's date and a
+//! dated "Met on" line is appended, so relationship alerts stay accurate
+//! without editing notes by hand. Both are optional (`[people.note_updates]`),
+//! and by default the daemon asks first with a notification action.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use std::path::Path;
+
+use crate::config::PeopleNoteUpdates;
+
+/// `content` updated for a meeting called `title` on `date`, or None when
+/// there is nothing to change (the meeting is already recorded, or a later
+/// contact is)
+pub fn record_meeting(
+    content: &str,
+    date: NaiveDate,
+    title: &str,
+    updates: &PeopleNoteUpdates,
+) -> Option<String> {
+    let date_text = date.format("%Y-%m-%d").to_string();
+    let mut note = content.to_string();
+
+    if updates.last_contact {
+        note = set_last_contact(&note, date, &date_text);
+    }
+    if updates.log_line {
+        let line = format!("- Met on {}: {}", date_text, title.trim());
+        if !note.lines().any(|l| l.trim_end() == line) {
+            if !note.is_empty() && !note.ends_with('\n') {
+                note.push('\n');
+            }
+            note.push_str(&line);
+            note.push('\n');
+        }
+    }
+
+    (note != content).then_some(note)
+}
+
+/// Set `last_contact` unless it already holds `date` or later, adding the
+/// field (and frontmatter) when missing
+fn set_last_contact(content: &str, date: NaiveDate, date_text: &str) -> String {
+    let field = format!("last_contact: {}", date_text);
+    let Some(rest) = content.strip_prefix("---\n") else {
+        return format!("---\n{}\n---\n{}", field, content);
+    };
+    let Some(end) = rest.find("\n---") else {
+        return format!("---\n{}\n---\n{}", field, content);
+    };
+    let (frontmatter, body) = rest.split_at(end);
+
+    let mut found = false;
+    let mut lines: Vec<String> = Vec::new();
+    for line in frontmatter.lines() {
+        match line.strip_prefix("last_contact:") {
+            Some(value) => {
+                found = true;
+                let current = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                match NaiveDate::parse_from_str(current, "%Y-%m-%d") {
+                    Ok(current) if current >= date => lines.push(line.to_string()),
+                    _ => lines.push(field.clone()),
+                }
+            }
+            None => lines.push(line.to_string()),
+        }
+    }
+    if !found {
+        lines.push(field);
+    }
+    format!("---\n{}{}", lines.join("\n"), body)
+}
+
+/// Apply [`record_meeting`] to the note at `path`. Returns whether the file
+/// changed.
+pub fn update_note(
+    path: &Path,
+    date: NaiveDate,
+    title: &str,
+    updates: &PeopleNoteUpdates,
+) -> Result<bool> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let Some(updated) = record_meeting(&content, date, title, updates) else {
+        return Ok(false);
+    };
+    let tmp = path.with_extension("md.tmp");
+    std::fs::write(&tmp, updated).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meetings_update_last_contact_and_log_once() {
+        let updates = PeopleNoteUpdates::default();
+        let date = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let note = "---\nname: Priya\nlast_contact: 2026-02-01\naliases: [P]\n---\n# Priya\n\nLikes climbing";
+
+        let updated = record_meeting(note, date, "Coffee ", &updates).unwrap();
+        assert_eq!(
+            updated,
+            "---\nname: Priya\nlast_contact: 2026-05-12\naliases: [P]\n---\n# Priya\n\nLikes climbing\n\
+             - Met on 2026-05-12: Coffee\n"
+        );
+        // Already recorded
+        assert_eq!(record_meeting(&updated, date, "Coffee", &updates), None);
+
+        // An earlier meeting logged late doesn't move last_contact back
+        let earlier = NaiveDate::from_ymd_opt(2026, 5, 1).unwrap();
+        let logged = record_meeting(&updated, earlier, "Lunch", &updates).unwrap();
+        assert!(logged.contains("last_contact: 2026-05-12"));
+        assert!(logged.ends_with("- Met on 2026-05-01: Lunch\n"));
+
+        // No frontmatter yet; log line turned off
+        let only_date = PeopleNoteUpdates {
+            log_line: false,
+            ..PeopleNoteUpdates::default()
+        };
+        assert_eq!(
+            record_meeting("# Sam\n", date, "Sync", &only_date).unwrap(),
+            "---\nlast_contact: 2026-05-12\n---\n# Sam\n"
+        );
+    }
+}
@@ -0,0 +1,627 @@
+//! Operating-system integrations behind small traits.
+//!
+//! The assistant core only needs two things from the host: a way to show a
+//! desktop notification and a way to start the daemon at login (plus, for
+//! `greet`, a way to read text aloud). Linux uses
+//! the notification server over D-Bus (`notification_server`, falling back
+//! to notify-send) and a systemd user unit, macOS osascript and a launchd agent,
+//! Windows a PowerShell toast and a Task Scheduler logon task. Sandboxed
+//! installs use the xdg-desktop-portal backends in `portal`.
+
+use crate::desktop_detection;
+use crate::errors::{JasperError, JasperResult};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How insistently a notification should be shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Normal,
+    /// Escalated items; stays on screen where the host supports it
+    Critical,
+}
+
+/// Shows a desktop notification
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn notify(&self, title: &str, body: &str, urgency: Urgency) -> JasperResult<()>;
+    /// Show a notification that opens `uri` when clicked; may block until it
+    /// is closed. Backends without click actions show a plain notification.
+    fn notify_with_link(
+        &self,
+        title: &str,
+        body: &str,
+        urgency: Urgency,
+        _label: &str,
+        _uri: &str,
+    ) -> JasperResult<()> {
+        self.notify(title, body, urgency)
+    }
+    /// Show an insight, opening `link` (label, URI) when clicked. Backends
+    /// that can replace the previous insight in place do, and those that
+    /// offer an inline reply return what was typed. May block until the
+    /// notification is closed.
+    fn notify_insight(
+        &self,
+        title: &str,
+        body: &str,
+        link: Option<(&str, &str)>,
+    ) -> JasperResult<Option<String>> {
+        match link {
+            Some((label, uri)) => self.notify_with_link(title, body, Urgency::Normal, label, uri),
+            None => self.notify(title, body, Urgency::Normal),
+        }
+        .map(|()| None)
+    }
+    /// Ask for a yes with one action button; true if it was clicked. May
+    /// block until the notification is closed. Backends without actions
+    /// show the question and answer no.
+    fn confirm(&self, title: &str, body: &str, _label: &str) -> JasperResult<bool> {
+        self.notify(title, body, Urgency::Normal).map(|()| false)
+    }
+}
+
+/// Who a login service is installed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceScope {
+    /// Only the invoking user; no root needed
+    CurrentUser,
+    /// Every user's session (system-wide directories, usually needs root)
+    AllUsers,
+}
+
+/// Registers the daemon to start at login
+pub trait Autostart {
+    fn name(&self) -> &'static str;
+    /// Install for `exe`, returning a description of what was set up
+    fn install(&self, exe: &Path, scope: ServiceScope) -> JasperResult<String>;
+    /// Remove what `install` set up, returning a description
+    fn uninstall(&self, scope: ServiceScope) -> JasperResult<String>;
+}
+
+fn run(command: &mut Command, what: &str) -> JasperResult<()> {
+    let output = command
+        .output()
+        .map_err(|e| JasperError::ServiceUnavailable {
+            service: format!("{} ({})", what, e),
+        })?;
+    if !output.status.success() {
+        return Err(JasperError::Internal {
+            message: format!(
+                "{} failed: {}",
+                what,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, content: &str) -> JasperResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+fn home_dir() -> JasperResult<PathBuf> {
+    dirs::home_dir().ok_or_else(|| JasperError::Internal {
+        message: "Unable to determine home directory".to_string(),
+    })
+}
+
+/// Remove a file, reporting whether it was there
+fn remove_file(path: &Path) -> JasperResult<bool> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// --- Linux ---
+
+pub struct NotifySend;
+
+impl Notifier for NotifySend {
+    fn name(&self) -> &'static str {
+        "notify-send"
+    }
+
+    fn notify(&self, title: &str, body: &str, urgency: Urgency) -> JasperResult<()> {
+        run(
+            Command::new("notify-send")
+                .args(["--app-name=Jasper", Self::urgency_flag(urgency), "--"])
+                .arg(title)
+                .arg(body),
+            "notify-send",
+        )
+    }
+
+    fn notify_with_link(
+        &self,
+        title: &str,
+        body: &str,
+        urgency: Urgency,
+        label: &str,
+        uri: &str,
+    ) -> JasperResult<()> {
+        // With an action, notify-send waits and prints the action invoked
+        let output = Command::new("notify-send")
+            .args(["--app-name=Jasper", Self::urgency_flag(urgency)])
+            .arg(format!("--action=default={}", label))
+            .arg("--")
+            .arg(title)
+            .arg(body)
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                if String::from_utf8_lossy(&output.stdout).trim() == "default" {
+                    open_uri(uri)?;
+                }
+                Ok(())
+            }
+            // notify-send from before libnotify 0.7.10 has no --action
+            _ => self.notify(title, body, urgency),
+        }
+    }
+
+    fn confirm(&self, title: &str, body: &str, label: &str) -> JasperResult<bool> {
+        let output = Command::new("notify-send")
+            .args(["--app-name=Jasper", Self::urgency_flag(Urgency::Normal)])
+            .arg(format!("--action=confirm={}", label))
+            .arg("--")
+            .arg(title)
+            .arg(body)
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                Ok(String::from_utf8_lossy(&output.stdout).trim() == "confirm")
+            }
+            _ => self.notify(title, body, Urgency::Normal).map(|()| false),
+        }
+    }
+}
+
+impl NotifySend {
+    fn urgency_flag(urgency: Urgency) -> &'static str {
+        match urgency {
+            Urgency::Normal => "--urgency=normal",
+            Urgency::Critical => "--urgency=critical",
+        }
+    }
+}
+
+pub struct SystemdUserUnit;
+
+impl SystemdUserUnit {
+    const UNIT: &'static str = "jasper-companion.service";
+
+    /// Secrets and SOPS settings the daemon reads from its environment. A user
+    /// manager doesn't see the login shell's variables, so they are passed
+    /// through (after `systemctl --user import-environment`) or read from
+    /// `~/.config/jasper-companion/environment`.
+    const PASS_ENVIRONMENT: &'static [&'static str] = &[
+        "ANTHROPIC_API_KEY",
+        "GOOGLE_CLIENT_ID",
+        "GOOGLE_CLIENT_SECRET",
+        "GOOGLE_WEATHER_API_KEY",
+        "GOOGLE_ROUTES_API_KEY",
+        "HOME_ADDRESS",
+        "JIRA_API_TOKEN",
+        "JASPER_DB_KEY",
+        "JASPER_SOPS_PATH",
+        "NTFY_TOKEN",
+        "OPENAQ_API_KEY",
+        "SLACK_BOT_TOKEN",
+        "SLACK_TOKEN",
+        "SOPS_AGE_KEY",
+        "SOPS_AGE_KEY_FILE",
+        "TELEGRAM_BOT_TOKEN",
+    ];
+
+    fn unit(exe: &Path) -> String {
+        format!(
+            "[Unit]\n\
+             Description=Jasper companion daemon\n\
+             Documentation=https://github.com/heytcass/jasper\n\
+             After=graphical-session.target\n\n\
+             [Service]\n\
+             Type=simple\n\
+             ExecStart=\"{}\" start\n\
+             Restart=on-failure\n\
+             RestartSec=5\n\
+             PassEnvironment={}\n\
+             EnvironmentFile=-%h/.config/jasper-companion/environment\n\n\
+             [Install]\n\
+             WantedBy=default.target\n",
+            exe.display(),
+            Self::PASS_ENVIRONMENT.join(" ")
+        )
+    }
+
+    /// D-Bus activation file: a frontend calling org.jasper.Daemon starts the unit
+    fn dbus_service(exe: &Path) -> String {
+        format!(
+            "[D-BUS Service]\n\
+             Name=org.jasper.Daemon\n\
+             Exec=\"{}\" start\n\
+             SystemdService={}\n",
+            exe.display(),
+            Self::UNIT
+        )
+    }
+
+    fn paths(scope: ServiceScope) -> JasperResult<(PathBuf, PathBuf)> {
+        Ok(match scope {
+            ServiceScope::CurrentUser => {
+                let config = dirs::config_dir().ok_or_else(|| JasperError::Internal {
+                    message: "Unable to determine config directory".to_string(),
+                })?;
+                let data = dirs::data_dir().ok_or_else(|| JasperError::Internal {
+                    message: "Unable to determine data directory".to_string(),
+                })?;
+                (
+                    config.join("systemd/user").join(Self::UNIT),
+                    data.join("dbus-1/services/org.jasper.Daemon.service"),
+                )
+            }
+            ServiceScope::AllUsers => (
+                Path::new("/etc/systemd/user").join(Self::UNIT),
+                PathBuf::from("/usr/local/share/dbus-1/services/org.jasper.Daemon.service"),
+            ),
+        })
+    }
+
+    fn systemctl(scope: ServiceScope, args: &[&str]) -> JasperResult<()> {
+        let flag = match scope {
+            ServiceScope::CurrentUser => "--user",
+            ServiceScope::AllUsers => "--global",
+        };
+        run(
+            Command::new("systemctl").arg(flag).args(args),
+            &format!("systemctl {} {}", flag, args.join(" ")),
+        )
+    }
+}
+
+impl Autostart for SystemdUserUnit {
+    fn name(&self) -> &'static str {
+        "systemd user unit"
+    }
+
+    fn install(&self, exe: &Path, scope: ServiceScope) -> JasperResult<String> {
+        let (unit_path, dbus_path) = Self::paths(scope)?;
+        write_file(&unit_path, &Self::unit(exe))?;
+        write_file(&dbus_path, &Self::dbus_service(exe))?;
+        Ok(match scope {
+            ServiceScope::CurrentUser => {
+                Self::systemctl(scope, &["daemon-reload"])?;
+                format!(
+                    "Wrote {} and {}; enable with `systemctl --user enable --now jasper-companion`",
+                    unit_path.display(),
+                    dbus_path.display()
+                )
+            }
+            ServiceScope::AllUsers => {
+                Self::systemctl(scope, &["enable", Self::UNIT])?;
+                format!(
+                    "Wrote {} and {}; enabled for every user from their next login",
+                    unit_path.display(),
+                    dbus_path.display()
+                )
+            }
+        })
+    }
+
+    fn uninstall(&self, scope: ServiceScope) -> JasperResult<String> {
+        let (unit_path, dbus_path) = Self::paths(scope)?;
+        // Disabling an already-removed unit fails; that's fine
+        let disable: &[&str] = match scope {
+            ServiceScope::CurrentUser => &["disable", "--now", Self::UNIT],
+            ServiceScope::AllUsers => &["disable", Self::UNIT],
+        };
+        Self::systemctl(scope, disable).ok();
+        let removed = remove_file(&unit_path)? | remove_file(&dbus_path)?;
+        if scope == ServiceScope::CurrentUser {
+            Self::systemctl(scope, &["daemon-reload"])?;
+        }
+        Ok(if removed {
+            format!(
+                "Removed {} and {}",
+                unit_path.display(),
+                dbus_path.display()
+            )
+        } else {
+            "No systemd unit was installed".to_string()
+        })
+    }
+}
+
+// --- macOS ---
+
+/// Quote a string for an AppleScript string literal
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub struct Osascript;
+
+impl Notifier for Osascript {
+    fn name(&self) -> &'static str {
+        "osascript"
+    }
+
+    fn notify(&self, title: &str, body: &str, urgency: Urgency) -> JasperResult<()> {
+        // Notification Center has no urgency levels; critical ones at least make a sound
+        let sound = match urgency {
+            Urgency::Normal => "",
+            Urgency::Critical => " sound name \"Sosumi\"",
+        };
+        let script = format!(
+            "display notification {} with title {}{}",
+            applescript_string(body),
+            applescript_string(title),
+            sound
+        );
+        run(Command::new("osascript").args(["-e", &script]), "osascript")
+    }
+}
+
+pub struct LaunchAgent;
+
+impl LaunchAgent {
+    const LABEL: &'static str = "org.jasper.companion";
+
+    fn plist(exe: &Path) -> String {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <plist version=\"1.0\">\n\
+             <dict>\n\
+             \x20 <key>Label</key><string>{}</string>\n\
+             \x20 <key>ProgramArguments</key>\n\
+             \x20 <array><string>{}</string><string>start</string></array>\n\
+             \x20 <key>RunAtLoad</key><true/>\n\
+             \x20 <key>KeepAlive</key><dict><key>SuccessfulExit</key><false/></dict>\n\
+             </dict>\n\
+             </plist>\n",
+            Self::LABEL,
+            xml_escape(&exe.display().to_string())
+        )
+    }
+}
+
+impl LaunchAgent {
+    fn path(scope: ServiceScope) -> JasperResult<PathBuf> {
+        let dir = match scope {
+            ServiceScope::CurrentUser => home_dir()?.join("Library/LaunchAgents"),
+            ServiceScope::AllUsers => PathBuf::from("/Library/LaunchAgents"),
+        };
+        Ok(dir.join(format!("{}.plist", Self::LABEL)))
+    }
+}
+
+impl Autostart for LaunchAgent {
+    fn name(&self) -> &'static str {
+        "launchd agent"
+    }
+
+    fn install(&self, exe: &Path, scope: ServiceScope) -> JasperResult<String> {
+        let path = Self::path(scope)?;
+        write_file(&path, &Self::plist(exe))?;
+        Ok(format!(
+            "Wrote {}; load with `launchctl load -w {}`",
+            path.display(),
+            path.display()
+        ))
+    }
+
+    fn uninstall(&self, scope: ServiceScope) -> JasperResult<String> {
+        let path = Self::path(scope)?;
+        if !path.exists() {
+            return Ok("No launchd agent was installed".to_string());
+        }
+        // Not loaded is fine
+        run(
+            Command::new("launchctl").arg("unload").arg("-w").arg(&path),
+            "launchctl unload",
+        )
+        .ok();
+        remove_file(&path)?;
+        Ok(format!("Removed {}", path.display()))
+    }
+}
+
+// --- Windows ---
+
+/// Quote a string as a PowerShell single-quoted literal
+fn powershell_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+pub struct PowerShellToast;
+
+impl Notifier for PowerShellToast {
+    fn name(&self) -> &'static str {
+        "Windows toast"
+    }
+
+    fn notify(&self, title: &str, body: &str, _urgency: Urgency) -> JasperResult<()> {
+        let script = format!(
+            "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+             $t = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+             $n = $t.GetElementsByTagName('text'); \
+             $n.Item(0).AppendChild($t.CreateTextNode({})) > $null; \
+             $n.Item(1).AppendChild($t.CreateTextNode({})) > $null; \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Jasper').Show([Windows.UI.Notifications.ToastNotification]::new($t))",
+            powershell_literal(title),
+            powershell_literal(body)
+        );
+        run(
+            Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", &script]),
+            "PowerShell toast",
+        )
+    }
+}
+
+pub struct TaskScheduler;
+
+impl Autostart for TaskScheduler {
+    fn name(&self) -> &'static str {
+        "Task Scheduler logon task"
+    }
+
+    fn install(&self, exe: &Path, scope: ServiceScope) -> JasperResult<String> {
+        Self::require_current_user(scope)?;
+        let action = format!("\"{}\" start", exe.display());
+        run(
+            Command::new("schtasks").args([
+                "/Create", "/F", "/SC", "ONLOGON", "/TN", "Jasper", "/TR", &action,
+            ]),
+            "schtasks",
+        )?;
+        Ok("Created logon task 'Jasper'".to_string())
+    }
+
+    fn uninstall(&self, scope: ServiceScope) -> JasperResult<String> {
+        Self::require_current_user(scope)?;
+        run(
+            Command::new("schtasks").args(["/Delete", "/F", "/TN", "Jasper"]),
+            "schtasks",
+        )?;
+        Ok("Deleted logon task 'Jasper'".to_string())
+    }
+}
+
+impl TaskScheduler {
+    /// Logon tasks run as the user who created them
+    fn require_current_user(scope: ServiceScope) -> JasperResult<()> {
+        if scope == ServiceScope::AllUsers {
+            return Err(JasperError::Config {
+                message: "Task Scheduler logon tasks are per-user; pass --user".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Notification backend for the current OS (the portal when sandboxed)
+pub fn notifier() -> Box<dyn Notifier> {
+    if desktop_detection::is_sandboxed() {
+        Box::new(crate::portal::PortalNotifier)
+    } else if cfg!(target_os = "macos") {
+        Box::new(Osascript)
+    } else if cfg!(target_os = "windows") {
+        Box::new(PowerShellToast)
+    } else {
+        Box::new(crate::notification_server::FreedesktopNotifier::new())
+    }
+}
+
+/// Read `text` aloud and wait until it has been spoken. `command` runs
+/// through `sh -c` with the text on stdin; without one, the system's speech
+/// tool is used (speech-dispatcher's spd-say, macOS say, or Windows
+/// System.Speech).
+pub fn speak(text: &str, command: Option<&str>) -> JasperResult<()> {
+    if let Some(command) = command {
+        use std::io::Write;
+        let mut child = Command::new("sh")
+            .args(["-c", command])
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| JasperError::ServiceUnavailable {
+                service: format!("TTS command ({})", e),
+            })?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(JasperError::Internal {
+                message: format!("TTS command failed: {}", status),
+            });
+        }
+        return Ok(());
+    }
+    if cfg!(target_os = "macos") {
+        run(Command::new("say").arg(text), "say")
+    } else if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; \
+             (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak({})",
+            powershell_literal(text)
+        );
+        run(
+            Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", &script]),
+            "System.Speech",
+        )
+    } else {
+        run(
+            Command::new("spd-say").args(["--wait", "--", text]),
+            "spd-say",
+        )
+    }
+}
+
+/// Open a URI with the desktop's default handler
+pub fn open_uri(uri: &str) -> JasperResult<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    run(command.arg(uri), "open URI")
+}
+
+/// Login-autostart backend for the current OS
+pub fn autostart() -> Box<dyn Autostart> {
+    if cfg!(target_os = "macos") {
+        Box::new(LaunchAgent)
+    } else if cfg!(target_os = "windows") {
+        Box::new(TaskScheduler)
+    } else {
+        Box::new(SystemdUserUnit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_quoting() {
+        assert_eq!(
+            applescript_string(r#"Say "hi" \ bye"#),
+            r#""Say \"hi\" \\ bye""#
+        );
+        assert_eq!(powershell_literal("Kieran's game"), "'Kieran''s game'");
+    }
+
+    #[test]
+    fn test_service_definitions_reference_exe() {
+        let exe = Path::new("/opt/jasper & co/jasper-companion-daemon");
+        let unit = SystemdUserUnit::unit(exe);
+        assert!(unit.contains("ExecStart=\"/opt/jasper & co/jasper-companion-daemon\" start"));
+        assert!(unit.contains(" SOPS_AGE_KEY_FILE"));
+        assert!(unit.contains(" OPENAQ_API_KEY "));
+        let dbus = SystemdUserUnit::dbus_service(exe);
+        assert!(dbus.contains("Exec=\"/opt/jasper & co/jasper-companion-daemon\" start"));
+        assert!(dbus.contains("SystemdService=jasper-companion.service"));
+        let plist = LaunchAgent::plist(exe);
+        assert!(plist.contains("<string>/opt/jasper &amp; co/jasper-companion-daemon</string>"));
+        assert!(plist.contains("<string>org.jasper.companion</string>"));
+    }
+}
@@ -0,0 +1,89 @@
+//! xdg-desktop-portal backends for sandboxed (Flatpak/Snap) installs.
+//!
+//! Inside a sandbox there is no notify-send binary and no direct access to
+//! the Secret Service, but the portal offers both: notifications through
+//! `org.freedesktop.portal.Notification` and a stable per-application secret
+//! through `org.freedesktop.portal.Secret`.
+
+use crate::errors::{JasperError, JasperResult};
+use crate::platform::{Notifier, Urgency};
+use std::collections::HashMap;
+use zbus::zvariant::Value;
+
+const PORTAL_DESTINATION: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+
+pub struct PortalNotifier;
+
+impl Notifier for PortalNotifier {
+    fn name(&self) -> &'static str {
+        "notification portal"
+    }
+
+    fn notify(&self, title: &str, body: &str, urgency: Urgency) -> JasperResult<()> {
+        let connection = zbus::blocking::Connection::session()?;
+
+        let mut notification: HashMap<&str, Value> = HashMap::new();
+        notification.insert("title", title.into());
+        notification.insert("body", body.into());
+        if urgency == Urgency::Critical {
+            notification.insert("priority", "urgent".into());
+        }
+
+        // A fixed id replaces the previous insight instead of stacking them
+        connection.call_method(
+            Some(PORTAL_DESTINATION),
+            PORTAL_PATH,
+            Some("org.freedesktop.portal.Notification"),
+            "AddNotification",
+            &("jasper-insight", notification),
+        )?;
+        Ok(())
+    }
+}
+
+/// Fetch this application's secret from the Secret portal.
+///
+/// The portal writes the secret to a file descriptor we hand it and closes
+/// its end when done. The same app always gets the same secret, so it can
+/// serve directly as the database key.
+#[cfg(unix)]
+pub fn retrieve_secret() -> JasperResult<String> {
+    use std::io::Read;
+    use std::os::unix::net::UnixStream;
+
+    let connection = zbus::blocking::Connection::session()?;
+    let (mut reader, writer) = UnixStream::pair()?;
+
+    let token = format!("jasper_{}", std::process::id());
+    let mut options: HashMap<&str, Value> = HashMap::new();
+    options.insert("handle_token", token.as_str().into());
+
+    connection.call_method(
+        Some(PORTAL_DESTINATION),
+        PORTAL_PATH,
+        Some("org.freedesktop.portal.Secret"),
+        "RetrieveSecret",
+        &(zbus::zvariant::Fd::from(&writer), options),
+    )?;
+    // Only the portal's copy may keep the pipe open, or we'd never see EOF
+    drop(writer);
+
+    reader.set_read_timeout(Some(std::time::Duration::from_secs(30)))?;
+    let mut secret = Vec::new();
+    reader.read_to_end(&mut secret)?;
+
+    if secret.is_empty() {
+        return Err(JasperError::ServiceUnavailable {
+            service: "Secret portal".to_string(),
+        });
+    }
+    Ok(secret.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(not(unix))]
+pub fn retrieve_secret() -> JasperResult<String> {
+    Err(JasperError::ServiceUnavailable {
+        service: "Secret portal".to_string(),
+    })
+}
@@ -0,0 +1,251 @@
+//! Local scoring of context changes, before any AI call.
+//!
+//! The significance engine says whether anything changed; this says whether
+//! the change is worth an insight. Each change has a base weight for its
+//! kind, scaled by how soon the event or task it touches comes up. The sum
+//! is scaled down overnight. A score under `[coalescing] min_score` keeps
+//! the new context (the change isn't reported again) but makes no AI call.
+//! The score goes in the trigger audit log either way.
+
+use std::fmt;
+
+use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
+
+use crate::significance_engine::{ContextSnapshot, SignificantChange};
+
+/// Local hours (start inclusive, end exclusive) when changes count for less
+const QUIET_HOURS: (u32, u32) = (22, 6);
+const QUIET_WEIGHT: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Score {
+    /// Every change's weight times its urgency, summed
+    pub changes: f64,
+    /// 1.0, or less overnight
+    pub time_of_day: f64,
+    pub total: f64,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.2} ({:.2} from changes × {:.1} time of day)",
+            self.total, self.changes, self.time_of_day
+        )
+    }
+}
+
+/// Score `changes` against the context they were found in
+pub fn score(
+    changes: &[SignificantChange],
+    context: &ContextSnapshot,
+    local_now: &DateTime<FixedOffset>,
+) -> Score {
+    let now = local_now.with_timezone(&Utc);
+    let changes: f64 = changes
+        .iter()
+        .map(|change| weight(change) * urgency(change, context, now))
+        .sum();
+    let hour = local_now.hour();
+    let time_of_day = if hour >= QUIET_HOURS.0 || hour < QUIET_HOURS.1 {
+        QUIET_WEIGHT
+    } else {
+        1.0
+    };
+    Score {
+        changes,
+        time_of_day,
+        total: changes * time_of_day,
+    }
+}
+
+/// Whether `changes` go to the AI: school alerts and the first context
+/// always do, anything else when it scores at least `min_score`
+pub fn passes(changes: &[SignificantChange], score: &Score, min_score: f64) -> bool {
+    score.total >= min_score
+        || changes.iter().any(|c| {
+            matches!(
+                c,
+                SignificantChange::InitialContext | SignificantChange::SchoolAlert { .. }
+            )
+        })
+}
+
+/// How much a change of this kind matters on its own
+fn weight(change: &SignificantChange) -> f64 {
+    match change {
+        // Always worth a call
+        SignificantChange::InitialContext | SignificantChange::SchoolAlert { .. } => 10.0,
+        SignificantChange::CancelledCalendarEvent(_) => 3.0,
+        // A script pushed it on purpose
+        SignificantChange::InjectedContext { .. } => 3.0,
+        // Found locally, and the kind of thing worth a heads-up
+        SignificantChange::ScheduleAnomaly(_) => 2.5,
+        SignificantChange::UnansweredMessage { .. } => 2.0,
+        SignificantChange::NewCalendarEvent(_) => 2.0,
+        SignificantChange::EventTimeChanged {
+            time_diff_hours, ..
+        } => (1.0 + time_diff_hours.abs() / 2.0).min(3.0),
+        SignificantChange::EventLocationChanged { .. } => 1.5,
+        SignificantChange::WeatherConditionChanged { .. } => 1.5,
+        SignificantChange::WeatherTemperatureChanged { diff, .. } => {
+            (diff.unsigned_abs() as f64 / 10.0).min(1.5)
+        }
+        SignificantChange::NewTask(_) => 1.0,
+        SignificantChange::TaskDueChanged { .. } => 1.0,
+        SignificantChange::TaskCompleted(_) => 0.5,
+    }
+}
+
+/// How soon the affected event or task comes up: sooner matters more
+fn urgency(change: &SignificantChange, context: &ContextSnapshot, now: DateTime<Utc>) -> f64 {
+    let event_start = |matches: &dyn Fn(&str, &str) -> bool| {
+        context
+            .calendar_events
+            .iter()
+            .find(|e| matches(&e.id, &e.title))
+            .map(|e| e.start_time)
+    };
+    let task = |matches: &dyn Fn(&str, &str) -> bool| {
+        context
+            .tasks
+            .iter()
+            .find(|t| matches(&t.id, &t.title))
+            .map(|t| (t.due, t.urgency))
+    };
+    let by_time = |at: Option<DateTime<Utc>>| match at {
+        Some(at) if at - now <= Duration::hours(3) => 2.0,
+        Some(at) if at - now <= Duration::hours(24) => 1.5,
+        Some(at) if at - now > Duration::days(7) => 0.5,
+        _ => 1.0,
+    };
+
+    match change {
+        SignificantChange::NewCalendarEvent(title) => {
+            by_time(event_start(&|_, t| t == title.as_str()))
+        }
+        SignificantChange::EventTimeChanged { event_id, .. }
+        | SignificantChange::EventLocationChanged { event_id } => {
+            by_time(event_start(&|id, _| id == event_id.as_str()))
+        }
+        SignificantChange::NewTask(title) => task(&|_, t| t == title.as_str())
+            .map_or(1.0, |(due, urgency)| {
+                by_time(due).max(urgency_weight(urgency))
+            }),
+        SignificantChange::TaskDueChanged { task_id, .. } => task(&|id, _| id == task_id.as_str())
+            .map_or(1.0, |(due, urgency)| {
+                by_time(due).max(urgency_weight(urgency))
+            }),
+        _ => 1.0,
+    }
+}
+
+fn urgency_weight(urgency: i32) -> f64 {
+    if urgency >= 8 {
+        1.5
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::significance_engine::{CalendarEventSummary, TaskSummary};
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_small_distant_and_overnight_changes_score_low() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let noon = tz.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let night = tz.with_ymd_and_hms(2026, 3, 2, 23, 0, 0).unwrap();
+        let event = |id: &str, title: &str, start: DateTime<Utc>| CalendarEventSummary {
+            id: id.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: None,
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let now = noon.with_timezone(&Utc);
+        let context = ContextSnapshot {
+            calendar_events: vec![
+                event("soon", "Dentist", now + Duration::hours(1)),
+                event("later", "Conference", now + Duration::days(20)),
+            ],
+            weather: None,
+            tasks: vec![TaskSummary {
+                id: "t1".to_string(),
+                title: "Renew passport".to_string(),
+                due: None,
+                completed: false,
+                priority: 9,
+                urgency: 9,
+                source: "todoist".to_string(),
+            }],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: now,
+            context_hash: String::new(),
+        };
+        let total = |changes: &[SignificantChange], at: &DateTime<FixedOffset>| {
+            score(changes, &context, at).total
+        };
+
+        let moved = [SignificantChange::EventLocationChanged {
+            event_id: "soon".to_string(),
+        }];
+        assert_eq!(total(&moved, &noon), 3.0);
+        assert_eq!(total(&moved, &night), 1.5);
+        // Something weeks away barely registers
+        assert_eq!(
+            total(
+                &[SignificantChange::NewCalendarEvent(
+                    "Conference".to_string()
+                )],
+                &noon
+            ),
+            1.0
+        );
+        assert_eq!(
+            total(
+                &[SignificantChange::TaskCompleted("Water plants".to_string())],
+                &night
+            ),
+            0.25
+        );
+        // High priority counts as urgent even without a due date
+        assert_eq!(
+            total(
+                &[SignificantChange::NewTask("Renew passport".to_string())],
+                &noon
+            ),
+            1.5
+        );
+        let alert = [SignificantChange::SchoolAlert {
+            district: "Lincoln USD".to_string(),
+            title: "Closed Tuesday".to_string(),
+        }];
+        let alert_score = score(&alert, &context, &night);
+        assert_eq!(
+            alert_score.to_string(),
+            "5.00 (10.00 from changes × 0.5 time of day)"
+        );
+        assert!(passes(&alert, &alert_score, 8.0));
+        assert!(!passes(&moved, &score(&moved, &context, &night), 2.0));
+    }
+}
@@ -0,0 +1,130 @@
+//! What leaves the machine and what is kept, as `privacy-audit` shows it.
+//!
+//! The report is worked out from the configuration alone, so it can be
+//! checked before the daemon ever runs. With `ai.zero_retention` set,
+//! prompts skip the provider's prompt cache, responses aren't reused,
+//! context snapshots aren't stored, prompts aren't written to debug logs and
+//! source metadata is dropped before the prompt is built. Providers that
+//! offer a per-request no-training or zero-retention header would have it
+//! sent here; neither provider Jasper supports has one (Anthropic's zero
+//! data retention is set on the account, not per request).
+
+use serde::Serialize;
+
+use crate::config::{Config, SanitizationLevel};
+
+/// Per-request retention headers each provider offers: none so far
+fn retention_headers(_provider: &str) -> &'static [(&'static str, &'static str)] {
+    &[]
+}
+
+/// How text from one context source is sanitized before it reaches the prompt
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceAudit {
+    /// Source id, or "default" for sources without their own profile
+    pub source: String,
+    pub level: SanitizationLevel,
+    /// Number of custom redaction patterns
+    pub redact_rules: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivacyAudit {
+    pub provider: String,
+    pub model: String,
+    pub zero_retention: bool,
+    /// Headers sent with each AI request to opt out of retention or training
+    pub retention_headers: Vec<String>,
+    pub provider_prompt_cache: bool,
+    /// Minutes a response is reused for (0 = never)
+    pub response_cache_minutes: u32,
+    /// Days context snapshots are kept for, or None when they aren't stored
+    pub snapshot_retention_days: Option<u32>,
+    /// Whether prompts are written to the log at debug level
+    pub prompts_logged: bool,
+    /// Whether source metadata (paths, ids, fetch details) reaches the prompt
+    pub source_metadata: bool,
+    pub sanitization: Vec<SourceAudit>,
+}
+
+impl PrivacyAudit {
+    pub fn from_config(config: &Config) -> Self {
+        let ai = &config.ai;
+        let zero_retention = ai.zero_retention;
+        let retention_headers = if zero_retention {
+            retention_headers(&ai.provider)
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let profile = |source: &str, profile: &crate::config::SanitizationProfile| SourceAudit {
+            source: source.to_string(),
+            level: profile.level,
+            redact_rules: profile.redact.len(),
+        };
+        let sanitization = std::iter::once(profile("default", &config.sanitization.default))
+            .chain(
+                config
+                    .sanitization
+                    .sources
+                    .iter()
+                    .map(|(source, p)| profile(source, p)),
+            )
+            .collect();
+
+        Self {
+            provider: ai.provider.clone(),
+            model: ai.model.clone(),
+            zero_retention,
+            retention_headers,
+            provider_prompt_cache: ai.uses_prompt_cache(),
+            response_cache_minutes: ai.response_cache_minutes(),
+            snapshot_retention_days: (!zero_retention)
+                .then_some(config.database.snapshot_retention_days),
+            prompts_logged: !zero_retention,
+            source_metadata: !zero_retention,
+            sanitization,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_retention_turns_off_everything_kept() {
+        let mut config = Config::default();
+        let audit = PrivacyAudit::from_config(&config);
+        assert!(!audit.zero_retention);
+        assert!(audit.provider_prompt_cache);
+        assert_eq!(
+            audit.snapshot_retention_days,
+            Some(config.database.snapshot_retention_days)
+        );
+        assert!(audit.prompts_logged && audit.source_metadata);
+        let sources: Vec<(&str, SanitizationLevel)> = audit
+            .sanitization
+            .iter()
+            .map(|s| (s.source.as_str(), s.level))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("default", SanitizationLevel::Off),
+                ("obsidian", SanitizationLevel::Standard)
+            ]
+        );
+
+        config.ai.zero_retention = true;
+        let audit = PrivacyAudit::from_config(&config);
+        assert!(!audit.provider_prompt_cache);
+        assert_eq!(audit.response_cache_minutes, 0);
+        assert_eq!(audit.snapshot_retention_days, None);
+        assert!(!audit.prompts_logged && !audit.source_metadata);
+        assert!(audit.retention_headers.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Which vault projects are falling behind their deadlines.
+//!
+//! A project note's `progress` (0-1) is compared with how much of the time
+//! between its start and its `due_date` has gone by. A project 30% done with
+//! 80% of its time used scores 0.5; only projects at or above
+//! [`AT_RISK_THRESHOLD`] reach the prompt, most at risk first. A note without
+//! `start_date` (or `created`) is taken to have started
+//! [`DEFAULT_WINDOW_DAYS`] before it is due, and one without a due date is
+//! never at risk.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::context_sources::{Project, ProjectStatus};
+
+/// Score at which a project counts as at risk
+pub const AT_RISK_THRESHOLD: f32 = 0.25;
+
+/// Assumed length of a project with no start date
+pub const DEFAULT_WINDOW_DAYS: i64 = 30;
+
+/// How far behind schedule `project` is at `now`: the share of its time used
+/// minus the share of its work done, from 0 (on track or ahead) to 1 (past
+/// due and unfinished)
+pub fn at_risk_score(project: &Project, now: DateTime<Utc>) -> f32 {
+    let Some(due) = project.due_date else {
+        return 0.0;
+    };
+    let progress = project.progress.clamp(0.0, 1.0);
+    if progress >= 1.0 {
+        return 0.0;
+    }
+    if due <= now {
+        return 1.0;
+    }
+
+    let start = project
+        .start_date
+        .filter(|start| *start < due)
+        .unwrap_or(due - Duration::days(DEFAULT_WINDOW_DAYS));
+    let elapsed = (now - start).num_minutes().max(0) as f32 / (due - start).num_minutes() as f32;
+    (elapsed.min(1.0) - progress).clamp(0.0, 1.0)
+}
+
+/// Open projects scoring at least [`AT_RISK_THRESHOLD`], with their scores,
+/// most at risk first
+pub fn at_risk(projects: &[Project], now: DateTime<Utc>) -> Vec<(&Project, f32)> {
+    let mut at_risk: Vec<(&Project, f32)> = projects
+        .iter()
+        .filter(|p| matches!(p.status, ProjectStatus::Active | ProjectStatus::Pending))
+        .map(|p| (p, at_risk_score(p, now)))
+        .filter(|(_, score)| *score >= AT_RISK_THRESHOLD)
+        .collect();
+    at_risk.sort_by(|a, b| b.1.total_cmp(&a.1));
+    at_risk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(
+        name: &str,
+        progress: f32,
+        start: Option<DateTime<Utc>>,
+        due: Option<DateTime<Utc>>,
+    ) -> Project {
+        Project {
+            id: name.to_string(),
+            name: name.to_string(),
+            description: None,
+            status: ProjectStatus::Active,
+            start_date: start,
+            due_date: due,
+            client: None,
+            priority: 5,
+            progress,
+            tasks: vec![],
+        }
+    }
+
+    #[test]
+    fn test_projects_behind_schedule_are_at_risk() {
+        let now = Utc::now();
+        let days = Duration::days;
+        let mut on_hold = project("Shed", 0.0, None, Some(now - days(1)));
+        on_hold.status = ProjectStatus::OnHold;
+        let projects = [
+            // 80% of ten days used, 30% done
+            project("Website", 0.3, Some(now - days(8)), Some(now + days(2))),
+            // Ahead of schedule
+            project("Garden", 0.9, Some(now - days(5)), Some(now + days(5))),
+            project("Taxes", 0.5, None, Some(now - days(1))),
+            project("Someday", 0.0, None, None),
+            // No start date: assumed to span the 30 days before it's due
+            project("Novel", 0.1, None, Some(now + days(15))),
+            on_hold,
+        ];
+
+        let scores: Vec<(&str, f32)> = at_risk(&projects, now)
+            .into_iter()
+            .map(|(p, score)| (p.name.as_str(), (score * 100.0).round() / 100.0))
+            .collect();
+        assert_eq!(scores, [("Taxes", 1.0), ("Website", 0.5), ("Novel", 0.4)]);
+
+        let done = project("Done", 1.0, None, Some(now - days(3)));
+        assert_eq!(at_risk_score(&done, now), 0.0);
+    }
+}
@@ -0,0 +1,158 @@
+//! Which delivery channels each insight reaches.
+//!
+//! `[routing]` is a matrix of insight category (see [`glyphs::CATEGORIES`],
+//! plus "other" for emoji outside them) by channel, where each cell is the
+//! least urgent insight the channel takes, or `off`. An insight's urgency
+//! comes from its emoji, as [`glyphs::emoji_urgency`] has it. A cell left out
+//! falls back to the `default` row, then to the built-in behaviour: every
+//! configured channel gets everything, except Slack, which only DMs urgent
+//! and warning insights, and email, which only gets critical ones. A channel
+//! still has to be set up under `[delivery]` to send anything.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::glyphs;
+
+/// Row for emoji that belong to no category
+pub const OTHER_CATEGORY: &str = "other";
+
+/// Row that applies to every category that doesn't set the channel itself
+pub const DEFAULT_ROW: &str = "default";
+
+/// A way an insight can reach the user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    /// D-Bus frontends: Waybar, Noctalia, the GNOME extension
+    Bar,
+    /// Native desktop notifications
+    Notification,
+    /// The ntfy topic
+    Push,
+    Webhook,
+    /// Slack DM
+    Slack,
+    Email,
+    Telegram,
+}
+
+impl Channel {
+    /// The threshold when `[routing]` doesn't set one for `category`
+    fn builtin_threshold(self, category: &str) -> Threshold {
+        match self {
+            Channel::Slack if matches!(category, "urgent" | "warning") => Threshold::Normal,
+            Channel::Slack => Threshold::Off,
+            Channel::Email => Threshold::Critical,
+            _ => Threshold::Normal,
+        }
+    }
+}
+
+/// How urgent an insight must be for a channel to take it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Threshold {
+    Normal,
+    High,
+    Critical,
+    /// Nothing, however urgent
+    Off,
+}
+
+impl Threshold {
+    /// The level of an insight with `emoji`
+    fn of_emoji(emoji: &str) -> Self {
+        match glyphs::emoji_urgency(emoji) {
+            "critical" => Threshold::Critical,
+            "high" => Threshold::High,
+            _ => Threshold::Normal,
+        }
+    }
+}
+
+/// Category (or "default") → channel → threshold
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RoutingConfig(pub BTreeMap<String, BTreeMap<Channel, Threshold>>);
+
+impl RoutingConfig {
+    /// The threshold `channel` has for insights in `category`
+    pub fn threshold(&self, category: &str, channel: Channel) -> Threshold {
+        [category, DEFAULT_ROW]
+            .iter()
+            .find_map(|row| self.0.get(*row)?.get(&channel).copied())
+            .unwrap_or_else(|| channel.builtin_threshold(category))
+    }
+
+    /// Whether an insight with `emoji` goes out on `channel`
+    pub fn delivers(&self, emoji: &str, channel: Channel) -> bool {
+        let category = glyphs::emoji_category(emoji).unwrap_or(OTHER_CATEGORY);
+        let threshold = self.threshold(category, channel);
+        threshold != Threshold::Off && Threshold::of_emoji(emoji) >= threshold
+    }
+
+    /// Whether some row routes insights to `channel` explicitly, which turns
+    /// on channels that are otherwise opt-in (desktop notifications)
+    pub fn enables(&self, channel: Channel) -> bool {
+        self.0
+            .values()
+            .any(|row| row.get(&channel).is_some_and(|t| *t != Threshold::Off))
+    }
+
+    /// Rows that name no known category, for config validation
+    pub fn unknown_rows(&self) -> Vec<&str> {
+        self.0
+            .keys()
+            .map(String::as_str)
+            .filter(|row| {
+                *row != DEFAULT_ROW
+                    && *row != OTHER_CATEGORY
+                    && !glyphs::CATEGORIES.iter().any(|(name, _)| name == row)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories_route_by_urgency() {
+        // Built in: everything everywhere, Slack for urgent and warning only
+        let builtin = RoutingConfig::default();
+        assert!(builtin.delivers("☕", Channel::Notification));
+        assert!(builtin.delivers("⚠️", Channel::Slack));
+        assert!(!builtin.delivers("⏰", Channel::Slack));
+        assert!(builtin.delivers("🚨", Channel::Email));
+        assert!(!builtin.delivers("⚠️", Channel::Email));
+
+        let routing: RoutingConfig = toml::from_str(
+            r#"
+            default = { notification = "high", telegram = "off" }
+            weather = { telegram = "normal", notification = "normal" }
+            people = { bar = "normal", notification = "off", push = "off" }
+            "#,
+        )
+        .unwrap();
+        assert!(routing.delivers("🌧", Channel::Telegram));
+        assert!(routing.delivers("🌧", Channel::Notification));
+        assert!(!routing.delivers("📅", Channel::Telegram));
+        assert!(!routing.delivers("📅", Channel::Notification));
+        assert!(routing.delivers("⏰", Channel::Notification));
+        assert!(routing.delivers("🎂", Channel::Bar));
+        assert!(!routing.delivers("🎂", Channel::Notification));
+        assert!(!routing.delivers("🎂", Channel::Push));
+        // Off stays off, even for critical insights
+        assert!(!routing.delivers("🚨", Channel::Telegram));
+
+        assert!(routing.enables(Channel::Notification));
+        assert!(!routing.enables(Channel::Push));
+        assert!(!builtin.enables(Channel::Notification));
+        assert!(routing.unknown_rows().is_empty());
+        let typo: RoutingConfig = toml::from_str("wether = { push = \"off\" }").unwrap();
+        assert_eq!(typo.unknown_rows(), ["wether"]);
+        assert!(toml::from_str::<RoutingConfig>("weather = { pager = \"off\" }").is_err());
+    }
+}
@@ -0,0 +1,725 @@
+//! Strict sanitization for text that leaves the user's own devices.
+//!
+//! Calendar titles and locations routinely carry meeting links, dial-in
+//! numbers, email addresses and street addresses. Anything shown to other
+//! people (the family digest, shared displays) goes through here first:
+//! those are removed and a location is cut down to its place name.
+//!
+//! Context source text bound for the prompt goes through
+//! [`sanitize_context_data`], at the level and with the redaction rules
+//! configured for its source under `[sanitization]`. Calendar events and
+//! injected context go through [`sanitize_calendar_events`] and
+//! [`sanitize_injected`], under the `calendar` and `injected` IDs.
+
+use regex::Regex;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::config::{SanitizationConfig, SanitizationLevel, SanitizationProfile};
+use crate::context_sources::{ContextContent, ContextData, Task};
+use crate::injected_context::InjectedContext;
+use crate::significance_engine::CalendarEventSummary;
+
+struct Patterns {
+    url: Regex,
+    email: Regex,
+    phone: Regex,
+    credential: Regex,
+    /// An ISO date, which `phone` would otherwise take for a number
+    date: Regex,
+    /// Brackets left empty (or with only "call"/"at") once details are removed
+    leftover: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| Patterns {
+        url: Regex::new(r"(?i)\b(?:https?://|www\.)\S+").unwrap(),
+        email: Regex::new(r"[\w.+-]+@[\w-]+\.[\w.-]+").unwrap(),
+        phone: Regex::new(r"\+?\d[\d\s().-]{6,}\d").unwrap(),
+        credential: Regex::new(
+            r"(?i)\b(?:passcode|password|pin|meeting id|code)\b\s*[:#]?\s*[^\s)]+",
+        )
+        .unwrap(),
+        date: Regex::new(r"\d{4}-\d{2}-\d{2}").unwrap(),
+        leftover: Regex::new(
+            r"(?i)[(\[]\s*(?:(?:call|text|tel|phone|dial|email|or|at|via)\b\s*)*[)\]]",
+        )
+        .unwrap(),
+    })
+}
+
+/// Remove links, email addresses, phone numbers and passcodes, then tidy up
+/// the separators they leave behind
+pub fn strict(text: &str) -> String {
+    let p = patterns();
+    let text = p.url.replace_all(text, "");
+    let text = p.email.replace_all(&text, "");
+    let text = p.credential.replace_all(&text, "");
+    let text = p.phone.replace_all(&text, "");
+    let text = p.leftover.replace_all(&text, "");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words
+        .join(" ")
+        .trim_matches(|c: char| c.is_whitespace() || matches!(c, '-' | '–' | '—' | ',' | ':' | '|'))
+        .to_string()
+}
+
+/// A location's place name ("Rec Center, 12 Oak St" → "Rec Center"). None
+/// for links and bare street addresses.
+pub fn location(location: &str) -> Option<String> {
+    let place = strict(location.split(',').next().unwrap_or_default());
+    let is_address = place.chars().next().is_some_and(|c| c.is_ascii_digit());
+    (!place.is_empty() && !is_address).then_some(place)
+}
+
+const REDACTED: &str = "[redacted]";
+
+/// Replace contact details in prompt-bound text, keeping its layout: email
+/// addresses and phone numbers (but not dates), and at the strict level
+/// links and passcodes too
+fn redact_details(text: &str, level: SanitizationLevel) -> String {
+    let p = patterns();
+    let mut text = std::borrow::Cow::Borrowed(text);
+    if level == SanitizationLevel::Strict {
+        text = p.url.replace_all(&text, REDACTED).into_owned().into();
+        text = p
+            .credential
+            .replace_all(&text, REDACTED)
+            .into_owned()
+            .into();
+    }
+    text = p.email.replace_all(&text, REDACTED).into_owned().into();
+    p.phone
+        .replace_all(&text, |caps: &regex::Captures| {
+            let found = &caps[0];
+            if p.date.is_match(found) {
+                found.to_string()
+            } else {
+                REDACTED.to_string()
+            }
+        })
+        .into_owned()
+}
+
+/// Rules compiled from a [`SanitizationProfile`]
+struct Profile {
+    level: SanitizationLevel,
+    rules: Vec<Regex>,
+}
+
+impl Profile {
+    fn new(profile: &SanitizationProfile) -> Self {
+        let rules = profile
+            .redact
+            .iter()
+            .filter_map(|rule| match Regex::new(rule) {
+                Ok(rule) => Some(rule),
+                Err(e) => {
+                    warn!("Skipping invalid redaction rule {}: {}", rule, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            level: profile.level,
+            rules,
+        }
+    }
+
+    fn apply(&self, text: &mut String) {
+        for rule in &self.rules {
+            if let std::borrow::Cow::Owned(redacted) = rule.replace_all(text, REDACTED) {
+                *text = redacted;
+            }
+        }
+        if self.level != SanitizationLevel::Off {
+            *text = redact_details(text, self.level);
+        }
+    }
+
+    fn apply_opt(&self, text: &mut Option<String>) {
+        if let Some(text) = text {
+            self.apply(text);
+        }
+    }
+
+    fn apply_tasks(&self, tasks: &mut [Task]) {
+        for task in tasks {
+            self.apply(&mut task.title);
+            self.apply_opt(&mut task.description);
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        self.level == SanitizationLevel::Off && self.rules.is_empty()
+    }
+
+    /// The profile configured for `source_id`, else the default
+    fn for_source(config: &SanitizationConfig, source_id: &str) -> Self {
+        Self::new(config.sources.get(source_id).unwrap_or(&config.default))
+    }
+}
+
+/// Scrub calendar events with the `calendar` profile: titles, locations and
+/// attendee names. Attendee addresses are kept for matching against people
+/// notes, but one known only by address is no longer named after it.
+pub fn sanitize_calendar_events(events: &mut [CalendarEventSummary], config: &SanitizationConfig) {
+    let profile = Profile::for_source(config, "calendar");
+    if profile.is_noop() {
+        return;
+    }
+    for event in events {
+        profile.apply(&mut event.title);
+        profile.apply_opt(&mut event.location);
+        for attendee in &mut event.attendees {
+            match attendee.name {
+                Some(ref mut name) => profile.apply(name),
+                None if attendee.email.is_some() => attendee.name = Some(REDACTED.to_string()),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Scrub context pushed in over D-Bus with the `injected` profile. The
+/// payload is replaced by its scrubbed prompt text.
+pub fn sanitize_injected(entries: &mut [InjectedContext], config: &SanitizationConfig) {
+    let profile = Profile::for_source(config, "injected");
+    if profile.is_noop() {
+        return;
+    }
+    for entry in entries {
+        let mut text = entry.summary();
+        profile.apply(&mut text);
+        entry.content = serde_json::Value::String(text).to_string();
+    }
+}
+
+/// Scrub the prompt-bound text in what each source fetched: titles, notes,
+/// descriptions, conditions. IDs, dates and people notes' names and
+/// addresses, which are only matched against, are left alone.
+pub fn sanitize_context_data(context: &mut [ContextData], config: &SanitizationConfig) {
+    for data in context {
+        let profile = Profile::for_source(config, &data.source_id);
+        if profile.is_noop() {
+            continue;
+        }
+        match &mut data.content {
+            ContextContent::Calendar(calendar) => {
+                for event in &mut calendar.events {
+                    profile.apply_opt(&mut event.title);
+                    profile.apply_opt(&mut event.description);
+                }
+            }
+            ContextContent::Tasks(tasks) => profile.apply_tasks(&mut tasks.tasks),
+            ContextContent::Notes(notes) => {
+                for note in &mut notes.daily_notes {
+                    profile.apply(&mut note.content);
+                    note.focus_areas.iter_mut().for_each(|f| profile.apply(f));
+                    profile.apply_tasks(&mut note.tasks);
+                }
+                for project in &mut notes.active_projects {
+                    profile.apply(&mut project.name);
+                    profile.apply_opt(&mut project.description);
+                    profile.apply_opt(&mut project.client);
+                    profile.apply_tasks(&mut project.tasks);
+                }
+                profile.apply_tasks(&mut notes.pending_tasks);
+            }
+            ContextContent::Weather(weather) => {
+                profile.apply(&mut weather.current_conditions);
+                weather.alerts.iter_mut().for_each(|a| profile.apply(a));
+                for forecast in &mut weather.forecast {
+                    profile.apply(&mut forecast.conditions);
+                    profile.apply(&mut forecast.description);
+                }
+            }
+            ContextContent::SchoolAlerts(school) => {
+                for alert in &mut school.alerts {
+                    profile.apply(&mut alert.title);
+                }
+            }
+            ContextContent::Messages(messages) => {
+                for message in &mut messages.waiting {
+                    profile.apply(&mut message.text);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_removes_contact_details() {
+        assert_eq!(
+            strict("Parent call https://zoom.us/j/123?pwd=x (passcode: 4411)"),
+            "Parent call"
+        );
+        let cake = strict("Pick up cake — call 555-123-4567 or mia@example.com");
+        assert!(cake.starts_with("Pick up cake"));
+        assert!(!cake.contains("555") && !cake.contains('@'), "{}", cake);
+        assert_eq!(strict("Soccer practice"), "Soccer practice");
+    }
+
+    #[test]
+    fn test_location_keeps_place_name() {
+        assert_eq!(
+            location("Rec Center, 12 Oak St, Springfield").as_deref(),
+            Some("Rec Center")
+        );
+        assert_eq!(location("12 Oak St, Springfield"), None);
+        assert_eq!(location("https://meet.google.com/abc-defg-hij"), None);
+    }
+
+    #[test]
+    fn test_context_is_sanitized_per_source() {
+        use crate::context_sources::{
+            ContextDataType, DailyNote, NotesContext, TaskContext, TaskStatus,
+        };
+        use chrono::Utc;
+
+        let data = |source_id: &str, content| ContextData {
+            source_id: source_id.to_string(),
+            timestamp: Utc::now(),
+            data_type: ContextDataType::Notes,
+            priority: 100,
+            content,
+            metadata: Default::default(),
+        };
+        let task = |title: &str| Task {
+            id: title.to_string(),
+            title: title.to_string(),
+            description: None,
+            due_date: None,
+            priority: 5,
+            status: TaskStatus::Pending,
+            tags: vec![],
+            source: "todoist".to_string(),
+        };
+        let note = "- [ ] Call Priya on 555-123-4567 before 2026-05-12\n\
+                    - Draft at https://docs.example.com, cc sam@example.com";
+        let mut context = vec![
+            data(
+                "obsidian",
+                ContextContent::Notes(NotesContext {
+                    daily_notes: vec![DailyNote {
+                        date: Utc::now(),
+                        title: "2026-05-11.md".to_string(),
+                        content: note.to_string(),
+                        tasks: vec![],
+                        mood: None,
+                        energy_level: None,
+                        focus_areas: vec![],
+                    }],
+                    active_projects: vec![],
+                    pending_tasks: vec![],
+                    people: vec![],
+                }),
+            ),
+            data(
+                "tasks_todoist",
+                ContextContent::Tasks(TaskContext::new(
+                    vec![task("Project Falcon review, ping ops@example.com")],
+                    Utc::now(),
+                )),
+            ),
+        ];
+
+        let mut config = SanitizationConfig::default();
+        config.default.redact = vec!["(?i)project falcon".to_string()];
+        sanitize_context_data(&mut context, &config);
+
+        // Obsidian is at the standard level by default: links stay
+        let ContextContent::Notes(notes) = &context[0].content else {
+            unreachable!()
+        };
+        assert_eq!(
+            notes.daily_notes[0].content,
+            "- [ ] Call Priya on [redacted] before 2026-05-12\n\
+             - Draft at https://docs.example.com, cc [redacted]"
+        );
+        // Other sources get the default profile: the custom rule only
+        let ContextContent::Tasks(tasks) = &context[1].content else {
+            unreachable!()
+        };
+        assert_eq!(
+            tasks.tasks[0].title,
+            "[redacted] review, ping ops@example.com"
+        );
+
+        config.sources.insert(
+            "obsidian".to_string(),
+            SanitizationProfile {
+                level: SanitizationLevel::Strict,
+                redact: vec![],
+            },
+        );
+        sanitize_context_data(&mut context[..1], &config);
+        let ContextContent::Notes(notes) = &context[0].content else {
+            unreachable!()
+        };
+        assert!(notes.daily_notes[0]
+            .content
+            .ends_with("- Draft at [redacted] cc [redacted]"));
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Ordinary title words: never a credential keyword, which would
+        /// take the following word with it
+        fn words() -> impl Strategy<Value = Vec<String>> {
+            prop::collection::vec("[A-Za-z]{1,10}", 0..6).prop_filter(
+                "credential keyword",
+                |words| {
+                    words.iter().all(|w| {
+                        !["passcode", "password", "pin", "code", "meeting"]
+                            .contains(&w.to_lowercase().as_str())
+                    })
+                },
+            )
+        }
+
+        fn email() -> impl Strategy<Value = String> {
+            "[a-z0-9][a-z0-9._+-]{0,11}@[a-z0-9-]{1,10}\\.[a-z]{2,6}"
+        }
+
+        fn phone() -> impl Strategy<Value = String> {
+            prop_oneof![
+                "\\+?1?[ .-]?[0-9]{3}[ .-][0-9]{3}[ .-][0-9]{4}",
+                "\\([0-9]{3}\\) [0-9]{3}-[0-9]{4}",
+                "\\+44 [0-9]{2} [0-9]{4} [0-9]{4}",
+            ]
+        }
+
+        fn url() -> impl Strategy<Value = String> {
+            "(https?://|www\\.)[a-z0-9-]{1,12}\\.[a-z]{2,4}(/[A-Za-z0-9?=&_./-]{0,20})?"
+        }
+
+        fn credential() -> impl Strategy<Value = String> {
+            (
+                "(passcode|Password|PIN|Meeting ID|code)",
+                "(: ?|# ?| )",
+                "[A-Za-z0-9]{3,10}",
+            )
+                .prop_map(|(key, sep, value)| format!("{}{}{}", key, sep, value))
+        }
+
+        /// `detail` dropped somewhere among ordinary words
+        fn title_with(
+            detail: impl Strategy<Value = String>,
+        ) -> impl Strategy<Value = (String, String)> {
+            (words(), detail, words()).prop_map(|(before, detail, after)| {
+                let mut parts = before;
+                parts.push(detail.clone());
+                parts.extend(after);
+                (parts.join(" "), detail)
+            })
+        }
+
+        /// Links and passcodes with no digits, which the standard level's
+        /// phone rule would otherwise take
+        fn url_without_digits() -> impl Strategy<Value = String> {
+            "(https?://|www\\.)[a-z-]{1,12}\\.[a-z]{2,4}(/[A-Za-z_/-]{0,20})?"
+        }
+
+        fn credential_without_digits() -> impl Strategy<Value = String> {
+            "(passcode|PIN|code)(: |# )[A-Za-z]{3,10}"
+        }
+
+        fn level() -> impl Strategy<Value = SanitizationLevel> {
+            prop_oneof![
+                Just(SanitizationLevel::Off),
+                Just(SanitizationLevel::Standard),
+                Just(SanitizationLevel::Strict),
+            ]
+        }
+
+        /// `level` and `redact` for the `calendar` source and a task source
+        fn configured(level: SanitizationLevel, redact: Vec<String>) -> SanitizationConfig {
+            let mut config = SanitizationConfig::default();
+            for source in ["calendar", "tasks_todoist"] {
+                config.sources.insert(
+                    source.to_string(),
+                    SanitizationProfile {
+                        level,
+                        redact: redact.clone(),
+                    },
+                );
+            }
+            config
+        }
+
+        /// `text` as a task title, through [`sanitize_context_data`]
+        fn scrub_task(text: &str, config: &SanitizationConfig) -> String {
+            use crate::context_sources::{ContextDataType, TaskContext, TaskStatus};
+            use chrono::Utc;
+
+            let task = Task {
+                id: "1".to_string(),
+                title: text.to_string(),
+                description: Some(text.to_string()),
+                due_date: None,
+                priority: 5,
+                status: TaskStatus::Pending,
+                tags: vec![],
+                source: "todoist".to_string(),
+            };
+            let mut context = vec![ContextData {
+                source_id: "tasks_todoist".to_string(),
+                timestamp: Utc::now(),
+                data_type: ContextDataType::Tasks,
+                priority: 100,
+                content: ContextContent::Tasks(TaskContext::new(vec![task], Utc::now())),
+                metadata: Default::default(),
+            }];
+            sanitize_context_data(&mut context, config);
+            let ContextContent::Tasks(ref tasks) = context[0].content else {
+                unreachable!()
+            };
+            let task = &tasks.tasks[0];
+            assert_eq!(task.description.as_deref(), Some(task.title.as_str()));
+            task.title.clone()
+        }
+
+        /// `text` as an event's title, location and attendee name, through
+        /// [`sanitize_calendar_events`]; each comes back the same
+        fn scrub_event(text: &str, config: &SanitizationConfig) -> String {
+            use crate::people::Attendee;
+            use chrono::Utc;
+
+            let mut events = vec![CalendarEventSummary {
+                id: "e1".to_string(),
+                title: text.to_string(),
+                start_time: Utc::now(),
+                end_time: None,
+                location: Some(text.to_string()),
+                is_all_day: false,
+                calendar_name: None,
+                calendar_color: None,
+                is_own_calendar: true,
+                is_primary_calendar: true,
+                travel_time: None,
+                attendees: vec![Attendee {
+                    name: Some(text.to_string()),
+                    email: Some("sam@example.com".to_string()),
+                }],
+                links: vec![],
+                awaiting_reply: false,
+                tentative: false,
+            }];
+            sanitize_calendar_events(&mut events, config);
+            let event = &events[0];
+            assert_eq!(event.location.as_deref(), Some(event.title.as_str()));
+            assert_eq!(
+                event.attendees[0].name.as_deref(),
+                Some(event.title.as_str())
+            );
+            event.title.clone()
+        }
+
+        /// The text through both prompt paths, which must agree
+        fn scrub(text: &str, config: &SanitizationConfig) -> String {
+            let scrubbed = scrub_task(text, config);
+            assert_eq!(scrub_event(text, config), scrubbed);
+            scrubbed
+        }
+
+        proptest! {
+            #[test]
+            fn prop_standard_removes_emails_and_phones(
+                (with_email, _) in title_with(email()),
+                (with_phone, _) in title_with(phone()),
+                strict in any::<bool>(),
+            ) {
+                let level = if strict { SanitizationLevel::Strict } else { SanitizationLevel::Standard };
+                let config = configured(level, vec![]);
+                let out = scrub(&with_email, &config);
+                prop_assert!(!out.contains('@'), "{:?} -> {:?}", with_email, out);
+                let out = scrub(&with_phone, &config);
+                prop_assert!(!out.chars().any(|c| c.is_ascii_digit()), "{:?} -> {:?}", with_phone, out);
+            }
+
+            #[test]
+            fn prop_links_and_passcodes_go_only_at_strict(
+                (with_url, url) in title_with(url_without_digits()),
+                (with_credential, credential) in title_with(credential_without_digits()),
+            ) {
+                let standard = configured(SanitizationLevel::Standard, vec![]);
+                prop_assert_eq!(scrub(&with_url, &standard), with_url.clone());
+                prop_assert_eq!(scrub(&with_credential, &standard), with_credential.clone());
+
+                let strict = configured(SanitizationLevel::Strict, vec![]);
+                let out = scrub(&with_url, &strict);
+                prop_assert!(!out.contains(&url), "{:?} -> {:?}", with_url, out);
+                let value = credential.rsplit(' ').next().unwrap();
+                let out = scrub(&with_credential, &strict);
+                prop_assert!(
+                    !out.split_whitespace().any(|w| w == value),
+                    "{:?} -> {:?}",
+                    with_credential,
+                    out
+                );
+            }
+
+            #[test]
+            fn prop_custom_terms_never_survive(
+                (title, term) in title_with(
+                    "[A-Za-z]{4,10}".prop_filter("part of the marker", |t| !REDACTED.contains(t.as_str()))
+                ),
+                level in level(),
+            ) {
+                let config = configured(level, vec![regex::escape(&term)]);
+                let out = scrub(&title, &config);
+                prop_assert!(!out.contains(&term), "{:?} -> {:?}", title, out);
+            }
+
+            #[test]
+            fn prop_off_without_rules_leaves_text_alone(
+                (title, _) in title_with(prop_oneof![email(), phone(), url(), credential()]),
+            ) {
+                let config = configured(SanitizationLevel::Off, vec![]);
+                prop_assert_eq!(scrub(&title, &config), title);
+            }
+
+            #[test]
+            fn prop_no_email_survives((title, email) in title_with(email())) {
+                let out = strict(&title);
+                prop_assert!(!out.contains('@'), "{:?} -> {:?}", title, out);
+                prop_assert!(!out.contains(&email));
+            }
+
+            #[test]
+            fn prop_no_phone_survives((title, _) in title_with(phone())) {
+                let out = strict(&title);
+                prop_assert!(!out.chars().any(|c| c.is_ascii_digit()), "{:?} -> {:?}", title, out);
+            }
+
+            #[test]
+            fn prop_no_url_survives((title, url) in title_with(url())) {
+                let out = strict(&title).to_lowercase();
+                prop_assert!(!out.contains("://") && !out.contains("www."), "{:?} -> {:?}", title, out);
+                prop_assert!(!out.contains(&url.to_lowercase()));
+            }
+
+            #[test]
+            fn prop_no_credential_survives((title, credential) in title_with(credential())) {
+                let value = credential.rsplit([' ', ':', '#']).next().unwrap();
+                let out = strict(&title);
+                prop_assert!(
+                    !out.split_whitespace().any(|w| w == value),
+                    "{:?} -> {:?}",
+                    title,
+                    out
+                );
+            }
+
+            #[test]
+            fn prop_plain_titles_pass_through(words in words()) {
+                let title = words.join(" ");
+                prop_assert_eq!(strict(&title), title);
+            }
+
+            #[test]
+            fn prop_keyword_prefixed_words_are_kept(
+                prefix in "(pin|Pin|code|Code|password)",
+                rest in "[a-z]{1,6}",
+                after in words(),
+            ) {
+                let title = format!("{}{} {}", prefix, rest, after.join(" "));
+                prop_assert_eq!(strict(&title), title.trim_end());
+            }
+
+            #[test]
+            fn prop_strict_is_idempotent(
+                (title, _) in title_with(prop_oneof![email(), phone(), url(), credential()])
+            ) {
+                let once = strict(&title);
+                prop_assert_eq!(strict(&once), once.clone());
+            }
+
+            #[test]
+            fn prop_location_shares_only_place_names(
+                (place, detail) in title_with(prop_oneof![email(), phone(), url()]),
+                street in "[0-9]{1,4} [A-Z][a-z]{2,8} St",
+            ) {
+                let shared = location(&format!("{}, {}", place, street));
+                if let Some(shared) = shared {
+                    prop_assert!(!shared.contains(&street));
+                    prop_assert!(!shared.contains(&detail));
+                    prop_assert!(!shared.starts_with(|c: char| c.is_ascii_digit()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_calendar_and_injected_use_their_own_profiles() {
+        use crate::people::Attendee;
+        use chrono::Utc;
+
+        let mut events = vec![CalendarEventSummary {
+            id: "e1".to_string(),
+            title: "Call Priya 555-123-4567".to_string(),
+            start_time: Utc::now(),
+            end_time: None,
+            location: Some("https://zoom.us/j/123".to_string()),
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![
+                Attendee {
+                    name: Some("Sam (sam@example.com)".to_string()),
+                    email: Some("sam@example.com".to_string()),
+                },
+                Attendee {
+                    name: None,
+                    email: Some("priya@example.com".to_string()),
+                },
+            ],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }];
+        let mut injected = vec![InjectedContext {
+            source_id: "doorbell".to_string(),
+            content: "\"Courier left a card, call 555-987-6543\"".to_string(),
+            expires_at: Utc::now(),
+        }];
+
+        // Off by default: passed through
+        let mut config = SanitizationConfig::default();
+        sanitize_calendar_events(&mut events, &config);
+        assert_eq!(events[0].title, "Call Priya 555-123-4567");
+
+        let strict = SanitizationProfile {
+            level: SanitizationLevel::Strict,
+            redact: vec![],
+        };
+        config
+            .sources
+            .insert("calendar".to_string(), strict.clone());
+        config.sources.insert("injected".to_string(), strict);
+        sanitize_calendar_events(&mut events, &config);
+        sanitize_injected(&mut injected, &config);
+        let event = &events[0];
+        assert_eq!(event.title, "Call Priya [redacted]");
+        assert_eq!(event.location.as_deref(), Some("[redacted]"));
+        assert_eq!(event.attendees[0].name.as_deref(), Some("Sam ([redacted])"));
+        // Addresses stay for matching; the address-only attendee loses its label
+        assert_eq!(event.attendees[0].email.as_deref(), Some("sam@example.com"));
+        assert_eq!(event.attendees[1].label(), "[redacted]");
+        assert_eq!(
+            injected[0].summary(),
+            "Courier left a card, call [redacted]"
+        );
+    }
+}
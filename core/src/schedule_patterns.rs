@@ -0,0 +1,389 @@
+//! Habits learned from the meeting log, and the days that break them.
+//!
+//! Each night the last [`LOOKBACK_WEEKS`] of logged primary-calendar events
+//! are reduced to patterns, stored in `user_patterns`: when the first event
+//! of each weekday usually starts, and routines (the same title at the same
+//! time on the same weekday, at least [`MIN_OCCURRENCES`] times). Upcoming
+//! events are checked against them locally, so the prompt is told outright
+//! when a day starts unusually early or a meeting lands in a routine's slot
+//! (when the routine itself isn't on the calendar that day; a clash with it
+//! is already an overlap), rather than leaving the AI to notice. A new
+//! anomaly is a significant change of its own.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::database::{MeetingLogEntry, UserPattern};
+use crate::significance_engine::CalendarEventSummary;
+
+/// How far back the meeting log is read
+pub const LOOKBACK_WEEKS: i64 = 8;
+
+/// Days a pattern must have been seen on
+pub const MIN_OCCURRENCES: usize = 3;
+
+/// How much earlier than usual a day must start to be flagged
+const EARLY_START_MINUTES: u32 = 60;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Pattern {
+    /// The first event on `weekday` (0 = Monday) usually starts `minutes`
+    /// after midnight (the median)
+    UsualStart { weekday: u32, minutes: u32 },
+    /// `title` usually runs from `start` to `end` (minutes after midnight)
+    /// on `weekday`
+    Routine {
+        title: String,
+        weekday: u32,
+        start: u32,
+        end: u32,
+    },
+}
+
+impl Pattern {
+    fn kind(&self) -> &'static str {
+        match self {
+            Pattern::UsualStart { .. } => "usual_start",
+            Pattern::Routine { .. } => "routine",
+        }
+    }
+}
+
+/// Something about the coming day that breaks a pattern
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ScheduleAnomaly {
+    /// Stays the same across refreshes, so each anomaly fires once
+    pub id: String,
+    pub description: String,
+}
+
+fn minutes_of<Tz: TimeZone>(t: &DateTime<Tz>) -> u32 {
+    t.hour() * 60 + t.minute()
+}
+
+fn clock(minutes: u32) -> String {
+    NaiveTime::from_hms_opt(minutes / 60 % 24, minutes % 60, 0)
+        .unwrap_or_default()
+        .format("%-I:%M %p")
+        .to_string()
+}
+
+fn same_title(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| {
+        s.split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+fn stored(pattern: Pattern, days: &BTreeSet<NaiveDate>, last_seen: DateTime<Utc>) -> UserPattern {
+    UserPattern {
+        pattern_type: pattern.kind().to_string(),
+        pattern_data: serde_json::to_string(&pattern).unwrap_or_default(),
+        occurrences: days.len() as u32,
+        last_seen,
+        confidence: (days.len() as f64 / LOOKBACK_WEEKS as f64).min(1.0),
+    }
+}
+
+/// Normalized title, weekday, start and end minutes
+type SlotKey = (String, u32, u32, u32);
+
+/// The title as first written, the days seen and the latest start
+type Slot = (String, BTreeSet<NaiveDate>, DateTime<Utc>);
+
+/// Patterns in the logged `meetings`, with times in `tz`
+pub fn learn<Tz: TimeZone>(meetings: &[MeetingLogEntry], tz: &Tz) -> Vec<UserPattern> {
+    let mut sorted: Vec<&MeetingLogEntry> = meetings.iter().collect();
+    sorted.sort_by_key(|m| m.start_time);
+
+    // First start of each day, and each (title, weekday, time) seen
+    let mut first_starts: BTreeMap<NaiveDate, (u32, DateTime<Utc>)> = BTreeMap::new();
+    let mut slots: BTreeMap<SlotKey, Slot> = BTreeMap::new();
+    for meeting in sorted {
+        let start = meeting.start_time.with_timezone(tz);
+        let end = meeting.end_time.with_timezone(tz);
+        let date = start.date_naive();
+        first_starts
+            .entry(date)
+            .or_insert((minutes_of(&start), meeting.start_time));
+        // Routines end the day they start
+        if end.date_naive() != date {
+            continue;
+        }
+        let weekday = date.weekday().num_days_from_monday();
+        let key = (
+            meeting
+                .title
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .to_lowercase(),
+            weekday,
+            minutes_of(&start),
+            minutes_of(&end),
+        );
+        let slot = slots
+            .entry(key)
+            .or_insert_with(|| (meeting.title.clone(), BTreeSet::new(), meeting.start_time));
+        slot.1.insert(date);
+        slot.2 = meeting.start_time;
+    }
+
+    let mut patterns = Vec::new();
+    for weekday in 0..7 {
+        let days: Vec<(&NaiveDate, &(u32, DateTime<Utc>))> = first_starts
+            .iter()
+            .filter(|(date, _)| date.weekday().num_days_from_monday() == weekday)
+            .collect();
+        if days.len() < MIN_OCCURRENCES {
+            continue;
+        }
+        let mut starts: Vec<u32> = days.iter().map(|(_, (minutes, _))| *minutes).collect();
+        starts.sort_unstable();
+        let dates: BTreeSet<NaiveDate> = days.iter().map(|(date, _)| **date).collect();
+        let last_seen = days
+            .iter()
+            .map(|(_, (_, at))| *at)
+            .max()
+            .unwrap_or_default();
+        patterns.push(stored(
+            Pattern::UsualStart {
+                weekday,
+                minutes: starts[starts.len() / 2],
+            },
+            &dates,
+            last_seen,
+        ));
+    }
+    for ((_, weekday, start, end), (title, dates, last_seen)) in slots {
+        if dates.len() >= MIN_OCCURRENCES {
+            patterns.push(stored(
+                Pattern::Routine {
+                    title,
+                    weekday,
+                    start,
+                    end,
+                },
+                &dates,
+                last_seen,
+            ));
+        }
+    }
+    patterns
+}
+
+/// Stored patterns this version understands
+pub fn parse(stored: &[UserPattern]) -> Vec<Pattern> {
+    stored
+        .iter()
+        .filter_map(|p| serde_json::from_str(&p.pattern_data).ok())
+        .collect()
+}
+
+/// Where the upcoming `events` break `patterns`, as of `now` in `tz`
+pub fn detect<Tz: TimeZone>(
+    patterns: &[Pattern],
+    events: &[CalendarEventSummary],
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<ScheduleAnomaly> {
+    // The events patterns are learned from: timed, on the primary calendar
+    let timed: Vec<&CalendarEventSummary> = events
+        .iter()
+        .filter(|e| !e.is_all_day && !e.tentative && e.is_own_calendar && e.is_primary_calendar)
+        .collect();
+    let mut days: BTreeMap<NaiveDate, Vec<&CalendarEventSummary>> = BTreeMap::new();
+    for event in &timed {
+        days.entry(event.start_time.with_timezone(tz).date_naive())
+            .or_default()
+            .push(event);
+    }
+
+    let mut anomalies = Vec::new();
+    for (date, mut day) in days {
+        day.sort_by_key(|e| e.start_time);
+        let weekday = date.weekday().num_days_from_monday();
+        let day_label = date.format("%a").to_string();
+
+        // A day already under way has started as it has
+        if day[0].start_time > now {
+            let first = day[0];
+            let first_minutes = minutes_of(&first.start_time.with_timezone(tz));
+            let usual = patterns.iter().find_map(|p| match p {
+                Pattern::UsualStart {
+                    weekday: w,
+                    minutes,
+                } if *w == weekday => Some(*minutes),
+                _ => None,
+            });
+            if let Some(usual) = usual.filter(|u| first_minutes + EARLY_START_MINUTES <= *u) {
+                let earlier = usual - first_minutes;
+                anomalies.push(ScheduleAnomaly {
+                    id: format!("early:{}:{}", date, first.id),
+                    description: format!(
+                        "{} starts early: \"{}\" at {} is {}h{:02} before the usual {}",
+                        day_label,
+                        first.title,
+                        clock(first_minutes),
+                        earlier / 60,
+                        earlier % 60,
+                        clock(usual)
+                    ),
+                });
+            }
+        }
+
+        for pattern in patterns {
+            let Pattern::Routine {
+                title,
+                weekday: w,
+                start,
+                end,
+            } = pattern
+            else {
+                continue;
+            };
+            if *w != weekday || day.iter().any(|e| same_title(&e.title, title)) {
+                continue;
+            }
+            let slot_start = date.and_time(NaiveTime::MIN) + Duration::minutes(*start as i64);
+            let slot_end = date.and_time(NaiveTime::MIN) + Duration::minutes(*end as i64);
+            for event in day.iter().filter(|e| e.start_time > now) {
+                let start_local = event.start_time.with_timezone(tz).naive_local();
+                let end_local = event
+                    .end_time
+                    .unwrap_or(event.start_time)
+                    .with_timezone(tz)
+                    .naive_local();
+                if start_local < slot_end && slot_start < end_local.max(start_local) {
+                    anomalies.push(ScheduleAnomaly {
+                        id: format!("routine:{}:{}:{}", date, title.to_lowercase(), event.id),
+                        description: format!(
+                            "\"{}\" at {} {} falls in the usual \"{}\" slot ({}-{})",
+                            event.title,
+                            day_label,
+                            clock(minutes_of(&event.start_time.with_timezone(tz))),
+                            title,
+                            clock(*start),
+                            clock(*end)
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(id: &str, title: &str, start: DateTime<Utc>, minutes: i64) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: id.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::minutes(minutes)),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_learns_habits_and_flags_breaks() {
+        let tz: chrono_tz::Tz = "America/Denver".parse().unwrap();
+        let at =
+            |d: u32, h: u32, m: u32| tz.with_ymd_and_hms(2026, 5, d, h, m, 0).unwrap().to_utc();
+        // Four Tuesdays: standup at 9 (8:30 once), gym 6-7 PM on three of them
+        let mut meetings = Vec::new();
+        for (i, day) in [5, 12, 19, 26].into_iter().enumerate() {
+            let standup = if i == 1 {
+                at(day, 8, 30)
+            } else {
+                at(day, 9, 0)
+            };
+            meetings.push(MeetingLogEntry {
+                event_id: format!("s{}", day),
+                title: "Standup".to_string(),
+                start_time: standup,
+                end_time: standup + Duration::minutes(15),
+            });
+            if i < 3 {
+                meetings.push(MeetingLogEntry {
+                    event_id: format!("g{}", day),
+                    title: "Gym".to_string(),
+                    start_time: at(day, 18, 0),
+                    end_time: at(day, 19, 0),
+                });
+            }
+        }
+        let stored = learn(&meetings, &tz);
+        let patterns = parse(&stored);
+        assert_eq!(
+            patterns,
+            [
+                Pattern::UsualStart {
+                    weekday: 1,
+                    minutes: 9 * 60
+                },
+                Pattern::Routine {
+                    title: "Gym".to_string(),
+                    weekday: 1,
+                    start: 18 * 60,
+                    end: 19 * 60
+                },
+                Pattern::Routine {
+                    title: "Standup".to_string(),
+                    weekday: 1,
+                    start: 9 * 60,
+                    end: 9 * 60 + 15
+                },
+            ]
+        );
+        assert_eq!(stored[0].occurrences, 4);
+        assert_eq!(stored[1].confidence, 3.0 / 8.0);
+
+        // The next Tuesday starts at 7:15 and a review runs into gym time
+        let now = at(26, 20, 0) + Duration::days(6);
+        let events = [
+            summary("a", "Vendor call", at(26, 7, 15) + Duration::weeks(1), 30),
+            summary("b", "Standup", at(26, 9, 0) + Duration::weeks(1), 15),
+            summary(
+                "c",
+                "Quarterly review",
+                at(26, 17, 30) + Duration::weeks(1),
+                60,
+            ),
+        ];
+        let anomalies = detect(&patterns, &events, now, &tz);
+        let descriptions: Vec<&str> = anomalies.iter().map(|a| a.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "Tue starts early: \"Vendor call\" at 7:15 AM is 1h45 before the usual 9:00 AM",
+                "\"Quarterly review\" at Tue 5:30 PM falls in the usual \"Gym\" slot (6:00 PM-7:00 PM)",
+            ]
+        );
+        assert_eq!(anomalies[1].id, "routine:2026-06-02:gym:c");
+
+        // Gym on the calendar: the clash is an ordinary overlap, not an anomaly
+        let mut with_gym = events.to_vec();
+        with_gym.push(summary("d", "gym", at(26, 18, 0) + Duration::weeks(1), 60));
+        assert_eq!(detect(&patterns, &with_gym, now, &tz).len(), 1);
+    }
+}
@@ -0,0 +1,991 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+use crate::activity::{WeatherRules, WeatherThreshold};
+use crate::attachments::EventLink;
+use crate::context_sources::{self, UnitSystem};
+use crate::database::{EventChange, EventChangeKind};
+use crate::injected_context::InjectedContext;
+use crate::people::Attendee;
+use crate::schedule_patterns::ScheduleAnomaly;
+
+/// Represents a snapshot of context at a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSnapshot {
+    pub calendar_events: Vec<CalendarEventSummary>,
+    pub weather: Option<WeatherSummary>,
+    pub tasks: Vec<TaskSummary>,
+    /// Full notes context (projects, relationships, focus areas) — passed through to AI prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes_context: Option<context_sources::NotesContext>,
+    /// Full weather context (forecasts, alerts) — passed through to AI prompt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather_context: Option<context_sources::WeatherContext>,
+    /// School closures, delays and early dismissals — any new one fires at once
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub school_alerts: Vec<context_sources::SchoolAlert>,
+    /// Context pushed in over D-Bus that hasn't expired yet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub injected: Vec<InjectedContext>,
+    /// Where the coming day breaks a learned habit (see `schedule_patterns`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedule_anomalies: Vec<ScheduleAnomaly>,
+    /// Slack DMs and mentions waiting on a reply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unanswered: Vec<context_sources::UnansweredMessage>,
+    pub timestamp: DateTime<Utc>,
+    pub context_hash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
+pub struct CalendarEventSummary {
+    pub id: String,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: Option<DateTime<Utc>>,
+    pub location: Option<String>,
+    pub is_all_day: bool,
+    /// Human-readable calendar name (e.g. "Christen" instead of an email address)
+    pub calendar_name: Option<String>,
+    /// The calendar's color (e.g. "#9fe1e7"), for frontends that style by calendar
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calendar_color: Option<String>,
+    /// True if access_role == "owner" — this is the user's own calendar
+    pub is_own_calendar: bool,
+    /// True if this is the user's primary (personal) calendar, not just any owned calendar
+    pub is_primary_calendar: bool,
+    /// Travel time from home to this event's location (enriched post-hashing, always None during hash)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub travel_time: Option<TravelTimeInfo>,
+    /// Who else is invited, excluding the user
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attendees: Vec<Attendee>,
+    /// Agenda docs, tickets and other links on the event
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<EventLink>,
+    /// An invitation the user hasn't answered yet
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub awaiting_reply: bool,
+    /// Tentatively accepted (or demoted per `[attendance]`); not counted in conflicts
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tentative: bool,
+}
+
+/// Travel time information attached to a calendar event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TravelTimeInfo {
+    pub duration_minutes: i32,
+    /// Traffic-aware duration (DRIVE mode only)
+    pub duration_in_traffic_minutes: Option<i32>,
+    pub distance_km: f32,
+    /// "home" for now; future: previous event title for chaining
+    pub origin_label: String,
+    /// Human-readable mode label: "drive", "transit", "walk", "bike ride"
+    pub travel_mode_label: String,
+}
+
+// Manual PartialEq and Hash impls so travel_time doesn't affect significance detection
+impl PartialEq for TravelTimeInfo {
+    fn eq(&self, other: &Self) -> bool {
+        self.duration_minutes == other.duration_minutes && self.origin_label == other.origin_label
+    }
+}
+
+impl std::hash::Hash for TravelTimeInfo {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.duration_minutes.hash(state);
+        self.origin_label.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
+pub struct WeatherSummary {
+    pub condition: String,
+    pub temperature: i32, // Use integer to avoid floating point hash issues
+    pub feels_like: i32,
+    /// Unit system `temperature` and `feels_like` are expressed in
+    #[serde(default)]
+    pub units: UnitSystem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Hash)]
+pub struct TaskSummary {
+    pub id: String,
+    pub title: String,
+    pub due: Option<DateTime<Utc>>,
+    pub completed: bool,
+    /// 1-10, as on `context_sources::Task`
+    #[serde(default)]
+    pub priority: i32,
+    /// Priority raised by time overdue and nearby related events (see
+    /// `task_urgency`); what everything ranking tasks goes by
+    #[serde(default)]
+    pub urgency: i32,
+    /// Where the task lives: "todoist", "obsidian", "local"
+    #[serde(default)]
+    pub source: String,
+}
+
+/// Types of significant changes that warrant an AI call
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignificantChange {
+    NewCalendarEvent(String),
+    CancelledCalendarEvent(String),
+    EventTimeChanged {
+        event_id: String,
+        time_diff_hours: f64,
+    },
+    EventLocationChanged {
+        event_id: String,
+    },
+    WeatherConditionChanged {
+        from: String,
+        to: String,
+    },
+    WeatherTemperatureChanged {
+        diff: i32,
+        units: UnitSystem,
+    },
+    NewTask(String),
+    TaskCompleted(String),
+    TaskDueChanged {
+        task_id: String,
+        time_diff_hours: f64,
+    },
+    /// A school district announced a closure, delay or early dismissal
+    SchoolAlert {
+        district: String,
+        title: String,
+    },
+    /// A script pushed new or changed context over D-Bus
+    InjectedContext {
+        source_id: String,
+    },
+    /// The coming day breaks a learned habit
+    ScheduleAnomaly(String),
+    /// A message has waited on a reply long enough to mention
+    UnansweredMessage {
+        from: String,
+    },
+    InitialContext,
+}
+
+/// Changes held back until the context settles
+#[derive(Debug, Clone)]
+struct Burst {
+    first_change: DateTime<Utc>,
+    last_change: DateTime<Utc>,
+    changes: Vec<SignificantChange>,
+}
+
+/// Engine that determines if context changes are significant enough to warrant an AI call
+pub struct SignificanceEngine {
+    last_snapshot: parking_lot::Mutex<Option<ContextSnapshot>>,
+    last_ai_call: parking_lot::Mutex<Option<DateTime<Utc>>>,
+    /// Event changes reported by calendar sync since the last analysis
+    pending_event_changes: parking_lot::Mutex<Vec<EventChange>>,
+    min_time_between_calls: Duration,
+    /// (settle window, max wait) when coalescing is on
+    coalescing: parking_lot::Mutex<Option<(Duration, Duration)>>,
+    burst: parking_lot::Mutex<Option<Burst>>,
+    /// Per-activity weather thresholds; without them every swing counts
+    weather_rules: parking_lot::Mutex<Option<WeatherRules>>,
+}
+
+impl Default for SignificanceEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SignificanceEngine {
+    pub fn new() -> Self {
+        Self {
+            last_snapshot: parking_lot::Mutex::new(None),
+            last_ai_call: parking_lot::Mutex::new(None),
+            pending_event_changes: parking_lot::Mutex::new(Vec::new()),
+            min_time_between_calls: Duration::minutes(5), // Don't call AI more than once per 5 minutes
+            coalescing: parking_lot::Mutex::new(None),
+            burst: parking_lot::Mutex::new(None),
+            weather_rules: parking_lot::Mutex::new(None),
+        }
+    }
+
+    /// Count weather changes only when they matter for the activities coming up
+    pub fn with_weather_rules(mut self, rules: WeatherRules) -> Self {
+        *self.weather_rules.get_mut() = Some(rules);
+        self
+    }
+
+    /// Hold significant changes until none has arrived for `settle` (or the
+    /// first is `max_wait` old), then report them together
+    pub fn with_coalescing(mut self, settle: Duration, max_wait: Duration) -> Self {
+        *self.coalescing.get_mut() = Some((settle, max_wait));
+        self
+    }
+
+    /// Replace the weather rules and coalescing windows of a running engine
+    /// (after a config change); what has been seen so far is kept
+    pub fn reconfigure(
+        &self,
+        weather_rules: Option<WeatherRules>,
+        coalescing: Option<(Duration, Duration)>,
+    ) {
+        *self.weather_rules.lock() = weather_rules;
+        *self.coalescing.lock() = coalescing;
+    }
+
+    /// Whether changes are waiting for the context to settle
+    pub fn is_settling(&self) -> bool {
+        self.burst.lock().is_some()
+    }
+
+    /// Analyze a new context snapshot and determine if changes are significant
+    pub fn analyze_context(&self, new_snapshot: ContextSnapshot) -> (bool, Vec<SignificantChange>) {
+        // Clone the previous snapshot (if any) and release the lock immediately
+        let previous = self.last_snapshot.lock().clone();
+
+        let Some(ref last) = previous else {
+            info!("Initial context detected - significant by default");
+            self.pending_event_changes.lock().clear();
+            *self.last_snapshot.lock() = Some(new_snapshot);
+            return (true, vec![SignificantChange::InitialContext]);
+        };
+
+        // A school alert reshuffles the whole day: it skips the cooldown and
+        // doesn't wait for the context to settle
+        let school_changes =
+            Self::check_school_alerts(&last.school_alerts, &new_snapshot.school_alerts);
+        let urgent = !school_changes.is_empty();
+
+        // Check minimum time between AI calls (a settling burst is already past it)
+        if !urgent && !self.is_settling() {
+            let last_ai_call = self.last_ai_call.lock();
+            if let Some(last_call) = *last_ai_call {
+                let time_since_last = Utc::now() - last_call;
+                if time_since_last < self.min_time_between_calls {
+                    debug!(
+                        "Skipping analysis - too soon since last AI call ({} seconds ago)",
+                        time_since_last.num_seconds()
+                    );
+                    return (false, vec![]);
+                }
+            }
+        }
+
+        let mut changes = school_changes;
+        changes.extend(Self::check_injected_context(
+            &last.injected,
+            &new_snapshot.injected,
+        ));
+        changes.extend(Self::check_schedule_anomalies(
+            &last.schedule_anomalies,
+            &new_snapshot.schedule_anomalies,
+        ));
+        changes.extend(Self::check_unanswered(
+            &last.unanswered,
+            &new_snapshot.unanswered,
+        ));
+
+        // Check calendar changes
+        changes.extend(
+            self.check_calendar_changes(&last.calendar_events, &new_snapshot.calendar_events),
+        );
+
+        // Merge what calendar sync reported; the snapshot diff may already cover some of it
+        let pending = std::mem::take(&mut *self.pending_event_changes.lock());
+        for change in self.sync_changes_to_significant(&pending) {
+            if !changes.contains(&change) {
+                changes.push(change);
+            }
+        }
+
+        // Check weather changes
+        if let (Some(ref old_weather), Some(ref new_weather)) =
+            (&last.weather, &new_snapshot.weather)
+        {
+            let threshold = self
+                .weather_rules
+                .lock()
+                .as_ref()
+                .map(|rules| rules.threshold_for(&new_snapshot.calendar_events, Utc::now()));
+            changes.extend(self.check_weather_changes(old_weather, new_weather, threshold));
+        }
+
+        // Check task changes
+        changes.extend(self.check_task_changes(&last.tasks, &new_snapshot.tasks));
+
+        // Always update the snapshot to track incremental changes
+        *self.last_snapshot.lock() = Some(new_snapshot);
+
+        if urgent {
+            // Whatever was settling goes out with the alert
+            if let Some(burst) = self.burst.lock().take() {
+                for change in burst.changes {
+                    if !changes.contains(&change) {
+                        changes.push(change);
+                    }
+                }
+            }
+        } else if let Some((settle, max_wait)) = *self.coalescing.lock() {
+            changes = self.coalesce(changes, Utc::now(), settle, max_wait);
+        }
+
+        // Determine if any changes are significant
+        let is_significant = !changes.is_empty();
+
+        if is_significant {
+            info!("Significant changes detected: {:?}", changes);
+            *self.last_ai_call.lock() = Some(Utc::now());
+        } else {
+            debug!("No significant changes detected");
+        }
+
+        (is_significant, changes)
+    }
+
+    fn check_calendar_changes(
+        &self,
+        old: &[CalendarEventSummary],
+        new: &[CalendarEventSummary],
+    ) -> Vec<SignificantChange> {
+        let mut changes = Vec::new();
+
+        // Create maps for easier comparison
+        let old_map: HashMap<&str, &CalendarEventSummary> =
+            old.iter().map(|e| (e.id.as_str(), e)).collect();
+        let new_map: HashMap<&str, &CalendarEventSummary> =
+            new.iter().map(|e| (e.id.as_str(), e)).collect();
+
+        // Check for new events
+        for (id, event) in &new_map {
+            if !old_map.contains_key(id) {
+                changes.push(SignificantChange::NewCalendarEvent(event.title.clone()));
+            }
+        }
+
+        // Check for cancelled events (only flag future events — past events
+        // simply ended naturally and shouldn't be reported as cancellations)
+        let now = Utc::now();
+        for (id, event) in &old_map {
+            if !new_map.contains_key(id) && event.start_time > now {
+                changes.push(SignificantChange::CancelledCalendarEvent(
+                    event.title.clone(),
+                ));
+            }
+        }
+
+        // Check for changed events
+        for (id, new_event) in &new_map {
+            if let Some(old_event) = old_map.get(id) {
+                // Check time changes
+                let time_diff =
+                    (new_event.start_time - old_event.start_time).num_minutes() as f64 / 60.0;
+                if time_diff.abs() > 1.0 {
+                    // More than 1 hour difference
+                    changes.push(SignificantChange::EventTimeChanged {
+                        event_id: (*id).to_string(),
+                        time_diff_hours: time_diff,
+                    });
+                }
+
+                // Check location changes
+                if old_event.location != new_event.location {
+                    changes.push(SignificantChange::EventLocationChanged {
+                        event_id: (*id).to_string(),
+                    });
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Translate sync change records into the same changes the snapshot diff would report
+    fn sync_changes_to_significant(&self, records: &[EventChange]) -> Vec<SignificantChange> {
+        let now = Utc::now().timestamp();
+        let mut changes = Vec::new();
+
+        for record in records {
+            match &record.kind {
+                EventChangeKind::Added => {
+                    changes.push(SignificantChange::NewCalendarEvent(record.title.clone()));
+                }
+                EventChangeKind::Removed if record.start_time > now => {
+                    changes.push(SignificantChange::CancelledCalendarEvent(
+                        record.title.clone(),
+                    ));
+                }
+                EventChangeKind::Removed => {}
+                EventChangeKind::Updated {
+                    previous_start,
+                    previous_location,
+                } => {
+                    let time_diff = (record.start_time - previous_start) as f64 / 3600.0;
+                    if time_diff.abs() > 1.0 {
+                        changes.push(SignificantChange::EventTimeChanged {
+                            event_id: record.source_id.clone(),
+                            time_diff_hours: time_diff,
+                        });
+                    }
+                    if *previous_location != record.location {
+                        changes.push(SignificantChange::EventLocationChanged {
+                            event_id: record.source_id.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Weather changes crossing `threshold`, or the unit system's default
+    /// temperature swing and any change of conditions without one
+    fn check_weather_changes(
+        &self,
+        old: &WeatherSummary,
+        new: &WeatherSummary,
+        threshold: Option<WeatherThreshold>,
+    ) -> Vec<SignificantChange> {
+        let mut changes = Vec::new();
+
+        // Check condition changes (sunny to rainy, etc)
+        if old.condition != new.condition {
+            if threshold.is_none_or(|t| t.conditions) {
+                changes.push(SignificantChange::WeatherConditionChanged {
+                    from: old.condition.clone(),
+                    to: new.condition.clone(),
+                });
+            } else {
+                debug!(
+                    "Weather went from {} to {}, but nothing coming up is affected",
+                    old.condition, new.condition
+                );
+            }
+        }
+
+        // Check significant temperature changes, comparing in the new snapshot's units
+        // so a units switch in config doesn't register as a huge swing
+        let old_temperature = old
+            .units
+            .convert_temperature(old.temperature as f32, new.units)
+            .round() as i32;
+        let diff = new.temperature - old_temperature;
+        let significant = match threshold {
+            None => diff.abs() > new.units.significant_temperature_delta(),
+            Some(t) if t.temperature_delta_f == 0 => false,
+            Some(t) => {
+                let diff_f = match new.units {
+                    UnitSystem::Imperial => diff as f32,
+                    UnitSystem::Metric => diff as f32 * 9.0 / 5.0,
+                };
+                diff_f.abs() >= t.temperature_delta_f as f32
+            }
+        };
+        if significant {
+            changes.push(SignificantChange::WeatherTemperatureChanged {
+                diff,
+                units: new.units,
+            });
+        }
+
+        changes
+    }
+
+    /// Alerts not in the previous snapshot
+    fn check_school_alerts(
+        old: &[context_sources::SchoolAlert],
+        new: &[context_sources::SchoolAlert],
+    ) -> Vec<SignificantChange> {
+        new.iter()
+            .filter(|alert| !old.iter().any(|o| o.id == alert.id))
+            .map(|alert| SignificantChange::SchoolAlert {
+                district: alert.district.clone(),
+                title: alert.title.clone(),
+            })
+            .collect()
+    }
+
+    /// Anomalies not in the previous snapshot
+    fn check_schedule_anomalies(
+        old: &[ScheduleAnomaly],
+        new: &[ScheduleAnomaly],
+    ) -> Vec<SignificantChange> {
+        new.iter()
+            .filter(|anomaly| !old.iter().any(|o| o.id == anomaly.id))
+            .map(|anomaly| SignificantChange::ScheduleAnomaly(anomaly.description.clone()))
+            .collect()
+    }
+
+    /// Messages that crossed the unanswered threshold since the previous
+    /// snapshot
+    fn check_unanswered(
+        old: &[context_sources::UnansweredMessage],
+        new: &[context_sources::UnansweredMessage],
+    ) -> Vec<SignificantChange> {
+        new.iter()
+            .filter(|message| !old.iter().any(|o| o.id == message.id))
+            .map(|message| SignificantChange::UnansweredMessage {
+                from: message.from.clone(),
+            })
+            .collect()
+    }
+
+    /// Injected context that is new or says something different; entries
+    /// expiring are not a change
+    fn check_injected_context(
+        old: &[InjectedContext],
+        new: &[InjectedContext],
+    ) -> Vec<SignificantChange> {
+        new.iter()
+            .filter(|entry| {
+                !old.iter()
+                    .any(|o| o.source_id == entry.source_id && o.content == entry.content)
+            })
+            .map(|entry| SignificantChange::InjectedContext {
+                source_id: entry.source_id.clone(),
+            })
+            .collect()
+    }
+
+    fn check_task_changes(
+        &self,
+        old: &[TaskSummary],
+        new: &[TaskSummary],
+    ) -> Vec<SignificantChange> {
+        let mut changes = Vec::new();
+
+        let old_map: HashMap<&str, &TaskSummary> = old.iter().map(|t| (t.id.as_str(), t)).collect();
+        let new_map: HashMap<&str, &TaskSummary> = new.iter().map(|t| (t.id.as_str(), t)).collect();
+
+        // Check for new tasks
+        for (id, task) in &new_map {
+            if !old_map.contains_key(id) {
+                changes.push(SignificantChange::NewTask(task.title.clone()));
+            }
+        }
+
+        // Check for completed tasks
+        for (id, new_task) in &new_map {
+            if let Some(old_task) = old_map.get(id) {
+                if !old_task.completed && new_task.completed {
+                    changes.push(SignificantChange::TaskCompleted(new_task.title.clone()));
+                }
+
+                // Check due date changes
+                if let (Some(old_due), Some(new_due)) = (old_task.due, new_task.due) {
+                    let time_diff = (new_due - old_due).num_minutes() as f64 / 60.0;
+                    if time_diff.abs() > 1.0 {
+                        changes.push(SignificantChange::TaskDueChanged {
+                            task_id: (*id).to_string(),
+                            time_diff_hours: time_diff,
+                        });
+                    }
+                }
+            }
+        }
+
+        changes
+    }
+
+    /// Most recent context snapshot, if any has been analyzed yet
+    pub fn last_snapshot(&self) -> Option<ContextSnapshot> {
+        self.last_snapshot.lock().clone()
+    }
+
+    /// Add `changes` to the current burst; returns the whole burst once it has
+    /// settled (or waited too long), and nothing while it is still settling
+    fn coalesce(
+        &self,
+        changes: Vec<SignificantChange>,
+        now: DateTime<Utc>,
+        settle: Duration,
+        max_wait: Duration,
+    ) -> Vec<SignificantChange> {
+        let mut burst = self.burst.lock();
+        if !changes.is_empty() {
+            let pending = burst.get_or_insert_with(|| Burst {
+                first_change: now,
+                last_change: now,
+                changes: Vec::new(),
+            });
+            pending.last_change = now;
+            for change in changes {
+                if !pending.changes.contains(&change) {
+                    pending.changes.push(change);
+                }
+            }
+        }
+
+        let ready = burst
+            .as_ref()
+            .is_some_and(|b| now - b.last_change >= settle || now - b.first_change >= max_wait);
+        if !ready {
+            if let Some(ref b) = *burst {
+                debug!(
+                    "Holding {} changes until the context settles",
+                    b.changes.len()
+                );
+            }
+            return Vec::new();
+        }
+        let b = burst.take().expect("ready burst");
+        info!(
+            "Context settled: {} changes over {}s in one insight",
+            b.changes.len(),
+            (b.last_change - b.first_change).num_seconds()
+        );
+        b.changes
+    }
+
+    /// Queue per-event changes from a calendar sync for the next analysis
+    pub fn record_event_changes(&self, changes: Vec<EventChange>) {
+        if !changes.is_empty() {
+            debug!("Calendar sync reported {} event changes", changes.len());
+            self.pending_event_changes.lock().extend(changes);
+        }
+    }
+
+    /// Record that an AI call was made (used by heartbeat triggers to respect cooldown)
+    pub fn record_ai_call(&self) {
+        *self.last_ai_call.lock() = Some(Utc::now());
+    }
+
+    /// When the last AI call was made, or the last significant change found
+    pub fn last_ai_call(&self) -> Option<DateTime<Utc>> {
+        *self.last_ai_call.lock()
+    }
+
+    /// When the minimum time between AI calls runs out, if it hasn't yet
+    pub fn cooldown_until(&self) -> Option<DateTime<Utc>> {
+        self.last_ai_call()
+            .map(|last| last + self.min_time_between_calls)
+            .filter(|until| *until > Utc::now())
+    }
+
+    /// Undo the call `analyze_context` recorded for changes that were then
+    /// not sent, so the cooldown doesn't hold back the next change
+    pub fn restore_ai_call(&self, last_call: Option<DateTime<Utc>>) {
+        *self.last_ai_call.lock() = last_call;
+    }
+
+    /// Force the next context to be considered significant (useful after cache clear)
+    pub fn reset(&self) {
+        *self.last_snapshot.lock() = None;
+        *self.last_ai_call.lock() = None;
+        self.pending_event_changes.lock().clear();
+        *self.burst.lock() = None;
+        info!("Significance engine reset - next context will be considered significant");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_context_is_significant() {
+        let engine = SignificanceEngine::new();
+        let snapshot = ContextSnapshot {
+            calendar_events: vec![],
+            weather: None,
+            tasks: vec![],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: "test".to_string(),
+        };
+
+        let (is_significant, changes) = engine.analyze_context(snapshot);
+        assert!(is_significant);
+        assert_eq!(changes, vec![SignificantChange::InitialContext]);
+    }
+
+    #[test]
+    fn test_new_calendar_event_is_significant() {
+        let engine = SignificanceEngine::new();
+
+        let snapshot1 = ContextSnapshot {
+            calendar_events: vec![],
+            weather: None,
+            tasks: vec![],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: "test1".to_string(),
+        };
+        engine.analyze_context(snapshot1);
+
+        let snapshot2 = ContextSnapshot {
+            calendar_events: vec![CalendarEventSummary {
+                id: "1".to_string(),
+                title: "Meeting".to_string(),
+                start_time: Utc::now() + Duration::hours(1),
+                end_time: None,
+                location: None,
+                is_all_day: false,
+                calendar_name: None,
+                calendar_color: None,
+                is_own_calendar: true,
+                is_primary_calendar: true,
+                travel_time: None,
+                attendees: vec![],
+                links: vec![],
+                awaiting_reply: false,
+                tentative: false,
+            }],
+            weather: None,
+            tasks: vec![],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: "test2".to_string(),
+        };
+
+        // Bypass time restriction for testing
+        *engine.last_ai_call.lock() = None;
+
+        let (is_significant, changes) = engine.analyze_context(snapshot2);
+        assert!(is_significant);
+        assert!(changes
+            .iter()
+            .any(|c| matches!(c, SignificantChange::NewCalendarEvent(_))));
+    }
+
+    #[test]
+    fn test_small_time_change_not_significant() {
+        let engine = SignificanceEngine::new();
+
+        let event = CalendarEventSummary {
+            id: "1".to_string(),
+            title: "Meeting".to_string(),
+            start_time: Utc::now() + Duration::hours(2),
+            end_time: None,
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+
+        let snapshot1 = ContextSnapshot {
+            calendar_events: vec![event.clone()],
+            weather: None,
+            tasks: vec![],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: "test1".to_string(),
+        };
+        engine.analyze_context(snapshot1);
+
+        let mut event2 = event;
+        event2.start_time += Duration::minutes(30); // Only 30 min change
+
+        let snapshot2 = ContextSnapshot {
+            calendar_events: vec![event2],
+            weather: None,
+            tasks: vec![],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: vec![],
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: "test2".to_string(),
+        };
+
+        // Bypass time restriction for testing
+        *engine.last_ai_call.lock() = None;
+
+        let (is_significant, changes) = engine.analyze_context(snapshot2);
+        assert!(!is_significant);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_units_switch_is_not_a_temperature_swing() {
+        let engine = SignificanceEngine::new();
+
+        let old = WeatherSummary {
+            condition: "Clear".to_string(),
+            temperature: 68,
+            feels_like: 68,
+            units: UnitSystem::Imperial,
+        };
+        let same_in_metric = WeatherSummary {
+            temperature: 20,
+            feels_like: 20,
+            units: UnitSystem::Metric,
+            ..old.clone()
+        };
+        assert!(engine
+            .check_weather_changes(&old, &same_in_metric, None)
+            .is_empty());
+
+        // 4°C exceeds the metric threshold even though 4°F wouldn't
+        let warmer = WeatherSummary {
+            temperature: 24,
+            ..same_in_metric.clone()
+        };
+        assert_eq!(
+            engine.check_weather_changes(&same_in_metric, &warmer, None),
+            vec![SignificantChange::WeatherTemperatureChanged {
+                diff: 4,
+                units: UnitSystem::Metric,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_weather_threshold_follows_activity() {
+        let engine = SignificanceEngine::new();
+        let rules = WeatherRules::from_config(&crate::config::ActivitiesConfig::default());
+        let calls_only = rules.threshold(crate::activity::ActivityType::Remote);
+        let outdoor = rules.threshold(crate::activity::ActivityType::Outdoor);
+
+        let old = WeatherSummary {
+            condition: "Clear".to_string(),
+            temperature: 60,
+            feels_like: 60,
+            units: UnitSystem::Imperial,
+        };
+        let rainy_and_cooler = WeatherSummary {
+            condition: "Rain".to_string(),
+            temperature: 48,
+            feels_like: 45,
+            ..old.clone()
+        };
+        assert!(engine
+            .check_weather_changes(&old, &rainy_and_cooler, Some(calls_only))
+            .is_empty());
+        assert_eq!(
+            engine
+                .check_weather_changes(&old, &rainy_and_cooler, Some(outdoor))
+                .len(),
+            2
+        );
+
+        // 8°F is under the outdoor threshold
+        let cooler = WeatherSummary {
+            temperature: 52,
+            ..old.clone()
+        };
+        assert!(engine
+            .check_weather_changes(&old, &cooler, Some(outdoor))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_burst_is_held_until_settled() {
+        let engine =
+            SignificanceEngine::new().with_coalescing(Duration::minutes(2), Duration::minutes(10));
+        let (settle, max_wait) = engine.coalescing.lock().unwrap();
+        let start = Utc::now();
+        let at = |minutes| start + Duration::minutes(minutes);
+        let task = |id: &str| SignificantChange::NewTask(id.to_string());
+
+        assert!(engine
+            .coalesce(vec![task("a")], at(0), settle, max_wait)
+            .is_empty());
+        assert!(engine.is_settling());
+        assert!(engine
+            .coalesce(vec![task("b"), task("a")], at(1), settle, max_wait)
+            .is_empty());
+        // Two minutes after the latest change, not the first
+        assert!(engine.coalesce(vec![], at(2), settle, max_wait).is_empty());
+        assert_eq!(
+            engine.coalesce(vec![], at(3), settle, max_wait),
+            vec![task("a"), task("b")]
+        );
+        assert!(!engine.is_settling());
+
+        // A steady trickle still goes out once the first change is max_wait old
+        for minute in 10..20 {
+            let changes = engine.coalesce(
+                vec![task(&minute.to_string())],
+                at(minute),
+                settle,
+                max_wait,
+            );
+            assert!(changes.is_empty());
+        }
+        assert_eq!(
+            engine
+                .coalesce(vec![task("20")], at(20), settle, max_wait)
+                .len(),
+            11
+        );
+    }
+
+    #[test]
+    fn test_school_alert_skips_cooldown_and_settling() {
+        let engine =
+            SignificanceEngine::new().with_coalescing(Duration::minutes(2), Duration::minutes(10));
+        let snapshot = |alerts: Vec<context_sources::SchoolAlert>| ContextSnapshot {
+            calendar_events: vec![],
+            weather: None,
+            tasks: vec![],
+            notes_context: None,
+            weather_context: None,
+            school_alerts: alerts,
+            schedule_anomalies: vec![],
+            unanswered: vec![],
+            injected: vec![],
+            timestamp: Utc::now(),
+            context_hash: "test".to_string(),
+        };
+        let closure = context_sources::SchoolAlert {
+            id: "412".to_string(),
+            district: "Lincoln USD".to_string(),
+            kind: context_sources::SchoolAlertKind::Closure,
+            title: "All schools closed Tuesday".to_string(),
+            published: None,
+        };
+
+        engine.analyze_context(snapshot(vec![]));
+        engine.record_ai_call();
+        // Well inside the cooldown, with coalescing on
+        let (is_significant, changes) = engine.analyze_context(snapshot(vec![closure.clone()]));
+        assert!(is_significant);
+        assert_eq!(
+            changes,
+            vec![SignificantChange::SchoolAlert {
+                district: "Lincoln USD".to_string(),
+                title: "All schools closed Tuesday".to_string(),
+            }]
+        );
+        assert!(!engine.is_settling());
+
+        // The same alert on the next check is old news
+        let (is_significant, _) = engine.analyze_context(snapshot(vec![closure]));
+        assert!(!is_significant);
+    }
+}
@@ -0,0 +1,224 @@
+//! Slack: DM high-urgency insights and alerts, and mirror meetings into the
+//! user's Slack status ("In a meeting until 3").
+//!
+//! Uses a user token (`xoxp-…`, scopes `chat:write` and `users.profile:write`)
+//! from `[delivery.slack] token`, SOPS `services.slack_token` or `SLACK_TOKEN`.
+//! Statuses are set with an expiration, so Slack clears them on its own even
+//! if the daemon stops.
+
+use chrono::{DateTime, Timelike, Utc};
+use serde::Deserialize;
+
+use crate::config::SlackConfig;
+use crate::errors::{JasperError, JasperResult};
+use crate::glyphs;
+use crate::significance_engine::CalendarEventSummary;
+
+const API_BASE: &str = "https://slack.com/api";
+
+/// Slack answers 200 with `ok: false` for most failures
+#[derive(Debug, Deserialize)]
+struct SlackResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+pub struct SlackClient {
+    client: reqwest::Client,
+    token: String,
+    dm_user_id: Option<String>,
+    set_status: bool,
+    status_emoji: String,
+}
+
+impl SlackClient {
+    /// None unless a token and at least one feature are configured
+    pub fn from_config(client: reqwest::Client, config: &SlackConfig) -> Option<Self> {
+        if config.token.is_empty() || (config.dm_user_id.is_none() && !config.set_status) {
+            return None;
+        }
+        Some(Self {
+            client,
+            token: config.token.clone(),
+            dm_user_id: config.dm_user_id.clone(),
+            set_status: config.set_status,
+            status_emoji: config.status_emoji.clone(),
+        })
+    }
+
+    pub fn can_dm(&self) -> bool {
+        self.dm_user_id.is_some()
+    }
+
+    pub fn sets_status(&self) -> bool {
+        self.set_status
+    }
+
+    /// Send a direct message to the configured user
+    pub async fn send_dm(&self, text: &str) -> JasperResult<()> {
+        let Some(ref user) = self.dm_user_id else {
+            return Ok(());
+        };
+        self.call(
+            "chat.postMessage",
+            serde_json::json!({ "channel": user, "text": text }),
+        )
+        .await
+    }
+
+    /// Set the status text until `until`, or clear it when `status` is None
+    pub async fn update_status(&self, status: Option<&MeetingStatus>) -> JasperResult<()> {
+        let profile = match status {
+            Some(status) => serde_json::json!({
+                "status_text": status.text,
+                "status_emoji": self.status_emoji,
+                "status_expiration": status.until.timestamp(),
+            }),
+            None => serde_json::json!({
+                "status_text": "",
+                "status_emoji": "",
+                "status_expiration": 0,
+            }),
+        };
+        self.call(
+            "users.profile.set",
+            serde_json::json!({ "profile": profile }),
+        )
+        .await
+    }
+
+    async fn call(&self, method: &str, body: serde_json::Value) -> JasperResult<()> {
+        let response = self
+            .client
+            .post(format!("{}/{}", API_BASE, method))
+            .bearer_auth(&self.token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| JasperError::Network {
+                message: format!("Slack {} failed: {}", method, e),
+            })?;
+        if !response.status().is_success() {
+            return Err(JasperError::Api {
+                service: "slack".to_string(),
+                message: format!("{}: HTTP {}", method, response.status()),
+            });
+        }
+        let reply: SlackResponse = response.json().await.map_err(|e| JasperError::Api {
+            service: "slack".to_string(),
+            message: format!("{}: unreadable response: {}", method, e),
+        })?;
+        if !reply.ok {
+            return Err(JasperError::Api {
+                service: "slack".to_string(),
+                message: format!(
+                    "{}: {}",
+                    method,
+                    reply.error.as_deref().unwrap_or("unknown error")
+                ),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Whether an insight is worth a Slack DM: its emoji is in the "urgent" or
+/// "warning" category
+pub fn is_urgent_insight(emoji: &str) -> bool {
+    matches!(glyphs::emoji_category(emoji), Some("urgent" | "warning"))
+}
+
+/// Status derived from the calendar
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeetingStatus {
+    pub text: String,
+    pub until: DateTime<Utc>,
+}
+
+/// "In a meeting until 3" while a timed event on the primary calendar is in
+/// progress. Back-to-back meetings extend the end time.
+pub fn meeting_status<Tz: chrono::TimeZone>(
+    events: &[CalendarEventSummary],
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Option<MeetingStatus>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut meetings: Vec<(DateTime<Utc>, DateTime<Utc>)> = events
+        .iter()
+        .filter(|e| e.is_primary_calendar && !e.is_all_day)
+        .filter_map(|e| Some((e.start_time, e.end_time?)))
+        .collect();
+    meetings.sort();
+
+    let mut until = meetings
+        .iter()
+        .find(|(start, end)| *start <= now && now < *end)?
+        .1;
+    for (start, end) in &meetings {
+        if *start <= until && *end > until {
+            until = *end;
+        }
+    }
+
+    let local = until.with_timezone(tz);
+    let time = if local.minute() == 0 {
+        local.format("%-I").to_string()
+    } else {
+        local.format("%-I:%M").to_string()
+    };
+    Some(MeetingStatus {
+        text: format!("In a meeting until {}", time),
+        until,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, TimeZone};
+
+    #[test]
+    fn test_meeting_status_follows_back_to_back_meetings() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let at = |h, m| tz.with_ymd_and_hms(2026, 5, 12, h, m, 0).unwrap().to_utc();
+        let event = |title: &str, start, end, primary| CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(end),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: primary,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let events = vec![
+            event("Planning", at(14, 0), at(14, 30), true),
+            event("1:1", at(14, 30), at(15, 0), true),
+            event("Kid pickup", at(14, 0), at(16, 0), false),
+            event("Review", at(16, 0), at(16, 45), true),
+        ];
+
+        let status = meeting_status(&events, at(14, 10), &tz).unwrap();
+        assert_eq!(status.text, "In a meeting until 3");
+        assert_eq!(status.until, at(15, 0));
+        assert_eq!(
+            meeting_status(&events, at(16, 5), &tz).unwrap().text,
+            "In a meeting until 4:45"
+        );
+        assert_eq!(meeting_status(&events, at(15, 30), &tz), None);
+
+        assert!(is_urgent_insight("🚨"));
+        assert!(is_urgent_insight("⚠️"));
+        assert!(!is_urgent_insight("☕"));
+    }
+}
@@ -0,0 +1,180 @@
+//! Compact storage for the context snapshot kept with each insight.
+//!
+//! Snapshots are stored zstd-compressed. Consecutive snapshots are mostly the
+//! same, so a snapshot is stored as a JSON Patch against the last full one
+//! (the keyframe) whenever that is less than half the size; an unchanged
+//! context costs a few bytes. A snapshot over the size cap first loses the
+//! pass-through notes and weather detail (the summaries stay), and is not
+//! stored at all if it is still too big.
+
+use serde_json::Value;
+
+use crate::errors::{JasperError, JasperResult};
+use crate::storage::StorageBackend;
+
+/// Plain JSON in `snapshot_json`, from before snapshots were compressed
+pub const ENCODING_JSON: &str = "json";
+/// The whole snapshot, zstd-compressed
+pub const ENCODING_ZSTD: &str = "zstd";
+/// A zstd-compressed JSON Patch against the keyframe `base_id`
+pub const ENCODING_PATCH: &str = "zstd-patch";
+
+const LEVEL: i32 = 3;
+/// Patches stored against one keyframe before a new full snapshot is taken
+const MAX_PATCHES_PER_KEYFRAME: u32 = 50;
+/// Pass-through fields dropped, in order, from a snapshot over the size cap
+const TRIMMABLE: &[&str] = &["notes_context", "weather_context"];
+
+/// The full snapshot patches are taken against
+pub struct Keyframe {
+    pub id: i64,
+    pub snapshot: Value,
+    /// Patches already stored against it
+    pub patches: u32,
+}
+
+/// A snapshot ready to store
+pub struct Encoded {
+    pub encoding: &'static str,
+    pub data: Vec<u8>,
+    /// Keyframe a patch applies to
+    pub base_id: Option<i64>,
+}
+
+/// Encode `snapshot` for storage, as a patch against `keyframe` when that
+/// is much smaller. None if it is over `max_bytes` even when trimmed.
+pub fn encode(
+    mut snapshot: Value,
+    keyframe: Option<&Keyframe>,
+    max_bytes: usize,
+) -> JasperResult<Option<Encoded>> {
+    let mut full = compress(&snapshot)?;
+    for field in TRIMMABLE {
+        if full.len() <= max_bytes {
+            break;
+        }
+        if let Some(object) = snapshot.as_object_mut() {
+            object.remove(*field);
+        }
+        full = compress(&snapshot)?;
+    }
+    if full.len() > max_bytes {
+        return Ok(None);
+    }
+
+    if let Some(keyframe) = keyframe.filter(|k| k.patches < MAX_PATCHES_PER_KEYFRAME) {
+        let patch = json_patch::diff(&keyframe.snapshot, &snapshot);
+        let data = compress(&serde_json::to_value(&patch)?)?;
+        if data.len() < full.len() / 2 {
+            return Ok(Some(Encoded {
+                encoding: ENCODING_PATCH,
+                data,
+                base_id: Some(keyframe.id),
+            }));
+        }
+    }
+    Ok(Some(Encoded {
+        encoding: ENCODING_ZSTD,
+        data: full,
+        base_id: None,
+    }))
+}
+
+/// The snapshot stored as `data`; a patch needs its decoded keyframe as `base`
+pub fn decode(encoding: &str, data: &[u8], base: Option<&Value>) -> JasperResult<Value> {
+    match encoding {
+        ENCODING_JSON => Ok(serde_json::from_slice(data)?),
+        ENCODING_ZSTD => Ok(serde_json::from_slice(&zstd::decode_all(data)?)?),
+        ENCODING_PATCH => {
+            let mut snapshot = base.cloned().ok_or_else(|| JasperError::Internal {
+                message: "Snapshot patch without its keyframe".to_string(),
+            })?;
+            let patch: json_patch::Patch = serde_json::from_slice(&zstd::decode_all(data)?)?;
+            json_patch::patch(&mut snapshot, &patch).map_err(|e| JasperError::Internal {
+                message: format!("Snapshot patch doesn't apply: {}", e),
+            })?;
+            Ok(snapshot)
+        }
+        other => Err(JasperError::Internal {
+            message: format!("Unknown snapshot encoding '{}'", other),
+        }),
+    }
+}
+
+/// The context snapshot an insight was generated from, if one was kept
+pub fn load_for_insight(db: &dyn StorageBackend, insight_id: i64) -> JasperResult<Option<Value>> {
+    let Some(stored) = db.get_insight_snapshot(insight_id)? else {
+        return Ok(None);
+    };
+    let base = match stored.base_id {
+        Some(base_id) => {
+            let Some(keyframe) = db.get_context_snapshot(base_id)? else {
+                return Ok(None);
+            };
+            Some(decode(&keyframe.encoding, &keyframe.data, None)?)
+        }
+        None => None,
+    };
+    decode(&stored.encoding, &stored.data, base.as_ref()).map(Some)
+}
+
+fn compress(value: &Value) -> JasperResult<Vec<u8>> {
+    Ok(zstd::encode_all(value.to_string().as_bytes(), LEVEL)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn snapshot(temperature: i32) -> Value {
+        let events: Vec<Value> = (0..20)
+            .map(|i| json!({"id": format!("event-{}", i), "title": format!("Meeting {}", i)}))
+            .collect();
+        json!({
+            "calendar_events": events,
+            "weather": {"condition": "Sunny", "temperature": temperature},
+            "notes_context": {"daily_notes": "x".repeat(20_000)},
+        })
+    }
+
+    #[test]
+    fn test_unchanged_context_is_stored_as_small_patch() {
+        let first = encode(snapshot(70), None, 64 * 1024).unwrap().unwrap();
+        assert_eq!(first.encoding, ENCODING_ZSTD);
+        let keyframe = Keyframe {
+            id: 1,
+            snapshot: decode(first.encoding, &first.data, None).unwrap(),
+            patches: 0,
+        };
+        assert_eq!(keyframe.snapshot, snapshot(70));
+
+        let second = encode(snapshot(72), Some(&keyframe), 64 * 1024)
+            .unwrap()
+            .unwrap();
+        assert_eq!(second.encoding, ENCODING_PATCH);
+        assert_eq!(second.base_id, Some(1));
+        assert!(second.data.len() < first.data.len() / 2);
+        let restored = decode(second.encoding, &second.data, Some(&keyframe.snapshot)).unwrap();
+        assert_eq!(restored, snapshot(72));
+    }
+
+    #[test]
+    fn test_oversized_snapshot_is_trimmed_then_refused() {
+        let big = {
+            let mut s = snapshot(70);
+            // Incompressible enough to blow a small cap
+            s["notes_context"]["daily_notes"] = json!((0..4000)
+                .map(|i| format!("{:x}", i * 7919))
+                .collect::<String>());
+            s
+        };
+        let full = compress(&big).unwrap().len();
+        let trimmed = encode(big.clone(), None, full - 1).unwrap().unwrap();
+        let kept = decode(trimmed.encoding, &trimmed.data, None).unwrap();
+        assert!(kept.get("notes_context").is_none());
+        assert_eq!(kept["weather"]["temperature"], 70);
+
+        assert!(encode(big, None, 16).unwrap().is_none());
+    }
+}
@@ -0,0 +1,408 @@
+//! The storage interface behind the daemon.
+//!
+//! Everything that reads or writes persistent state goes through
+//! [`StorageBackend`]; [`Database`] is a shared handle to whichever backend
+//! was opened. [`DatabaseInner`](crate::database::DatabaseInner) is the
+//! SQLite backend and the default. A backend for a server database (say,
+//! Postgres for a daemon on a home server serving several clients)
+//! implements the same trait and is opened in its place; nothing above this
+//! layer names SQLite.
+//!
+//! Methods are synchronous. Async code calls them through
+//! [`blocking`](StorageBackend::blocking) so a slow query never parks a
+//! runtime worker.
+
+use crate::data_wipe::DataClass;
+use crate::database::{
+    ActiveFrontend, ApiUsage, ApiUsageSummary, CalendarEvents, Database, Digest, EscalationRecord,
+    Event, EventChange, EventRelationship, Insight, InsightMemory, Invitation, JasperItem,
+    MaintenanceRun, MeetingLink, MeetingLogEntry, StoredSnapshot, TaskLogEntry, TriggerAuditEntry,
+    UserPattern, WeekStats,
+};
+use crate::errors::{JasperError, JasperResult};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Calendar data, insights, history and registered frontends
+pub trait StorageBackend: Send + Sync {
+    /// Whether this database is discarded when dropped
+    fn is_ephemeral(&self) -> bool;
+
+    /// Events overlapping `start..=end`: starting in it, or started before
+    /// it and still going (a conference on its second day)
+    fn get_events_in_range(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<Event>>;
+
+    /// Get events in range with pagination support for large datasets
+    fn get_events_in_range_paginated(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> JasperResult<Vec<Event>>;
+
+    /// Events overlapping the range grouped by calendar, with each calendar's
+    /// metadata.
+    ///
+    /// Everything comes from a single JOIN rather than a calendar lookup per
+    /// event. `calendar_ids` and `access_roles` narrow the result (e.g. only
+    /// owned calendars); an empty slice means no filter. Groups are ordered by
+    /// calendar id and events within a group by start time.
+    fn get_events_by_calendar(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        calendar_ids: &[i64],
+        access_roles: &[&str],
+    ) -> JasperResult<Vec<CalendarEvents>>;
+
+    /// Stored relationships between events that both start in the range
+    fn get_event_relationships(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<EventRelationship>>;
+
+    /// Reconcile a calendar's stored events with a freshly fetched set.
+    ///
+    /// Events are matched by `source_id`: new ones are inserted, changed ones
+    /// (by content hash) are updated in place so their row ids and any
+    /// relationships survive, and ones no longer present are removed. Returns
+    /// one change record per affected event; unchanged events produce none.
+    fn sync_calendar_events(
+        &self,
+        calendar_db_id: i64,
+        events: &[Event],
+    ) -> JasperResult<Vec<EventChange>>;
+
+    /// Create or update calendar record
+    fn create_or_update_calendar(
+        &self,
+        calendar_id: &str,
+        calendar_name: &str,
+        calendar_type: Option<&str>,
+        access_role: Option<&str>,
+        is_primary: bool,
+    ) -> JasperResult<i64>;
+
+    /// Store how the user shows a calendar in Google Calendar. Without a
+    /// `color` the one inferred at creation is kept.
+    fn set_calendar_appearance(
+        &self,
+        calendar_db_id: i64,
+        color: Option<&str>,
+        hidden: bool,
+        selected: bool,
+    ) -> JasperResult<()>;
+
+    /// Store a new insight from AI analysis
+    fn store_insight(
+        &self,
+        emoji: &str,
+        insight: &str,
+        context_hash: Option<&str>,
+    ) -> JasperResult<i64>;
+
+    /// Record the context sources an insight's prompt drew on
+    fn store_insight_sources(
+        &self,
+        insight_id: i64,
+        sources: &BTreeMap<String, u32>,
+    ) -> JasperResult<()>;
+
+    /// Items each context source contributed to an insight's prompt; empty for
+    /// local insights and ones stored before sources were recorded
+    fn get_insight_sources(&self, insight_id: i64) -> JasperResult<BTreeMap<String, u32>>;
+
+    /// Record the (label, URI) actions for an insight, in display order
+    fn store_insight_actions(
+        &self,
+        insight_id: i64,
+        actions: &[(String, String)],
+    ) -> JasperResult<()>;
+
+    /// (label, URI) actions for an insight, in display order
+    fn get_insight_actions(&self, insight_id: i64) -> JasperResult<Vec<(String, String)>>;
+
+    /// Record a reply typed into an insight's notification
+    fn store_insight_reply(&self, insight_id: i64, reply: &str) -> JasperResult<()>;
+
+    /// (insight text, reply, replied at) for replies since `since`, oldest first
+    fn get_insight_replies_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> JasperResult<Vec<(String, String, DateTime<Utc>)>>;
+
+    /// Stored Google Calendar API data for an event, by its source ID
+    fn get_event_raw_data(&self, source_id: &str) -> JasperResult<Option<String>>;
+
+    /// Events from `since` on that the user was invited to, soonest first
+    fn get_invitations(&self, since: DateTime<Utc>) -> JasperResult<Vec<Invitation>>;
+
+    /// Record the user's answer to an invitation until the next sync
+    /// brings it back from Google
+    fn set_event_response(&self, source_id: &str, response: &str) -> JasperResult<()>;
+
+    /// The first event with a video call that hasn't ended by `now`, so a
+    /// meeting already under way can still be joined. Declined ones are skipped.
+    fn get_next_meeting(&self, now: DateTime<Utc>) -> JasperResult<Option<MeetingLink>>;
+
+    /// Record which model generated an insight
+    fn set_insight_model(&self, insight_id: i64, model: &str, fallback: bool) -> JasperResult<()>;
+
+    /// Get the latest active insight
+    fn get_latest_insight(&self) -> JasperResult<Option<Insight>>;
+
+    /// Get insight by ID
+    fn get_insight_by_id(&self, insight_id: i64) -> JasperResult<Option<Insight>>;
+
+    /// Get the N most recent insights (for deduplication in prompts)
+    fn get_recent_insights(&self, limit: u32) -> JasperResult<Vec<Insight>>;
+
+    /// Store a digest generated in the same AI call as an insight
+    fn store_digest(&self, insight_id: i64, content: &str) -> JasperResult<i64>;
+
+    /// Get the most recent digest
+    fn get_latest_digest(&self) -> JasperResult<Option<Digest>>;
+
+    /// Get a cached AI response for this prompt hash if it is younger than `max_age`
+    fn get_cached_response(
+        &self,
+        prompt_hash: &str,
+        max_age: chrono::Duration,
+    ) -> JasperResult<Option<String>>;
+
+    /// Cache an AI response, dropping entries older than `max_age`
+    fn store_cached_response(
+        &self,
+        prompt_hash: &str,
+        response: &str,
+        max_age: chrono::Duration,
+    ) -> JasperResult<()>;
+
+    /// Record a trigger decision, dropping entries older than the retention window
+    fn record_trigger(
+        &self,
+        trigger: &str,
+        decision: &str,
+        detail: Option<&str>,
+    ) -> JasperResult<i64>;
+
+    /// Remember an insight's signature, dropping ones past the retention window
+    fn record_insight_memory(
+        &self,
+        insight_id: i64,
+        emoji: &str,
+        keywords: &str,
+        summary: &str,
+    ) -> JasperResult<()>;
+
+    /// The rolling summary saved for `day` (YYYY-MM-DD), as JSON
+    fn get_day_summary(&self, day: &str) -> JasperResult<Option<String>>;
+
+    /// Save the rolling summary for `day`, replacing earlier days' summaries
+    fn store_day_summary(&self, day: &str, summary: &str) -> JasperResult<()>;
+
+    /// Insight signatures still inside the retention window, oldest first
+    fn get_insight_memory(&self) -> JasperResult<Vec<InsightMemory>>;
+
+    /// Advance the streak of every critical item present in this insight's
+    /// context and forget the ones that are gone (completed, rescheduled).
+    /// Returns the current records.
+    fn advance_escalations(
+        &self,
+        items: &[(String, String)],
+    ) -> JasperResult<Vec<EscalationRecord>>;
+
+    fn mark_escalation_notified(&self, item_key: &str) -> JasperResult<()>;
+
+    /// Stop escalating one item, or all of them; returns how many were dismissed
+    fn dismiss_escalations(&self, item_key: Option<&str>) -> JasperResult<usize>;
+
+    /// Tracked critical items, longest-running first
+    fn get_escalations(&self) -> JasperResult<Vec<EscalationRecord>>;
+
+    /// Log the meetings currently on the calendar. Future entries are replaced
+    /// wholesale, so a cancelled meeting drops out; past ones are kept.
+    fn log_meetings(&self, meetings: &[MeetingLogEntry], now: DateTime<Utc>) -> JasperResult<()>;
+
+    /// Log the tasks with a due date that a source reports right now.
+    /// Completed ones are closed, and so are open ones no longer reported
+    /// (Todoist drops closed tasks from its list).
+    fn log_tasks(&self, tasks: &[TaskLogEntry], now: DateTime<Utc>) -> JasperResult<()>;
+
+    /// Logged meetings starting in `start..end`
+    fn get_meeting_log(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<MeetingLogEntry>>;
+
+    /// Replace the learned schedule patterns (see `schedule_patterns`)
+    fn replace_user_patterns(&self, patterns: &[UserPattern]) -> JasperResult<()>;
+
+    /// The learned schedule patterns
+    fn get_user_patterns(&self) -> JasperResult<Vec<UserPattern>>;
+
+    /// Logged tasks due in `start..end`
+    fn get_task_log(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> JasperResult<Vec<TaskLogEntry>>;
+
+    fn store_week_stats(&self, stats: &WeekStats) -> JasperResult<()>;
+
+    /// The most recent weeks, oldest first
+    fn get_week_stats(&self, weeks: u32) -> JasperResult<Vec<WeekStats>>;
+
+    /// When a trigger last fired, within the audit retention window
+    fn last_trigger_fired(&self, trigger: &str) -> JasperResult<Option<DateTime<Utc>>>;
+
+    /// Delete history past its retention window from every table that keeps
+    /// it. Most are also trimmed as they are written; this catches tables that
+    /// have gone quiet (a disabled feature, a daemon that was off for weeks).
+    /// Returns the number of rows removed.
+    fn prune_expired(
+        &self,
+        now: DateTime<Utc>,
+        snapshot_retention_days: u32,
+        response_cache_ttl: Option<chrono::Duration>,
+    ) -> JasperResult<usize>;
+
+    /// Rebuild the database file to return the space deleted rows left
+    /// behind. Returns the bytes freed.
+    fn vacuum(&self) -> JasperResult<u64>;
+
+    /// Copy the write-ahead log into the database and truncate it
+    fn checkpoint_wal(&self) -> JasperResult<()>;
+
+    /// Delete everything stored for `classes`, leaving no readable copy
+    /// behind in free pages or the write-ahead log. Returns the rows removed
+    /// per class, in [`DataClass::ALL`] order.
+    fn wipe(&self, classes: &[DataClass]) -> JasperResult<Vec<(DataClass, usize)>>;
+
+    /// Store a maintenance run, keeping the most recent ones
+    fn record_maintenance_run(&self, run: &MaintenanceRun) -> JasperResult<()>;
+
+    /// The most recent maintenance run, if any has happened
+    fn latest_maintenance_run(&self) -> JasperResult<Option<MaintenanceRun>>;
+
+    /// Record one AI call's token usage, dropping entries past the retention window
+    fn record_api_usage(&self, usage: &ApiUsage) -> JasperResult<()>;
+
+    /// Usage totals for calls made since `since`
+    fn get_api_usage_summary(&self, since: DateTime<Utc>) -> JasperResult<ApiUsageSummary>;
+
+    /// Most recent trigger decisions, newest first
+    fn get_trigger_audit(&self, limit: u32) -> JasperResult<Vec<TriggerAuditEntry>>;
+
+    /// The last trigger that fired in `since..=until`
+    fn get_fired_trigger(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> JasperResult<Option<TriggerAuditEntry>>;
+
+    /// Save a reminder (no end time) or time block
+    fn add_jasper_item(
+        &self,
+        kind: &str,
+        title: &str,
+        notes: Option<&str>,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> JasperResult<i64>;
+
+    /// Remove a reminder or time block; false if there was no such id
+    fn delete_jasper_item(&self, id: i64) -> JasperResult<bool>;
+
+    /// All reminders and time blocks, earliest first
+    fn get_jasper_items(&self) -> JasperResult<Vec<JasperItem>>;
+
+    /// Store the encoded context snapshot that triggered an insight, dropping
+    /// snapshots older than `retention_days` unless a kept patch still needs them
+    fn store_context_snapshot(
+        &self,
+        insight_id: i64,
+        source: &str,
+        encoding: &str,
+        data: &[u8],
+        base_id: Option<i64>,
+        retention_days: u32,
+    ) -> JasperResult<i64>;
+
+    /// The latest full snapshot from `source`, with how many patches were stored against it
+    fn get_snapshot_keyframe(&self, source: &str) -> JasperResult<Option<(StoredSnapshot, u32)>>;
+
+    /// A stored snapshot by ID
+    fn get_context_snapshot(&self, id: i64) -> JasperResult<Option<StoredSnapshot>>;
+
+    /// The snapshot stored with an insight
+    fn get_insight_snapshot(&self, insight_id: i64) -> JasperResult<Option<StoredSnapshot>>;
+
+    /// Register a frontend as active, with the insight format it displays,
+    /// its access level and the bus name it called from
+    fn register_frontend(
+        &self,
+        frontend_id: &str,
+        pid: Option<i32>,
+        format: &str,
+        access: &str,
+        bus_name: Option<&str>,
+    ) -> JasperResult<()>;
+
+    /// Whether a guest frontend is registered under `frontend_id` or from `bus_name`
+    fn is_guest_frontend(
+        &self,
+        frontend_id: Option<&str>,
+        bus_name: Option<&str>,
+    ) -> JasperResult<bool>;
+
+    /// Whether any guest frontend is registered
+    fn has_guest_frontends(&self) -> JasperResult<bool>;
+
+    /// Unregister a frontend
+    fn unregister_frontend(&self, frontend_id: &str) -> JasperResult<()>;
+
+    /// Update frontend heartbeat
+    fn update_frontend_heartbeat(&self, frontend_id: &str) -> JasperResult<()>;
+
+    /// Get list of active frontends
+    fn get_active_frontends(&self) -> JasperResult<Vec<ActiveFrontend>>;
+
+    /// Clean up expired frontends: no heartbeat within `timeout_secs`, or a
+    /// registered PID that no longer exists (crashed without unregistering).
+    /// The timeout must comfortably exceed the poll interval (30s) plus
+    /// the daemon check interval (60s by default) to avoid race-condition expiry.
+    fn cleanup_expired_frontends(&self, timeout_secs: u64) -> JasperResult<usize>;
+
+    /// Check if any frontends are currently active
+    fn has_active_frontends(&self, timeout_secs: u64) -> JasperResult<bool>;
+}
+
+impl dyn StorageBackend {
+    /// Run a storage operation on tokio's blocking pool.
+    ///
+    /// Backends are synchronous; calling one straight from an async task
+    /// parks a runtime worker for the whole query, which during a large
+    /// calendar sync stalls timers and D-Bus handling. Async callers go
+    /// through here.
+    pub async fn blocking<F, R>(self: &Arc<Self>, operation: F) -> JasperResult<R>
+    where
+        F: FnOnce(&dyn StorageBackend) -> JasperResult<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let db: Database = Arc::clone(self);
+        tokio::task::spawn_blocking(move || operation(db.as_ref()))
+            .await
+            .map_err(|e| JasperError::Internal {
+                message: format!("Database task panicked or was cancelled: {}", e),
+            })?
+    }
+}
@@ -0,0 +1,174 @@
+//! How pressing each open task is right now.
+//!
+//! A task's urgency starts at its priority (1-10) and rises the longer it
+//! sits overdue and the sooner an event that looks related to it begins
+//! (a "Dentist" event lifts "Fill in dentist forms"). It is worked out once
+//! per context collection and stored on the task, so the prompt's ordering,
+//! the mock provider's insight and the pre-filter all rank tasks the same
+//! way; it is also written to the task log with each task. Escalation still
+//! goes by the priority the user set.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::significance_engine::{CalendarEventSummary, TaskSummary};
+
+/// Highest urgency, as for priority
+pub const MAX_URGENCY: i32 = 10;
+
+/// Words too common in titles to tie a task to an event
+const STOPWORDS: [&str; 8] = [
+    "with", "from", "this", "that", "about", "meeting", "call", "sync",
+];
+
+/// Points added for time overdue: (at least this long, boost)
+const OVERDUE_BOOSTS: [(i64, i32); 3] = [(7 * 24, 3), (3 * 24, 2), (0, 1)];
+
+/// Points added when a related event starts within: (hours, boost)
+const EVENT_BOOSTS: [(i64, i32); 2] = [(24, 2), (72, 1)];
+
+fn significant_words(title: &str) -> Vec<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 4)
+        .map(str::to_lowercase)
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect()
+}
+
+/// Whether `event` looks like it's about `task`
+fn related(task: &TaskSummary, event: &CalendarEventSummary) -> bool {
+    related_titles(&task.title, &event.title)
+}
+
+/// Whether an event called `event_title` looks like it's about a task called
+/// `task_title`: they share every significant word of a one-word event
+/// title, or at least two otherwise
+pub(crate) fn related_titles(task_title: &str, event_title: &str) -> bool {
+    let event_words = significant_words(event_title);
+    if event_words.is_empty() {
+        return false;
+    }
+    let task_words = significant_words(task_title);
+    let shared = event_words
+        .iter()
+        .filter(|w| task_words.contains(w))
+        .count();
+    shared >= event_words.len().min(2)
+}
+
+/// Urgency of `task` at `now`, given the events in view
+pub fn urgency(task: &TaskSummary, events: &[CalendarEventSummary], now: DateTime<Utc>) -> i32 {
+    let base = task.priority.clamp(1, MAX_URGENCY);
+    if task.completed {
+        return base;
+    }
+
+    let overdue = task
+        .due
+        .filter(|due| *due < now)
+        .and_then(|due| {
+            let hours = (now - due).num_hours();
+            OVERDUE_BOOSTS.iter().find(|(min, _)| hours >= *min)
+        })
+        .map_or(0, |(_, boost)| *boost);
+
+    let next_related = events
+        .iter()
+        .filter(|e| e.end_time.unwrap_or(e.start_time) >= now && related(task, e))
+        .map(|e| e.start_time.max(now))
+        .min();
+    let event = next_related
+        .and_then(|start| {
+            EVENT_BOOSTS
+                .iter()
+                .find(|(hours, _)| start - now <= Duration::hours(*hours))
+        })
+        .map_or(0, |(_, boost)| *boost);
+
+    (base + overdue + event).min(MAX_URGENCY)
+}
+
+/// Score every task and order them most urgent first (ties keep their order)
+pub fn apply(tasks: &mut [TaskSummary], events: &[CalendarEventSummary], now: DateTime<Utc>) {
+    for task in tasks.iter_mut() {
+        task.urgency = urgency(task, events, now);
+    }
+    tasks.sort_by_key(|t| std::cmp::Reverse(t.urgency));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str, priority: i32, due: Option<DateTime<Utc>>) -> TaskSummary {
+        TaskSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            due,
+            completed: false,
+            priority,
+            urgency: 0,
+            source: "local".to_string(),
+        }
+    }
+
+    fn event(title: &str, start: DateTime<Utc>) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::hours(1)),
+            location: None,
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    #[test]
+    fn test_overdue_and_related_events_raise_urgency() {
+        let now = Utc::now();
+        let events = [
+            event("Dentist", now + Duration::hours(5)),
+            event("Quarterly board meeting", now + Duration::hours(48)),
+        ];
+
+        let mut tasks = vec![
+            task("Water plants", 4, None),
+            task("Fill in dentist forms", 4, None),
+            task("Board slides for quarterly review", 4, None),
+            task("Renew library card", 4, Some(now - Duration::days(8))),
+            task("Send invoice", 4, Some(now - Duration::hours(2))),
+            task("Buy gift", 9, Some(now - Duration::days(30))),
+        ];
+        apply(&mut tasks, &events, now);
+
+        let scores: Vec<(&str, i32)> = tasks
+            .iter()
+            .map(|t| (t.title.as_str(), t.urgency))
+            .collect();
+        assert_eq!(
+            scores,
+            [
+                ("Buy gift", 10),
+                ("Renew library card", 7),
+                ("Fill in dentist forms", 6),
+                ("Board slides for quarterly review", 5),
+                ("Send invoice", 5),
+                ("Water plants", 4),
+            ]
+        );
+
+        // Finished tasks don't age
+        let mut done = task("Renew library card", 4, Some(now - Duration::days(8)));
+        done.completed = true;
+        assert_eq!(urgency(&done, &events, now), 4);
+    }
+}
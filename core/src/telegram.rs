@@ -0,0 +1,68 @@
+//! Send insights to a Telegram chat through a bot.
+//!
+//! Create a bot with @BotFather, message it once, and put its token and your
+//! chat ID under `[delivery.telegram]` (or the token in SOPS
+//! `services.telegram_bot_token` or `TELEGRAM_BOT_TOKEN`).
+
+use serde::Deserialize;
+
+use crate::config::TelegramConfig;
+use crate::errors::{JasperError, JasperResult};
+
+const API_BASE: &str = "https://api.telegram.org";
+
+/// The Bot API answers failures with `ok: false` and a description
+#[derive(Debug, Deserialize)]
+struct TelegramResponse {
+    ok: bool,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+pub struct TelegramPublisher {
+    client: reqwest::Client,
+    token: String,
+    chat_id: String,
+}
+
+impl TelegramPublisher {
+    /// None unless both a token and a chat ID are configured
+    pub fn from_config(client: reqwest::Client, config: &TelegramConfig) -> Option<Self> {
+        if config.bot_token.is_empty() || config.chat_id.is_empty() {
+            return None;
+        }
+        Some(Self {
+            client,
+            token: config.bot_token.clone(),
+            chat_id: config.chat_id.clone(),
+        })
+    }
+
+    /// Send one insight as a plain-text message
+    pub async fn publish(&self, emoji: &str, text: &str) -> JasperResult<()> {
+        let response = self
+            .client
+            .post(format!("{}/bot{}/sendMessage", API_BASE, self.token))
+            .json(&serde_json::json!({
+                "chat_id": self.chat_id,
+                "text": format!("{} {}", emoji, text).trim(),
+            }))
+            .send()
+            .await
+            .map_err(|e| JasperError::Network {
+                // reqwest errors include the URL, and with it the token
+                message: format!("Telegram send failed: {}", e.without_url()),
+            })?;
+        let status = response.status();
+        let body: Option<TelegramResponse> = response.json().await.ok();
+        match body {
+            Some(body) if body.ok => Ok(()),
+            body => Err(JasperError::Api {
+                service: "telegram".to_string(),
+                message: body
+                    .and_then(|b| b.description)
+                    .unwrap_or_else(|| format!("HTTP {}", status)),
+            }),
+        }
+    }
+}
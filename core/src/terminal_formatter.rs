@@ -0,0 +1,227 @@
+//! User-customisable one-line output for scripts and terminals.
+//!
+//! `waybar --simple` renders the latest insight through a template such as
+//! `"{emoji} {text} ({age})"`, configured as `[frontends] terminal_template`
+//! or passed with `--template`.
+
+use std::collections::HashMap;
+
+/// Placeholders the daemon fills in, with a short description for `--help`-style listings
+pub const PLACEHOLDERS: &[(&str, &str)] = &[
+    (
+        "emoji",
+        "insight emoji (or glyph when glyph mapping is enabled)",
+    ),
+    (
+        "text",
+        "insight text, shortened to the terminal length budget",
+    ),
+    (
+        "age",
+        "how long ago the insight was generated, e.g. \"12m ago\"",
+    ),
+    (
+        "stale",
+        "\"stale\" once the insight is older than `stale_after_minutes`, else empty",
+    ),
+    (
+        "next_event",
+        "next upcoming calendar event with its start time",
+    ),
+    ("tasks", "number of pending tasks"),
+    (
+        "sunrise",
+        "today's sunrise, e.g. \"6:42 AM\" (needs a weather location)",
+    ),
+    ("sunset", "today's sunset, e.g. \"7:58 PM\""),
+    ("daylight", "\"day\" or \"night\", for daylight indicators"),
+];
+
+pub const DEFAULT_TEMPLATE: &str = "{emoji} {text}";
+
+pub struct TemplateFormatter {
+    template: String,
+}
+
+/// A template rendered up to layout: its own characters, and what each
+/// placeholder became
+enum Unit {
+    Literal(char),
+    Value(String),
+}
+
+impl TemplateFormatter {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    /// Render the template. Unknown or missing placeholders become empty, a
+    /// bracket group in the template left with nothing in it ("({age})" with
+    /// no age) is dropped, and the template's whitespace is collapsed so the
+    /// line stays tidy. Values are never rewritten.
+    pub fn render(&self, fields: &HashMap<String, String>) -> String {
+        let mut units = Vec::with_capacity(self.template.len());
+        let literal = |units: &mut Vec<Unit>, text: &str| {
+            units.extend(text.replace("}}", "}").chars().map(Unit::Literal));
+        };
+        let mut rest = self.template.as_str();
+
+        while let Some(open) = rest.find('{') {
+            literal(&mut units, &rest[..open]);
+            let after = &rest[open + 1..];
+
+            // "{{" is a literal brace
+            if let Some(stripped) = after.strip_prefix('{') {
+                units.push(Unit::Literal('{'));
+                rest = stripped;
+                continue;
+            }
+
+            match after.find('}') {
+                Some(close) => {
+                    let name = after[..close].trim();
+                    units.push(Unit::Value(fields.get(name).cloned().unwrap_or_default()));
+                    rest = &after[close + 1..];
+                }
+                None => {
+                    units.extend(rest[open..].chars().map(Unit::Literal));
+                    rest = "";
+                }
+            }
+        }
+        literal(&mut units, rest);
+
+        drop_empty_groups(&mut units);
+
+        // One space wherever the template has whitespace, none at the ends
+        let mut out = String::with_capacity(units.len());
+        let mut space = false;
+        for unit in &units {
+            match unit {
+                Unit::Literal(c) if c.is_whitespace() => space = true,
+                Unit::Value(value) if value.is_empty() => {}
+                Unit::Literal(_) | Unit::Value(_) => {
+                    if space && !out.is_empty() {
+                        out.push(' ');
+                    }
+                    space = false;
+                    match unit {
+                        Unit::Literal(c) => out.push(*c),
+                        Unit::Value(value) => out.push_str(value),
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Placeholders in the template that the daemon does not provide
+    pub fn unknown_placeholders(&self) -> Vec<String> {
+        let mut unknown = Vec::new();
+        let mut rest = self.template.replace("{{", "");
+        while let Some(open) = rest.find('{') {
+            let Some(close) = rest[open..].find('}') else {
+                break;
+            };
+            let name = rest[open + 1..open + close].trim().to_string();
+            if !PLACEHOLDERS.iter().any(|(known, _)| *known == name) {
+                unknown.push(name);
+            }
+            rest = rest[open + close + 1..].to_string();
+        }
+        unknown
+    }
+}
+
+/// Remove "(…)" and "[…]" groups of the template that hold placeholders,
+/// all of them empty, and nothing else but whitespace
+fn drop_empty_groups(units: &mut Vec<Unit>) {
+    let mut i = 0;
+    while i < units.len() {
+        let close = match units[i] {
+            Unit::Literal('(') => ')',
+            Unit::Literal('[') => ']',
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let mut placeholders = 0;
+        let mut end = None;
+        for (j, unit) in units.iter().enumerate().skip(i + 1) {
+            match unit {
+                Unit::Literal(c) if *c == close => {
+                    end = Some(j);
+                    break;
+                }
+                Unit::Literal(c) if c.is_whitespace() => {}
+                Unit::Value(value) if value.is_empty() => placeholders += 1,
+                _ => break,
+            }
+        }
+        match end {
+            Some(end) if placeholders > 0 => {
+                units.drain(i..=end);
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> HashMap<String, String> {
+        [
+            ("emoji", "📅"),
+            ("text", "Standup in 10 minutes"),
+            ("age", "3m ago"),
+            ("next_event", "Standup at 10:00"),
+            ("tasks", "2"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+    }
+
+    #[test]
+    fn test_render_substitutes_placeholders() {
+        let formatter = TemplateFormatter::new("{emoji} {text} ({age}) | tasks: {tasks}");
+        assert_eq!(
+            formatter.render(&fields()),
+            "📅 Standup in 10 minutes (3m ago) | tasks: 2"
+        );
+
+        assert_eq!(
+            TemplateFormatter::new(DEFAULT_TEMPLATE).render(&fields()),
+            "📅 Standup in 10 minutes"
+        );
+    }
+
+    #[test]
+    fn test_render_handles_missing_fields_and_escapes() {
+        let mut partial = fields();
+        partial.remove("age");
+        let formatter = TemplateFormatter::new("{{{text}}} ({age}) {bogus}");
+        assert_eq!(formatter.render(&partial), "{Standup in 10 minutes}");
+        assert_eq!(formatter.unknown_placeholders(), vec!["bogus".to_string()]);
+    }
+
+    #[test]
+    fn test_render_leaves_values_alone() {
+        let mut fields = fields();
+        fields.insert(
+            "text".to_string(),
+            "Run make()  before the [] deploy".to_string(),
+        );
+        fields.remove("age");
+        let formatter = TemplateFormatter::new("  {emoji}   {text} ({age}) [ {stale} ] ()");
+        assert_eq!(
+            formatter.render(&fields),
+            "📅 Run make()  before the [] deploy ()"
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! Per-frontend insight length budgets.
+//!
+//! Each frontend has room for a different amount of text: a bar module fits
+//! about a line, a notification a short paragraph, and a terminal the whole
+//! insight. Rather than cutting at a byte offset (which can split a UTF-8
+//! character and panic), text is shortened at the last sentence or word
+//! boundary that fits the budget.
+
+use std::collections::BTreeMap;
+
+/// Built-in budgets, in characters. `None` means unlimited.
+pub fn default_budget(frontend_id: &str) -> Option<usize> {
+    match frontend_id {
+        "waybar" => Some(60),
+        "notification" => Some(200),
+        "noctalia" | "tui" | "terminal" => None,
+        _ => None,
+    }
+}
+
+/// Budget for a frontend, honouring `[frontends.max_length]` overrides where 0 means unlimited
+pub fn budget_for(frontend_id: &str, overrides: &BTreeMap<String, usize>) -> Option<usize> {
+    match overrides.get(frontend_id) {
+        Some(0) => None,
+        Some(&max) => Some(max),
+        None => default_budget(frontend_id),
+    }
+}
+
+/// Shorten `text` to at most `max_chars` characters.
+///
+/// Prefers ending on a complete sentence, then on a word boundary (with an
+/// ellipsis), and only cuts mid-word when a single word exceeds the budget.
+pub fn fit(text: &str, max_chars: Option<usize>) -> String {
+    let text = text.trim();
+    let Some(max_chars) = max_chars else {
+        return text.to_string();
+    };
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    if max_chars == 0 {
+        return String::new();
+    }
+
+    // Byte index just past the `max_chars`-th character
+    let limit = text
+        .char_indices()
+        .nth(max_chars)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let window = &text[..limit];
+
+    // A whole sentence reads better than a cut one, if it isn't too short
+    if let Some(end) = window.rfind(['.', '!', '?']) {
+        let sentence = &window[..=end];
+        if sentence.chars().count() * 2 >= max_chars {
+            return sentence.to_string();
+        }
+    }
+
+    // Leave room for the ellipsis
+    let limit = text
+        .char_indices()
+        .nth(max_chars - 1)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let window = &text[..limit];
+    let cut = match window.rfind(char::is_whitespace) {
+        Some(space) if space > 0 => window[..space].trim_end_matches([',', ';', ':', ' ', '-']),
+        _ => window,
+    };
+
+    format!("{}…", cut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_is_utf8_safe_and_respects_budget() {
+        let text = "Café déjà vu — réunion à 15h avec l'équipe produit, n'oubliez pas le café";
+        for max in 1..text.chars().count() {
+            let fitted = fit(text, Some(max));
+            assert!(fitted.chars().count() <= max, "{} > {}", fitted, max);
+        }
+        assert_eq!(fit(text, None), text);
+    }
+
+    #[test]
+    fn test_fit_prefers_sentence_then_word_boundaries() {
+        assert_eq!(
+            fit("Dentist at 3 PM. Leave by 2:30 to beat traffic.", Some(30)),
+            "Dentist at 3 PM."
+        );
+        assert_eq!(
+            fit("Standup moved to 10:30 because of the offsite", Some(24)),
+            "Standup moved to 10:30…"
+        );
+    }
+
+    #[test]
+    fn test_budget_overrides() {
+        let mut overrides = BTreeMap::new();
+        overrides.insert("waybar".to_string(), 40);
+        overrides.insert("notification".to_string(), 0);
+
+        assert_eq!(budget_for("waybar", &overrides), Some(40));
+        assert_eq!(budget_for("notification", &overrides), None);
+        assert_eq!(budget_for("notification", &BTreeMap::new()), Some(200));
+        assert_eq!(budget_for("tui", &BTreeMap::new()), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! Fits prompt context into a token budget.
+//!
+//! The prompt builder produces one section per kind of context (calendar,
+//! tasks, weather, notes...). When the combined size exceeds the configured
+//! budget, the lowest-priority sections are trimmed line by line and then
+//! dropped entirely, and whatever was cut is logged.
+
+use tracing::info;
+
+/// Section priorities; higher survives longer
+pub mod priority {
+    /// Never dropped (trigger reason, empty-schedule notice)
+    pub const REQUIRED: u32 = u32::MAX;
+    pub const SCHOOL_ALERTS: u32 = 950;
+    pub const INJECTED_CONTEXT: u32 = 920;
+    pub const SCHEDULE_SITUATIONS: u32 = 900;
+    pub const SCHEDULE_ANOMALIES: u32 = 880;
+    pub const PRIMARY_CALENDAR: u32 = 800;
+    pub const FAMILY_CALENDARS: u32 = 700;
+    pub const WEATHER_GEAR: u32 = 650;
+    pub const TASKS: u32 = 600;
+    pub const INVITATIONS: u32 = 550;
+    pub const SHARED_CALENDARS: u32 = 500;
+    pub const UNANSWERED_MESSAGES: u32 = 460;
+    pub const PEOPLE: u32 = 450;
+    pub const PROJECTS: u32 = 400;
+    pub const DAYLIGHT: u32 = 350;
+    pub const WEATHER: u32 = 300;
+    /// Daily notes start here and lose one point per day of age
+    pub const DAILY_NOTES: u32 = 200;
+}
+
+/// Rough token estimate (~4 characters per token for English text)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+#[derive(Debug, Clone)]
+pub struct PromptSection {
+    pub name: String,
+    pub priority: u32,
+    pub text: String,
+}
+
+impl PromptSection {
+    pub fn new(name: impl Into<String>, priority: u32, text: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            priority,
+            text: text.into(),
+        }
+    }
+}
+
+pub struct TokenBudgeter {
+    max_tokens: usize,
+}
+
+impl TokenBudgeter {
+    pub fn new(max_tokens: usize) -> Self {
+        Self { max_tokens }
+    }
+
+    /// Trim sections to fit the budget, preserving their original order.
+    ///
+    /// Sections are cut from the lowest priority upward: first their trailing
+    /// lines (the header line is kept with an omission note), then the whole
+    /// section. Required sections are never touched; dropped ones are left out.
+    pub fn fit(&self, mut sections: Vec<PromptSection>) -> Vec<PromptSection> {
+        let total = |sections: &[PromptSection]| -> usize {
+            sections.iter().map(|s| estimate_tokens(&s.text)).sum()
+        };
+
+        if total(&sections) <= self.max_tokens {
+            return sections;
+        }
+
+        // Indices from least to most important; stable so earlier sections win ties
+        let mut order: Vec<usize> = (0..sections.len()).collect();
+        order.sort_by_key(|&i| sections[i].priority);
+
+        let mut dropped = Vec::new();
+        for i in order {
+            let excess = total(&sections).saturating_sub(self.max_tokens);
+            if excess == 0 {
+                break;
+            }
+            if sections[i].priority == priority::REQUIRED || sections[i].text.is_empty() {
+                continue;
+            }
+
+            let section = &mut sections[i];
+            let lines: Vec<&str> = section.text.lines().collect();
+            let target = estimate_tokens(&section.text).saturating_sub(excess);
+
+            // Keep the header plus as many leading lines as fit in what's left
+            let mut kept = Vec::new();
+            let mut used = 0;
+            for line in &lines {
+                let cost = estimate_tokens(line) + 1;
+                if used + cost > target.saturating_sub(8) {
+                    break;
+                }
+                used += cost;
+                kept.push(*line);
+            }
+
+            // A header alone carries no information
+            let has_content = kept.iter().any(|l| l.trim_start().starts_with('-'));
+            if has_content {
+                let omitted = lines.len() - kept.len();
+                dropped.push(format!("{} ({} lines)", section.name, omitted));
+                section.text = format!("{}\n- ({} more omitted)", kept.join("\n"), omitted);
+            } else {
+                dropped.push(section.name.clone());
+                section.text.clear();
+            }
+        }
+
+        if !dropped.is_empty() {
+            info!(
+                "Prompt context over {} token budget; trimmed: {}",
+                self.max_tokens,
+                dropped.join(", ")
+            );
+        }
+
+        sections.retain(|s| !s.text.is_empty());
+        sections
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulleted(header: &str, items: usize) -> String {
+        let mut text = header.to_string();
+        for n in 0..items {
+            text.push_str(&format!("\n- item number {} with some padding text", n));
+        }
+        text
+    }
+
+    #[test]
+    fn test_under_budget_is_untouched() {
+        let sections = vec![
+            PromptSection::new("trigger", priority::REQUIRED, "Trigger: test"),
+            PromptSection::new("tasks", priority::TASKS, bulleted("Tasks:", 3)),
+        ];
+        let fitted = TokenBudgeter::new(1000).fit(sections.clone());
+        assert_eq!(fitted.len(), 2);
+        assert_eq!(fitted[1].text, sections[1].text);
+    }
+
+    #[test]
+    fn test_lowest_priority_trimmed_first() {
+        let sections = vec![
+            PromptSection::new("trigger", priority::REQUIRED, "Trigger: test"),
+            PromptSection::new("calendar", priority::PRIMARY_CALENDAR, bulleted("Cal:", 10)),
+            PromptSection::new("old note", priority::DAILY_NOTES - 3, bulleted("Old:", 10)),
+            PromptSection::new("new note", priority::DAILY_NOTES, bulleted("New:", 10)),
+        ];
+        let budget = 300;
+        let fitted = TokenBudgeter::new(budget).fit(sections.clone());
+
+        let total: usize = fitted.iter().map(|s| estimate_tokens(&s.text)).sum();
+        assert!(total <= budget, "{} > {}", total, budget);
+        assert_eq!(fitted[0].text, "Trigger: test");
+        assert_eq!(fitted[1].text, sections[1].text);
+        // The older note is cut before the newer one is touched
+        assert!(fitted[2].text.starts_with("Old:") && fitted[2].text.ends_with("more omitted)"));
+        assert_eq!(fitted[3].text, sections[3].text);
+    }
+}
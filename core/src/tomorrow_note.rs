@@ -0,0 +1,229 @@
+//! Tomorrow's daily note, written the evening before.
+//!
+//! With `[context_sources.obsidian.tomorrow_note]` enabled, the daemon
+//! creates tomorrow's note in the vault's daily notes folder from a template
+//! in the templates folder. The template's `{{events}}`, `{{tasks}}` and
+//! `{{weather}}` are filled in with tomorrow's schedule, the open tasks due
+//! by the end of it and the forecast; `{{date}}`, `{{title}}` and `{{time}}`
+//! work as in Obsidian's own templates. A template without the schedule
+//! placeholders gets them in a section at the end. An existing note is never
+//! touched, so a note started by hand wins.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, TimeZone};
+use std::path::{Path, PathBuf};
+
+use crate::agenda;
+use crate::context_sources::WeatherContext;
+use crate::significance_engine::{CalendarEventSummary, TaskSummary};
+
+/// Used when the configured template doesn't exist
+pub const DEFAULT_TEMPLATE: &str = "# {{date}}\n\n## Focus\n\n## Notes\n";
+
+/// What tomorrow holds, as far as Jasper knows
+pub struct Tomorrow<'a> {
+    pub date: NaiveDate,
+    pub events: &'a [CalendarEventSummary],
+    pub tasks: &'a [TaskSummary],
+    pub weather: Option<&'a WeatherContext>,
+}
+
+/// Where the note for `date` goes: `<daily folder>/YYYY-MM-DD.md`, the name
+/// the Obsidian source reads daily notes from
+pub fn note_path(vault: &Path, daily_folder: &str, date: NaiveDate) -> PathBuf {
+    vault
+        .join(daily_folder)
+        .join(format!("{}.md", date.format("%Y-%m-%d")))
+}
+
+/// `template` with placeholders filled in; `now` is for `{{time}}`
+pub fn render<Tz: TimeZone>(template: &str, tomorrow: &Tomorrow, now: &DateTime<Tz>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let tz = now.timezone();
+    let events = if tomorrow.events.is_empty() {
+        "- Nothing scheduled".to_string()
+    } else {
+        tomorrow
+            .events
+            .iter()
+            .map(|e| {
+                let when = if e.is_all_day {
+                    "All day".to_string()
+                } else {
+                    e.start_time.with_timezone(&tz).format("%H:%M").to_string()
+                };
+                match &e.location {
+                    Some(location) if !location.is_empty() => {
+                        format!("- {} {} ({})", when, e.title, location)
+                    }
+                    _ => format!("- {} {}", when, e.title),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let tasks = if tomorrow.tasks.is_empty() {
+        "- [ ] ".to_string()
+    } else {
+        tomorrow
+            .tasks
+            .iter()
+            .map(|t| format!("- [ ] {}", t.title))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let weather = tomorrow
+        .weather
+        .and_then(|w| agenda::weather_line(w, tomorrow.date))
+        .unwrap_or_else(|| "No forecast".to_string());
+
+    let mut note = template.to_string();
+    let missing: Vec<(&str, &str)> = [
+        ("Weather", "{{weather}}"),
+        ("Schedule", "{{events}}"),
+        ("Due", "{{tasks}}"),
+    ]
+    .into_iter()
+    .filter(|(_, placeholder)| !template.contains(placeholder))
+    .collect();
+    if !missing.is_empty() {
+        if !note.ends_with('\n') {
+            note.push('\n');
+        }
+        for (heading, placeholder) in missing {
+            note.push_str(&format!("\n## {}\n{}\n", heading, placeholder));
+        }
+    }
+
+    let date = tomorrow.date.format("%Y-%m-%d").to_string();
+    note.replace("{{date}}", &date)
+        .replace("{{title}}", &date)
+        .replace("{{time}}", &now.format("%H:%M").to_string())
+        .replace("{{events}}", &events)
+        .replace("{{tasks}}", &tasks)
+        .replace("{{weather}}", &weather)
+}
+
+/// Write `content` to `path` unless a note is already there. Returns whether
+/// the note was created.
+pub fn create(path: &Path, content: &str) -> Result<bool> {
+    if path.exists() {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("md.tmp");
+    std::fs::write(&tmp, content).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    // hard_link fails rather than replace a note made in the meantime
+    let linked = std::fs::hard_link(&tmp, path);
+    std::fs::remove_file(&tmp).ok();
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to write {}", path.display())),
+    }
+}
+
+/// The template text: `<templates folder>/<name>` (".md" optional), or
+/// [`DEFAULT_TEMPLATE`] when there is no such file
+pub fn load_template(vault: &Path, templates_folder: &str, name: &str) -> Result<String> {
+    let mut path = vault.join(templates_folder).join(name);
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    if !path.exists() {
+        return Ok(DEFAULT_TEMPLATE.to_string());
+    }
+    std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_sources::{UnitSystem, WeatherForecast};
+    use chrono::{Duration, FixedOffset};
+
+    #[test]
+    fn test_note_is_filled_in_and_never_overwritten() {
+        let tz = FixedOffset::east_opt(3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 5, 11, 20, 0, 0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 5, 12).unwrap();
+        let at = |h| tz.with_ymd_and_hms(2026, 5, 12, h, 0, 0).unwrap().to_utc();
+        let event = |title: &str, start, location: Option<&str>| CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::hours(1)),
+            location: location.map(str::to_string),
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let events = [
+            event("Standup", at(9), None),
+            event("Dentist", at(14), Some("Main St")),
+        ];
+        let tasks = [TaskSummary {
+            id: "1".to_string(),
+            title: "Send invoice".to_string(),
+            due: Some(at(17)),
+            completed: false,
+            priority: 5,
+            urgency: 5,
+            source: "todoist".to_string(),
+        }];
+        let weather = WeatherContext {
+            current_conditions: String::new(),
+            forecast: vec![WeatherForecast {
+                date: date.and_hms_opt(12, 0, 0).unwrap().and_utc(),
+                temperature_high: 21.4,
+                temperature_low: 12.0,
+                conditions: "Showers".to_string(),
+                precipitation_chance: 0.6,
+                description: String::new(),
+            }],
+            alerts: vec![],
+            units: UnitSystem::Metric,
+        };
+        let tomorrow = Tomorrow {
+            date,
+            events: &events,
+            tasks: &tasks,
+            weather: Some(&weather),
+        };
+
+        let note = render(
+            "# {{title}}\nWritten {{time}}\n\n## Plan\n{{events}}\n",
+            &tomorrow,
+            &now,
+        );
+        assert_eq!(
+            note,
+            "# 2026-05-12\nWritten 20:00\n\n## Plan\n- 09:00 Standup\n- 14:00 Dentist (Main St)\n\n\
+             ## Weather\nShowers, 21°C / 12°C, 60% rain\n\n## Due\n- [ ] Send invoice\n"
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            load_template(dir.path(), "Templates", "Daily").unwrap(),
+            DEFAULT_TEMPLATE
+        );
+        let path = note_path(dir.path(), "Daily", date);
+        assert!(path.ends_with("Daily/2026-05-12.md"));
+        assert!(create(&path, &note).unwrap());
+        assert!(!create(&path, "replaced").unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), note);
+        assert!(!path.with_extension("md.tmp").exists());
+    }
+}
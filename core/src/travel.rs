@@ -4,9 +4,10 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use tokio::sync::RwLock;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 
-use crate::config::TravelConfig;
+use crate::config::{Config, TravelConfig};
+use crate::http_utils;
 use crate::significance_engine::CalendarEventSummary;
 
 /// Result of a travel time calculation for a single event
@@ -59,6 +60,20 @@ pub struct TravelTimeService {
 }
 
 impl TravelTimeService {
+    /// The service, when enabled with an API key and home address
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .get_travel_config()
+            .filter(|tc| tc.enabled && !tc.google_api_key.is_empty() && !tc.home_address.is_empty())
+            .map(|tc| {
+                info!(
+                    "Travel time service initialized (mode: {}, from: {})",
+                    tc.travel_mode, tc.home_address
+                );
+                Self::new(tc)
+            })
+    }
+
     pub fn new(config: &TravelConfig) -> Self {
         Self {
             api_key: config.google_api_key.clone(),
@@ -75,6 +90,22 @@ impl TravelTimeService {
         }
     }
 
+    /// Drop expired routes (the cache is keyed by departure hour, so it only
+    /// grows otherwise) and give an API that answered 403 another try.
+    /// Returns the number of routes dropped.
+    pub async fn refresh(&self) -> usize {
+        let now = Utc::now();
+        let mut cache = self.cache.write().await;
+        let before = cache.len();
+        cache.retain(|_, cached| {
+            (now - cached.fetched_at).num_minutes() < self.cache_duration_minutes as i64
+        });
+        if self.api_disabled.swap(false, Ordering::Relaxed) {
+            info!("Retrying the Routes API after it was disabled");
+        }
+        before - cache.len()
+    }
+
     /// Calculate travel times for a batch of calendar events.
     /// Filters to only own-calendar events with physical locations within the lookahead window.
     /// Returns a map of event ID -> TravelTimeResult.
@@ -214,14 +245,14 @@ impl TravelTimeService {
             "routes.duration,routes.distanceMeters"
         };
 
-        let response = self
-            .client
-            .post("https://routes.googleapis.com/directions/v2:computeRoutes")
-            .header("X-Goog-Api-Key", &self.api_key)
-            .header("X-Goog-FieldMask", field_mask)
-            .json(&body)
-            .send()
-            .await?;
+        let response = http_utils::send(
+            self.client
+                .post("https://routes.googleapis.com/directions/v2:computeRoutes")
+                .header("X-Goog-Api-Key", &self.api_key)
+                .header("X-Goog-FieldMask", field_mask)
+                .json(&body),
+        )
+        .await?;
 
         let status = response.status();
 
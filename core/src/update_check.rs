@@ -0,0 +1,74 @@
+//! Opt-in check for newer releases on GitHub.
+//!
+//! Detection only: the daemon reports that an update exists (in `status`
+//! and over D-Bus) but never downloads or installs anything.
+
+use crate::errors::{JasperError, JasperResult};
+use serde::Deserialize;
+
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/heytcass/jasper/releases/latest";
+
+/// How often the running daemon re-checks
+pub const CHECK_INTERVAL_HOURS: i64 = 24;
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// Version of the latest published release, without a leading "v"
+pub async fn latest_release(client: &reqwest::Client) -> JasperResult<String> {
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .header(
+            "User-Agent",
+            format!("jasper-companion/{}", CURRENT_VERSION),
+        )
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(JasperError::Api {
+            service: "GitHub releases".to_string(),
+            message: format!("HTTP {}", response.status()),
+        });
+    }
+    let release: Release = response.json().await?;
+    Ok(release.tag_name.trim_start_matches('v').to_string())
+}
+
+fn numeric_parts(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Whether `latest` is a newer release than `current` (pre-release suffixes are ignored)
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let (mut latest, mut current) = (numeric_parts(latest), numeric_parts(current));
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_comparison() {
+        assert!(is_newer("0.3.0", "0.2.0"));
+        assert!(is_newer("0.2.10", "0.2.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("0.2.0", "0.2"));
+        assert!(!is_newer("0.2.0-rc1", "0.2.0"));
+        assert!(!is_newer("0.1.5", "0.2.0"));
+    }
+}
@@ -0,0 +1,275 @@
+//! "Wake me earlier" rule for the evening before an early start.
+//!
+//! From `[alarm] remind_after` onward, tomorrow's first event on the user's own
+//! calendar is worked back to a wake time: start, minus the trip (traffic-aware
+//! when travel times are enabled), minus `ready_minutes`, minus a buffer when
+//! ice or rain is forecast for an in-person event. If that lands before
+//! `usual_wake`, Jasper posts a "set an earlier alarm" insight and, with a
+//! backend configured, sets the alarm itself: a transient systemd timer that
+//! fires a critical notification, or any command (GNOME Clocks, a phone
+//! bridge) given the time through environment variables.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveTime, Timelike};
+use std::process::Command;
+
+use crate::config::AlarmConfig;
+use crate::context_sources::WeatherContext;
+use crate::errors::{JasperError, JasperResult};
+use crate::significance_engine::CalendarEventSummary;
+use crate::weather_gear::{self, GearFlag};
+
+/// Suggested wake times are rounded down to this many minutes
+const ROUND_TO_MINUTES: u32 = 5;
+
+/// An earlier-than-usual alarm for tomorrow, and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlarmSuggestion {
+    pub wake_at: DateTime<FixedOffset>,
+    pub event_title: String,
+    pub event_start: DateTime<FixedOffset>,
+    /// Minutes of travel to the event, when known
+    pub travel_minutes: Option<i32>,
+    pub travel_mode: Option<String>,
+    /// Forecast that added the bad-weather buffer
+    pub weather: Option<GearFlag>,
+}
+
+impl AlarmSuggestion {
+    /// One-sentence insight text
+    pub fn describe(&self) -> String {
+        let mut reasons = vec![format!(
+            "{} starts at {}",
+            self.event_title,
+            self.event_start.format("%-I:%M %p")
+        )];
+        if let Some(minutes) = self.travel_minutes {
+            reasons.push(format!(
+                "{} min {}",
+                minutes,
+                self.travel_mode.as_deref().unwrap_or("trip")
+            ));
+        }
+        match self.weather {
+            Some(GearFlag::IceRisk) => reasons.push("ice is likely".to_string()),
+            Some(GearFlag::Rain) => reasons.push("rain is likely".to_string()),
+            _ => {}
+        }
+        format!(
+            "Set an earlier alarm for {} tomorrow — {}",
+            self.wake_at.format("%-I:%M %p"),
+            reasons.join(", ")
+        )
+    }
+}
+
+/// Earlier wake time needed for tomorrow, if any
+pub fn suggest(
+    events: &[CalendarEventSummary],
+    weather: Option<&WeatherContext>,
+    now: &DateTime<FixedOffset>,
+    config: &AlarmConfig,
+) -> Option<AlarmSuggestion> {
+    let tz = now.timezone();
+    let tomorrow = now.date_naive().succ_opt()?;
+    let usual_wake = NaiveTime::parse_from_str(&config.usual_wake, "%H:%M").ok()?;
+
+    let first = events
+        .iter()
+        .filter(|e| !e.is_all_day && e.is_own_calendar && e.is_primary_calendar)
+        .filter(|e| e.start_time.with_timezone(&tz).date_naive() == tomorrow)
+        .min_by_key(|e| e.start_time)?;
+
+    let travel = first.travel_time.as_ref();
+    let travel_minutes =
+        travel.map(|t| t.duration_in_traffic_minutes.unwrap_or(t.duration_minutes));
+    let weather = first
+        .location
+        .as_ref()
+        .and(weather)
+        .and_then(|w| weather_gear::commute_flag(w, tomorrow))
+        .filter(|flag| matches!(flag, GearFlag::IceRisk | GearFlag::Rain));
+
+    let event_start = first.start_time.with_timezone(&tz);
+    let mut lead = config.ready_minutes as i64 + travel_minutes.unwrap_or(0) as i64;
+    if weather.is_some() {
+        lead += config.bad_weather_buffer_minutes as i64;
+    }
+    let wake_at = event_start - Duration::minutes(lead);
+    let wake_at = wake_at
+        - Duration::minutes((wake_at.minute() % ROUND_TO_MINUTES) as i64)
+        - Duration::seconds(wake_at.second() as i64);
+
+    let early = wake_at.date_naive() < tomorrow || wake_at.time() < usual_wake;
+    early.then(|| AlarmSuggestion {
+        wake_at,
+        event_title: first.title.clone(),
+        event_start,
+        travel_minutes,
+        travel_mode: travel.map(|t| t.travel_mode_label.clone()),
+        weather,
+    })
+}
+
+/// Somewhere to set the alarm
+pub trait AlarmBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn set(&self, at: &DateTime<FixedOffset>, label: &str) -> JasperResult<()>;
+}
+
+/// The configured backend; None for "none"
+pub fn backend(config: &AlarmConfig) -> Option<Box<dyn AlarmBackend>> {
+    match config.backend.as_str() {
+        "systemd" => Some(Box::new(SystemdTimer)),
+        "command" => config
+            .command
+            .clone()
+            .map(|command| Box::new(CommandAlarm { command }) as Box<dyn AlarmBackend>),
+        _ => None,
+    }
+}
+
+/// Transient `systemd-run --user` timer that shows a critical notification
+struct SystemdTimer;
+
+impl AlarmBackend for SystemdTimer {
+    fn name(&self) -> &'static str {
+        "systemd timer"
+    }
+
+    fn set(&self, at: &DateTime<FixedOffset>, label: &str) -> JasperResult<()> {
+        let unit = format!("jasper-alarm-{}", at.format("%Y%m%d"));
+        // Replacing tonight's suggestion if the calendar moved since the last one
+        let _ = Command::new("systemctl")
+            .args(["--user", "stop", &format!("{}.timer", unit)])
+            .output();
+        let on_calendar = at.to_utc().format("%Y-%m-%d %H:%M:%S UTC").to_string();
+        run(
+            Command::new("systemd-run")
+                .args(["--user", "--unit", &unit, "--on-calendar", &on_calendar])
+                .args(["--timer-property=AccuracySec=1s", "--"])
+                .args(["notify-send", "--urgency=critical", "⏰ Jasper", label]),
+            "systemd-run",
+        )
+    }
+}
+
+/// User command, given JASPER_ALARM_TIME ("05:45"), JASPER_ALARM_AT (RFC 3339)
+/// and JASPER_ALARM_LABEL in its environment
+struct CommandAlarm {
+    command: String,
+}
+
+impl AlarmBackend for CommandAlarm {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
+    fn set(&self, at: &DateTime<FixedOffset>, label: &str) -> JasperResult<()> {
+        run(
+            Command::new("sh")
+                .args(["-c", &self.command])
+                .env("JASPER_ALARM_TIME", at.format("%H:%M").to_string())
+                .env("JASPER_ALARM_AT", at.to_rfc3339())
+                .env("JASPER_ALARM_LABEL", label),
+            "alarm command",
+        )
+    }
+}
+
+fn run(command: &mut Command, what: &str) -> JasperResult<()> {
+    let output = command.output().map_err(|e| JasperError::Internal {
+        message: format!("Failed to run {}: {}", what, e),
+    })?;
+    if !output.status.success() {
+        return Err(JasperError::Internal {
+            message: format!(
+                "{} failed: {}",
+                what,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context_sources::{UnitSystem, WeatherForecast};
+    use crate::significance_engine::TravelTimeInfo;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_early_start_with_drive_and_ice_moves_the_alarm() {
+        let tz = FixedOffset::west_opt(6 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 1, 19, 20, 30, 0).unwrap();
+        let at = |h, m| tz.with_ymd_and_hms(2026, 1, 20, h, m, 0).unwrap().to_utc();
+        let event = |title: &str, start, location: Option<&str>| CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + Duration::hours(1)),
+            location: location.map(str::to_string),
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        };
+        let mut site_visit = event("Site visit", at(7, 30), Some("Plant 2"));
+        site_visit.travel_time = Some(TravelTimeInfo {
+            duration_minutes: 25,
+            duration_in_traffic_minutes: Some(32),
+            distance_km: 30.0,
+            origin_label: "home".to_string(),
+            travel_mode_label: "drive".to_string(),
+        });
+        let weather = WeatherContext {
+            current_conditions: "Clear".to_string(),
+            forecast: vec![WeatherForecast {
+                date: Utc.with_ymd_and_hms(2026, 1, 20, 12, 0, 0).unwrap(),
+                temperature_high: 28.0,
+                temperature_low: 15.0,
+                conditions: "Snow".to_string(),
+                precipitation_chance: 0.6,
+                description: String::new(),
+            }],
+            alerts: vec![],
+            units: UnitSystem::Imperial,
+        };
+        let config = AlarmConfig::default();
+
+        let suggestion = suggest(
+            &[event("Standup", at(9, 0), None), site_visit],
+            Some(&weather),
+            &now,
+            &config,
+        )
+        .unwrap();
+        // 7:30 - 32 min drive - 60 min ready - 15 min ice buffer = 5:43 → 5:40
+        assert_eq!(
+            suggestion.wake_at,
+            tz.with_ymd_and_hms(2026, 1, 20, 5, 40, 0).unwrap()
+        );
+        assert_eq!(
+            suggestion.describe(),
+            "Set an earlier alarm for 5:40 AM tomorrow — Site visit starts at 7:30 AM, 32 min drive, ice is likely"
+        );
+
+        // A 9:00 standup fits the usual 6:30 wake-up
+        assert_eq!(
+            suggest(
+                &[event("Standup", at(9, 0), None)],
+                Some(&weather),
+                &now,
+                &config
+            ),
+            None
+        );
+    }
+}
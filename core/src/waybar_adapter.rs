@@ -0,0 +1,418 @@
+use crate::accessibility;
+use crate::config::Config;
+use crate::glyphs::GlyphMapper;
+use crate::insight_format::InsightFormat;
+use crate::terminal_formatter::TemplateFormatter;
+use crate::text_budget;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tracing::{debug, error, info};
+use zbus::{proxy, Connection};
+
+/// Waybar adapter that connects to the simplified Jasper daemon via D-Bus
+/// This replaces all the complex waybar formatting code in the old architecture
+
+#[proxy(
+    interface = "org.jasper.Daemon1",
+    default_service = "org.jasper.Daemon",
+    default_path = "/org/jasper/Daemon"
+)]
+trait JasperDaemon {
+    async fn get_latest_insight(&self) -> zbus::Result<(i64, String, String, String)>;
+    async fn register_frontend(&self, frontend_id: String, pid: i32) -> zbus::Result<bool>;
+    async fn register_frontend_with_format(
+        &self,
+        frontend_id: String,
+        pid: i32,
+        format: String,
+    ) -> zbus::Result<bool>;
+    async fn unregister_frontend(&self, frontend_id: String) -> zbus::Result<bool>;
+    async fn get_status(&self) -> zbus::Result<(bool, u32, i64)>;
+    async fn get_template_fields(&self) -> zbus::Result<HashMap<String, String>>;
+    async fn get_insight_freshness(&self) -> zbus::Result<(i64, bool)>;
+
+    // TODO: Add signal subscription for real-time updates
+}
+
+pub struct WaybarAdapter {
+    proxy: Option<JasperDaemonProxy<'static>>,
+    /// Screen-reader friendly output: no glyphs, full sentences
+    accessible: bool,
+    glyphs: GlyphMapper,
+    /// Maximum insight length in characters (None = unlimited)
+    max_length: Option<usize>,
+}
+
+impl WaybarAdapter {
+    pub async fn new() -> Self {
+        Self {
+            proxy: None,
+            accessible: false,
+            glyphs: GlyphMapper::disabled(),
+            max_length: text_budget::default_budget("waybar"),
+        }
+    }
+
+    /// Enable or disable accessible (plain text) output
+    pub fn with_accessible(mut self, accessible: bool) -> Self {
+        self.accessible = accessible;
+        self
+    }
+
+    /// Map insight emoji to bar glyphs before display
+    pub fn with_glyphs(mut self, glyphs: GlyphMapper) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Override the bar text length budget
+    pub fn with_max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Connect to the Jasper daemon
+    pub async fn connect(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let connection = Connection::session().await?;
+        let proxy = JasperDaemonProxy::new(&connection).await?;
+
+        // Register as waybar frontend; Waybar renders its text as Pango markup
+        let pid = std::process::id() as i32;
+        let success = match proxy
+            .register_frontend_with_format(
+                "waybar".to_string(),
+                pid,
+                InsightFormat::Pango.name().to_string(),
+            )
+            .await
+        {
+            Ok(success) => success,
+            // Daemons older than format negotiation
+            Err(_) => proxy.register_frontend("waybar".to_string(), pid).await?,
+        };
+        if !success {
+            error!("Failed to register with Jasper daemon");
+            return Err("Registration failed".into());
+        }
+
+        self.proxy = Some(proxy);
+        info!("Connected to Jasper daemon");
+        Ok(())
+    }
+
+    /// Get the current insight formatted for waybar
+    pub async fn get_waybar_output(&self) -> Value {
+        let Some(ref proxy) = self.proxy else {
+            return self.error_output("Not connected to daemon");
+        };
+
+        match proxy.get_latest_insight().await {
+            Ok((id, emoji, insight, _context_hash)) => {
+                if id > 0 {
+                    // Older daemons lack GetInsightFreshness; treat as fresh
+                    let freshness = proxy.get_insight_freshness().await.unwrap_or((-1, false));
+                    self.format_insight_output(&emoji, &insight, freshness)
+                } else {
+                    self.waiting_output()
+                }
+            }
+            Err(e) => {
+                error!("Failed to get insight from daemon: {}", e);
+                self.error_output("Daemon error")
+            }
+        }
+    }
+
+    /// Get the current insight rendered through a user template, for scripts
+    pub async fn get_simple_output(&self, formatter: &TemplateFormatter) -> String {
+        let Some(ref proxy) = self.proxy else {
+            return "Jasper: not connected to daemon".to_string();
+        };
+
+        let (id, emoji, insight, _context_hash) = match proxy.get_latest_insight().await {
+            Ok(latest) => latest,
+            Err(e) => {
+                error!("Failed to get insight from daemon: {}", e);
+                return "Jasper: daemon error".to_string();
+            }
+        };
+        if id <= 0 {
+            return "Jasper: analyzing...".to_string();
+        }
+
+        // Older daemons lack GetTemplateFields; fall back to emoji/text only
+        let mut fields = proxy.get_template_fields().await.unwrap_or_default();
+        if self.accessible {
+            fields.insert(
+                "text".to_string(),
+                accessibility::plain_insight(&emoji, &insight),
+            );
+            fields.insert("emoji".to_string(), String::new());
+            if let Some(next) = fields.get_mut("next_event") {
+                *next = accessibility::strip_glyphs(next);
+            }
+        } else {
+            fields.insert(
+                "text".to_string(),
+                text_budget::fit(&insight, self.max_length),
+            );
+            fields.insert("emoji".to_string(), self.glyphs.map(&emoji));
+        }
+
+        formatter.render(&fields)
+    }
+
+    /// Format insight for waybar JSON output. A stale insight gets the extra
+    /// "jasper-stale" class so the bar's CSS can gray it out.
+    fn format_insight_output(
+        &self,
+        emoji: &str,
+        insight: &str,
+        (age_seconds, stale): (i64, bool),
+    ) -> Value {
+        let class = if stale {
+            json!(["jasper-insight", "jasper-stale"])
+        } else {
+            json!("jasper-insight")
+        };
+
+        if self.accessible {
+            // Screen readers get the whole sentence; never cut words off
+            let mut sentence = accessibility::plain_insight(emoji, insight);
+            if stale {
+                sentence.push_str(" This insight may be out of date.");
+            }
+            let sentence = InsightFormat::Pango.escape(&sentence);
+            return json!({
+                "text": sentence,
+                "tooltip": sentence,
+                "class": class,
+                "percentage": 100,
+                "age_seconds": age_seconds,
+                "stale": stale
+            });
+        }
+
+        // Shorten long insights at a word boundary; the tooltip keeps the full text
+        let display_text = text_budget::fit(insight, self.max_length);
+
+        json!({
+            "text": InsightFormat::Pango.render(&self.glyphs.map(emoji), &display_text),
+            "tooltip": InsightFormat::Pango.escape(insight),
+            "class": class,
+            "percentage": 100,
+            "age_seconds": age_seconds,
+            "stale": stale
+        })
+    }
+
+    /// Output for when daemon is analyzing
+    fn waiting_output(&self) -> Value {
+        if self.accessible {
+            return json!({
+                "text": "Jasper is analyzing your context.",
+                "tooltip": "Jasper is analyzing your context.",
+                "class": "jasper-waiting",
+                "percentage": 0
+            });
+        }
+
+        json!({
+            "text": "🔍 Analyzing...",
+            "tooltip": "Jasper is analyzing your context",
+            "class": "jasper-waiting",
+            "percentage": 0
+        })
+    }
+
+    /// Output for error states
+    fn error_output(&self, message: &str) -> Value {
+        if self.accessible {
+            let sentence = format!("Jasper error: {}.", message);
+            return json!({
+                "text": sentence,
+                "tooltip": sentence,
+                "class": "jasper-error",
+                "percentage": 0
+            });
+        }
+
+        json!({
+            "text": "⚠️ Jasper",
+            "tooltip": format!("Error: {}", message),
+            "class": "jasper-error",
+            "percentage": 0
+        })
+    }
+
+    /// Check daemon status
+    pub async fn get_status(&self) -> Result<(bool, u32, i64), Box<dyn std::error::Error>> {
+        let Some(ref proxy) = self.proxy else {
+            return Err("Not connected to daemon".into());
+        };
+
+        let status = proxy.get_status().await?;
+        Ok(status)
+    }
+
+    /// Disconnect from daemon
+    pub async fn disconnect(&mut self) {
+        if let Some(ref proxy) = self.proxy {
+            if let Err(e) = proxy.unregister_frontend("waybar".to_string()).await {
+                error!("Failed to unregister from daemon: {}", e);
+            }
+        }
+        self.proxy = None;
+        debug!("Disconnected from Jasper daemon");
+    }
+}
+
+impl Drop for WaybarAdapter {
+    fn drop(&mut self) {
+        // Note: This is sync drop, so we can't await the disconnect
+        // The daemon will clean up expired frontends automatically
+    }
+}
+
+/// The line for waybar integration, for main.rs to print: the module's JSON,
+/// or with `simple` a plain text line
+pub async fn run_waybar_mode(
+    accessible: bool,
+    simple: bool,
+    template: Option<String>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // Don't initialize logging - it's already initialized by main.rs
+
+    let frontends = Config::load_frontends();
+    let mut adapter = WaybarAdapter::new()
+        .await
+        .with_accessible(accessible)
+        .with_glyphs(GlyphMapper::from_config(&frontends.glyphs))
+        .with_max_length(text_budget::budget_for("waybar", &frontends.max_length));
+
+    if simple {
+        let formatter = TemplateFormatter::new(template.unwrap_or(frontends.terminal_template));
+        let mut adapter =
+            adapter.with_max_length(text_budget::budget_for("terminal", &frontends.max_length));
+        let line = match adapter.connect().await {
+            Ok(()) => adapter.get_simple_output(&formatter).await,
+            Err(_) => "Jasper: daemon not running".to_string(),
+        };
+        adapter.disconnect().await;
+        return Ok(line);
+    }
+
+    // Try to connect to daemon
+    if let Err(_e) = adapter.connect().await {
+        // If daemon is not running, output error state and exit
+        let output = adapter.error_output("Daemon not running");
+        return Ok(serde_json::to_string(&output)?);
+    }
+
+    // Get and output the current insight
+    let output = adapter.get_waybar_output().await;
+
+    // Disconnect cleanly
+    adapter.disconnect().await;
+
+    Ok(serde_json::to_string(&output)?)
+}
+
+/// Simple status check for waybar, as lines of text
+pub async fn waybar_status() -> Result<String, Box<dyn std::error::Error>> {
+    let mut adapter = WaybarAdapter::new().await;
+
+    let status = match adapter.connect().await {
+        Ok(()) => {
+            let status = match adapter.get_status().await {
+                Ok((_is_running, active_frontends, insights_count)) => format!(
+                    "Daemon: Running\nActive Frontends: {}\nInsights Generated: {}",
+                    active_frontends, insights_count
+                ),
+                Err(e) => format!("Status Error: {}", e),
+            };
+            adapter.disconnect().await;
+            status
+        }
+        Err(_) => "Daemon: Not Running".to_string(),
+    };
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRESH: (i64, bool) = (60, false);
+
+    #[tokio::test]
+    async fn test_waybar_adapter_creation() {
+        let adapter = WaybarAdapter::new().await;
+        assert!(adapter.proxy.is_none());
+    }
+
+    #[test]
+    fn test_output_formatting() {
+        let adapter = WaybarAdapter {
+            proxy: None,
+            accessible: false,
+            glyphs: GlyphMapper::disabled(),
+            max_length: text_budget::default_budget("waybar"),
+        };
+
+        // Test insight formatting
+        let output = adapter.format_insight_output("🎯", "Short insight", FRESH);
+        assert!(output["text"].as_str().unwrap().contains("🎯"));
+        assert!(output["text"].as_str().unwrap().contains("Short insight"));
+
+        // Test long insight truncation
+        let long_insight =
+            "This is a very long insight that should be truncated for waybar display";
+        let output = adapter.format_insight_output("📅", long_insight, FRESH);
+        assert!(output["text"].as_str().unwrap().chars().count() <= 62); // emoji + space + 60 char budget
+        assert!(output["tooltip"].as_str().unwrap() == long_insight);
+
+        // Multi-byte text near the cut point must not panic
+        let accented =
+            "Réunion d'équipe à 15h — préparez les diapositives et le budget trimestriel";
+        let output = adapter.format_insight_output("📅", accented, FRESH);
+        assert!(output["text"].as_str().unwrap().ends_with('…'));
+
+        // Waybar parses Pango markup, so insight text is escaped
+        let output = adapter.format_insight_output("🛒", "Milk & <eggs>", FRESH);
+        assert_eq!(output["text"], "🛒 Milk &amp; &lt;eggs&gt;");
+        assert_eq!(output["class"], "jasper-insight");
+
+        // Stale insights get an extra class for the bar's CSS
+        let output = adapter.format_insight_output("🛒", "Buy milk", (3 * 3600, true));
+        assert_eq!(output["class"], json!(["jasper-insight", "jasper-stale"]));
+        assert_eq!(output["age_seconds"], 3 * 3600);
+        assert_eq!(output["stale"], true);
+
+        // Test error output
+        let output = adapter.error_output("Test error");
+        assert!(output["text"].as_str().unwrap().contains("⚠️"));
+        assert!(output["tooltip"].as_str().unwrap().contains("Test error"));
+    }
+
+    #[test]
+    fn test_accessible_output() {
+        let adapter = WaybarAdapter {
+            proxy: None,
+            accessible: true,
+            glyphs: GlyphMapper::disabled(),
+            max_length: text_budget::default_budget("waybar"),
+        };
+
+        let output =
+            adapter.format_insight_output("🚨", "Flight boards in 40 min → Gate B12", FRESH);
+        assert_eq!(
+            output["text"],
+            "Urgent: Flight boards in 40 min to Gate B12."
+        );
+
+        let output = adapter.error_output("Daemon not running");
+        assert_eq!(output["text"], "Jasper error: Daemon not running.");
+    }
+}
@@ -0,0 +1,231 @@
+//! Deterministic weather-to-gear flags for upcoming events.
+//!
+//! The forecast is daily, so each in-person event is matched to its day's
+//! forecast and checked for ice risk, heat (outdoor events only) and rain.
+//! The flags become a prompt section, giving the model something concrete to
+//! anchor on, and they double as a local insight when the API is unavailable
+//! ("Rain during Soccer practice — pack rain gear").
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::activity::{ActivityClassifier, ActivityType};
+use crate::context_sources::{UnitSystem, WeatherContext, WeatherForecast};
+use crate::significance_engine::CalendarEventSummary;
+
+/// Rain this likely (0–1) is worth planning around
+const RAIN_CHANCE: f32 = 0.5;
+/// Lower bar for precipitation turning to ice on a freezing day
+const ICE_PRECIP_CHANCE: f32 = 0.3;
+const FREEZING_F: f32 = 32.0;
+const HEAT_F: f32 = 90.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GearFlag {
+    IceRisk,
+    Heat,
+    Rain,
+}
+
+impl GearFlag {
+    pub fn emoji(self) -> &'static str {
+        match self {
+            GearFlag::IceRisk => "🧊",
+            GearFlag::Heat => "🥵",
+            GearFlag::Rain => "☔",
+        }
+    }
+}
+
+/// One flag attached to one event
+#[derive(Debug, Clone, PartialEq)]
+pub struct GearNote {
+    pub event_title: String,
+    pub flag: GearFlag,
+    pub outdoor: bool,
+}
+
+impl GearNote {
+    /// Short practical sentence, usable as an insight on its own
+    pub fn describe(&self) -> String {
+        match (self.flag, self.outdoor) {
+            (GearFlag::IceRisk, _) => format!(
+                "Ice risk around {} — leave extra time and watch your footing",
+                self.event_title
+            ),
+            (GearFlag::Heat, _) => format!(
+                "Heat during {} — bring water and sun protection",
+                self.event_title
+            ),
+            (GearFlag::Rain, true) => {
+                format!("Rain during {} — pack rain gear", self.event_title)
+            }
+            (GearFlag::Rain, false) => {
+                format!("Rain likely for {} — grab an umbrella", self.event_title)
+            }
+        }
+    }
+}
+
+/// Flag upcoming in-person events against their day's forecast. Remote
+/// events (calls, focus blocks) are skipped.
+pub fn notes_for_events(
+    events: &[CalendarEventSummary],
+    weather: &WeatherContext,
+    activities: &ActivityClassifier,
+    now: &DateTime<FixedOffset>,
+) -> Vec<GearNote> {
+    let tz = now.timezone();
+    let now_utc = now.to_utc();
+
+    events
+        .iter()
+        .filter(|e| !e.is_all_day)
+        .filter(|e| e.end_time.unwrap_or(e.start_time) > now_utc)
+        .filter_map(|event| {
+            let outdoor = match activities.classify(event) {
+                ActivityType::Remote => return None,
+                activity => activity == ActivityType::Outdoor,
+            };
+            let day = event.start_time.with_timezone(&tz).date_naive();
+            let forecast = weather.forecast.iter().find(|f| forecast_date(f) == day)?;
+            let flag = flag_for(forecast, weather.units, outdoor)?;
+            Some(GearNote {
+                event_title: event.title.clone(),
+                flag,
+                outdoor,
+            })
+        })
+        .collect()
+}
+
+/// Ice or rain on a given day, as it affects getting somewhere (heat doesn't)
+pub fn commute_flag(weather: &WeatherContext, day: chrono::NaiveDate) -> Option<GearFlag> {
+    let forecast = weather.forecast.iter().find(|f| forecast_date(f) == day)?;
+    flag_for(forecast, weather.units, false)
+}
+
+/// Forecast days are stored as noon UTC of the local calendar date
+fn forecast_date(forecast: &WeatherForecast) -> chrono::NaiveDate {
+    forecast.date.date_naive()
+}
+
+/// Most important flag for one forecast day; ice beats heat beats rain
+fn flag_for(forecast: &WeatherForecast, units: UnitSystem, outdoor: bool) -> Option<GearFlag> {
+    let low = units.convert_temperature(forecast.temperature_low, UnitSystem::Imperial);
+    let high = units.convert_temperature(forecast.temperature_high, UnitSystem::Imperial);
+    let conditions = format!("{} {}", forecast.conditions, forecast.description).to_lowercase();
+    let wintry = ["snow", "sleet", "ice", "freezing"]
+        .iter()
+        .any(|w| conditions.contains(w));
+    let rainy = ["rain", "shower", "storm", "drizzle"]
+        .iter()
+        .any(|w| conditions.contains(w));
+
+    if low <= FREEZING_F && (wintry || forecast.precipitation_chance >= ICE_PRECIP_CHANCE) {
+        Some(GearFlag::IceRisk)
+    } else if outdoor && high >= HEAT_F {
+        Some(GearFlag::Heat)
+    } else if rainy || forecast.precipitation_chance >= RAIN_CHANCE {
+        Some(GearFlag::Rain)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone, Utc};
+
+    fn event(title: &str, location: Option<&str>, start: DateTime<Utc>) -> CalendarEventSummary {
+        CalendarEventSummary {
+            id: title.to_string(),
+            title: title.to_string(),
+            start_time: start,
+            end_time: Some(start + chrono::Duration::hours(1)),
+            location: location.map(str::to_string),
+            is_all_day: false,
+            calendar_name: None,
+            calendar_color: None,
+            is_own_calendar: true,
+            is_primary_calendar: true,
+            travel_time: None,
+            attendees: vec![],
+            links: vec![],
+            awaiting_reply: false,
+            tentative: false,
+        }
+    }
+
+    fn forecast(
+        date: NaiveDate,
+        high: f32,
+        low: f32,
+        conditions: &str,
+        rain: f32,
+    ) -> WeatherForecast {
+        WeatherForecast {
+            date: Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap()),
+            temperature_high: high,
+            temperature_low: low,
+            conditions: conditions.to_string(),
+            precipitation_chance: rain,
+            description: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_flags_attach_to_in_person_events() {
+        let tz = FixedOffset::west_opt(5 * 3600).unwrap();
+        let now = tz.with_ymd_and_hms(2026, 5, 12, 8, 0, 0).unwrap();
+        let today = now.date_naive();
+        let tomorrow = today.succ_opt().unwrap();
+        let weather = WeatherContext {
+            current_conditions: "Cloudy".to_string(),
+            forecast: vec![
+                forecast(today, 64.0, 50.0, "Showers", 0.7),
+                forecast(tomorrow, 93.0, 72.0, "Sunny", 0.0),
+            ],
+            alerts: vec![],
+            units: UnitSystem::Imperial,
+        };
+        let at = |day: NaiveDate, hour| {
+            tz.from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+                .unwrap()
+                .to_utc()
+        };
+        let events = vec![
+            event("Soccer practice", Some("Rec Center"), at(today, 17)),
+            event("Dentist", Some("Main St"), at(today, 11)),
+            event("Standup", None, at(today, 10)),
+            event("Morning run", None, at(tomorrow, 7)),
+            event("Client lunch", Some("Bistro"), at(tomorrow, 12)),
+        ];
+
+        let notes: Vec<String> =
+            notes_for_events(&events, &weather, &ActivityClassifier::default(), &now)
+                .iter()
+                .map(GearNote::describe)
+                .collect();
+        assert_eq!(
+            notes,
+            [
+                "Rain during Soccer practice — pack rain gear",
+                "Rain likely for Dentist — grab an umbrella",
+                "Heat during Morning run — bring water and sun protection",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ice_risk_in_metric() {
+        let date = NaiveDate::from_ymd_opt(2026, 1, 20).unwrap();
+        let freezing = forecast(date, 2.0, -4.0, "Light snow", 0.2);
+        assert_eq!(
+            flag_for(&freezing, UnitSystem::Metric, false),
+            Some(GearFlag::IceRisk)
+        );
+        let mild = forecast(date, 12.0, 4.0, "Partly cloudy", 0.1);
+        assert_eq!(flag_for(&mild, UnitSystem::Metric, true), None);
+    }
+}
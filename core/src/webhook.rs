@@ -0,0 +1,189 @@
+//! Outbound webhook for new insights and high-urgency alerts.
+//!
+//! POSTs a JSON body built from `[delivery.webhook] template` to a URL, so
+//! Slack, Discord, n8n and the like can take Jasper's output without a
+//! dedicated integration. Placeholders (`{event}`, `{emoji}`, `{text}`,
+//! `{insight_id}`, `{timestamp}`) are substituted inside the template's string
+//! values after it is parsed, so inserted text never breaks the JSON.
+
+use std::collections::BTreeMap;
+
+use crate::config::WebhookConfig;
+use crate::errors::{JasperError, JasperResult};
+
+/// Sent when no template is configured
+const DEFAULT_TEMPLATE: &str = r#"{
+    "event": "{event}",
+    "emoji": "{emoji}",
+    "text": "{text}",
+    "insight_id": "{insight_id}",
+    "timestamp": "{timestamp}"
+}"#;
+
+/// What fired the webhook; also the `{event}` placeholder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// A new insight (the InsightUpdated signal)
+    Insight,
+    /// A severe weather alert or unhealthy air quality reading
+    Alert,
+    /// The daily family digest, on `[family_digest.webhook]`
+    FamilyDigest,
+}
+
+impl WebhookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::Insight => "insight",
+            WebhookEvent::Alert => "alert",
+            WebhookEvent::FamilyDigest => "family_digest",
+        }
+    }
+}
+
+pub struct WebhookPublisher {
+    client: reqwest::Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+    template: serde_json::Value,
+    alerts: bool,
+}
+
+impl WebhookPublisher {
+    pub fn new(client: reqwest::Client, config: &WebhookConfig) -> JasperResult<Self> {
+        Ok(Self {
+            client,
+            url: config.url.clone(),
+            headers: config.headers.clone(),
+            template: parse_template(config.template.as_deref())?,
+            alerts: config.alerts,
+        })
+    }
+
+    /// Whether high-urgency alerts should be sent, not just insights
+    pub fn wants_alerts(&self) -> bool {
+        self.alerts
+    }
+
+    pub async fn publish(
+        &self,
+        event: WebhookEvent,
+        emoji: &str,
+        text: &str,
+        insight_id: Option<i64>,
+    ) -> JasperResult<()> {
+        let insight_id = insight_id.map(|id| id.to_string()).unwrap_or_default();
+        let timestamp = chrono::Utc::now().to_rfc3339();
+        let body = fill(
+            &self.template,
+            &[
+                ("{event}", event.name()),
+                ("{emoji}", emoji),
+                ("{text}", text),
+                ("{insight_id}", &insight_id),
+                ("{timestamp}", &timestamp),
+            ],
+        );
+
+        let mut request = self.client.post(&self.url).json(&body);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        let response = request.send().await.map_err(|e| JasperError::Network {
+            message: format!("webhook request failed: {}", e),
+        })?;
+        if !response.status().is_success() {
+            return Err(JasperError::Api {
+                service: "webhook".to_string(),
+                message: format!("HTTP {}", response.status()),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The configured template, or the default one, as JSON
+pub fn parse_template(template: Option<&str>) -> JasperResult<serde_json::Value> {
+    serde_json::from_str(template.unwrap_or(DEFAULT_TEMPLATE)).map_err(|e| JasperError::Config {
+        message: format!("delivery.webhook.template is not valid JSON: {}", e),
+    })
+}
+
+/// Substitute placeholders in every string (keys and values) of the template
+fn fill(template: &serde_json::Value, values: &[(&str, &str)]) -> serde_json::Value {
+    use serde_json::Value;
+    let substitute = |s: &str| {
+        values
+            .iter()
+            .fold(s.to_string(), |acc, (placeholder, value)| {
+                acc.replace(placeholder, value)
+            })
+    };
+    match template {
+        Value::String(s) => Value::String(substitute(s)),
+        Value::Array(items) => Value::Array(items.iter().map(|v| fill(v, values)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (substitute(k), fill(v, values)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Weather alerts marked severe or extreme ("Tornado Warning (SEVERE)") and
+/// air quality at "unhealthy" (AQI 151) or worse
+pub fn is_high_urgency(alert: &str) -> bool {
+    let upper = alert.to_uppercase();
+    if upper.ends_with("(SEVERE)") || upper.ends_with("(EXTREME)") {
+        return true;
+    }
+    alert
+        .strip_prefix("Air quality AQI ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|aqi| aqi.parse::<u32>().ok())
+        .is_some_and(|aqi| aqi > 150)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_substitution_stays_valid_json() {
+        let template = parse_template(Some(
+            r#"{"content": "{emoji} {text}", "embeds": [{"title": "Jasper {event}"}], "n": 1}"#,
+        ))
+        .unwrap();
+        let body = fill(
+            &template,
+            &[
+                ("{event}", "insight"),
+                ("{emoji}", "🚗"),
+                ("{text}", "Leave by 5 — \"traffic\" on I-94"),
+            ],
+        );
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "content": "🚗 Leave by 5 — \"traffic\" on I-94",
+                "embeds": [{"title": "Jasper insight"}],
+                "n": 1
+            })
+        );
+        assert!(parse_template(Some("{not json")).is_err());
+        assert!(parse_template(None).is_ok());
+    }
+
+    #[test]
+    fn test_high_urgency_alerts() {
+        assert!(is_high_urgency("Tornado Warning (SEVERE)"));
+        assert!(is_high_urgency("Blizzard Warning (Extreme)"));
+        assert!(!is_high_urgency("Frost Advisory (MINOR)"));
+        assert!(is_high_urgency("Air quality AQI 162 (unhealthy)"));
+        assert!(!is_high_urgency(
+            "Air quality AQI 112 (unhealthy for sensitive groups)"
+        ));
+        assert!(!is_high_urgency("Grass pollen high (4/5)"));
+    }
+}
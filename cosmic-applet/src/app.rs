@@ -1,5 +1,5 @@
 use crate::config::{JasperAppletConfig, APP_ID};
-use crate::dbus_client;
+use jasper_client::{Format, JasperClient, RefreshStage};
 
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
 use cosmic::iced::window::Id;
@@ -210,19 +210,18 @@ impl cosmic::Application for JasperApplet {
                 cosmic::iced::stream::channel(4, move |mut channel| async move {
                     loop {
                         // Try to connect and listen for signals
-                        if let Ok(proxy) = dbus_client::connect().await {
-                            if let Ok(mut stream) = proxy.receive_insight_updated().await {
+                        if let Ok(client) = JasperClient::connect().await {
+                            if let Ok(stream) = client.insight_updates().await {
                                 info!("Listening for D-Bus insight signals");
-                                while let Some(signal) = stream.next().await {
-                                    if let Ok(args) = signal.args() {
-                                        let _ = channel
-                                            .send(Message::InsightReceived(
-                                                args.insight_id,
-                                                args.emoji.to_string(),
-                                                args.preview.to_string(),
-                                            ))
-                                            .await;
-                                    }
+                                let mut stream = std::pin::pin!(stream);
+                                while let Some(update) = stream.next().await {
+                                    let _ = channel
+                                        .send(Message::InsightReceived(
+                                            update.id,
+                                            update.emoji,
+                                            update.preview,
+                                        ))
+                                        .await;
                                 }
                                 debug!("D-Bus signal stream ended");
                             }
@@ -303,8 +302,11 @@ impl cosmic::Application for JasperApplet {
             Message::ForceRefresh => {
                 return Task::perform(
                     async {
-                        match dbus_client::connect().await {
-                            Ok(proxy) => proxy.force_refresh().await.unwrap_or(false),
+                        match JasperClient::connect().await {
+                            Ok(client) => client
+                                .refresh()
+                                .await
+                                .is_ok_and(|done| done.stage == RefreshStage::Done),
                             Err(_) => false,
                         }
                     },
@@ -335,8 +337,8 @@ impl cosmic::Application for JasperApplet {
             Message::HeartbeatTick => {
                 return Task::perform(
                     async {
-                        if let Ok(proxy) = dbus_client::connect().await {
-                            let _ = proxy.heartbeat(FRONTEND_ID.to_string()).await;
+                        if let Ok(client) = JasperClient::connect().await {
+                            let _ = client.heartbeat(FRONTEND_ID).await;
                         }
                     },
                     |_| cosmic::Action::App(Message::ConfigChannel),
@@ -367,28 +369,26 @@ impl cosmic::Application for JasperApplet {
 
 async fn connect_and_register(
 ) -> Result<(String, String, i64), Box<dyn std::error::Error + Send + Sync>> {
-    let proxy = dbus_client::connect().await?;
-    let pid = std::process::id() as i32;
-    proxy
-        .register_frontend(FRONTEND_ID.to_string(), pid)
-        .await?;
-
-    let (id, emoji, text, _hash) = proxy.get_latest_insight().await?;
-    Ok((emoji, text, id))
+    let client = JasperClient::connect().await?;
+    client.register(FRONTEND_ID, Format::Plain).await?;
+
+    Ok(match client.latest_insight().await? {
+        Some(insight) => (insight.emoji, insight.text, insight.id),
+        None => (
+            "\u{1f50d}".to_string(),
+            "No insights available".to_string(),
+            0,
+        ),
+    })
 }
 
 async fn fetch_insight() -> Option<(i64, String, String)> {
-    let proxy = dbus_client::connect().await.ok()?;
+    let client = JasperClient::connect().await.ok()?;
     // Always re-register to ensure we're known to the daemon
-    let pid = std::process::id() as i32;
-    let _ = proxy
-        .register_frontend(FRONTEND_ID.to_string(), pid)
-        .await;
-
-    let (id, emoji, text, _hash) = proxy.get_latest_insight().await.ok()?;
-    if id > 0 {
-        Some((id, emoji, text))
-    } else {
-        Some((0, "\u{1f50d}".to_string(), "Analyzing...".to_string()))
+    let _ = client.register(FRONTEND_ID, Format::Plain).await;
+
+    match client.latest_insight().await.ok()? {
+        Some(insight) => Some((insight.id, insight.emoji, insight.text)),
+        None => Some((0, "\u{1f50d}".to_string(), "Analyzing...".to_string())),
     }
 }
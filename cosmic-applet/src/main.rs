@@ -1,6 +1,5 @@
 mod app;
 mod config;
-mod dbus_client;
 
 fn main() -> cosmic::iced::Result {
     tracing_subscriber::fmt()
@@ -0,0 +1,369 @@
+//! The calendar from the command line: upcoming events, joining and
+//! answering them, reminders and the .ics export.
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use super::{daemon_method, open_local_store};
+use api_manager::ApiManager;
+use config::Config;
+use context_sources::{todo_txt, ContextSourceManager};
+use google_calendar::GoogleCalendarService;
+use jasper_core::{
+    agenda, api_manager, command_menu, config, context_sources, deep_links, google_calendar,
+    ics_export, invitations, new_daemon_core, platform, storage, travel,
+};
+use new_daemon_core::SimplifiedDaemonCore;
+use std::path::PathBuf;
+use std::sync::Arc;
+use storage::StorageBackend;
+use travel::TravelTimeService;
+
+pub async fn show_next_event(json: bool) -> Result<()> {
+    let connection = zbus::Connection::session()
+        .await
+        .context("Failed to connect to D-Bus session bus")?;
+
+    let reply = connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.jasper.Daemon1"),
+            "GetNextEvent",
+            &(),
+        )
+        .await
+        .context("Daemon is not running")?;
+    let (title, location, starts_at, starts_in_seconds, countdown): (
+        String,
+        String,
+        i64,
+        i64,
+        String,
+    ) = reply.body().deserialize()?;
+
+    if json {
+        let output = if title.is_empty() {
+            serde_json::json!({ "text": "", "class": "jasper-no-event" })
+        } else {
+            serde_json::json!({
+                "text": countdown,
+                "class": "jasper-next-event",
+                "title": title,
+                "location": location,
+                "starts_at": starts_at,
+                "starts_in_seconds": starts_in_seconds,
+            })
+        };
+        println!("{}", output);
+    } else if title.is_empty() {
+        println!("No upcoming events");
+    } else if location.is_empty() {
+        println!("{}", countdown);
+    } else {
+        println!("{} ({})", countdown, location);
+    }
+    Ok(())
+}
+
+pub async fn show_agenda(week: bool, json: bool, markdown: bool) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+
+    let tz = config_handle.read().get_timezone();
+    let days = if week { 7 } else { 1 };
+    let first_day = chrono::Utc::now().with_timezone(&tz).date_naive();
+    let start = first_day
+        .and_hms_opt(0, 0, 0)
+        .and_then(|midnight| midnight.and_local_timezone(tz).earliest())
+        .context("Could not resolve local midnight")?
+        .to_utc();
+    let end = start + chrono::Duration::days(days as i64);
+
+    let context_manager = ContextSourceManager::from_config(&config_handle.read());
+    let travel_service = TravelTimeService::from_config(&config_handle.read());
+    let core = SimplifiedDaemonCore::new(
+        database,
+        context_manager,
+        ApiManager::new(),
+        config_handle,
+        None,
+        travel_service,
+    );
+    let context = core.agenda_context(start, end).await?;
+    let agenda_days = agenda::build(&context, first_day, days, &tz);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&agenda_days)?);
+    } else if markdown {
+        println!("{}", agenda::render_markdown(&agenda_days));
+    } else {
+        println!("{}", agenda::render_text(&agenda_days));
+    }
+    Ok(())
+}
+
+pub async fn join_next_meeting(print: bool) -> Result<()> {
+    let (_, database) = open_local_store().await?;
+    let meeting = database
+        .get_next_meeting(chrono::Utc::now())?
+        .context("No upcoming meeting with a video call")?;
+    if print {
+        println!("{}", meeting.url);
+    } else {
+        println!("Joining {}", meeting.title);
+        platform::open_uri(&meeting.url)?;
+    }
+    Ok(())
+}
+
+pub async fn rsvp(event: &str, response: invitations::Response) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let gc = config_handle
+        .read()
+        .google_calendar
+        .clone()
+        .filter(|gc| gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty())
+        .context("Google Calendar is not configured")?;
+    let gcal_config = google_calendar::GoogleCalendarConfig {
+        client_id: gc.client_id,
+        client_secret: gc.client_secret,
+        redirect_uri: gc.redirect_uri,
+        calendar_ids: gc.calendar_ids,
+    };
+    let tz = config_handle.read().get_timezone();
+    let service = GoogleCalendarService::new(gcal_config, Config::get_data_dir()?, tz);
+
+    let core = SimplifiedDaemonCore::new(
+        database,
+        ContextSourceManager::from_config(&config_handle.read()),
+        ApiManager::new(),
+        config_handle.clone(),
+        Some(service),
+        None,
+    );
+    let invitation = core.rsvp(event, response).await?;
+    let start = chrono::DateTime::from_timestamp(invitation.start_time, 0)
+        .unwrap_or_default()
+        .with_timezone(&tz);
+    println!(
+        "Answered {} to \"{}\" ({})",
+        response.status(),
+        invitation.title,
+        start.format("%a %-d %b %-I:%M %p")
+    );
+    Ok(())
+}
+
+/// Without a selection print the menu's lines; with one, do what that line offers
+pub async fn command_menu(selection: Option<String>) -> Result<()> {
+    use command_menu::{MenuAction, MenuState};
+
+    let (config_handle, database) = open_local_store().await?;
+    let tz = config_handle.read().get_timezone();
+    let now = chrono::Utc::now();
+
+    // Refresh and snooze go through the daemon; the rest is read locally
+    let connection = zbus::Connection::session().await.ok();
+    let snoozed_until = match connection {
+        Some(ref connection) => daemon_method::<_, i64>(connection, "GetSnoozedUntil", &())
+            .await
+            .ok()
+            .map(|until| chrono::DateTime::from_timestamp(until, 0).filter(|_| until > 0)),
+        None => None,
+    };
+
+    let insight = database.get_latest_insight()?;
+    let insight_actions: Vec<deep_links::InsightAction> = match insight {
+        Some(ref insight) => database
+            .get_insight_actions(insight.id)?
+            .into_iter()
+            .map(|(label, uri)| deep_links::InsightAction { label, uri })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let core = SimplifiedDaemonCore::new(
+        Arc::clone(&database),
+        ContextSourceManager::from_config(&config_handle.read()),
+        ApiManager::new(),
+        config_handle.clone(),
+        None,
+        None,
+    );
+    let context = core
+        .agenda_context(now, now + chrono::Duration::hours(12))
+        .await?;
+    let mut event_links = std::collections::HashMap::new();
+    for event in &context.calendar_events {
+        if let Some(link) = database
+            .get_event_raw_data(&event.id)?
+            .and_then(|raw| deep_links::event_link(&raw))
+        {
+            event_links.insert(event.id.clone(), link);
+        }
+    }
+
+    let entries = command_menu::entries(&MenuState {
+        insight: insight
+            .as_ref()
+            .map(|i| (i.emoji.as_str(), i.insight.as_str())),
+        insight_actions: &insight_actions,
+        context: &context,
+        event_links: &event_links,
+        snoozed_until,
+        now,
+        tz,
+    });
+    let Some(selection) = selection else {
+        for entry in &entries {
+            println!("{}", entry.line);
+        }
+        return Ok(());
+    };
+
+    // Print nothing on success so rofi's script mode closes the menu
+    let action = command_menu::select(&entries, &selection)
+        .with_context(|| format!("Not a menu entry: {}", selection.trim()))?;
+    let daemon = || connection.as_ref().context("Daemon is not running");
+    match action {
+        MenuAction::Show => {}
+        MenuAction::Open(uri) => platform::open_uri(uri)?,
+        MenuAction::Refresh => {
+            daemon_method::<_, u64>(daemon()?, "ForceRefresh", &()).await?;
+        }
+        MenuAction::Snooze(minutes) => {
+            daemon_method::<_, i64>(daemon()?, "Snooze", &(*minutes,)).await?;
+        }
+        MenuAction::CompleteTask(task_id) if task_id.starts_with(todo_txt::ID_PREFIX) => {
+            let config = config_handle.read().clone();
+            let path = config
+                .todo_txt_path()
+                .context("No todo.txt file is configured")?;
+            let today = chrono::Utc::now()
+                .with_timezone(&config.get_timezone())
+                .date_naive();
+            todo_txt::complete_in_file(&path, task_id, today)?;
+            if let Ok(connection) = daemon() {
+                let _ = daemon_method::<_, u64>(connection, "ForceRefresh", &()).await;
+            }
+        }
+        MenuAction::CompleteTask(task_id) => {
+            let vault_path = config_handle
+                .read()
+                .get_obsidian_config()
+                .filter(|c| c.enabled)
+                .map(|c| c.vault_path.clone())
+                .context("Obsidian is not configured")?;
+            let source = context_sources::obsidian::ObsidianVaultSource::new(
+                context_sources::obsidian::ObsidianConfig {
+                    vault_path,
+                    ..Default::default()
+                },
+            )?;
+            source.complete_task(task_id).await?;
+            // Let the daemon see the change now rather than at the next check
+            if let Ok(connection) = daemon() {
+                let _ = daemon_method::<_, u64>(connection, "ForceRefresh", &()).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// "YYYY-MM-DD HH:MM" (or with a T) in the configured timezone, or RFC 3339
+fn parse_local_time(input: &str, tz: chrono_tz::Tz) -> Result<chrono::DateTime<chrono::Utc>> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(input) {
+        return Ok(t.to_utc());
+    }
+    let naive = ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(input, format).ok())
+        .with_context(|| format!("Could not parse '{}'; use \"YYYY-MM-DD HH:MM\"", input))?;
+    naive
+        .and_local_timezone(tz)
+        .earliest()
+        .map(|t| t.to_utc())
+        .with_context(|| format!("{} does not exist in {}", input, tz))
+}
+
+/// Keep the configured .ics file in step after a change
+fn regenerate_ics(config: &Config, database: &dyn StorageBackend) {
+    match ics_export::regenerate(config, database) {
+        Ok(Some(path)) => info!("Updated {}", path.display()),
+        Ok(None) => {}
+        Err(e) => warn!("Could not update the .ics export: {:#}", e),
+    }
+}
+
+pub async fn add_reminder(
+    title: String,
+    at: String,
+    minutes: Option<u32>,
+    notes: Option<String>,
+) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let tz = config_handle.read().get_timezone();
+    let start = parse_local_time(&at, tz)?;
+    let end = minutes.map(|m| start + chrono::Duration::minutes(m.into()));
+    let kind = if end.is_some() { "block" } else { "reminder" };
+
+    let id = database.add_jasper_item(kind, &title, notes.as_deref(), start, end)?;
+    println!(
+        "Added {} #{}: {} at {}",
+        kind,
+        id,
+        title,
+        start.with_timezone(&tz).format("%a %-d %b %-I:%M %p")
+    );
+    regenerate_ics(&config_handle.read(), database.as_ref());
+    Ok(())
+}
+
+pub async fn list_reminders() -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let tz = config_handle.read().get_timezone();
+    let items = database.get_jasper_items()?;
+    if items.is_empty() {
+        println!("No reminders or time blocks.");
+        return Ok(());
+    }
+    for item in items {
+        let start = item.start_time.with_timezone(&tz);
+        let when = match item.end_time {
+            Some(end) => format!(
+                "{}-{}",
+                start.format("%a %-d %b %-I:%M %p"),
+                end.with_timezone(&tz).format("%-I:%M %p")
+            ),
+            None => start.format("%a %-d %b %-I:%M %p").to_string(),
+        };
+        println!(
+            "#{:<4} {:<8} {:<30} {}",
+            item.id, item.kind, when, item.title
+        );
+    }
+    Ok(())
+}
+
+pub async fn cancel_reminder(id: i64) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    if !database.delete_jasper_item(id)? {
+        anyhow::bail!("No reminder or time block #{}", id);
+    }
+    println!("Removed #{}", id);
+    regenerate_ics(&config_handle.read(), database.as_ref());
+    Ok(())
+}
+
+pub async fn export_ics(output: Option<PathBuf>) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+    let items = database.get_jasper_items()?;
+    match output {
+        Some(path) => {
+            ics_export::write(&path, &items)?;
+            println!("Wrote {} item(s) to {}", items.len(), path.display());
+        }
+        None => print!("{}", ics_export::render(&items, chrono::Utc::now())),
+    }
+    Ok(())
+}
@@ -0,0 +1,201 @@
+//! Bringing data in from other tools, and wiping it.
+
+use anyhow::{Context, Result};
+
+use super::{daemon_method, open_local_store};
+use crate::ImportSource;
+use config::Config;
+use data_wipe::{DataClass, WipeReport};
+use jasper_core::{config, context_sources, data_import, data_wipe, database, google_calendar};
+use std::path::PathBuf;
+
+pub async fn import_data(source: ImportSource) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let tz = config_handle.read().get_timezone();
+    let read = |file: &PathBuf| {
+        std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))
+    };
+
+    let tasks = match source {
+        ImportSource::Ics { file, calendar } => {
+            let import = data_import::parse_ics(&read(&file)?, tz);
+            let name = calendar.unwrap_or_else(|| {
+                file.file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "Imported".to_string())
+            });
+            let count = import.events.len();
+            let changes = database
+                .blocking(move |db| {
+                    let calendar_id = format!("{}{}", data_import::CALENDAR_PREFIX, name);
+                    let id = db.create_or_update_calendar(
+                        &calendar_id,
+                        &name,
+                        Some("imported"),
+                        Some("owner"),
+                        false,
+                    )?;
+                    db.sync_calendar_events(id, &import.events)
+                })
+                .await?;
+            let removed = changes
+                .iter()
+                .filter(|c| matches!(c.kind, database::EventChangeKind::Removed))
+                .count();
+            println!("Imported {} event(s) from {}", count, file.display());
+            if removed > 0 {
+                println!("Removed {} event(s) no longer in the file", removed);
+            }
+            if import.recurring_skipped > 0 {
+                println!(
+                    "Skipped {} recurring event(s); sync the calendar to see those",
+                    import.recurring_skipped
+                );
+            }
+            return Ok(());
+        }
+        ImportSource::Vcf { file } => {
+            let contacts = data_import::parse_vcf(&read(&file)?);
+            let config = config_handle.read().clone();
+            match config.get_obsidian_config().filter(|o| o.enabled) {
+                Some(obsidian) => {
+                    let folder = PathBuf::from(&obsidian.vault_path)
+                        .join(context_sources::obsidian::ObsidianConfig::default().people_folder);
+                    let written = data_import::write_people_notes(&folder, &contacts)?;
+                    println!(
+                        "Wrote {} people note(s) to {} ({} already had one)",
+                        written.len(),
+                        folder.display(),
+                        contacts.len() - written.len()
+                    );
+                }
+                None => println!(
+                    "Obsidian isn't configured, so no people notes were written for {} contact(s)",
+                    contacts.len()
+                ),
+            }
+            let events = data_import::birthday_events(
+                &contacts,
+                chrono::Utc::now().with_timezone(&tz).date_naive(),
+                tz,
+            );
+            let count = events.len();
+            database
+                .blocking(move |db| {
+                    let calendar_id = format!(
+                        "{}{}",
+                        data_import::CALENDAR_PREFIX,
+                        data_import::BIRTHDAYS_CALENDAR
+                    );
+                    let id = db.create_or_update_calendar(
+                        &calendar_id,
+                        data_import::BIRTHDAYS_CALENDAR,
+                        Some("imported"),
+                        Some("owner"),
+                        false,
+                    )?;
+                    db.sync_calendar_events(id, &events)
+                })
+                .await?;
+            println!(
+                "Added the next birthday of {} contact(s) to the {} calendar; import again \
+                 next year to roll them forward",
+                count,
+                data_import::BIRTHDAYS_CALENDAR
+            );
+            return Ok(());
+        }
+        ImportSource::Todoist { file } => data_import::parse_todoist_csv(&read(&file)?, tz)?,
+        ImportSource::TodoTxt { file } => data_import::parse_todo_txt(&read(&file)?, tz),
+    };
+
+    let (open, done): (Vec<_>, Vec<_>) = tasks.into_iter().partition(|t| {
+        !matches!(
+            t.status,
+            context_sources::TaskStatus::Completed | context_sources::TaskStatus::Cancelled
+        )
+    });
+    let path = config_handle.read().local_tasks_path()?;
+    let added = context_sources::tasks::add_to_local_file(&path, &open, chrono::Utc::now())?;
+    println!(
+        "Imported {} open task(s) into {} ({} new)",
+        open.len(),
+        path.display(),
+        added
+    );
+    if !done.is_empty() {
+        println!("Skipped {} completed task(s)", done.len());
+    }
+    Ok(())
+}
+
+pub async fn wipe_data(
+    classes: Vec<DataClass>,
+    tokens: bool,
+    revoke: bool,
+    json: bool,
+) -> Result<()> {
+    if classes.is_empty() {
+        anyhow::bail!("Choose what to wipe: --events, --insights, --notes-cache or --all");
+    }
+    let (_config_handle, database) = open_local_store().await?;
+    let mut report = WipeReport {
+        rows: database.blocking(move |db| db.wipe(&classes)).await?,
+        ..WipeReport::default()
+    };
+
+    if tokens {
+        let token_file = Config::get_data_dir()?.join(google_calendar::TOKEN_FILE);
+        if revoke {
+            match google_calendar::revoke_stored_token(&token_file).await {
+                Ok(true) => report.revoked.push("Google Calendar".to_string()),
+                Ok(false) => {}
+                Err(e) => report.notes.push(format!(
+                    "Google Calendar token could not be revoked ({:#}); remove Jasper at \
+                     https://myaccount.google.com/permissions",
+                    e
+                )),
+            }
+        }
+        if data_wipe::shred(&token_file)
+            .with_context(|| format!("Failed to remove {}", token_file.display()))?
+        {
+            report.files_removed.push(token_file);
+        }
+        report.notes.push(format!(
+            "API keys in {} and in the environment are kept; remove them there",
+            Config::get_config_path()?.display()
+        ));
+    }
+
+    // A running daemon still holds context in memory and writes new rows
+    if let Ok(connection) = zbus::Connection::session().await {
+        if daemon_method::<_, (bool, u32, i64)>(&connection, "GetStatus", &())
+            .await
+            .is_ok()
+        {
+            report.notes.push(
+                "The daemon is running; restart it to drop what it holds in memory".to_string(),
+            );
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    println!("Wiped:");
+    for (class, rows) in &report.rows {
+        println!("  {:<16} {} rows", class.name(), rows);
+    }
+    for file in &report.files_removed {
+        println!("  {}", file.display());
+    }
+    for service in &report.revoked {
+        println!("Revoked the {} token", service);
+    }
+    for note in &report.notes {
+        println!("Note: {}", note);
+    }
+    Ok(())
+}
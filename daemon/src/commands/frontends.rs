@@ -0,0 +1,123 @@
+//! One-shot output for the bar frontends, and the glyph preview.
+
+use anyhow::Result;
+use tracing::warn;
+
+use config::Config;
+use jasper_core::{config, glyphs, noctalia_adapter, terminal_formatter, waybar_adapter};
+
+pub async fn waybar_mode(accessible: bool, simple: bool, template: Option<String>) -> Result<()> {
+    if let Some(ref template) = template {
+        let unknown =
+            terminal_formatter::TemplateFormatter::new(template.as_str()).unknown_placeholders();
+        if !unknown.is_empty() {
+            let known: Vec<&str> = terminal_formatter::PLACEHOLDERS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect();
+            warn!(
+                "Unknown template placeholder(s): {} (known: {})",
+                unknown.join(", "),
+                known.join(", ")
+            );
+        }
+    }
+
+    let line = waybar_adapter::run_waybar_mode(accessible, simple, template)
+        .await
+        .map_err(|e| anyhow::anyhow!("Waybar mode failed: {}", e))?;
+    println!("{}", line);
+    Ok(())
+}
+
+pub async fn waybar_status_mode() -> Result<()> {
+    let status = waybar_adapter::waybar_status()
+        .await
+        .map_err(|e| anyhow::anyhow!("Waybar status failed: {}", e))?;
+    println!("{}", status);
+    Ok(())
+}
+
+pub async fn noctalia_mode(accessible: bool) -> Result<()> {
+    let line = noctalia_adapter::run_noctalia_mode(accessible)
+        .await
+        .map_err(|e| anyhow::anyhow!("Noctalia mode failed: {}", e))?;
+    println!("{}", line);
+    Ok(())
+}
+
+pub async fn noctalia_refresh_mode(accessible: bool) -> Result<()> {
+    let line = noctalia_adapter::run_noctalia_refresh(accessible)
+        .await
+        .map_err(|e| anyhow::anyhow!("Noctalia refresh failed: {}", e))?;
+    println!("{}", line);
+    Ok(())
+}
+
+pub fn preview_glyphs() -> Result<()> {
+    let glyph_config = Config::load_frontends().glyphs;
+    // Preview what the mapping would produce even if it is switched off
+    let mapper = glyphs::GlyphMapper::from_config(&config::GlyphConfig {
+        enabled: true,
+        ..glyph_config.clone()
+    });
+
+    println!(
+        "Glyph mapping is {}",
+        if glyph_config.enabled {
+            "enabled"
+        } else {
+            "disabled (set enabled = true under [frontends.glyphs])"
+        }
+    );
+    println!("\nCategories:");
+    for (category, _) in glyphs::CATEGORIES {
+        let glyph = glyph_config
+            .categories
+            .get(*category)
+            .map(String::as_str)
+            .unwrap_or_else(|| {
+                glyphs::CATEGORIES
+                    .iter()
+                    .find(|(name, _)| name == category)
+                    .map(|(_, glyph)| *glyph)
+                    .unwrap_or_default()
+            });
+        println!("  {:<10} {}  {}", category, glyph, codepoints(glyph));
+    }
+
+    if !glyph_config.emoji.is_empty() {
+        println!("\nEmoji overrides:");
+        for emoji in glyph_config.emoji.keys() {
+            let glyph = mapper.map(emoji);
+            println!("  {}  ->  {}  {}", emoji, glyph, codepoints(&glyph));
+        }
+    }
+
+    let fallback = mapper.map("\u{1F984}");
+    println!("\nFallback: {}  {}", fallback, codepoints(&fallback));
+    println!("\nIf any glyph above shows as a box or question mark, your terminal font lacks it.");
+
+    let problems = glyphs::GlyphMapper::validate(&glyph_config);
+    if problems.is_empty() {
+        println!("\nNo problems found.");
+        return Ok(());
+    }
+
+    println!();
+    for problem in &problems {
+        println!("  ! {}", problem);
+    }
+    Err(anyhow::anyhow!(
+        "{} problem(s) in [frontends.glyphs]",
+        problems.len()
+    ))
+}
+
+fn codepoints(glyph: &str) -> String {
+    glyph
+        .chars()
+        .map(|c| format!("U+{:04X}", c as u32))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,432 @@
+//! Reading insights and escalations, and asking for new ones.
+
+use anyhow::{Context, Result};
+use tracing::{info, warn};
+
+use super::{daemon_method, open_local_store};
+use config::Config;
+use jasper_core::{
+    config, correlation_graph, glyphs, greet, insight_detail, platform, snapshot_codec,
+};
+
+pub async fn show_digest() -> Result<()> {
+    let connection = zbus::Connection::session()
+        .await
+        .context("Failed to connect to D-Bus session bus")?;
+
+    let reply = connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.jasper.Daemon1"),
+            "GetLatestDigest",
+            &(),
+        )
+        .await
+        .context("Daemon is not running")?;
+    let (id, content, created_at): (i64, String, i64) = reply.body().deserialize()?;
+
+    if id <= 0 {
+        println!("No digest yet — one is generated with the first insight each morning.");
+        return Ok(());
+    }
+
+    let when = chrono::DateTime::from_timestamp(created_at, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%A %-d %B, %-I:%M %p")
+                .to_string()
+        })
+        .unwrap_or_default();
+    println!("Digest ({})\n", when);
+    println!("{}", content);
+    Ok(())
+}
+
+pub async fn list_escalations() -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let after = config_handle.read().escalation.after_insights;
+    let records = database.get_escalations()?;
+    if records.is_empty() {
+        println!("No critical items are being tracked.");
+        return Ok(());
+    }
+    for record in records {
+        let state = if record.dismissed {
+            "dismissed".to_string()
+        } else if record.streak >= after {
+            "escalated".to_string()
+        } else {
+            format!("{}/{}", record.streak, after)
+        };
+        println!("{:<10} {:<30} {}", state, record.item_key, record.label);
+    }
+    Ok(())
+}
+
+pub async fn dismiss_escalations(key: Option<String>) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+    match database.dismiss_escalations(key.as_deref())? {
+        0 => match key {
+            Some(key) => anyhow::bail!("No tracked item {}", key),
+            None => println!("Nothing to dismiss."),
+        },
+        n => println!("Dismissed {} item(s)", n),
+    }
+    Ok(())
+}
+
+pub async fn show_history(limit: u32, verbose: bool) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+
+    let insights = database.get_recent_insights(limit)?;
+    if insights.is_empty() {
+        println!("No insights yet.");
+        return Ok(());
+    }
+    // How many of the listed insights each source contributed to
+    let mut used: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+    for insight in &insights {
+        let when = insight
+            .created_at
+            .with_timezone(&chrono::Local)
+            .format("%a %-d %b %H:%M");
+        println!("{}  {} {}", when, insight.emoji, insight.insight);
+        if !verbose {
+            continue;
+        }
+        let sources = database.get_insight_sources(insight.id)?;
+        let detail = if sources.is_empty() {
+            "sources: none recorded".to_string()
+        } else {
+            let listed: Vec<String> = sources
+                .iter()
+                .map(|(source, items)| format!("{} ({})", source, items))
+                .collect();
+            format!("sources: {}", listed.join(", "))
+        };
+        println!("{:>16}  {}", "", detail);
+        for source in sources.into_keys() {
+            *used.entry(source).or_insert(0) += 1;
+        }
+    }
+    if verbose && !used.is_empty() {
+        let listed: Vec<String> = used
+            .iter()
+            .map(|(source, n)| format!("{} {}/{}", source, n, insights.len()))
+            .collect();
+        println!("\nSources used: {}", listed.join(", "));
+    }
+    Ok(())
+}
+
+/// One line for an insight generated `age_seconds` ago: tab-separated, or a
+/// JSON object with `json`
+fn insight_line(id: i64, emoji: &str, text: &str, age_seconds: i64, json: bool) -> String {
+    let at = (chrono::Local::now() - chrono::Duration::seconds(age_seconds.max(0)))
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    let urgency = glyphs::emoji_urgency(emoji);
+    if json {
+        return serde_json::json!({
+            "id": id,
+            "timestamp": at,
+            "emoji": emoji,
+            "text": text,
+            "urgency": urgency,
+        })
+        .to_string();
+    }
+    let text = text.replace(['\t', '\n'], " ");
+    format!("{}\t{}\t{}\t{}", at, emoji, text, urgency)
+}
+
+pub async fn greet(force: bool, speak: bool) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let config = config_handle.read().clone();
+    let tz = config.get_timezone();
+    let today = chrono::Utc::now().with_timezone(&tz).date_naive();
+    let marker = Config::get_data_dir()?.join(greet::MARKER_FILE);
+    if !force && greet::greeted_on(&marker, today) {
+        info!("Already greeted today");
+        return Ok(());
+    }
+
+    let todays_digest = || -> Result<Option<String>> {
+        Ok(database
+            .get_latest_digest()?
+            .filter(|d| greet::is_on(d.created_at, today, &tz))
+            .map(|d| d.content))
+    };
+    let mut digest = todays_digest()?;
+    if digest.is_none() {
+        match refresh_and_wait(config.greet.wait_seconds).await {
+            Ok(()) => digest = todays_digest()?,
+            Err(e) => warn!("No refresh from the daemon: {:#}", e),
+        }
+    }
+    // Digests are only written in the morning; later, today's insight stands in
+    let (title, text) = match digest {
+        Some(digest) => ("Good morning", digest),
+        None => match database
+            .get_latest_insight()?
+            .filter(|i| greet::is_on(i.created_at, today, &tz))
+        {
+            Some(insight) => ("Jasper", format!("{} {}", insight.emoji, insight.insight)),
+            None => anyhow::bail!("Nothing to greet with: no digest or insight yet today"),
+        },
+    };
+
+    platform::notifier().notify(title, &text, platform::Urgency::Normal)?;
+    if speak || config.greet.speak {
+        // Being heard is a bonus; the notification is already up
+        if let Err(e) = platform::speak(
+            &greet::speakable(&text),
+            config.greet.tts_command.as_deref(),
+        ) {
+            warn!("Could not read the greeting aloud: {}", e);
+        }
+    }
+    greet::mark_greeted(&marker, today)
+}
+
+/// Ask the daemon for a refresh and wait until it has finished, giving the
+/// daemon `wait_seconds` to come up first
+async fn refresh_and_wait(wait_seconds: u64) -> Result<()> {
+    use futures_util::StreamExt;
+    use std::time::{Duration, Instant};
+
+    let connection = zbus::Connection::session()
+        .await
+        .context("Failed to connect to D-Bus session bus")?;
+    let deadline = Instant::now() + Duration::from_secs(wait_seconds);
+    while daemon_method::<_, (bool, u32, i64)>(&connection, "GetStatus", &())
+        .await
+        .is_err()
+    {
+        if Instant::now() >= deadline {
+            anyhow::bail!("Daemon is not running");
+        }
+        tokio::time::sleep(Duration::from_secs(2)).await;
+    }
+
+    // Subscribe first so the final stage can't be missed
+    let proxy = zbus::Proxy::new(
+        &connection,
+        "org.jasper.Daemon",
+        "/org/jasper/Daemon",
+        "org.jasper.Daemon1",
+    )
+    .await?;
+    let mut progress = proxy.receive_signal("RefreshProgress").await?;
+    let request_id: u64 = daemon_method(&connection, "ForceRefresh", &()).await?;
+    let finished = async {
+        while let Some(message) = progress.next().await {
+            let Ok((id, stage, detail)) = message.body().deserialize::<(u64, String, String)>()
+            else {
+                continue;
+            };
+            if id == request_id && matches!(stage.as_str(), "done" | "failed") {
+                return Some((stage, detail));
+            }
+        }
+        None
+    };
+    match tokio::time::timeout(Duration::from_secs(120), finished).await {
+        Ok(Some((stage, _))) if stage == "done" => Ok(()),
+        Ok(Some((_, detail))) => anyhow::bail!("Refresh failed: {}", detail),
+        Ok(None) => anyhow::bail!("The daemon stopped during the refresh"),
+        Err(_) => anyhow::bail!("Refresh still running after 2 minutes"),
+    }
+}
+
+pub async fn print_insights(follow: bool, json: bool) -> Result<()> {
+    use futures_util::StreamExt;
+    use std::io::Write;
+
+    let connection = zbus::Connection::session()
+        .await
+        .context("Failed to connect to D-Bus session bus")?;
+    // Subscribe before reading the latest so nothing falls in between
+    let mut updates = if follow {
+        let proxy = zbus::Proxy::new(
+            &connection,
+            "org.jasper.Daemon",
+            "/org/jasper/Daemon",
+            "org.jasper.Daemon1",
+        )
+        .await?;
+        Some(proxy.receive_signal("InsightUpdated").await?)
+    } else {
+        None
+    };
+
+    let mut stdout = std::io::stdout();
+    let (id, emoji, text, _hash): (i64, String, String, String) =
+        daemon_method(&connection, "GetLatestInsight", &()).await?;
+    if id > 0 {
+        let (age, _stale): (i64, bool) =
+            daemon_method(&connection, "GetInsightFreshness", &()).await?;
+        writeln!(stdout, "{}", insight_line(id, &emoji, &text, age, json))?;
+        stdout.flush()?;
+    }
+
+    let Some(updates) = updates.as_mut() else {
+        return Ok(());
+    };
+    while let Some(message) = updates.next().await {
+        type Update = (
+            i64,
+            String,
+            String,
+            std::collections::HashMap<String, String>,
+            i64,
+            bool,
+            Vec<(String, String)>,
+        );
+        let Ok((id, emoji, preview, _formatted, age, _stale, _actions)) =
+            message.body().deserialize::<Update>()
+        else {
+            continue;
+        };
+        // A closed pipe (`| head`) ends the follow
+        if writeln!(stdout, "{}", insight_line(id, &emoji, &preview, age, json))
+            .and_then(|()| stdout.flush())
+            .is_err()
+        {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub async fn show_insight(insight_id: Option<i64>, json: bool) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+
+    let insight_id = match insight_id {
+        Some(id) => id,
+        None => match database.get_latest_insight()? {
+            Some(insight) => insight.id,
+            None => {
+                println!("No insights yet.");
+                return Ok(());
+            }
+        },
+    };
+    let detail = insight_detail::load(database.as_ref(), insight_id)?
+        .with_context(|| format!("No insight {}", insight_id))?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&detail)?);
+        return Ok(());
+    }
+    let tz = config_handle.read().get_timezone();
+    page(&insight_detail::render(&detail, &tz))
+}
+
+/// Show `text` through $PAGER (or `less`) when stdout is a terminal, else print it
+fn page(text: &str) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+    if !std::io::stdout().is_terminal() {
+        print!("{}", text);
+        return Ok(());
+    }
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    // -F quits at once if it fits, -R passes colour, -X leaves it on screen
+    let child = std::process::Command::new("sh")
+        .args(["-c", &pager])
+        .env(
+            "LESS",
+            std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string()),
+        )
+        .stdin(std::process::Stdio::piped())
+        .spawn();
+    let Ok(mut child) = child else {
+        print!("{}", text);
+        return Ok(());
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closing early (q) breaks the pipe; that's fine
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait().context("Pager failed")?;
+    Ok(())
+}
+
+pub async fn show_graph(dot: bool, days: u32, json: bool) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let tz = config_handle.read().get_timezone();
+    let now = chrono::Utc::now();
+    let graph = correlation_graph::load(
+        database.as_ref(),
+        now,
+        now + chrono::Duration::days(days as i64),
+    )?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&graph)?);
+    } else if dot {
+        print!("{}", correlation_graph::to_dot(&graph, &tz));
+    } else if graph.edges.is_empty() {
+        println!("Nothing connected in the next {} days.", days);
+    } else {
+        let label = |id: &str| {
+            graph
+                .nodes
+                .iter()
+                .find(|n| n.id == id)
+                .map_or_else(|| id.to_string(), |n| n.label.clone())
+        };
+        for edge in &graph.edges {
+            println!(
+                "{} —{}→ {} ({:.0}%)",
+                label(&edge.from),
+                edge.relation,
+                label(&edge.to),
+                edge.confidence * 100.0
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn show_snapshot(insight_id: Option<i64>) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+
+    let insight_id = match insight_id {
+        Some(id) => id,
+        None => match database.get_latest_insight()? {
+            Some(insight) => insight.id,
+            None => {
+                println!("No insights yet.");
+                return Ok(());
+            }
+        },
+    };
+    match snapshot_codec::load_for_insight(database.as_ref(), insight_id)? {
+        Some(snapshot) => println!("{}", serde_json::to_string_pretty(&snapshot)?),
+        None => println!("No context snapshot kept for insight {}.", insight_id),
+    }
+    Ok(())
+}
+
+pub async fn show_triggers(limit: u32) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+
+    let entries = database.get_trigger_audit(limit)?;
+    if entries.is_empty() {
+        println!("No trigger decisions recorded yet.");
+        return Ok(());
+    }
+    for entry in entries {
+        let when = entry
+            .created_at
+            .with_timezone(&chrono::Local)
+            .format("%a %-d %b %H:%M");
+        println!("{}  {:<22} {}", when, entry.trigger, entry.decision);
+        if let Some(detail) = entry.detail {
+            println!("{:>16}  {}", "", detail);
+        }
+    }
+    Ok(())
+}
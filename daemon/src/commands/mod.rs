@@ -0,0 +1,71 @@
+//! The commands behind `jasper-companion-daemon`'s subcommands, grouped by
+//! what they work on. Commands print their own output; main.rs parses the
+//! command line and dispatches here.
+
+pub mod calendar;
+pub mod data;
+pub mod frontends;
+pub mod insights;
+pub mod service;
+pub mod status;
+
+use anyhow::{Context, Result};
+
+use config::Config;
+use config_handle::ConfigHandle;
+use database::{Database, DatabaseInner};
+use jasper_core::{config, config_handle, database};
+
+/// Read a property of the daemon's D-Bus interface
+async fn daemon_property<T: TryFrom<zbus::zvariant::OwnedValue>>(
+    connection: &zbus::Connection,
+    name: &str,
+) -> Option<T> {
+    let reply = connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &("org.jasper.Daemon1", name),
+        )
+        .await
+        .ok()?;
+    let value: zbus::zvariant::OwnedValue = reply.body().deserialize().ok()?;
+    T::try_from(value).ok()
+}
+
+/// Call an org.jasper.Daemon1 method on the running daemon
+async fn daemon_method<B, R>(connection: &zbus::Connection, method: &str, body: &B) -> Result<R>
+where
+    B: serde::Serialize + zbus::zvariant::DynamicType,
+    R: for<'d> serde::Deserialize<'d> + zbus::zvariant::Type,
+{
+    let reply = connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.jasper.Daemon1"),
+            method,
+            body,
+        )
+        .await
+        .context("Daemon is not running")?;
+    Ok(reply.body().deserialize()?)
+}
+
+/// Config and database for commands that work without the daemon running
+async fn open_local_store() -> Result<(ConfigHandle, Database)> {
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+    let db_key = config_handle
+        .read()
+        .database_key()
+        .context("Failed to obtain database encryption key")?;
+    let db_path = config_handle.read().database_path()?;
+    let database = DatabaseInner::new(&db_path, db_key)
+        .await
+        .context("Failed to open database")?;
+    Ok((config_handle, database))
+}
@@ -0,0 +1,560 @@
+//! Running the daemon, and setting it up: service files, credentials,
+//! config migration and the demo data.
+
+use anyhow::{Context, Result};
+use tracing::{error, info, warn};
+
+use api_manager::ApiManager;
+use config::Config;
+use context_sources::ContextSourceManager;
+use database::DatabaseInner;
+use google_calendar::GoogleCalendarService;
+use jasper_core::{
+    ai_check, api_manager, config, config_migration, context_sources, database, demo,
+    desktop_detection, google_calendar, mock_ai, new_daemon_core, new_dbus_service, platform,
+    travel,
+};
+use new_daemon_core::SimplifiedDaemonCore;
+use new_dbus_service::SimplifiedDbusService;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::RwLock;
+use travel::TravelTimeService;
+
+pub async fn start_daemon(force_headless: bool, accessible: bool) -> Result<()> {
+    info!("Starting Jasper simplified daemon");
+
+    let headless = force_headless || desktop_detection::is_headless_environment();
+    if headless && !force_headless {
+        info!("No session D-Bus found; running headless");
+    }
+
+    // Load configuration
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+
+    // Initialize database
+    let db_path = config_handle.read().database_path()?;
+    let db_key = config_handle
+        .read()
+        .database_key()
+        .context("Failed to obtain database encryption key")?;
+    let database = DatabaseInner::new(&db_path, db_key)
+        .await
+        .context("Failed to initialize database")?;
+
+    let context_manager = ContextSourceManager::from_config(&config_handle.read());
+
+    // Initialize Google Calendar service if configured
+    let calendar_service = {
+        let config = config_handle.read();
+        config.google_calendar.as_ref().and_then(|gc| {
+            if gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty() {
+                let gcal_config = google_calendar::GoogleCalendarConfig {
+                    client_id: gc.client_id.clone(),
+                    client_secret: gc.client_secret.clone(),
+                    redirect_uri: gc.redirect_uri.clone(),
+                    calendar_ids: gc.calendar_ids.clone(),
+                };
+                let data_dir = Config::get_data_dir().ok()?;
+                let tz = config.get_timezone();
+                info!("Google Calendar service initialized");
+                Some(GoogleCalendarService::new(gcal_config, data_dir, tz))
+            } else {
+                None
+            }
+        })
+    };
+
+    let travel_service = TravelTimeService::from_config(&config_handle.read());
+
+    // Pick up edits to config.toml while running
+    config_handle.watch_file(
+        Config::get_config_path()?,
+        tokio::time::Duration::from_secs(5),
+    );
+
+    // Initialize API manager
+    let api_manager = ApiManager::new();
+
+    // Create the simplified daemon core
+    let daemon_core = Arc::new(RwLock::new(
+        SimplifiedDaemonCore::new(
+            database,
+            context_manager,
+            api_manager,
+            config_handle,
+            calendar_service,
+            travel_service,
+        )
+        .with_headless(headless)
+        .with_accessible(accessible),
+    ));
+
+    info!("Simplified daemon core created");
+
+    // Start D-Bus service in background (skipped headless: there is no session bus)
+    let dbus_handle = if headless {
+        None
+    } else {
+        let dbus_daemon = daemon_core.clone();
+        let handle = tokio::spawn(async move {
+            if let Err(e) = SimplifiedDbusService::start(dbus_daemon).await {
+                error!("D-Bus service failed: {}", e);
+            }
+        });
+
+        // Give D-Bus service time to establish connection
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+        // Initialize the D-Bus signal emitter for push notifications to frontends
+        let daemon = daemon_core.read().await;
+        if let Err(e) = daemon.init_signal_emitter().await {
+            warn!(
+                "Could not initialize signal emitter (frontends will use polling): {}",
+                e
+            );
+        }
+        Some(handle)
+    };
+
+    // Start the main daemon loop in a separate task
+    // Using start_with_arc to avoid holding lock for entire runtime
+    let daemon_core_clone = daemon_core.clone();
+    let daemon_handle =
+        tokio::spawn(async move { SimplifiedDaemonCore::start_with_arc(daemon_core_clone).await });
+
+    let dbus_stopped = async {
+        match dbus_handle {
+            Some(handle) => {
+                let _ = handle.await;
+            }
+            None => std::future::pending().await,
+        }
+    };
+
+    // Wait for either the daemon or D-Bus service to finish
+    tokio::select! {
+        result = daemon_handle => {
+            if let Err(e) = result {
+                error!("Daemon loop failed: {}", e);
+            }
+        }
+        _ = dbus_stopped => {
+            info!("D-Bus service stopped");
+        }
+    }
+
+    info!("Simplified daemon stopped");
+    Ok(())
+}
+
+pub async fn stop_daemon() -> Result<()> {
+    let connection = zbus::Connection::session()
+        .await
+        .context("Failed to connect to D-Bus session bus")?;
+
+    match connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.jasper.Daemon1"),
+            "GetStatus",
+            &(),
+        )
+        .await
+    {
+        Ok(_) => {
+            println!(
+                "Daemon is running. Use 'systemctl --user stop jasper-daemon' or send SIGTERM."
+            );
+        }
+        Err(_) => {
+            println!("Daemon is not running.");
+        }
+    }
+    Ok(())
+}
+
+pub async fn set_api_key(key: String) -> Result<()> {
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+    config_handle
+        .update(|config| config.ai.api_key = Some(key))?
+        .save()
+        .await
+        .context("Failed to save configuration")?;
+
+    println!("Claude API key updated successfully");
+    Ok(())
+}
+
+pub async fn migrate_config(dry_run: bool) -> Result<()> {
+    let Some((report, backup)) = Config::migrate_file(dry_run).await? else {
+        println!("No config file at {}", Config::get_config_path()?.display());
+        return Ok(());
+    };
+
+    if !report.changed() {
+        if report.from_version > config_migration::CURRENT_VERSION {
+            println!(
+                "Config is version {}, newer than this Jasper ({}); nothing to do",
+                report.from_version,
+                config_migration::CURRENT_VERSION
+            );
+        } else {
+            println!("Config is up to date (version {})", report.from_version);
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{} config from version {} to {}:",
+        if dry_run { "Would migrate" } else { "Migrated" },
+        report.from_version,
+        config_migration::CURRENT_VERSION
+    );
+    for change in &report.changes {
+        println!("  - {}", change);
+    }
+    if let Some(backup) = backup {
+        println!("Original kept at {}", backup.display());
+    }
+    Ok(())
+}
+
+pub async fn verify_ai() -> Result<()> {
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+    let ai = config_handle.read().ai.clone();
+    if ai.provider == mock_ai::PROVIDER {
+        println!("AI provider is \"mock\": canned responses, no key needed");
+        return Ok(());
+    }
+    let api_key = config_handle.read().get_api_key().context(
+        "No API key configured. Run `set-api-key <key>`, set ai.api_key in SOPS secrets, \
+         or export ANTHROPIC_API_KEY",
+    )?;
+    let shown = api_key
+        .get(api_key.len().saturating_sub(4)..)
+        .unwrap_or_default();
+    println!("Checking API key …{} with Anthropic", shown);
+
+    let client = reqwest::Client::new();
+    let mut models = ai_check::list_models(&client, &api_key).await?;
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+    println!("✓ Key accepted; {} models available:", models.len());
+    for model in &models {
+        println!("  {} ({})", model.id, model.display_name);
+    }
+
+    let missing: Vec<_> = ai_check::configured_models(&ai)
+        .into_iter()
+        .filter(|(model, _)| !ai_check::is_available(model, &models))
+        .collect();
+    for (model, setting) in &missing {
+        println!("✗ {} ({}) is not available to this key", model, setting);
+    }
+
+    if ai_check::is_available(&ai.model, &models) {
+        let headers = ai_check::ping(&client, &api_key, &ai.model).await?;
+        println!("✓ {} answered a one-token request", ai.model);
+        for line in ai_check::rate_limits(&headers) {
+            println!("  {}", line);
+        }
+    }
+
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "{} configured model(s) can't be used; pick replacements from the list above",
+            missing.len()
+        );
+    }
+    Ok(())
+}
+
+pub async fn auth_google() -> Result<()> {
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+
+    let gc = {
+        let config = config_handle.read();
+        config.google_calendar.as_ref()
+            .filter(|gc| gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "Google Calendar is not configured. Set enabled=true, client_id, and client_secret in [google_calendar] section of config.toml"
+            ))?
+    };
+
+    let gcal_config = google_calendar::GoogleCalendarConfig {
+        client_id: gc.client_id,
+        client_secret: gc.client_secret,
+        redirect_uri: gc.redirect_uri.clone(),
+        calendar_ids: gc.calendar_ids,
+    };
+    let data_dir = Config::get_data_dir()?;
+    let tz = config_handle.read().get_timezone();
+    let service = GoogleCalendarService::new(gcal_config, data_dir.clone(), tz);
+
+    // Check if already authenticated
+    if service.is_authenticated().await && service.can_answer_invitations().await {
+        println!("Already authenticated with Google Calendar.");
+        println!("To re-authenticate, delete the token file and run this command again:");
+        println!(
+            "  rm {}",
+            data_dir.join(google_calendar::TOKEN_FILE).display()
+        );
+        return Ok(());
+    }
+
+    if service.is_authenticated().await {
+        println!("Authorizing again so Jasper can answer invitations (rsvp).\n");
+    }
+
+    let (auth_url, csrf_token) = service
+        .get_auth_url()
+        .context("Failed to generate OAuth URL")?;
+
+    println!("Open this URL in your browser to authorize Jasper:\n");
+    println!("  {}\n", auth_url);
+    println!("Waiting for callback on {} ...", gc.redirect_uri);
+
+    // Try to open the URL automatically
+    let _ = std::process::Command::new("xdg-open")
+        .arg(&auth_url)
+        .spawn();
+
+    // Start one-shot callback server on 127.0.0.1:8080
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:8080")
+        .await
+        .context("Failed to bind to 127.0.0.1:8080 - is another process using this port?")?;
+
+    let (mut stream, _addr) = listener
+        .accept()
+        .await
+        .context("Failed to accept callback connection")?;
+
+    // Read the HTTP request
+    let mut buf = vec![0u8; 4096];
+    let n = tokio::io::AsyncReadExt::read(&mut stream, &mut buf)
+        .await
+        .context("Failed to read callback request")?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+
+    // Parse the request line to extract query params: GET /auth/callback?code=...&state=... HTTP/1.1
+    let code = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1)) // "/auth/callback?code=...&state=..."
+        .and_then(|path| path.split('?').nth(1)) // "code=...&state=..."
+        .and_then(|query| {
+            query
+                .split('&')
+                .find_map(|param| param.strip_prefix("code="))
+        })
+        .map(|c| c.to_string())
+        .ok_or_else(|| anyhow::anyhow!("No authorization code found in callback"))?;
+
+    // Send success response to browser
+    let html = "<html><body><h2>Authentication successful!</h2><p>You can close this tab and return to the terminal.</p></body></html>";
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        html.len(),
+        html
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.flush().await;
+
+    // Exchange the code for a token
+    println!("Authorization code received, exchanging for token...");
+    service
+        .authenticate_with_code(&code, csrf_token.secret())
+        .await
+        .context("Failed to exchange authorization code for token")?;
+
+    println!("Google Calendar authentication successful!");
+    println!("Token saved. Restart the daemon to begin syncing calendar events.");
+    Ok(())
+}
+
+pub async fn list_calendars() -> Result<()> {
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+
+    let gc = {
+        let config = config_handle.read();
+        config.google_calendar.as_ref()
+            .filter(|gc| gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "Google Calendar is not configured. Set enabled=true, client_id, and client_secret in [google_calendar] section of config.toml"
+            ))?
+    };
+
+    let gcal_config = google_calendar::GoogleCalendarConfig {
+        client_id: gc.client_id,
+        client_secret: gc.client_secret,
+        redirect_uri: gc.redirect_uri,
+        calendar_ids: gc.calendar_ids.clone(),
+    };
+    let synced_ids: std::collections::HashSet<String> = gc.calendar_ids.into_iter().collect();
+
+    let data_dir = Config::get_data_dir()?;
+    let tz = config_handle.read().get_timezone();
+    let service = GoogleCalendarService::new(gcal_config, data_dir, tz);
+
+    println!("Fetching calendars from Google...");
+    let calendars = service.fetch_calendar_list().await.context(
+        "Failed to fetch calendar list. Are you authenticated? Run 'auth-google' first.",
+    )?;
+
+    if calendars.is_empty() {
+        println!("No calendars found on this Google account.");
+        return Ok(());
+    }
+
+    // Build display labels and figure out which are pre-selected
+    let labels: Vec<String> = calendars
+        .iter()
+        .map(|cal| {
+            let name = cal.summary.as_deref().unwrap_or("(unnamed)");
+            if cal.primary.unwrap_or(false) {
+                format!("{} (primary)", name)
+            } else if cal.hidden.unwrap_or(false) {
+                format!("{} (hidden in Google Calendar)", name)
+            } else {
+                name.to_string()
+            }
+        })
+        .collect();
+
+    let defaults: Vec<bool> = calendars
+        .iter()
+        .map(|cal| {
+            synced_ids.contains(&cal.id)
+                || (cal.primary.unwrap_or(false) && synced_ids.contains("primary"))
+        })
+        .collect();
+
+    // Interactive checkbox selector
+    let selections = dialoguer::MultiSelect::new()
+        .with_prompt("Select calendars to sync (space to toggle, enter to confirm)")
+        .items(&labels)
+        .defaults(&defaults)
+        .interact_opt()
+        .context("Calendar selection cancelled")?;
+
+    let Some(selections) = selections else {
+        println!("Cancelled, no changes made.");
+        return Ok(());
+    };
+
+    // Map selected indices back to calendar IDs
+    let new_calendar_ids: Vec<String> = selections
+        .iter()
+        .map(|&i| {
+            let cal = &calendars[i];
+            if cal.primary.unwrap_or(false) {
+                "primary".to_string()
+            } else {
+                cal.id.clone()
+            }
+        })
+        .collect();
+
+    // Check if anything actually changed
+    let old_set: std::collections::HashSet<&str> = synced_ids.iter().map(|s| s.as_str()).collect();
+    let new_set: std::collections::HashSet<&str> =
+        new_calendar_ids.iter().map(|s| s.as_str()).collect();
+    if old_set == new_set {
+        println!("No changes made.");
+        return Ok(());
+    }
+
+    // Show what changed
+    for id in new_set.difference(&old_set) {
+        let name = calendars
+            .iter()
+            .find(|c| c.id == *id || (*id == "primary" && c.primary.unwrap_or(false)))
+            .and_then(|c| c.summary.as_deref())
+            .unwrap_or(id);
+        println!("  + {}", name);
+    }
+    for id in old_set.difference(&new_set) {
+        let name = calendars
+            .iter()
+            .find(|c| c.id == *id || (*id == "primary" && c.primary.unwrap_or(false)))
+            .and_then(|c| c.summary.as_deref())
+            .unwrap_or(id);
+        println!("  - {}", name);
+    }
+
+    // Save to config
+    config_handle
+        .update(|config| {
+            if let Some(ref mut gc) = config.google_calendar {
+                gc.calendar_ids = new_calendar_ids;
+            }
+        })?
+        .save()
+        .await
+        .context("Failed to save configuration")?;
+
+    println!("\nConfiguration saved. Restart the daemon to apply changes.");
+    Ok(())
+}
+
+fn service_scope(user: bool) -> platform::ServiceScope {
+    if user {
+        platform::ServiceScope::CurrentUser
+    } else {
+        platform::ServiceScope::AllUsers
+    }
+}
+
+pub fn install_service(user: bool) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the daemon executable")?;
+    let autostart = platform::autostart();
+    let summary = autostart
+        .install(&exe, service_scope(user))
+        .with_context(|| format!("Failed to install {}", autostart.name()))?;
+    println!("{}", summary);
+    Ok(())
+}
+
+pub fn uninstall_service(user: bool) -> Result<()> {
+    let autostart = platform::autostart();
+    let summary = autostart
+        .uninstall(service_scope(user))
+        .with_context(|| format!("Failed to remove {}", autostart.name()))?;
+    println!("{}", summary);
+    Ok(())
+}
+
+pub async fn seed_demo(scenario: demo::Scenario, dir: Option<PathBuf>) -> Result<()> {
+    let dir = dir.unwrap_or_else(|| demo::default_profile_dir(scenario));
+    let profile = demo::seed_profile(scenario, &dir, chrono::Local::now())
+        .await
+        .with_context(|| format!("Failed to seed demo profile in {:?}", dir))?;
+
+    println!(
+        "Seeded '{}' demo profile with {} events in {}",
+        scenario.name(),
+        profile.events,
+        dir.display()
+    );
+    println!("Run it with:");
+    println!(
+        "  jasper-companion-daemon --config {} --data-dir {} start",
+        profile.config_path.display(),
+        profile.data_dir.display()
+    );
+    Ok(())
+}
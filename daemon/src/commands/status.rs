@@ -0,0 +1,383 @@
+//! Reporting on the daemon: status, health, usage and what it stores.
+
+use anyhow::{Context, Result};
+
+use super::{daemon_property, open_local_store};
+use config::Config;
+use jasper_core::{config, new_daemon_core, privacy_audit, update_check};
+use new_daemon_core::StatusDetails;
+
+pub async fn show_status(verbose: bool, json: bool) -> Result<()> {
+    let connection = match zbus::Connection::session().await {
+        Ok(c) => c,
+        Err(_) => {
+            if json {
+                println!("{}", serde_json::json!({ "running": false }));
+                return Ok(());
+            }
+            println!("Daemon Status: Not Running");
+            println!("  D-Bus session bus unavailable");
+            return Ok(());
+        }
+    };
+
+    let status = connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.jasper.Daemon1"),
+            "GetStatus",
+            &(),
+        )
+        .await;
+    let (is_running, active_frontends, insights_count): (bool, u32, i64) = match status {
+        Ok(reply) => reply.body().deserialize()?,
+        Err(_) => {
+            if json {
+                println!("{}", serde_json::json!({ "running": false }));
+            } else {
+                println!("Daemon Status: Not Running");
+            }
+            return Ok(());
+        }
+    };
+    let version = daemon_property::<String>(&connection, "Version").await;
+    let details = if verbose || json {
+        status_details(&connection).await
+    } else {
+        None
+    };
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "running": is_running,
+                "active_frontends": active_frontends,
+                "insights": insights_count,
+                "version": version,
+                "details": details,
+            }))?
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Daemon Status: {}",
+        if is_running { "Running" } else { "Stopped" }
+    );
+    println!("  Active frontends: {}", active_frontends);
+    println!("  Total insights:   {}", insights_count);
+    println!(
+        "  Version:          {}",
+        version.as_deref().unwrap_or("unknown")
+    );
+    if let Some(model) = daemon_property::<String>(&connection, "LatestInsightModel")
+        .await
+        .filter(|m| !m.is_empty())
+    {
+        let fallback = daemon_property::<bool>(&connection, "LatestInsightFallback")
+            .await
+            .unwrap_or(false);
+        println!(
+            "  Latest insight:   {}{}",
+            model,
+            if fallback {
+                " (fallback — primary model was unavailable)"
+            } else {
+                ""
+            }
+        );
+    }
+    if let Some(update) = daemon_property::<String>(&connection, "UpdateAvailable")
+        .await
+        .filter(|v| !v.is_empty())
+    {
+        println!(
+            "  Update available: {} (current {})",
+            update,
+            update_check::CURRENT_VERSION
+        );
+    }
+
+    if verbose {
+        match details {
+            Some(details) => print_status_details(&details),
+            None => println!("\n  (This daemon doesn't report details; update it for --verbose)"),
+        }
+    }
+    Ok(())
+}
+
+/// The daemon's `status --verbose` details, if it is new enough to have them
+async fn status_details(connection: &zbus::Connection) -> Option<StatusDetails> {
+    let reply = connection
+        .call_method(
+            Some("org.jasper.Daemon"),
+            "/org/jasper/Daemon",
+            Some("org.jasper.Daemon1"),
+            "GetStatusDetails",
+            &(),
+        )
+        .await
+        .ok()?;
+    let json: String = reply.body().deserialize().ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn print_status_details(details: &StatusDetails) {
+    let now = chrono::Utc::now();
+    let local = |t: chrono::DateTime<chrono::Utc>| {
+        t.with_timezone(&chrono::Local).format("%H:%M").to_string()
+    };
+
+    println!();
+    match details.last_calendar_sync {
+        Some(at) => println!(
+            "  Calendar sync:    {} ({})",
+            new_daemon_core::insight_age(at, now),
+            local(at)
+        ),
+        None => println!("  Calendar sync:    not yet (or not configured)"),
+    }
+
+    if details.sources.is_empty() {
+        println!("  Context sources:  none enabled");
+    } else {
+        println!("  Context sources:");
+        for source in &details.sources {
+            let outcome = match (source.fetched_at, &source.error) {
+                (None, _) => "not fetched yet".to_string(),
+                (Some(at), None) => format!("ok, {}", new_daemon_core::insight_age(at, now)),
+                (Some(at), Some(error)) => format!(
+                    "failed {}: {}",
+                    new_daemon_core::insight_age(at, now),
+                    error
+                ),
+            };
+            println!("    {:<16} {}", source.display_name, outcome);
+        }
+    }
+
+    println!(
+        "  API calls left:   {} today",
+        details.api_calls_remaining_today
+    );
+
+    if details.frontends.is_empty() {
+        println!("  Frontends:        none registered");
+    } else {
+        println!("  Frontends:");
+        for frontend in &details.frontends {
+            let pid = frontend
+                .pid
+                .map_or_else(|| "no pid".to_string(), |pid| format!("pid {}", pid));
+            println!(
+                "    {:<16} {}, {}{}, last heartbeat {}",
+                frontend.id,
+                pid,
+                frontend.format,
+                if frontend.access == "guest" {
+                    ", guest"
+                } else {
+                    ""
+                },
+                new_daemon_core::insight_age(frontend.last_heartbeat, now)
+            );
+        }
+    }
+
+    match details.insight_age_seconds {
+        Some(seconds) => println!(
+            "  Insight age:      {}",
+            new_daemon_core::insight_age(now - chrono::Duration::seconds(seconds), now)
+        ),
+        None => println!("  Insight age:      no insight yet"),
+    }
+
+    match details.cooldown_until {
+        Some(until) => println!("  Significance:     cooling down until {}", local(until)),
+        None => println!("  Significance:     ready"),
+    }
+}
+
+pub async fn show_stats(weeks: u32, json: bool) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+    let stats = database.get_week_stats(weeks)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    if stats.is_empty() {
+        println!("No load stats yet; the daemon records them as it runs.");
+        return Ok(());
+    }
+    println!(
+        "{:<12} {:>8} {:>7} {:>13} {:>11} {:>10}",
+        "Week of", "Meetings", "Hours", "Back-to-back", "After hours", "Tasks done"
+    );
+    for week in stats {
+        let tasks = match week.completion_rate() {
+            Some(rate) => format!(
+                "{}/{} {:>3.0}%",
+                week.tasks_done,
+                week.tasks_due,
+                rate * 100.0
+            ),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<12} {:>8} {:>7.1} {:>13} {:>11} {:>10}",
+            week.week_start.format("%Y-%m-%d").to_string(),
+            week.meetings,
+            week.meeting_minutes as f64 / 60.0,
+            week.longest_back_to_back,
+            week.after_hours_meetings,
+            tasks
+        );
+    }
+    Ok(())
+}
+
+pub async fn show_usage(days: u32, json: bool) -> Result<()> {
+    let (_config_handle, database) = open_local_store().await?;
+    let since = chrono::Utc::now() - chrono::Duration::days(days as i64);
+    let usage = database.get_api_usage_summary(since)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&usage)?);
+        return Ok(());
+    }
+    let Some(hit_rate) = usage.cache_hit_rate() else {
+        println!("No AI calls in the last {} days.", days);
+        return Ok(());
+    };
+    println!("Last {} days: {} AI calls", days, usage.calls);
+    println!("  Input tokens:         {}", usage.input_tokens);
+    println!("  Output tokens:        {}", usage.output_tokens);
+    println!("  Cache writes:         {}", usage.cache_creation_tokens);
+    println!("  Cache reads:          {}", usage.cache_read_tokens);
+    println!(
+        "  Cache hits:           {}/{} ({:.0}%)",
+        usage.cache_hits,
+        usage.calls,
+        hit_rate * 100.0
+    );
+    Ok(())
+}
+
+pub async fn show_privacy_audit(json: bool) -> Result<()> {
+    let config_handle = Config::load()
+        .await
+        .context("Failed to load configuration")?;
+    let audit = privacy_audit::PrivacyAudit::from_config(&config_handle.read());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&audit)?);
+        return Ok(());
+    }
+    let yes_no = |on: bool| if on { "yes" } else { "no" };
+    println!(
+        "AI provider:              {} ({})",
+        audit.provider, audit.model
+    );
+    println!(
+        "Zero-retention mode:      {}",
+        if audit.zero_retention { "on" } else { "off" }
+    );
+    match audit.retention_headers.as_slice() {
+        [] if audit.zero_retention => {
+            println!(
+                "  Retention headers:      none offered by {}",
+                audit.provider
+            )
+        }
+        [] => println!("  Retention headers:      none"),
+        headers => println!("  Retention headers:      {}", headers.join(", ")),
+    }
+    println!(
+        "  Provider prompt cache:  {}",
+        yes_no(audit.provider_prompt_cache)
+    );
+    match audit.response_cache_minutes {
+        0 => println!("  Response cache:         off"),
+        minutes => println!("  Response cache:         {} min", minutes),
+    }
+    match audit.snapshot_retention_days {
+        Some(days) => println!("  Context snapshots:      kept {} days", days),
+        None => println!("  Context snapshots:      not stored"),
+    }
+    println!("  Prompts in debug log:   {}", yes_no(audit.prompts_logged));
+    println!(
+        "  Source metadata sent:   {}",
+        yes_no(audit.source_metadata)
+    );
+    println!("Sanitization:");
+    for source in &audit.sanitization {
+        println!(
+            "  {:<24}{:?}, {} custom pattern(s)",
+            source.source, source.level, source.redact_rules
+        );
+    }
+    Ok(())
+}
+
+pub async fn show_health(json: bool) -> Result<()> {
+    let (config_handle, database) = open_local_store().await?;
+    let run = database.latest_maintenance_run()?;
+    // The update check runs in the daemon, so there is only news while it's up
+    let update = match zbus::Connection::session().await {
+        Ok(connection) => daemon_property::<String>(&connection, "UpdateAvailable")
+            .await
+            .filter(|v| !v.is_empty()),
+        Err(_) => None,
+    };
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "maintenance": run,
+                "current_version": update_check::CURRENT_VERSION,
+                "update_available": update,
+            }))?
+        );
+    } else {
+        let maintenance = config_handle.read().maintenance.clone();
+        match run {
+            None if maintenance.enabled => println!(
+                "No maintenance has run yet; it runs nightly from {}.",
+                maintenance.run_at
+            ),
+            None => println!("No maintenance has run; [maintenance] is disabled."),
+            Some(ref run) => {
+                println!(
+                    "Last maintenance: {} ({}s)",
+                    run.started_at
+                        .with_timezone(&chrono::Local)
+                        .format("%a %-d %b %H:%M"),
+                    (run.finished_at - run.started_at).num_seconds()
+                );
+                for task in &run.tasks {
+                    println!(
+                        "  {:<7} {:<11} {}",
+                        if task.ok { "ok" } else { "FAILED" },
+                        task.task,
+                        task.detail
+                    );
+                }
+                if !maintenance.enabled {
+                    println!("[maintenance] is disabled; no further runs are scheduled.");
+                }
+            }
+        }
+        if let Some(ref update) = update {
+            println!(
+                "Update available: {} (current {})",
+                update,
+                update_check::CURRENT_VERSION
+            );
+        }
+    }
+    match run.map_or(0, |run| run.failures()) {
+        0 => Ok(()),
+        n => anyhow::bail!("{} maintenance task(s) failed", n),
+    }
+}
@@ -1,40 +1,22 @@
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use tracing::{error, info, warn};
+use anyhow::Result;
+use clap::{CommandFactory, Parser, Subcommand};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-// Only import what we need for the simplified architecture
-mod api_manager;
-mod config;
-mod context_sources;
-mod database;
-mod errors;
-mod google_calendar;
-mod http_utils;
-mod new_daemon_core;
-mod new_dbus_service;
-mod noctalia_adapter;
-mod significance_engine;
-mod sops_integration;
-mod travel;
-mod waybar_adapter;
+mod commands;
+
+use commands::{calendar, data, frontends, insights, service, status};
 
-use api_manager::ApiManager;
-use config::Config;
-use context_sources::weather::WeatherContextSource;
-use context_sources::ContextSourceManager;
-use database::DatabaseInner;
-use google_calendar::GoogleCalendarService;
-use new_daemon_core::SimplifiedDaemonCore;
-use new_dbus_service::SimplifiedDbusService;
-use std::sync::Arc;
-use tokio::io::AsyncWriteExt;
-use tokio::sync::RwLock;
-use travel::TravelTimeService;
+// Only import what we need for the simplified architecture
+use config::PathOverrides;
+use data_wipe::DataClass;
+use exit_code::{ErrorReport, ExitStatus};
+use jasper_core::{config, data_wipe, demo, exit_code, invitations};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "jasper-daemon")]
 #[command(about = "Jasper Simplified Daemon - Backend AI Analysis Only")]
+#[command(after_help = exit_code_help())]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
@@ -42,6 +24,33 @@ struct Cli {
     /// Enable debug logging
     #[arg(short, long)]
     debug: bool,
+
+    /// Screen-reader friendly output: no emoji or icon glyphs, full sentences
+    #[arg(long, global = true)]
+    accessible: bool,
+
+    /// Print JSON instead of text (status, health, next-event, agenda, stats, usage); errors
+    /// are reported as JSON on stderr
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Config file to use instead of ~/.config/jasper-companion/config.toml [env: JASPER_CONFIG]
+    #[arg(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
+
+    /// Directory for the database and OAuth tokens [env: JASPER_DATA_DIR]
+    #[arg(long, global = true, value_name = "DIR")]
+    data_dir: Option<PathBuf>,
+
+    /// Database file, if it should live outside the data directory, or
+    /// ":memory:" for a throwaway one [env: JASPER_DB_PATH]
+    #[arg(long, global = true, value_name = "FILE")]
+    db_path: Option<PathBuf>,
+
+    /// Run without D-Bus or desktop frontends, delivering via [delivery] only
+    /// (automatic when no session bus is found)
+    #[arg(long, global = true)]
+    headless: bool,
 }
 
 #[derive(Subcommand)]
@@ -49,7 +58,11 @@ enum Commands {
     /// Start the daemon
     Start,
     /// Check daemon status
-    Status,
+    Status {
+        /// Also show calendar sync, context sources, API budget, frontends and cooldown
+        #[arg(long, short)]
+        verbose: bool,
+    },
     /// Stop the daemon (via D-Bus)
     Stop,
     /// Set Claude API key in configuration
@@ -57,8 +70,23 @@ enum Commands {
         /// The Claude API key from console.anthropic.com
         key: String,
     },
+    /// Check the AI key: list the models it can use and its rate limits
+    VerifyAi,
+    /// Upgrade an old config file layout, keeping a backup of the original
+    MigrateConfig {
+        /// Show what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Get insights for waybar
-    Waybar,
+    Waybar {
+        /// Print a single plain line instead of Waybar JSON (for scripts)
+        #[arg(long)]
+        simple: bool,
+        /// Template for --simple, e.g. "{emoji} {text} ({age})"; overrides [frontends] terminal_template
+        #[arg(long, requires = "simple")]
+        template: Option<String>,
+    },
     /// Check waybar status
     WaybarStatus,
     /// Get insights for Noctalia bar widget (JSON output)
@@ -69,499 +97,380 @@ enum Commands {
     AuthGoogle,
     /// List Google Calendars and choose which ones to sync
     ListCalendars,
+    /// Preview how insight emoji map to Nerd Font glyphs and validate [frontends.glyphs]
+    Glyphs,
+    /// Show today's morning digest
+    Digest,
+    /// Show the next event with a countdown ("Standup in 12m"); --json gives
+    /// starts_at, starts_in_seconds, title and location for bars
+    NextEvent,
+    /// Answer an invitation through Google Calendar
+    Rsvp {
+        /// The event: part of its title, or its ID
+        event: String,
+        #[arg(value_enum)]
+        response: invitations::Response,
+    },
+    /// Open the next video call (Meet, Zoom, Teams, Webex), or the one under way
+    Join {
+        /// Print the link instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Show events from all calendars, tasks due and weather, day by day
+    Agenda {
+        /// Just today (the default)
+        #[arg(long)]
+        today: bool,
+        /// Today and the next six days
+        #[arg(long, conflicts_with = "today")]
+        week: bool,
+        /// Print Markdown instead of text
+        #[arg(long)]
+        markdown: bool,
+    },
+    /// Print a menu for rofi, wofi or fuzzel (dmenu protocol), or run the line chosen from it
+    Menu {
+        /// The chosen line, as the launcher returns it
+        selection: Option<String>,
+    },
+    /// Add a reminder, or a time block with --minutes
+    Remind {
+        /// What to be reminded of
+        title: String,
+        /// When, as "YYYY-MM-DD HH:MM" in the configured timezone (or RFC 3339)
+        #[arg(long)]
+        at: String,
+        /// Block out this many minutes instead of a single reminder
+        #[arg(long)]
+        minutes: Option<u32>,
+        /// Extra detail shown in calendar apps
+        #[arg(long)]
+        notes: Option<String>,
+    },
+    /// List reminders and time blocks
+    Reminders,
+    /// Remove a reminder or time block by id (see `reminders`)
+    CancelReminder { id: i64 },
+    /// Export reminders and time blocks as an iCalendar feed
+    ExportIcs {
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Import events, tasks or contacts from files, without setting up an API
+    Import {
+        #[command(subcommand)]
+        source: ImportSource,
+    },
+    /// Weekly meeting load: hours, back-to-back runs, after-hours meetings, tasks done
+    Stats {
+        /// How many weeks to show
+        #[arg(long, default_value_t = 8)]
+        weeks: u32,
+    },
+    /// AI calls, token usage and prompt cache hits
+    Usage {
+        /// How many days back to count
+        #[arg(long, default_value_t = 30)]
+        days: u32,
+    },
+    /// Show the last nightly maintenance run; fails if any of its tasks did
+    Health,
+    /// Show what is sent to the AI provider and what is kept, locally and there
+    PrivacyAudit,
+    /// Securely erase stored data, by class or all of it
+    WipeData {
+        /// Synced calendar events and the meeting log
+        #[arg(long)]
+        events: bool,
+        /// Insights, digests, replies and the context snapshots behind them
+        #[arg(long)]
+        insights: bool,
+        /// Day summaries, cached AI responses and tasks read from sources
+        #[arg(long)]
+        notes_cache: bool,
+        /// Everything, including history, reminders and the Google Calendar token
+        #[arg(long)]
+        all: bool,
+        /// With --all, also revoke the Google Calendar token with Google
+        #[arg(long, requires = "all")]
+        revoke: bool,
+    },
+    /// List critical items being tracked for escalation
+    Escalations,
+    /// Acknowledge a critical item so it stops escalating (all of them without a key)
+    Dismiss {
+        /// Item key from `escalations`, e.g. "task:123"
+        key: Option<String>,
+    },
+    /// Print the latest insight as one line: timestamp, emoji, text and urgency
+    Insight {
+        /// Keep running and print a line for every new insight
+        #[arg(long)]
+        follow: bool,
+    },
+    /// Show today's morning digest once, as a notification (and aloud with
+    /// [greet] speak), then exit; meant for login autostart
+    Greet {
+        /// Greet even if it was already done today
+        #[arg(long)]
+        force: bool,
+        /// Read it aloud this time, whatever [greet] speak says
+        #[arg(long)]
+        speak: bool,
+    },
+    /// Show recent insights
+    History {
+        /// How many insights to show
+        #[arg(long, default_value_t = 10)]
+        limit: u32,
+        /// Also show which context sources each prompt drew on, and how many items each gave
+        #[arg(long, short)]
+        verbose: bool,
+    },
+    /// Show an insight in full: its trigger, sources, links and the events, tasks
+    /// and weather behind it, in a pager
+    Show {
+        /// Insight id (see `history`); the latest insight without one
+        insight_id: Option<i64>,
+    },
+    /// Print the context snapshot an insight was generated from, as JSON
+    Snapshot {
+        /// Insight id (see `history`); the latest insight without one
+        insight_id: Option<i64>,
+    },
+    /// Show how Jasper connects the coming days: events, the people in them and
+    /// related tasks, with the relationships between them
+    Graph {
+        /// Print Graphviz DOT (pipe to `dot -Tsvg`) instead of text
+        #[arg(long)]
+        dot: bool,
+        /// Days ahead to include
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+    /// Show recent trigger decisions: why insights were or weren't generated
+    Triggers {
+        /// How many entries to show
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+    },
+    /// Start the daemon at login (systemd user unit, launchd agent or Task Scheduler)
+    InstallService {
+        /// Install for the current user only (default: all users, needs root)
+        #[arg(long)]
+        user: bool,
+    },
+    /// Remove what install-service set up
+    UninstallService {
+        /// Remove the current user's install (default: the all-users one)
+        #[arg(long)]
+        user: bool,
+    },
+    /// Seed a throwaway profile with realistic sample data for trying Jasper out
+    Demo {
+        /// Which day to simulate
+        #[arg(long, value_enum)]
+        scenario: demo::Scenario,
+        /// Where to write the profile (default: a new directory under the temp dir)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    let cli = Cli::parse();
-
-    // Initialize logging
-    let log_level = if cli.debug { "debug" } else { "info" };
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| format!("jasper_companion_daemon={},warn", log_level).into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    match cli.command.unwrap_or(Commands::Start) {
-        Commands::Start => start_daemon().await,
-        Commands::Status => show_status().await,
-        Commands::Stop => stop_daemon().await,
-        Commands::SetApiKey { key } => set_api_key(key).await,
-        Commands::Waybar => waybar_mode().await,
-        Commands::WaybarStatus => waybar_status_mode().await,
-        Commands::Noctalia => noctalia_mode().await,
-        Commands::NoctaliaRefresh => noctalia_refresh_mode().await,
-        Commands::AuthGoogle => auth_google().await,
-        Commands::ListCalendars => list_calendars().await,
-    }
+#[derive(Subcommand)]
+enum ImportSource {
+    /// Events from an iCalendar (.ics) file, as a calendar of their own
+    Ics {
+        file: PathBuf,
+        /// Calendar to import into; importing into it again replaces its
+        /// events (default: the file name)
+        #[arg(long)]
+        calendar: Option<String>,
+    },
+    /// Open tasks from a Todoist CSV export, into the local task file
+    Todoist { file: PathBuf },
+    /// Open tasks from a todo.txt file, into the local task file
+    TodoTxt { file: PathBuf },
+    /// Contacts from a vCard (.vcf) file, as Obsidian people notes, and
+    /// their birthdays as events
+    Vcf { file: PathBuf },
 }
 
-async fn start_daemon() -> Result<()> {
-    info!("Starting Jasper simplified daemon");
-
-    // Load configuration
-    let config_arc = Config::load()
-        .await
-        .context("Failed to load configuration")?;
-
-    // Initialize database
-    let db_path = Config::get_data_dir()?.join("jasper.db");
-    let database = DatabaseInner::new(&db_path)
-        .await
-        .context("Failed to initialize database")?;
-
-    // Initialize context source manager
-    let mut context_manager = ContextSourceManager::new();
-
-    // Register weather context source if configured
-    {
-        let config = config_arc.read();
-        if let Some(weather_config) = config.get_weather_config() {
-            if weather_config.enabled && !weather_config.google_api_key.is_empty() {
-                let weather_source = WeatherContextSource::new(
-                    weather_config.google_api_key.clone(),
-                    weather_config.latitude,
-                    weather_config.longitude,
-                    weather_config.units.clone(),
-                    weather_config.cache_duration_minutes,
-                );
-                context_manager.add_source(Box::new(weather_source));
-                info!(
-                    "Weather context source registered ({}, {})",
-                    weather_config.latitude, weather_config.longitude
-                );
-            }
+impl Commands {
+    /// Whether the command can print JSON: on request, or always (bar output,
+    /// snapshots)
+    fn prints_json(&self) -> bool {
+        match self {
+            Commands::Status { .. }
+            | Commands::Health
+            | Commands::PrivacyAudit
+            | Commands::WipeData { .. }
+            | Commands::NextEvent
+            | Commands::Agenda { .. }
+            | Commands::Stats { .. }
+            | Commands::Usage { .. }
+            | Commands::Noctalia
+            | Commands::NoctaliaRefresh
+            | Commands::Show { .. }
+            | Commands::Insight { .. }
+            | Commands::Snapshot { .. }
+            | Commands::Graph { .. } => true,
+            Commands::Waybar { simple, .. } => !simple,
+            _ => false,
         }
     }
-
-    // Initialize Google Calendar service if configured
-    let calendar_service = {
-        let config = config_arc.read();
-        config.google_calendar.as_ref().and_then(|gc| {
-            if gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty() {
-                let gcal_config = google_calendar::GoogleCalendarConfig {
-                    client_id: gc.client_id.clone(),
-                    client_secret: gc.client_secret.clone(),
-                    redirect_uri: gc.redirect_uri.clone(),
-                    calendar_ids: gc.calendar_ids.clone(),
-                };
-                let data_dir = Config::get_data_dir().ok()?;
-                let tz = config.get_timezone();
-                info!("Google Calendar service initialized");
-                Some(GoogleCalendarService::new(gcal_config, data_dir, tz))
-            } else {
-                None
-            }
-        })
-    };
-
-    // Initialize travel time service if configured
-    let travel_service = {
-        let config = config_arc.read();
-        config
-            .get_travel_config()
-            .filter(|tc| tc.enabled && !tc.google_api_key.is_empty() && !tc.home_address.is_empty())
-            .map(|tc| {
-                info!(
-                    "Travel time service initialized (mode: {}, from: {})",
-                    tc.travel_mode, tc.home_address
-                );
-                TravelTimeService::new(tc)
-            })
-    };
-
-    // Initialize API manager
-    let api_manager = ApiManager::new();
-
-    // Create the simplified daemon core
-    let daemon_core = Arc::new(RwLock::new(SimplifiedDaemonCore::new(
-        database,
-        context_manager,
-        api_manager,
-        config_arc,
-        calendar_service,
-        travel_service,
-    )));
-
-    info!("Simplified daemon core created");
-
-    // Start D-Bus service in background
-    let dbus_daemon = daemon_core.clone();
-    let dbus_handle = tokio::spawn(async move {
-        if let Err(e) = SimplifiedDbusService::start(dbus_daemon).await {
-            error!("D-Bus service failed: {}", e);
-        }
-    });
-
-    // Give D-Bus service time to establish connection
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-    // Initialize the D-Bus signal emitter for push notifications to frontends
-    {
-        let daemon = daemon_core.read().await;
-        if let Err(e) = daemon.init_signal_emitter().await {
-            warn!(
-                "Could not initialize signal emitter (frontends will use polling): {}",
-                e
-            );
-        }
-    }
-
-    // Start the main daemon loop in a separate task
-    // Using start_with_arc to avoid holding lock for entire runtime
-    let daemon_core_clone = daemon_core.clone();
-    let daemon_handle =
-        tokio::spawn(async move { SimplifiedDaemonCore::start_with_arc(daemon_core_clone).await });
-
-    // Wait for either the daemon or D-Bus service to finish
-    tokio::select! {
-        result = daemon_handle => {
-            if let Err(e) = result {
-                error!("Daemon loop failed: {}", e);
-            }
-        }
-        _ = dbus_handle => {
-            info!("D-Bus service stopped");
-        }
-    }
-
-    info!("Simplified daemon stopped");
-    Ok(())
 }
 
-async fn show_status() -> Result<()> {
-    let connection = match zbus::Connection::session().await {
-        Ok(c) => c,
-        Err(_) => {
-            println!("Daemon Status: Not Running");
-            println!("  D-Bus session bus unavailable");
-            return Ok(());
-        }
-    };
-
-    match connection
-        .call_method(
-            Some("org.jasper.Daemon"),
-            "/org/jasper/Daemon",
-            Some("org.jasper.Daemon1"),
-            "GetStatus",
-            &(),
-        )
-        .await
-    {
-        Ok(reply) => {
-            let (is_running, active_frontends, insights_count): (bool, u32, i64) =
-                reply.body().deserialize()?;
-            println!(
-                "Daemon Status: {}",
-                if is_running { "Running" } else { "Stopped" }
-            );
-            println!("  Active frontends: {}", active_frontends);
-            println!("  Total insights:   {}", insights_count);
-        }
-        Err(_) => {
-            println!("Daemon Status: Not Running");
+/// The exit code table shown at the end of `--help`
+fn exit_code_help() -> String {
+    let mut help = String::from("Exit codes:\n  0   success\n");
+    for status in ExitStatus::ALL {
+        help.push_str(&format!("  {:<3} {}\n", status.code(), status.describe()));
+        if status == ExitStatus::Failure {
+            help.push_str("  2   invalid arguments\n");
         }
     }
-    Ok(())
+    help
 }
 
-async fn stop_daemon() -> Result<()> {
-    let connection = zbus::Connection::session()
-        .await
-        .context("Failed to connect to D-Bus session bus")?;
-
-    match connection
-        .call_method(
-            Some("org.jasper.Daemon"),
-            "/org/jasper/Daemon",
-            Some("org.jasper.Daemon1"),
-            "GetStatus",
-            &(),
-        )
-        .await
-    {
-        Ok(_) => {
-            println!(
-                "Daemon is running. Use 'systemctl --user stop jasper-daemon' or send SIGTERM."
-            );
+#[tokio::main]
+async fn main() -> std::process::ExitCode {
+    let cli = Cli::parse();
+    let json = cli.json;
+
+    if let Some(command) = cli.command.as_ref() {
+        if json && !command.prints_json() {
+            Cli::command()
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "this command has no JSON output; --json is not supported",
+                )
+                .exit();
         }
-        Err(_) => {
-            println!("Daemon is not running.");
+        if json && matches!(command, Commands::Agenda { markdown: true, .. }) {
+            Cli::command()
+                .error(
+                    clap::error::ErrorKind::ArgumentConflict,
+                    "--markdown cannot be used with --json",
+                )
+                .exit();
         }
     }
-    Ok(())
-}
-
-async fn set_api_key(key: String) -> Result<()> {
-    let config_arc = Config::load()
-        .await
-        .context("Failed to load configuration")?;
-    {
-        let mut config = config_arc.write();
-        config.ai.api_key = Some(key);
-    }
-    // Clone config data before awaiting to avoid holding lock across await
-    let config = config_arc.read().clone();
-    config
-        .save()
-        .await
-        .context("Failed to save configuration")?;
-
-    println!("Claude API key updated successfully");
-    Ok(())
-}
 
-async fn waybar_mode() -> Result<()> {
-    waybar_adapter::run_waybar_mode()
-        .await
-        .map_err(|e| anyhow::anyhow!("Waybar mode failed: {}", e))
-}
-
-async fn waybar_status_mode() -> Result<()> {
-    waybar_adapter::waybar_status()
-        .await
-        .map_err(|e| anyhow::anyhow!("Waybar status failed: {}", e))
-}
-
-async fn noctalia_mode() -> Result<()> {
-    noctalia_adapter::run_noctalia_mode()
-        .await
-        .map_err(|e| anyhow::anyhow!("Noctalia mode failed: {}", e))
-}
-
-async fn noctalia_refresh_mode() -> Result<()> {
-    noctalia_adapter::run_noctalia_refresh()
-        .await
-        .map_err(|e| anyhow::anyhow!("Noctalia refresh failed: {}", e))
-}
-
-async fn auth_google() -> Result<()> {
-    let config_arc = Config::load()
-        .await
-        .context("Failed to load configuration")?;
-
-    let gc = {
-        let config = config_arc.read();
-        config.google_calendar.as_ref()
-            .filter(|gc| gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty())
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!(
-                "Google Calendar is not configured. Set enabled=true, client_id, and client_secret in [google_calendar] section of config.toml"
-            ))?
-    };
-
-    let gcal_config = google_calendar::GoogleCalendarConfig {
-        client_id: gc.client_id,
-        client_secret: gc.client_secret,
-        redirect_uri: gc.redirect_uri.clone(),
-        calendar_ids: gc.calendar_ids,
-    };
-    let data_dir = Config::get_data_dir()?;
-    let tz = config_arc.read().get_timezone();
-    let service = GoogleCalendarService::new(gcal_config, data_dir, tz);
-
-    // Check if already authenticated
-    if service.is_authenticated().await {
-        println!("Already authenticated with Google Calendar.");
-        println!("To re-authenticate, delete the token file and run this command again:");
-        println!("  rm ~/.local/share/jasper-companion/google_calendar_token.json");
-        return Ok(());
+    match run(cli).await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(e) => {
+            let report = ErrorReport::new(&e);
+            if json {
+                eprintln!(
+                    "{}",
+                    serde_json::to_string(&report).unwrap_or_else(|_| report.error.clone())
+                );
+            } else {
+                eprintln!("Error: {}", report.error);
+            }
+            std::process::ExitCode::from(report.exit_code)
+        }
     }
-
-    let (auth_url, csrf_token) = service
-        .get_auth_url()
-        .context("Failed to generate OAuth URL")?;
-
-    println!("Open this URL in your browser to authorize Jasper:\n");
-    println!("  {}\n", auth_url);
-    println!("Waiting for callback on {} ...", gc.redirect_uri);
-
-    // Try to open the URL automatically
-    let _ = std::process::Command::new("xdg-open")
-        .arg(&auth_url)
-        .spawn();
-
-    // Start one-shot callback server on 127.0.0.1:8080
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:8080")
-        .await
-        .context("Failed to bind to 127.0.0.1:8080 - is another process using this port?")?;
-
-    let (mut stream, _addr) = listener
-        .accept()
-        .await
-        .context("Failed to accept callback connection")?;
-
-    // Read the HTTP request
-    let mut buf = vec![0u8; 4096];
-    let n = tokio::io::AsyncReadExt::read(&mut stream, &mut buf)
-        .await
-        .context("Failed to read callback request")?;
-    let request = String::from_utf8_lossy(&buf[..n]);
-
-    // Parse the request line to extract query params: GET /auth/callback?code=...&state=... HTTP/1.1
-    let code = request
-        .lines()
-        .next()
-        .and_then(|line| line.split_whitespace().nth(1)) // "/auth/callback?code=...&state=..."
-        .and_then(|path| path.split('?').nth(1)) // "code=...&state=..."
-        .and_then(|query| {
-            query
-                .split('&')
-                .find_map(|param| param.strip_prefix("code="))
-        })
-        .map(|c| c.to_string())
-        .ok_or_else(|| anyhow::anyhow!("No authorization code found in callback"))?;
-
-    // Send success response to browser
-    let html = "<html><body><h2>Authentication successful!</h2><p>You can close this tab and return to the terminal.</p></body></html>";
-    let response = format!(
-        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        html.len(),
-        html
-    );
-    let _ = stream.write_all(response.as_bytes()).await;
-    let _ = stream.flush().await;
-
-    // Exchange the code for a token
-    println!("Authorization code received, exchanging for token...");
-    service
-        .authenticate_with_code(&code, csrf_token.secret())
-        .await
-        .context("Failed to exchange authorization code for token")?;
-
-    println!("Google Calendar authentication successful!");
-    println!("Token saved. Restart the daemon to begin syncing calendar events.");
-    Ok(())
 }
 
-async fn list_calendars() -> Result<()> {
-    let config_arc = Config::load()
-        .await
-        .context("Failed to load configuration")?;
-
-    let gc = {
-        let config = config_arc.read();
-        config.google_calendar.as_ref()
-            .filter(|gc| gc.enabled && !gc.client_id.is_empty() && !gc.client_secret.is_empty())
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!(
-                "Google Calendar is not configured. Set enabled=true, client_id, and client_secret in [google_calendar] section of config.toml"
-            ))?
-    };
-
-    let gcal_config = google_calendar::GoogleCalendarConfig {
-        client_id: gc.client_id,
-        client_secret: gc.client_secret,
-        redirect_uri: gc.redirect_uri,
-        calendar_ids: gc.calendar_ids.clone(),
-    };
-    let synced_ids: std::collections::HashSet<String> = gc.calendar_ids.into_iter().collect();
-
-    let data_dir = Config::get_data_dir()?;
-    let tz = config_arc.read().get_timezone();
-    let service = GoogleCalendarService::new(gcal_config, data_dir, tz);
-
-    println!("Fetching calendars from Google...");
-    let calendars = service.fetch_calendar_list().await.context(
-        "Failed to fetch calendar list. Are you authenticated? Run 'auth-google' first.",
-    )?;
-
-    if calendars.is_empty() {
-        println!("No calendars found on this Google account.");
-        return Ok(());
+async fn run(cli: Cli) -> Result<()> {
+    PathOverrides {
+        config: cli.config.clone(),
+        data_dir: cli.data_dir.clone(),
+        db_path: cli.db_path.clone(),
     }
+    .install();
 
-    // Build display labels and figure out which are pre-selected
-    let labels: Vec<String> = calendars
-        .iter()
-        .map(|cal| {
-            let name = cal.summary.as_deref().unwrap_or("(unnamed)");
-            if cal.primary.unwrap_or(false) {
-                format!("{} (primary)", name)
-            } else {
-                name.to_string()
-            }
-        })
-        .collect();
-
-    let defaults: Vec<bool> = calendars
-        .iter()
-        .map(|cal| {
-            synced_ids.contains(&cal.id)
-                || (cal.primary.unwrap_or(false) && synced_ids.contains("primary"))
-        })
-        .collect();
-
-    // Interactive checkbox selector
-    let selections = dialoguer::MultiSelect::new()
-        .with_prompt("Select calendars to sync (space to toggle, enter to confirm)")
-        .items(&labels)
-        .defaults(&defaults)
-        .interact_opt()
-        .context("Calendar selection cancelled")?;
-
-    let Some(selections) = selections else {
-        println!("Cancelled, no changes made.");
-        return Ok(());
-    };
+    // Initialize logging
+    let log_level = if cli.debug { "debug" } else { "info" };
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+                format!(
+                    "jasper_companion_daemon={0},jasper_core={0},warn",
+                    log_level
+                )
+                .into()
+            }),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
 
-    // Map selected indices back to calendar IDs
-    let new_calendar_ids: Vec<String> = selections
-        .iter()
-        .map(|&i| {
-            let cal = &calendars[i];
-            if cal.primary.unwrap_or(false) {
-                "primary".to_string()
+    match cli.command.unwrap_or(Commands::Start) {
+        Commands::Start => service::start_daemon(cli.headless, cli.accessible).await,
+        Commands::Status { verbose } => status::show_status(verbose, cli.json).await,
+        Commands::Stop => service::stop_daemon().await,
+        Commands::SetApiKey { key } => service::set_api_key(key).await,
+        Commands::VerifyAi => service::verify_ai().await,
+        Commands::MigrateConfig { dry_run } => service::migrate_config(dry_run).await,
+        Commands::Waybar { simple, template } => {
+            frontends::waybar_mode(cli.accessible, simple, template).await
+        }
+        Commands::WaybarStatus => frontends::waybar_status_mode().await,
+        Commands::Noctalia => frontends::noctalia_mode(cli.accessible).await,
+        Commands::NoctaliaRefresh => frontends::noctalia_refresh_mode(cli.accessible).await,
+        Commands::AuthGoogle => service::auth_google().await,
+        Commands::ListCalendars => service::list_calendars().await,
+        Commands::Glyphs => frontends::preview_glyphs(),
+        Commands::Digest => insights::show_digest().await,
+        Commands::NextEvent => calendar::show_next_event(cli.json).await,
+        Commands::Join { print } => calendar::join_next_meeting(print).await,
+        Commands::Rsvp { event, response } => calendar::rsvp(&event, response).await,
+        Commands::Agenda {
+            today: _,
+            week,
+            markdown,
+        } => calendar::show_agenda(week, cli.json, markdown).await,
+        Commands::Menu { selection } => calendar::command_menu(selection).await,
+        Commands::Remind {
+            title,
+            at,
+            minutes,
+            notes,
+        } => calendar::add_reminder(title, at, minutes, notes).await,
+        Commands::Reminders => calendar::list_reminders().await,
+        Commands::CancelReminder { id } => calendar::cancel_reminder(id).await,
+        Commands::ExportIcs { output } => calendar::export_ics(output).await,
+        Commands::Import { source } => data::import_data(source).await,
+        Commands::Stats { weeks } => status::show_stats(weeks, cli.json).await,
+        Commands::Usage { days } => status::show_usage(days, cli.json).await,
+        Commands::Health => status::show_health(cli.json).await,
+        Commands::PrivacyAudit => status::show_privacy_audit(cli.json).await,
+        Commands::WipeData {
+            events,
+            insights,
+            notes_cache,
+            all,
+            revoke,
+        } => {
+            let classes: Vec<DataClass> = if all {
+                DataClass::ALL.to_vec()
             } else {
-                cal.id.clone()
-            }
-        })
-        .collect();
-
-    // Check if anything actually changed
-    let old_set: std::collections::HashSet<&str> = synced_ids.iter().map(|s| s.as_str()).collect();
-    let new_set: std::collections::HashSet<&str> =
-        new_calendar_ids.iter().map(|s| s.as_str()).collect();
-    if old_set == new_set {
-        println!("No changes made.");
-        return Ok(());
-    }
-
-    // Show what changed
-    for id in new_set.difference(&old_set) {
-        let name = calendars
-            .iter()
-            .find(|c| c.id == *id || (*id == "primary" && c.primary.unwrap_or(false)))
-            .and_then(|c| c.summary.as_deref())
-            .unwrap_or(id);
-        println!("  + {}", name);
-    }
-    for id in old_set.difference(&new_set) {
-        let name = calendars
-            .iter()
-            .find(|c| c.id == *id || (*id == "primary" && c.primary.unwrap_or(false)))
-            .and_then(|c| c.summary.as_deref())
-            .unwrap_or(id);
-        println!("  - {}", name);
-    }
-
-    // Save to config
-    {
-        let mut config = config_arc.write();
-        if let Some(ref mut gc) = config.google_calendar {
-            gc.calendar_ids = new_calendar_ids;
+                [
+                    (insights, DataClass::Insights),
+                    (notes_cache, DataClass::NotesCache),
+                    (events, DataClass::Events),
+                ]
+                .into_iter()
+                .filter_map(|(chosen, class)| chosen.then_some(class))
+                .collect()
+            };
+            data::wipe_data(classes, all, revoke, cli.json).await
         }
+        Commands::Escalations => insights::list_escalations().await,
+        Commands::Dismiss { key } => insights::dismiss_escalations(key).await,
+        Commands::Greet { force, speak } => insights::greet(force, speak).await,
+        Commands::History { limit, verbose } => insights::show_history(limit, verbose).await,
+        Commands::Show { insight_id } => insights::show_insight(insight_id, cli.json).await,
+        Commands::Insight { follow } => insights::print_insights(follow, cli.json).await,
+        Commands::Snapshot { insight_id } => insights::show_snapshot(insight_id).await,
+        Commands::Graph { dot, days } => insights::show_graph(dot, days, cli.json).await,
+        Commands::Triggers { limit } => insights::show_triggers(limit).await,
+        Commands::InstallService { user } => service::install_service(user),
+        Commands::UninstallService { user } => service::uninstall_service(user),
+        Commands::Demo { scenario, dir } => service::seed_demo(scenario, dir).await,
     }
-    let config = config_arc.read().clone();
-    config
-        .save()
-        .await
-        .context("Failed to save configuration")?;
-
-    println!("\nConfiguration saved. Restart the daemon to apply changes.");
-    Ok(())
 }
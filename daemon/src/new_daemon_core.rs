@@ -1,1553 +0,0 @@
-use crate::api_manager::ApiManager;
-use crate::config::Config;
-use crate::context_sources::{self, ContextSourceManager};
-use crate::database::{Database, Insight};
-use crate::errors::JasperResult;
-use crate::google_calendar::GoogleCalendarService;
-use crate::new_dbus_service::DbusSignalEmitter;
-use crate::significance_engine::{
-    ContextSnapshot as ContextSnapshotSummary, SignificanceEngine, SignificantChange,
-    TravelTimeInfo,
-};
-use crate::travel::TravelTimeService;
-
-use chrono::{DateTime, Timelike, Utc};
-use parking_lot::RwLock;
-use std::collections::BTreeMap;
-use std::sync::Arc;
-use std::time::SystemTime;
-use tokio::time::{interval, Duration};
-use tracing::{debug, error, info, warn};
-
-// Trait to detect emoji characters
-trait EmojiChar {
-    fn is_emoji_char(&self) -> bool;
-}
-
-impl EmojiChar for char {
-    fn is_emoji_char(&self) -> bool {
-        // Simple emoji detection - Unicode ranges for common emojis
-        matches!(*self,
-            '\u{1F600}'..='\u{1F64F}' | // Emoticons
-            '\u{1F300}'..='\u{1F5FF}' | // Misc Symbols and Pictographs
-            '\u{1F680}'..='\u{1F6FF}' | // Transport and Map
-            '\u{1F700}'..='\u{1F77F}' | // Alchemical Symbols
-            '\u{1F780}'..='\u{1F7FF}' | // Geometric Shapes Extended
-            '\u{1F800}'..='\u{1F8FF}' | // Supplemental Arrows-C
-            '\u{1F900}'..='\u{1F9FF}' | // Supplemental Symbols and Pictographs
-            '\u{1FA00}'..='\u{1FA6F}' | // Chess Symbols
-            '\u{1FA70}'..='\u{1FAFF}' | // Symbols and Pictographs Extended-A
-            '\u{2600}'..='\u{26FF}' |   // Misc symbols
-            '\u{2700}'..='\u{27BF}'     // Dingbats
-        )
-    }
-}
-
-/// The new simplified daemon core that only handles backend processing
-pub struct SimplifiedDaemonCore {
-    database: Database,
-    significance_engine: SignificanceEngine,
-    context_manager: Arc<tokio::sync::RwLock<ContextSourceManager>>,
-    api_manager: ApiManager,
-    config: Arc<parking_lot::RwLock<Config>>,
-    http_client: reqwest::Client,
-
-    // Google Calendar sync
-    calendar_service: Option<Arc<GoogleCalendarService>>,
-    last_calendar_sync: Arc<RwLock<Option<DateTime<Utc>>>>,
-    calendar_sync_interval: Duration,
-
-    // Configuration
-    check_interval: Duration,
-
-    // State
-    is_running: Arc<RwLock<bool>>,
-
-    // D-Bus signal emitter (initialized when D-Bus connection is ready)
-    signal_emitter: Arc<tokio::sync::RwLock<Option<DbusSignalEmitter>>>,
-
-    // Track whether we've already emitted an auth warning (avoid spamming every sync cycle)
-    auth_warning_emitted: Arc<RwLock<bool>>,
-
-    // Cached personal context file: (file_mtime, contents)
-    personal_context_cache: Arc<RwLock<Option<(SystemTime, String)>>>,
-
-    // Travel time enrichment service (None if not configured)
-    travel_service: Option<TravelTimeService>,
-}
-
-impl SimplifiedDaemonCore {
-    pub fn new(
-        database: Database,
-        context_manager: ContextSourceManager,
-        api_manager: ApiManager,
-        config: Arc<parking_lot::RwLock<Config>>,
-        calendar_service: Option<GoogleCalendarService>,
-        travel_service: Option<TravelTimeService>,
-    ) -> Self {
-        let calendar_sync_interval = {
-            let cfg = config.read();
-            let minutes = cfg
-                .google_calendar
-                .as_ref()
-                .map(|gc| gc.sync_interval_minutes)
-                .unwrap_or(15);
-            Duration::from_secs(minutes as u64 * 60)
-        };
-
-        Self {
-            database,
-            significance_engine: SignificanceEngine::new(),
-            context_manager: Arc::new(tokio::sync::RwLock::new(context_manager)),
-            api_manager,
-            config,
-            calendar_service: calendar_service.map(Arc::new),
-            last_calendar_sync: Arc::new(RwLock::new(None)),
-            calendar_sync_interval,
-            http_client: reqwest::Client::new(),
-            check_interval: Duration::from_secs(60), // Check every minute
-            is_running: Arc::new(RwLock::new(false)),
-            signal_emitter: Arc::new(tokio::sync::RwLock::new(None)),
-            auth_warning_emitted: Arc::new(RwLock::new(false)),
-            personal_context_cache: Arc::new(RwLock::new(None)),
-            travel_service,
-        }
-    }
-
-    /// Initialize the D-Bus signal emitter (call after D-Bus connection is established)
-    pub async fn init_signal_emitter(&self) -> JasperResult<()> {
-        match DbusSignalEmitter::new().await {
-            Ok(emitter) => {
-                *self.signal_emitter.write().await = Some(emitter);
-                info!("D-Bus signal emitter initialized");
-                Ok(())
-            }
-            Err(e) => {
-                warn!("Failed to initialize D-Bus signal emitter: {}", e);
-                Err(e)
-            }
-        }
-    }
-
-    /// Emit an insight updated signal
-    async fn emit_insight_signal(&self, insight_id: i64, emoji: &str, preview: &str) {
-        if let Some(ref emitter) = *self.signal_emitter.read().await {
-            if let Err(e) = emitter
-                .emit_insight_updated(insight_id, emoji, preview)
-                .await
-            {
-                warn!("Failed to emit InsightUpdated signal: {}", e);
-            }
-        } else {
-            debug!("Signal emitter not initialized, skipping signal emission");
-        }
-    }
-
-    /// Start the daemon main loop
-    /// Takes an Arc to self so it can release locks between iterations
-    pub async fn start_with_arc(daemon: Arc<tokio::sync::RwLock<Self>>) -> JasperResult<()> {
-        // Check and set running state
-        {
-            let d = daemon.read().await;
-            let mut running = d.is_running.write();
-            if *running {
-                warn!("Daemon is already running");
-                return Ok(());
-            }
-            *running = true;
-        }
-
-        info!("Starting simplified daemon core");
-
-        // Give initial grace period for frontends to connect.
-        // Must be longer than the Noctalia plugin poll interval (30s)
-        // so the plugin has time to register via D-Bus.
-        info!("Waiting for frontends to connect...");
-        tokio::time::sleep(tokio::time::Duration::from_secs(45)).await;
-
-        // Get check interval from daemon (briefly acquire lock)
-        let check_interval = daemon.read().await.check_interval;
-        let mut ticker = interval(check_interval);
-
-        loop {
-            // Check if we should still be running (briefly acquire lock)
-            {
-                let d = daemon.read().await;
-                if !*d.is_running.read() {
-                    info!("Daemon stop requested");
-                    break;
-                }
-            }
-
-            // Check if any frontends are still active
-            let has_frontends = {
-                let d = daemon.read().await;
-                d.database.has_active_frontends().unwrap_or(true)
-            };
-
-            if !has_frontends {
-                info!("No active frontends detected - stopping daemon");
-                // Give a grace period to allow reconnection
-                tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
-
-                // Recheck after grace period
-                let still_no_frontends = {
-                    let d = daemon.read().await;
-                    !d.database.has_active_frontends().unwrap_or(false)
-                };
-                if still_no_frontends {
-                    break;
-                }
-            }
-
-            ticker.tick().await;
-
-            // Sync Google Calendar events if interval has elapsed
-            {
-                let d = daemon.read().await;
-                d.sync_calendar_if_needed().await;
-            }
-
-            // Perform context check and analysis (this acquires locks internally as needed)
-            {
-                let d = daemon.read().await;
-                if let Err(e) = d.check_and_analyze().await {
-                    error!("Error during context check and analysis: {}", e);
-                }
-            }
-        }
-
-        // Clear running state
-        {
-            let d = daemon.read().await;
-            let mut running = d.is_running.write();
-            *running = false;
-        }
-
-        info!("Simplified daemon core stopped");
-        Ok(())
-    }
-
-    /// Stop the daemon (available for graceful shutdown)
-    #[allow(dead_code)]
-    pub fn stop(&self) {
-        info!("Stopping daemon");
-        let mut running = self.is_running.write();
-        *running = false;
-    }
-
-    /// Sync Google Calendar events if the sync interval has elapsed
-    async fn sync_calendar_if_needed(&self) {
-        let calendar_service = match &self.calendar_service {
-            Some(svc) => svc.clone(),
-            None => return,
-        };
-
-        // Check if sync interval has elapsed
-        {
-            let last_sync = self.last_calendar_sync.read();
-            if let Some(last) = *last_sync {
-                if Utc::now() - last
-                    < chrono::Duration::from_std(self.calendar_sync_interval)
-                        .unwrap_or(chrono::Duration::minutes(15))
-                {
-                    return;
-                }
-            }
-        }
-
-        // Check if authenticated (this now attempts token refresh if expired)
-        if !calendar_service.is_authenticated().await {
-            warn!("Google Calendar not authenticated — token refresh failed or no credentials");
-
-            // Surface auth failure to user (once, not every sync cycle)
-            if !*self.auth_warning_emitted.read() {
-                *self.auth_warning_emitted.write() = true;
-                let emoji = "🔑";
-                let text = "Google Calendar authentication expired — run `jasper-companion-daemon auth-google` to re-authenticate.";
-                if let Ok(insight_id) = self.database.store_insight(emoji, text, None) {
-                    self.emit_insight_signal(insight_id, emoji, text).await;
-                    warn!("Auth warning insight emitted to frontends");
-                }
-            }
-            return;
-        }
-
-        // Auth succeeded — clear warning flag if it was set
-        if *self.auth_warning_emitted.read() {
-            *self.auth_warning_emitted.write() = false;
-        }
-
-        info!("Starting Google Calendar sync");
-
-        let now = Utc::now();
-        let planning_horizon = self.config.read().get_planning_horizon();
-        let end_time = now + planning_horizon;
-
-        // Fetch calendar metadata (display names, access roles) once per sync cycle
-        let calendar_metadata = match calendar_service.fetch_calendar_metadata().await {
-            Ok(meta) => {
-                debug!("Fetched metadata for {} calendars", meta.len());
-                meta
-            }
-            Err(e) => {
-                warn!(
-                    "Failed to fetch calendar metadata, falling back to IDs: {}",
-                    e
-                );
-                std::collections::HashMap::new()
-            }
-        };
-
-        match calendar_service.fetch_events(now, end_time).await {
-            Ok(events_by_calendar) => {
-                let mut total_events = 0usize;
-
-                for (google_calendar_id, events) in &events_by_calendar {
-                    // Look up display name and access role from metadata
-                    let (calendar_name, access_role, is_primary) =
-                        if let Some(meta) = calendar_metadata.get(google_calendar_id) {
-                            (
-                                meta.display_name.clone(),
-                                Some(meta.access_role.as_str()),
-                                meta.is_primary,
-                            )
-                        } else {
-                            (google_calendar_id.clone(), None, false)
-                        };
-                    let db_calendar_id = match self.database.create_or_update_calendar(
-                        google_calendar_id,
-                        &calendar_name,
-                        Some("google_calendar"),
-                        access_role,
-                        is_primary,
-                    ) {
-                        Ok(id) => id,
-                        Err(e) => {
-                            warn!(
-                                "Failed to create/update calendar {}: {}",
-                                google_calendar_id, e
-                            );
-                            continue;
-                        }
-                    };
-
-                    // Delete old events for this calendar then bulk-insert fresh ones
-                    if let Err(e) = self.database.delete_events_for_calendar(db_calendar_id) {
-                        warn!(
-                            "Failed to delete old events for calendar {}: {}",
-                            google_calendar_id, e
-                        );
-                        continue;
-                    }
-
-                    // Set the calendar_id on each event before inserting
-                    let events_with_cal_id: Vec<_> = events
-                        .iter()
-                        .map(|e| {
-                            let mut ev = e.clone();
-                            ev.calendar_id = db_calendar_id;
-                            ev
-                        })
-                        .collect();
-
-                    match self.database.create_events_bulk(&events_with_cal_id) {
-                        Ok(ids) => {
-                            total_events += ids.len();
-                            debug!(
-                                "Synced {} events for calendar {}",
-                                ids.len(),
-                                google_calendar_id
-                            );
-                        }
-                        Err(e) => {
-                            warn!(
-                                "Failed to insert events for calendar {}: {}",
-                                google_calendar_id, e
-                            );
-                        }
-                    }
-                }
-
-                info!("Google Calendar sync complete: {} events", total_events);
-
-                // Update last sync timestamp
-                *self.last_calendar_sync.write() = Some(Utc::now());
-            }
-            Err(e) => {
-                warn!("Google Calendar sync failed: {}", e);
-            }
-        }
-    }
-
-    /// Determine the current heartbeat phase based on time of day.
-    /// Returns the phase name if we should fire a heartbeat, or None if one already fired this phase.
-    fn should_fire_heartbeat(&self) -> Option<String> {
-        let tz = self.config.read().get_timezone();
-        let local_now = Utc::now().with_timezone(&tz);
-        let hour = local_now.hour();
-
-        // Define heartbeat phases: morning (7-8), midday (12-13), evening (18-19)
-        let phase = match hour {
-            7..=8 => Some("morning"),
-            12..=13 => Some("midday"),
-            18..=19 => Some("evening"),
-            _ => None,
-        };
-
-        phase.map(|p| p.to_string())
-    }
-
-    /// Check context for changes and analyze if significant.
-    /// Uses a dual trigger model: heartbeat (time-of-day phases) + event-driven (context changes).
-    async fn check_and_analyze(&self) -> JasperResult<()> {
-        debug!("Checking context for significant changes");
-
-        // Collect current context from all sources
-        let mut current_context = self.collect_current_context().await?;
-
-        // Determine trigger: context change or heartbeat
-        let (is_significant, changes) = self
-            .significance_engine
-            .analyze_context(current_context.clone());
-
-        // Enrich calendar events with travel times (after significance check so
-        // traffic fluctuations don't trigger unnecessary AI calls)
-        if let Some(ref travel_service) = self.travel_service {
-            let travel_times = travel_service
-                .get_travel_times_for_events(&current_context.calendar_events)
-                .await;
-            for event in &mut current_context.calendar_events {
-                if let Some(tt) = travel_times.get(&event.id) {
-                    event.travel_time = Some(TravelTimeInfo {
-                        duration_minutes: (tt.duration_seconds / 60) as i32,
-                        duration_in_traffic_minutes: tt
-                            .duration_in_traffic_seconds
-                            .map(|s| (s / 60) as i32),
-                        distance_km: tt.distance_meters as f32 / 1000.0,
-                        origin_label: "home".to_string(),
-                        travel_mode_label: travel_service.travel_mode_label().to_string(),
-                    });
-                }
-            }
-        }
-
-        let trigger = if is_significant {
-            info!("Significant changes detected: {:?}", changes);
-            Some(InsightTrigger::ContextChange(changes))
-        } else if let Some(phase) = self.should_fire_heartbeat() {
-            // Check if we already fired a heartbeat this phase by looking at recent insights
-            let dominated_by_recent = self
-                .database
-                .get_recent_insights(1)
-                .unwrap_or_default()
-                .first()
-                .map(|i| {
-                    let age_minutes = (Utc::now() - i.created_at).num_minutes();
-                    // Don't fire heartbeat if we generated an insight within the last 90 minutes
-                    age_minutes < 90
-                })
-                .unwrap_or(false);
-
-            if dominated_by_recent {
-                debug!("Skipping heartbeat — recent insight is still fresh");
-                None
-            } else {
-                info!("Heartbeat trigger: {} phase", phase);
-                // Record this as an AI call in the significance engine so it respects the cooldown
-                self.significance_engine.record_ai_call();
-                Some(InsightTrigger::Heartbeat(phase))
-            }
-        } else {
-            None
-        };
-
-        if let Some(trigger) = trigger {
-            // Call AI for analysis with full context and trigger info
-            match self.analyze_with_ai(&current_context, &trigger).await {
-                Ok(insight) => {
-                    // Store the insight
-                    match self.database.store_insight(
-                        &insight.emoji,
-                        &insight.text,
-                        Some(&insight.context_hash),
-                    ) {
-                        Ok(insight_id) => {
-                            info!("Stored new insight with ID: {}", insight_id);
-
-                            // Store the context snapshot that triggered this insight
-                            let snapshot_json = serde_json::to_string(&current_context)
-                                .unwrap_or_else(|_| "{}".to_string());
-
-                            if let Err(e) = self.database.store_context_snapshot(
-                                insight_id,
-                                "combined",
-                                &snapshot_json,
-                                None,
-                            ) {
-                                warn!("Failed to store context snapshot: {}", e);
-                            }
-
-                            // Emit D-Bus signal to notify frontends of new insight
-                            self.emit_insight_signal(insight_id, &insight.emoji, &insight.text)
-                                .await;
-                        }
-                        Err(e) => {
-                            error!("Failed to store insight: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("AI analysis failed: {}", e);
-                }
-            }
-        } else {
-            debug!("No trigger fired — skipping AI call");
-        }
-
-        Ok(())
-    }
-
-    /// Collect current context from all sources
-    async fn collect_current_context(&self) -> JasperResult<ContextSnapshotSummary> {
-        let now = Utc::now();
-        let end_time = now + chrono::Duration::hours(24);
-
-        // Look back 12 hours so in-progress events remain visible in the context
-        // rather than dropping out the instant their start_time passes (which would
-        // cause the significance engine to misinterpret them as cancelled).
-        let lookback_start = now - chrono::Duration::hours(12);
-
-        // Get calendar events from lookback window through next 24 hours (with calendar context)
-        let calendar_events: Vec<_> = self
-            .database
-            .get_events_in_range_with_calendar(lookback_start, end_time)?
-            .into_iter()
-            .map(|(event, calendar_name, access_role, is_primary)| {
-                let is_own = access_role.as_deref() == Some("owner");
-                crate::significance_engine::CalendarEventSummary {
-                    id: event.source_id,
-                    title: event.title.unwrap_or_default(),
-                    start_time: DateTime::from_timestamp(event.start_time, 0).unwrap_or_default(),
-                    end_time: event
-                        .end_time
-                        .map(|ts| DateTime::from_timestamp(ts, 0).unwrap_or_default()),
-                    location: event.location,
-                    is_all_day: event.is_all_day.unwrap_or(false),
-                    calendar_name: Some(calendar_name),
-                    is_own_calendar: is_own,
-                    is_primary_calendar: is_primary,
-                    travel_time: None,
-                }
-            })
-            .collect();
-
-        // Collect additional context from all enabled context sources
-        let context_data = match self
-            .context_manager
-            .read()
-            .await
-            .fetch_all_context(now, end_time)
-            .await
-        {
-            Ok(data) => data,
-            Err(e) => {
-                warn!("Failed to fetch context from sources: {}", e);
-                Vec::new()
-            }
-        };
-
-        // Extract weather, tasks, and full notes context from context data
-        let mut weather: Option<crate::significance_engine::WeatherSummary> = None;
-        let mut tasks: Vec<crate::significance_engine::TaskSummary> = Vec::new();
-        let mut notes_context: Option<context_sources::NotesContext> = None;
-        let mut weather_context: Option<context_sources::WeatherContext> = None;
-
-        for ctx in &context_data {
-            match &ctx.content {
-                context_sources::ContextContent::Weather(weather_ctx) => {
-                    weather_context = Some(weather_ctx.clone());
-                    // Parse temperature from forecast or conditions
-                    if let Some(forecast) = weather_ctx.forecast.first() {
-                        weather = Some(crate::significance_engine::WeatherSummary {
-                            condition: forecast.conditions.clone(),
-                            temperature: forecast.temperature_high as i32,
-                            feels_like: forecast.temperature_high as i32,
-                        });
-                    }
-                }
-                context_sources::ContextContent::Tasks(task_ctx) => {
-                    tasks.extend(task_ctx.tasks.iter().map(|t| {
-                        crate::significance_engine::TaskSummary {
-                            id: t.id.clone(),
-                            title: t.title.clone(),
-                            due: t.due_date,
-                            completed: matches!(t.status, context_sources::TaskStatus::Completed),
-                        }
-                    }));
-                }
-                context_sources::ContextContent::Notes(notes_ctx) => {
-                    notes_context = Some(notes_ctx.clone());
-                    // Also extract tasks from notes for the significance engine
-                    tasks.extend(notes_ctx.pending_tasks.iter().map(|t| {
-                        crate::significance_engine::TaskSummary {
-                            id: t.id.clone(),
-                            title: t.title.clone(),
-                            due: t.due_date,
-                            completed: matches!(t.status, context_sources::TaskStatus::Completed),
-                        }
-                    }));
-                }
-                _ => {}
-            }
-        }
-
-        // Create context hash for comparison
-        let context_hash = format!("{:x}", {
-            use std::hash::{Hash, Hasher};
-            let mut hasher = std::collections::hash_map::DefaultHasher::new();
-            calendar_events.hash(&mut hasher);
-            weather.hash(&mut hasher);
-            tasks.hash(&mut hasher);
-            hasher.finish()
-        });
-
-        Ok(ContextSnapshotSummary {
-            calendar_events,
-            weather,
-            tasks,
-            notes_context,
-            weather_context,
-            timestamp: now,
-            context_hash,
-        })
-    }
-
-    /// Call AI service for analysis, with automatic retry on transient failures
-    async fn analyze_with_ai(
-        &self,
-        context: &ContextSnapshotSummary,
-        trigger: &InsightTrigger,
-    ) -> JasperResult<AiInsight> {
-        debug!("Calling AI service for context analysis");
-
-        // Build the request body once so retries reuse it
-        let request_body = self.build_anthropic_request(context, trigger)?;
-
-        match self
-            .api_manager
-            .execute_with_retry(|| {
-                let body = request_body.clone();
-                async move {
-                    self.send_anthropic_request(&body)
-                        .await
-                        .map_err(|e| anyhow::anyhow!("{}", e))
-                }
-            })
-            .await
-        {
-            Ok((insight, tokens_used)) => {
-                self.api_manager.record_api_call(tokens_used);
-                Ok(insight)
-            }
-            Err(e) => {
-                // If rate-limited / circuit-broken, return fallback
-                if e.to_string().contains("Daily API limit")
-                    || e.to_string().contains("Circuit breaker")
-                {
-                    warn!("API call blocked: {}", e);
-                    Ok(AiInsight {
-                        emoji: "⏳".to_string(),
-                        text: "Rate limited - check back later for fresh insights".to_string(),
-                        context_hash: context.context_hash.clone(),
-                    })
-                } else {
-                    Err(crate::errors::JasperError::Internal {
-                        message: format!("AI analysis failed: {}", e),
-                    })
-                }
-            }
-        }
-    }
-
-    /// Load personal context from the user's context file, with mtime-based caching
-    fn load_personal_context(&self) -> Option<String> {
-        // Resolve path: explicit config override, or default convention
-        let path = {
-            let cfg = self.config.read();
-            if let Some(ref explicit) = cfg.general.personal_context_file {
-                if let Some(stripped) = explicit.strip_prefix("~/") {
-                    if let Some(home) = dirs::home_dir() {
-                        home.join(stripped)
-                    } else {
-                        std::path::PathBuf::from(explicit)
-                    }
-                } else {
-                    std::path::PathBuf::from(explicit)
-                }
-            } else {
-                Config::get_personal_context_path()?
-            }
-        };
-
-        let metadata = std::fs::metadata(&path).ok()?;
-        let mtime = metadata.modified().ok()?;
-
-        // Check cache: return cached content if file hasn't changed
-        {
-            let cache = self.personal_context_cache.read();
-            if let Some((cached_mtime, ref content)) = *cache {
-                if cached_mtime == mtime {
-                    return Some(content.clone());
-                }
-            }
-        }
-
-        // File changed (or first read) — reload
-        match std::fs::read_to_string(&path) {
-            Ok(content) if !content.trim().is_empty() => {
-                let trimmed = content.trim().to_string();
-                *self.personal_context_cache.write() = Some((mtime, trimmed.clone()));
-                info!(
-                    "Loaded personal context from {:?} ({} bytes)",
-                    path,
-                    trimmed.len()
-                );
-                Some(trimmed)
-            }
-            _ => None,
-        }
-    }
-
-    /// Determine the current time-of-day phase for the user's timezone
-    fn get_time_of_day_phase(&self) -> (&'static str, DateTime<chrono::FixedOffset>) {
-        let tz = self.config.read().get_timezone();
-        let local_now = Utc::now().with_timezone(&tz);
-        // Convert to FixedOffset for storage
-        let fixed_now = local_now.fixed_offset();
-        let hour = fixed_now.hour();
-        let phase = match hour {
-            5..=8 => "early morning — you're starting your day",
-            9..=11 => "morning — your day is underway",
-            12..=13 => "midday",
-            14..=16 => "afternoon",
-            17..=19 => "evening — winding down the day",
-            20..=22 => "night — wrapping up",
-            _ => "late night",
-        };
-        (phase, fixed_now)
-    }
-
-    /// Format a datetime as a human-relative time string (e.g., "in 45 minutes", "tomorrow at 3pm")
-    fn format_relative_time(now: &DateTime<chrono::FixedOffset>, target: &DateTime<Utc>) -> String {
-        let target_local = target.with_timezone(&now.timezone());
-        let diff = target_local.signed_duration_since(*now);
-        let minutes = diff.num_minutes();
-        let hours = diff.num_hours();
-
-        if minutes < 0 {
-            let abs_min = minutes.abs();
-            if abs_min < 60 {
-                format!("{} minutes ago", abs_min)
-            } else if abs_min < 1440 {
-                format!("{} hours ago", (abs_min / 60))
-            } else {
-                format!("{} days ago", (abs_min / 1440))
-            }
-        } else if minutes < 60 {
-            format!("in {} minutes", minutes)
-        } else if hours < 24 {
-            if hours == 1 {
-                format!("in about an hour ({})", target_local.format("%I:%M %p"))
-            } else {
-                format!("in {} hours ({})", hours, target_local.format("%I:%M %p"))
-            }
-        } else {
-            let days = hours / 24;
-            if days == 1 {
-                format!("tomorrow at {}", target_local.format("%I:%M %p"))
-            } else {
-                format!("in {} days ({})", days, target_local.format("%a %I:%M %p"))
-            }
-        }
-    }
-
-    /// Format a relative deadline for tasks (e.g., "due in 2 days", "OVERDUE by 3 days")
-    fn format_relative_deadline(
-        now: &DateTime<chrono::FixedOffset>,
-        due: &DateTime<Utc>,
-    ) -> String {
-        let due_local = due.with_timezone(&now.timezone());
-        let diff = due_local.signed_duration_since(*now);
-        let hours = diff.num_hours();
-        let days = diff.num_days();
-
-        if hours < 0 {
-            let abs_days = days.abs();
-            if abs_days == 0 {
-                "OVERDUE (due today)".to_string()
-            } else if abs_days == 1 {
-                "OVERDUE by 1 day".to_string()
-            } else {
-                format!("OVERDUE by {} days", abs_days)
-            }
-        } else if hours < 24 {
-            "due today".to_string()
-        } else if days == 1 {
-            "due tomorrow".to_string()
-        } else if days <= 7 {
-            format!("due in {} days", days)
-        } else {
-            format!("due in {} weeks", days / 7)
-        }
-    }
-
-    /// Format travel time info for inclusion in the AI prompt
-    fn format_travel_time(travel: &Option<TravelTimeInfo>) -> String {
-        let Some(tt) = travel else {
-            return String::new();
-        };
-
-        let base = format!(
-            "{} min {} from {}",
-            tt.duration_minutes, tt.travel_mode_label, tt.origin_label
-        );
-
-        if let Some(traffic_min) = tt.duration_in_traffic_minutes {
-            // Only mention traffic if it adds 5+ minutes over base
-            if traffic_min > tt.duration_minutes + 5 {
-                format!(" ({}, ~{} min in current traffic)", base, traffic_min)
-            } else {
-                format!(" ({})", base)
-            }
-        } else {
-            format!(" ({})", base)
-        }
-    }
-
-    /// Format an event label for schedule situation output (e.g. "Kieran: Soccer (5:30-7:00 PM, at Rec Center)")
-    fn format_situation_event_label(
-        event: &crate::significance_engine::CalendarEventSummary,
-        tz: &chrono::FixedOffset,
-    ) -> String {
-        let cal_prefix = if !event.is_primary_calendar {
-            event
-                .calendar_name
-                .as_deref()
-                .map(|n| format!("{}: ", n))
-                .unwrap_or_default()
-        } else {
-            String::new()
-        };
-
-        let start_local = event.start_time.with_timezone(tz);
-        let time_range = if let Some(end) = event.end_time {
-            let end_local = end.with_timezone(tz);
-            format!(
-                "{}-{}",
-                start_local.format("%-I:%M %p"),
-                end_local.format("%-I:%M %p")
-            )
-        } else {
-            format!("{}", start_local.format("%-I:%M %p"))
-        };
-
-        let location = event
-            .location
-            .as_deref()
-            .map(|l| format!(", at {}", l))
-            .unwrap_or_default();
-
-        format!(
-            "\"{}{}\" ({}{})",
-            cal_prefix, event.title, time_range, location
-        )
-    }
-
-    /// Detect cross-event schedule situations worth surfacing to the AI.
-    /// Finds time overlaps between owned calendars and tight timing on the user's primary calendar.
-    fn detect_schedule_situations(
-        events: &[crate::significance_engine::CalendarEventSummary],
-        now: &DateTime<chrono::FixedOffset>,
-    ) -> Vec<String> {
-        let mut situations = Vec::new();
-        let tz = now.timezone();
-        let now_utc = now.to_utc();
-
-        // Filter to future/in-progress, non-all-day events on owned calendars only
-        let relevant: Vec<&crate::significance_engine::CalendarEventSummary> = events
-            .iter()
-            .filter(|e| !e.is_all_day)
-            .filter(|e| e.is_own_calendar) // primary + owned non-primary
-            .filter(|e| {
-                // Keep events that haven't ended yet
-                e.end_time.map(|end| end > now_utc).unwrap_or(true)
-            })
-            .collect();
-
-        // Compare all pairs (N is small — typically <20 events in 24h window)
-        for i in 0..relevant.len() {
-            for j in (i + 1)..relevant.len() {
-                let a = relevant[i];
-                let b = relevant[j];
-
-                let a_end = match a.end_time {
-                    Some(end) => end,
-                    None => continue,
-                };
-                let b_end = match b.end_time {
-                    Some(end) => end,
-                    None => continue,
-                };
-
-                // Hard overlap: A starts before B ends AND B starts before A ends
-                let overlaps = a.start_time < b_end && b.start_time < a_end;
-
-                if overlaps {
-                    // Flag cross-calendar overlaps or same-person double-bookings
-                    let cross_calendar = a.calendar_name != b.calendar_name
-                        || a.is_primary_calendar
-                        || b.is_primary_calendar;
-
-                    if cross_calendar {
-                        let a_label = Self::format_situation_event_label(a, &tz);
-                        let b_label = Self::format_situation_event_label(b, &tz);
-                        situations.push(format!(
-                            "OVERLAP: {} and {} — both need someone there",
-                            a_label, b_label
-                        ));
-                    }
-                } else {
-                    // Check for tight timing: gap < 15 minutes between consecutive events
-                    let gap = if a_end <= b.start_time {
-                        (b.start_time - a_end).num_minutes()
-                    } else if b_end <= a.start_time {
-                        (a.start_time - b_end).num_minutes()
-                    } else {
-                        continue;
-                    };
-
-                    if (0..=15).contains(&gap) {
-                        // Only flag tight timing when the user's primary calendar is involved
-                        if a.is_primary_calendar || b.is_primary_calendar {
-                            let (first, second) = if a.start_time <= b.start_time {
-                                (a, b)
-                            } else {
-                                (b, a)
-                            };
-                            let first_label = Self::format_situation_event_label(first, &tz);
-                            let second_label = Self::format_situation_event_label(second, &tz);
-                            situations.push(format!(
-                                "TIGHT: {} then {} ({} min gap)",
-                                first_label, second_label, gap
-                            ));
-                        }
-                    }
-                }
-            }
-        }
-
-        situations
-    }
-
-    /// Build the Anthropic API request body from context (no I/O, can be reused for retries)
-    fn build_anthropic_request(
-        &self,
-        context: &ContextSnapshotSummary,
-        trigger: &InsightTrigger,
-    ) -> JasperResult<serde_json::Value> {
-        let (time_phase, local_now) = self.get_time_of_day_phase();
-
-        // --- Build the system message with personality and guidance ---
-        let (personality, _timezone_str) = {
-            let cfg = self.config.read();
-            let (p, tz) = cfg.get_personality_config();
-            (p.clone(), tz.to_string())
-        };
-
-        let persona_desc = personality
-            .persona_reference
-            .as_deref()
-            .map(|r| format!(" ({})", r))
-            .unwrap_or_default();
-
-        // Get recent insights for deduplication
-        let recent_insights = self.database.get_recent_insights(5).unwrap_or_default();
-        let recent_insights_text = if recent_insights.is_empty() {
-            "None yet — this is your first insight of the session.".to_string()
-        } else {
-            recent_insights
-                .iter()
-                .map(|i| {
-                    format!(
-                        "- {} {} ({})",
-                        i.emoji,
-                        i.insight,
-                        Self::format_relative_time(&local_now, &i.created_at)
-                    )
-                })
-                .collect::<Vec<_>>()
-                .join("\n")
-        };
-
-        let system_message = format!(
-            "You are Jasper, a {persona}{persona_ref}. \
-You provide a single glanceable insight for {title}'s status bar — like Android's At a Glance widget, but smarter.\n\n\
-Current time: {now} ({phase}).\n\n\
-Your job: Surface the ONE most useful situation {title} needs to know about right now. \
-An insight is NOT just a single event — it could be a conflict between events, a logistics problem, \
-a timing crunch, or a pattern across the schedule that needs attention. \
-Think about what a thoughtful family coordinator who can see everyone's calendars would tap them on the shoulder about.\n\n\
-Prioritize (in rough order):\n\
-1. Logistics conflicts — two family members need to be in different places at the same time, or {title} is double-booked\n\
-2. Tight timing — back-to-back events that leave no margin, especially with travel time\n\
-3. Things that need action or preparation in the next 1-2 hours\n\
-4. Travel logistics — if an event has travel time, remind them when they need to leave (e.g. \"you should head out in 20 minutes for your 2pm meeting\")\n\
-5. Tasks or deadlines that are creeping up and easy to forget (the kind assigned weeks ago that slip through the cracks)\n\
-6. Cross-domain connections (a task relates to an upcoming event, weather affects plans)\n\n\
-Do NOT:\n\
-- Simply restate a calendar entry (\"You have a meeting at 3pm\") — add value beyond what a calendar shows\n\
-- Focus on weather unless it meaningfully impacts plans or activities\n\
-- Repeat something you've already surfaced recently (see recent insights below)\n\
-- Be robotic or generic — write like someone who knows {title} personally, with warmth\n\
-- Use any name or title other than \"{title}\" when addressing the user — always call them \"{title}\", never \"Sir\", \"Ma'am\", or any other title\n\
-- NEVER invent, fabricate, or assume events, tasks, or appointments that are not listed in the context below — if the schedule is empty, it's empty\n\
-- NEVER assume {title} is involved in events from shared calendars — those are shown for awareness only. Never say \"you were briefed on\" or \"your call with\" for shared events. Frame as: \"Christen has a call at 3pm\" not \"you have a call at 3pm\"\n\
-- Don't mention travel time if it's trivially short (under 5 minutes) — only surface it when it's actionable\n\
-- Don't focus on a single event in isolation when there's a more interesting relationship between events \
-(e.g. don't just say \"Kieran has soccer at 6\" when the real insight is that two kids have overlapping events at different locations)\n\n\
-Tone: {formality}. Keep it to ONE concise sentence. Warm and familiar, not stiff.\n\
-Start your response with a single emoji that captures the mood or topic (e.g. ☕ for morning routines, ⏰ for time-sensitive items, 🌧️ for weather impacts, 📋 for tasks). Vary it — don't reuse the same emoji back-to-back.\n\n\
-Recent insights (DO NOT repeat these):\n{recent_insights}",
-            persona = personality.assistant_persona,
-            persona_ref = persona_desc,
-            title = personality.user_title,
-            now = local_now.format("%A, %B %-d at %-I:%M %p"),
-            phase = time_phase,
-            formality = personality.formality,
-            recent_insights = recent_insights_text,
-        );
-
-        // Append personal context if the user has a context.md file
-        let system_message = if let Some(personal_ctx) = self.load_personal_context() {
-            let title = &personality.user_title;
-            format!(
-                "{system_message}\n\n\
-                 Personal context about {title} (provided by them — use this to understand their life, \
-                 relationships, and routines when interpreting calendar events and generating insights):\n\
-                 {personal_ctx}"
-            )
-        } else {
-            system_message
-        };
-
-        // --- Build the context (user message) with full data and relative times ---
-        let mut context_parts: Vec<String> = Vec::new();
-
-        // Trigger reason
-        let trigger_text = match trigger {
-            InsightTrigger::Heartbeat(phase) => format!("Trigger: Regular {} check-in.", phase),
-            InsightTrigger::ContextChange(changes) => {
-                let change_descriptions: Vec<String> = changes
-                    .iter()
-                    .map(|c| match c {
-                        SignificantChange::NewCalendarEvent(title) => {
-                            format!("New event added: \"{}\"", title)
-                        }
-                        SignificantChange::CancelledCalendarEvent(title) => {
-                            format!("Event cancelled: \"{}\"", title)
-                        }
-                        SignificantChange::EventTimeChanged {
-                            event_id,
-                            time_diff_hours,
-                        } => format!("Event {} moved by {:.1} hours", event_id, time_diff_hours),
-                        SignificantChange::EventLocationChanged { event_id } => {
-                            format!("Event {} location changed", event_id)
-                        }
-                        SignificantChange::WeatherConditionChanged { from, to } => {
-                            format!("Weather changed: {} → {}", from, to)
-                        }
-                        SignificantChange::WeatherTemperatureChanged { diff } => {
-                            format!("Temperature shifted by {}°F", diff)
-                        }
-                        SignificantChange::NewTask(title) => format!("New task: \"{}\"", title),
-                        SignificantChange::TaskCompleted(title) => {
-                            format!("Task completed: \"{}\"", title)
-                        }
-                        SignificantChange::TaskDueChanged {
-                            task_id,
-                            time_diff_hours,
-                        } => format!(
-                            "Task {} due date moved by {:.1} hours",
-                            task_id, time_diff_hours
-                        ),
-                        SignificantChange::InitialContext => {
-                            "Initial startup — first look at the day.".to_string()
-                        }
-                    })
-                    .collect();
-                format!(
-                    "Trigger: Context changed — {}",
-                    change_descriptions.join("; ")
-                )
-            }
-        };
-        context_parts.push(trigger_text);
-
-        // Detect when there is no real data at all
-        let has_calendar = !context.calendar_events.is_empty();
-        let has_tasks = !context.tasks.is_empty();
-        let has_weather = context.weather.is_some() || context.weather_context.is_some();
-        let has_notes = context.notes_context.is_some();
-
-        if !has_calendar && !has_tasks && !has_weather && !has_notes {
-            context_parts.push(
-                "\nNo calendar events, tasks, weather, or notes are available. \
-                The schedule is completely clear. Do NOT invent or assume any events — \
-                provide a genuine observation about having a clear schedule."
-                    .to_string(),
-            );
-        }
-
-        // Pre-computed schedule analysis — surfaces cross-event situations for the AI
-        let situations = Self::detect_schedule_situations(&context.calendar_events, &local_now);
-        if !situations.is_empty() {
-            let mut section =
-                String::from("\nSchedule situations (cross-calendar analysis — prioritize these):");
-            for sit in &situations {
-                section.push_str(&format!("\n- {}", sit));
-            }
-            context_parts.push(section);
-        }
-
-        // Calendar events with relative times — 3-way partition:
-        // 1. Primary calendar (user's personal events) → "Your calendar"
-        // 2. Owned non-primary calendars (family calendars user created) → grouped by calendar name
-        // 3. Shared calendars (reader/writer access) → "Shared calendar — X (awareness only)"
-        if !context.calendar_events.is_empty() {
-            let mut primary_events = Vec::new();
-            let mut owned_non_primary: BTreeMap<
-                &str,
-                Vec<&crate::significance_engine::CalendarEventSummary>,
-            > = BTreeMap::new();
-            let mut shared_events: BTreeMap<
-                &str,
-                Vec<&crate::significance_engine::CalendarEventSummary>,
-            > = BTreeMap::new();
-
-            for event in &context.calendar_events {
-                if event.is_primary_calendar {
-                    primary_events.push(event);
-                } else if event.is_own_calendar {
-                    // Owned but not primary — family calendars like "Kieran Thomas", "House"
-                    let name = event.calendar_name.as_deref().unwrap_or("Other");
-                    owned_non_primary.entry(name).or_default().push(event);
-                } else {
-                    let name = event.calendar_name.as_deref().unwrap_or("Shared");
-                    shared_events.entry(name).or_default().push(event);
-                }
-            }
-
-            // 1. Primary calendar events
-            if !primary_events.is_empty() {
-                let mut cal_section = String::from("\nYour calendar (next 24h):");
-                for event in &primary_events {
-                    let timing = if event.is_all_day {
-                        "all day".to_string()
-                    } else {
-                        Self::format_relative_time(&local_now, &event.start_time)
-                    };
-                    let location = event
-                        .location
-                        .as_ref()
-                        .map(|l| format!(", at {}", l))
-                        .unwrap_or_default();
-                    let travel = Self::format_travel_time(&event.travel_time);
-                    cal_section.push_str(&format!(
-                        "\n- \"{}\" — {}{}{}",
-                        event.title, timing, location, travel
-                    ));
-                }
-                context_parts.push(cal_section);
-            }
-
-            // 2. Owned non-primary calendars, grouped by calendar name
-            for (cal_name, events) in &owned_non_primary {
-                let mut section = format!(
-                    "\n{} calendar (these are {}'s events, NOT {}'s own):",
-                    cal_name, cal_name, personality.user_title
-                );
-                for event in events {
-                    let timing = if event.is_all_day {
-                        "all day".to_string()
-                    } else {
-                        Self::format_relative_time(&local_now, &event.start_time)
-                    };
-                    let location = event
-                        .location
-                        .as_ref()
-                        .map(|l| format!(", at {}", l))
-                        .unwrap_or_default();
-                    let travel = Self::format_travel_time(&event.travel_time);
-                    section.push_str(&format!(
-                        "\n- \"{}\" — {}{}{}",
-                        event.title, timing, location, travel
-                    ));
-                }
-                context_parts.push(section);
-            }
-
-            // 3. Shared calendar events, grouped by calendar name
-            for (cal_name, events) in &shared_events {
-                let mut section = format!(
-                    "\nShared calendar — {} (awareness only, NOT {}'s events):",
-                    cal_name, personality.user_title,
-                );
-                for event in events {
-                    let timing = if event.is_all_day {
-                        "all day".to_string()
-                    } else {
-                        Self::format_relative_time(&local_now, &event.start_time)
-                    };
-                    let location = event
-                        .location
-                        .as_ref()
-                        .map(|l| format!(", at {}", l))
-                        .unwrap_or_default();
-                    section.push_str(&format!("\n- \"{}\" — {}{}", event.title, timing, location));
-                }
-                context_parts.push(section);
-            }
-        }
-
-        // Tasks with relative deadlines
-        if !context.tasks.is_empty() {
-            let mut task_section = String::from("\nTasks:");
-            for task in &context.tasks {
-                if task.completed {
-                    continue;
-                }
-                let deadline = task
-                    .due
-                    .as_ref()
-                    .map(|d| format!(" ({})", Self::format_relative_deadline(&local_now, d)))
-                    .unwrap_or_else(|| " (no due date)".to_string());
-                task_section.push_str(&format!("\n- {}{}", task.title, deadline));
-            }
-            context_parts.push(task_section);
-        }
-
-        // Full weather context (if available)
-        if let Some(weather_ctx) = &context.weather_context {
-            let mut weather_section = format!("\nWeather: {}", weather_ctx.current_conditions);
-            if !weather_ctx.forecast.is_empty() {
-                let today = &weather_ctx.forecast[0];
-                weather_section.push_str(&format!(
-                    " (High: {:.0}°F, Low: {:.0}°F, {}% chance of precipitation)",
-                    today.temperature_high,
-                    today.temperature_low,
-                    (today.precipitation_chance * 100.0) as i32
-                ));
-            }
-            if !weather_ctx.alerts.is_empty() {
-                weather_section.push_str(&format!(
-                    "\nWeather alerts: {}",
-                    weather_ctx.alerts.join(", ")
-                ));
-            }
-            context_parts.push(weather_section);
-        } else if let Some(weather) = &context.weather {
-            context_parts.push(format!(
-                "\nWeather: {} ({}°F)",
-                weather.condition, weather.temperature
-            ));
-        }
-
-        // Notes context: projects, relationships, focus areas
-        if let Some(notes) = &context.notes_context {
-            // Active projects with deadlines
-            let active_projects: Vec<_> = notes
-                .active_projects
-                .iter()
-                .filter(|p| {
-                    matches!(
-                        p.status,
-                        context_sources::ProjectStatus::Active
-                            | context_sources::ProjectStatus::Pending
-                    )
-                })
-                .collect();
-            if !active_projects.is_empty() {
-                let mut proj_section = String::from("\nActive Projects:");
-                for project in &active_projects {
-                    let deadline = project
-                        .due_date
-                        .as_ref()
-                        .map(|d| format!(" ({})", Self::format_relative_deadline(&local_now, d)))
-                        .unwrap_or_default();
-                    let progress = if project.progress > 0.0 {
-                        format!(", {:.0}% complete", project.progress * 100.0)
-                    } else {
-                        String::new()
-                    };
-                    proj_section.push_str(&format!("\n- {}{}{}", project.name, deadline, progress));
-                }
-                context_parts.push(proj_section);
-            }
-
-            // Today's focus areas from daily notes
-            let focus_areas: Vec<_> = notes
-                .daily_notes
-                .iter()
-                .flat_map(|n| n.focus_areas.iter())
-                .collect();
-            if !focus_areas.is_empty() {
-                let mut focus_section = String::from("\nToday's focus areas:");
-                for area in &focus_areas {
-                    focus_section.push_str(&format!("\n- {}", area));
-                }
-                context_parts.push(focus_section);
-            }
-        }
-
-        let user_message = context_parts.join("\n");
-        debug!("AI prompt user message:\n{}", user_message);
-
-        let model = self.config.read().ai.model.clone();
-
-        Ok(serde_json::json!({
-            "model": model,
-            "max_tokens": 300,
-            "system": system_message,
-            "messages": [{
-                "role": "user",
-                "content": user_message
-            }],
-            "_context_hash": context.context_hash
-        }))
-    }
-
-    /// Send the pre-built request body to the Anthropic API. Returns insight and tokens used.
-    async fn send_anthropic_request(
-        &self,
-        request_body: &serde_json::Value,
-    ) -> JasperResult<(AiInsight, u64)> {
-        let api_key = self.config.read().get_api_key()
-            .ok_or_else(|| crate::errors::JasperError::Authentication { service: "anthropic".into(), message: "API key not configured. Set via config, SOPS secrets, or ANTHROPIC_API_KEY environment variable.".into() })?;
-
-        // Strip our internal field before sending
-        let mut body = request_body.clone();
-        let context_hash = body
-            .get("_context_hash")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        body.as_object_mut().map(|o| o.remove("_context_hash"));
-
-        let response = self
-            .http_client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| crate::errors::JasperError::Internal {
-                message: format!("API request failed: {}", e),
-            })?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(crate::errors::JasperError::Internal {
-                message: format!("API call failed with status {}: {}", status, error_text),
-            });
-        }
-
-        let response_json: serde_json::Value =
-            response
-                .json()
-                .await
-                .map_err(|e| crate::errors::JasperError::Internal {
-                    message: format!("Failed to parse response: {}", e),
-                })?;
-
-        let content = response_json
-            .get("content")
-            .and_then(|c| c.as_array())
-            .and_then(|arr| arr.first())
-            .and_then(|item| item.get("text"))
-            .and_then(|text| text.as_str())
-            .ok_or_else(|| crate::errors::JasperError::Internal {
-                message: "Invalid API response format".to_string(),
-            })?;
-
-        let tokens_used = response_json
-            .get("usage")
-            .map(|u| {
-                let input = u.get("input_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                let output = u.get("output_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
-                input + output
-            })
-            .unwrap_or(0);
-
-        let (emoji, insight) = self.parse_ai_response(content);
-
-        Ok((
-            AiInsight {
-                emoji,
-                text: insight,
-                context_hash,
-            },
-            tokens_used,
-        ))
-    }
-
-    /// Parse AI response to extract emoji and insight.
-    /// Supports both "Emoji:/Insight:" format and freeform "emoji text" format.
-    fn parse_ai_response(&self, content: &str) -> (String, String) {
-        let content = content.trim();
-        let lines: Vec<&str> = content.lines().collect();
-        let mut emoji = Option::<String>::None;
-        let mut insight = Option::<String>::None;
-
-        // Try structured format first
-        for line in &lines {
-            if line.starts_with("Emoji:") {
-                let e = line.replace("Emoji:", "").trim().to_string();
-                if !e.is_empty() {
-                    emoji = Some(e);
-                }
-            } else if line.starts_with("Insight:") {
-                let i = line.replace("Insight:", "").trim().to_string();
-                if !i.is_empty() {
-                    insight = Some(i);
-                }
-            }
-        }
-
-        // If structured parsing worked, return
-        if let (Some(e), Some(i)) = (emoji.clone(), insight.clone()) {
-            return (e, i);
-        }
-
-        // Freeform: expect the response to start with an emoji followed by text
-        let mut chars = content.chars().peekable();
-        let mut leading_emoji = String::new();
-
-        // Collect leading emoji characters (may be multi-codepoint)
-        while let Some(&ch) = chars.peek() {
-            if ch.is_emoji_char() || ch == '\u{FE0F}' || ch == '\u{200D}' {
-                leading_emoji.push(ch);
-                chars.next();
-            } else {
-                break;
-            }
-        }
-
-        let remaining: String = chars.collect();
-        let remaining = remaining.trim().to_string();
-
-        if !leading_emoji.is_empty() && !remaining.is_empty() {
-            return (leading_emoji, remaining);
-        }
-
-        // Last resort: use full content as insight
-        (
-            emoji.unwrap_or_else(|| "🤖".to_string()),
-            insight.unwrap_or_else(|| {
-                if content.is_empty() {
-                    "AI analysis complete - check your schedule and priorities".to_string()
-                } else {
-                    content.to_string()
-                }
-            }),
-        )
-    }
-
-    /// Get the latest insight from database
-    pub fn get_latest_insight(&self) -> JasperResult<Option<Insight>> {
-        self.database.get_latest_insight()
-    }
-
-    /// Get insight by ID
-    pub fn get_insight_by_id(&self, insight_id: i64) -> JasperResult<Option<Insight>> {
-        self.database.get_insight_by_id(insight_id)
-    }
-
-    /// Register a frontend as active
-    pub fn register_frontend(&self, frontend_id: &str, pid: Option<i32>) -> JasperResult<()> {
-        info!("Registering frontend: {}", frontend_id);
-        self.database.register_frontend(frontend_id, pid)
-    }
-
-    /// Unregister a frontend
-    pub fn unregister_frontend(&self, frontend_id: &str) -> JasperResult<()> {
-        info!("Unregistering frontend: {}", frontend_id);
-        self.database.unregister_frontend(frontend_id)
-    }
-
-    /// Update frontend heartbeat
-    pub fn update_frontend_heartbeat(&self, frontend_id: &str) -> JasperResult<()> {
-        self.database.update_frontend_heartbeat(frontend_id)
-    }
-
-    /// Force an immediate context check and analysis
-    pub async fn force_refresh(&self) -> JasperResult<()> {
-        info!("Forcing immediate context refresh");
-        self.check_and_analyze().await
-    }
-
-    /// Reset significance engine (useful after cache clear)
-    #[allow(dead_code)]
-    pub fn reset_significance_engine(&self) {
-        info!("Resetting significance engine");
-        self.significance_engine.reset();
-    }
-}
-
-/// What triggered the insight generation
-#[derive(Debug, Clone)]
-enum InsightTrigger {
-    /// Regular time-of-day heartbeat (morning, afternoon, evening)
-    Heartbeat(String),
-    /// Detected context change from significance engine
-    ContextChange(Vec<SignificantChange>),
-}
-
-/// Simplified AI insight result
-#[derive(Debug, Clone)]
-struct AiInsight {
-    emoji: String,
-    text: String,
-    context_hash: String,
-}
-
-/// Daemon status information
-#[derive(Debug)]
-pub struct DaemonStatus {
-    pub is_running: bool,
-    pub active_frontends: usize,
-    #[allow(dead_code)] // TODO: Track last analysis time
-    pub last_analysis: Option<DateTime<Utc>>,
-    pub insights_count: i64,
-}
-
-impl SimplifiedDaemonCore {
-    /// Get daemon status
-    pub async fn get_status(&self) -> JasperResult<DaemonStatus> {
-        let is_running = *self.is_running.read();
-        let active_frontends = self.database.get_active_frontends()?.len();
-
-        // Get insights count from database
-        let insights_count = 0; // TODO: Add method to count insights
-
-        Ok(DaemonStatus {
-            is_running,
-            active_frontends,
-            last_analysis: None, // TODO: Track last analysis time
-            insights_count,
-        })
-    }
-}
@@ -94,12 +94,14 @@
             fileset = fs.unions [
               ./Cargo.toml
               ./Cargo.lock
+              ./core
               ./daemon
+              ./client
             ];
           };
 
           cargoBuildFlags = [ "-p" "jasper-companion-daemon" ];
-          cargoTestFlags = [ "-p" "jasper-companion-daemon" ];
+          cargoTestFlags = [ "-p" "jasper-core" "-p" "jasper-companion-daemon" ];
 
           cargoLock = {
             lockFile = ./Cargo.lock;
@@ -32,6 +32,14 @@ export default class JasperExtension extends Extension.Extension {
         this._updateMenuText();
         this._indicator.menu.addMenuItem(this._item);
 
+        // Clicking the insight opens what it's about, when the daemon linked it
+        this._actionUri = null;
+        this._item.connect('activate', () => {
+            if (this._actionUri) {
+                Gio.AppInfo.launch_default_for_uri(this._actionUri, null);
+            }
+        });
+
         // Add separator
         this._indicator.menu.addMenuItem(new PopupMenu.PopupSeparatorMenuItem());
 
@@ -125,10 +133,13 @@ export default class JasperExtension extends Extension.Extension {
     }
 
     _onInsightUpdated(parameters) {
-        // Signal parameters: (insight_id, emoji, preview)
-        const [insightId, emoji, preview] = parameters.deep_unpack();
+        // Signal parameters: (insight_id, emoji, preview, formatted, age_seconds, stale,
+        // actions); labels are plain text. Older daemons send fewer fields.
+        const [insightId, emoji, preview, , , stale, actions] = parameters.deep_unpack();
         if (insightId > 0) {
             this._label.set_text(emoji || '🤖');
+            this._label.opacity = stale ? 128 : 255;
+            this._actionUri = actions && actions.length > 0 ? actions[0][1] : null;
             this._insightText = `Jasper: ${preview}`;
             this._updateMenuText();
         }
@@ -17,13 +17,15 @@
 
 let
   fs = lib.fileset;
+  # The applet talks to the daemon through the shared jasper-client crate
   filteredSrc = fs.toSource {
-    root = ../cosmic-applet;
+    root = ../.;
     fileset = fs.unions [
       ../cosmic-applet/Cargo.toml
       ../cosmic-applet/Cargo.lock
       ../cosmic-applet/src
       ../cosmic-applet/data
+      ../client
     ];
   };
 in
@@ -33,6 +35,8 @@ rustPlatform.buildRustPackage {
   version = "0.2.0";
 
   src = filteredSrc;
+  cargoRoot = "cosmic-applet";
+  buildAndTestSubdir = "cosmic-applet";
 
   cargoLock = {
     lockFile = ../cosmic-applet/Cargo.lock;
@@ -63,7 +67,7 @@ rustPlatform.buildRustPackage {
   dontUseJustInstall = true;
 
   postInstall = ''
-    install -Dm644 data/com.system76.CosmicAppletJasper.desktop \
+    install -Dm644 cosmic-applet/data/com.system76.CosmicAppletJasper.desktop \
       $out/share/applications/com.system76.CosmicAppletJasper.desktop
   '';
 
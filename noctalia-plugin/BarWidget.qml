@@ -25,6 +25,7 @@ Item {
     readonly property string insight: jasper?.currentInsight  ?? ""
     readonly property string state:   jasper?.currentState   ?? "offline"
     readonly property bool refreshing: jasper?.isRefreshing   ?? false
+    readonly property bool stale:      jasper?.isStale        ?? false
 
     // Display emoji — pick a fallback per state
     readonly property string displayText: {
@@ -49,9 +50,10 @@ Item {
             return "Jasper \u{00B7} Refreshing\u{2026}";
         if (state === "active" && lastUpdated > 0) {
             var ago = Math.floor((Date.now() - lastUpdated) / 60000);
-            if (ago < 1) return "Jasper \u{00B7} Just now";
-            if (ago === 1) return "Jasper \u{00B7} 1m ago";
-            return "Jasper \u{00B7} " + ago + "m ago";
+            var suffix = stale ? " (stale)" : "";
+            if (ago < 1) return "Jasper \u{00B7} Just now" + suffix;
+            if (ago === 1) return "Jasper \u{00B7} 1m ago" + suffix;
+            return "Jasper \u{00B7} " + ago + "m ago" + suffix;
         }
         if (state === "waiting")
             return "Jasper \u{00B7} Analyzing\u{2026}";
@@ -72,6 +74,8 @@ Item {
         text: root.displayText
         forceOpen: true
         tooltipText: root.tooltipContent
+        // Gray out an insight the daemon flags as stale
+        opacity: (root.stale && root.state === "active" && !root.refreshing) ? 0.5 : 1.0
 
         onClicked: {
             pluginApi.togglePanel(root.screen, root);
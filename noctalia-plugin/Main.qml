@@ -12,7 +12,8 @@ Item {
     property string currentInsight: ""
     property string currentState: "offline"   // active | waiting | error | offline
     property bool   isRefreshing: false
-    property real   lastUpdatedAt: 0          // epoch ms of last successful insight
+    property real   lastUpdatedAt: 0          // epoch ms the current insight was generated
+    property bool   isStale: false            // older than the daemon's stale_after_minutes
 
     // Poll interval from plugin settings (default 30 s)
     readonly property int pollInterval: {
@@ -107,7 +108,11 @@ Item {
             currentEmoji   = data.emoji   || "";
             currentInsight = data.insight  || "";
             currentState   = data.state   || "offline";
-            if (currentState === "active") lastUpdatedAt = Date.now();
+            isStale        = data.stale   === true;
+            if (currentState === "active") {
+                var age = data.age_seconds;
+                lastUpdatedAt = Date.now() - ((age && age > 0) ? age * 1000 : 0);
+            }
         } catch (e) {
             currentState = "error";
             currentEmoji = "";
@@ -242,7 +242,7 @@ show_help() {
     echo "  help            - Show this help"
     echo ""
     echo "Quick Development Workflow:"
-    echo "  1. Edit Rust code in daemon/src/"
+    echo "  1. Edit Rust code in core/src/ (engine) or daemon/src/ (CLI)"
     echo "  2. Run: $0 quick"
     echo "  3. Check: $0 logs"
     echo ""